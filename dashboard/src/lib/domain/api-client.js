@@ -24,6 +24,7 @@ import {
  * @property {RequestCache} [cache]
  * @property {HTMLElement | null} [messageTarget]
  * @property {{ tab?: string, reason?: string, source?: string }} [telemetry]
+ * @property {boolean} [stepUpAttempted]
  */
 
 const JSON_CONTENT_TYPE = 'application/json';
@@ -1210,6 +1211,12 @@ export const create = (options = {}) => {
     typeof options.request === 'function'
       ? options.request
       : fetch.bind(globalThis);
+  const promptStepUpCode =
+    typeof options.promptStepUpCode === 'function'
+      ? options.promptStepUpCode
+      : () => (typeof window !== 'undefined' && typeof window.prompt === 'function'
+        ? window.prompt('Enter your authenticator code to confirm this change.')
+        : null);
 
   /**
    * Parse response payloads defensively because some local/runtime paths may
@@ -1403,6 +1410,28 @@ export const create = (options = {}) => {
       throw unauthorizedError;
     }
 
+    if (
+      response.status === 403 &&
+      String(response.headers.get('x-shuma-step-up') || '').toLowerCase() === 'required' &&
+      options.stepUpAttempted !== true
+    ) {
+      const code = String((await promptStepUpCode()) || '').trim();
+      if (code && context.sessionAuth === true) {
+        await request('/shuma/admin/mfa/step-up', {
+          method: 'POST',
+          json: { code },
+          messageTarget: options.messageTarget,
+          stepUpAttempted: true
+        });
+        return request(path, { ...options, stepUpAttempted: true });
+      }
+      if (code) {
+        const retryHeaders = new Headers(options.headers || {});
+        retryHeaders.set('X-Shuma-OTP', code);
+        return request(path, { ...options, headers: retryHeaders, stepUpAttempted: true });
+      }
+    }
+
     if (!response.ok) {
       const apiError = new DashboardApiError(
         errorMessageFromPayload(payload),
@@ -2,6 +2,9 @@
 
 export const advancedConfigTemplatePaths = Object.freeze([
     'shadow_mode',
+    'shadow_policy_sources',
+    'request_facts_journal_sample_percent',
+    'config_profiles',
     'adversary_sim_duration_seconds',
     'ban_duration',
     'ban_durations.honeypot',
@@ -33,6 +36,7 @@ export const advancedConfigTemplatePaths = Object.freeze([
     'ip_range_policy_mode',
     'ip_range_emergency_allowlist',
     'ip_range_custom_rules',
+    'custom_rules',
     'maze_enabled',
     'tarpit_enabled',
     'tarpit_progress_token_ttl_seconds',
@@ -116,6 +120,7 @@ export const advancedConfigTemplatePaths = Object.freeze([
     'not_a_bot_marker_ttl_seconds',
     'not_a_bot_attempt_limit_per_window',
     'not_a_bot_attempt_window_seconds',
+    'not_a_bot_accessible_questions',
     'challenge_puzzle_enabled',
     'challenge_puzzle_transform_count',
     'challenge_puzzle_seed_ttl_seconds',
@@ -128,6 +133,10 @@ export const advancedConfigTemplatePaths = Object.freeze([
     'botness_weights.rate_medium',
     'botness_weights.rate_high',
     'botness_weights.maze_behavior',
+    'botness_weights.session_asset_ratio',
+    'botness_weights.session_traversal',
+    'botness_weights.session_timing',
+    'botness_weights.session_referer',
     'defence_modes.rate',
     'defence_modes.geo',
     'defence_modes.js',
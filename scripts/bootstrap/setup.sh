@@ -160,11 +160,28 @@ SHUMA_JS_SECRET=${SHUMA_JS_SECRET:-}
 SHUMA_POW_SECRET=${SHUMA_POW_SECRET:-}
 SHUMA_CHALLENGE_SECRET=${SHUMA_CHALLENGE_SECRET:-}
 SHUMA_MAZE_PREVIEW_SECRET=${SHUMA_MAZE_PREVIEW_SECRET:-}
+SHUMA_PRIVACY_PASS_ISSUER_SECRET=${SHUMA_PRIVACY_PASS_ISSUER_SECRET:-}
 SHUMA_FORWARDED_IP_SECRET=${SHUMA_FORWARDED_IP_SECRET:-}
 SHUMA_HEALTH_SECRET=${SHUMA_HEALTH_SECRET:-}
 SHUMA_ADMIN_IP_ALLOWLIST=${SHUMA_ADMIN_IP_ALLOWLIST:-}
 SHUMA_ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE=${SHUMA_ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE:-}
+SHUMA_ADMIN_OIDC_ISSUER=${SHUMA_ADMIN_OIDC_ISSUER:-}
+SHUMA_ADMIN_OIDC_CLIENT_ID=${SHUMA_ADMIN_OIDC_CLIENT_ID:-}
+SHUMA_ADMIN_OIDC_CLIENT_SECRET=${SHUMA_ADMIN_OIDC_CLIENT_SECRET:-}
+SHUMA_ADMIN_OIDC_REDIRECT_URI=${SHUMA_ADMIN_OIDC_REDIRECT_URI:-}
+SHUMA_ADMIN_OIDC_ROLE_CLAIM=${SHUMA_ADMIN_OIDC_ROLE_CLAIM:-}
+SHUMA_ADMIN_OIDC_ROLE_MAP=${SHUMA_ADMIN_OIDC_ROLE_MAP:-}
 SHUMA_EVENT_LOG_RETENTION_HOURS=${SHUMA_EVENT_LOG_RETENTION_HOURS:-}
+SHUMA_EVENT_EXPORT_SYSLOG_TARGET=${SHUMA_EVENT_EXPORT_SYSLOG_TARGET:-}
+SHUMA_EVENT_EXPORT_WEBHOOK_URL=${SHUMA_EVENT_EXPORT_WEBHOOK_URL:-}
+SHUMA_EVENT_EXPORT_WEBHOOK_SECRET=${SHUMA_EVENT_EXPORT_WEBHOOK_SECRET:-}
+SHUMA_EVENT_EXPORT_OTLP_ENDPOINT=${SHUMA_EVENT_EXPORT_OTLP_ENDPOINT:-}
+SHUMA_EVENT_EXPORT_OTLP_HEADERS=${SHUMA_EVENT_EXPORT_OTLP_HEADERS:-}
+SHUMA_ALERT_WEBHOOK_URL=${SHUMA_ALERT_WEBHOOK_URL:-}
+SHUMA_ALERT_WEBHOOK_SECRET=${SHUMA_ALERT_WEBHOOK_SECRET:-}
+SHUMA_ALERT_EMAIL_RELAY_URL=${SHUMA_ALERT_EMAIL_RELAY_URL:-}
+SHUMA_ALERT_EMAIL_RELAY_TOKEN=${SHUMA_ALERT_EMAIL_RELAY_TOKEN:-}
+SHUMA_ALERT_EMAIL_TO=${SHUMA_ALERT_EMAIL_TO:-}
 SHUMA_ADMIN_CONFIG_WRITE_ENABLED=${SHUMA_ADMIN_CONFIG_WRITE_ENABLED:-}
 SHUMA_KV_STORE_FAIL_OPEN=${SHUMA_KV_STORE_FAIL_OPEN:-}
 SHUMA_ENFORCE_HTTPS=${SHUMA_ENFORCE_HTTPS:-}
@@ -774,10 +791,27 @@ ensure_local_dev_secret "SHUMA_SIM_TELEMETRY_SECRET" 32
 ensure_env_local_default_from_defaults "SHUMA_POW_SECRET"
 ensure_env_local_default_from_defaults "SHUMA_CHALLENGE_SECRET"
 ensure_env_local_default_from_defaults "SHUMA_MAZE_PREVIEW_SECRET"
+ensure_env_local_default_from_defaults "SHUMA_PRIVACY_PASS_ISSUER_SECRET"
 ensure_env_local_default_from_defaults "SHUMA_HEALTH_SECRET"
 ensure_env_local_default_from_defaults "SHUMA_ADMIN_IP_ALLOWLIST"
 ensure_env_local_default_from_defaults "SHUMA_ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE"
+ensure_env_local_default_from_defaults "SHUMA_ADMIN_OIDC_ISSUER"
+ensure_env_local_default_from_defaults "SHUMA_ADMIN_OIDC_CLIENT_ID"
+ensure_env_local_default_from_defaults "SHUMA_ADMIN_OIDC_CLIENT_SECRET"
+ensure_env_local_default_from_defaults "SHUMA_ADMIN_OIDC_REDIRECT_URI"
+ensure_env_local_default_from_defaults "SHUMA_ADMIN_OIDC_ROLE_CLAIM"
+ensure_env_local_default_from_defaults "SHUMA_ADMIN_OIDC_ROLE_MAP"
 ensure_env_local_default_from_defaults "SHUMA_EVENT_LOG_RETENTION_HOURS"
+ensure_env_local_default_from_defaults "SHUMA_EVENT_EXPORT_SYSLOG_TARGET"
+ensure_env_local_default_from_defaults "SHUMA_EVENT_EXPORT_WEBHOOK_URL"
+ensure_env_local_default_from_defaults "SHUMA_EVENT_EXPORT_WEBHOOK_SECRET"
+ensure_env_local_default_from_defaults "SHUMA_EVENT_EXPORT_OTLP_ENDPOINT"
+ensure_env_local_default_from_defaults "SHUMA_EVENT_EXPORT_OTLP_HEADERS"
+ensure_env_local_default_from_defaults "SHUMA_ALERT_WEBHOOK_URL"
+ensure_env_local_default_from_defaults "SHUMA_ALERT_WEBHOOK_SECRET"
+ensure_env_local_default_from_defaults "SHUMA_ALERT_EMAIL_RELAY_URL"
+ensure_env_local_default_from_defaults "SHUMA_ALERT_EMAIL_RELAY_TOKEN"
+ensure_env_local_default_from_defaults "SHUMA_ALERT_EMAIL_TO"
 ensure_env_local_default_from_defaults "SHUMA_MONITORING_RETENTION_HOURS"
 ensure_env_local_default_from_defaults "SHUMA_MONITORING_ROLLUP_RETENTION_HOURS"
 ensure_env_local_default_from_defaults "SHUMA_ADMIN_CONFIG_WRITE_ENABLED"
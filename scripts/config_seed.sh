@@ -151,6 +151,7 @@ cat > "${tmp_json}" <<EOF
   "ip_range_policy_mode": "${SHUMA_IP_RANGE_POLICY_MODE}",
   "ip_range_emergency_allowlist": ${SHUMA_IP_RANGE_EMERGENCY_ALLOWLIST},
   "ip_range_custom_rules": ${SHUMA_IP_RANGE_CUSTOM_RULES},
+  "custom_rules": ${SHUMA_CUSTOM_RULES},
   "ip_range_suggestions_min_observations": ${SHUMA_IP_RANGE_SUGGESTIONS_MIN_OBSERVATIONS},
   "ip_range_suggestions_min_bot_events": ${SHUMA_IP_RANGE_SUGGESTIONS_MIN_BOT_EVENTS},
   "ip_range_suggestions_min_confidence_percent": ${SHUMA_IP_RANGE_SUGGESTIONS_MIN_CONFIDENCE_PERCENT},
@@ -160,6 +161,9 @@ cat > "${tmp_json}" <<EOF
   "ip_range_suggestions_ipv6_min_prefix_len": ${SHUMA_IP_RANGE_SUGGESTIONS_IPV6_MIN_PREFIX_LEN},
   "ip_range_suggestions_likely_human_sample_percent": ${SHUMA_IP_RANGE_SUGGESTIONS_LIKELY_HUMAN_SAMPLE_PERCENT},
   "shadow_mode": $(bool_norm "${SHUMA_SHADOW_MODE}"),
+  "shadow_policy_sources": ${SHUMA_SHADOW_POLICY_SOURCES},
+  "request_facts_journal_sample_percent": ${SHUMA_REQUEST_FACTS_JOURNAL_SAMPLE_PERCENT},
+  "config_profiles": ${SHUMA_CONFIG_PROFILES},
   "adversary_sim_enabled": $(bool_norm "${SHUMA_ADVERSARY_SIM_ENABLED}"),
   "adversary_sim_duration_seconds": ${SHUMA_ADVERSARY_SIM_DURATION_SECONDS},
   "maze_enabled": $(bool_norm "${SHUMA_MAZE_ENABLED}"),
@@ -241,13 +245,18 @@ cat > "${tmp_json}" <<EOF
   "not_a_bot_marker_ttl_seconds": ${SHUMA_NOT_A_BOT_MARKER_TTL_SECONDS},
   "not_a_bot_attempt_limit_per_window": ${SHUMA_NOT_A_BOT_ATTEMPT_LIMIT_PER_WINDOW},
   "not_a_bot_attempt_window_seconds": ${SHUMA_NOT_A_BOT_ATTEMPT_WINDOW_SECONDS},
+  "not_a_bot_accessible_questions": ${SHUMA_NOT_A_BOT_ACCESSIBLE_QUESTIONS},
   "botness_maze_threshold": ${SHUMA_BOTNESS_MAZE_THRESHOLD},
   "botness_weights": {
     "js_required": ${SHUMA_BOTNESS_WEIGHT_JS_REQUIRED},
     "geo_risk": ${SHUMA_BOTNESS_WEIGHT_GEO_RISK},
     "rate_medium": ${SHUMA_BOTNESS_WEIGHT_RATE_MEDIUM},
     "rate_high": ${SHUMA_BOTNESS_WEIGHT_RATE_HIGH},
-    "maze_behavior": ${SHUMA_BOTNESS_WEIGHT_MAZE_BEHAVIOR}
+    "maze_behavior": ${SHUMA_BOTNESS_WEIGHT_MAZE_BEHAVIOR},
+    "session_asset_ratio": ${SHUMA_BOTNESS_WEIGHT_SESSION_ASSET_RATIO},
+    "session_traversal": ${SHUMA_BOTNESS_WEIGHT_SESSION_TRAVERSAL},
+    "session_timing": ${SHUMA_BOTNESS_WEIGHT_SESSION_TIMING},
+    "session_referer": ${SHUMA_BOTNESS_WEIGHT_SESSION_REFERER}
   },
   "defence_modes": {
     "rate": "${SHUMA_MODE_RATE}",
@@ -272,7 +281,16 @@ cat > "${tmp_json}" <<EOF
     "directory_freshness_requirement_seconds": ${SHUMA_VERIFIED_IDENTITY_DIRECTORY_FRESHNESS_REQUIREMENT_SECONDS},
     "named_policies": ${SHUMA_VERIFIED_IDENTITY_NAMED_POLICIES},
     "category_defaults": ${SHUMA_VERIFIED_IDENTITY_CATEGORY_DEFAULTS},
-    "service_profiles": ${SHUMA_VERIFIED_IDENTITY_SERVICE_PROFILES}
+    "service_profiles": ${SHUMA_VERIFIED_IDENTITY_SERVICE_PROFILES},
+    "restrict_requests_per_minute": ${SHUMA_VERIFIED_IDENTITY_RESTRICT_REQUESTS_PER_MINUTE},
+    "restrict_denied_path_prefixes": ${SHUMA_VERIFIED_IDENTITY_RESTRICT_DENIED_PATH_PREFIXES},
+    "mtls_enabled": ${SHUMA_VERIFIED_IDENTITY_MTLS_ENABLED},
+    "mtls_ca_bundle": ${SHUMA_VERIFIED_IDENTITY_MTLS_CA_BUNDLE},
+    "usage_quotas": ${SHUMA_VERIFIED_IDENTITY_USAGE_QUOTAS},
+    "licence_offers": ${SHUMA_VERIFIED_IDENTITY_LICENCE_OFFERS},
+    "licence_keys": ${SHUMA_VERIFIED_IDENTITY_LICENCE_KEYS},
+    "delegation_keys": ${SHUMA_VERIFIED_IDENTITY_DELEGATION_KEYS},
+    "pinned_directories": ${SHUMA_VERIFIED_IDENTITY_PINNED_DIRECTORIES}
   }
 }
 EOF
@@ -47,6 +47,8 @@ EDGE_CRON_SCHEDULES = (
     "4-59/5 * * * *",
 )
 EDGE_CRON_SECRET_QUERY_KEY = "edge_cron_secret"
+EDGE_ALERT_RULES_CRON_JOB_NAME = "shuma-alert-rules-evaluate"
+EDGE_ALERT_RULES_CRON_SCHEDULE = "* * * * *"
 EDGE_ADVERSARY_SIM_SMOKE_TIMEOUT_SECONDS = 185
 EDGE_ADVERSARY_SIM_CONTROL_TIMEOUT_SECONDS = 90
 EDGE_CONTROL_LEASE_RELEASE_TIMEOUT_SECONDS = 45
@@ -404,6 +406,16 @@ def redacted_edge_cron_path_and_query() -> str:
     return f"/shuma/internal/adversary-sim/beat?{EDGE_CRON_SECRET_QUERY_KEY}=<redacted>"
 
 
+def alert_rules_cron_path_and_query(env: dict[str, str]) -> str:
+    secret = required_env_value(env, "SHUMA_ADVERSARY_SIM_EDGE_CRON_SECRET")
+    query = urllib_parse.urlencode({EDGE_CRON_SECRET_QUERY_KEY: secret})
+    return f"/shuma/internal/alerts/evaluate?{query}"
+
+
+def redacted_alert_rules_cron_path_and_query() -> str:
+    return f"/shuma/internal/alerts/evaluate?{EDGE_CRON_SECRET_QUERY_KEY}=<redacted>"
+
+
 def cron_scope_args(*, app_id: str, account_id: str, account_name: str) -> list[str]:
     args: list[str] = []
     if app_id:
@@ -528,6 +540,61 @@ def ensure_adversary_sim_edge_cron(*, env: dict[str, str], app_id: str, account_
     }
 
 
+def ensure_alert_rules_edge_cron(*, env: dict[str, str], app_id: str, account_id: str, account_name: str) -> dict[str, str]:
+    scope_args = cron_scope_args(app_id=app_id, account_id=account_id, account_name=account_name)
+    existing_names = listed_cron_job_names_for_scope(
+        env=env,
+        app_id=app_id,
+        account_id=account_id,
+        account_name=account_name,
+    )
+    if EDGE_ALERT_RULES_CRON_JOB_NAME in existing_names:
+        delete_command = ["spin", "aka", "cron", "delete", *scope_args, EDGE_ALERT_RULES_CRON_JOB_NAME]
+        deleted = run_command(delete_command, env=env, cwd=REPO_ROOT)
+        if deleted.returncode != 0:
+            raise SystemExit(
+                deleted.stderr.strip()
+                or deleted.stdout.strip()
+                or f"spin aka cron delete failed for {EDGE_ALERT_RULES_CRON_JOB_NAME}"
+            )
+
+    create_command = [
+        "spin",
+        "aka",
+        "cron",
+        "create",
+        *scope_args,
+        "--name",
+        EDGE_ALERT_RULES_CRON_JOB_NAME,
+        "--schedule",
+        EDGE_ALERT_RULES_CRON_SCHEDULE,
+        "--path-and-query",
+        alert_rules_cron_path_and_query(env),
+    ]
+    created = run_command(create_command, env=env, cwd=REPO_ROOT)
+    if created.returncode != 0:
+        raise SystemExit(
+            created.stderr.strip()
+            or created.stdout.strip()
+            or f"spin aka cron create failed for {EDGE_ALERT_RULES_CRON_JOB_NAME}"
+        )
+
+    verified_names = listed_cron_job_names_for_scope(
+        env=env,
+        app_id=app_id,
+        account_id=account_id,
+        account_name=account_name,
+    )
+    if EDGE_ALERT_RULES_CRON_JOB_NAME not in verified_names:
+        raise SystemExit("Fermyon edge cron verification failed: alert-rules evaluation job was not present after create")
+
+    return {
+        "job_name": EDGE_ALERT_RULES_CRON_JOB_NAME,
+        "schedule": EDGE_ALERT_RULES_CRON_SCHEDULE,
+        "path_and_query": redacted_alert_rules_cron_path_and_query(),
+    }
+
+
 def admin_session_opener(base_url: str, env: dict[str, str]) -> tuple[Any, str]:
     cookie_jar = http.cookiejar.CookieJar()
     opener = urllib_request.build_opener(urllib_request.HTTPCookieProcessor(cookie_jar))
@@ -951,6 +1018,12 @@ def main(argv: Sequence[str] | None = None) -> int:
         account_id=account_id,
         account_name=account_name,
     )
+    alert_rules_cron_job = ensure_alert_rules_edge_cron(
+        env=env,
+        app_id=app_id,
+        account_id=account_id,
+        account_name=account_name,
+    )
     print("Smoke step 1/4: bootstrap remote config if missing")
     bootstrap_remote_config_if_missing(primary_url, env)
     print("Smoke step 2/4: verify dashboard/public/admin routes")
@@ -976,6 +1049,7 @@ def main(argv: Sequence[str] | None = None) -> int:
             "primary_url": primary_url,
             "auth_mode": auth_mode,
             "cron": cron_job,
+            "alert_rules_cron": alert_rules_cron_job,
             "info": account_info,
             "status": status_payload,
         },
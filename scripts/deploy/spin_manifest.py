@@ -29,11 +29,28 @@ FERMYON_EDGE_RUNTIME_ENV_KEYS = (
     "SHUMA_POW_SECRET",
     "SHUMA_CHALLENGE_SECRET",
     "SHUMA_MAZE_PREVIEW_SECRET",
+    "SHUMA_PRIVACY_PASS_ISSUER_SECRET",
     "SHUMA_FORWARDED_IP_SECRET",
     "SHUMA_HEALTH_SECRET",
     "SHUMA_ADMIN_IP_ALLOWLIST",
     "SHUMA_ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE",
+    "SHUMA_ADMIN_OIDC_ISSUER",
+    "SHUMA_ADMIN_OIDC_CLIENT_ID",
+    "SHUMA_ADMIN_OIDC_CLIENT_SECRET",
+    "SHUMA_ADMIN_OIDC_REDIRECT_URI",
+    "SHUMA_ADMIN_OIDC_ROLE_CLAIM",
+    "SHUMA_ADMIN_OIDC_ROLE_MAP",
     "SHUMA_EVENT_LOG_RETENTION_HOURS",
+    "SHUMA_EVENT_EXPORT_SYSLOG_TARGET",
+    "SHUMA_EVENT_EXPORT_WEBHOOK_URL",
+    "SHUMA_EVENT_EXPORT_WEBHOOK_SECRET",
+    "SHUMA_EVENT_EXPORT_OTLP_ENDPOINT",
+    "SHUMA_EVENT_EXPORT_OTLP_HEADERS",
+    "SHUMA_ALERT_WEBHOOK_URL",
+    "SHUMA_ALERT_WEBHOOK_SECRET",
+    "SHUMA_ALERT_EMAIL_RELAY_URL",
+    "SHUMA_ALERT_EMAIL_RELAY_TOKEN",
+    "SHUMA_ALERT_EMAIL_TO",
     "SHUMA_MONITORING_RETENTION_HOURS",
     "SHUMA_MONITORING_ROLLUP_RETENTION_HOURS",
     "SHUMA_KV_STORE_FAIL_OPEN",
@@ -9,11 +9,13 @@ fi
 ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
 APP_PID=""
 OVERSIGHT_MANAGER_PID=""
+ALERT_RULES_MANAGER_PID=""
 
 BASE_URL="${SHUMA_ADVERSARY_SIM_SUPERVISOR_BASE_URL:-http://127.0.0.1:3000}"
 ADMIN_API_KEY="${SHUMA_API_KEY:-}"
 FORWARDED_SECRET="${SHUMA_FORWARDED_IP_SECRET:-}"
 PERIODIC_INTERVAL_SECONDS=300
+ALERT_RULES_INTERVAL_SECONDS=60
 
 post_periodic_agent_run() {
   if [[ -z "${ADMIN_API_KEY}" ]]; then
@@ -37,6 +39,33 @@ post_periodic_agent_run() {
     --data '{"trigger_kind":"periodic_supervisor"}' >/dev/null
 }
 
+post_alert_rules_evaluation() {
+  if [[ -z "${ADMIN_API_KEY}" ]]; then
+    return 1
+  fi
+
+  local headers=(
+    -H "Authorization: Bearer ${ADMIN_API_KEY}"
+    -H "X-Forwarded-For: 127.0.0.1"
+    -H "X-Forwarded-Proto: https"
+    -H "X-Shuma-Internal-Supervisor: alert-rules"
+  )
+  if [[ -n "${FORWARDED_SECRET}" ]]; then
+    headers+=(-H "X-Shuma-Forwarded-Secret: ${FORWARDED_SECRET}")
+  fi
+
+  curl -fsS --max-time 5 -X POST \
+    "${headers[@]}" \
+    "${BASE_URL}/shuma/internal/alerts/evaluate" >/dev/null
+}
+
+run_alert_rules_manager() {
+  while kill -0 "${APP_PID}" 2>/dev/null; do
+    post_alert_rules_evaluation || true
+    sleep "${ALERT_RULES_INTERVAL_SECONDS}"
+  done
+}
+
 run_oversight_manager() {
   while kill -0 "${APP_PID}" 2>/dev/null; do
     post_periodic_agent_run || true
@@ -50,6 +79,11 @@ cleanup() {
     wait "${OVERSIGHT_MANAGER_PID}" 2>/dev/null || true
     OVERSIGHT_MANAGER_PID=""
   fi
+  if [[ -n "${ALERT_RULES_MANAGER_PID}" ]]; then
+    kill "${ALERT_RULES_MANAGER_PID}" 2>/dev/null || true
+    wait "${ALERT_RULES_MANAGER_PID}" 2>/dev/null || true
+    ALERT_RULES_MANAGER_PID=""
+  fi
 }
 
 trap cleanup EXIT INT TERM
@@ -58,10 +92,12 @@ trap cleanup EXIT INT TERM
 APP_PID=$!
 
 if [[ -z "${ADMIN_API_KEY}" ]]; then
-  echo "[oversight-supervisor] disabled: SHUMA_API_KEY is empty; cannot post /shuma/internal/oversight/agent/run" >&2
+  echo "[oversight-supervisor] disabled: SHUMA_API_KEY is empty; cannot post /shuma/internal/oversight/agent/run or /shuma/internal/alerts/evaluate" >&2
 else
   run_oversight_manager &
   OVERSIGHT_MANAGER_PID=$!
+  run_alert_rules_manager &
+  ALERT_RULES_MANAGER_PID=$!
 fi
 
 APP_EXIT=0
@@ -250,6 +250,10 @@ class DeployFermyonAkamaiEdgeTests(unittest.TestCase):
             "ensure_adversary_sim_edge_cron",
             return_value={"job_name_prefix": "shuma-adversary-sim-beat", "job_count": 5},
         ) as cron_mock, patch.object(
+            deploy,
+            "ensure_alert_rules_edge_cron",
+            return_value={"job_name": "shuma-alert-rules-evaluate"},
+        ) as alert_cron_mock, patch.object(
             deploy, "smoke_adversary_sim_generation"
         ) as sim_smoke_mock, patch.object(
             deploy, "fetch_aka_info", return_value={"account": {"id": "acc_123"}}
@@ -277,6 +281,12 @@ class DeployFermyonAkamaiEdgeTests(unittest.TestCase):
             account_id="acc_123",
             account_name="",
         )
+        alert_cron_mock.assert_called_once_with(
+            env=unittest.mock.ANY,
+            app_id="app_existing_123",
+            account_id="acc_123",
+            account_name="",
+        )
         sim_smoke_mock.assert_called_once_with("https://app.example.com", unittest.mock.ANY)
         deploy_command = interactive_calls[0]
         self.assertIn("--variable", deploy_command)
@@ -405,6 +415,78 @@ class DeployFermyonAkamaiEdgeTests(unittest.TestCase):
         self.assertEqual(cron["schedules"], list(deploy.EDGE_CRON_SCHEDULES))
         self.assertEqual(cron["path_and_query"], "/shuma/internal/adversary-sim/beat?edge_cron_secret=<redacted>")
 
+    def test_ensure_alert_rules_edge_cron_recreates_minutely_evaluation_job(self) -> None:
+        calls: list[list[str]] = []
+
+        def fake_run(command, *, env=None, cwd=None, capture_output=True):
+            calls.append(list(command))
+            if command[:4] == ["spin", "aka", "cron", "list"]:
+                return result(
+                    stdout="\n".join(
+                        [
+                            "+----------------------------+-----------+-------------------------+",
+                            "| Name                       | Schedule  | Next Run                |",
+                            "+=================================================================+",
+                            "| shuma-alert-rules-evaluate | * * * * * | 2026-03-12 13:01:00 UTC |",
+                            "+----------------------------+-----------+-------------------------+",
+                        ]
+                    )
+                    + "\n"
+                )
+            if command[:4] == ["spin", "aka", "cron", "delete"]:
+                return result(stdout="deleted\n")
+            if command[:4] == ["spin", "aka", "cron", "create"]:
+                return result(stdout="created\n")
+            raise AssertionError(f"Unexpected command: {command}")
+
+        with patch.object(deploy, "run_command", side_effect=fake_run):
+            cron = deploy.ensure_alert_rules_edge_cron(
+                env={"SHUMA_ADVERSARY_SIM_EDGE_CRON_SECRET": "test-edge-cron-secret"},
+                app_id="app_123",
+                account_id="acc_123",
+                account_name="",
+            )
+
+        self.assertEqual(
+            calls[1],
+            [
+                "spin",
+                "aka",
+                "cron",
+                "delete",
+                "--app-id",
+                "app_123",
+                "--account-id",
+                "acc_123",
+                "shuma-alert-rules-evaluate",
+            ],
+        )
+        self.assertEqual(
+            calls[2],
+            [
+                "spin",
+                "aka",
+                "cron",
+                "create",
+                "--app-id",
+                "app_123",
+                "--account-id",
+                "acc_123",
+                "--name",
+                "shuma-alert-rules-evaluate",
+                "--schedule",
+                "* * * * *",
+                "--path-and-query",
+                "/shuma/internal/alerts/evaluate?edge_cron_secret=test-edge-cron-secret",
+            ],
+        )
+        self.assertEqual(len(calls), 4)
+        self.assertEqual(cron["schedule"], "* * * * *")
+        self.assertEqual(
+            cron["path_and_query"],
+            "/shuma/internal/alerts/evaluate?edge_cron_secret=<redacted>",
+        )
+
     def test_ensure_adversary_sim_edge_cron_tolerates_delete_when_job_is_already_gone(self) -> None:
         calls: list[list[str]] = []
 
@@ -629,6 +711,10 @@ class DeployFermyonAkamaiEdgeTests(unittest.TestCase):
             "ensure_adversary_sim_edge_cron",
             return_value={"job_name_prefix": "shuma-adversary-sim-beat", "job_count": 5},
         ) as cron_mock, patch.object(
+            deploy,
+            "ensure_alert_rules_edge_cron",
+            return_value={"job_name": "shuma-alert-rules-evaluate"},
+        ) as alert_cron_mock, patch.object(
             deploy, "smoke_adversary_sim_generation"
         ) as sim_smoke_mock, patch.object(
             deploy, "fetch_aka_info", return_value={"account": {"id": "acc_123"}}
@@ -654,6 +740,12 @@ class DeployFermyonAkamaiEdgeTests(unittest.TestCase):
             account_id="acc_123",
             account_name="",
         )
+        alert_cron_mock.assert_called_once_with(
+            env=unittest.mock.ANY,
+            app_id="app_existing_123",
+            account_id="acc_123",
+            account_name="",
+        )
         sim_smoke_mock.assert_called_once_with("https://app.example.com", unittest.mock.ANY)
         external_smoke_mock.assert_called_once_with("https://app.example.com", unittest.mock.ANY)
         self.assertEqual(len(interactive_calls), 1)
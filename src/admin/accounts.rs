@@ -0,0 +1,490 @@
+//! Named admin accounts and the role model behind admin route permissions.
+//!
+//! Accounts live in KV under `admin_account:v1:<username>`. Passwords are stored as
+//! `pbkdf2_sha256$<iterations>$<salt>$<hash>` so the work factor can be raised later without
+//! invalidating existing credentials. The shared `SHUMA_API_KEY` keeps working as an implicit
+//! owner, and `SHUMA_ADMIN_READONLY_API_KEY` as an implicit viewer.
+
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::Rng as _;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::challenge::KeyValueStore;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const ADMIN_ACCOUNT_KEY_PREFIX: &str = "admin_account:v1:";
+const PASSWORD_HASH_SCHEME: &str = "pbkdf2_sha256";
+const PASSWORD_HASH_ITERATIONS: u32 = 100_000;
+const PASSWORD_SALT_BYTES: usize = 16;
+const ADMIN_USERNAME_MAX_CHARS: usize = 64;
+const ADMIN_PASSWORD_MIN_CHARS: usize = 12;
+const ADMIN_PASSWORD_MAX_CHARS: usize = 256;
+const MAX_ADMIN_ACCOUNTS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    Viewer,
+    Analyst,
+    PolicyEditor,
+    SimOperator,
+    Owner,
+}
+
+impl AdminRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminRole::Viewer => "viewer",
+            AdminRole::Analyst => "analyst",
+            AdminRole::PolicyEditor => "policy_editor",
+            AdminRole::SimOperator => "sim_operator",
+            AdminRole::Owner => "owner",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Some(AdminRole::Viewer),
+            "analyst" => Some(AdminRole::Analyst),
+            "policy_editor" => Some(AdminRole::PolicyEditor),
+            "sim_operator" => Some(AdminRole::SimOperator),
+            "owner" => Some(AdminRole::Owner),
+            _ => None,
+        }
+    }
+
+    pub fn grants(self, permission: AdminPermission) -> bool {
+        matches!(
+            (self, permission),
+            (_, AdminPermission::Read)
+                | (AdminRole::Owner, _)
+                | (
+                    AdminRole::Analyst | AdminRole::PolicyEditor,
+                    AdminPermission::BanWrite
+                )
+                | (AdminRole::PolicyEditor, AdminPermission::PolicyWrite)
+                | (AdminRole::SimOperator, AdminPermission::SimControl)
+        )
+    }
+
+    /// Coarse access level kept for the session endpoint and legacy audit labels.
+    pub fn access_level(self) -> super::auth::AdminAccessLevel {
+        match self {
+            AdminRole::Viewer => super::auth::AdminAccessLevel::ReadOnly,
+            _ => super::auth::AdminAccessLevel::ReadWrite,
+        }
+    }
+}
+
+/// Permission an admin route demands; see `required_admin_permission` in the admin API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminPermission {
+    Read,
+    BanWrite,
+    PolicyWrite,
+    SimControl,
+    ManageAccounts,
+}
+
+impl AdminPermission {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminPermission::Read => "read",
+            AdminPermission::BanWrite => "ban_write",
+            AdminPermission::PolicyWrite => "policy_write",
+            AdminPermission::SimControl => "sim_control",
+            AdminPermission::ManageAccounts => "manage_accounts",
+        }
+    }
+
+    pub fn is_write(self) -> bool {
+        !matches!(self, AdminPermission::Read)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AdminAccount {
+    pub username: String,
+    pub role: AdminRole,
+    pub password_hash: String,
+    pub created_at_ts: u64,
+    pub updated_at_ts: u64,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub last_login_ts: Option<u64>,
+}
+
+/// Account view returned by the admin API; never carries the password hash.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AdminAccountSummary {
+    pub username: String,
+    pub role: AdminRole,
+    pub disabled: bool,
+    pub created_at_ts: u64,
+    pub updated_at_ts: u64,
+    pub last_login_ts: Option<u64>,
+}
+
+impl From<&AdminAccount> for AdminAccountSummary {
+    fn from(account: &AdminAccount) -> Self {
+        Self {
+            username: account.username.clone(),
+            role: account.role,
+            disabled: account.disabled,
+            created_at_ts: account.created_at_ts,
+            updated_at_ts: account.updated_at_ts,
+            last_login_ts: account.last_login_ts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AdminAccountUpsertRequest {
+    pub username: String,
+    #[serde(default)]
+    pub role: Option<AdminRole>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub disabled: Option<bool>,
+}
+
+fn account_key(username: &str) -> String {
+    format!("{}{}", ADMIN_ACCOUNT_KEY_PREFIX, username)
+}
+
+/// Lowercases and validates a username: 1-64 chars of `[a-z0-9._-]`.
+pub(crate) fn normalize_admin_username(raw: &str) -> Result<String, String> {
+    let username = raw.trim().to_ascii_lowercase();
+    if username.is_empty() || username.len() > ADMIN_USERNAME_MAX_CHARS {
+        return Err(format!(
+            "username must be 1-{} characters",
+            ADMIN_USERNAME_MAX_CHARS
+        ));
+    }
+    if !username
+        .chars()
+        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, '.' | '_' | '-'))
+    {
+        return Err("username may only contain a-z, 0-9, '.', '_' and '-'".to_string());
+    }
+    Ok(username)
+}
+
+fn validate_password(password: &str) -> Result<(), String> {
+    let chars = password.chars().count();
+    if !(ADMIN_PASSWORD_MIN_CHARS..=ADMIN_PASSWORD_MAX_CHARS).contains(&chars) {
+        return Err(format!(
+            "password must be {}-{} characters",
+            ADMIN_PASSWORD_MIN_CHARS, ADMIN_PASSWORD_MAX_CHARS
+        ));
+    }
+    Ok(())
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mac = HmacSha256::new_from_slice(password).expect("HMAC accepts any key length");
+    let mut first = mac.clone();
+    first.update(salt);
+    first.update(&1u32.to_be_bytes());
+    let mut block = first.finalize().into_bytes();
+    let mut derived: [u8; 32] = block.into();
+    for _ in 1..iterations {
+        let mut round = mac.clone();
+        round.update(&block);
+        block = round.finalize().into_bytes();
+        for (out, byte) in derived.iter_mut().zip(block.iter()) {
+            *out ^= byte;
+        }
+    }
+    derived
+}
+
+pub(crate) fn hash_admin_password(password: &str) -> String {
+    let mut salt = [0u8; PASSWORD_SALT_BYTES];
+    rand::rng().fill(&mut salt);
+    let derived = pbkdf2_sha256(password.as_bytes(), &salt, PASSWORD_HASH_ITERATIONS);
+    format!(
+        "{}${}${}${}",
+        PASSWORD_HASH_SCHEME,
+        PASSWORD_HASH_ITERATIONS,
+        STANDARD_NO_PAD.encode(salt),
+        STANDARD_NO_PAD.encode(derived)
+    )
+}
+
+pub(crate) fn verify_admin_password(password: &str, encoded: &str) -> bool {
+    let mut parts = encoded.split('$');
+    let (Some(scheme), Some(iterations), Some(salt), Some(expected), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return false;
+    };
+    if scheme != PASSWORD_HASH_SCHEME {
+        return false;
+    }
+    let Ok(iterations) = iterations.parse::<u32>() else {
+        return false;
+    };
+    let (Ok(salt), Ok(expected)) = (STANDARD_NO_PAD.decode(salt), STANDARD_NO_PAD.decode(expected))
+    else {
+        return false;
+    };
+    if iterations == 0 || expected.len() != 32 {
+        return false;
+    }
+    let derived = pbkdf2_sha256(password.as_bytes(), &salt, iterations);
+    let mut diff = 0u8;
+    for (a, b) in derived.iter().zip(expected.iter()) {
+        diff |= a ^ b;
+    }
+    diff == 0
+}
+
+pub(crate) fn load_admin_account<S: KeyValueStore>(store: &S, username: &str) -> Option<AdminAccount> {
+    let raw = store.get(account_key(username).as_str()).ok()??;
+    serde_json::from_slice::<AdminAccount>(raw.as_slice()).ok()
+}
+
+fn save_admin_account<S: KeyValueStore>(store: &S, account: &AdminAccount) -> Result<(), ()> {
+    let value = serde_json::to_vec(account).map_err(|_| ())?;
+    store.set(account_key(account.username.as_str()).as_str(), value.as_slice())
+}
+
+pub(crate) fn list_admin_accounts<S: KeyValueStore>(store: &S) -> Vec<AdminAccount> {
+    let mut accounts: Vec<AdminAccount> = store
+        .get_keys()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|key| key.strip_prefix(ADMIN_ACCOUNT_KEY_PREFIX))
+        .filter_map(|username| load_admin_account(store, username))
+        .collect();
+    accounts.sort_by(|a, b| a.username.cmp(&b.username));
+    accounts
+}
+
+/// Creates or updates an account. New accounts need a role and a password; updates change only
+/// the fields supplied.
+pub(crate) fn upsert_admin_account<S: KeyValueStore>(
+    store: &S,
+    request: AdminAccountUpsertRequest,
+    now: u64,
+) -> Result<AdminAccount, String> {
+    let username = normalize_admin_username(request.username.as_str())?;
+    if let Some(password) = request.password.as_deref() {
+        validate_password(password)?;
+    }
+    let account = match load_admin_account(store, username.as_str()) {
+        Some(mut account) => {
+            if let Some(role) = request.role {
+                account.role = role;
+            }
+            if let Some(password) = request.password.as_deref() {
+                account.password_hash = hash_admin_password(password);
+            }
+            if let Some(disabled) = request.disabled {
+                account.disabled = disabled;
+            }
+            account.updated_at_ts = now;
+            account
+        }
+        None => {
+            if list_admin_accounts(store).len() >= MAX_ADMIN_ACCOUNTS {
+                return Err(format!("at most {} admin accounts are supported", MAX_ADMIN_ACCOUNTS));
+            }
+            let role = request
+                .role
+                .ok_or_else(|| "role is required when creating an account".to_string())?;
+            let password = request
+                .password
+                .as_deref()
+                .ok_or_else(|| "password is required when creating an account".to_string())?;
+            AdminAccount {
+                username,
+                role,
+                password_hash: hash_admin_password(password),
+                created_at_ts: now,
+                updated_at_ts: now,
+                disabled: request.disabled.unwrap_or(false),
+                last_login_ts: None,
+            }
+        }
+    };
+    save_admin_account(store, &account).map_err(|_| "failed persisting admin account".to_string())?;
+    Ok(account)
+}
+
+pub(crate) fn delete_admin_account<S: KeyValueStore>(store: &S, username: &str) -> Result<bool, ()> {
+    if load_admin_account(store, username).is_none() {
+        return Ok(false);
+    }
+    store.delete(account_key(username).as_str())?;
+    Ok(true)
+}
+
+/// Checks a username/password pair and stamps the login time on success. Unknown usernames
+/// still pay for one hash so response timing does not reveal which accounts exist.
+pub(crate) fn verify_admin_account_login<S: KeyValueStore>(
+    store: &S,
+    username: &str,
+    password: &str,
+    now: u64,
+) -> Option<AdminAccount> {
+    let Some(mut account) = normalize_admin_username(username)
+        .ok()
+        .and_then(|username| load_admin_account(store, username.as_str()))
+    else {
+        let _ = pbkdf2_sha256(password.as_bytes(), &[0u8; PASSWORD_SALT_BYTES], PASSWORD_HASH_ITERATIONS);
+        return None;
+    };
+    if !verify_admin_password(password, account.password_hash.as_str()) || account.disabled {
+        return None;
+    }
+    account.last_login_ts = Some(now);
+    if save_admin_account(store, &account).is_err() {
+        eprintln!(
+            "[admin] failed recording last login for account {}",
+            account.username
+        );
+    }
+    Some(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStore;
+
+    #[test]
+    fn pbkdf2_matches_rfc7914_test_vector() {
+        let derived = pbkdf2_sha256(b"passwd", b"salt", 1);
+        assert_eq!(
+            derived[..16],
+            [
+                0x55, 0xac, 0x04, 0x6e, 0x56, 0xe3, 0x08, 0x9f, 0xec, 0x16, 0x91, 0xc2, 0x25,
+                0x44, 0xb6, 0x05
+            ]
+        );
+    }
+
+    fn pbkdf2_hex(password: &[u8], salt: &[u8], iterations: u32) -> String {
+        pbkdf2_sha256(password, salt, iterations)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    #[test]
+    fn pbkdf2_matches_multi_iteration_test_vectors() {
+        // RFC 6070 inputs with their published PBKDF2-HMAC-SHA256 outputs, plus the
+        // c=80000 vector from RFC 7914 section 11 (first 32 bytes).
+        assert_eq!(
+            pbkdf2_hex(b"password", b"salt", 2),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+        assert_eq!(
+            pbkdf2_hex(b"password", b"salt", 4096),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+        assert_eq!(
+            pbkdf2_hex(
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096
+            ),
+            "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1"
+        );
+        assert_eq!(
+            pbkdf2_hex(b"pass\0word", b"sa\0lt", 4096),
+            "89b69d0516f829893c696226650a86878c029ac13ee276509d5ae58b6466a724"
+        );
+        assert_eq!(
+            pbkdf2_hex(b"Password", b"NaCl", 80_000),
+            "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56"
+        );
+    }
+
+    #[test]
+    fn role_grants_follow_least_privilege_ladder() {
+        assert!(AdminRole::Viewer.grants(AdminPermission::Read));
+        assert!(!AdminRole::Viewer.grants(AdminPermission::BanWrite));
+        assert!(AdminRole::Analyst.grants(AdminPermission::BanWrite));
+        assert!(!AdminRole::Analyst.grants(AdminPermission::PolicyWrite));
+        assert!(AdminRole::PolicyEditor.grants(AdminPermission::PolicyWrite));
+        assert!(!AdminRole::PolicyEditor.grants(AdminPermission::SimControl));
+        assert!(AdminRole::SimOperator.grants(AdminPermission::SimControl));
+        assert!(!AdminRole::SimOperator.grants(AdminPermission::BanWrite));
+        assert!(!AdminRole::PolicyEditor.grants(AdminPermission::ManageAccounts));
+        assert!(AdminRole::Owner.grants(AdminPermission::ManageAccounts));
+    }
+
+    #[test]
+    fn accounts_store_salted_hashes_and_verify_logins() {
+        let store = InMemoryStore::default();
+        let request = AdminAccountUpsertRequest {
+            username: " Alice ".to_string(),
+            role: Some(AdminRole::Analyst),
+            password: Some("correct horse battery".to_string()),
+            disabled: None,
+        };
+        let account = upsert_admin_account(&store, request, 100).expect("account created");
+        assert_eq!(account.username, "alice");
+        assert!(account.password_hash.starts_with("pbkdf2_sha256$"));
+        assert!(!account.password_hash.contains("correct horse"));
+
+        let again = hash_admin_password("correct horse battery");
+        assert_ne!(again, account.password_hash, "salts must differ");
+
+        assert!(verify_admin_account_login(&store, "alice", "wrong password!!", 200).is_none());
+        assert!(verify_admin_account_login(&store, "bob", "correct horse battery", 200).is_none());
+        let login = verify_admin_account_login(&store, "ALICE", "correct horse battery", 200)
+            .expect("login succeeds");
+        assert_eq!(login.role, AdminRole::Analyst);
+        assert_eq!(
+            load_admin_account(&store, "alice").and_then(|account| account.last_login_ts),
+            Some(200)
+        );
+
+        upsert_admin_account(
+            &store,
+            AdminAccountUpsertRequest {
+                username: "alice".to_string(),
+                role: None,
+                password: None,
+                disabled: Some(true),
+            },
+            300,
+        )
+        .expect("account disabled");
+        assert!(verify_admin_account_login(&store, "alice", "correct horse battery", 400).is_none());
+    }
+
+    #[test]
+    fn new_accounts_require_role_and_strong_password() {
+        let store = InMemoryStore::default();
+        let missing_role = AdminAccountUpsertRequest {
+            username: "bob".to_string(),
+            role: None,
+            password: Some("long enough password".to_string()),
+            disabled: None,
+        };
+        assert!(upsert_admin_account(&store, missing_role, 1).is_err());
+        let short_password = AdminAccountUpsertRequest {
+            username: "bob".to_string(),
+            role: Some(AdminRole::Viewer),
+            password: Some("short".to_string()),
+            disabled: None,
+        };
+        assert!(upsert_admin_account(&store, short_password, 1).is_err());
+        assert!(normalize_admin_username("bad name").is_err());
+    }
+}
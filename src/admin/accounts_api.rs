@@ -0,0 +1,181 @@
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::admin::accounts::{
+    delete_admin_account, list_admin_accounts, normalize_admin_username, upsert_admin_account,
+    AdminAccountSummary, AdminAccountUpsertRequest,
+};
+
+pub(crate) fn handle_admin_accounts(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+) -> Response {
+    match *req.method() {
+        Method::Get => {
+            let accounts: Vec<AdminAccountSummary> = list_admin_accounts(store)
+                .iter()
+                .map(AdminAccountSummary::from)
+                .collect();
+            json_response(json!({ "accounts": accounts }))
+        }
+        Method::Post => handle_admin_accounts_upsert(req, store),
+        Method::Delete => handle_admin_accounts_delete(req, store),
+        _ => Response::new(405, "Method Not Allowed"),
+    }
+}
+
+fn json_response(body: serde_json::Value) -> Response {
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string()))
+        .build()
+}
+
+fn log_account_action(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    reason: &str,
+    outcome: String,
+) {
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: crate::admin::now_ts(),
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some(reason.to_string()),
+            outcome: Some(outcome),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
+        },
+    );
+}
+
+fn handle_admin_accounts_upsert(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+) -> Response {
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid account payload: {}", err)),
+    };
+    let request = match serde_json::from_value::<AdminAccountUpsertRequest>(payload) {
+        Ok(request) => request,
+        Err(err) => return Response::new(400, format!("Invalid account payload: {}", err)),
+    };
+    let password_changed = request.password.is_some();
+    let account = match upsert_admin_account(store, request, crate::admin::now_ts()) {
+        Ok(account) => account,
+        Err(err) => return Response::new(400, err),
+    };
+
+    log_account_action(
+        req,
+        store,
+        "admin_account_upsert",
+        format!(
+            "username={} role={} disabled={} password_changed={}",
+            account.username,
+            account.role.as_str(),
+            account.disabled,
+            password_changed
+        ),
+    );
+    json_response(json!({
+        "updated": true,
+        "account": AdminAccountSummary::from(&account),
+    }))
+}
+
+fn handle_admin_accounts_delete(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+) -> Response {
+    let Some(raw_username) = crate::request_validation::query_param(req.query(), "username") else {
+        return Response::new(400, "Missing username");
+    };
+    let username = match normalize_admin_username(raw_username.as_str()) {
+        Ok(username) => username,
+        Err(err) => return Response::new(400, err),
+    };
+    match delete_admin_account(store, username.as_str()) {
+        Ok(true) => {
+            log_account_action(
+                req,
+                store,
+                "admin_account_delete",
+                format!("username={}", username),
+            );
+            json_response(json!({ "deleted": true, "username": username }))
+        }
+        Ok(false) => Response::new(404, "Account not found"),
+        Err(()) => Response::new(500, "Failed deleting admin account"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::handle_admin_accounts;
+    use crate::test_support::InMemoryStore;
+    use spin_sdk::http::{Method, Request};
+
+    fn accounts_request(method: Method, uri: &str, body: serde_json::Value) -> Request {
+        let mut builder = Request::builder();
+        builder
+            .method(method)
+            .uri(uri)
+            .body(serde_json::to_vec(&body).expect("body serializes"));
+        builder.build()
+    }
+
+    #[test]
+    fn accounts_endpoint_creates_lists_and_deletes_without_exposing_hashes() {
+        let _lock = crate::test_support::lock_env();
+        let store = InMemoryStore::default();
+
+        let req = accounts_request(
+            Method::Post,
+            "/shuma/admin/accounts",
+            serde_json::json!({
+                "username": "Alice",
+                "role": "policy_editor",
+                "password": "correct horse battery"
+            }),
+        );
+        let resp = handle_admin_accounts(&req, &store);
+        assert_eq!(*resp.status(), 200);
+        let payload: serde_json::Value = serde_json::from_slice(resp.body()).expect("json body");
+        assert_eq!(payload["account"]["username"], "alice");
+        assert_eq!(payload["account"]["role"], "policy_editor");
+
+        let req = accounts_request(Method::Get, "/shuma/admin/accounts", serde_json::Value::Null);
+        let resp = handle_admin_accounts(&req, &store);
+        let body = String::from_utf8(resp.body().to_vec()).expect("utf8 body");
+        assert!(body.contains("\"alice\""));
+        assert!(!body.contains("pbkdf2"));
+
+        let req = accounts_request(
+            Method::Post,
+            "/shuma/admin/accounts",
+            serde_json::json!({ "username": "bob", "role": "root", "password": "x" }),
+        );
+        assert_eq!(*handle_admin_accounts(&req, &store).status(), 400);
+
+        let req = accounts_request(
+            Method::Delete,
+            "/shuma/admin/accounts?username=alice",
+            serde_json::Value::Null,
+        );
+        assert_eq!(*handle_admin_accounts(&req, &store).status(), 200);
+        let req = accounts_request(
+            Method::Delete,
+            "/shuma/admin/accounts?username=alice",
+            serde_json::Value::Null,
+        );
+        assert_eq!(*handle_admin_accounts(&req, &store).status(), 404);
+    }
+}
@@ -729,7 +729,7 @@ pub(crate) fn handle_admin_adversary_sim_history_cleanup(
                 cleanup_result.deleted_keys,
                 cleanup_result.deleted_by_family.len()
             )),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
 
@@ -790,7 +790,7 @@ pub(crate) fn log_adversary_sim_transition<S: crate::challenge::KeyValueStore>(
             "adversary_sim_transition",
             &["adversary_sim_control"],
             &["representative_adversary_effectiveness"],
-            auth.audit_actor_label(),
+            auth.audit_actor_label().as_str(),
             format!(
                 "adversary sim transition {} -> {} ({})",
                 transition.from.as_str(),
@@ -0,0 +1,227 @@
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::observability::alert_rules::{
+    alert_rules_from_request, alert_summary, evaluate_alert_rules, load_alert_state,
+    load_or_seed_alert_rules, save_alert_rules, AlertRulesUpsertRequest,
+};
+
+pub(crate) fn handle_admin_alert_rules(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    match *req.method() {
+        Method::Get => {
+            let rule_set = load_or_seed_alert_rules(store, site_id, crate::admin::now_ts());
+            let state = load_alert_state(store, site_id);
+            let body = serde_json::to_string(&json!({
+                "rules": rule_set,
+                "state": alert_summary(&rule_set, &state),
+            }))
+            .unwrap_or_else(|_| "{}".to_string());
+            Response::builder()
+                .status(200)
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "no-store")
+                .body(body)
+                .build()
+        }
+        Method::Post => handle_admin_alert_rules_update(req, store, site_id),
+        _ => Response::new(405, "Method Not Allowed"),
+    }
+}
+
+fn handle_admin_alert_rules_update(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if !crate::config::admin_config_write_enabled() {
+        return Response::new(
+            403,
+            "Alert-rule updates are disabled when SHUMA_ADMIN_CONFIG_WRITE_ENABLED=false",
+        );
+    }
+
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid alert-rules payload: {}", err)),
+    };
+    let request = match serde_json::from_value::<AlertRulesUpsertRequest>(payload) {
+        Ok(request) => request,
+        Err(err) => return Response::new(400, format!("Invalid alert-rules payload: {}", err)),
+    };
+
+    let updated_at_ts = crate::admin::now_ts();
+    let admin_id = crate::admin::auth::get_admin_id(req, store);
+    let rule_set = match alert_rules_from_request(request, updated_at_ts, "manual_admin_rules") {
+        Ok(rule_set) => rule_set,
+        Err(err) => return Response::new(400, err),
+    };
+    if save_alert_rules(store, site_id, &rule_set).is_err() {
+        return Response::new(500, "Failed persisting alert rules");
+    }
+    crate::observability::hot_read_projection::refresh_after_admin_mutation(store, site_id);
+
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: updated_at_ts,
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some("alert_rules_update".to_string()),
+            outcome: Some(rule_set.revision.clone()),
+            admin: Some(admin_id),
+        },
+    );
+
+    let state = load_alert_state(store, site_id);
+    let body = serde_json::to_string(&json!({
+        "updated": true,
+        "rules": rule_set,
+        "state": alert_summary(&rule_set, &state),
+    }))
+    .unwrap_or_else(|_| "{}".to_string());
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
+
+/// Scheduled evaluation hook called by the host supervisor loop or the edge cron, so rule
+/// evaluation and notification delivery never run inside a visitor or dashboard request.
+pub(crate) fn handle_internal_alert_rules_evaluate(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if !matches!(req.method(), &Method::Post | &Method::Get) {
+        return Response::new(405, "Method Not Allowed");
+    }
+    if !crate::admin::auth::is_internal_alert_rules_evaluate_request(req) {
+        return Response::new(
+            401,
+            "Unauthorized: Internal alert-rules scheduler authorization required",
+        );
+    }
+
+    let summary = evaluate_alert_rules(store, site_id, crate::admin::now_ts());
+    let body = serde_json::to_string(&summary).unwrap_or_else(|_| "{}".to_string());
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handle_admin_alert_rules, handle_internal_alert_rules_evaluate};
+    use crate::test_support::InMemoryStore;
+    use super::load_alert_state;
+    use spin_sdk::http::{Method, Request};
+
+    fn alert_rules_request(method: Method, body: serde_json::Value) -> Request {
+        let mut builder = Request::builder();
+        builder
+            .method(method)
+            .uri("/shuma/admin/alert-rules")
+            .body(serde_json::to_vec(&body).expect("body serializes"));
+        builder.build()
+    }
+
+    #[test]
+    fn get_alert_rules_seeds_defaults_and_reports_state() {
+        let _lock = crate::test_support::lock_env();
+        let store = InMemoryStore::default();
+        let req = alert_rules_request(Method::Get, serde_json::Value::Null);
+
+        let resp = handle_admin_alert_rules(&req, &store, "default");
+
+        assert_eq!(*resp.status(), 200);
+        let payload: serde_json::Value = serde_json::from_slice(resp.body()).expect("json body");
+        assert_eq!(payload["rules"]["schema_version"], "alert_rules_v1");
+        assert_eq!(payload["state"]["rows"].as_array().map(Vec::len), Some(5));
+        assert_eq!(payload["state"]["rows"][0]["status"], "ok");
+    }
+
+    #[test]
+    fn post_alert_rules_replaces_rule_set_and_rejects_invalid_rules() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = InMemoryStore::default();
+
+        let req = alert_rules_request(
+            Method::Post,
+            serde_json::json!({
+                "rules": [{
+                    "rule_id": "rate_limit_hits_1m",
+                    "metric": "rate_limit_hits",
+                    "threshold": 20,
+                    "window_seconds": 60,
+                    "cooldown_seconds": 300
+                }]
+            }),
+        );
+        let resp = handle_admin_alert_rules(&req, &store, "default");
+        assert_eq!(*resp.status(), 200);
+        let payload: serde_json::Value = serde_json::from_slice(resp.body()).expect("json body");
+        assert_eq!(payload["updated"], true);
+        assert_eq!(payload["rules"]["source"], "manual_admin_rules");
+        assert_eq!(payload["rules"]["rules"][0]["for_seconds"], 0);
+        assert_eq!(payload["state"]["rows"][0]["rule_id"], "rate_limit_hits_1m");
+
+        let req = alert_rules_request(
+            Method::Post,
+            serde_json::json!({
+                "rules": [{
+                    "rule_id": "too_short",
+                    "metric": "bans",
+                    "threshold": 5,
+                    "window_seconds": 10
+                }]
+            }),
+        );
+        let resp = handle_admin_alert_rules(&req, &store, "default");
+        assert_eq!(*resp.status(), 400);
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn internal_alert_evaluation_requires_scheduler_authorization_and_persists_state() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "test-admin-key");
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let store = InMemoryStore::default();
+
+        let unauthorized = alert_rules_request(Method::Post, serde_json::Value::Null);
+        let resp = handle_internal_alert_rules_evaluate(&unauthorized, &store, "default");
+        assert_eq!(*resp.status(), 401);
+        assert!(crate::observability::alert_rules::load_alert_rules(&store, "default").is_none());
+
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/internal/alerts/evaluate")
+            .header("authorization", "Bearer test-admin-key")
+            .header("x-shuma-forwarded-secret", "test-forwarded-secret")
+            .header("x-forwarded-proto", "https")
+            .header("x-forwarded-for", "127.0.0.1")
+            .header("x-shuma-internal-supervisor", "alert-rules");
+        let resp = handle_internal_alert_rules_evaluate(&builder.build(), &store, "default");
+        assert_eq!(*resp.status(), 200);
+        let payload: serde_json::Value = serde_json::from_slice(resp.body()).expect("json body");
+        assert_eq!(payload["rows"].as_array().map(Vec::len), Some(5));
+        assert!(load_alert_state(&store, "default").evaluated_at_ts > 0);
+
+        std::env::remove_var("SHUMA_API_KEY");
+        std::env::remove_var("SHUMA_FORWARDED_IP_SECRET");
+    }
+}
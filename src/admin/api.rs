@@ -17,6 +17,9 @@ use super::config_api::{
     handle_admin_config, handle_admin_config_bootstrap, handle_admin_config_export,
     handle_admin_config_validate,
 };
+use super::config_history_api::{
+    handle_admin_config_diff, handle_admin_config_history, handle_admin_config_rollback,
+};
 use super::diagnostics_api::{
     handle_admin_maze_preview, handle_admin_maze_seed_refresh, handle_admin_maze_seed_sources,
     handle_admin_tarpit_preview,
@@ -25,6 +28,15 @@ use super::monitoring_api::{
     handle_admin_events, handle_admin_ip_bans_delta, handle_admin_ip_bans_stream,
     handle_admin_monitoring, handle_admin_monitoring_delta, handle_admin_monitoring_stream,
 };
+use super::accounts::AdminPermission;
+use super::accounts_api::handle_admin_accounts;
+use super::alert_rules_api::handle_admin_alert_rules;
+use super::signature_directories_api::handle_admin_signature_directories;
+use super::mfa_api::{
+    admin_step_up_is_satisfied, handle_admin_mfa, handle_admin_mfa_step_up, step_up_header_code,
+    step_up_required_response,
+};
+use super::tokens_api::handle_admin_tokens;
 use super::operator_objectives_api::handle_admin_operator_objectives;
 use super::oversight_agent::OVERSIGHT_AGENT_INTERNAL_PATH;
 use super::oversight_api::{
@@ -32,6 +44,9 @@ use super::oversight_api::{
     handle_admin_oversight_reconcile, handle_internal_oversight_agent_run,
 };
 use super::operator_snapshot_api::handle_admin_operator_snapshot;
+use super::gitops_api::handle_admin_gitops_route;
+use super::policy_simulation_api::handle_admin_policy_simulation;
+use super::botness_calibration_api::handle_admin_botness_calibration;
 use super::replay_promotion_api::handle_admin_replay_promotion;
 #[cfg(test)]
 use super::recent_changes_ledger::load_operator_snapshot_recent_changes;
@@ -203,15 +218,21 @@ const IP_RANGE_MAX_CIDRS_PER_RULE: usize = 512;
 const IP_RANGE_MAX_EMERGENCY_ALLOWLIST: usize = 1024;
 const IP_RANGE_CUSTOM_MESSAGE_MAX_CHARS: usize = 280;
 const IP_RANGE_REDIRECT_URL_MAX_CHARS: usize = 512;
-pub(super) const CONFIG_EXPORT_SECRET_KEYS: [&str; 15] = [
+pub(super) const CONFIG_EXPORT_SECRET_KEYS: [&str; 21] = [
     "SHUMA_API_KEY",
     "SHUMA_ADMIN_READONLY_API_KEY",
+    "SHUMA_ADMIN_OIDC_CLIENT_SECRET",
     "SHUMA_JS_SECRET",
     "SHUMA_POW_SECRET",
     "SHUMA_CHALLENGE_SECRET",
     "SHUMA_MAZE_PREVIEW_SECRET",
+    "SHUMA_PRIVACY_PASS_ISSUER_SECRET",
     "SHUMA_FORWARDED_IP_SECRET",
     "SHUMA_HEALTH_SECRET",
+    "SHUMA_EVENT_EXPORT_WEBHOOK_SECRET",
+    "SHUMA_EVENT_EXPORT_OTLP_HEADERS",
+    "SHUMA_ALERT_WEBHOOK_SECRET",
+    "SHUMA_ALERT_EMAIL_RELAY_TOKEN",
     "SHUMA_SIM_TELEMETRY_SECRET",
     "SHUMA_FRONTIER_OPENAI_API_KEY",
     "SHUMA_FRONTIER_ANTHROPIC_API_KEY",
@@ -979,6 +1000,7 @@ pub(crate) fn persist_event_record<S: crate::challenge::KeyValueStore>(
             crate::observability::retention::register_event_log_key(store, hour, key.as_str());
             crate::observability::retention::run_worker_if_due(store);
             crate::observability::hot_read_projection::refresh_after_event_append(store, "default");
+            export_event_record(store, &record);
         }
         Err(_) => eprintln!(
             "[log_event] serialization error; dropping event for key {}",
@@ -987,6 +1009,17 @@ pub(crate) fn persist_event_record<S: crate::challenge::KeyValueStore>(
     }
 }
 
+/// External sinks only ever see the pseudonymized view; simulation traffic stays local.
+fn export_event_record<S: crate::challenge::KeyValueStore>(store: &S, record: &EventLogRecord) {
+    if record.is_simulation {
+        return;
+    }
+    match serde_json::to_value(pseudonymize_event_record(record)) {
+        Ok(event) => crate::observability::event_export::export_event(store, event, now_ts()),
+        Err(_) => eprintln!("[log_event] serialization error; skipping event export"),
+    }
+}
+
 pub fn log_event_with_execution_metadata<S: crate::challenge::KeyValueStore>(
     store: &S,
     entry: &EventLogEntry,
@@ -3192,6 +3225,41 @@ mod tests {
         assert_eq!(read_u64_counter(&store, scrub_counter_key.as_str()), 1);
     }
 
+    #[test]
+    fn log_event_queues_pseudonymized_record_for_configured_export_sinks() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_EVENT_EXPORT_WEBHOOK_URL", "http://127.0.0.1:9/events");
+        std::env::set_var("SHUMA_EVENT_EXPORT_WEBHOOK_SECRET", "export-secret");
+        let store = MockStore::new();
+        let entry = EventLogEntry {
+            ts: now_ts(),
+            event: EventType::Ban,
+            ip: Some("198.51.100.10".to_string()),
+            reason: Some("honeypot".to_string()),
+            outcome: None,
+            admin: Some("ops".to_string()),
+        };
+        log_event(&store, &entry);
+
+        let outbox: serde_json::Value = serde_json::from_slice(
+            store
+                .get("event_export:v1:outbox:webhook")
+                .unwrap()
+                .expect("webhook outbox")
+                .as_slice(),
+        )
+        .unwrap();
+        let queued = &outbox["pending"][0];
+        assert_eq!(
+            queued["ip"].as_str(),
+            Some(pseudonymize_ip_identifier("198.51.100.10").as_str())
+        );
+        assert_eq!(queued["admin"].as_str(), Some("[masked]"));
+        assert_eq!(queued["reason"].as_str(), Some("honeypot"));
+        std::env::remove_var("SHUMA_EVENT_EXPORT_WEBHOOK_URL");
+        std::env::remove_var("SHUMA_EVENT_EXPORT_WEBHOOK_SECRET");
+    }
+
     #[test]
     fn log_event_drops_secret_canary_and_emits_incident_state() {
         let store = MockStore::new();
@@ -4694,6 +4762,9 @@ mod tests {
             csrf_token: Some("csrf-token".to_string()),
             session_id: Some("session-abc".to_string()),
             session_expires_at: Some(now_ts().saturating_add(3600)),
+            role: Some(crate::admin::accounts::AdminRole::Owner),
+            username: None,
+            token: None,
         };
 
         let session_scope = dashboard_refresh_session_scope(&auth).expect("session scope");
@@ -4730,6 +4801,9 @@ mod tests {
             csrf_token: None,
             session_id: None,
             session_expires_at: None,
+            role: Some(crate::admin::accounts::AdminRole::Viewer),
+            username: None,
+            token: None,
         };
         assert!(dashboard_refresh_session_scope(&auth).is_none());
     }
@@ -4839,6 +4913,9 @@ mod admin_config_tests {
             csrf_token: None,
             session_id: None,
             session_expires_at: None,
+            role: Some(crate::admin::accounts::AdminRole::Owner),
+            username: None,
+            token: None,
         }
     }
 
@@ -4853,6 +4930,9 @@ mod admin_config_tests {
             csrf_token: Some(csrf_token.to_string()),
             session_id: Some(session_id.to_string()),
             session_expires_at: Some(session_expires_at),
+            role: Some(crate::admin::accounts::AdminRole::Owner),
+            username: None,
+            token: None,
         }
     }
 
@@ -5771,6 +5851,7 @@ mod admin_config_tests {
                     stable_identity: None,
                     operator: Some("openai".to_string()),
                     category: None,
+                    delegated: None,
                     path_prefixes: Vec::new(),
                 },
                 action: crate::bot_identity::policy::IdentityPolicyAction::UseServiceProfile(
@@ -6237,6 +6318,82 @@ mod admin_config_tests {
         );
     }
 
+    #[test]
+    fn admin_config_writes_are_versioned_diffable_and_roll_back() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        for rate_limit in [123, 456] {
+            let mut builder = Request::builder();
+            builder
+                .method(Method::Post)
+                .uri("/shuma/admin/config")
+                .header("x-shuma-change-reason", "load test prep")
+                .body(format!(r#"{{"rate_limit": {rate_limit}}}"#).into_bytes());
+            let resp = handle_admin_config(&builder.build(), &store, "default");
+            assert_eq!(*resp.status(), 200u16);
+        }
+
+        let history = handle_admin_config_history(
+            &make_request(Method::Get, "/shuma/admin/config/history", Vec::new()),
+            &store,
+            "default",
+        );
+        assert_eq!(*history.status(), 200u16);
+        let history: serde_json::Value = serde_json::from_slice(history.body()).unwrap();
+        assert_eq!(history["latest_version"], 3);
+        assert_eq!(history["versions"][2]["reason"], "baseline_before_history");
+        assert_eq!(history["versions"][0]["reason"], "config_patch: load test prep");
+        assert_eq!(history["versions"][0]["changed_fields"], json!(["rate_limit"]));
+
+        let diff = handle_admin_config_diff(
+            &make_request(Method::Get, "/shuma/admin/config/diff?from=2&to=3", Vec::new()),
+            &store,
+            "default",
+        );
+        let diff: serde_json::Value = serde_json::from_slice(diff.body()).unwrap();
+        assert_eq!(
+            diff["changes"],
+            json!([{"path": "rate_limit", "before": 123, "after": 456}])
+        );
+
+        let rollback = handle_admin_config_rollback(
+            &make_request(
+                Method::Post,
+                "/shuma/admin/config/rollback",
+                br#"{"version": 2, "reason": "bad threshold"}"#.to_vec(),
+            ),
+            &store,
+            "default",
+        );
+        assert_eq!(*rollback.status(), 200u16);
+        assert_eq!(
+            crate::config::Config::load(&store, "default")
+                .unwrap()
+                .rate_limit,
+            123
+        );
+        let latest = crate::admin::config_history::load_config_version(&store, "default", 4)
+            .expect("rollback recorded as a new version");
+        assert_eq!(latest.reason, "rollback_to_v2: bad threshold");
+        assert!(request_requires_admin_step_up(
+            "/shuma/admin/config/rollback",
+            &Method::Post
+        ));
+
+        let missing = handle_admin_config_rollback(
+            &make_request(
+                Method::Post,
+                "/shuma/admin/config/rollback",
+                br#"{"version": 99}"#.to_vec(),
+            ),
+            &store,
+            "default",
+        );
+        assert_eq!(*missing.status(), 404u16);
+    }
+
     #[test]
     fn admin_config_includes_runtime_environment_and_adversary_sim_state() {
         let _lock = crate::test_support::lock_env();
@@ -11657,6 +11814,11 @@ mod admin_config_tests {
         assert!(sanitize_path("/shuma/admin/ip-range/suggestions"));
         assert!(sanitize_path("/shuma/admin/operator-snapshot"));
         assert!(sanitize_path("/shuma/admin/operator-objectives"));
+        assert!(sanitize_path("/shuma/admin/alert-rules"));
+        assert!(sanitize_path("/shuma/admin/accounts"));
+        assert!(sanitize_path("/shuma/admin/tokens"));
+        assert!(sanitize_path("/shuma/admin/mfa"));
+        assert!(sanitize_path("/shuma/admin/mfa/step-up"));
         assert!(sanitize_path("/shuma/admin/replay-promotion"));
         assert!(sanitize_path("/shuma/admin/benchmark-suite"));
         assert!(sanitize_path("/shuma/admin/monitoring/stream"));
@@ -11664,6 +11826,139 @@ mod admin_config_tests {
         assert!(sanitize_path("/shuma/admin/adversary-sim/history/cleanup"));
     }
 
+    #[test]
+    fn admin_policy_simulation_replays_journal_under_candidate_patch() {
+        let _lock = crate::test_support::lock_env();
+        let store = TestStore::default();
+        for country in ["BR", "US"] {
+            let row = serde_json::from_value(json!({
+                "recorded_at_ts": now_ts(),
+                "stage": "second_tranche",
+                "method": "GET",
+                "path": "/catalog",
+                "ip_bucket": "198.51.100.0",
+                "user_agent_family": "other",
+                "honeypot_hit": false,
+                "rate_limit_exceeded": false,
+                "existing_ban": false,
+                "geo_country": country,
+                "needs_js": false,
+                "browser_navigation_like": true,
+                "botness_score": 0,
+                "not_a_bot_marker_valid": false,
+                "privacy_pass_token_valid": false
+            }))
+            .unwrap();
+            crate::observability::request_facts_journal::record_row(&store, "default", row)
+                .unwrap();
+        }
+
+        let req = make_request(
+            Method::Post,
+            "/shuma/admin/policy-simulation",
+            br#"{"patch":{"shadow_mode":false,"geo_block":["BR"]},"hours":2}"#.to_vec(),
+        );
+        let resp = handle_admin_policy_simulation(&req, &store, "default");
+        assert_eq!(*resp.status(), 200u16);
+        let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body["window_hours"], 2);
+        assert_eq!(body["report"]["replayed_requests"], 2);
+        assert_eq!(body["report"]["transitions"]["allow->block"], 1);
+
+        let invalid = make_request(
+            Method::Post,
+            "/shuma/admin/policy-simulation",
+            br#"{"patch":["not","an","object"]}"#.to_vec(),
+        );
+        let resp = handle_admin_policy_simulation(&invalid, &store, "default");
+        assert_eq!(*resp.status(), 400u16);
+        let get = make_request(Method::Get, "/shuma/admin/policy-simulation", Vec::new());
+        assert_eq!(
+            *handle_admin_policy_simulation(&get, &store, "default").status(),
+            405u16
+        );
+        assert!(sanitize_path("/shuma/admin/policy-simulation"));
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/policy-simulation", &Method::Post),
+            Some("config:read")
+        );
+    }
+
+    #[test]
+    fn admin_botness_calibration_fits_labelled_journal_and_proposes_bounded_weights() {
+        let _lock = crate::test_support::lock_env();
+        let store = TestStore::default();
+        let sim_site = crate::observability::request_facts_journal::adversary_sim_journal_site_id(
+            "default",
+        );
+        for index in 0..24 {
+            let human_signals = if index % 2 == 0 {
+                json!(["S_RATE_USAGE_MEDIUM"])
+            } else {
+                json!([])
+            };
+            let bot_signals = if index % 2 == 0 {
+                json!(["S_GEO_RISK", "S_RATE_USAGE_MEDIUM"])
+            } else {
+                json!(["S_GEO_RISK"])
+            };
+            for (site, signals, marker) in [
+                ("default", human_signals, true),
+                (sim_site.as_str(), bot_signals, false),
+            ] {
+                let row = serde_json::from_value(json!({
+                    "recorded_at_ts": now_ts(),
+                    "stage": "second_tranche",
+                    "method": "GET",
+                    "path": "/catalog",
+                    "ip_bucket": "198.51.100.0",
+                    "user_agent_family": "other",
+                    "honeypot_hit": false,
+                    "rate_limit_exceeded": false,
+                    "existing_ban": false,
+                    "needs_js": false,
+                    "browser_navigation_like": true,
+                    "botness_score": 1,
+                    "botness_signal_ids": signals,
+                    "not_a_bot_marker_valid": marker,
+                    "privacy_pass_token_valid": false
+                }))
+                .unwrap();
+                crate::observability::request_facts_journal::record_row(&store, site, row)
+                    .unwrap();
+            }
+        }
+
+        let req = make_request(
+            Method::Get,
+            "/shuma/admin/botness-calibration?hours=2",
+            Vec::new(),
+        );
+        let resp = handle_admin_botness_calibration(&req, &store, "default");
+        assert_eq!(*resp.status(), 200u16);
+        let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body["window_hours"], 2);
+        assert_eq!(body["report"]["bot_rows"], 24);
+        assert_eq!(body["report"]["human_rows"], 24);
+        assert_eq!(body["report"]["families"]["geo"]["roc_auc"], 1.0);
+        assert_eq!(body["proposal_status"], "proposed");
+        assert_eq!(body["proposal"]["patch"]["botness_weights"]["geo_risk"], 3);
+        assert_eq!(body["proposal"]["patch"]["botness_weights"]["rate_medium"], 0);
+        assert_eq!(body["proposal"]["matched_group_ids"], json!(["botness.thresholds"]));
+        assert_eq!(body["proposal"]["canary_requirement"], "required");
+
+        let post = make_request(Method::Post, "/shuma/admin/botness-calibration", Vec::new());
+        assert_eq!(
+            *handle_admin_botness_calibration(&post, &store, "default").status(),
+            405u16
+        );
+        assert!(sanitize_path("/shuma/admin/botness-calibration"));
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/botness-calibration", &Method::Get),
+            Some("config:read")
+        );
+    }
+
     #[test]
     fn admin_tarpit_preview_serves_progressive_bootstrap() {
         let _lock = crate::test_support::lock_env();
@@ -13068,6 +13363,105 @@ mod admin_config_tests {
             .is_some());
     }
 
+    #[test]
+    fn admin_verified_identity_usage_reports_metered_identities() {
+        let _lock = crate::test_support::lock_env();
+        let store = TestStore::default();
+        let identity = crate::bot_identity::contracts::VerifiedIdentityEvidence {
+            scheme: crate::bot_identity::contracts::IdentityScheme::HttpMessageSignatures,
+            stable_identity: "searchbot".to_string(),
+            operator: "example".to_string(),
+            category: crate::bot_identity::contracts::IdentityCategory::Search,
+            verification_strength:
+                crate::bot_identity::contracts::VerificationStrength::Cryptographic,
+            end_user_controlled: false,
+            directory_source: None,
+            provenance: crate::bot_identity::contracts::IdentityProvenance::Native,
+            delegation: None,
+        };
+        for path in ["/docs", "/docs", "/blog"] {
+            crate::bot_identity::usage::record_usage(
+                &store,
+                "default",
+                &crate::bot_identity::telemetry::IdentityUsageRecord::from_response(
+                    &identity, path, 200, 100,
+                ),
+                now_ts(),
+            );
+        }
+
+        let req = make_request(
+            Method::Get,
+            "/shuma/admin/verified-identity/usage?days=500",
+            Vec::new(),
+        );
+        let resp = handle_admin_verified_identity_usage(&req, &store, "default");
+        assert_eq!(*resp.status(), 200u16);
+        let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body["days"], 62);
+        assert_eq!(body["requests"], 3);
+        assert_eq!(body["bytes"], 300);
+        assert_eq!(body["identities"][0]["stable_identity"], "searchbot");
+        assert_eq!(body["identities"][0]["top_paths"][0]["path"], "/docs");
+        assert_eq!(body["operators"][0]["operator"], "example");
+        assert!(sanitize_path("/shuma/admin/verified-identity/usage"));
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/verified-identity/usage", &Method::Get),
+            Some("monitoring:read")
+        );
+
+        let post = make_request(Method::Post, "/shuma/admin/verified-identity/usage", Vec::new());
+        assert_eq!(
+            *handle_admin_verified_identity_usage(&post, &store, "default").status(),
+            405u16
+        );
+    }
+
+    #[test]
+    fn admin_verified_identity_licences_lists_the_ledger() {
+        let _lock = crate::test_support::lock_env();
+        let store = TestStore::default();
+        let identity = crate::bot_identity::contracts::VerifiedIdentityEvidence {
+            scheme: crate::bot_identity::contracts::IdentityScheme::HttpMessageSignatures,
+            stable_identity: "trainingbot".to_string(),
+            operator: "example".to_string(),
+            category: crate::bot_identity::contracts::IdentityCategory::Training,
+            verification_strength:
+                crate::bot_identity::contracts::VerificationStrength::Cryptographic,
+            end_user_controlled: false,
+            directory_source: None,
+            provenance: crate::bot_identity::contracts::IdentityProvenance::Native,
+            delegation: None,
+        };
+        let licence = crate::bot_identity::licensing::VerifiedCrawlLicence {
+            licence_id: "lic-42".to_string(),
+            offer_id: "archive".to_string(),
+            key_id: "billing".to_string(),
+            issued_at: now_ts(),
+            expires_at: now_ts() + 3_600,
+        };
+        crate::bot_identity::licensing::record_accepted_licence(
+            &store,
+            "default",
+            &identity,
+            &licence,
+            now_ts(),
+        );
+
+        let req = make_request(Method::Get, "/shuma/admin/verified-identity/licences", Vec::new());
+        let resp = handle_admin_verified_identity_licences(&req, &store, "default");
+        assert_eq!(*resp.status(), 200u16);
+        let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body["identities"][0]["stable_identity"], "trainingbot");
+        assert_eq!(body["identities"][0]["licences"][0]["licence_id"], "lic-42");
+        assert_eq!(body["identities"][0]["licences"][0]["requests"], 1);
+        assert!(sanitize_path("/shuma/admin/verified-identity/licences"));
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/verified-identity/licences", &Method::Get),
+            Some("monitoring:read")
+        );
+    }
+
     #[test]
     fn admin_ip_range_suggestions_ignore_operator_originated_events() {
         let _lock = crate::test_support::lock_env();
@@ -14708,7 +15102,7 @@ mod admin_config_tests {
     }
 
     #[test]
-    fn admin_config_rejects_invalid_defence_mode_value() {
+    fn admin_config_updates_and_validates_custom_rules() {
         let _lock = crate::test_support::lock_env();
         std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
         let store = TestStore::default();
@@ -14716,37 +15110,55 @@ mod admin_config_tests {
         let post_req = make_request(
             Method::Post,
             "/shuma/admin/config",
-            br#"{"defence_modes":{"rate":"invalid"}}"#.to_vec(),
+            br#"{
+                "custom_rules":[
+                    {
+                        "id":"checkout_guard",
+                        "priority":10,
+                        "when":"path starts_with \"/checkout\" && botness >= 4",
+                        "action":"challenge"
+                    }
+                ]
+            }"#
+            .to_vec(),
         );
         let post_resp = handle_admin_config(&post_req, &store, "default");
-        assert_eq!(*post_resp.status(), 400u16);
-        let msg = String::from_utf8_lossy(post_resp.body());
-        assert!(msg.contains("defence_modes.rate must be one of"));
+        assert_eq!(*post_resp.status(), 200u16);
 
-        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
-    }
+        let saved_bytes = store.get("config:default").unwrap().unwrap();
+        let saved_cfg: crate::config::Config = serde_json::from_slice(&saved_bytes).unwrap();
+        assert_eq!(saved_cfg.custom_rules.len(), 1);
+        assert_eq!(saved_cfg.custom_rules[0].id, "checkout_guard");
+        assert!(saved_cfg.custom_rules[0].enabled);
+        assert_eq!(
+            saved_cfg.custom_rules[0].action,
+            crate::config::CustomRuleAction::Challenge
+        );
 
-    #[test]
-    fn admin_config_rejects_unknown_defence_mode_key() {
-        let _lock = crate::test_support::lock_env();
-        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
-        let store = TestStore::default();
+        let invalid_expr = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"custom_rules":[{"id":"broken","when":"path ~= \"/x\"","action":"block"}]}"#
+                .to_vec(),
+        );
+        let invalid_expr_resp = handle_admin_config(&invalid_expr, &store, "default");
+        assert_eq!(*invalid_expr_resp.status(), 400u16);
+        assert!(String::from_utf8_lossy(invalid_expr_resp.body()).contains("custom_rules[0].when"));
 
-        let post_req = make_request(
+        let invalid_action = make_request(
             Method::Post,
             "/shuma/admin/config",
-            br#"{"defence_modes":{"rate":"both","foo":"off"}}"#.to_vec(),
+            br#"{"custom_rules":[{"id":"odd","when":"true","action":"tarpit"}]}"#.to_vec(),
         );
-        let post_resp = handle_admin_config(&post_req, &store, "default");
-        assert_eq!(*post_resp.status(), 400u16);
-        let msg = String::from_utf8_lossy(post_resp.body());
-        assert!(msg.contains("unknown field `foo`"));
+        let invalid_action_resp = handle_admin_config(&invalid_action, &store, "default");
+        assert_eq!(*invalid_action_resp.status(), 400u16);
+        assert!(String::from_utf8_lossy(invalid_action_resp.body()).contains("must be one of"));
 
         std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
     }
 
     #[test]
-    fn admin_config_updates_provider_backends_and_edge_mode() {
+    fn admin_config_accepts_per_source_shadow_modes() {
         let _lock = crate::test_support::lock_env();
         std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
         let store = TestStore::default();
@@ -14755,86 +15167,451 @@ mod admin_config_tests {
             Method::Post,
             "/shuma/admin/config",
             br#"{
-                "provider_backends": {
-                    "rate_limiter": "external",
-                    "ban_store": "external",
-                    "fingerprint_signal": "external"
-                },
-                "edge_integration_mode": "additive"
+                "shadow_policy_sources":["geo_block","honeypot:/trap"],
+                "ip_range_custom_rules":[
+                    {"id":"trial","cidrs":["198.51.100.0/24"],"action":"forbidden_403","execution_mode":"shadow"}
+                ],
+                "custom_rules":[
+                    {"id":"checkout_trial","when":"path starts_with \"/checkout\"","action":"block","execution_mode":"shadow"}
+                ]
             }"#
             .to_vec(),
         );
         let post_resp = handle_admin_config(&post_req, &store, "default");
         assert_eq!(*post_resp.status(), 200u16);
-        let post_json: serde_json::Value = serde_json::from_slice(post_resp.body()).unwrap();
-        let cfg = post_json.get("config").unwrap();
-        assert_eq!(
-            cfg.get("provider_backends"),
-            Some(&serde_json::json!({
-                "rate_limiter": "external",
-                "ban_store": "external",
-                "challenge_engine": "internal",
-                "maze_tarpit": "internal",
-                "fingerprint_signal": "external"
-            }))
-        );
-        assert_eq!(
-            cfg.get("edge_integration_mode"),
-            Some(&serde_json::json!("additive"))
-        );
 
         let saved_bytes = store.get("config:default").unwrap().unwrap();
         let saved_cfg: crate::config::Config = serde_json::from_slice(&saved_bytes).unwrap();
         assert_eq!(
-            saved_cfg.provider_backends.rate_limiter,
-            crate::config::ProviderBackend::External
-        );
-        assert_eq!(
-            saved_cfg.provider_backends.ban_store,
-            crate::config::ProviderBackend::External
+            saved_cfg.shadow_policy_sources,
+            vec!["geo_block".to_string(), "honeypot:/trap".to_string()]
         );
         assert_eq!(
-            saved_cfg.provider_backends.fingerprint_signal,
-            crate::config::ProviderBackend::External
+            saved_cfg.ip_range_custom_rules[0].execution_mode,
+            crate::config::PolicyExecutionMode::Shadow
         );
         assert_eq!(
-            saved_cfg.edge_integration_mode,
-            crate::config::EdgeIntegrationMode::Additive
+            saved_cfg.custom_rules[0].execution_mode,
+            crate::config::PolicyExecutionMode::Shadow
         );
 
-        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
-    }
-
-    #[test]
-    fn admin_config_rejects_invalid_provider_backend_value() {
-        let _lock = crate::test_support::lock_env();
-        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
-        let store = TestStore::default();
+        let unknown_source = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"shadow_policy_sources":["rate_limit"]}"#.to_vec(),
+        );
+        let unknown_source_resp = handle_admin_config(&unknown_source, &store, "default");
+        assert_eq!(*unknown_source_resp.status(), 400u16);
+        assert!(String::from_utf8_lossy(unknown_source_resp.body())
+            .contains("not a shadowable policy source"));
 
-        let post_req = make_request(
+        let bad_mode = make_request(
             Method::Post,
             "/shuma/admin/config",
-            br#"{"provider_backends":{"rate_limiter":"invalid"}}"#.to_vec(),
+            br#"{"custom_rules":[{"id":"x","when":"true","action":"block","execution_mode":"dry_run"}]}"#
+                .to_vec(),
         );
-        let post_resp = handle_admin_config(&post_req, &store, "default");
-        assert_eq!(*post_resp.status(), 400u16);
-        let msg = String::from_utf8_lossy(post_resp.body());
-        assert!(msg.contains("provider_backends.rate_limiter must be one of"));
+        let bad_mode_resp = handle_admin_config(&bad_mode, &store, "default");
+        assert_eq!(*bad_mode_resp.status(), 400u16);
+        assert!(String::from_utf8_lossy(bad_mode_resp.body()).contains("execution_mode"));
 
         std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
     }
 
+    struct LocalGitopsBans<'a>(&'a TestStore);
+
+    impl crate::admin::gitops_api::GitopsBanBackend for LocalGitopsBans<'_> {
+        fn list_active_bans(
+            &self,
+            site_id: &str,
+        ) -> Option<Vec<(String, crate::enforcement::ban::BanEntry)>> {
+            Some(crate::enforcement::ban::list_active_bans(self.0, site_id))
+        }
+
+        fn ban(&self, site_id: &str, ip: &str, duration_secs: u64) -> bool {
+            crate::enforcement::ban::ban_ip(self.0, site_id, ip, "manual_ban", duration_secs);
+            true
+        }
+
+        fn unban(&self, site_id: &str, ip: &str) -> bool {
+            crate::enforcement::ban::unban_ip(self.0, site_id, ip);
+            true
+        }
+    }
+
     #[test]
-    fn admin_config_rejects_invalid_edge_integration_mode() {
+    fn admin_gitops_plan_apply_and_drift_round_trip() {
+        use crate::admin::gitops_api::{
+            handle_admin_gitops_apply, handle_admin_gitops_plan, handle_admin_gitops_status,
+        };
         let _lock = crate::test_support::lock_env();
         std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
         let store = TestStore::default();
+        let bans = LocalGitopsBans(&store);
+        crate::enforcement::ban::ban_ip(&store, "default", "198.51.100.7", "manual_ban", 3_600);
+        crate::enforcement::ban::ban_ip(&store, "default", "198.51.100.8", "honeypot", 3_600);
+        let document = json!({
+            "schema_version": "desired_state_v1",
+            "config": {"rate_limit": 321, "geo_block": ["BR"]},
+            "bans": [{"ip": "203.0.113.9", "duration": 600}]
+        });
 
-        let post_req = make_request(
-            Method::Post,
-            "/shuma/admin/config",
-            br#"{"edge_integration_mode":"invalid"}"#.to_vec(),
-        );
+        let plan_resp = handle_admin_gitops_plan(
+            &make_request(
+                Method::Post,
+                "/shuma/admin/gitops/plan",
+                document.to_string().into_bytes(),
+            ),
+            &store,
+            "default",
+            &bans,
+        );
+        assert_eq!(*plan_resp.status(), 200u16);
+        let plan: serde_json::Value = serde_json::from_slice(plan_resp.body()).unwrap();
+        assert_eq!(plan["never_ring_paths"], json!(["geo_block"]));
+        assert_eq!(plan["ban_removals"], json!(["198.51.100.7"]));
+        let plan_id = plan["plan_id"].as_str().unwrap().to_string();
+
+        let apply = |plan_id: &str, acknowledge: bool| {
+            handle_admin_gitops_apply(
+                &make_request(
+                    Method::Post,
+                    "/shuma/admin/gitops/apply",
+                    json!({"document": document, "plan_id": plan_id, "acknowledge_never_ring": acknowledge})
+                        .to_string()
+                        .into_bytes(),
+                ),
+                &store,
+                "default",
+                &bans,
+            )
+        };
+        let unacknowledged = apply(plan_id.as_str(), false);
+        assert_eq!(*unacknowledged.status(), 400u16);
+        assert!(String::from_utf8_lossy(unacknowledged.body()).contains("acknowledge_never_ring"));
+        assert_eq!(*apply("stale", true).status(), 409u16);
+
+        let applied = apply(plan_id.as_str(), true);
+        assert_eq!(*applied.status(), 200u16);
+        let saved: crate::config::Config =
+            serde_json::from_slice(&store.get("config:default").unwrap().unwrap()).unwrap();
+        assert_eq!(saved.rate_limit, 321);
+        assert_eq!(saved.geo_block, vec!["BR".to_string()]);
+        let banned: Vec<String> = crate::enforcement::ban::list_active_bans(&store, "default")
+            .into_iter()
+            .map(|(ip, _)| ip)
+            .collect();
+        assert!(banned.contains(&"203.0.113.9".to_string()));
+        assert!(banned.contains(&"198.51.100.8".to_string()));
+        assert!(!banned.contains(&"198.51.100.7".to_string()));
+
+        let status = |store: &TestStore| -> serde_json::Value {
+            let resp = handle_admin_gitops_status(
+                &make_request(Method::Get, "/shuma/admin/gitops", Vec::new()),
+                store,
+                "default",
+                &bans,
+            );
+            serde_json::from_slice(resp.body()).unwrap()
+        };
+        assert_eq!(status(&store)["drifted"], false);
+
+        let out_of_band = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"rate_limit": 999}"#.to_vec(),
+        );
+        assert_eq!(*handle_admin_config(&out_of_band, &store, "default").status(), 200u16);
+        let drifted = status(&store);
+        assert_eq!(drifted["drifted"], true);
+        assert_eq!(
+            drifted["drift"],
+            json!([{"path": "rate_limit", "before": 321, "after": 999}])
+        );
+
+        let invalid = handle_admin_gitops_plan(
+            &make_request(
+                Method::Post,
+                "/shuma/admin/gitops/plan",
+                br#"{"config":{"adversary_sim_enabled":true}}"#.to_vec(),
+            ),
+            &store,
+            "default",
+            &bans,
+        );
+        assert_eq!(*invalid.status(), 400u16);
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/gitops/apply", &Method::Post),
+            Some("config:write")
+        );
+        assert!(request_requires_admin_step_up(
+            "/shuma/admin/gitops/apply",
+            &Method::Post
+        ));
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    /// Ban backend whose writes for one IP fail, to exercise apply rollback.
+    struct FailingGitopsBans<'a> {
+        inner: LocalGitopsBans<'a>,
+        failing_ip: &'static str,
+    }
+
+    impl crate::admin::gitops_api::GitopsBanBackend for FailingGitopsBans<'_> {
+        fn list_active_bans(
+            &self,
+            site_id: &str,
+        ) -> Option<Vec<(String, crate::enforcement::ban::BanEntry)>> {
+            self.inner.list_active_bans(site_id)
+        }
+
+        fn ban(&self, site_id: &str, ip: &str, duration_secs: u64) -> bool {
+            ip != self.failing_ip && self.inner.ban(site_id, ip, duration_secs)
+        }
+
+        fn unban(&self, site_id: &str, ip: &str) -> bool {
+            ip != self.failing_ip && self.inner.unban(site_id, ip)
+        }
+    }
+
+    #[test]
+    fn admin_gitops_apply_rolls_back_when_a_ban_write_fails() {
+        use crate::admin::gitops_api::{handle_admin_gitops_apply, handle_admin_gitops_plan};
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+        let bans = FailingGitopsBans {
+            inner: LocalGitopsBans(&store),
+            failing_ip: "203.0.113.10",
+        };
+        crate::enforcement::ban::ban_ip(&store, "default", "198.51.100.7", "manual_ban", 3_600);
+        let document = json!({
+            "schema_version": "desired_state_v1",
+            "config": {"rate_limit": 321},
+            "bans": [
+                {"ip": "203.0.113.9", "duration": 600},
+                {"ip": "203.0.113.10", "duration": 600}
+            ]
+        });
+        let plan_resp = handle_admin_gitops_plan(
+            &make_request(
+                Method::Post,
+                "/shuma/admin/gitops/plan",
+                document.to_string().into_bytes(),
+            ),
+            &store,
+            "default",
+            &bans,
+        );
+        let plan: serde_json::Value = serde_json::from_slice(plan_resp.body()).unwrap();
+        let config_before = store.get("config:default").unwrap();
+
+        let resp = handle_admin_gitops_apply(
+            &make_request(
+                Method::Post,
+                "/shuma/admin/gitops/apply",
+                json!({"document": document, "plan_id": plan["plan_id"]})
+                    .to_string()
+                    .into_bytes(),
+            ),
+            &store,
+            "default",
+            &bans,
+        );
+
+        assert_eq!(*resp.status(), 503u16);
+        let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(body["status"], "rolled_back");
+        assert_eq!(body["ban_failures"], json!(["203.0.113.10"]));
+        assert_eq!(store.get("config:default").unwrap(), config_before);
+        assert!(crate::admin::gitops::load_applied_state(&store, "default").is_none());
+        let banned: Vec<String> = crate::enforcement::ban::list_active_bans(&store, "default")
+            .into_iter()
+            .map(|(ip, _)| ip)
+            .collect();
+        assert_eq!(banned, vec!["198.51.100.7".to_string()]);
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn admin_config_accepts_scheduled_config_profiles_and_rejects_invalid_overlays() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        let post_req = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{
+                "config_profiles":[
+                    {"id":"launch","windows":[{"kind":"between","start_ts":1000,"end_ts":2000}],"overlay":{"rate_limit":25}}
+                ]
+            }"#
+            .to_vec(),
+        );
+        let post_resp = handle_admin_config(&post_req, &store, "default");
+        assert_eq!(*post_resp.status(), 200u16);
+
+        let saved_bytes = store.get("config:default").unwrap().unwrap();
+        let saved_cfg: crate::config::Config = serde_json::from_slice(&saved_bytes).unwrap();
+        assert_eq!(saved_cfg.config_profiles.len(), 1);
+        assert_eq!(saved_cfg.config_profiles[0].id, "launch");
+        assert_ne!(saved_cfg.rate_limit, 25);
+
+        let forbidden_key = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"config_profiles":[{"id":"x","windows":[{"kind":"between","start_ts":1,"end_ts":2}],"overlay":{"adversary_sim_enabled":true}}]}"#
+                .to_vec(),
+        );
+        let forbidden_resp = handle_admin_config(&forbidden_key, &store, "default");
+        assert_eq!(*forbidden_resp.status(), 400u16);
+        assert!(String::from_utf8_lossy(forbidden_resp.body())
+            .contains("not an overlayable config field"));
+
+        let malformed = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"config_profiles":[{"id":"x","windows":[{"kind":"monthly"}],"overlay":{"rate_limit":1}}]}"#
+                .to_vec(),
+        );
+        let malformed_resp = handle_admin_config(&malformed, &store, "default");
+        assert_eq!(*malformed_resp.status(), 400u16);
+        assert!(String::from_utf8_lossy(malformed_resp.body()).contains("config_profiles"));
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn admin_config_rejects_invalid_defence_mode_value() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        let post_req = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"defence_modes":{"rate":"invalid"}}"#.to_vec(),
+        );
+        let post_resp = handle_admin_config(&post_req, &store, "default");
+        assert_eq!(*post_resp.status(), 400u16);
+        let msg = String::from_utf8_lossy(post_resp.body());
+        assert!(msg.contains("defence_modes.rate must be one of"));
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn admin_config_rejects_unknown_defence_mode_key() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        let post_req = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"defence_modes":{"rate":"both","foo":"off"}}"#.to_vec(),
+        );
+        let post_resp = handle_admin_config(&post_req, &store, "default");
+        assert_eq!(*post_resp.status(), 400u16);
+        let msg = String::from_utf8_lossy(post_resp.body());
+        assert!(msg.contains("unknown field `foo`"));
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn admin_config_updates_provider_backends_and_edge_mode() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        let post_req = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{
+                "provider_backends": {
+                    "rate_limiter": "external",
+                    "ban_store": "external",
+                    "fingerprint_signal": "external"
+                },
+                "edge_integration_mode": "additive"
+            }"#
+            .to_vec(),
+        );
+        let post_resp = handle_admin_config(&post_req, &store, "default");
+        assert_eq!(*post_resp.status(), 200u16);
+        let post_json: serde_json::Value = serde_json::from_slice(post_resp.body()).unwrap();
+        let cfg = post_json.get("config").unwrap();
+        assert_eq!(
+            cfg.get("provider_backends"),
+            Some(&serde_json::json!({
+                "rate_limiter": "external",
+                "ban_store": "external",
+                "challenge_engine": "internal",
+                "maze_tarpit": "internal",
+                "fingerprint_signal": "external"
+            }))
+        );
+        assert_eq!(
+            cfg.get("edge_integration_mode"),
+            Some(&serde_json::json!("additive"))
+        );
+
+        let saved_bytes = store.get("config:default").unwrap().unwrap();
+        let saved_cfg: crate::config::Config = serde_json::from_slice(&saved_bytes).unwrap();
+        assert_eq!(
+            saved_cfg.provider_backends.rate_limiter,
+            crate::config::ProviderBackend::External
+        );
+        assert_eq!(
+            saved_cfg.provider_backends.ban_store,
+            crate::config::ProviderBackend::External
+        );
+        assert_eq!(
+            saved_cfg.provider_backends.fingerprint_signal,
+            crate::config::ProviderBackend::External
+        );
+        assert_eq!(
+            saved_cfg.edge_integration_mode,
+            crate::config::EdgeIntegrationMode::Additive
+        );
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn admin_config_rejects_invalid_provider_backend_value() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        let post_req = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"provider_backends":{"rate_limiter":"invalid"}}"#.to_vec(),
+        );
+        let post_resp = handle_admin_config(&post_req, &store, "default");
+        assert_eq!(*post_resp.status(), 400u16);
+        let msg = String::from_utf8_lossy(post_resp.body());
+        assert!(msg.contains("provider_backends.rate_limiter must be one of"));
+
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+
+    #[test]
+    fn admin_config_rejects_invalid_edge_integration_mode() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = TestStore::default();
+
+        let post_req = make_request(
+            Method::Post,
+            "/shuma/admin/config",
+            br#"{"edge_integration_mode":"invalid"}"#.to_vec(),
+        );
         let post_resp = handle_admin_config(&post_req, &store, "default");
         assert_eq!(*post_resp.status(), 400u16);
         let msg = String::from_utf8_lossy(post_resp.body());
@@ -15102,6 +15879,296 @@ mod admin_auth_tests {
         std::env::remove_var("SHUMA_API_KEY");
     }
 
+    #[test]
+    fn login_with_named_account_opens_session_carrying_username_and_role() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "test-admin-key");
+        let store = TestStore::default();
+        crate::admin::accounts::upsert_admin_account(
+            &store,
+            crate::admin::accounts::AdminAccountUpsertRequest {
+                username: "alice".to_string(),
+                role: Some(crate::admin::accounts::AdminRole::Analyst),
+                password: Some("correct horse battery".to_string()),
+                disabled: None,
+            },
+            now_ts(),
+        )
+        .expect("account created");
+
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/admin/login")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(b"username=alice&password=correct+horse+battery".to_vec());
+        let resp = handle_admin_login(&builder.build(), &store);
+        assert_eq!(*resp.status(), 303u16);
+        let cookie = resp
+            .header("set-cookie")
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.split(';').next())
+            .expect("session cookie")
+            .to_string();
+
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Get)
+            .uri("/shuma/admin/session")
+            .header("cookie", cookie.as_str());
+        let session_req = builder.build();
+        let auth = crate::admin::auth::authenticate_admin(&session_req, &store);
+        assert_eq!(auth.role, Some(crate::admin::accounts::AdminRole::Analyst));
+        assert_eq!(auth.audit_actor_label(), "user:alice");
+        assert_eq!(
+            crate::admin::auth::get_admin_id(&session_req, &store),
+            "user:alice"
+        );
+        let payload: serde_json::Value =
+            serde_json::from_slice(handle_admin_session(&session_req, &store).body())
+                .expect("session json");
+        assert_eq!(payload["username"], "alice");
+        assert_eq!(payload["role"], "analyst");
+
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/admin/login")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(b"username=alice&password=wrong+password+here".to_vec());
+        let resp = handle_admin_login(&builder.build(), &store);
+        assert!(resp
+            .header("location")
+            .and_then(|value| value.as_str())
+            .map(|value| value.contains("error=invalid_key"))
+            .unwrap_or(false));
+
+        std::env::remove_var("SHUMA_API_KEY");
+    }
+
+    #[test]
+    fn oidc_sign_in_against_mock_idp_opens_role_pinned_session() {
+        use crate::admin::oidc::tests::{mock_claims, MockIdp, MOCK_CLIENT_ID, MOCK_ISSUER};
+
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "test-admin-key");
+        let store = TestStore::default();
+        let mut claims = mock_claims();
+        claims["iat"] = serde_json::json!(now_ts());
+        claims["exp"] = serde_json::json!(now_ts() + 600);
+        let idp = MockIdp::new(claims);
+
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Get)
+            .uri("/shuma/admin/oidc/login?next=/shuma/dashboard/index.html");
+        let login_req = builder.build();
+        assert_eq!(
+            *handle_admin_oidc_login(&login_req, &store, &idp).status(),
+            404u16
+        );
+
+        std::env::set_var("SHUMA_ADMIN_OIDC_ISSUER", MOCK_ISSUER);
+        std::env::set_var("SHUMA_ADMIN_OIDC_CLIENT_ID", MOCK_CLIENT_ID);
+        std::env::set_var(
+            "SHUMA_ADMIN_OIDC_REDIRECT_URI",
+            "https://shuma.example.test/shuma/admin/oidc/callback",
+        );
+        std::env::set_var("SHUMA_ADMIN_OIDC_ROLE_MAP", "shuma-analysts=analyst");
+        let resp = handle_admin_oidc_login(&login_req, &store, &idp);
+        assert_eq!(*resp.status(), 302u16);
+        let location = resp
+            .header("location")
+            .and_then(|value| value.as_str())
+            .expect("issuer redirect")
+            .to_string();
+        assert!(location.starts_with("https://idp.example.test/authorize?"));
+        let flow_cookie = resp
+            .header("set-cookie")
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.split(';').next())
+            .expect("flow cookie")
+            .to_string();
+        let (code, state) = idp.authorize(location.as_str());
+
+        let callback_uri = format!("/shuma/admin/oidc/callback?code={}&state={}", code, state);
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Get)
+            .uri(callback_uri.as_str())
+            .header("cookie", flow_cookie.as_str());
+        let resp =
+            handle_admin_oidc_callback_with_failure_handler(&builder.build(), &store, &idp, || {
+                false
+            });
+        assert_eq!(*resp.status(), 303u16);
+        assert_eq!(
+            resp.header("location").and_then(|value| value.as_str()),
+            Some("/shuma/dashboard/index.html")
+        );
+        let set_cookie = resp
+            .header("set-cookie")
+            .and_then(|value| value.as_str())
+            .expect("session cookie")
+            .to_string();
+        assert!(set_cookie.contains("Max-Age=900"));
+        let session_cookie = set_cookie
+            .split(';')
+            .next()
+            .expect("session cookie")
+            .to_string();
+
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Get)
+            .uri("/shuma/admin/session")
+            .header("cookie", session_cookie.as_str());
+        let session_req = builder.build();
+        let auth = crate::admin::auth::authenticate_admin(&session_req, &store);
+        assert_eq!(auth.role, Some(crate::admin::accounts::AdminRole::Analyst));
+        assert!(auth.csrf_token.is_some());
+        assert!(
+            auth.session_expires_at.expect("session expiry")
+                <= now_ts() + crate::admin::auth::admin_sso_session_ttl_seconds()
+        );
+        assert_eq!(auth.audit_actor_label(), "user:oidc:ops@example.test");
+        let payload: serde_json::Value =
+            serde_json::from_slice(handle_admin_session(&session_req, &store).body())
+                .expect("session json");
+        assert_eq!(payload["sso_enabled"], true);
+
+        // Replaying the callback without the browser-bound flow cookie fails closed.
+        let mut builder = Request::builder();
+        builder.method(Method::Get).uri(callback_uri.as_str());
+        let resp =
+            handle_admin_oidc_callback_with_failure_handler(&builder.build(), &store, &idp, || {
+                false
+            });
+        assert!(resp
+            .header("location")
+            .and_then(|value| value.as_str())
+            .map(|value| value.contains("error=sso_failed"))
+            .unwrap_or(false));
+
+        for key in [
+            "SHUMA_API_KEY",
+            "SHUMA_ADMIN_OIDC_ISSUER",
+            "SHUMA_ADMIN_OIDC_CLIENT_ID",
+            "SHUMA_ADMIN_OIDC_REDIRECT_URI",
+            "SHUMA_ADMIN_OIDC_ROLE_MAP",
+        ] {
+            std::env::remove_var(key);
+        }
+    }
+
+    #[test]
+    fn totp_enrolled_key_needs_a_code_at_login_and_step_up_for_sensitive_writes() {
+        use crate::admin::totp::tests::current_totp_code;
+        use crate::admin::totp::{
+            begin_admin_totp_enrollment, confirm_admin_totp_enrollment, SHARED_KEY_PRINCIPAL,
+        };
+
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "test-admin-key");
+        let store = TestStore::default();
+        let now = now_ts();
+        let (secret, _) =
+            begin_admin_totp_enrollment(&store, SHARED_KEY_PRINCIPAL, now).expect("enrolment");
+        let recovery = confirm_admin_totp_enrollment(
+            &store,
+            SHARED_KEY_PRINCIPAL,
+            current_totp_code(secret.as_str(), now).as_str(),
+            now,
+        )
+        .expect("confirmed");
+
+        let location = |resp: &Response| {
+            resp.header("location")
+                .and_then(|value| value.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let resp = handle_admin_login(&login_request("test-admin-key"), &store);
+        assert!(location(&resp).contains("error=otp_required"));
+        let login_with_otp = |otp: &str| {
+            let mut builder = Request::builder();
+            builder
+                .method(Method::Post)
+                .uri("/shuma/admin/login")
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(format!("password=test-admin-key&otp={}", otp).into_bytes());
+            builder.build()
+        };
+        let resp = handle_admin_login(&login_with_otp("000000"), &store);
+        assert!(location(&resp).contains("error=invalid_otp"));
+        let next_code = current_totp_code(secret.as_str(), now + 30);
+        let resp = handle_admin_login(&login_with_otp(next_code.as_str()), &store);
+        assert_eq!(*resp.status(), 303u16);
+        let session_id = resp
+            .header("set-cookie")
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.split(';').next())
+            .and_then(|value| value.split_once('='))
+            .map(|(_, value)| value.to_string())
+            .expect("session cookie");
+        assert!(crate::admin::auth::admin_session_step_up_active(
+            &store,
+            session_id.as_str()
+        ));
+        assert!(request_requires_admin_step_up("/shuma/admin/config", &Method::Post));
+        assert!(!request_requires_admin_step_up("/shuma/admin/config", &Method::Get));
+        assert!(!request_requires_admin_step_up("/shuma/admin/ban", &Method::Post));
+
+        // A fresh session has to step up before it can change config.
+        let (fresh_session, csrf, _) =
+            crate::admin::auth::create_admin_session(&store).expect("session");
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/admin/mfa/step-up")
+            .header("cookie", format!("shuma_admin_session={}", fresh_session))
+            .header("x-shuma-csrf", csrf)
+            .body(serde_json::to_vec(&serde_json::json!({ "code": "000000" })).unwrap());
+        let step_up_req = builder.build();
+        let auth = crate::admin::auth::authenticate_admin(&step_up_req, &store);
+        assert!(!admin_step_up_is_satisfied(&step_up_req, &store, &auth));
+        let resp = handle_admin_mfa_step_up(&step_up_req, &store, &auth, || false);
+        assert_eq!(*resp.status(), 403u16);
+        assert_eq!(
+            resp.header("x-shuma-step-up").and_then(|value| value.as_str()),
+            Some("required")
+        );
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/admin/mfa/step-up")
+            .header("cookie", format!("shuma_admin_session={}", fresh_session))
+            .body(serde_json::to_vec(&serde_json::json!({ "code": recovery[0] })).unwrap());
+        let resp = handle_admin_mfa_step_up(&builder.build(), &store, &auth, || false);
+        assert_eq!(*resp.status(), 200u16);
+        assert!(admin_step_up_is_satisfied(&step_up_req, &store, &auth));
+
+        // Bearer callers carry the code on the request itself.
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/admin/config")
+            .header("authorization", "Bearer test-admin-key");
+        let bearer_req = builder.build();
+        let bearer_auth = crate::admin::auth::authenticate_admin(&bearer_req, &store);
+        assert!(!admin_step_up_is_satisfied(&bearer_req, &store, &bearer_auth));
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Post)
+            .uri("/shuma/admin/config")
+            .header("authorization", "Bearer test-admin-key")
+            .header("x-shuma-otp", recovery[1].as_str());
+        let bearer_req = builder.build();
+        assert!(admin_step_up_is_satisfied(&bearer_req, &store, &bearer_auth));
+
+        std::env::remove_var("SHUMA_API_KEY");
+    }
+
     #[test]
     fn login_success_rejects_external_next_path_and_redirects_to_dashboard_index() {
         let _lock = crate::test_support::lock_env();
@@ -15163,6 +16230,10 @@ mod admin_auth_tests {
             "/shuma/admin/operator-objectives",
             &Method::Post
         ));
+        assert!(request_requires_admin_write(
+            "/shuma/admin/alert-rules",
+            &Method::Post
+        ));
         assert!(request_requires_admin_write(
             "/shuma/admin/replay-promotion",
             &Method::Post
@@ -15175,6 +16246,26 @@ mod admin_auth_tests {
             "/shuma/admin/config/validate",
             &Method::Post
         ));
+        assert!(request_requires_admin_write(
+            "/shuma/admin/config/rollback",
+            &Method::Post
+        ));
+        assert!(request_requires_admin_write(
+            "/shuma/admin/gitops/apply",
+            &Method::Post
+        ));
+        assert!(request_requires_admin_write(
+            "/shuma/admin/verified-identity/directories",
+            &Method::Delete
+        ));
+        assert!(request_requires_admin_step_up(
+            "/shuma/admin/verified-identity/directories",
+            &Method::Delete
+        ));
+        assert!(!request_requires_admin_step_up(
+            "/shuma/admin/verified-identity/directories",
+            &Method::Get
+        ));
         assert!(request_requires_admin_write(
             "/shuma/admin/adversary-sim/control",
             &Method::Post
@@ -15193,6 +16284,14 @@ mod admin_auth_tests {
             "/shuma/admin/tarpit/preview",
             &Method::Post
         ));
+        assert!(!request_requires_admin_write(
+            "/shuma/admin/policy-simulation",
+            &Method::Post
+        ));
+        assert!(!request_requires_admin_write(
+            "/shuma/admin/gitops/plan",
+            &Method::Post
+        ));
         assert!(!request_requires_admin_write(
             "/shuma/admin/events",
             &Method::Post
@@ -15209,6 +16308,10 @@ mod admin_auth_tests {
             "/shuma/admin/operator-objectives",
             &Method::Get
         ));
+        assert!(!request_requires_admin_write(
+            "/shuma/admin/alert-rules",
+            &Method::Get
+        ));
         assert!(!request_requires_admin_write(
             "/shuma/admin/replay-promotion",
             &Method::Get
@@ -15226,6 +16329,10 @@ mod admin_auth_tests {
             "/shuma/admin/config/validate",
             &Method::Get
         ));
+        assert!(!request_requires_admin_write(
+            "/shuma/admin/config/history",
+            &Method::Get
+        ));
         assert!(!request_requires_admin_write(
             "/shuma/admin/adversary-sim/control",
             &Method::Get
@@ -15242,6 +16349,75 @@ mod admin_auth_tests {
             "/shuma/admin/analytics",
             &Method::Get
         ));
+        assert!(request_requires_admin_write("/shuma/admin/accounts", &Method::Post));
+        assert!(request_requires_admin_write("/shuma/admin/accounts", &Method::Delete));
+        assert!(request_requires_admin_write("/shuma/admin/tokens", &Method::Post));
+    }
+
+    #[test]
+    fn admin_token_scopes_cover_routes_and_exclude_credential_management() {
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/unban", &Method::Post),
+            Some("bans:write")
+        );
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/ban", &Method::Get),
+            Some("bans:read")
+        );
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/monitoring", &Method::Get),
+            Some("monitoring:read")
+        );
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/config", &Method::Get),
+            Some("config:read")
+        );
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/config", &Method::Post),
+            Some("config:write")
+        );
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/robots/preview", &Method::Post),
+            Some("config:read")
+        );
+        assert_eq!(
+            required_admin_token_scope("/shuma/admin/adversary-sim/control", &Method::Post),
+            Some("sim:write")
+        );
+        assert_eq!(required_admin_token_scope("/shuma/admin/accounts", &Method::Get), None);
+        assert_eq!(required_admin_token_scope("/shuma/admin/tokens", &Method::Post), None);
+    }
+
+    #[test]
+    fn admin_route_permissions_map_to_role_ladder() {
+        use crate::admin::accounts::AdminRole;
+
+        let allowed = |role: AdminRole, path: &str, method: Method| {
+            role.grants(required_admin_permission(path, &method))
+        };
+        assert!(allowed(AdminRole::Viewer, "/shuma/admin/monitoring", Method::Get));
+        assert!(!allowed(AdminRole::Viewer, "/shuma/admin/unban", Method::Post));
+        assert!(allowed(AdminRole::Analyst, "/shuma/admin/unban", Method::Post));
+        assert!(!allowed(AdminRole::Analyst, "/shuma/admin/config", Method::Post));
+        assert!(allowed(AdminRole::PolicyEditor, "/shuma/admin/config", Method::Post));
+        assert!(allowed(AdminRole::PolicyEditor, "/shuma/admin/alert-rules", Method::Post));
+        assert!(!allowed(
+            AdminRole::PolicyEditor,
+            "/shuma/admin/adversary-sim/control",
+            Method::Post
+        ));
+        assert!(allowed(
+            AdminRole::SimOperator,
+            "/shuma/admin/adversary-sim/control",
+            Method::Post
+        ));
+        assert!(!allowed(AdminRole::SimOperator, "/shuma/admin/ban", Method::Post));
+        assert!(!allowed(AdminRole::PolicyEditor, "/shuma/admin/accounts", Method::Get));
+        assert!(allowed(AdminRole::Owner, "/shuma/admin/accounts", Method::Post));
+        assert_eq!(
+            required_admin_permission("/shuma/admin/not-a-route", &Method::Get),
+            AdminPermission::ManageAccounts
+        );
     }
 }
 
@@ -15370,22 +16546,37 @@ fn sanitize_path(path: &str) -> bool {
             | "/shuma/admin/login"
             | "/shuma/admin/session"
             | "/shuma/admin/logout"
+            | "/shuma/admin/oidc/login"
+            | "/shuma/admin/oidc/callback"
             | "/shuma/admin/ban"
             | "/shuma/admin/unban"
             | "/shuma/admin/analytics"
             | "/shuma/admin/events"
             | "/shuma/admin/operator-snapshot"
             | "/shuma/admin/operator-objectives"
+            | "/shuma/admin/alert-rules"
+            | "/shuma/admin/accounts"
+            | "/shuma/admin/tokens"
+            | "/shuma/admin/mfa"
+            | "/shuma/admin/mfa/step-up"
             | "/shuma/admin/oversight/reconcile"
             | "/shuma/admin/oversight/history"
             | "/shuma/admin/oversight/agent/status"
             | "/shuma/admin/replay-promotion"
+            | "/shuma/admin/policy-simulation"
+            | "/shuma/admin/botness-calibration"
             | "/shuma/admin/benchmark-suite"
             | "/shuma/admin/benchmark-results"
             | "/shuma/admin/config"
             | "/shuma/admin/config/bootstrap"
             | "/shuma/admin/config/validate"
             | "/shuma/admin/config/export"
+            | "/shuma/admin/config/history"
+            | "/shuma/admin/config/diff"
+            | "/shuma/admin/config/rollback"
+            | "/shuma/admin/gitops"
+            | "/shuma/admin/gitops/plan"
+            | "/shuma/admin/gitops/apply"
             | "/shuma/admin/adversary-sim/control"
             | "/shuma/admin/adversary-sim/status"
             | "/shuma/admin/adversary-sim/history/cleanup"
@@ -15404,11 +16595,17 @@ fn sanitize_path(path: &str) -> bool {
             | "/shuma/admin/ip-bans/delta"
             | "/shuma/admin/ip-bans/stream"
             | "/shuma/admin/ip-range/suggestions"
+            | "/shuma/admin/verified-identity/usage"
+            | "/shuma/admin/verified-identity/licences"
+            | "/shuma/admin/verified-identity/directories"
     )
 }
 
 fn session_cookie_value(session_id: &str) -> String {
-    let max_age = crate::admin::auth::admin_session_ttl_seconds();
+    session_cookie_value_with_max_age(session_id, crate::admin::auth::admin_session_ttl_seconds())
+}
+
+fn session_cookie_value_with_max_age(session_id: &str, max_age: u64) -> String {
     let secure = if crate::config::https_enforced() {
         "; Secure"
     } else {
@@ -15423,6 +16620,22 @@ fn session_cookie_value(session_id: &str) -> String {
     )
 }
 
+fn oidc_flow_cookie_value(value: &str, max_age: u64) -> String {
+    let secure = if crate::config::https_enforced() {
+        "; Secure"
+    } else {
+        ""
+    };
+    // Lax, not Strict: the cookie must come back on the issuer's cross-site redirect.
+    format!(
+        "{}={}; Path=/shuma/admin/oidc; HttpOnly; SameSite=Lax; Max-Age={}{}",
+        crate::admin::oidc::ADMIN_OIDC_FLOW_COOKIE_NAME,
+        value,
+        max_age,
+        secure
+    )
+}
+
 fn clear_session_cookie_value() -> String {
     let secure = if crate::config::https_enforced() {
         "; Secure"
@@ -15541,7 +16754,7 @@ fn request_has_form_urlencoded_content_type(req: &Request) -> bool {
         .unwrap_or(false)
 }
 
-fn too_many_admin_auth_attempts_response() -> Response {
+pub(super) fn too_many_admin_auth_attempts_response() -> Response {
     Response::builder()
         .status(429)
         .header("Retry-After", "60")
@@ -15567,6 +16780,8 @@ const INTERNAL_ADVERSARY_SIM_BEAT_PATH: &str =
     route_namespace::SHUMA_INTERNAL_ADVERSARY_SIM_BEAT_PATH;
 const INTERNAL_ADVERSARY_SIM_WORKER_RESULT_PATH: &str =
     route_namespace::SHUMA_INTERNAL_ADVERSARY_SIM_WORKER_RESULT_PATH;
+const INTERNAL_ALERT_RULES_EVALUATE_PATH: &str =
+    route_namespace::SHUMA_INTERNAL_ALERT_RULES_EVALUATE_PATH;
 const MONITORING_STALE_LAG_THRESHOLD_MS: u64 = 10_000;
 const MONITORING_LOAD_ENVELOPE_EVENTS_PER_SEC: u64 = 1_000;
 const MONITORING_LOAD_ENVELOPE_OPERATOR_CLIENTS: u64 = 5;
@@ -15727,28 +16942,174 @@ pub(super) fn adversary_sim_control_submission_is_limited<S: crate::challenge::K
     )
 }
 
-fn request_requires_admin_write(path: &str, method: &Method) -> bool {
-    if !matches!(
+/// Permission each admin route demands. Read-only methods need `Read` unless the route is
+/// sensitive as a whole; unknown routes fail closed to owner-only.
+fn required_admin_permission(path: &str, method: &Method) -> AdminPermission {
+    let is_write = matches!(
         method,
         Method::Post | Method::Put | Method::Patch | Method::Delete
-    ) {
-        return false;
+    );
+    let (read, write) = match path {
+        "/shuma/admin/ban" | "/shuma/admin/unban" => {
+            (AdminPermission::Read, AdminPermission::BanWrite)
+        }
+        "/shuma/admin/config"
+        | "/shuma/admin/operator-objectives"
+        | "/shuma/admin/alert-rules"
+        | "/shuma/admin/oversight/reconcile"
+        | "/shuma/admin/replay-promotion"
+        | "/shuma/admin/config/bootstrap"
+        | "/shuma/admin/config/validate"
+        | "/shuma/admin/config/history"
+        | "/shuma/admin/config/diff"
+        | "/shuma/admin/config/rollback"
+        | "/shuma/admin/gitops"
+        | "/shuma/admin/gitops/apply"
+        | "/shuma/admin/maze/seeds"
+        | "/shuma/admin/maze/seeds/refresh"
+        | "/shuma/admin/verified-identity/directories" => {
+            (AdminPermission::Read, AdminPermission::PolicyWrite)
+        }
+        "/shuma/admin/adversary-sim/control" | "/shuma/admin/adversary-sim/history/cleanup" => {
+            (AdminPermission::Read, AdminPermission::SimControl)
+        }
+        "/shuma/admin/accounts" | "/shuma/admin/tokens" => (
+            AdminPermission::ManageAccounts,
+            AdminPermission::ManageAccounts,
+        ),
+        "/shuma/admin"
+        | "/shuma/admin/analytics"
+        | "/shuma/admin/events"
+        | "/shuma/admin/operator-snapshot"
+        | "/shuma/admin/oversight/history"
+        | "/shuma/admin/oversight/agent/status"
+        | "/shuma/admin/benchmark-suite"
+        | "/shuma/admin/benchmark-results"
+        | "/shuma/admin/config/export"
+        | "/shuma/admin/adversary-sim/status"
+        | "/shuma/admin/maze"
+        | "/shuma/admin/maze/preview"
+        | "/shuma/admin/tarpit/preview"
+        | "/shuma/admin/robots"
+        | "/shuma/admin/robots/preview"
+        | "/shuma/admin/policy-simulation"
+        | "/shuma/admin/botness-calibration"
+        | "/shuma/admin/gitops/plan"
+        | "/shuma/admin/cdp"
+        | "/shuma/admin/cdp/events"
+        | "/shuma/admin/monitoring"
+        | "/shuma/admin/monitoring/delta"
+        | "/shuma/admin/monitoring/stream"
+        | "/shuma/admin/ip-bans/delta"
+        | "/shuma/admin/ip-bans/stream"
+        | "/shuma/admin/ip-range/suggestions"
+        | "/shuma/admin/verified-identity/usage"
+        | "/shuma/admin/verified-identity/licences"
+        | "/shuma/admin/mfa"
+        | "/shuma/admin/mfa/step-up" => (AdminPermission::Read, AdminPermission::Read),
+        _ => (
+            AdminPermission::ManageAccounts,
+            AdminPermission::ManageAccounts,
+        ),
+    };
+    if is_write {
+        write
+    } else {
+        read
     }
-    matches!(
-        path,
+}
+
+/// Scope a scoped API token needs for a route, or `None` when tokens may never use it.
+fn required_admin_token_scope(path: &str, method: &Method) -> Option<&'static str> {
+    let is_write = matches!(
+        method,
+        Method::Post | Method::Put | Method::Patch | Method::Delete
+    );
+    let resource = match path {
+        "/shuma/admin" => return Some("monitoring:read"),
         "/shuma/admin/ban"
-            | "/shuma/admin/unban"
-            | "/shuma/admin/config"
-            | "/shuma/admin/operator-objectives"
-            | "/shuma/admin/oversight/reconcile"
-            | "/shuma/admin/replay-promotion"
-            | "/shuma/admin/config/bootstrap"
-            | "/shuma/admin/config/validate"
-            | "/shuma/admin/adversary-sim/control"
-            | "/shuma/admin/adversary-sim/history/cleanup"
-            | "/shuma/admin/maze/seeds"
-            | "/shuma/admin/maze/seeds/refresh"
-    )
+        | "/shuma/admin/unban"
+        | "/shuma/admin/ip-bans/delta"
+        | "/shuma/admin/ip-bans/stream"
+        | "/shuma/admin/ip-range/suggestions" => "bans",
+        "/shuma/admin/analytics"
+        | "/shuma/admin/events"
+        | "/shuma/admin/operator-snapshot"
+        | "/shuma/admin/oversight/history"
+        | "/shuma/admin/oversight/agent/status"
+        | "/shuma/admin/benchmark-suite"
+        | "/shuma/admin/benchmark-results"
+        | "/shuma/admin/cdp/events"
+        | "/shuma/admin/monitoring"
+        | "/shuma/admin/monitoring/delta"
+        | "/shuma/admin/monitoring/stream"
+        | "/shuma/admin/verified-identity/usage"
+        | "/shuma/admin/verified-identity/licences" => "monitoring",
+        "/shuma/admin/config"
+        | "/shuma/admin/config/bootstrap"
+        | "/shuma/admin/config/validate"
+        | "/shuma/admin/config/export"
+        | "/shuma/admin/config/history"
+        | "/shuma/admin/config/diff"
+        | "/shuma/admin/config/rollback"
+        | "/shuma/admin/gitops"
+        | "/shuma/admin/gitops/plan"
+        | "/shuma/admin/gitops/apply"
+        | "/shuma/admin/operator-objectives"
+        | "/shuma/admin/alert-rules"
+        | "/shuma/admin/oversight/reconcile"
+        | "/shuma/admin/replay-promotion"
+        | "/shuma/admin/maze"
+        | "/shuma/admin/maze/preview"
+        | "/shuma/admin/tarpit/preview"
+        | "/shuma/admin/maze/seeds"
+        | "/shuma/admin/maze/seeds/refresh"
+        | "/shuma/admin/robots"
+        | "/shuma/admin/robots/preview"
+        | "/shuma/admin/policy-simulation"
+        | "/shuma/admin/botness-calibration"
+        | "/shuma/admin/verified-identity/directories"
+        | "/shuma/admin/cdp" => "config",
+        "/shuma/admin/adversary-sim/control"
+        | "/shuma/admin/adversary-sim/status"
+        | "/shuma/admin/adversary-sim/history/cleanup" => "sim",
+        _ => return None,
+    };
+    let needs_write = is_write && required_admin_permission(path, method).is_write();
+    Some(match (resource, needs_write) {
+        ("bans", true) => "bans:write",
+        ("bans", false) => "bans:read",
+        ("config", true) => "config:write",
+        ("config", false) => "config:read",
+        ("sim", true) => "sim:write",
+        ("sim", false) => "sim:read",
+        (_, true) => return None,
+        (_, false) => "monitoring:read",
+    })
+}
+
+/// Writes that need a recent second-factor check from callers with an enrolled TOTP factor.
+/// Provider switching rides on config writes; there is no bulk-unban route to cover.
+fn request_requires_admin_step_up(path: &str, method: &Method) -> bool {
+    request_requires_admin_write(path, method)
+        && matches!(
+            path,
+            "/shuma/admin/config"
+                | "/shuma/admin/config/bootstrap"
+                | "/shuma/admin/config/rollback"
+                | "/shuma/admin/gitops/apply"
+                | "/shuma/admin/accounts"
+                | "/shuma/admin/tokens"
+                | "/shuma/admin/adversary-sim/history/cleanup"
+                | "/shuma/admin/verified-identity/directories"
+        )
+}
+
+fn request_requires_admin_write(path: &str, method: &Method) -> bool {
+    matches!(
+        method,
+        Method::Post | Method::Put | Method::Patch | Method::Delete
+    ) && required_admin_permission(path, method).is_write()
 }
 
 fn parse_unban_identity(raw: &str) -> Option<String> {
@@ -15759,26 +17120,34 @@ fn parse_unban_identity(raw: &str) -> Option<String> {
     crate::request_validation::parse_ip_addr(trimmed)
 }
 
-fn log_admin_write_denied<S: crate::challenge::KeyValueStore>(
+fn log_admin_permission_denied<S: crate::challenge::KeyValueStore>(
     store: &S,
     req: &Request,
     path: &str,
     auth: &crate::admin::auth::AdminAuthResult,
+    permission: AdminPermission,
 ) {
+    let reason = if request_requires_admin_write(path, req.method()) {
+        "admin_write_denied"
+    } else {
+        "admin_permission_denied"
+    };
     log_event(
         store,
         &EventLogEntry {
             ts: now_ts(),
             event: EventType::AdminAction,
             ip: None,
-            reason: Some("admin_write_denied".to_string()),
+            reason: Some(reason.to_string()),
             outcome: Some(format!(
-                "path={} method={} access={}",
+                "path={} method={} access={} role={} permission={}",
                 path,
                 req.method(),
-                auth.access_label()
+                auth.access_label(),
+                auth.role_label(),
+                permission.as_str()
             )),
-            admin: Some(auth.audit_actor_label().to_string()),
+            admin: Some(auth.audit_actor_label()),
         },
     );
 }
@@ -15837,8 +17206,16 @@ where
     if api_key.is_empty() {
         return build_login_redirect_response(next_path.as_str(), Some("invalid_request"), None);
     }
-
-    if !crate::admin::auth::verify_admin_api_key_candidate(api_key) {
+    // Named-account credentials win; otherwise the password must be the shared admin key, which
+    // keeps the dashboard's fixed `admin` password-manager identity working.
+    let account = form
+        .get("username")
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .and_then(|username| {
+            crate::admin::accounts::verify_admin_account_login(store, username, api_key, now_ts())
+        });
+    if account.is_none() && !crate::admin::auth::verify_admin_api_key_candidate(api_key) {
         if register_failure() {
             return build_login_redirect_response(
                 next_path.as_str(),
@@ -15848,9 +17225,58 @@ where
         }
         return build_login_redirect_response(next_path.as_str(), Some("invalid_key"), None);
     }
+    // A principal with an enrolled TOTP factor must also present a current or recovery code.
+    let mfa_principal = match account.as_ref() {
+        Some(account) => crate::admin::totp::account_principal(account.username.as_str()),
+        None => crate::admin::totp::SHARED_KEY_PRINCIPAL.to_string(),
+    };
+    let second_factor_required =
+        crate::admin::totp::is_admin_totp_enrolled(store, mfa_principal.as_str());
+    if second_factor_required {
+        let Some(code) = form
+            .get("otp")
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+        else {
+            return build_login_redirect_response(next_path.as_str(), Some("otp_required"), None);
+        };
+        if !crate::admin::totp::verify_admin_second_factor(
+            store,
+            mfa_principal.as_str(),
+            code,
+            now_ts(),
+        ) {
+            if register_failure() {
+                return build_login_redirect_response(
+                    next_path.as_str(),
+                    Some("rate_limited"),
+                    Some("60"),
+                );
+            }
+            return build_login_redirect_response(next_path.as_str(), Some("invalid_otp"), None);
+        }
+    }
 
-    let (session_id, csrf_token, expires_at) = match crate::admin::auth::create_admin_session(store)
-    {
+    let session = match account.as_ref() {
+        Some(account) => {
+            crate::admin::auth::create_admin_account_session(store, account.username.as_str())
+        }
+        None => crate::admin::auth::create_admin_session(store),
+    };
+    if let Some(account) = account.as_ref() {
+        log_event(
+            store,
+            &EventLogEntry {
+                ts: now_ts(),
+                event: EventType::AdminAction,
+                ip: None,
+                reason: Some("admin_account_login".to_string()),
+                outcome: Some(format!("role={}", account.role.as_str())),
+                admin: Some(format!("user:{}", account.username)),
+            },
+        );
+    }
+    let (session_id, csrf_token, expires_at) = match session {
         Ok(v) => v,
         Err(_) => {
             return build_login_redirect_response(next_path.as_str(), Some("login_failed"), None)
@@ -15858,6 +17284,9 @@ where
     };
     let _ = csrf_token;
     let _ = expires_at;
+    if second_factor_required {
+        let _ = crate::admin::auth::mark_admin_session_step_up(store, session_id.as_str());
+    }
     Response::builder()
         .status(303)
         .header("Location", next_path.as_str())
@@ -15878,6 +17307,143 @@ fn handle_admin_login<S: crate::challenge::KeyValueStore>(req: &Request, store:
     })
 }
 
+fn handle_admin_oidc_login<S: crate::challenge::KeyValueStore>(
+    req: &Request,
+    store: &S,
+    client: &dyn crate::admin::oidc::OidcHttpClient,
+) -> Response {
+    if req.method() != &spin_sdk::http::Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let Some(config) = crate::admin::oidc::admin_oidc_config_from_env() else {
+        return Response::new(404, "Not Found: OIDC sign-in not configured");
+    };
+    let next_path = normalize_dashboard_login_next(
+        crate::request_validation::query_param(req.query(), "next").as_deref(),
+    );
+    match crate::admin::oidc::begin_admin_oidc_login(
+        store,
+        &config,
+        client,
+        next_path.as_str(),
+        now_ts(),
+    ) {
+        Ok(start) => Response::builder()
+            .status(302)
+            .header("Location", start.authorization_url.as_str())
+            .header("Cache-Control", "no-store")
+            .header(
+                "Set-Cookie",
+                oidc_flow_cookie_value(
+                    start.flow_cookie.as_str(),
+                    crate::admin::oidc::ADMIN_OIDC_FLOW_TTL_SECONDS,
+                ),
+            )
+            .body(Vec::new())
+            .build(),
+        Err(err) => {
+            eprintln!("[admin-oidc] failed to start sign-in: {}", err.as_str());
+            build_login_redirect_response(next_path.as_str(), Some("sso_unavailable"), None)
+        }
+    }
+}
+
+fn handle_admin_oidc_callback_with_failure_handler<S, F>(
+    req: &Request,
+    store: &S,
+    client: &dyn crate::admin::oidc::OidcHttpClient,
+    mut register_failure: F,
+) -> Response
+where
+    S: crate::challenge::KeyValueStore,
+    F: FnMut() -> bool,
+{
+    if req.method() != &spin_sdk::http::Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let Some(config) = crate::admin::oidc::admin_oidc_config_from_env() else {
+        return Response::new(404, "Not Found: OIDC sign-in not configured");
+    };
+    let query = req.query();
+    let param = |key: &str| crate::request_validation::query_param(query, key);
+    let flow_cookie =
+        crate::admin::auth::parse_cookie(req, crate::admin::oidc::ADMIN_OIDC_FLOW_COOKIE_NAME);
+    let result = match param("error") {
+        Some(idp_error) => Err(format!(
+            "idp_error:{}",
+            idp_error.chars().take(64).collect::<String>()
+        )),
+        None => crate::admin::oidc::complete_admin_oidc_login(
+            store,
+            &config,
+            client,
+            flow_cookie.as_deref(),
+            param("state").unwrap_or_default().as_str(),
+            param("code").unwrap_or_default().as_str(),
+            now_ts(),
+        )
+        .map_err(|err| err.as_str().to_string()),
+    };
+    let fallback_next = DASHBOARD_INDEX_PATH.to_string();
+    let failure = match result {
+        Ok(identity) => {
+            match crate::admin::auth::create_admin_sso_session(
+                store,
+                identity.username.as_str(),
+                identity.role,
+            ) {
+                Ok((session_id, _csrf_token, _expires_at)) => {
+                    log_event(
+                        store,
+                        &EventLogEntry {
+                            ts: now_ts(),
+                            event: EventType::AdminAction,
+                            ip: None,
+                            reason: Some("admin_oidc_login".to_string()),
+                            outcome: Some(format!("role={}", identity.role.as_str())),
+                            admin: Some(format!("user:{}", identity.username)),
+                        },
+                    );
+                    return Response::builder()
+                        .status(303)
+                        .header("Location", identity.next.as_str())
+                        .header("Cache-Control", "no-store")
+                        .header(
+                            "Set-Cookie",
+                            session_cookie_value_with_max_age(
+                                &session_id,
+                                crate::admin::auth::admin_sso_session_ttl_seconds(),
+                            ),
+                        )
+                        .body(Vec::new())
+                        .build();
+                }
+                Err(()) => "session_create_failed".to_string(),
+            }
+        }
+        Err(reason) => reason,
+    };
+
+    log_event(
+        store,
+        &EventLogEntry {
+            ts: now_ts(),
+            event: EventType::AdminAction,
+            ip: Some(crate::extract_client_ip(req)),
+            reason: Some("admin_oidc_login_failed".to_string()),
+            outcome: Some(failure),
+            admin: Some("-".to_string()),
+        },
+    );
+    let mut response = if register_failure() {
+        build_login_redirect_response(fallback_next.as_str(), Some("rate_limited"), Some("60"))
+    } else {
+        build_login_redirect_response(fallback_next.as_str(), Some("sso_failed"), None)
+    };
+    response.set_header("Set-Cookie", oidc_flow_cookie_value("", 0).as_str());
+    response
+}
+
 fn handle_admin_session<S: crate::challenge::KeyValueStore>(req: &Request, store: &S) -> Response {
     if req.method() != &spin_sdk::http::Method::Get {
         return Response::new(405, "Method Not Allowed");
@@ -15889,7 +17455,7 @@ fn handle_admin_session<S: crate::challenge::KeyValueStore>(req: &Request, store
             true,
             "session",
             auth.csrf_token.clone(),
-            crate::admin::auth::AdminAccessLevel::ReadWrite.as_str(),
+            auth.access_label(),
             auth.session_expires_at,
         ),
         Some(crate::admin::auth::AdminAuthMethod::BearerToken) => {
@@ -15902,7 +17468,10 @@ fn handle_admin_session<S: crate::challenge::KeyValueStore>(req: &Request, store
         "method": method,
         "csrf_token": csrf_token,
         "access": access,
+        "role": auth.role.map(|role| role.as_str()),
+        "username": auth.username,
         "expires_at": expires_at,
+        "sso_enabled": crate::admin::oidc::admin_oidc_config_from_env().is_some(),
         "runtime_environment": crate::config::runtime_environment().as_str()
     }))
     .unwrap();
@@ -17487,6 +19056,36 @@ fn botness_signal_definitions(cfg: &crate::config::Config) -> serde_json::Value
                 "key": "fp_akamai_edge_additive",
                 "label": "Fingerprint Akamai edge signal (additive)",
                 "weight": 2
+            },
+            {
+                "key": "cdp_report_binding_anomaly",
+                "label": "CDP report binding anomaly (unbound, replayed or too fast)",
+                "weight": 3
+            },
+            {
+                "key": "cdp_report_missing",
+                "label": "Expected CDP report missing",
+                "weight": 2
+            },
+            {
+                "key": "session_asset_ratio",
+                "label": "Session fetches documents without assets",
+                "weight": cfg.botness_weights.session_asset_ratio
+            },
+            {
+                "key": "session_traversal",
+                "label": "Session walks the site breadth-first",
+                "weight": cfg.botness_weights.session_traversal
+            },
+            {
+                "key": "session_timing",
+                "label": "Session requests arrive on a fixed clock",
+                "weight": cfg.botness_weights.session_timing
+            },
+            {
+                "key": "session_referer",
+                "label": "Session referers inconsistent with its history",
+                "weight": cfg.botness_weights.session_referer
             }
         ],
         "terminal_signals": [
@@ -17659,6 +19258,18 @@ pub(super) fn config_export_env_entries(cfg: &crate::config::Config) -> Vec<(Str
             "SHUMA_SHADOW_MODE".to_string(),
             bool_env(cfg.shadow_mode).to_string(),
         ),
+        (
+            "SHUMA_SHADOW_POLICY_SOURCES".to_string(),
+            json_env(&cfg.shadow_policy_sources),
+        ),
+        (
+            "SHUMA_REQUEST_FACTS_JOURNAL_SAMPLE_PERCENT".to_string(),
+            cfg.request_facts_journal_sample_percent.to_string(),
+        ),
+        (
+            "SHUMA_CONFIG_PROFILES".to_string(),
+            json_env(&cfg.config_profiles),
+        ),
         (
             "SHUMA_ADVERSARY_SIM_ENABLED".to_string(),
             bool_env(cfg.adversary_sim_enabled).to_string(),
@@ -17754,6 +19365,42 @@ pub(super) fn config_export_env_entries(cfg: &crate::config::Config) -> Vec<(Str
             "SHUMA_VERIFIED_IDENTITY_SERVICE_PROFILES".to_string(),
             json_env(&cfg.verified_identity.service_profiles),
         ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_RESTRICT_REQUESTS_PER_MINUTE".to_string(),
+            cfg.verified_identity.restrict_requests_per_minute.to_string(),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_RESTRICT_DENIED_PATH_PREFIXES".to_string(),
+            json_env(&cfg.verified_identity.restrict_denied_path_prefixes),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_MTLS_ENABLED".to_string(),
+            cfg.verified_identity.mtls_enabled.to_string(),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_MTLS_CA_BUNDLE".to_string(),
+            json_env(&cfg.verified_identity.mtls_ca_bundle),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_USAGE_QUOTAS".to_string(),
+            json_env(&cfg.verified_identity.usage_quotas),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_LICENCE_OFFERS".to_string(),
+            json_env(&cfg.verified_identity.licence_offers),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_LICENCE_KEYS".to_string(),
+            json_env(&cfg.verified_identity.licence_keys),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_DELEGATION_KEYS".to_string(),
+            json_env(&cfg.verified_identity.delegation_keys),
+        ),
+        (
+            "SHUMA_VERIFIED_IDENTITY_PINNED_DIRECTORIES".to_string(),
+            json_env(&cfg.verified_identity.pinned_directories),
+        ),
         (
             "SHUMA_POW_ENABLED".to_string(),
             bool_env(cfg.pow_enabled).to_string(),
@@ -17822,6 +19469,10 @@ pub(super) fn config_export_env_entries(cfg: &crate::config::Config) -> Vec<(Str
             "SHUMA_NOT_A_BOT_ATTEMPT_WINDOW_SECONDS".to_string(),
             cfg.not_a_bot_attempt_window_seconds.to_string(),
         ),
+        (
+            "SHUMA_NOT_A_BOT_ACCESSIBLE_QUESTIONS".to_string(),
+            json_env(&cfg.not_a_bot_accessible_questions),
+        ),
         (
             "SHUMA_BOTNESS_MAZE_THRESHOLD".to_string(),
             cfg.botness_maze_threshold.to_string(),
@@ -17846,6 +19497,22 @@ pub(super) fn config_export_env_entries(cfg: &crate::config::Config) -> Vec<(Str
             "SHUMA_BOTNESS_WEIGHT_MAZE_BEHAVIOR".to_string(),
             cfg.botness_weights.maze_behavior.to_string(),
         ),
+        (
+            "SHUMA_BOTNESS_WEIGHT_SESSION_ASSET_RATIO".to_string(),
+            cfg.botness_weights.session_asset_ratio.to_string(),
+        ),
+        (
+            "SHUMA_BOTNESS_WEIGHT_SESSION_TRAVERSAL".to_string(),
+            cfg.botness_weights.session_traversal.to_string(),
+        ),
+        (
+            "SHUMA_BOTNESS_WEIGHT_SESSION_TIMING".to_string(),
+            cfg.botness_weights.session_timing.to_string(),
+        ),
+        (
+            "SHUMA_BOTNESS_WEIGHT_SESSION_REFERER".to_string(),
+            cfg.botness_weights.session_referer.to_string(),
+        ),
         (
             "SHUMA_BAN_DURATION".to_string(),
             cfg.ban_duration.to_string(),
@@ -17957,6 +19624,10 @@ pub(super) fn config_export_env_entries(cfg: &crate::config::Config) -> Vec<(Str
             "SHUMA_IP_RANGE_CUSTOM_RULES".to_string(),
             json_env(&cfg.ip_range_custom_rules),
         ),
+        (
+            "SHUMA_CUSTOM_RULES".to_string(),
+            json_env(&cfg.custom_rules),
+        ),
         (
             "SHUMA_MAZE_ENABLED".to_string(),
             bool_env(cfg.maze_enabled).to_string(),
@@ -18363,7 +20034,24 @@ fn parse_ip_range_policy_action_json(
             field
         ));
     };
-    Ok(action)
+    Ok(action)
+}
+
+fn parse_policy_execution_mode_json(
+    field: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<crate::config::PolicyExecutionMode, String> {
+    let Some(raw_value) = value else {
+        return Ok(crate::config::PolicyExecutionMode::Enforced);
+    };
+    match raw_value.as_str().map(str::trim) {
+        Some("enforced") => Ok(crate::config::PolicyExecutionMode::Enforced),
+        Some("shadow") => Ok(crate::config::PolicyExecutionMode::Shadow),
+        _ => Err(format!(
+            "{} execution_mode must be one of: enforced, shadow",
+            field
+        )),
+    }
 }
 
 fn validate_ip_range_action_params(
@@ -18387,6 +20075,107 @@ fn validate_ip_range_action_params(
     Ok(())
 }
 
+fn parse_not_a_bot_accessible_questions_json(
+    field: &str,
+    value: &serde_json::Value,
+) -> Result<Vec<crate::config::NotABotAccessibleQuestion>, String> {
+    let items = value
+        .as_array()
+        .ok_or_else(|| format!("{} must be an array of objects", field))?;
+
+    let mut parsed = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let obj = item
+            .as_object()
+            .ok_or_else(|| format!("{}[{}] must be an object", field, index))?;
+        let question = obj
+            .get("question")
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| format!("{}[{}].question is required", field, index))?
+            .trim()
+            .to_string();
+        let answers = obj
+            .get("answers")
+            .and_then(|value| value.as_array())
+            .ok_or_else(|| format!("{}[{}].answers must be an array of strings", field, index))?
+            .iter()
+            .map(|answer| {
+                answer.as_str().map(|value| value.trim().to_string()).ok_or_else(|| {
+                    format!("{}[{}].answers must be an array of strings", field, index)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.push(crate::config::NotABotAccessibleQuestion { question, answers });
+    }
+    crate::config::validate_not_a_bot_accessible_questions(&parsed)?;
+    Ok(parsed)
+}
+
+fn parse_custom_rules_json(
+    field: &str,
+    value: &serde_json::Value,
+) -> Result<Vec<crate::config::CustomPolicyRule>, String> {
+    let items = value
+        .as_array()
+        .ok_or_else(|| format!("{} must be an array of objects", field))?;
+
+    let mut parsed = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let obj = item
+            .as_object()
+            .ok_or_else(|| format!("{}[{}] must be an object", field, index))?;
+        let enabled = obj
+            .get("enabled")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(true);
+        let id = obj
+            .get("id")
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string)
+            .unwrap_or_else(|| format!("rule_{}", index + 1));
+        let priority = match obj.get("priority") {
+            None => 0,
+            Some(value) => value
+                .as_i64()
+                .and_then(|value| i32::try_from(value).ok())
+                .ok_or_else(|| format!("{}[{}].priority must be an integer", field, index))?,
+        };
+        let when = obj
+            .get("when")
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| format!("{}[{}].when is required", field, index))?
+            .to_string();
+        let action = obj
+            .get("action")
+            .cloned()
+            .and_then(|value| {
+                serde_json::from_value::<crate::config::CustomRuleAction>(value).ok()
+            })
+            .ok_or_else(|| {
+                format!(
+                    "{}[{}].action must be one of allow, block, challenge, not_a_bot, js_challenge, maze",
+                    field, index
+                )
+            })?;
+        let execution_mode = parse_policy_execution_mode_json(
+            format!("{}[{}]", field, index).as_str(),
+            obj.get("execution_mode"),
+        )?;
+        parsed.push(crate::config::CustomPolicyRule {
+            id,
+            enabled,
+            priority,
+            when,
+            action,
+            execution_mode,
+        });
+    }
+    crate::runtime::custom_rules::validate_custom_rules(&parsed)?;
+    Ok(parsed)
+}
+
 fn parse_ip_range_custom_rules_json(
     field: &str,
     value: &serde_json::Value,
@@ -18462,6 +20251,11 @@ fn parse_ip_range_custom_rules_json(
             custom_message.as_deref(),
         )?;
 
+        let execution_mode = parse_policy_execution_mode_json(
+            format!("{}[{}]", field, index).as_str(),
+            obj.get("execution_mode"),
+        )?;
+
         parsed.push(crate::config::IpRangePolicyRule {
             id,
             enabled,
@@ -18469,6 +20263,7 @@ fn parse_ip_range_custom_rules_json(
             action,
             redirect_url,
             custom_message,
+            execution_mode,
         });
     }
     Ok(parsed)
@@ -18833,6 +20628,10 @@ struct AdminBotnessWeightsPatch {
     rate_medium: Option<u64>,
     rate_high: Option<u64>,
     maze_behavior: Option<u64>,
+    session_asset_ratio: Option<u64>,
+    session_traversal: Option<u64>,
+    session_timing: Option<u64>,
+    session_referer: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -18866,12 +20665,24 @@ struct AdminVerifiedIdentityPatch {
     named_policies: Option<Vec<crate::bot_identity::policy::IdentityPolicyEntry>>,
     category_defaults: Option<Vec<crate::bot_identity::policy::IdentityCategoryDefaultAction>>,
     service_profiles: Option<Vec<crate::bot_identity::policy::IdentityServiceProfileBinding>>,
+    restrict_requests_per_minute: Option<u32>,
+    restrict_denied_path_prefixes: Option<Vec<String>>,
+    mtls_enabled: Option<bool>,
+    mtls_ca_bundle: Option<Vec<String>>,
+    usage_quotas: Option<Vec<crate::bot_identity::policy::IdentityUsageQuota>>,
+    licence_offers: Option<Vec<crate::bot_identity::policy::IdentityLicenceOffer>>,
+    licence_keys: Option<Vec<crate::bot_identity::policy::IdentityLicenceKey>>,
+    delegation_keys: Option<Vec<crate::bot_identity::policy::IdentityDelegationKey>>,
+    pinned_directories: Option<Vec<crate::bot_identity::policy::IdentityPinnedDirectory>>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 #[serde(default, deny_unknown_fields)]
 struct AdminConfigPatch {
     shadow_mode: Option<bool>,
+    shadow_policy_sources: Option<serde_json::Value>,
+    request_facts_journal_sample_percent: Option<u64>,
+    config_profiles: Option<serde_json::Value>,
     adversary_sim_duration_seconds: Option<u64>,
     ban_duration: Option<u64>,
     rate_limit: Option<u64>,
@@ -18894,6 +20705,7 @@ struct AdminConfigPatch {
     ip_range_policy_mode: Option<String>,
     ip_range_emergency_allowlist: Option<serde_json::Value>,
     ip_range_custom_rules: Option<serde_json::Value>,
+    custom_rules: Option<serde_json::Value>,
     ban_durations: Option<AdminBanDurationsPatch>,
     maze_enabled: Option<bool>,
     tarpit_enabled: Option<bool>,
@@ -18966,6 +20778,7 @@ struct AdminConfigPatch {
     fingerprint_family_cap_temporal: Option<u64>,
     fingerprint_family_cap_persistence: Option<u64>,
     fingerprint_family_cap_behavior: Option<u64>,
+    fingerprint_transport_profiles: Option<serde_json::Value>,
     pow_enabled: Option<bool>,
     pow_difficulty: Option<u64>,
     pow_ttl_seconds: Option<u64>,
@@ -18982,6 +20795,7 @@ struct AdminConfigPatch {
     not_a_bot_marker_ttl_seconds: Option<u64>,
     not_a_bot_attempt_limit_per_window: Option<u64>,
     not_a_bot_attempt_window_seconds: Option<u64>,
+    not_a_bot_accessible_questions: Option<serde_json::Value>,
     provider_backends: Option<AdminProviderBackendsPatch>,
     edge_integration_mode: Option<String>,
     verified_identity: Option<AdminVerifiedIdentityPatch>,
@@ -19144,11 +20958,23 @@ pub(super) fn persist_site_config(
     site_id: &str,
     cfg: &crate::config::Config,
     recent_change_rows: &[OperatorSnapshotRecentChangeLedgerRow],
+    author: &str,
+    reason: &str,
 ) -> Result<(), ()> {
     let recorded_at_ts = now_ts();
     let key = format!("config:{}", site_id);
     let encoded = crate::config::serialize_persisted_kv_config(cfg).map_err(|_| ())?;
+    let previous = store.get(&key).ok().flatten();
     store.set(&key, &encoded).map_err(|_| ())?;
+    crate::admin::config_history::record_persisted_config_write(
+        store,
+        site_id,
+        previous.as_deref(),
+        encoded.as_slice(),
+        author,
+        reason,
+        recorded_at_ts,
+    );
     let decision_rows =
         operator_snapshot_recent_change_rows_with_decisions(store, site_id, recent_change_rows, recorded_at_ts);
     record_operator_snapshot_recent_change_rows(
@@ -19162,6 +20988,16 @@ pub(super) fn persist_site_config(
     Ok(())
 }
 
+/// Operator note recorded with a config version; defaults to the write kind.
+pub(super) fn admin_config_change_reason(req: &Request) -> String {
+    req.header("x-shuma-change-reason")
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| format!("config_patch: {value}"))
+        .unwrap_or_else(|| "config_patch".to_string())
+}
+
 fn operator_snapshot_recent_change_rows_with_decisions(
     store: &impl crate::challenge::KeyValueStore,
     site_id: &str,
@@ -19255,7 +21091,7 @@ pub(super) fn handle_admin_config_internal(
             Err(err) => return Response::new(500, err.user_message()),
         };
         let original_cfg = cfg.clone();
-        let admin_id = crate::admin::auth::get_admin_id(req);
+        let admin_id = crate::admin::auth::get_admin_id(req, store);
         let mut changed = false;
 
         // Update shadow_mode if provided.
@@ -19273,12 +21109,45 @@ pub(super) fn handle_admin_config_internal(
                             ip: None,
                             reason: Some("shadow_mode_toggle".to_string()),
                             outcome: Some(format!("{} -> {}", old_value, shadow_mode)),
-                            admin: Some(crate::admin::auth::get_admin_id(req)),
+                            admin: Some(crate::admin::auth::get_admin_id(req, store)),
                         },
                     );
                 }
             }
         }
+        if let Some(value) = json.get("shadow_policy_sources") {
+            match parse_string_list_json("shadow_policy_sources", value).and_then(|sources| {
+                crate::config::validate_shadow_policy_sources(&sources).map(|_| sources)
+            }) {
+                Ok(sources) => {
+                    cfg.shadow_policy_sources = sources;
+                    changed = true;
+                }
+                Err(msg) => return Response::new(400, msg),
+            }
+        }
+        if let Some(value) = json
+            .get("request_facts_journal_sample_percent")
+            .and_then(|v| v.as_u64())
+        {
+            if value > 100 {
+                return Response::new(
+                    400,
+                    "request_facts_journal_sample_percent out of range (0-100)",
+                );
+            }
+            cfg.request_facts_journal_sample_percent = value as u8;
+            changed = true;
+        }
+        if let Some(value) = json.get("config_profiles") {
+            match serde_json::from_value::<Vec<crate::config::ConfigProfile>>(value.clone()) {
+                Ok(profiles) => {
+                    cfg.config_profiles = profiles;
+                    changed = true;
+                }
+                Err(err) => return Response::new(400, format!("config_profiles: {}", err)),
+            }
+        }
         if let Some(adversary_sim_duration_seconds) = json
             .get("adversary_sim_duration_seconds")
             .and_then(|v| v.as_u64())
@@ -19472,6 +21341,15 @@ pub(super) fn handle_admin_config_internal(
                 Err(msg) => return Response::new(400, msg),
             }
         }
+        if let Some(value) = json.get("custom_rules") {
+            match parse_custom_rules_json("custom_rules", value) {
+                Ok(rules) => {
+                    cfg.custom_rules = rules;
+                    changed = true;
+                }
+                Err(msg) => return Response::new(400, msg),
+            }
+        }
 
         // Update per-type ban durations if provided
         if let Some(ban_durations) = json.get("ban_durations") {
@@ -20181,7 +22059,7 @@ pub(super) fn handle_admin_config_internal(
                         old_tarpit_fallback_action.as_str(),
                         cfg.tarpit_fallback_action.as_str()
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
         }
@@ -20350,6 +22228,27 @@ pub(super) fn handle_admin_config_internal(
             cfg.fingerprint_family_cap_behavior = value as u8;
             changed = true;
         }
+        if let Some(value) = json.get("fingerprint_transport_profiles") {
+            let profiles = match serde_json::from_value::<
+                Vec<crate::signals::fingerprint::TransportFingerprintProfile>,
+            >(value.clone())
+            {
+                Ok(profiles) => profiles,
+                Err(err) => {
+                    return Response::new(
+                        400,
+                        format!("fingerprint_transport_profiles invalid: {}", err),
+                    )
+                }
+            };
+            if let Err(msg) =
+                crate::signals::fingerprint::validate_transport_profiles(&profiles)
+            {
+                return Response::new(400, msg);
+            }
+            cfg.fingerprint_transport_profiles = profiles;
+            changed = true;
+        }
 
         let old_pow_enabled = cfg.pow_enabled;
         let old_pow_difficulty = cfg.pow_difficulty;
@@ -20400,7 +22299,7 @@ pub(super) fn handle_admin_config_internal(
                         old_pow_ttl,
                         cfg.pow_ttl_seconds
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
         }
@@ -20513,7 +22412,7 @@ pub(super) fn handle_admin_config_internal(
                         old_attempt_window_seconds,
                         cfg.challenge_puzzle_attempt_window_seconds
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
         }
@@ -20526,6 +22425,7 @@ pub(super) fn handle_admin_config_internal(
         let old_not_a_bot_marker_ttl_seconds = cfg.not_a_bot_marker_ttl_seconds;
         let old_not_a_bot_attempt_limit_per_window = cfg.not_a_bot_attempt_limit_per_window;
         let old_not_a_bot_attempt_window_seconds = cfg.not_a_bot_attempt_window_seconds;
+        let old_not_a_bot_accessible_question_count = cfg.not_a_bot_accessible_questions.len();
         let mut not_a_bot_changed = false;
 
         if let Some(not_a_bot_enabled) = json.get("not_a_bot_enabled").and_then(|v| v.as_bool()) {
@@ -20633,6 +22533,19 @@ pub(super) fn handle_admin_config_internal(
                 not_a_bot_changed = true;
             }
         }
+        if let Some(value) = json.get("not_a_bot_accessible_questions") {
+            match parse_not_a_bot_accessible_questions_json("not_a_bot_accessible_questions", value)
+            {
+                Ok(questions) => {
+                    if cfg.not_a_bot_accessible_questions != questions {
+                        cfg.not_a_bot_accessible_questions = questions;
+                        changed = true;
+                        not_a_bot_changed = true;
+                    }
+                }
+                Err(msg) => return Response::new(400, msg),
+            }
+        }
 
         if not_a_bot_changed && !validate_only {
             log_event(
@@ -20643,7 +22556,7 @@ pub(super) fn handle_admin_config_internal(
                     ip: None,
                     reason: Some("not_a_bot_config_update".to_string()),
                     outcome: Some(format!(
-                        "enabled:{}->{} threshold:{}->{} score_pass:{}->{} score_escalate:{}->{} nonce_ttl:{}->{} marker_ttl:{}->{} attempts:{}->{} window:{}->{}",
+                        "enabled:{}->{} threshold:{}->{} score_pass:{}->{} score_escalate:{}->{} nonce_ttl:{}->{} marker_ttl:{}->{} attempts:{}->{} window:{}->{} accessible_questions:{}->{}",
                         old_not_a_bot_enabled,
                         cfg.not_a_bot_enabled,
                         old_not_a_bot_threshold,
@@ -20659,9 +22572,11 @@ pub(super) fn handle_admin_config_internal(
                         old_not_a_bot_attempt_limit_per_window,
                         cfg.not_a_bot_attempt_limit_per_window,
                         old_not_a_bot_attempt_window_seconds,
-                        cfg.not_a_bot_attempt_window_seconds
+                        cfg.not_a_bot_attempt_window_seconds,
+                        old_not_a_bot_accessible_question_count,
+                        cfg.not_a_bot_accessible_questions.len()
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
         }
@@ -20777,7 +22692,7 @@ pub(super) fn handle_admin_config_internal(
                         old_edge_integration_mode.as_str(),
                         cfg.edge_integration_mode.as_str(),
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
         }
@@ -20843,6 +22758,51 @@ pub(super) fn handle_admin_config_internal(
                 changed = true;
                 verified_identity_changed = true;
             }
+            if let Some(value) = patch.restrict_requests_per_minute {
+                cfg.verified_identity.restrict_requests_per_minute = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.restrict_denied_path_prefixes {
+                cfg.verified_identity.restrict_denied_path_prefixes = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.mtls_enabled {
+                cfg.verified_identity.mtls_enabled = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.mtls_ca_bundle {
+                cfg.verified_identity.mtls_ca_bundle = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.usage_quotas {
+                cfg.verified_identity.usage_quotas = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.licence_offers {
+                cfg.verified_identity.licence_offers = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.licence_keys {
+                cfg.verified_identity.licence_keys = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.delegation_keys {
+                cfg.verified_identity.delegation_keys = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
+            if let Some(value) = patch.pinned_directories {
+                cfg.verified_identity.pinned_directories = value;
+                changed = true;
+                verified_identity_changed = true;
+            }
         }
 
         if verified_identity_changed && !validate_only {
@@ -20854,7 +22814,7 @@ pub(super) fn handle_admin_config_internal(
                     ip: None,
                     reason: Some("verified_identity_config_update".to_string()),
                     outcome: Some(format!(
-                        "enabled:{}->{} native:{}->{} provider:{}->{} replay:{}->{} skew:{}->{} cache_ttl:{}->{} freshness:{}->{} policies:{}->{} category_defaults:{}->{} profiles:{}->{}",
+                        "enabled:{}->{} native:{}->{} provider:{}->{} replay:{}->{} skew:{}->{} cache_ttl:{}->{} freshness:{}->{} policies:{}->{} category_defaults:{}->{} profiles:{}->{} restrict_rpm:{}->{} restrict_paths:{}->{} mtls:{}->{} mtls_cas:{}->{} quotas:{}->{} licence_offers:{}->{} licence_keys:{}->{} delegation_keys:{}->{} pinned_directories:{}->{}",
                         old_verified_identity.enabled,
                         cfg.verified_identity.enabled,
                         old_verified_identity.native_web_bot_auth_enabled,
@@ -20874,9 +22834,27 @@ pub(super) fn handle_admin_config_internal(
                         old_verified_identity.category_defaults.len(),
                         cfg.verified_identity.category_defaults.len(),
                         old_verified_identity.service_profiles.len(),
-                        cfg.verified_identity.service_profiles.len()
+                        cfg.verified_identity.service_profiles.len(),
+                        old_verified_identity.restrict_requests_per_minute,
+                        cfg.verified_identity.restrict_requests_per_minute,
+                        old_verified_identity.restrict_denied_path_prefixes.len(),
+                        cfg.verified_identity.restrict_denied_path_prefixes.len(),
+                        old_verified_identity.mtls_enabled,
+                        cfg.verified_identity.mtls_enabled,
+                        old_verified_identity.mtls_ca_bundle.len(),
+                        cfg.verified_identity.mtls_ca_bundle.len(),
+                        old_verified_identity.usage_quotas.len(),
+                        cfg.verified_identity.usage_quotas.len(),
+                        old_verified_identity.licence_offers.len(),
+                        cfg.verified_identity.licence_offers.len(),
+                        old_verified_identity.licence_keys.len(),
+                        cfg.verified_identity.licence_keys.len(),
+                        old_verified_identity.delegation_keys.len(),
+                        cfg.verified_identity.delegation_keys.len(),
+                        old_verified_identity.pinned_directories.len(),
+                        cfg.verified_identity.pinned_directories.len()
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
         }
@@ -20946,6 +22924,38 @@ pub(super) fn handle_admin_config_internal(
                 changed = true;
                 botness_changed = true;
             }
+            if let Some(session_asset_ratio) = weights.get("session_asset_ratio").and_then(|v| v.as_u64()) {
+                if session_asset_ratio > 10 {
+                    return Response::new(400, "botness_weights.session_asset_ratio out of range (0-10)");
+                }
+                cfg.botness_weights.session_asset_ratio = session_asset_ratio as u8;
+                changed = true;
+                botness_changed = true;
+            }
+            if let Some(session_traversal) = weights.get("session_traversal").and_then(|v| v.as_u64()) {
+                if session_traversal > 10 {
+                    return Response::new(400, "botness_weights.session_traversal out of range (0-10)");
+                }
+                cfg.botness_weights.session_traversal = session_traversal as u8;
+                changed = true;
+                botness_changed = true;
+            }
+            if let Some(session_timing) = weights.get("session_timing").and_then(|v| v.as_u64()) {
+                if session_timing > 10 {
+                    return Response::new(400, "botness_weights.session_timing out of range (0-10)");
+                }
+                cfg.botness_weights.session_timing = session_timing as u8;
+                changed = true;
+                botness_changed = true;
+            }
+            if let Some(session_referer) = weights.get("session_referer").and_then(|v| v.as_u64()) {
+                if session_referer > 10 {
+                    return Response::new(400, "botness_weights.session_referer out of range (0-10)");
+                }
+                cfg.botness_weights.session_referer = session_referer as u8;
+                changed = true;
+                botness_changed = true;
+            }
         }
         if let Some(defence_modes) = json.get("defence_modes") {
             let Some(modes_obj) = defence_modes.as_object() else {
@@ -21005,7 +23015,7 @@ pub(super) fn handle_admin_config_internal(
                     ip: None,
                     reason: Some("botness_config_update".to_string()),
                     outcome: Some(format!(
-                        "challenge:{}->{} maze:{}->{} weights(js:{}->{} geo:{}->{} rate_med:{}->{} rate_high:{}->{} maze_behavior:{}->{}) modes(rate:{:?}->{:?} geo:{:?}->{:?} js:{:?}->{:?})",
+                        "challenge:{}->{} maze:{}->{} weights(js:{}->{} geo:{}->{} rate_med:{}->{} rate_high:{}->{} maze_behavior:{}->{} session_assets:{}->{} session_traversal:{}->{} session_timing:{}->{} session_referer:{}->{}) modes(rate:{:?}->{:?} geo:{:?}->{:?} js:{:?}->{:?})",
                         old_challenge_threshold,
                         cfg.challenge_puzzle_risk_threshold,
                         old_maze_threshold,
@@ -21020,6 +23030,14 @@ pub(super) fn handle_admin_config_internal(
                         cfg.botness_weights.rate_high,
                         old_weights.maze_behavior,
                         cfg.botness_weights.maze_behavior,
+                        old_weights.session_asset_ratio,
+                        cfg.botness_weights.session_asset_ratio,
+                        old_weights.session_traversal,
+                        cfg.botness_weights.session_traversal,
+                        old_weights.session_timing,
+                        cfg.botness_weights.session_timing,
+                        old_weights.session_referer,
+                        cfg.botness_weights.session_referer,
                         old_modes.rate,
                         cfg.defence_modes.rate,
                         old_modes.geo,
@@ -21027,7 +23045,7 @@ pub(super) fn handle_admin_config_internal(
                         old_modes.js,
                         cfg.defence_modes.js
                     )),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 });
         }
 
@@ -21046,7 +23064,16 @@ pub(super) fn handle_admin_config_internal(
             )
             .into_iter()
             .collect::<Vec<_>>();
-            if persist_site_config(store, site_id, &cfg, recent_change_rows.as_slice()).is_err() {
+            if persist_site_config(
+                store,
+                site_id,
+                &cfg,
+                recent_change_rows.as_slice(),
+                admin_id.as_str(),
+                admin_config_change_reason(req).as_str(),
+            )
+            .is_err()
+            {
                 return Response::new(500, "Key-value store error");
             }
         }
@@ -21091,7 +23118,7 @@ pub(super) fn handle_admin_config_internal(
             ip: None,
             reason: Some("config_view".to_string()),
             outcome: Some(format!("shadow_mode={}", cfg.shadow_mode)),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
     let challenge_default = challenge_threshold_default();
@@ -21283,6 +23310,44 @@ where
         .build()
 }
 
+/// GET aggregates metered verified-identity usage per identity and operator over the last
+/// `?days=N` days (default 7).
+fn handle_admin_verified_identity_usage<S>(req: &Request, store: &S, site_id: &str) -> Response
+where
+    S: crate::challenge::KeyValueStore,
+{
+    if *req.method() != spin_sdk::http::Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let days = query_u64_param(req.query(), "days", 7).clamp(
+        1,
+        u64::from(crate::bot_identity::usage::USAGE_REPORT_MAX_DAYS),
+    ) as u32;
+    let report = crate::bot_identity::usage::usage_report(store, site_id, days, now_ts());
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&report).unwrap())
+        .build()
+}
+
+fn handle_admin_verified_identity_licences<S>(req: &Request, store: &S, site_id: &str) -> Response
+where
+    S: crate::challenge::KeyValueStore,
+{
+    if *req.method() != spin_sdk::http::Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let ledgers = crate::bot_identity::licensing::licence_ledgers(store, site_id);
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&serde_json::json!({ "identities": ledgers })).unwrap())
+        .build()
+}
+
 fn read_u64_counter<S>(store: &S, key: &str) -> u64
 where
     S: crate::challenge::KeyValueStore,
@@ -22174,6 +24239,9 @@ fn request_bypasses_admin_ip_allowlist(req: &Request, path: &str) -> bool {
         INTERNAL_ADVERSARY_SIM_WORKER_RESULT_PATH => {
             crate::admin::auth::is_internal_adversary_sim_supervisor_request(req)
         }
+        INTERNAL_ALERT_RULES_EVALUATE_PATH => {
+            crate::admin::auth::is_internal_alert_rules_evaluate_request(req)
+        }
         _ => false,
     }
 }
@@ -22185,6 +24253,8 @@ fn request_bypasses_admin_ip_allowlist(req: &Request, path: &str) -> bool {
 ///   - GET /shuma/internal/adversary-sim/beat?edge_cron_secret=...: run one bounded edge cron beat
 ///   - POST /shuma/internal/adversary-sim/worker-result: persist one bounded Scrapling worker result
 ///   - POST /shuma/internal/oversight/agent/run: execute one bounded shared-host recommend-only agent cycle
+///   - POST /shuma/internal/alerts/evaluate: evaluate alert rules and deliver owed notifications
+///   - GET /shuma/internal/alerts/evaluate?edge_cron_secret=...: the same evaluation from edge cron
 pub fn handle_internal(req: &Request) -> Response {
     let path = req.path();
     let internal_beat_authorized = path == INTERNAL_ADVERSARY_SIM_BEAT_PATH
@@ -22193,9 +24263,12 @@ pub fn handle_internal(req: &Request) -> Response {
         && crate::admin::auth::is_internal_adversary_sim_supervisor_request(req);
     let internal_oversight_agent_authorized = path == OVERSIGHT_AGENT_INTERNAL_PATH
         && crate::admin::auth::is_internal_oversight_supervisor_request(req);
+    let internal_alert_evaluate_authorized = path == INTERNAL_ALERT_RULES_EVALUATE_PATH
+        && crate::admin::auth::is_internal_alert_rules_evaluate_request(req);
     if !internal_beat_authorized
         && !internal_worker_result_authorized
         && !internal_oversight_agent_authorized
+        && !internal_alert_evaluate_authorized
         && !request_bypasses_admin_ip_allowlist(req, path)
         && !crate::admin::auth::is_admin_ip_allowed(req)
     {
@@ -22204,6 +24277,7 @@ pub fn handle_internal(req: &Request) -> Response {
     if !internal_beat_authorized
         && !internal_worker_result_authorized
         && !internal_oversight_agent_authorized
+        && !internal_alert_evaluate_authorized
         && !crate::admin::auth::is_admin_api_key_configured()
     {
         return Response::new(503, "Internal API disabled: admin key not configured");
@@ -22231,6 +24305,15 @@ pub fn handle_internal(req: &Request) -> Response {
             };
             handle_internal_oversight_agent_run(req, &store, "default")
         }
+        INTERNAL_ALERT_RULES_EVALUATE_PATH => {
+            let store = match Store::open_default() {
+                Ok(s) => s,
+                Err(_) => return Response::new(500, "Key-value store error"),
+            };
+            crate::admin::alert_rules_api::handle_internal_alert_rules_evaluate(
+                req, &store, "default",
+            )
+        }
         _ => Response::new(404, "Not Found"),
     }
 }
@@ -22278,12 +24361,16 @@ fn finalize_manual_ban_result<S: crate::challenge::KeyValueStore>(
             ip: Some(ip.to_string()),
             reason: Some(reason.to_string()),
             outcome: Some("banned".to_string()),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
     Response::new(200, json!({"status": "banned", "ip": ip}).to_string())
 }
 
+pub(super) fn admin_ban_duration_bounds() -> (u64, u64) {
+    (ADMIN_BAN_DURATION_MIN, ADMIN_BAN_DURATION_MAX)
+}
+
 fn resolve_manual_ban_duration_seconds(
     json: &serde_json::Value,
     cfg: &crate::config::Config,
@@ -22369,7 +24456,7 @@ fn finalize_manual_unban_result<S: crate::challenge::KeyValueStore>(
             ip: Some(ip.to_string()),
             reason: Some("admin_unban".to_string()),
             outcome: Some("unbanned".to_string()),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
     Response::new(200, "Unbanned")
@@ -22380,6 +24467,8 @@ fn finalize_manual_unban_result<S: crate::challenge::KeyValueStore>(
 ///   - POST /shuma/admin/login: Exchange API key for short-lived admin session cookie
 ///   - GET /shuma/admin/session: Return current admin auth session state
 ///   - POST /shuma/admin/logout: Clear admin session cookie
+///   - GET /shuma/admin/oidc/login: Start OIDC single sign-on (authorization code + PKCE)
+///   - GET /shuma/admin/oidc/callback: Finish OIDC sign-on and open a role-pinned admin session
 ///   - GET /shuma/admin/ban: List all bans for the site
 ///   - POST /shuma/admin/ban: Manually ban an IP (expects JSON body: {"ip": "1.2.3.4", "duration": 3600}; reason is fixed to "manual_ban")
 ///   - POST /shuma/admin/unban?ip=...: Remove a ban for an IP
@@ -22388,6 +24477,11 @@ fn finalize_manual_unban_result<S: crate::challenge::KeyValueStore>(
 ///   - GET /shuma/admin/cdp/events: Query CDP-only events
 ///   - GET /shuma/admin/operator-snapshot: Query the machine-first operator snapshot contract
 ///   - GET/POST /shuma/admin/operator-objectives: Read or update the persisted operator-objectives contract
+///   - GET/POST /shuma/admin/alert-rules: Read alert rules with their current state, or replace the rule set
+///   - GET/POST/DELETE /shuma/admin/accounts: List, create/update, or delete named admin accounts (owner only)
+///   - GET/POST/DELETE /shuma/admin/tokens: List, issue, or revoke scoped admin API tokens (owner only)
+///   - GET/POST /shuma/admin/mfa: Read, enrol, confirm, disable, or (owner) reset a TOTP second factor
+///   - POST /shuma/admin/mfa/step-up: Re-verify the session's second factor before sensitive writes
 ///   - GET/POST /shuma/admin/replay-promotion: Read or materialize bounded replay-promotion lineage
 ///   - GET /shuma/admin/benchmark-suite: Query the machine-first benchmark family registry
 ///   - GET /shuma/admin/benchmark-results: Query the bounded machine-first benchmark result envelope
@@ -22401,7 +24495,15 @@ fn finalize_manual_unban_result<S: crate::challenge::KeyValueStore>(
 ///   - POST /shuma/admin/config: Update config (e.g., toggle shadow_mode)
 ///   - POST /shuma/admin/config/bootstrap: Seed missing KV config explicitly from a full config payload
 ///   - POST /shuma/admin/config/validate: Validate a config patch without persisting changes
+///   - POST /shuma/admin/policy-simulation: Replay journaled request facts under a candidate config patch
+///   - GET /shuma/admin/botness-calibration: Fit botness weights from labelled journal rows and propose a bounded patch
 ///   - GET /shuma/admin/config/export: Export non-secret runtime config for immutable deploy handoff
+///   - GET /shuma/admin/config/history: List config versions (`?version=N` for one snapshot and its diff)
+///   - GET /shuma/admin/config/diff: Field-level diff between two config versions (`from`, `to`)
+///   - POST /shuma/admin/config/rollback: Restore a prior config version as a new version
+///   - GET /shuma/admin/gitops: Last desired-state apply and drift from it
+///   - POST /shuma/admin/gitops/plan: Diff a desired-state document against live config and bans
+///   - POST /shuma/admin/gitops/apply: Apply an unchanged plan (`plan_id`) atomically for config
 ///   - POST /shuma/admin/adversary-sim/control: Start/stop adversary simulation orchestration
 ///   - GET /shuma/admin/adversary-sim/status: Read orchestration state and guardrails
 ///   - POST /shuma/admin/adversary-sim/history/cleanup: Explicitly clear retained telemetry history
@@ -22424,7 +24526,14 @@ pub fn handle_admin(req: &Request) -> Response {
         return Response::new(400, "Bad Request: Invalid admin endpoint");
     }
 
-    if path == "/shuma/admin/login" || path == "/shuma/admin/session" || path == "/shuma/admin/logout" {
+    if matches!(
+        path,
+        "/shuma/admin/login"
+            | "/shuma/admin/session"
+            | "/shuma/admin/logout"
+            | "/shuma/admin/oidc/login"
+            | "/shuma/admin/oidc/callback"
+    ) {
         let store = match Store::open_default() {
             Ok(s) => s,
             Err(_) => return Response::new(500, "Key-value store error"),
@@ -22442,6 +24551,22 @@ pub fn handle_admin(req: &Request) -> Response {
                 )
             }),
             "/shuma/admin/session" => handle_admin_session(req, &store),
+            "/shuma/admin/oidc/login" => {
+                handle_admin_oidc_login(req, &store, &crate::admin::oidc::SpinOidcHttpClient)
+            }
+            "/shuma/admin/oidc/callback" => handle_admin_oidc_callback_with_failure_handler(
+                req,
+                &store,
+                &crate::admin::oidc::SpinOidcHttpClient,
+                || {
+                    register_admin_auth_failure_with_selected_rate_limiter(
+                        &store,
+                        req,
+                        crate::admin::auth::AdminAuthFailureScope::Login,
+                        provider_registry.as_ref(),
+                    )
+                },
+            ),
             "/shuma/admin/logout" => handle_admin_logout_with_failure_handler(req, &store, || {
                 register_admin_auth_failure_with_selected_rate_limiter(
                     &store,
@@ -22524,9 +24649,50 @@ pub fn handle_admin(req: &Request) -> Response {
             return Response::new(403, "Forbidden");
         }
     }
-    if request_requires_admin_write(path, req.method()) && !auth.is_write_authorized() {
-        log_admin_write_denied(&store, req, path, &auth);
-        return Response::new(403, "Forbidden: admin write access required");
+    let required_permission = required_admin_permission(path, req.method());
+    if let Some(token) = auth.token.as_ref() {
+        let required_scope = required_admin_token_scope(path, req.method());
+        if !required_scope
+            .map(|scope| token.grants_scope(scope))
+            .unwrap_or(false)
+        {
+            log_admin_permission_denied(&store, req, path, &auth, required_permission);
+            return Response::new(
+                403,
+                format!(
+                    "Forbidden: token scope {} required",
+                    required_scope.unwrap_or("(not available to API tokens)")
+                ),
+            );
+        }
+    } else if !auth.has_permission(required_permission) {
+        log_admin_permission_denied(&store, req, path, &auth, required_permission);
+        if request_requires_admin_write(path, req.method()) && !auth.is_write_authorized() {
+            return Response::new(403, "Forbidden: admin write access required");
+        }
+        return Response::new(
+            403,
+            format!(
+                "Forbidden: admin permission {} required",
+                required_permission.as_str()
+            ),
+        );
+    }
+    if request_requires_admin_step_up(path, req.method())
+        && !admin_step_up_is_satisfied(req, &store, &auth)
+    {
+        if step_up_header_code(req).is_some()
+            && register_admin_auth_failure_with_selected_rate_limiter(
+                &store,
+                req,
+                crate::admin::auth::AdminAuthFailureScope::Endpoint,
+                provider_registry.as_ref(),
+            )
+        {
+            return too_many_admin_auth_attempts_response();
+        }
+        log_admin_permission_denied(&store, req, path, &auth, required_permission);
+        return step_up_required_response();
     }
 
     let site_id = "default";
@@ -22611,12 +24777,43 @@ pub fn handle_admin(req: &Request) -> Response {
             handle_admin_operator_snapshot(req, &store)
         }
         "/shuma/admin/operator-objectives" => handle_admin_operator_objectives(req, &store, site_id),
+        "/shuma/admin/alert-rules" => handle_admin_alert_rules(req, &store, site_id),
+        "/shuma/admin/accounts" => handle_admin_accounts(req, &store),
+        "/shuma/admin/tokens" => handle_admin_tokens(req, &store),
+        "/shuma/admin/mfa" => handle_admin_mfa(req, &store, &auth, || {
+            register_admin_auth_failure_with_selected_rate_limiter(
+                &store,
+                req,
+                crate::admin::auth::AdminAuthFailureScope::Endpoint,
+                provider_registry.as_ref(),
+            )
+        }),
+        "/shuma/admin/mfa/step-up" => handle_admin_mfa_step_up(req, &store, &auth, || {
+            register_admin_auth_failure_with_selected_rate_limiter(
+                &store,
+                req,
+                crate::admin::auth::AdminAuthFailureScope::Endpoint,
+                provider_registry.as_ref(),
+            )
+        }),
         "/shuma/admin/oversight/reconcile" => handle_admin_oversight_reconcile(req, &store, site_id),
         "/shuma/admin/oversight/history" => handle_admin_oversight_history(req, &store, site_id),
         "/shuma/admin/oversight/agent/status" => {
             handle_admin_oversight_agent_status(req, &store, site_id)
         }
         "/shuma/admin/replay-promotion" => handle_admin_replay_promotion(req, &store, site_id),
+        "/shuma/admin/policy-simulation" => {
+            if expensive_admin_read_is_limited(&store, req, &auth, provider_registry.as_ref()) {
+                return too_many_admin_read_requests_response();
+            }
+            handle_admin_policy_simulation(req, &store, site_id)
+        }
+        "/shuma/admin/botness-calibration" => {
+            if expensive_admin_read_is_limited(&store, req, &auth, provider_registry.as_ref()) {
+                return too_many_admin_read_requests_response();
+            }
+            handle_admin_botness_calibration(req, &store, site_id)
+        }
         "/shuma/admin/benchmark-suite" => handle_admin_benchmark_suite(req),
         "/shuma/admin/benchmark-results" => {
             if expensive_admin_read_is_limited(&store, req, &auth, provider_registry.as_ref()) {
@@ -22672,6 +24869,21 @@ pub fn handle_admin(req: &Request) -> Response {
             }
             handle_admin_ip_range_suggestions(req, &store, site_id)
         }
+        "/shuma/admin/verified-identity/usage" => {
+            if expensive_admin_read_is_limited(&store, req, &auth, provider_registry.as_ref()) {
+                return too_many_admin_read_requests_response();
+            }
+            handle_admin_verified_identity_usage(req, &store, site_id)
+        }
+        "/shuma/admin/verified-identity/licences" => {
+            if expensive_admin_read_is_limited(&store, req, &auth, provider_registry.as_ref()) {
+                return too_many_admin_read_requests_response();
+            }
+            handle_admin_verified_identity_licences(req, &store, site_id)
+        }
+        "/shuma/admin/verified-identity/directories" => {
+            handle_admin_signature_directories(req, &store, site_id)
+        }
         "/shuma/admin/ban" => {
             if *req.method() == spin_sdk::http::Method::Get
                 && (dashboard_refresh_is_limited(&store, &auth, provider_registry.as_ref())
@@ -22753,6 +24965,12 @@ pub fn handle_admin(req: &Request) -> Response {
         "/shuma/admin/config/export" => {
             return handle_admin_config_export(req, &store, site_id);
         }
+        "/shuma/admin/config/history" => handle_admin_config_history(req, &store, site_id),
+        "/shuma/admin/config/diff" => handle_admin_config_diff(req, &store, site_id),
+        "/shuma/admin/config/rollback" => handle_admin_config_rollback(req, &store, site_id),
+        "/shuma/admin/gitops" | "/shuma/admin/gitops/plan" | "/shuma/admin/gitops/apply" => {
+            handle_admin_gitops_route(req, &store, site_id)
+        }
         "/shuma/admin/adversary-sim/control" => {
             return handle_admin_adversary_sim_control(req, &store, site_id, &auth);
         }
@@ -22784,10 +25002,10 @@ pub fn handle_admin(req: &Request) -> Response {
                     ip: None,
                     reason: Some("help".to_string()),
                     outcome: None,
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, &store)),
                 },
             );
-            Response::new(200, "WASM Bot Defence Admin API. Endpoints: /shuma/admin/ban, /shuma/admin/unban?ip=IP, /shuma/admin/analytics, /shuma/admin/events, /shuma/admin/operator-snapshot, /shuma/admin/operator-objectives, /shuma/admin/oversight/reconcile, /shuma/admin/oversight/history, /shuma/admin/oversight/agent/status, /shuma/admin/replay-promotion, /shuma/admin/benchmark-suite, /shuma/admin/benchmark-results, /shuma/admin/monitoring, /shuma/admin/monitoring/delta, /shuma/admin/monitoring/stream, /shuma/admin/ip-bans/delta, /shuma/admin/ip-bans/stream, /shuma/admin/ip-range/suggestions, /shuma/admin/config, /shuma/admin/config/bootstrap, /shuma/admin/config/validate, /shuma/admin/config/export, /shuma/admin/adversary-sim/control, /shuma/admin/adversary-sim/status, /shuma/admin/adversary-sim/history/cleanup, /shuma/admin/maze (GET for maze stats), /shuma/admin/maze/preview (GET non-operational maze preview), /shuma/admin/tarpit/preview (GET non-operational progressive tarpit preview), /shuma/admin/maze/seeds (GET/POST seed source adapters), /shuma/admin/maze/seeds/refresh (POST manual seed refresh), /shuma/admin/robots (GET for robots.txt config & preview), /shuma/admin/robots/preview (POST unsaved robots preview patch), /shuma/admin/cdp (GET for CDP detection config & stats), /shuma/admin/cdp/events (GET for CDP detection and auto-ban events).")
+            Response::new(200, "WASM Bot Defence Admin API. Endpoints: /shuma/admin/ban, /shuma/admin/unban?ip=IP, /shuma/admin/analytics, /shuma/admin/events, /shuma/admin/operator-snapshot, /shuma/admin/operator-objectives, /shuma/admin/alert-rules, /shuma/admin/accounts, /shuma/admin/tokens, /shuma/admin/mfa, /shuma/admin/mfa/step-up, /shuma/admin/oversight/reconcile, /shuma/admin/oversight/history, /shuma/admin/oversight/agent/status, /shuma/admin/replay-promotion, /shuma/admin/benchmark-suite, /shuma/admin/benchmark-results, /shuma/admin/monitoring, /shuma/admin/monitoring/delta, /shuma/admin/monitoring/stream, /shuma/admin/ip-bans/delta, /shuma/admin/ip-bans/stream, /shuma/admin/ip-range/suggestions, /shuma/admin/config, /shuma/admin/config/bootstrap, /shuma/admin/config/validate, /shuma/admin/config/export, /shuma/admin/config/history, /shuma/admin/config/diff, /shuma/admin/config/rollback, /shuma/admin/gitops, /shuma/admin/gitops/plan, /shuma/admin/gitops/apply, /shuma/admin/adversary-sim/control, /shuma/admin/adversary-sim/status, /shuma/admin/adversary-sim/history/cleanup, /shuma/admin/maze (GET for maze stats), /shuma/admin/maze/preview (GET non-operational maze preview), /shuma/admin/tarpit/preview (GET non-operational progressive tarpit preview), /shuma/admin/maze/seeds (GET/POST seed source adapters), /shuma/admin/maze/seeds/refresh (POST manual seed refresh), /shuma/admin/robots (GET for robots.txt config & preview), /shuma/admin/robots/preview (POST unsaved robots preview patch), /shuma/admin/policy-simulation (POST what-if replay of a config patch), /shuma/admin/botness-calibration (GET weight fit from labelled outcomes), /shuma/admin/cdp (GET for CDP detection config & stats), /shuma/admin/cdp/events (GET for CDP detection and auto-ban events).")
         }
         "/shuma/admin/maze" => {
             // Return maze statistics
@@ -22846,7 +25064,7 @@ pub fn handle_admin(req: &Request) -> Response {
                     ip: None,
                     reason: Some("maze_stats_view".to_string()),
                     outcome: Some(format!("{} crawlers, {} hits", maze_ips.len(), total_hits)),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, &store)),
                 },
             );
 
@@ -22884,7 +25102,7 @@ pub fn handle_admin(req: &Request) -> Response {
                     ip: None,
                     reason: Some("robots_preview_patch".to_string()),
                     outcome: None,
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, &store)),
                 },
             );
             admin_robots_response(&cfg)
@@ -22905,7 +25123,7 @@ pub fn handle_admin(req: &Request) -> Response {
                     ip: None,
                     reason: Some("robots_config_view".to_string()),
                     outcome: None,
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, &store)),
                 },
             );
             admin_robots_response(&cfg)
@@ -22993,7 +25211,7 @@ pub fn handle_admin(req: &Request) -> Response {
                     ip: None,
                     reason: Some("cdp_config_view".to_string()),
                     outcome: None,
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, &store)),
                 },
             );
 
@@ -1,7 +1,11 @@
+use super::accounts::{AdminPermission, AdminRole};
+use super::tokens::AdminTokenGrant;
 use crate::challenge::KeyValueStore;
 use crate::signals::allowlist;
+use hmac::{Hmac, Mac};
 use rand::Rng as _;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use spin_sdk::http::{Method, Request};
 use spin_sdk::key_value::Store;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -10,6 +14,10 @@ const INSECURE_DEFAULT_API_KEY: &str = "changeme-supersecret";
 const ADMIN_SESSION_COOKIE_NAME: &str = "shuma_admin_session";
 const ADMIN_SESSION_KEY_PREFIX: &str = "admin_session:";
 const ADMIN_SESSION_TTL_SECONDS: u64 = 3600;
+/// SSO sessions pin the issuer-asserted role, so they are kept short: a group removal or
+/// offboarding at the identity provider takes effect at the next sign-in, at most this late.
+const ADMIN_SSO_SESSION_TTL_SECONDS: u64 = 900;
+const ADMIN_STEP_UP_TTL_SECONDS: u64 = 300;
 const ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE_DEFAULT: u32 = 10;
 const ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE_MIN: u32 = 1;
 const ADMIN_AUTH_FAILURE_LIMIT_PER_MINUTE_MAX: u32 = 10_000;
@@ -20,6 +28,15 @@ const ADMIN_AUTH_FAILURE_SITE_ENDPOINT: &str = "admin-auth-endpoint";
 struct AdminSessionRecord {
     csrf_token: String,
     expires_at: u64,
+    /// Named account behind the session; absent for sessions opened with `SHUMA_API_KEY`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    /// Role asserted by the OIDC issuer at sign-in; SSO sessions have no local account to re-read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sso_role: Option<AdminRole>,
+    /// Sensitive writes skip the second-factor prompt until this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    step_up_until: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -50,6 +67,10 @@ pub struct AdminAuthResult {
     pub csrf_token: Option<String>,
     pub session_id: Option<String>,
     pub session_expires_at: Option<u64>,
+    pub role: Option<AdminRole>,
+    pub username: Option<String>,
+    /// Scoped API token behind a bearer request; such requests carry no role.
+    pub token: Option<AdminTokenGrant>,
 }
 
 impl AdminAuthResult {
@@ -60,6 +81,9 @@ impl AdminAuthResult {
             csrf_token: None,
             session_id: None,
             session_expires_at: None,
+            role: None,
+            username: None,
+            token: None,
         }
     }
 
@@ -75,6 +99,19 @@ impl AdminAuthResult {
         self.access == Some(AdminAccessLevel::ReadWrite)
     }
 
+    pub fn has_permission(&self, permission: AdminPermission) -> bool {
+        self.role
+            .map(|role| role.grants(permission))
+            .unwrap_or(false)
+    }
+
+    pub fn role_label(&self) -> &'static str {
+        match self.role {
+            Some(role) => role.as_str(),
+            None => "none",
+        }
+    }
+
     pub fn access_label(&self) -> &'static str {
         match self.access {
             Some(level) => level.as_str(),
@@ -82,8 +119,16 @@ impl AdminAuthResult {
         }
     }
 
-    pub fn audit_actor_label(&self) -> &'static str {
-        match (self.method, self.access) {
+    /// Actor recorded in audit trails: `user:<name>` for named accounts, otherwise a label for
+    /// the shared credential that authenticated the request.
+    pub fn audit_actor_label(&self) -> String {
+        if let Some(username) = self.username.as_deref() {
+            return account_actor_label(username);
+        }
+        if let Some(token) = self.token.as_ref() {
+            return token_actor_label(token.token_id.as_str());
+        }
+        let label = match (self.method, self.access) {
             (Some(AdminAuthMethod::BearerToken), Some(AdminAccessLevel::ReadOnly)) => {
                 "admin_bearer_ro"
             }
@@ -94,7 +139,23 @@ impl AdminAuthResult {
                 "admin_session_rw"
             }
             _ => "-",
-        }
+        };
+        label.to_string()
+    }
+}
+
+fn account_actor_label(username: &str) -> String {
+    format!("user:{}", username)
+}
+
+fn token_actor_label(token_id: &str) -> String {
+    format!("token:{}", token_id)
+}
+
+fn legacy_bearer_role(access: AdminAccessLevel) -> AdminRole {
+    match access {
+        AdminAccessLevel::ReadOnly => AdminRole::Viewer,
+        AdminAccessLevel::ReadWrite => AdminRole::Owner,
     }
 }
 
@@ -112,7 +173,7 @@ fn method_is_write(method: &Method) -> bool {
     )
 }
 
-fn constant_time_eq(a: &str, b: &str) -> bool {
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
     if a.len() != b.len() {
         return false;
     }
@@ -144,7 +205,7 @@ fn session_store_key(session_id: &str) -> String {
     format!("{}{}", ADMIN_SESSION_KEY_PREFIX, session_id)
 }
 
-fn parse_cookie(req: &Request, key: &str) -> Option<String> {
+pub(crate) fn parse_cookie(req: &Request, key: &str) -> Option<String> {
     let cookie_header = req.header("cookie")?.as_str()?;
     for part in cookie_header.split(';') {
         let trimmed = part.trim();
@@ -169,6 +230,14 @@ fn get_admin_api_key() -> Option<String> {
     Some(key)
 }
 
+/// Derives a purpose-bound signing key from `SHUMA_API_KEY` for short-lived browser state.
+pub(crate) fn derive_admin_secret(purpose: &str) -> Option<[u8; 32]> {
+    let key = get_admin_api_key()?;
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key.as_bytes()).ok()?;
+    mac.update(purpose.as_bytes());
+    Some(mac.finalize().into_bytes().into())
+}
+
 fn get_admin_readonly_api_key() -> Option<String> {
     let key = crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_READONLY_API_KEY")?;
     if key.is_empty() {
@@ -215,25 +284,59 @@ fn bearer_token(req: &Request) -> Option<String> {
     Some(header[prefix.len()..].trim().to_string())
 }
 
-pub fn bearer_access_level(req: &Request) -> Option<AdminAccessLevel> {
+fn bearer_api_key_access_level(req: &Request) -> Option<AdminAccessLevel> {
     let candidate = bearer_token(req)?;
     classify_admin_api_key_candidate(&candidate)
 }
 
+fn bearer_admin_api_token<S: KeyValueStore>(
+    req: &Request,
+    store: &S,
+) -> Option<super::tokens::AdminApiToken> {
+    let candidate = bearer_token(req)?;
+    super::tokens::verify_admin_api_token(
+        store,
+        candidate.as_str(),
+        crate::extract_client_ip(req).as_str(),
+        now_ts(),
+    )
+}
+
+fn admin_api_token_access_level(token: &super::tokens::AdminApiToken) -> AdminAccessLevel {
+    if AdminTokenGrant::from(token).has_write_scope() {
+        AdminAccessLevel::ReadWrite
+    } else {
+        AdminAccessLevel::ReadOnly
+    }
+}
+
+/// Store-free pre-check: true for a valid shared admin key or anything shaped like a scoped API
+/// token, which `authenticate_admin` then verifies against KV.
 pub fn is_bearer_authorized(req: &Request) -> bool {
-    bearer_access_level(req).is_some()
+    bearer_api_key_access_level(req).is_some()
+        || bearer_token(req)
+            .map(|candidate| super::tokens::looks_like_admin_api_token(candidate.as_str()))
+            .unwrap_or(false)
 }
 
 pub fn has_admin_session_cookie(req: &Request) -> bool {
     parse_cookie(req, ADMIN_SESSION_COOKIE_NAME).is_some()
 }
 
-pub fn get_admin_id(req: &Request) -> String {
-    match bearer_access_level(req) {
+pub fn get_admin_id<S: KeyValueStore>(req: &Request, store: &S) -> String {
+    if let Some(token) = bearer_admin_api_token(req, store) {
+        return token_actor_label(token.token_id.as_str());
+    }
+    match bearer_api_key_access_level(req) {
         Some(AdminAccessLevel::ReadOnly) => "admin_ro".to_string(),
         Some(AdminAccessLevel::ReadWrite) => "admin_rw".to_string(),
-        None if has_admin_session_cookie(req) => "admin_session".to_string(),
-        None => "-".to_string(),
+        None => match parse_cookie(req, ADMIN_SESSION_COOKIE_NAME) {
+            Some(session_id) => load_session_record(store, &session_id)
+                .and_then(|record| record.username)
+                .map(|username| account_actor_label(&username))
+                .unwrap_or_else(|| "admin_session".to_string()),
+            None => "-".to_string(),
+        },
     }
 }
 
@@ -257,13 +360,35 @@ fn load_session_record<S: KeyValueStore>(
 }
 
 pub fn authenticate_admin<S: KeyValueStore>(req: &Request, store: &S) -> AdminAuthResult {
-    if let Some(access) = bearer_access_level(req) {
+    if let Some(access) = bearer_api_key_access_level(req) {
         return AdminAuthResult {
             method: Some(AdminAuthMethod::BearerToken),
             access: Some(access),
             csrf_token: None,
             session_id: None,
             session_expires_at: None,
+            role: Some(legacy_bearer_role(access)),
+            username: None,
+            token: None,
+        };
+    }
+    // Verify a scoped token once: the same record decides access and is recorded as used.
+    if let Some(token) = bearer_admin_api_token(req, store) {
+        super::tokens::record_admin_api_token_use(
+            store,
+            &token,
+            crate::extract_client_ip(req).as_str(),
+            now_ts(),
+        );
+        return AdminAuthResult {
+            method: Some(AdminAuthMethod::BearerToken),
+            access: Some(admin_api_token_access_level(&token)),
+            csrf_token: None,
+            session_id: None,
+            session_expires_at: None,
+            role: None,
+            username: None,
+            token: Some(AdminTokenGrant::from(&token)),
         };
     }
 
@@ -273,12 +398,25 @@ pub fn authenticate_admin<S: KeyValueStore>(req: &Request, store: &S) -> AdminAu
     let Some(record) = load_session_record(store, &session_id) else {
         return AdminAuthResult::unauthorized();
     };
+    // SSO sessions keep the role the issuer asserted at sign-in. Account sessions re-read the
+    // account so role changes and disabling take effect at once.
+    let role = match (record.sso_role, record.username.as_deref()) {
+        (Some(role), _) => role,
+        (None, Some(username)) => match super::accounts::load_admin_account(store, username) {
+            Some(account) if !account.disabled => account.role,
+            _ => return AdminAuthResult::unauthorized(),
+        },
+        (None, None) => AdminRole::Owner,
+    };
     AdminAuthResult {
         method: Some(AdminAuthMethod::SessionCookie),
-        access: Some(AdminAccessLevel::ReadWrite),
+        access: Some(role.access_level()),
         csrf_token: Some(record.csrf_token),
         session_id: Some(session_id),
         session_expires_at: Some(record.expires_at),
+        role: Some(role),
+        username: record.username,
+        token: None,
     }
 }
 
@@ -290,18 +428,72 @@ pub fn validate_session_csrf(req: &Request, expected_csrf: &str) -> bool {
 }
 
 pub fn create_admin_session<S: KeyValueStore>(store: &S) -> Result<(String, String, u64), ()> {
+    create_session_record(store, None, None, ADMIN_SESSION_TTL_SECONDS)
+}
+
+pub fn create_admin_account_session<S: KeyValueStore>(
+    store: &S,
+    username: &str,
+) -> Result<(String, String, u64), ()> {
+    create_session_record(store, Some(username.to_string()), None, ADMIN_SESSION_TTL_SECONDS)
+}
+
+/// Opens a session for an identity asserted by the OIDC issuer, pinned to the mapped role for
+/// the shorter SSO session lifetime.
+pub fn create_admin_sso_session<S: KeyValueStore>(
+    store: &S,
+    username: &str,
+    role: AdminRole,
+) -> Result<(String, String, u64), ()> {
+    create_session_record(
+        store,
+        Some(username.to_string()),
+        Some(role),
+        ADMIN_SSO_SESSION_TTL_SECONDS,
+    )
+}
+
+fn create_session_record<S: KeyValueStore>(
+    store: &S,
+    username: Option<String>,
+    sso_role: Option<AdminRole>,
+    ttl_seconds: u64,
+) -> Result<(String, String, u64), ()> {
     let session_id = random_hex(32);
     let csrf_token = random_hex(16);
-    let expires_at = now_ts().saturating_add(ADMIN_SESSION_TTL_SECONDS);
+    let expires_at = now_ts().saturating_add(ttl_seconds);
     let record = AdminSessionRecord {
         csrf_token: csrf_token.clone(),
         expires_at,
+        username,
+        sso_role,
+        step_up_until: None,
     };
     let value = serde_json::to_vec(&record).map_err(|_| ())?;
     store.set(&session_store_key(&session_id), &value)?;
     Ok((session_id, csrf_token, expires_at))
 }
 
+/// True while the session's last second-factor check still covers sensitive writes.
+pub fn admin_session_step_up_active<S: KeyValueStore>(store: &S, session_id: &str) -> bool {
+    load_session_record(store, session_id)
+        .and_then(|record| record.step_up_until)
+        .map(|until| until > now_ts())
+        .unwrap_or(false)
+}
+
+/// Records a passed second-factor check on the session and returns when it lapses.
+pub fn mark_admin_session_step_up<S: KeyValueStore>(store: &S, session_id: &str) -> Result<u64, ()> {
+    let mut record = load_session_record(store, session_id).ok_or(())?;
+    let until = now_ts()
+        .saturating_add(ADMIN_STEP_UP_TTL_SECONDS)
+        .min(record.expires_at);
+    record.step_up_until = Some(until);
+    let value = serde_json::to_vec(&record).map_err(|_| ())?;
+    store.set(&session_store_key(session_id), &value)?;
+    Ok(until)
+}
+
 pub fn clear_admin_session<S: KeyValueStore>(store: &S, req: &Request) -> Result<(), ()> {
     if let Some(session_id) = parse_cookie(req, ADMIN_SESSION_COOKIE_NAME) {
         store.delete(&session_store_key(&session_id))?;
@@ -317,6 +509,10 @@ pub fn admin_session_ttl_seconds() -> u64 {
     ADMIN_SESSION_TTL_SECONDS
 }
 
+pub fn admin_sso_session_ttl_seconds() -> u64 {
+    ADMIN_SSO_SESSION_TTL_SECONDS
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AdminAuthFailureScope {
     Login,
@@ -405,7 +601,7 @@ pub fn is_internal_adversary_sim_supervisor_request(req: &Request) -> bool {
         return false;
     }
 
-    if bearer_access_level(req) != Some(AdminAccessLevel::ReadWrite) {
+    if bearer_api_key_access_level(req) != Some(AdminAccessLevel::ReadWrite) {
         return false;
     }
 
@@ -431,7 +627,33 @@ pub fn is_internal_oversight_supervisor_request(req: &Request) -> bool {
         return false;
     }
 
-    if bearer_access_level(req) != Some(AdminAccessLevel::ReadWrite) {
+    if bearer_api_key_access_level(req) != Some(AdminAccessLevel::ReadWrite) {
+        return false;
+    }
+
+    if !crate::forwarded_ip_trusted(req) || !crate::request_is_https(req) {
+        return false;
+    }
+
+    req.header("x-forwarded-for")
+        .and_then(|value| value.as_str())
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .map(|value| value == "127.0.0.1" || value == "::1")
+        .unwrap_or(false)
+}
+
+pub fn is_internal_alert_rules_supervisor_request(req: &Request) -> bool {
+    let marker = req
+        .header("x-shuma-internal-supervisor")
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .unwrap_or("");
+    if marker != "alert-rules" {
+        return false;
+    }
+
+    if bearer_api_key_access_level(req) != Some(AdminAccessLevel::ReadWrite) {
         return false;
     }
 
@@ -476,6 +698,12 @@ pub fn is_internal_adversary_sim_beat_request(req: &Request) -> bool {
     is_internal_adversary_sim_supervisor_request(req) || is_internal_adversary_sim_edge_cron_request(req)
 }
 
+/// Scheduled alert evaluation comes from the host supervisor loop or, on edge deployments, from
+/// a cron job carrying the shared edge cron secret.
+pub fn is_internal_alert_rules_evaluate_request(req: &Request) -> bool {
+    is_internal_alert_rules_supervisor_request(req) || is_internal_adversary_sim_edge_cron_request(req)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -564,7 +792,10 @@ mod tests {
 
         assert!(is_bearer_authorized(&req));
         assert!(!verify_admin_api_key_candidate("test-readonly-key"));
-        assert_eq!(bearer_access_level(&req), Some(AdminAccessLevel::ReadOnly));
+        assert_eq!(
+            authenticate_admin(&req, &store).access,
+            Some(AdminAccessLevel::ReadOnly)
+        );
 
         let auth = authenticate_admin(&req, &store);
         assert_eq!(auth.method, Some(AdminAuthMethod::BearerToken));
@@ -572,7 +803,7 @@ mod tests {
         assert!(!auth.is_write_authorized());
         assert_eq!(auth.access_label(), "read_only");
         assert_eq!(auth.audit_actor_label(), "admin_bearer_ro");
-        assert_eq!(get_admin_id(&req), "admin_ro");
+        assert_eq!(get_admin_id(&req, &store), "admin_ro");
     }
 
     #[test]
@@ -583,13 +814,63 @@ mod tests {
         let req = request_with_auth(Some("Bearer test-admin-key"));
         let store = MockStore::default();
 
-        assert_eq!(bearer_access_level(&req), Some(AdminAccessLevel::ReadWrite));
+        assert_eq!(
+            authenticate_admin(&req, &store).access,
+            Some(AdminAccessLevel::ReadWrite)
+        );
         let auth = authenticate_admin(&req, &store);
         assert_eq!(auth.access, Some(AdminAccessLevel::ReadWrite));
         assert!(auth.is_write_authorized());
         assert_eq!(auth.access_label(), "read_write");
         assert_eq!(auth.audit_actor_label(), "admin_bearer_rw");
-        assert_eq!(get_admin_id(&req), "admin_rw");
+        assert_eq!(get_admin_id(&req, &store), "admin_rw");
+    }
+
+    #[test]
+    fn scoped_api_token_authenticates_without_role_and_tracks_last_use() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "test-admin-key");
+        let store = MockStore::default();
+        let (token, plaintext) = crate::admin::tokens::create_admin_api_token(
+            &store,
+            crate::admin::tokens::AdminApiTokenCreateRequest {
+                name: "dashboard backend".to_string(),
+                scopes: vec!["monitoring:read".to_string()],
+                expires_in_seconds: Some(3600),
+                ip_allowlist: Vec::new(),
+            },
+            now_ts(),
+        )
+        .expect("token created");
+        let req = request_with_auth(Some(format!("Bearer {}", plaintext).as_str()));
+
+        assert!(is_bearer_authorized(&req));
+        assert_eq!(
+            authenticate_admin(&req, &store).access,
+            Some(AdminAccessLevel::ReadOnly)
+        );
+        let auth = authenticate_admin(&req, &store);
+        assert_eq!(auth.method, Some(AdminAuthMethod::BearerToken));
+        assert_eq!(auth.role, None);
+        assert!(!auth.has_permission(AdminPermission::Read));
+        assert!(auth
+            .token
+            .as_ref()
+            .map(|grant| grant.grants_scope("monitoring:read"))
+            .unwrap_or(false));
+        let expected_actor = format!("token:{}", token.token_id);
+        assert_eq!(auth.audit_actor_label(), expected_actor);
+        assert_eq!(get_admin_id(&req, &store), expected_actor);
+        assert!(
+            crate::admin::tokens::load_admin_api_token(&store, token.token_id.as_str())
+                .and_then(|token| token.last_used_ts)
+                .is_some()
+        );
+
+        crate::admin::tokens::revoke_admin_api_token(&store, token.token_id.as_str(), now_ts())
+            .expect("revoked");
+        assert!(!authenticate_admin(&req, &store).is_authorized());
+        std::env::remove_var("SHUMA_API_KEY");
     }
 
     #[test]
@@ -846,6 +1127,45 @@ mod tests {
         std::env::remove_var("SHUMA_ADVERSARY_SIM_EDGE_CRON_SECRET");
     }
 
+    #[test]
+    fn internal_alert_rules_evaluate_request_accepts_supervisor_marker_or_edge_cron_secret() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "test-admin-key");
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+
+        let supervisor = |marker: &str| {
+            let mut builder = Request::builder();
+            builder
+                .method(Method::Post)
+                .uri("/shuma/internal/alerts/evaluate")
+                .header("authorization", "Bearer test-admin-key")
+                .header("x-shuma-forwarded-secret", "test-forwarded-secret")
+                .header("x-forwarded-proto", "https")
+                .header("x-forwarded-for", "127.0.0.1")
+                .header("x-shuma-internal-supervisor", marker);
+            builder.build()
+        };
+        assert!(is_internal_alert_rules_evaluate_request(&supervisor("alert-rules")));
+        assert!(!is_internal_alert_rules_evaluate_request(&supervisor("oversight-agent")));
+
+        std::env::set_var("SHUMA_GATEWAY_DEPLOYMENT_PROFILE", "edge-fermyon");
+        std::env::set_var("SHUMA_ADVERSARY_SIM_EDGE_CRON_SECRET", "edge-cron-secret");
+        let mut edge_cron = Request::builder();
+        edge_cron
+            .method(Method::Get)
+            .uri("/shuma/internal/alerts/evaluate?edge_cron_secret=edge-cron-secret")
+            .header(
+                "spin-full-url",
+                "https://edge.example.com/shuma/internal/alerts/evaluate?edge_cron_secret=edge-cron-secret",
+            );
+        assert!(is_internal_alert_rules_evaluate_request(&edge_cron.build()));
+
+        std::env::remove_var("SHUMA_GATEWAY_DEPLOYMENT_PROFILE");
+        std::env::remove_var("SHUMA_ADVERSARY_SIM_EDGE_CRON_SECRET");
+        std::env::remove_var("SHUMA_API_KEY");
+        std::env::remove_var("SHUMA_FORWARDED_IP_SECRET");
+    }
+
     #[test]
     fn internal_oversight_supervisor_request_requires_marker_bearer_secret_https_and_loopback() {
         let _lock = crate::test_support::lock_env();
@@ -0,0 +1,85 @@
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::admin::oversight_patch_policy::{
+    propose_botness_weights_patch, OversightPatchPolicyError,
+};
+use crate::observability::request_facts_journal::{
+    adversary_sim_journal_site_id, load_recent_rows, REQUEST_FACTS_JOURNAL_RETENTION_HOURS,
+};
+
+const BOTNESS_CALIBRATION_SCHEMA_VERSION: &str = "botness_calibration_v1";
+const BOTNESS_CALIBRATION_DEFAULT_HOURS: u64 = 24;
+
+pub(crate) fn handle_admin_botness_calibration(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if *req.method() != Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let hours =
+        super::api::query_u64_param(req.query(), "hours", BOTNESS_CALIBRATION_DEFAULT_HOURS)
+            .clamp(1, REQUEST_FACTS_JOURNAL_RETENTION_HOURS);
+    let cfg = match crate::config::Config::load(store, site_id) {
+        Ok(cfg) => cfg,
+        Err(err) => return Response::new(500, err.user_message()),
+    };
+
+    let now = crate::admin::now_ts();
+    let rows = load_recent_rows(store, site_id, now, hours);
+    let adversary_sim_rows =
+        load_recent_rows(store, &adversary_sim_journal_site_id(site_id), now, hours);
+    let report = crate::runtime::botness_calibration::calibrate(
+        &rows,
+        &adversary_sim_rows,
+        &cfg.botness_weights,
+    );
+
+    let (proposal_status, proposal) = match report.proposal_blocker.as_deref() {
+        Some(blocker) => (blocker.to_string(), None),
+        None => {
+            let weights: Vec<(&str, u64)> = report
+                .proposed_weights
+                .iter()
+                .map(|(field, value)| (field.as_str(), *value))
+                .collect();
+            match propose_botness_weights_patch(
+                &cfg,
+                &crate::config::allowed_actions_v1(),
+                &weights,
+                format!(
+                    "Reweight botness signals to the logistic fit over {} labelled requests.",
+                    report.labelled_rows
+                ),
+                report.confidence(),
+            ) {
+                Ok(proposal) => ("proposed".to_string(), Some(proposal)),
+                Err(OversightPatchPolicyError::NoBoundedPatch(_)) => {
+                    ("no_change".to_string(), None)
+                }
+                Err(OversightPatchPolicyError::UnsupportedCandidateFamily(_)) => {
+                    ("controller_family_not_allowed".to_string(), None)
+                }
+                Err(_) => ("invalid_patch".to_string(), None),
+            }
+        }
+    };
+
+    let body = serde_json::to_string(&json!({
+        "schema_version": BOTNESS_CALIBRATION_SCHEMA_VERSION,
+        "window_hours": hours,
+        "journal_sample_percent": cfg.request_facts_journal_sample_percent,
+        "report": report,
+        "proposal_status": proposal_status,
+        "proposal": proposal
+    }))
+    .unwrap_or_else(|_| "{}".to_string());
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
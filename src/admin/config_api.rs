@@ -29,7 +29,7 @@ pub(crate) fn handle_admin_config_export(
             ip: None,
             reason: Some("config_export".to_string()),
             outcome: Some(format!("{} keys", entries.len())),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
 
@@ -90,7 +90,17 @@ pub(crate) fn handle_admin_config_bootstrap(
         return Response::new(400, msg);
     }
 
-    if super::api::persist_site_config(store, site_id, &cfg, &[]).is_err() {
+    let admin_id = crate::admin::auth::get_admin_id(req, store);
+    if super::api::persist_site_config(
+        store,
+        site_id,
+        &cfg,
+        &[],
+        admin_id.as_str(),
+        "config_bootstrap",
+    )
+    .is_err()
+    {
         return Response::new(500, "Key-value store error");
     }
 
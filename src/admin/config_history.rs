@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::challenge::KeyValueStore;
+
+const CONFIG_HISTORY_SCHEMA_VERSION: &str = "config_history_v1";
+const CONFIG_HISTORY_PREFIX: &str = "config_history:v1";
+const CONFIG_HISTORY_MAX_VERSIONS: usize = 50;
+const CONFIG_HISTORY_SUMMARY_MAX_FIELDS: usize = 24;
+const CONFIG_HISTORY_TEXT_MAX_CHARS: usize = 240;
+const CONFIG_HISTORY_BASELINE_REASON: &str = "baseline_before_history";
+
+/// One leaf that differs between two config snapshots. Objects are walked key by key; arrays and
+/// scalars compare as whole values. A missing side means the field was added or removed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ConfigFieldChange {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ConfigVersionSummary {
+    pub version: u64,
+    pub recorded_at_ts: u64,
+    pub author: String,
+    pub reason: String,
+    pub changed_field_count: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_fields: Vec<String>,
+}
+
+/// Full persisted config as written by one config write, with the diff against the version
+/// before it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ConfigVersionRecord {
+    pub schema_version: String,
+    pub version: u64,
+    pub recorded_at_ts: u64,
+    pub author: String,
+    pub reason: String,
+    pub diff: Vec<ConfigFieldChange>,
+    pub config: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ConfigHistoryIndex {
+    schema_version: String,
+    next_version: u64,
+    versions: Vec<ConfigVersionSummary>,
+}
+
+impl Default for ConfigHistoryIndex {
+    fn default() -> Self {
+        Self {
+            schema_version: CONFIG_HISTORY_SCHEMA_VERSION.to_string(),
+            next_version: 1,
+            versions: Vec::new(),
+        }
+    }
+}
+
+fn config_history_index_key(site_id: &str) -> String {
+    format!("{CONFIG_HISTORY_PREFIX}:{site_id}:index")
+}
+
+fn config_history_version_key(site_id: &str, version: u64) -> String {
+    format!("{CONFIG_HISTORY_PREFIX}:{site_id}:{version}")
+}
+
+fn load_config_history_index<S: KeyValueStore>(store: &S, site_id: &str) -> ConfigHistoryIndex {
+    store
+        .get(config_history_index_key(site_id).as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<ConfigHistoryIndex>(raw.as_slice()).ok())
+        .unwrap_or_default()
+}
+
+fn truncate_history_text(value: &str) -> String {
+    let trimmed = value.trim();
+    if trimmed.chars().count() <= CONFIG_HISTORY_TEXT_MAX_CHARS {
+        return trimmed.to_string();
+    }
+    trimmed
+        .chars()
+        .take(CONFIG_HISTORY_TEXT_MAX_CHARS)
+        .collect()
+}
+
+fn collect_config_changes(
+    path: &str,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+    changes: &mut Vec<ConfigFieldChange>,
+) {
+    if let (Some(serde_json::Value::Object(before)), Some(serde_json::Value::Object(after))) =
+        (before, after)
+    {
+        let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+        for key in keys {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            collect_config_changes(child.as_str(), before.get(key), after.get(key), changes);
+        }
+        return;
+    }
+    if before != after {
+        changes.push(ConfigFieldChange {
+            path: path.to_string(),
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+}
+
+/// Field-level diff between two persisted config snapshots, sorted by dotted path.
+pub(crate) fn diff_config_snapshots(
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+) -> Vec<ConfigFieldChange> {
+    let mut changes = Vec::new();
+    collect_config_changes("", Some(before), Some(after), &mut changes);
+    changes
+}
+
+fn write_config_version<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    index: &mut ConfigHistoryIndex,
+    record: &ConfigVersionRecord,
+) -> Result<(), ()> {
+    let payload = serde_json::to_vec(record).map_err(|_| ())?;
+    store.set(
+        config_history_version_key(site_id, record.version).as_str(),
+        payload.as_slice(),
+    )?;
+    index.versions.push(ConfigVersionSummary {
+        version: record.version,
+        recorded_at_ts: record.recorded_at_ts,
+        author: record.author.clone(),
+        reason: record.reason.clone(),
+        changed_field_count: record.diff.len(),
+        changed_fields: record
+            .diff
+            .iter()
+            .take(CONFIG_HISTORY_SUMMARY_MAX_FIELDS)
+            .map(|change| change.path.clone())
+            .collect(),
+    });
+    index.next_version = record.version.saturating_add(1);
+    Ok(())
+}
+
+/// Record a config write as the next version. `previous` is the persisted config the write
+/// replaced; it seeds a baseline version the first time history is recorded so the pre-history
+/// config can still be rolled back to.
+pub(crate) fn record_config_version<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    previous: Option<&serde_json::Value>,
+    current: &serde_json::Value,
+    author: &str,
+    reason: &str,
+    recorded_at_ts: u64,
+) -> Result<u64, ()> {
+    let mut index = load_config_history_index(store, site_id);
+    let mut last_config = index
+        .versions
+        .last()
+        .and_then(|summary| load_config_version(store, site_id, summary.version))
+        .map(|record| record.config);
+    if last_config.is_none() {
+        if let Some(previous) = previous.filter(|previous| *previous != current) {
+            let baseline = ConfigVersionRecord {
+                schema_version: CONFIG_HISTORY_SCHEMA_VERSION.to_string(),
+                version: index.next_version,
+                recorded_at_ts,
+                author: "-".to_string(),
+                reason: CONFIG_HISTORY_BASELINE_REASON.to_string(),
+                diff: Vec::new(),
+                config: previous.clone(),
+            };
+            write_config_version(store, site_id, &mut index, &baseline)?;
+            last_config = Some(previous.clone());
+        }
+    }
+
+    let record = ConfigVersionRecord {
+        schema_version: CONFIG_HISTORY_SCHEMA_VERSION.to_string(),
+        version: index.next_version,
+        recorded_at_ts,
+        author: truncate_history_text(author),
+        reason: truncate_history_text(reason),
+        diff: last_config
+            .as_ref()
+            .map(|last| diff_config_snapshots(last, current))
+            .unwrap_or_default(),
+        config: current.clone(),
+    };
+    write_config_version(store, site_id, &mut index, &record)?;
+
+    let overflow = index
+        .versions
+        .len()
+        .saturating_sub(CONFIG_HISTORY_MAX_VERSIONS);
+    for pruned in index.versions.drain(..overflow) {
+        let _ = store.delete(config_history_version_key(site_id, pruned.version).as_str());
+    }
+    index.schema_version = CONFIG_HISTORY_SCHEMA_VERSION.to_string();
+    let payload = serde_json::to_vec(&index).map_err(|_| ())?;
+    store.set(
+        config_history_index_key(site_id).as_str(),
+        payload.as_slice(),
+    )?;
+    Ok(record.version)
+}
+
+/// Record a write of `config:{site_id}` from the persisted bytes on either side of it. History
+/// is best effort: a failure is logged and never fails the config write itself.
+pub(crate) fn record_persisted_config_write<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    previous: Option<&[u8]>,
+    written: &[u8],
+    author: &str,
+    reason: &str,
+    recorded_at_ts: u64,
+) {
+    let Ok(current) = serde_json::from_slice::<serde_json::Value>(written) else {
+        return;
+    };
+    let previous = previous.and_then(|raw| serde_json::from_slice::<serde_json::Value>(raw).ok());
+    if record_config_version(
+        store,
+        site_id,
+        previous.as_ref(),
+        &current,
+        author,
+        reason,
+        recorded_at_ts,
+    )
+    .is_err()
+    {
+        eprintln!(
+            "[config-history] failed recording config version site={}",
+            site_id
+        );
+    }
+}
+
+/// Retained version summaries, newest first.
+pub(crate) fn list_config_versions<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+) -> Vec<ConfigVersionSummary> {
+    let mut versions = load_config_history_index(store, site_id).versions;
+    versions.reverse();
+    versions
+}
+
+pub(crate) fn latest_config_version<S: KeyValueStore>(store: &S, site_id: &str) -> Option<u64> {
+    load_config_history_index(store, site_id)
+        .versions
+        .last()
+        .map(|summary| summary.version)
+}
+
+pub(crate) fn load_config_version<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    version: u64,
+) -> Option<ConfigVersionRecord> {
+    store
+        .get(config_history_version_key(site_id, version).as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<ConfigVersionRecord>(raw.as_slice()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStore;
+    use serde_json::json;
+
+    #[test]
+    fn versions_record_field_diffs_and_seed_a_baseline_from_the_replaced_config() {
+        let store = InMemoryStore::default();
+        let original =
+            json!({"rate_limit": 80, "geo_block": [], "botness_weights": {"geo_risk": 2}});
+        let updated =
+            json!({"rate_limit": 120, "geo_block": ["BR"], "botness_weights": {"geo_risk": 2}});
+
+        let version = record_config_version(
+            &store,
+            "default",
+            Some(&original),
+            &updated,
+            "user:ops",
+            "config_patch",
+            100,
+        )
+        .unwrap();
+        assert_eq!(version, 2);
+        let baseline = load_config_version(&store, "default", 1).unwrap();
+        assert_eq!(baseline.reason, CONFIG_HISTORY_BASELINE_REASON);
+        assert_eq!(baseline.config, original);
+
+        let record = load_config_version(&store, "default", 2).unwrap();
+        assert_eq!(record.author, "user:ops");
+        let paths: Vec<&str> = record
+            .diff
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["geo_block", "rate_limit"]);
+        assert_eq!(record.diff[1].before, Some(json!(80)));
+        assert_eq!(record.diff[1].after, Some(json!(120)));
+
+        let nested =
+            json!({"rate_limit": 120, "geo_block": ["BR"], "botness_weights": {"geo_risk": 4}});
+        record_config_version(
+            &store,
+            "default",
+            Some(&updated),
+            &nested,
+            "user:ops",
+            "config_patch",
+            200,
+        )
+        .unwrap();
+        let listed = list_config_versions(&store, "default");
+        assert_eq!(
+            listed
+                .iter()
+                .map(|summary| summary.version)
+                .collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(
+            listed[0].changed_fields,
+            vec!["botness_weights.geo_risk".to_string()]
+        );
+    }
+
+    #[test]
+    fn history_keeps_only_the_newest_versions() {
+        let store = InMemoryStore::default();
+        for step in 0..(CONFIG_HISTORY_MAX_VERSIONS as u64 + 5) {
+            let cfg = json!({ "rate_limit": step });
+            record_config_version(
+                &store,
+                "default",
+                None,
+                &cfg,
+                "user:ops",
+                "config_patch",
+                step,
+            )
+            .unwrap();
+        }
+        let listed = list_config_versions(&store, "default");
+        assert_eq!(listed.len(), CONFIG_HISTORY_MAX_VERSIONS);
+        assert_eq!(latest_config_version(&store, "default"), Some(55));
+        assert!(load_config_version(&store, "default", 5).is_none());
+        assert!(load_config_version(&store, "default", 6).is_some());
+    }
+}
@@ -0,0 +1,209 @@
+use serde::Deserialize;
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use super::config_history::{
+    diff_config_snapshots, latest_config_version, list_config_versions, load_config_version,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigRollbackRequest {
+    version: u64,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> Response {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string()))
+        .build()
+}
+
+fn version_query_param(req: &Request, key: &str) -> Result<Option<u64>, Response> {
+    match crate::request_validation::query_param(req.query(), key) {
+        None => Ok(None),
+        Some(raw) => raw
+            .trim()
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|_| Response::new(400, format!("{key} must be a version number"))),
+    }
+}
+
+/// GET lists retained versions, newest first; `?version=N` returns one full snapshot and its diff.
+pub(crate) fn handle_admin_config_history(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if *req.method() != Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let version = match version_query_param(req, "version") {
+        Ok(version) => version,
+        Err(resp) => return resp,
+    };
+    match version {
+        Some(version) => match load_config_version(store, site_id, version) {
+            Some(record) => json_response(200, json!(record)),
+            None => Response::new(404, "Config version not found"),
+        },
+        None => json_response(
+            200,
+            json!({
+                "latest_version": latest_config_version(store, site_id),
+                "versions": list_config_versions(store, site_id)
+            }),
+        ),
+    }
+}
+
+/// GET `?from=A&to=B` diffs two retained versions; `to` defaults to the latest version.
+pub(crate) fn handle_admin_config_diff(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if *req.method() != Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let from = match version_query_param(req, "from") {
+        Ok(Some(from)) => from,
+        Ok(None) => return Response::new(400, "from is required"),
+        Err(resp) => return resp,
+    };
+    let to = match version_query_param(req, "to") {
+        Ok(Some(to)) => to,
+        Ok(None) => match latest_config_version(store, site_id) {
+            Some(latest) => latest,
+            None => return Response::new(404, "Config version not found"),
+        },
+        Err(resp) => return resp,
+    };
+    let (Some(before), Some(after)) = (
+        load_config_version(store, site_id, from),
+        load_config_version(store, site_id, to),
+    ) else {
+        return Response::new(404, "Config version not found");
+    };
+    let changes = diff_config_snapshots(&before.config, &after.config);
+    json_response(
+        200,
+        json!({
+            "from": from,
+            "to": to,
+            "changed_field_count": changes.len(),
+            "changes": changes
+        }),
+    )
+}
+
+/// POST `{"version": N}` restores a retained snapshot. The snapshot goes through the persisted
+/// config validation every config write ends with, and lands in one KV write as a new version.
+pub(crate) fn handle_admin_config_rollback(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if *req.method() != Method::Post {
+        return Response::new(405, "Method Not Allowed");
+    }
+    if !crate::config::admin_config_write_enabled() {
+        return Response::new(
+            403,
+            "Config updates are disabled when SHUMA_ADMIN_CONFIG_WRITE_ENABLED=false",
+        );
+    }
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid rollback payload: {}", err)),
+    };
+    let request = match serde_json::from_value::<ConfigRollbackRequest>(payload) {
+        Ok(request) => request,
+        Err(err) => return Response::new(400, format!("Invalid rollback payload: {}", err)),
+    };
+    let Some(record) = load_config_version(store, site_id, request.version) else {
+        return Response::new(404, "Config version not found");
+    };
+
+    let current_cfg = match crate::config::Config::load(store, site_id) {
+        Ok(cfg) => cfg,
+        Err(crate::config::ConfigLoadError::MissingConfig) => {
+            crate::config::default_seeded_config()
+        }
+        Err(err) => return Response::new(500, err.user_message()),
+    };
+    let mut restored = match serde_json::from_value::<crate::config::Config>(record.config.clone())
+    {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            return Response::new(
+                400,
+                format!(
+                    "Config version {} is not restorable: {}",
+                    record.version, err
+                ),
+            )
+        }
+    };
+    if let Err(msg) = crate::config::normalize_persisted_config(&mut restored) {
+        return Response::new(400, msg);
+    }
+
+    let current_value = serde_json::to_value(&current_cfg).unwrap_or_default();
+    let restored_value = serde_json::to_value(&restored).unwrap_or_default();
+    let rollback_patch = match (current_value.as_object(), restored_value.as_object()) {
+        (Some(current), Some(target)) => serde_json::Value::Object(
+            target
+                .iter()
+                .filter(|(key, value)| current.get(key.as_str()) != Some(*value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        _ => json!({}),
+    };
+    let admin_id = crate::admin::auth::get_admin_id(req, store);
+    let reason = match request.reason.as_deref().map(str::trim) {
+        Some(note) if !note.is_empty() => format!("rollback_to_v{}: {}", record.version, note),
+        _ => format!("rollback_to_v{}", record.version),
+    };
+    let recent_change_rows =
+        super::recent_changes_ledger::operator_snapshot_config_patch_recent_change_row(
+            &current_cfg,
+            &restored,
+            &rollback_patch,
+            admin_id.as_str(),
+            crate::admin::now_ts(),
+        )
+        .into_iter()
+        .collect::<Vec<_>>();
+    if super::api::persist_site_config(
+        store,
+        site_id,
+        &restored,
+        recent_change_rows.as_slice(),
+        admin_id.as_str(),
+        reason.as_str(),
+    )
+    .is_err()
+    {
+        return Response::new(500, "Key-value store error");
+    }
+
+    json_response(
+        200,
+        json!({
+            "status": "rolled_back",
+            "restored_version": record.version,
+            "version": latest_config_version(store, site_id),
+            "config": super::api::admin_config_settings_payload(&restored)
+        }),
+    )
+}
@@ -128,7 +128,7 @@ where
                         "suspicious_forwarded_bytes",
                         "suspicious_forwarded_requests",
                     ],
-                    crate::admin::auth::get_admin_id(req).as_str(),
+                    crate::admin::auth::get_admin_id(req, store).as_str(),
                     format!(
                         "operator maze seed sources updated: {} sources",
                         sources.len()
@@ -145,7 +145,7 @@ where
                     ip: None,
                     reason: Some("maze_seed_sources_update".to_string()),
                     outcome: Some(format!("sources={}", sources.len())),
-                    admin: Some(crate::admin::auth::get_admin_id(req)),
+                    admin: Some(crate::admin::auth::get_admin_id(req, store)),
                 },
             );
             let body = serde_json::to_string(&json!({
@@ -240,7 +240,7 @@ where
                 "suspicious_forwarded_bytes",
                 "suspicious_forwarded_requests",
             ],
-            crate::admin::auth::get_admin_id(req).as_str(),
+            crate::admin::auth::get_admin_id(req, store).as_str(),
             format!(
                 "maze seed corpus refreshed: provider={} version={} sources={}",
                 refreshed.provider, refreshed.version, refreshed.source_count
@@ -263,7 +263,7 @@ where
                 refreshed.terms.len(),
                 refreshed.source_count
             )),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
     let body = serde_json::to_string(&json!({
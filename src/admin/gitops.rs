@@ -0,0 +1,503 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+
+use super::config_history::{diff_config_snapshots, ConfigFieldChange};
+use crate::challenge::KeyValueStore;
+use crate::config::{controller_mutability_ring_for_admin_config_path, ControllerMutabilityRing};
+use crate::enforcement::ban::BanEntry;
+
+pub(crate) const DESIRED_STATE_SCHEMA_VERSION: &str = "desired_state_v1";
+const GITOPS_PLAN_SCHEMA_VERSION: &str = "gitops_plan_v1";
+const GITOPS_APPLIED_SCHEMA_VERSION: &str = "gitops_applied_v1";
+const GITOPS_PREFIX: &str = "gitops:v1";
+pub(crate) const GITOPS_MANUAL_BAN_REASON: &str = "manual_ban";
+const GITOPS_MAX_DESIRED_BANS: usize = 1_000;
+// adversary_sim_enabled is lifecycle state with its own control endpoint, not desired policy.
+const GITOPS_FORBIDDEN_CONFIG_KEYS: &[&str] = &["adversary_sim_enabled"];
+// `GET /shuma/admin/config` reports the robots AI toggles under these names.
+const GITOPS_CONFIG_KEY_ALIASES: &[(&str, &str)] = &[
+    ("ai_policy_block_training", "robots_block_ai_training"),
+    ("ai_policy_block_search", "robots_block_ai_search"),
+    (
+        "ai_policy_allow_search_engines",
+        "robots_allow_search_engines",
+    ),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct DesiredBan {
+    pub ip: String,
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+/// Declarative desired state for one site. `config` names only the fields it manages; fields it
+/// leaves out keep their live values. `bans`, when present, is the complete set of manual bans.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct DesiredStateDocument {
+    #[serde(default)]
+    pub schema_version: Option<String>,
+    #[serde(default)]
+    pub config: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    pub bans: Option<Vec<DesiredBan>>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct GitopsConfigChange {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+    pub ring: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct GitopsBanAddition {
+    pub ip: String,
+    pub duration: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct GitopsPlan {
+    pub schema_version: String,
+    pub plan_id: String,
+    pub in_sync: bool,
+    pub applicable: bool,
+    pub config_changes: Vec<GitopsConfigChange>,
+    pub ban_additions: Vec<GitopsBanAddition>,
+    pub ban_removals: Vec<String>,
+    /// Changed paths the controller mutability policy does not classify; these never apply.
+    pub blocked_paths: Vec<String>,
+    /// Changed paths in the `never` ring; apply needs `acknowledge_never_ring`.
+    pub never_ring_paths: Vec<String>,
+    pub drift: Vec<ConfigFieldChange>,
+}
+
+/// A plan together with the state it would write.
+pub(crate) struct PlannedDesiredState {
+    pub plan: GitopsPlan,
+    pub patch: serde_json::Value,
+    pub target: crate::config::Config,
+    pub desired_bans: Option<Vec<GitopsBanAddition>>,
+}
+
+/// What the last apply wrote for the fields and bans it manages; drift is measured against it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct GitopsAppliedState {
+    pub schema_version: String,
+    pub applied_at_ts: u64,
+    pub author: String,
+    pub plan_id: String,
+    pub config: serde_json::Map<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bans: Option<Vec<String>>,
+}
+
+fn gitops_applied_key(site_id: &str) -> String {
+    format!("{GITOPS_PREFIX}:{site_id}:applied")
+}
+
+pub(crate) fn load_applied_state<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+) -> Option<GitopsAppliedState> {
+    store
+        .get(gitops_applied_key(site_id).as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<GitopsAppliedState>(raw.as_slice()).ok())
+}
+
+pub(crate) fn save_applied_state<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    state: &GitopsAppliedState,
+) -> Result<(), ()> {
+    let payload = serde_json::to_vec(state).map_err(|_| ())?;
+    store.set(gitops_applied_key(site_id).as_str(), payload.as_slice())
+}
+
+fn manual_ban_ips(live_bans: &[(String, BanEntry)]) -> BTreeSet<String> {
+    live_bans
+        .iter()
+        .filter(|(_, entry)| entry.reason == GITOPS_MANUAL_BAN_REASON)
+        .map(|(ip, _)| ip.clone())
+        .collect()
+}
+
+fn config_object(
+    cfg: &crate::config::Config,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    match serde_json::to_value(cfg) {
+        Ok(serde_json::Value::Object(object)) => Ok(object),
+        _ => Err("Unable to serialize live config".to_string()),
+    }
+}
+
+fn normalized_desired_config(
+    desired: &serde_json::Map<String, serde_json::Value>,
+    known: &serde_json::Map<String, serde_json::Value>,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let mut patch = serde_json::Map::new();
+    for (key, value) in desired {
+        let key = GITOPS_CONFIG_KEY_ALIASES
+            .iter()
+            .find(|(alias, _)| alias == key)
+            .map_or(key.as_str(), |(_, canonical)| canonical);
+        if GITOPS_FORBIDDEN_CONFIG_KEYS.contains(&key) || !known.contains_key(key) {
+            return Err(format!("config.{key} is not a declarative config field"));
+        }
+        if patch.insert(key.to_string(), value.clone()).is_some() {
+            return Err(format!("config.{key} is set more than once"));
+        }
+    }
+    Ok(patch)
+}
+
+/// The mutability policy names paths as `GET /shuma/admin/config` does.
+fn admin_config_policy_path(path: &str) -> String {
+    let (head, rest) = path
+        .split_once('.')
+        .map_or((path, None), |(head, rest)| (head, Some(rest)));
+    let head = GITOPS_CONFIG_KEY_ALIASES
+        .iter()
+        .find(|(_, canonical)| *canonical == head)
+        .map_or(head, |(alias, _)| alias);
+    match rest {
+        Some(rest) => format!("{head}.{rest}"),
+        None => head.to_string(),
+    }
+}
+
+fn resolve_desired_bans(
+    bans: &[DesiredBan],
+    default_duration: u64,
+    duration_bounds: (u64, u64),
+) -> Result<Vec<GitopsBanAddition>, String> {
+    if bans.len() > GITOPS_MAX_DESIRED_BANS {
+        return Err(format!(
+            "bans supports at most {GITOPS_MAX_DESIRED_BANS} entries"
+        ));
+    }
+    let mut seen = BTreeSet::new();
+    let mut resolved = Vec::with_capacity(bans.len());
+    for (index, ban) in bans.iter().enumerate() {
+        let ip = crate::request_validation::parse_ip_addr(ban.ip.as_str())
+            .ok_or_else(|| format!("bans[{index}].ip is not a valid IP address"))?;
+        if !seen.insert(ip.clone()) {
+            return Err(format!("bans[{index}].ip '{ip}' is duplicated"));
+        }
+        resolved.push(GitopsBanAddition {
+            ip,
+            duration: ban
+                .duration
+                .unwrap_or(default_duration)
+                .clamp(duration_bounds.0, duration_bounds.1),
+        });
+    }
+    Ok(resolved)
+}
+
+/// Field changes since the last apply, limited to what that apply manages. `before` is the
+/// applied value and `after` the live one; a `bans` entry compares managed manual-ban sets.
+pub(crate) fn detect_drift(
+    applied: &GitopsAppliedState,
+    live: &serde_json::Map<String, serde_json::Value>,
+    live_bans: &[(String, BanEntry)],
+) -> Vec<ConfigFieldChange> {
+    let live_managed: serde_json::Map<String, serde_json::Value> = applied
+        .config
+        .keys()
+        .filter_map(|key| live.get(key).map(|value| (key.clone(), value.clone())))
+        .collect();
+    let mut drift = diff_config_snapshots(
+        &serde_json::Value::Object(applied.config.clone()),
+        &serde_json::Value::Object(live_managed),
+    );
+    if let Some(applied_bans) = applied.bans.as_ref() {
+        let applied_bans: BTreeSet<String> = applied_bans.iter().cloned().collect();
+        let live_manual = manual_ban_ips(live_bans);
+        if applied_bans != live_manual {
+            drift.push(ConfigFieldChange {
+                path: "bans".to_string(),
+                before: Some(serde_json::json!(applied_bans)),
+                after: Some(serde_json::json!(live_manual)),
+            });
+        }
+    }
+    drift
+}
+
+fn plan_fingerprint(
+    live: &serde_json::Map<String, serde_json::Value>,
+    live_bans: &[(String, BanEntry)],
+    patch: &serde_json::Value,
+    desired_bans: Option<&[GitopsBanAddition]>,
+) -> String {
+    let mut live_ban_rows: Vec<(&str, &str)> = live_bans
+        .iter()
+        .map(|(ip, entry)| (ip.as_str(), entry.reason.as_str()))
+        .collect();
+    live_ban_rows.sort_unstable();
+    let canonical = serde_json::json!({
+        "live": live,
+        "live_bans": live_ban_rows,
+        "patch": patch,
+        "bans": desired_bans
+    });
+    let digest = format!("{:x}", Sha256::digest(canonical.to_string().as_bytes()));
+    digest[..32].to_string()
+}
+
+/// Diff a desired-state document against live config and bans. Invalid documents are errors;
+/// changes the mutability policy refuses are reported in the plan and block apply.
+pub(crate) fn plan_desired_state(
+    live_cfg: &crate::config::Config,
+    live_bans: &[(String, BanEntry)],
+    desired: &DesiredStateDocument,
+    applied: Option<&GitopsAppliedState>,
+    ban_duration_bounds: (u64, u64),
+) -> Result<PlannedDesiredState, String> {
+    if let Some(version) = desired.schema_version.as_deref() {
+        if version != DESIRED_STATE_SCHEMA_VERSION {
+            return Err(format!(
+                "schema_version must be {DESIRED_STATE_SCHEMA_VERSION}"
+            ));
+        }
+    }
+    let live = config_object(live_cfg)?;
+    let patch = serde_json::Value::Object(normalized_desired_config(&desired.config, &live)?);
+    let target = crate::config::apply_persisted_patch(live_cfg, &patch)?;
+    let target_object = config_object(&target)?;
+
+    let mut config_changes = Vec::new();
+    let mut blocked_paths = Vec::new();
+    let mut never_ring_paths = Vec::new();
+    for change in diff_config_snapshots(
+        &serde_json::Value::Object(live.clone()),
+        &serde_json::Value::Object(target_object),
+    ) {
+        let ring = controller_mutability_ring_for_admin_config_path(
+            admin_config_policy_path(change.path.as_str()).as_str(),
+        );
+        match ring {
+            None => blocked_paths.push(change.path.clone()),
+            Some(ControllerMutabilityRing::Never) => never_ring_paths.push(change.path.clone()),
+            Some(_) => {}
+        }
+        config_changes.push(GitopsConfigChange {
+            path: change.path,
+            before: change.before,
+            after: change.after,
+            ring: ring.map(|ring| ring.as_str().to_string()),
+        });
+    }
+
+    let desired_bans = desired
+        .bans
+        .as_deref()
+        .map(|bans| {
+            resolve_desired_bans(
+                bans,
+                live_cfg.get_ban_duration("admin"),
+                ban_duration_bounds,
+            )
+        })
+        .transpose()?;
+    let (ban_additions, ban_removals) = match desired_bans.as_ref() {
+        Some(desired_bans) => {
+            let banned: BTreeSet<&str> = live_bans.iter().map(|(ip, _)| ip.as_str()).collect();
+            let wanted: BTreeSet<&str> = desired_bans.iter().map(|ban| ban.ip.as_str()).collect();
+            (
+                desired_bans
+                    .iter()
+                    .filter(|ban| !banned.contains(ban.ip.as_str()))
+                    .cloned()
+                    .collect::<Vec<_>>(),
+                manual_ban_ips(live_bans)
+                    .into_iter()
+                    .filter(|ip| !wanted.contains(ip.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let plan = GitopsPlan {
+        schema_version: GITOPS_PLAN_SCHEMA_VERSION.to_string(),
+        plan_id: plan_fingerprint(&live, live_bans, &patch, desired_bans.as_deref()),
+        in_sync: config_changes.is_empty() && ban_additions.is_empty() && ban_removals.is_empty(),
+        applicable: blocked_paths.is_empty(),
+        config_changes,
+        ban_additions,
+        ban_removals,
+        blocked_paths,
+        never_ring_paths,
+        drift: applied
+            .map(|applied| detect_drift(applied, &live, live_bans))
+            .unwrap_or_default(),
+    };
+    Ok(PlannedDesiredState {
+        plan,
+        patch,
+        target,
+        desired_bans,
+    })
+}
+
+/// Applied-state record for a plan: the normalized values now live for every managed field.
+pub(crate) fn applied_state_for(
+    planned: &PlannedDesiredState,
+    author: &str,
+    applied_at_ts: u64,
+) -> Result<GitopsAppliedState, String> {
+    let target = config_object(&planned.target)?;
+    let config = planned
+        .patch
+        .as_object()
+        .map(|patch| {
+            patch
+                .keys()
+                .filter_map(|key| target.get(key).map(|value| (key.clone(), value.clone())))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(GitopsAppliedState {
+        schema_version: GITOPS_APPLIED_SCHEMA_VERSION.to_string(),
+        applied_at_ts,
+        author: author.to_string(),
+        plan_id: planned.plan.plan_id.clone(),
+        config,
+        bans: planned.desired_bans.as_ref().map(|bans| {
+            let mut ips: Vec<String> = bans.iter().map(|ban| ban.ip.clone()).collect();
+            ips.sort();
+            ips
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ban(reason: &str) -> BanEntry {
+        BanEntry {
+            reason: reason.to_string(),
+            expires: u64::MAX,
+            banned_at: 1,
+            fingerprint: None,
+        }
+    }
+
+    fn document(value: serde_json::Value) -> DesiredStateDocument {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn plan_diffs_config_and_manual_bans_and_classifies_paths_by_mutability_ring() {
+        let live = crate::config::defaults().clone();
+        let live_bans = vec![
+            ("198.51.100.7".to_string(), ban("manual_ban")),
+            ("198.51.100.8".to_string(), ban("honeypot")),
+        ];
+        let desired = document(json!({
+            "schema_version": "desired_state_v1",
+            "config": {"rate_limit": live.rate_limit + 5, "geo_block": ["BR"], "ai_policy_block_search": !live.robots_block_ai_search},
+            "bans": [{"ip": "203.0.113.9", "duration": 600}, {"ip": "198.51.100.8"}]
+        }));
+
+        let planned = plan_desired_state(&live, &live_bans, &desired, None, (60, 86_400)).unwrap();
+        let plan = &planned.plan;
+        let paths: Vec<&str> = plan
+            .config_changes
+            .iter()
+            .map(|c| c.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["geo_block", "rate_limit", "robots_block_ai_search"]
+        );
+        assert!(plan.never_ring_paths.contains(&"geo_block".to_string()));
+        assert!(!plan.never_ring_paths.contains(&"rate_limit".to_string()));
+        assert!(plan.applicable);
+        assert!(!plan.in_sync);
+        assert_eq!(
+            plan.ban_additions,
+            vec![GitopsBanAddition {
+                ip: "203.0.113.9".to_string(),
+                duration: 600
+            }]
+        );
+        assert_eq!(plan.ban_removals, vec!["198.51.100.7".to_string()]);
+
+        let again = plan_desired_state(&live, &live_bans, &desired, None, (60, 86_400)).unwrap();
+        assert_eq!(again.plan.plan_id, plan.plan_id);
+        let mut moved = live.clone();
+        moved.rate_limit += 1;
+        let stale = plan_desired_state(&moved, &live_bans, &desired, None, (60, 86_400)).unwrap();
+        assert_ne!(stale.plan.plan_id, plan.plan_id);
+
+        for (bad, expected) in [
+            (
+                json!({"config": {"adversary_sim_enabled": true}}),
+                "not a declarative config field",
+            ),
+            (
+                json!({"config": {"no_such_field": 1}}),
+                "not a declarative config field",
+            ),
+            (json!({"schema_version": "v0"}), "schema_version"),
+            (json!({"bans": [{"ip": "not-an-ip"}]}), "bans[0].ip"),
+        ] {
+            let err = plan_desired_state(&live, &live_bans, &document(bad), None, (60, 86_400))
+                .err()
+                .unwrap();
+            assert!(err.contains(expected), "{err} should mention {expected}");
+        }
+    }
+
+    #[test]
+    fn drift_reports_out_of_band_changes_to_managed_fields_and_bans_only() {
+        let live = crate::config::defaults().clone();
+        let desired = document(json!({
+            "config": {"rate_limit": 77},
+            "bans": [{"ip": "203.0.113.9"}]
+        }));
+        let planned = plan_desired_state(&live, &[], &desired, None, (60, 86_400)).unwrap();
+        let applied = applied_state_for(&planned, "user:ops", 10).unwrap();
+        assert_eq!(applied.config.get("rate_limit"), Some(&json!(77)));
+        assert_eq!(applied.bans, Some(vec!["203.0.113.9".to_string()]));
+
+        let mut live_after = planned.target.clone();
+        let live_bans = vec![("203.0.113.9".to_string(), ban("manual_ban"))];
+        let in_sync = plan_desired_state(
+            &live_after,
+            &live_bans,
+            &desired,
+            Some(&applied),
+            (60, 86_400),
+        )
+        .unwrap();
+        assert!(in_sync.plan.in_sync);
+        assert!(in_sync.plan.drift.is_empty());
+
+        live_after.rate_limit = 500;
+        live_after.js_required_enforced = !live_after.js_required_enforced;
+        let drift = detect_drift(&applied, &config_object(&live_after).unwrap(), &[]);
+        assert_eq!(
+            drift
+                .iter()
+                .map(|change| change.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rate_limit", "bans"]
+        );
+        assert_eq!(drift[0].before, Some(json!(77)));
+        assert_eq!(drift[0].after, Some(json!(500)));
+    }
+}
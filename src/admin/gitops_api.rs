@@ -0,0 +1,473 @@
+use serde::Deserialize;
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+use spin_sdk::key_value::Store;
+
+use super::gitops::{
+    applied_state_for, detect_drift, load_applied_state, plan_desired_state, save_applied_state,
+    DesiredStateDocument, PlannedDesiredState, GITOPS_MANUAL_BAN_REASON,
+};
+use crate::enforcement::ban::BanEntry;
+
+/// Ban-store access for desired-state sync, so planning reads and apply writes go through the
+/// configured ban provider.
+pub(crate) trait GitopsBanBackend {
+    fn list_active_bans(&self, site_id: &str) -> Option<Vec<(String, BanEntry)>>;
+    fn ban(&self, site_id: &str, ip: &str, duration_secs: u64) -> bool;
+    fn unban(&self, site_id: &str, ip: &str) -> bool;
+}
+
+pub(crate) struct ProviderBanBackend<'a> {
+    store: &'a Store,
+    registry: crate::providers::registry::ProviderRegistry,
+}
+
+impl<'a> ProviderBanBackend<'a> {
+    pub(crate) fn new(store: &'a Store, cfg: &crate::config::Config) -> Self {
+        Self {
+            store,
+            registry: crate::providers::registry::ProviderRegistry::from_config(cfg),
+        }
+    }
+}
+
+impl GitopsBanBackend for ProviderBanBackend<'_> {
+    fn list_active_bans(&self, site_id: &str) -> Option<Vec<(String, BanEntry)>> {
+        match self
+            .registry
+            .list_active_bans_for_read_surface(self.store, site_id)
+        {
+            crate::providers::contracts::BanListResult::Available(bans) => Some(bans),
+            crate::providers::contracts::BanListResult::Unavailable => None,
+        }
+    }
+
+    fn ban(&self, site_id: &str, ip: &str, duration_secs: u64) -> bool {
+        self.registry.ban_store_provider().ban_ip_with_fingerprint(
+            self.store,
+            site_id,
+            ip,
+            GITOPS_MANUAL_BAN_REASON,
+            duration_secs,
+            Some(crate::enforcement::ban::BanFingerprint {
+                score: None,
+                signals: vec!["gitops_apply".to_string()],
+                summary: Some("gitops_desired_state_ban".to_string()),
+            }),
+        ) != crate::providers::contracts::BanSyncResult::Failed
+    }
+
+    fn unban(&self, site_id: &str, ip: &str) -> bool {
+        self.registry
+            .ban_store_provider()
+            .unban_ip(self.store, site_id, ip)
+            != crate::providers::contracts::BanSyncResult::Failed
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GitopsApplyRequest {
+    document: DesiredStateDocument,
+    plan_id: String,
+    #[serde(default)]
+    acknowledge_never_ring: bool,
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> Response {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string()))
+        .build()
+}
+
+fn load_persisted_config(
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Result<crate::config::Config, Response> {
+    match crate::config::Config::load(store, site_id) {
+        Ok(cfg) => Ok(cfg),
+        Err(crate::config::ConfigLoadError::MissingConfig) => {
+            Ok(crate::config::default_seeded_config())
+        }
+        Err(err) => Err(Response::new(500, err.user_message())),
+    }
+}
+
+type LivePlan = (
+    crate::config::Config,
+    Vec<(String, BanEntry)>,
+    PlannedDesiredState,
+);
+
+fn plan_against_live(
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+    bans: &impl GitopsBanBackend,
+    document: &DesiredStateDocument,
+) -> Result<LivePlan, Response> {
+    let live_cfg = load_persisted_config(store, site_id)?;
+    let Some(live_bans) = bans.list_active_bans(site_id) else {
+        return Err(Response::new(503, "Ban store unavailable"));
+    };
+    let applied = load_applied_state(store, site_id);
+    let planned = plan_desired_state(
+        &live_cfg,
+        live_bans.as_slice(),
+        document,
+        applied.as_ref(),
+        super::api::admin_ban_duration_bounds(),
+    )
+    .map_err(|err| Response::new(400, err))?;
+    Ok((live_cfg, live_bans, planned))
+}
+
+/// Ban writes already made by one apply, so a failed apply can restore the live ban set.
+#[derive(Default)]
+struct AppliedBanChanges {
+    added: Vec<String>,
+    /// Removed IPs with the seconds their ban had left.
+    removed: Vec<(String, u64)>,
+}
+
+impl AppliedBanChanges {
+    /// Reverts every recorded write and returns the IPs whose revert failed.
+    fn roll_back(&self, site_id: &str, bans: &impl GitopsBanBackend) -> Vec<String> {
+        let mut failures = Vec::new();
+        for ip in self.added.iter().rev() {
+            if !bans.unban(site_id, ip) {
+                failures.push(ip.clone());
+            }
+        }
+        for (ip, remaining) in self.removed.iter().rev() {
+            if !bans.ban(site_id, ip, *remaining) {
+                failures.push(ip.clone());
+            }
+        }
+        failures
+    }
+}
+
+/// GET reports the last apply and any out-of-band drift from it.
+pub(crate) fn handle_admin_gitops_status(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+    bans: &impl GitopsBanBackend,
+) -> Response {
+    if *req.method() != Method::Get {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let Some(applied) = load_applied_state(store, site_id) else {
+        return json_response(200, json!({ "last_applied": null, "drift": [] }));
+    };
+    let live_cfg = match load_persisted_config(store, site_id) {
+        Ok(cfg) => cfg,
+        Err(resp) => return resp,
+    };
+    let Some(live_bans) = bans.list_active_bans(site_id) else {
+        return Response::new(503, "Ban store unavailable");
+    };
+    let live = match serde_json::to_value(&live_cfg) {
+        Ok(serde_json::Value::Object(object)) => object,
+        _ => return Response::new(500, "Unable to serialize live config"),
+    };
+    let drift = detect_drift(&applied, &live, live_bans.as_slice());
+    json_response(
+        200,
+        json!({
+            "last_applied": {
+                "applied_at_ts": applied.applied_at_ts,
+                "author": applied.author,
+                "plan_id": applied.plan_id,
+                "managed_config_fields": applied.config.keys().collect::<Vec<_>>(),
+                "manages_bans": applied.bans.is_some()
+            },
+            "drifted": !drift.is_empty(),
+            "drift": drift
+        }),
+    )
+}
+
+/// POST a desired-state document; returns the plan without writing anything.
+pub(crate) fn handle_admin_gitops_plan(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+    bans: &impl GitopsBanBackend,
+) -> Response {
+    if *req.method() != Method::Post {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid desired-state document: {}", err)),
+    };
+    let document = match serde_json::from_value::<DesiredStateDocument>(payload) {
+        Ok(document) => document,
+        Err(err) => return Response::new(400, format!("Invalid desired-state document: {}", err)),
+    };
+    match plan_against_live(store, site_id, bans, &document) {
+        Ok((_, _, planned)) => json_response(200, json!(planned.plan)),
+        Err(resp) => resp,
+    }
+}
+
+/// POST `{"document", "plan_id"}` re-plans against live state and applies only if the plan is
+/// unchanged. Ban changes go through the ban provider first, then config lands in one KV write,
+/// then the applied-state record. A failure at any step rolls back the writes before it, so an
+/// apply either lands whole or leaves live state and the applied-state record as they were.
+pub(crate) fn handle_admin_gitops_apply(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+    bans: &impl GitopsBanBackend,
+) -> Response {
+    if *req.method() != Method::Post {
+        return Response::new(405, "Method Not Allowed");
+    }
+    if !crate::config::admin_config_write_enabled() {
+        return Response::new(
+            403,
+            "Config updates are disabled when SHUMA_ADMIN_CONFIG_WRITE_ENABLED=false",
+        );
+    }
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid apply payload: {}", err)),
+    };
+    let request = match serde_json::from_value::<GitopsApplyRequest>(payload) {
+        Ok(request) => request,
+        Err(err) => return Response::new(400, format!("Invalid apply payload: {}", err)),
+    };
+    let (live_cfg, live_bans, planned) =
+        match plan_against_live(store, site_id, bans, &request.document) {
+            Ok(planned) => planned,
+            Err(resp) => return resp,
+        };
+    let plan = &planned.plan;
+    if plan.plan_id != request.plan_id {
+        return Response::new(
+            409,
+            "Live state changed since this plan was made; plan again before applying",
+        );
+    }
+    if !plan.blocked_paths.is_empty() {
+        return Response::new(
+            400,
+            format!(
+                "Plan changes paths outside the controller mutability policy: {}",
+                plan.blocked_paths.join(", ")
+            ),
+        );
+    }
+    if !plan.never_ring_paths.is_empty() && !request.acknowledge_never_ring {
+        return Response::new(
+            400,
+            format!(
+                "Plan changes never-ring paths ({}); set acknowledge_never_ring to apply",
+                plan.never_ring_paths.join(", ")
+            ),
+        );
+    }
+
+    let admin_id = crate::admin::auth::get_admin_id(req, store);
+    let now = crate::admin::now_ts();
+    let applied = match applied_state_for(&planned, admin_id.as_str(), now) {
+        Ok(applied) => applied,
+        Err(err) => return Response::new(500, err),
+    };
+
+    let mut ban_changes = AppliedBanChanges::default();
+    let mut ban_failures = Vec::new();
+    for ip in &plan.ban_removals {
+        if !bans.unban(site_id, ip) {
+            ban_failures.push(ip.clone());
+            break;
+        }
+        let remaining = live_bans
+            .iter()
+            .find(|(live_ip, _)| live_ip == ip)
+            .map(|(_, entry)| entry.expires.saturating_sub(now).max(1))
+            .unwrap_or(1);
+        ban_changes.removed.push((ip.clone(), remaining));
+    }
+    if ban_failures.is_empty() {
+        for addition in &plan.ban_additions {
+            if !bans.ban(site_id, addition.ip.as_str(), addition.duration) {
+                ban_failures.push(addition.ip.clone());
+                break;
+            }
+            ban_changes.added.push(addition.ip.clone());
+        }
+    }
+    if !ban_failures.is_empty() {
+        let rollback_failures = ban_changes.roll_back(site_id, bans);
+        return failed_apply_response(
+            store,
+            503,
+            plan.plan_id.as_str(),
+            "ban_write_failed",
+            ban_failures,
+            rollback_failures,
+            admin_id,
+            now,
+        );
+    }
+
+    let config_written = !plan.config_changes.is_empty();
+    if config_written {
+        let recent_change_rows =
+            super::recent_changes_ledger::operator_snapshot_config_patch_recent_change_row(
+                &live_cfg,
+                &planned.target,
+                &planned.patch,
+                admin_id.as_str(),
+                now,
+            )
+            .into_iter()
+            .collect::<Vec<_>>();
+        if super::api::persist_site_config(
+            store,
+            site_id,
+            &planned.target,
+            recent_change_rows.as_slice(),
+            admin_id.as_str(),
+            format!("gitops_apply: {}", plan.plan_id).as_str(),
+        )
+        .is_err()
+        {
+            let rollback_failures = ban_changes.roll_back(site_id, bans);
+            return failed_apply_response(
+                store,
+                500,
+                plan.plan_id.as_str(),
+                "config_write_failed",
+                Vec::new(),
+                rollback_failures,
+                admin_id,
+                now,
+            );
+        }
+    }
+
+    if save_applied_state(store, site_id, &applied).is_err() {
+        let mut rollback_failures = ban_changes.roll_back(site_id, bans);
+        if config_written
+            && super::api::persist_site_config(
+                store,
+                site_id,
+                &live_cfg,
+                &[],
+                admin_id.as_str(),
+                format!("gitops_apply_rollback: {}", plan.plan_id).as_str(),
+            )
+            .is_err()
+        {
+            rollback_failures.push("config".to_string());
+        }
+        return failed_apply_response(
+            store,
+            500,
+            plan.plan_id.as_str(),
+            "applied_state_write_failed",
+            Vec::new(),
+            rollback_failures,
+            admin_id,
+            now,
+        );
+    }
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: now,
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some("gitops_apply".to_string()),
+            outcome: Some(format!(
+                "plan={} config_fields={} bans_added={} bans_removed={}",
+                plan.plan_id,
+                plan.config_changes.len(),
+                plan.ban_additions.len(),
+                plan.ban_removals.len()
+            )),
+            admin: Some(admin_id),
+        },
+    );
+
+    json_response(
+        200,
+        json!({
+            "status": "applied",
+            "plan_id": plan.plan_id,
+            "config_version": super::config_history::latest_config_version(store, site_id),
+            "config_changes": plan.config_changes.len(),
+            "bans_added": plan.ban_additions.len(),
+            "bans_removed": plan.ban_removals.len()
+        }),
+    )
+}
+
+/// Audits and reports an apply that was rolled back. `rollback_failures` lists what could not be
+/// restored (IPs, or `config`), which the operator has to reconcile by hand.
+#[allow(clippy::too_many_arguments)]
+fn failed_apply_response(
+    store: &impl crate::challenge::KeyValueStore,
+    status: u16,
+    plan_id: &str,
+    failure: &str,
+    ban_failures: Vec<String>,
+    rollback_failures: Vec<String>,
+    admin_id: String,
+    now: u64,
+) -> Response {
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: now,
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some("gitops_apply_failed".to_string()),
+            outcome: Some(format!(
+                "plan={} failure={} ban_failures={} rollback_failures={}",
+                plan_id,
+                failure,
+                ban_failures.len(),
+                rollback_failures.len()
+            )),
+            admin: Some(admin_id),
+        },
+    );
+    json_response(
+        status,
+        json!({
+            "status": if rollback_failures.is_empty() { "rolled_back" } else { "rollback_incomplete" },
+            "plan_id": plan_id,
+            "failure": failure,
+            "ban_failures": ban_failures,
+            "rollback_failures": rollback_failures
+        }),
+    )
+}
+
+/// Route entry: builds the provider-backed ban backend from the live runtime config.
+pub(crate) fn handle_admin_gitops_route(req: &Request, store: &Store, site_id: &str) -> Response {
+    let cfg = match crate::config::load_runtime_cached(store, site_id) {
+        Ok(cfg) => cfg,
+        Err(err) => return Response::new(500, err.user_message()),
+    };
+    let bans = ProviderBanBackend::new(store, &cfg);
+    match req.path() {
+        "/shuma/admin/gitops/plan" => handle_admin_gitops_plan(req, store, site_id, &bans),
+        "/shuma/admin/gitops/apply" => handle_admin_gitops_apply(req, store, site_id, &bans),
+        _ => handle_admin_gitops_status(req, store, site_id, &bans),
+    }
+}
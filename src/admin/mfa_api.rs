@@ -0,0 +1,267 @@
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::admin::accounts::AdminPermission;
+use crate::admin::auth::{AdminAuthMethod, AdminAuthResult};
+use crate::admin::totp::{
+    admin_mfa_principal, admin_totp_status, begin_admin_totp_enrollment,
+    confirm_admin_totp_enrollment, delete_admin_totp_enrollment, is_admin_totp_enrolled,
+    verify_admin_second_factor,
+};
+
+const STEP_UP_HEADER: &str = "x-shuma-otp";
+
+/// `GET` reports the caller's enrolment; `POST` runs one of the `enroll`, `confirm`, `disable`
+/// or `reset` actions. `register_failure` records a wrong code and returns true when throttled.
+pub(crate) fn handle_admin_mfa<S, F>(
+    req: &Request,
+    store: &S,
+    auth: &AdminAuthResult,
+    register_failure: F,
+) -> Response
+where
+    S: crate::challenge::KeyValueStore,
+    F: FnMut() -> bool,
+{
+    let Some(principal) = admin_mfa_principal(auth) else {
+        return Response::new(
+            403,
+            "Forbidden: this credential cannot carry a second factor",
+        );
+    };
+    match *req.method() {
+        Method::Get => json_response(json!(admin_totp_status(store, principal.as_str()))),
+        Method::Post => handle_admin_mfa_action(req, store, auth, principal, register_failure),
+        _ => Response::new(405, "Method Not Allowed"),
+    }
+}
+
+fn handle_admin_mfa_action<S, F>(
+    req: &Request,
+    store: &S,
+    auth: &AdminAuthResult,
+    principal: String,
+    mut register_failure: F,
+) -> Response
+where
+    S: crate::challenge::KeyValueStore,
+    F: FnMut() -> bool,
+{
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid MFA payload: {}", err)),
+    };
+    let field = |name: &str| {
+        payload
+            .get(name)
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .unwrap_or("")
+            .to_string()
+    };
+    let now = crate::admin::now_ts();
+    match field("action").as_str() {
+        "enroll" => match begin_admin_totp_enrollment(store, principal.as_str(), now) {
+            Ok((secret, otpauth_uri)) => {
+                log_mfa_action(req, store, "admin_mfa_enroll_start", &principal);
+                json_response(json!({
+                    "principal": principal,
+                    "secret": secret,
+                    "otpauth_uri": otpauth_uri,
+                }))
+            }
+            Err(err) => Response::new(409, err),
+        },
+        "confirm" => {
+            let code = field("code");
+            match confirm_admin_totp_enrollment(store, principal.as_str(), code.as_str(), now) {
+                Ok(recovery_codes) => {
+                    log_mfa_action(req, store, "admin_mfa_enrolled", &principal);
+                    json_response(json!({
+                        "enrolled": true,
+                        "principal": principal,
+                        "recovery_codes": recovery_codes,
+                    }))
+                }
+                Err(err) => {
+                    if register_failure() {
+                        return super::api::too_many_admin_auth_attempts_response();
+                    }
+                    Response::new(400, err)
+                }
+            }
+        }
+        "disable" => {
+            let code = field("code");
+            if !verify_admin_second_factor(store, principal.as_str(), code.as_str(), now) {
+                if register_failure() {
+                    return super::api::too_many_admin_auth_attempts_response();
+                }
+                return Response::new(400, "A current code or recovery code is required");
+            }
+            match delete_admin_totp_enrollment(store, principal.as_str()) {
+                Ok(_) => {
+                    log_mfa_action(req, store, "admin_mfa_disabled", &principal);
+                    json_response(json!({ "enrolled": false, "principal": principal }))
+                }
+                Err(()) => Response::new(500, "Failed removing second factor"),
+            }
+        }
+        "reset" => {
+            if !auth.has_permission(AdminPermission::ManageAccounts) {
+                return Response::new(403, "Forbidden: admin permission manage_accounts required");
+            }
+            if !admin_step_up_is_satisfied(req, store, auth) {
+                return step_up_required_response();
+            }
+            let target = field("principal");
+            match delete_admin_totp_enrollment(store, target.as_str()) {
+                Ok(true) => {
+                    log_mfa_action(req, store, "admin_mfa_reset", &target);
+                    json_response(json!({ "reset": true, "principal": target }))
+                }
+                Ok(false) => Response::new(404, "No second factor enrolled for that principal"),
+                Err(()) => Response::new(500, "Failed removing second factor"),
+            }
+        }
+        _ => Response::new(
+            400,
+            "Invalid MFA payload: action must be enroll, confirm, disable or reset",
+        ),
+    }
+}
+
+/// Re-verifies a session's second factor so sensitive writes go through for a few minutes.
+pub(crate) fn handle_admin_mfa_step_up<S, F>(
+    req: &Request,
+    store: &S,
+    auth: &AdminAuthResult,
+    mut register_failure: F,
+) -> Response
+where
+    S: crate::challenge::KeyValueStore,
+    F: FnMut() -> bool,
+{
+    if *req.method() != Method::Post {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let (Some(AdminAuthMethod::SessionCookie), Some(session_id)) =
+        (auth.method, auth.session_id.as_deref())
+    else {
+        return Response::new(
+            400,
+            "Step-up applies to dashboard sessions; send X-Shuma-OTP with bearer requests",
+        );
+    };
+    let Some(principal) = admin_mfa_principal(auth)
+        .filter(|principal| is_admin_totp_enrolled(store, principal.as_str()))
+    else {
+        return Response::new(409, "No second factor enrolled");
+    };
+    let code = crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    )
+    .ok()
+    .and_then(|payload| {
+        payload
+            .get("code")
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    })
+    .unwrap_or_default();
+    if !verify_admin_second_factor(
+        store,
+        principal.as_str(),
+        code.as_str(),
+        crate::admin::now_ts(),
+    ) {
+        if register_failure() {
+            return super::api::too_many_admin_auth_attempts_response();
+        }
+        return step_up_required_response();
+    }
+    match crate::admin::auth::mark_admin_session_step_up(store, session_id) {
+        Ok(until) => {
+            log_mfa_action(req, store, "admin_mfa_step_up", &principal);
+            json_response(json!({ "step_up_until": until }))
+        }
+        Err(()) => Response::new(500, "Failed recording step-up"),
+    }
+}
+
+/// True when the caller has no enrolled second factor, or has proven it recently: a session
+/// via `/shuma/admin/mfa/step-up`, a bearer request via a fresh code in `X-Shuma-OTP`.
+pub(crate) fn admin_step_up_is_satisfied<S: crate::challenge::KeyValueStore>(
+    req: &Request,
+    store: &S,
+    auth: &AdminAuthResult,
+) -> bool {
+    let Some(principal) = admin_mfa_principal(auth) else {
+        return true;
+    };
+    if !is_admin_totp_enrolled(store, principal.as_str()) {
+        return true;
+    }
+    if let Some(session_id) = auth.session_id.as_deref() {
+        return crate::admin::auth::admin_session_step_up_active(store, session_id);
+    }
+    step_up_header_code(req)
+        .map(|code| {
+            verify_admin_second_factor(
+                store,
+                principal.as_str(),
+                code.as_str(),
+                crate::admin::now_ts(),
+            )
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn step_up_header_code(req: &Request) -> Option<String> {
+    req.header(STEP_UP_HEADER)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+pub(crate) fn step_up_required_response() -> Response {
+    Response::builder()
+        .status(403)
+        .header("X-Shuma-Step-Up", "required")
+        .header("Cache-Control", "no-store")
+        .body("Forbidden: second-factor step-up required")
+        .build()
+}
+
+fn json_response(body: serde_json::Value) -> Response {
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string()))
+        .build()
+}
+
+fn log_mfa_action(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    reason: &str,
+    principal: &str,
+) {
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: crate::admin::now_ts(),
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some(reason.to_string()),
+            outcome: Some(format!("principal={}", principal)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
+        },
+    );
+}
@@ -11,13 +11,25 @@ pub(crate) mod adversary_sim_state;
 pub(crate) mod adversary_sim_trusted_ingress;
 pub(crate) mod adversary_sim_worker_plan;
 mod adversary_sim_api;
+mod accounts_api;
+mod alert_rules_api;
 pub(crate) mod adversary_sim_control;
 mod benchmark_api;
 mod config_api;
+pub(crate) mod config_history;
+mod config_history_api;
 mod diagnostics_api;
+mod gitops;
+mod gitops_api;
 mod monitoring_api;
+mod signature_directories_api;
 mod api;
+pub(crate) mod accounts;
 pub(crate) mod auth;
+pub(crate) mod oidc;
+pub(crate) mod oidc_jwt;
+pub(crate) mod tokens;
+pub(crate) mod totp;
 mod operator_objectives_api;
 pub(crate) mod oversight_agent;
 pub(crate) mod oversight_apply;
@@ -28,7 +40,11 @@ pub(crate) mod oversight_observer_round_archive;
 pub(crate) mod oversight_patch_policy;
 pub(crate) mod oversight_reconcile;
 mod operator_snapshot_api;
+mod policy_simulation_api;
+mod botness_calibration_api;
 mod replay_promotion_api;
+mod tokens_api;
+mod mfa_api;
 mod recent_changes_ledger;
 
 pub use api::{handle_admin, handle_internal, log_event, now_ts, EventLogEntry, EventType};
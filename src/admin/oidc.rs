@@ -0,0 +1,917 @@
+//! OpenID Connect single sign-on for the admin dashboard.
+//!
+//! The dashboard runs the authorization-code flow with PKCE (S256) against one operator-configured
+//! issuer. Pending flows live in a signed, short-lived browser cookie rather than KV. Unauthenticated
+//! callers therefore cannot grow storage, and a callback only completes in the browser that
+//! started it. Issuer discovery metadata and the JWKS are cached in KV under
+//! `admin_oidc:v1:provider`. The cache refreshes hourly, again when an ID token names an unknown
+//! `kid`, and falls back to a stale copy for a day if the issuer is unreachable. Successful
+//! sign-ins map a claim to an [`AdminRole`] and open a regular admin session pinned to that role.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::Rng as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::accounts::AdminRole;
+use super::oidc_jwt::{id_token_key_id, verify_id_token_signature, IdTokenError, OidcJwks};
+use crate::challenge::KeyValueStore;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub(crate) const ADMIN_OIDC_FLOW_COOKIE_NAME: &str = "shuma_admin_oidc_flow";
+pub(crate) const ADMIN_OIDC_FLOW_TTL_SECONDS: u64 = 600;
+pub(crate) const ADMIN_OIDC_CALLBACK_PATH: &str = "/shuma/admin/oidc/callback";
+const ADMIN_OIDC_PROVIDER_CACHE_KEY: &str = "admin_oidc:v1:provider";
+const ADMIN_OIDC_FLOW_KEY_PURPOSE: &str = "shuma-admin-oidc-flow-v1";
+const OIDC_PROVIDER_CACHE_TTL_SECONDS: u64 = 3600;
+const OIDC_PROVIDER_STALE_GRACE_SECONDS: u64 = 86_400;
+const OIDC_JWKS_MIN_REFRESH_INTERVAL_SECONDS: u64 = 60;
+const OIDC_CLOCK_SKEW_SECONDS: u64 = 60;
+const MAX_OIDC_RESPONSE_BYTES: usize = 64 * 1024;
+const MAX_OIDC_IDENTITY_CHARS: usize = 128;
+const DEFAULT_OIDC_ROLE_CLAIM: &str = "groups";
+const OIDC_SCOPES: &str = "openid email profile";
+const OIDC_QUERY_ENCODE_SET: &percent_encoding::AsciiSet = percent_encoding::NON_ALPHANUMERIC;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AdminOidcConfig {
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub role_claim: String,
+    /// Claim value to role, checked in order; the first match wins.
+    pub role_map: Vec<(String, AdminRole)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AdminOidcError {
+    ProviderUnavailable,
+    InvalidState,
+    TokenExchangeFailed,
+    IdToken(IdTokenError),
+    ClaimsInvalid(&'static str),
+    RoleNotMapped,
+}
+
+impl AdminOidcError {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdminOidcError::ProviderUnavailable => "provider_unavailable",
+            AdminOidcError::InvalidState => "invalid_state",
+            AdminOidcError::TokenExchangeFailed => "token_exchange_failed",
+            AdminOidcError::IdToken(err) => err.as_str(),
+            AdminOidcError::ClaimsInvalid(reason) => reason,
+            AdminOidcError::RoleNotMapped => "role_not_mapped",
+        }
+    }
+}
+
+pub(crate) struct OidcHttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+pub(crate) trait OidcHttpClient {
+    fn get(&self, uri: &str) -> Result<OidcHttpResponse, ()>;
+    fn post_form(&self, uri: &str, body: &str) -> Result<OidcHttpResponse, ()>;
+}
+
+#[derive(Default)]
+pub(crate) struct SpinOidcHttpClient;
+
+impl OidcHttpClient for SpinOidcHttpClient {
+    fn get(&self, uri: &str) -> Result<OidcHttpResponse, ()> {
+        dispatch_oidc_request(spin_sdk::http::Method::Get, uri, None)
+    }
+
+    fn post_form(&self, uri: &str, body: &str) -> Result<OidcHttpResponse, ()> {
+        dispatch_oidc_request(spin_sdk::http::Method::Post, uri, Some(body))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn dispatch_oidc_request(
+    method: spin_sdk::http::Method,
+    uri: &str,
+    form_body: Option<&str>,
+) -> Result<OidcHttpResponse, ()> {
+    let mut builder = spin_sdk::http::Request::builder();
+    builder
+        .method(method)
+        .uri(uri)
+        .header("accept", "application/json");
+    if form_body.is_some() {
+        builder.header("content-type", "application/x-www-form-urlencoded");
+    }
+    let request = builder
+        .body(form_body.unwrap_or_default().as_bytes().to_vec())
+        .build();
+    let response: spin_sdk::http::Response =
+        spin_sdk::http::run(spin_sdk::http::send(request)).map_err(|_| ())?;
+    Ok(OidcHttpResponse {
+        status: *response.status(),
+        body: response.body().to_vec(),
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn dispatch_oidc_request(
+    _method: spin_sdk::http::Method,
+    _uri: &str,
+    _form_body: Option<&str>,
+) -> Result<OidcHttpResponse, ()> {
+    Err(())
+}
+
+/// Reads the OIDC settings from the environment. SSO stays off unless issuer, client id and
+/// redirect URI are all set and valid.
+pub(crate) fn admin_oidc_config_from_env() -> Option<AdminOidcConfig> {
+    let issuer = crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_OIDC_ISSUER")?;
+    let client_id = crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_OIDC_CLIENT_ID")?;
+    let redirect_uri =
+        crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_OIDC_REDIRECT_URI")?;
+    let parsed = parse_admin_oidc_config(
+        issuer.as_str(),
+        client_id.as_str(),
+        crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_OIDC_CLIENT_SECRET"),
+        redirect_uri.as_str(),
+        crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_OIDC_ROLE_CLAIM"),
+        crate::config::runtime_var_trimmed_optional("SHUMA_ADMIN_OIDC_ROLE_MAP")
+            .unwrap_or_default()
+            .as_str(),
+    );
+    match parsed {
+        Ok(config) => Some(config),
+        Err(err) => {
+            eprintln!("[admin-oidc] SSO disabled: {}", err);
+            None
+        }
+    }
+}
+
+fn parse_admin_oidc_config(
+    issuer: &str,
+    client_id: &str,
+    client_secret: Option<String>,
+    redirect_uri: &str,
+    role_claim: Option<String>,
+    role_map: &str,
+) -> Result<AdminOidcConfig, String> {
+    if !is_allowed_oidc_url(issuer) {
+        return Err("SHUMA_ADMIN_OIDC_ISSUER must be an https URL".to_string());
+    }
+    if !is_allowed_oidc_url(redirect_uri)
+        || !redirect_uri
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .ends_with(ADMIN_OIDC_CALLBACK_PATH)
+    {
+        return Err(format!(
+            "SHUMA_ADMIN_OIDC_REDIRECT_URI must be an https URL ending in {}",
+            ADMIN_OIDC_CALLBACK_PATH
+        ));
+    }
+    let mut mappings = Vec::new();
+    for entry in role_map
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let Some((value, role)) = entry.rsplit_once('=') else {
+            return Err(format!(
+                "invalid SHUMA_ADMIN_OIDC_ROLE_MAP entry '{}'",
+                entry
+            ));
+        };
+        let Some(role) = AdminRole::parse(role) else {
+            return Err(format!(
+                "unknown role in SHUMA_ADMIN_OIDC_ROLE_MAP entry '{}'",
+                entry
+            ));
+        };
+        mappings.push((value.trim().to_string(), role));
+    }
+    if mappings.is_empty() {
+        return Err("SHUMA_ADMIN_OIDC_ROLE_MAP must map at least one claim value".to_string());
+    }
+    Ok(AdminOidcConfig {
+        issuer: issuer.to_string(),
+        client_id: client_id.to_string(),
+        client_secret,
+        redirect_uri: redirect_uri.to_string(),
+        role_claim: role_claim.unwrap_or_else(|| DEFAULT_OIDC_ROLE_CLAIM.to_string()),
+        role_map: mappings,
+    })
+}
+
+/// HTTPS everywhere, except plain HTTP to a loopback host for local identity providers.
+fn is_allowed_oidc_url(raw: &str) -> bool {
+    if let Some(rest) = raw.strip_prefix("https://") {
+        return !rest.is_empty() && !rest.starts_with('/');
+    }
+    let Some(rest) = raw.strip_prefix("http://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|ch| ch.is_ascii_digit()) => host,
+        _ => authority,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedOidcProvider {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    jwks: OidcJwks,
+    fetched_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcDiscoveryDocument {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+fn load_cached_provider<S: KeyValueStore>(store: &S, issuer: &str) -> Option<CachedOidcProvider> {
+    let raw = store.get(ADMIN_OIDC_PROVIDER_CACHE_KEY).ok()??;
+    let cached = serde_json::from_slice::<CachedOidcProvider>(raw.as_slice()).ok()?;
+    (cached.issuer == issuer).then_some(cached)
+}
+
+fn fetch_json<T: serde::de::DeserializeOwned>(
+    client: &dyn OidcHttpClient,
+    uri: &str,
+) -> Result<T, ()> {
+    let response = client.get(uri)?;
+    if response.status != 200 || response.body.len() > MAX_OIDC_RESPONSE_BYTES {
+        return Err(());
+    }
+    serde_json::from_slice::<T>(response.body.as_slice()).map_err(|_| ())
+}
+
+fn fetch_provider(
+    config: &AdminOidcConfig,
+    client: &dyn OidcHttpClient,
+    now: u64,
+) -> Result<CachedOidcProvider, ()> {
+    let discovery_uri = format!(
+        "{}/.well-known/openid-configuration",
+        config.issuer.trim_end_matches('/')
+    );
+    let discovery: OidcDiscoveryDocument = fetch_json(client, discovery_uri.as_str())?;
+    if discovery.issuer != config.issuer
+        || ![
+            discovery.authorization_endpoint.as_str(),
+            discovery.token_endpoint.as_str(),
+            discovery.jwks_uri.as_str(),
+        ]
+        .iter()
+        .all(|uri| is_allowed_oidc_url(uri))
+    {
+        return Err(());
+    }
+    let jwks: OidcJwks = fetch_json(client, discovery.jwks_uri.as_str())?;
+    Ok(CachedOidcProvider {
+        issuer: discovery.issuer,
+        authorization_endpoint: discovery.authorization_endpoint,
+        token_endpoint: discovery.token_endpoint,
+        jwks_uri: discovery.jwks_uri,
+        jwks,
+        fetched_at: now,
+    })
+}
+
+/// Returns issuer metadata and keys, refetching when the cache is stale or `force_refresh` is set
+/// (rate-limited so a flood of unknown `kid`s cannot hammer the issuer).
+fn resolve_provider<S: KeyValueStore>(
+    store: &S,
+    config: &AdminOidcConfig,
+    client: &dyn OidcHttpClient,
+    now: u64,
+    force_refresh: bool,
+) -> Result<CachedOidcProvider, AdminOidcError> {
+    let cached = load_cached_provider(store, config.issuer.as_str());
+    if let Some(cached) = cached.as_ref() {
+        let age = now.saturating_sub(cached.fetched_at);
+        let fresh = if force_refresh {
+            age < OIDC_JWKS_MIN_REFRESH_INTERVAL_SECONDS
+        } else {
+            age < OIDC_PROVIDER_CACHE_TTL_SECONDS
+        };
+        if fresh {
+            return Ok(cached.clone());
+        }
+    }
+    match fetch_provider(config, client, now) {
+        Ok(provider) => {
+            if let Ok(raw) = serde_json::to_vec(&provider) {
+                if store
+                    .set(ADMIN_OIDC_PROVIDER_CACHE_KEY, raw.as_slice())
+                    .is_err()
+                {
+                    eprintln!("[admin-oidc] failed to cache issuer metadata");
+                }
+            }
+            Ok(provider)
+        }
+        Err(()) => cached
+            .filter(|cached| {
+                now.saturating_sub(cached.fetched_at) < OIDC_PROVIDER_STALE_GRACE_SECONDS
+            })
+            .ok_or(AdminOidcError::ProviderUnavailable),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PendingOidcFlow {
+    state: String,
+    nonce: String,
+    code_verifier: String,
+    next: String,
+    expires_at: u64,
+}
+
+fn random_urlsafe(num_bytes: usize) -> String {
+    let mut bytes = vec![0u8; num_bytes];
+    rand::rng().fill(bytes.as_mut_slice());
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn flow_mac(payload: &str) -> Option<HmacSha256> {
+    let key = super::auth::derive_admin_secret(ADMIN_OIDC_FLOW_KEY_PURPOSE)?;
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(&key).ok()?;
+    mac.update(payload.as_bytes());
+    Some(mac)
+}
+
+fn seal_flow(flow: &PendingOidcFlow) -> Option<String> {
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(flow).ok()?);
+    let tag = flow_mac(payload.as_str())?.finalize().into_bytes();
+    Some(format!("{}.{}", payload, URL_SAFE_NO_PAD.encode(tag)))
+}
+
+fn open_flow(sealed: &str, now: u64) -> Option<PendingOidcFlow> {
+    let (payload, tag) = sealed.split_once('.')?;
+    let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+    flow_mac(payload)?.verify_slice(tag.as_slice()).ok()?;
+    let flow: PendingOidcFlow =
+        serde_json::from_slice(URL_SAFE_NO_PAD.decode(payload).ok()?.as_slice()).ok()?;
+    (flow.expires_at > now).then_some(flow)
+}
+
+pub(crate) struct AdminOidcLoginStart {
+    pub authorization_url: String,
+    /// Signed pending-flow value for the [`ADMIN_OIDC_FLOW_COOKIE_NAME`] cookie.
+    pub flow_cookie: String,
+}
+
+/// Starts a sign-in: picks fresh state, nonce and PKCE verifier and builds the issuer redirect.
+pub(crate) fn begin_admin_oidc_login<S: KeyValueStore>(
+    store: &S,
+    config: &AdminOidcConfig,
+    client: &dyn OidcHttpClient,
+    next: &str,
+    now: u64,
+) -> Result<AdminOidcLoginStart, AdminOidcError> {
+    let provider = resolve_provider(store, config, client, now, false)?;
+    let flow = PendingOidcFlow {
+        state: random_urlsafe(24),
+        nonce: random_urlsafe(24),
+        code_verifier: random_urlsafe(48),
+        next: next.to_string(),
+        expires_at: now.saturating_add(ADMIN_OIDC_FLOW_TTL_SECONDS),
+    };
+    let flow_cookie = seal_flow(&flow).ok_or(AdminOidcError::InvalidState)?;
+    let encode = |value: &str| {
+        percent_encoding::utf8_percent_encode(value, OIDC_QUERY_ENCODE_SET).to_string()
+    };
+    let separator = if provider.authorization_endpoint.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+    let authorization_url = format!(
+        "{}{}response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&nonce={}&code_challenge={}&code_challenge_method=S256",
+        provider.authorization_endpoint,
+        separator,
+        encode(config.client_id.as_str()),
+        encode(config.redirect_uri.as_str()),
+        encode(OIDC_SCOPES),
+        flow.state,
+        flow.nonce,
+        pkce_challenge(flow.code_verifier.as_str()),
+    );
+    Ok(AdminOidcLoginStart {
+        authorization_url,
+        flow_cookie,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AdminOidcIdentity {
+    /// Session username, `oidc:<email|preferred_username|sub>`.
+    pub username: String,
+    pub role: AdminRole,
+    pub next: String,
+}
+
+/// Finishes a sign-in from the issuer callback and returns the identity to open a session for.
+pub(crate) fn complete_admin_oidc_login<S: KeyValueStore>(
+    store: &S,
+    config: &AdminOidcConfig,
+    client: &dyn OidcHttpClient,
+    flow_cookie: Option<&str>,
+    state: &str,
+    code: &str,
+    now: u64,
+) -> Result<AdminOidcIdentity, AdminOidcError> {
+    let flow = flow_cookie
+        .and_then(|sealed| open_flow(sealed, now))
+        .filter(|flow| {
+            super::auth::constant_time_eq(flow.state.as_str(), state) && !code.is_empty()
+        })
+        .ok_or(AdminOidcError::InvalidState)?;
+
+    let mut provider = resolve_provider(store, config, client, now, false)?;
+    let id_token = exchange_code(config, client, &provider, code, flow.code_verifier.as_str())?;
+    let claims = match verify_id_token_signature(id_token.as_str(), &provider.jwks) {
+        Err(IdTokenError::UnknownKey) if id_token_key_id(id_token.as_str()).is_some() => {
+            // The issuer may have rotated keys since the cache was filled.
+            provider = resolve_provider(store, config, client, now, true)?;
+            verify_id_token_signature(id_token.as_str(), &provider.jwks)
+        }
+        other => other,
+    }
+    .map_err(AdminOidcError::IdToken)?;
+
+    validate_id_token_claims(config, &claims, flow.nonce.as_str(), now)?;
+    let role = map_claims_to_role(config, &claims).ok_or(AdminOidcError::RoleNotMapped)?;
+    let identity = identity_from_claims(&claims)
+        .ok_or(AdminOidcError::ClaimsInvalid("identity_claim_missing"))?;
+    Ok(AdminOidcIdentity {
+        username: format!("oidc:{}", identity),
+        role,
+        next: flow.next,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenEndpointResponse {
+    id_token: String,
+}
+
+fn exchange_code(
+    config: &AdminOidcConfig,
+    client: &dyn OidcHttpClient,
+    provider: &CachedOidcProvider,
+    code: &str,
+    code_verifier: &str,
+) -> Result<String, AdminOidcError> {
+    let encode = |value: &str| {
+        percent_encoding::utf8_percent_encode(value, OIDC_QUERY_ENCODE_SET).to_string()
+    };
+    let mut body = format!(
+        "grant_type=authorization_code&code={}&redirect_uri={}&client_id={}&code_verifier={}",
+        encode(code),
+        encode(config.redirect_uri.as_str()),
+        encode(config.client_id.as_str()),
+        encode(code_verifier),
+    );
+    if let Some(secret) = config.client_secret.as_deref() {
+        body.push_str("&client_secret=");
+        body.push_str(encode(secret).as_str());
+    }
+    let response = client
+        .post_form(provider.token_endpoint.as_str(), body.as_str())
+        .map_err(|_| AdminOidcError::TokenExchangeFailed)?;
+    if response.status != 200 || response.body.len() > MAX_OIDC_RESPONSE_BYTES {
+        return Err(AdminOidcError::TokenExchangeFailed);
+    }
+    serde_json::from_slice::<TokenEndpointResponse>(response.body.as_slice())
+        .map(|parsed| parsed.id_token)
+        .map_err(|_| AdminOidcError::TokenExchangeFailed)
+}
+
+fn validate_id_token_claims(
+    config: &AdminOidcConfig,
+    claims: &serde_json::Map<String, serde_json::Value>,
+    expected_nonce: &str,
+    now: u64,
+) -> Result<(), AdminOidcError> {
+    if claims.get("iss").and_then(|value| value.as_str()) != Some(config.issuer.as_str()) {
+        return Err(AdminOidcError::ClaimsInvalid("issuer_mismatch"));
+    }
+    let audiences: Vec<&str> = match claims.get("aud") {
+        Some(serde_json::Value::String(aud)) => vec![aud.as_str()],
+        Some(serde_json::Value::Array(values)) => {
+            values.iter().filter_map(|value| value.as_str()).collect()
+        }
+        _ => Vec::new(),
+    };
+    if !audiences.contains(&config.client_id.as_str()) {
+        return Err(AdminOidcError::ClaimsInvalid("audience_mismatch"));
+    }
+    let azp = claims.get("azp").and_then(|value| value.as_str());
+    if (audiences.len() > 1 || azp.is_some()) && azp != Some(config.client_id.as_str()) {
+        return Err(AdminOidcError::ClaimsInvalid("authorized_party_mismatch"));
+    }
+    let expires_at = claims
+        .get("exp")
+        .and_then(|value| value.as_u64())
+        .unwrap_or(0);
+    if expires_at.saturating_add(OIDC_CLOCK_SKEW_SECONDS) <= now {
+        return Err(AdminOidcError::ClaimsInvalid("id_token_expired"));
+    }
+    if claims
+        .get("iat")
+        .and_then(|value| value.as_u64())
+        .is_none_or(|issued_at| issued_at > now.saturating_add(OIDC_CLOCK_SKEW_SECONDS))
+    {
+        return Err(AdminOidcError::ClaimsInvalid("id_token_issued_at_invalid"));
+    }
+    let nonce = claims
+        .get("nonce")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    if !super::auth::constant_time_eq(nonce, expected_nonce) {
+        return Err(AdminOidcError::ClaimsInvalid("nonce_mismatch"));
+    }
+    Ok(())
+}
+
+fn map_claims_to_role(
+    config: &AdminOidcConfig,
+    claims: &serde_json::Map<String, serde_json::Value>,
+) -> Option<AdminRole> {
+    let values: Vec<&str> = match claims.get(config.role_claim.as_str())? {
+        serde_json::Value::String(value) => vec![value.as_str()],
+        serde_json::Value::Array(values) => {
+            values.iter().filter_map(|value| value.as_str()).collect()
+        }
+        _ => return None,
+    };
+    config
+        .role_map
+        .iter()
+        .find(|(claim_value, _)| values.contains(&claim_value.as_str()))
+        .map(|(_, role)| *role)
+}
+
+fn identity_from_claims(claims: &serde_json::Map<String, serde_json::Value>) -> Option<String> {
+    let email_verified = claims
+        .get("email_verified")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true);
+    let email = claims
+        .get("email")
+        .and_then(|value| value.as_str())
+        .filter(|_| email_verified);
+    [
+        email,
+        claims
+            .get("preferred_username")
+            .and_then(|value| value.as_str()),
+        claims.get("sub").and_then(|value| value.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::trim)
+    .find(|value| {
+        !value.is_empty()
+            && value.chars().count() <= MAX_OIDC_IDENTITY_CHARS
+            && !value
+                .chars()
+                .any(|ch| ch.is_control() || ch.is_whitespace())
+    })
+    .map(str::to_string)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{
+        begin_admin_oidc_login, complete_admin_oidc_login, parse_admin_oidc_config,
+        AdminOidcConfig, AdminOidcError, OidcHttpClient, OidcHttpResponse,
+    };
+    use crate::admin::accounts::AdminRole;
+    use crate::admin::oidc_jwt::tests::{mint_ed25519_id_token, mock_ed25519_jwk};
+    use crate::admin::oidc_jwt::{IdTokenError, OidcJwks};
+    use crate::test_support::InMemoryStore;
+    use std::cell::{Cell, RefCell};
+
+    pub(crate) const MOCK_ISSUER: &str = "https://idp.example.test";
+    pub(crate) const MOCK_CLIENT_ID: &str = "shuma-dashboard";
+    const MOCK_NOW: u64 = 1_800_000_000;
+
+    /// Local stand-in for an identity provider: serves discovery and JWKS, and mints an
+    /// EdDSA-signed ID token for the code it handed out once the PKCE verifier checks out.
+    pub(crate) struct MockIdp {
+        pub claims: RefCell<serde_json::Value>,
+        pub jwks: RefCell<OidcJwks>,
+        pub jwks_fetches: Cell<u32>,
+        issued: RefCell<Option<(String, String, String)>>,
+    }
+
+    impl MockIdp {
+        pub(crate) fn new(claims: serde_json::Value) -> Self {
+            Self {
+                claims: RefCell::new(claims),
+                jwks: RefCell::new(OidcJwks {
+                    keys: vec![mock_ed25519_jwk()],
+                }),
+                jwks_fetches: Cell::new(0),
+                issued: RefCell::new(None),
+            }
+        }
+
+        /// Plays the user approving the sign-in; returns `(code, state)` for the callback.
+        pub(crate) fn authorize(&self, authorization_url: &str) -> (String, String) {
+            let query = authorization_url.split_once('?').expect("query").1;
+            let param = |key: &str| {
+                crate::request_validation::query_param(query, key).expect("authorization param")
+            };
+            assert_eq!(param("client_id"), MOCK_CLIENT_ID);
+            assert_eq!(param("code_challenge_method"), "S256");
+            let code = format!("code-{}", param("state"));
+            *self.issued.borrow_mut() =
+                Some((code.clone(), param("code_challenge"), param("nonce")));
+            (code, param("state"))
+        }
+    }
+
+    fn ok_json(body: serde_json::Value) -> Result<OidcHttpResponse, ()> {
+        Ok(OidcHttpResponse {
+            status: 200,
+            body: body.to_string().into_bytes(),
+        })
+    }
+
+    impl OidcHttpClient for MockIdp {
+        fn get(&self, uri: &str) -> Result<OidcHttpResponse, ()> {
+            match uri {
+                "https://idp.example.test/.well-known/openid-configuration" => {
+                    ok_json(serde_json::json!({
+                        "issuer": MOCK_ISSUER,
+                        "authorization_endpoint": "https://idp.example.test/authorize",
+                        "token_endpoint": "https://idp.example.test/token",
+                        "jwks_uri": "https://idp.example.test/jwks"
+                    }))
+                }
+                "https://idp.example.test/jwks" => {
+                    self.jwks_fetches.set(self.jwks_fetches.get() + 1);
+                    ok_json(serde_json::to_value(&*self.jwks.borrow()).expect("jwks"))
+                }
+                _ => Err(()),
+            }
+        }
+
+        fn post_form(&self, uri: &str, body: &str) -> Result<OidcHttpResponse, ()> {
+            assert_eq!(uri, "https://idp.example.test/token");
+            let param = |key: &str| crate::request_validation::query_param(body, key);
+            let Some((code, challenge, nonce)) = self.issued.borrow_mut().take() else {
+                return Ok(OidcHttpResponse {
+                    status: 400,
+                    body: br#"{"error":"invalid_grant"}"#.to_vec(),
+                });
+            };
+            let verifier = param("code_verifier").unwrap_or_default();
+            if param("code").as_deref() != Some(code.as_str())
+                || super::pkce_challenge(verifier.as_str()) != challenge
+            {
+                return Ok(OidcHttpResponse {
+                    status: 400,
+                    body: br#"{"error":"invalid_grant"}"#.to_vec(),
+                });
+            }
+            let mut claims = self.claims.borrow().clone();
+            if claims.get("nonce").is_none() {
+                claims["nonce"] = serde_json::Value::String(nonce);
+            }
+            ok_json(serde_json::json!({
+                "access_token": "opaque",
+                "token_type": "Bearer",
+                "id_token": mint_ed25519_id_token(&claims)
+            }))
+        }
+    }
+
+    pub(crate) fn mock_claims() -> serde_json::Value {
+        serde_json::json!({
+            "iss": MOCK_ISSUER,
+            "aud": MOCK_CLIENT_ID,
+            "sub": "user-42",
+            "email": "ops@example.test",
+            "email_verified": true,
+            "groups": ["everyone", "shuma-analysts"],
+            "iat": MOCK_NOW,
+            "exp": MOCK_NOW + 600
+        })
+    }
+
+    pub(crate) fn mock_config() -> AdminOidcConfig {
+        parse_admin_oidc_config(
+            MOCK_ISSUER,
+            MOCK_CLIENT_ID,
+            None,
+            "https://shuma.example.test/shuma/admin/oidc/callback",
+            None,
+            "shuma-owners=owner, shuma-analysts=analyst",
+        )
+        .expect("valid config")
+    }
+
+    fn run_flow(
+        store: &InMemoryStore,
+        idp: &MockIdp,
+        now: u64,
+    ) -> Result<super::AdminOidcIdentity, AdminOidcError> {
+        let config = mock_config();
+        let start = begin_admin_oidc_login(store, &config, idp, "/shuma/dashboard/index.html", now)
+            .expect("login starts");
+        let (code, state) = idp.authorize(start.authorization_url.as_str());
+        complete_admin_oidc_login(
+            store,
+            &config,
+            idp,
+            Some(start.flow_cookie.as_str()),
+            state.as_str(),
+            code.as_str(),
+            now,
+        )
+    }
+
+    #[test]
+    fn config_requires_https_callback_and_known_roles() {
+        let valid = mock_config();
+        assert_eq!(valid.role_claim, "groups");
+        assert_eq!(
+            valid.role_map,
+            vec![
+                ("shuma-owners".to_string(), AdminRole::Owner),
+                ("shuma-analysts".to_string(), AdminRole::Analyst),
+            ]
+        );
+        let callback = "https://shuma.example.test/shuma/admin/oidc/callback";
+        assert!(parse_admin_oidc_config(
+            "http://idp.example.test",
+            "c",
+            None,
+            callback,
+            None,
+            "g=owner"
+        )
+        .is_err());
+        assert!(parse_admin_oidc_config(
+            "http://127.0.0.1:8080",
+            "c",
+            None,
+            callback,
+            None,
+            "g=owner"
+        )
+        .is_ok());
+        assert!(parse_admin_oidc_config(
+            MOCK_ISSUER,
+            "c",
+            None,
+            "https://shuma.example.test/other",
+            None,
+            "g=owner"
+        )
+        .is_err());
+        assert!(parse_admin_oidc_config(MOCK_ISSUER, "c", None, callback, None, "g=root").is_err());
+        assert!(parse_admin_oidc_config(MOCK_ISSUER, "c", None, callback, None, "").is_err());
+    }
+
+    #[test]
+    fn authorization_code_flow_maps_claims_and_caches_jwks() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "oidc-test-admin-key");
+        let store = InMemoryStore::default();
+        let idp = MockIdp::new(mock_claims());
+
+        let identity = run_flow(&store, &idp, MOCK_NOW).expect("sign-in succeeds");
+        assert_eq!(identity.username, "oidc:ops@example.test");
+        assert_eq!(identity.role, AdminRole::Analyst);
+        assert_eq!(identity.next, "/shuma/dashboard/index.html");
+
+        run_flow(&store, &idp, MOCK_NOW + 30).expect("second sign-in succeeds");
+        assert_eq!(
+            idp.jwks_fetches.get(),
+            1,
+            "JWKS should come from the KV cache"
+        );
+        std::env::remove_var("SHUMA_API_KEY");
+    }
+
+    #[test]
+    fn unknown_key_id_refreshes_jwks_at_most_once_per_interval() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "oidc-test-admin-key");
+        let store = InMemoryStore::default();
+        let idp = MockIdp::new(mock_claims());
+        let mut retired = mock_ed25519_jwk();
+        retired.kid = Some("retired".to_string());
+        idp.jwks.borrow_mut().keys = vec![retired];
+
+        assert_eq!(
+            run_flow(&store, &idp, MOCK_NOW),
+            Err(AdminOidcError::IdToken(IdTokenError::UnknownKey))
+        );
+        assert_eq!(
+            idp.jwks_fetches.get(),
+            1,
+            "fresh cache must not be refetched"
+        );
+
+        idp.jwks.borrow_mut().keys = vec![mock_ed25519_jwk()];
+        run_flow(&store, &idp, MOCK_NOW + 120).expect("rotated key is picked up");
+        run_flow(&store, &idp, MOCK_NOW + 150).expect("rotated key is cached");
+        assert_eq!(idp.jwks_fetches.get(), 2);
+        std::env::remove_var("SHUMA_API_KEY");
+    }
+
+    #[test]
+    fn callback_rejects_forged_state_bad_claims_and_unmapped_users() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_API_KEY", "oidc-test-admin-key");
+        let store = InMemoryStore::default();
+        let config = mock_config();
+        let idp = MockIdp::new(mock_claims());
+
+        let start =
+            begin_admin_oidc_login(&store, &config, &idp, "/", MOCK_NOW).expect("login starts");
+        let (code, state) = idp.authorize(start.authorization_url.as_str());
+        for (cookie, state) in [
+            (None, state.as_str()),
+            (Some(start.flow_cookie.as_str()), "forged"),
+            (Some("tampered.cookie"), state.as_str()),
+        ] {
+            assert_eq!(
+                complete_admin_oidc_login(
+                    &store,
+                    &config,
+                    &idp,
+                    cookie,
+                    state,
+                    code.as_str(),
+                    MOCK_NOW
+                ),
+                Err(AdminOidcError::InvalidState)
+            );
+        }
+        assert_eq!(
+            complete_admin_oidc_login(
+                &store,
+                &config,
+                &idp,
+                Some(start.flow_cookie.as_str()),
+                state.as_str(),
+                code.as_str(),
+                MOCK_NOW + super::ADMIN_OIDC_FLOW_TTL_SECONDS,
+            ),
+            Err(AdminOidcError::InvalidState)
+        );
+
+        let cases = [
+            (
+                "aud",
+                serde_json::json!("someone-else"),
+                "audience_mismatch",
+            ),
+            (
+                "iss",
+                serde_json::json!("https://evil.example.test"),
+                "issuer_mismatch",
+            ),
+            (
+                "exp",
+                serde_json::json!(MOCK_NOW - 3600),
+                "id_token_expired",
+            ),
+            ("nonce", serde_json::json!("replayed"), "nonce_mismatch"),
+            ("groups", serde_json::json!(["everyone"]), "role_not_mapped"),
+        ];
+        for (claim, value, expected) in cases {
+            let mut claims = mock_claims();
+            claims[claim] = value;
+            *idp.claims.borrow_mut() = claims;
+            let err = run_flow(&store, &idp, MOCK_NOW).expect_err("sign-in rejected");
+            assert_eq!(err.as_str(), expected, "claim {}", claim);
+        }
+        std::env::remove_var("SHUMA_API_KEY");
+    }
+}
@@ -63,7 +63,7 @@ fn handle_admin_operator_objectives_update(
     };
 
     let updated_at_ts = crate::admin::now_ts();
-    let admin_id = crate::admin::auth::get_admin_id(req);
+    let admin_id = crate::admin::auth::get_admin_id(req, store);
     let profile = match persisted_operator_objectives_from_request(
         request,
         updated_at_ts,
@@ -796,8 +796,19 @@ fn persist_config_change<S: KeyValueStore>(
     expected_impact_summary: &str,
     evidence_references: Vec<OperatorDecisionEvidenceReference>,
 ) -> Result<(), ()> {
+    let key = format!("config:{site_id}");
     let encoded = crate::config::serialize_persisted_kv_config(new_cfg).map_err(|_| ())?;
-    store.set(format!("config:{site_id}").as_str(), &encoded)?;
+    let previous = store.get(key.as_str()).ok().flatten();
+    store.set(key.as_str(), &encoded)?;
+    crate::admin::config_history::record_persisted_config_write(
+        store,
+        site_id,
+        previous.as_deref(),
+        encoded.as_slice(),
+        OVERSIGHT_CONTROLLER_ADMIN_ID,
+        decision_kind,
+        changed_at_ts,
+    );
 
     if let Some(change_row) = operator_snapshot_config_patch_recent_change_row(
         old_cfg,
@@ -181,6 +181,82 @@ pub(crate) fn rank_patch_candidates(
     }
 }
 
+/// Turn calibrated botness weights into a bounded `botness` proposal. Each weight is clamped to
+/// its `botness_weights.<field>` catalog constraint and unchanged fields are left out, so the
+/// patch carries the same group status and canary requirement as any other controller move.
+pub(crate) fn propose_botness_weights_patch(
+    cfg: &Config,
+    allowed_actions: &AllowedActionsSurface,
+    weights: &[(&str, u64)],
+    expected_impact: String,
+    confidence: &str,
+) -> Result<OversightPatchProposal, OversightPatchPolicyError> {
+    const FAMILY: &str = "botness";
+    if !family_is_proposable(allowed_actions, FAMILY) {
+        return Err(OversightPatchPolicyError::UnsupportedCandidateFamily(
+            FAMILY.to_string(),
+        ));
+    }
+    let current = serde_json::to_value(&cfg.botness_weights).unwrap_or_default();
+    let mut changed = serde_json::Map::new();
+    for (field, value) in weights {
+        let path = format!("botness_weights.{field}");
+        let constraint = allowed_actions
+            .groups
+            .iter()
+            .filter(|group| group.family == FAMILY)
+            .flat_map(|group| group.value_constraints.iter())
+            .find(|constraint| constraint.path == path)
+            .ok_or_else(|| OversightPatchPolicyError::InvalidPatch(path.clone()))?;
+        let min = constraint
+            .min_inclusive
+            .map(|value| value.max(0.0) as u64)
+            .unwrap_or(0);
+        let max = constraint
+            .max_inclusive
+            .map(|value| value.max(0.0) as u64)
+            .unwrap_or(*value);
+        let bounded = (*value).clamp(min, max.max(min));
+        if current.get(*field).and_then(serde_json::Value::as_u64) != Some(bounded) {
+            changed.insert((*field).to_string(), json!(bounded));
+        }
+    }
+    if changed.is_empty() {
+        return Err(OversightPatchPolicyError::NoBoundedPatch(FAMILY.to_string()));
+    }
+    let patch = json!({ "botness_weights": changed });
+    crate::config::apply_persisted_patch(cfg, &patch)
+        .map_err(|_| OversightPatchPolicyError::InvalidPatch("botness_weights".to_string()))?;
+    let groups = matched_groups_for_patch(
+        allowed_actions,
+        FAMILY,
+        patch.as_object().into_iter().flat_map(|patch| patch.keys()),
+    )?;
+
+    Ok(OversightPatchProposal {
+        patch_family: FAMILY.to_string(),
+        patch,
+        expected_impact,
+        confidence: confidence.to_string(),
+        required_verification: vec![
+            OVERSIGHT_VERIFICATION_WATCH_LIVE_BUDGET_WINDOW.to_string(),
+            OVERSIGHT_VERIFICATION_RERUN_ADVERSARY_SIM.to_string(),
+        ],
+        controller_status: dominant_group_value(groups.as_slice(), |group| {
+            &group.controller_status
+        }),
+        canary_requirement: dominant_group_value(groups.as_slice(), |group| {
+            &group.canary_requirement
+        }),
+        matched_group_ids: groups.iter().map(|group| group.group_id.clone()).collect(),
+        note: groups
+            .iter()
+            .map(|group| group.note.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    })
+}
+
 fn candidate_priority(problem_class: OversightProblemClass) -> &'static [&'static str] {
     match problem_class {
         OversightProblemClass::LikelyHumanFrictionOverspend => &[
@@ -612,7 +688,8 @@ fn step_numeric_path(
 #[cfg(test)]
 mod tests {
     use super::{
-        propose_patch, rank_patch_candidates, OversightPatchPolicyError, OversightProblemClass,
+        propose_botness_weights_patch, propose_patch, rank_patch_candidates,
+        OversightPatchPolicyError, OversightProblemClass,
         OVERSIGHT_VERIFICATION_REVIEW_REPLAY_PROMOTION,
     };
     use crate::config::{allowed_actions_v1, defaults};
@@ -632,6 +709,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn calibrated_botness_weights_are_clamped_to_catalog_constraints() {
+        let mut cfg = defaults().clone();
+        cfg.botness_weights.geo_risk = 2;
+        cfg.botness_weights.rate_high = 3;
+
+        let proposal = propose_botness_weights_patch(
+            &cfg,
+            &allowed_actions_v1(),
+            &[("geo_risk", 2), ("rate_high", 40)],
+            "Reweight botness signals.".to_string(),
+            "medium",
+        )
+        .expect("proposal builds");
+
+        assert_eq!(proposal.patch_family, "botness");
+        assert_eq!(proposal.patch["botness_weights"]["rate_high"], 10);
+        assert!(proposal.patch["botness_weights"].get("geo_risk").is_none());
+        assert_eq!(proposal.matched_group_ids, vec!["botness.thresholds".to_string()]);
+        assert_eq!(proposal.canary_requirement, "required");
+
+        assert_eq!(
+            propose_botness_weights_patch(
+                &cfg,
+                &allowed_actions_v1(),
+                &[("geo_risk", 2)],
+                String::new(),
+                "medium",
+            ),
+            Err(OversightPatchPolicyError::NoBoundedPatch("botness".to_string()))
+        );
+        assert_eq!(
+            propose_botness_weights_patch(
+                &cfg,
+                &allowed_actions_v1(),
+                &[("browser_outdated", 4)],
+                String::new(),
+                "medium",
+            ),
+            Err(OversightPatchPolicyError::InvalidPatch(
+                "botness_weights.browser_outdated".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn suspicious_cost_policy_prefers_low_friction_signal_families_first() {
         let mut cfg = defaults().clone();
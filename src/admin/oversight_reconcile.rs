@@ -951,6 +951,7 @@ mod tests {
                 runtime_environment: "runtime_dev".to_string(),
                 gateway_deployment_profile: "shared_server".to_string(),
                 adversary_sim_available: true,
+                active_config_profile: None,
             },
             recent_changes: OperatorSnapshotRecentChanges::default(),
             budget_distance: OperatorBudgetDistanceSummary {
@@ -1063,6 +1064,7 @@ mod tests {
                 policy_tranche: OperatorSnapshotVerifiedIdentityPolicySummary::default(),
             },
             replay_promotion: ReplayPromotionSummary::not_materialized(),
+            alerts: Default::default(),
         }
     }
 
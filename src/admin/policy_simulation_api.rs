@@ -0,0 +1,72 @@
+use serde::Deserialize;
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::observability::request_facts_journal::{
+    load_recent_rows, REQUEST_FACTS_JOURNAL_RETENTION_HOURS,
+};
+
+const POLICY_SIMULATION_SCHEMA_VERSION: &str = "policy_simulation_v1";
+const POLICY_SIMULATION_DEFAULT_HOURS: u64 = 24;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicySimulationRequest {
+    patch: serde_json::Value,
+    #[serde(default)]
+    hours: Option<u64>,
+}
+
+pub(crate) fn handle_admin_policy_simulation(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if *req.method() != Method::Post {
+        return Response::new(405, "Method Not Allowed");
+    }
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => {
+            return Response::new(400, format!("Invalid policy-simulation payload: {}", err))
+        }
+    };
+    let request = match serde_json::from_value::<PolicySimulationRequest>(payload) {
+        Ok(request) => request,
+        Err(err) => {
+            return Response::new(400, format!("Invalid policy-simulation payload: {}", err))
+        }
+    };
+    let hours = request
+        .hours
+        .unwrap_or(POLICY_SIMULATION_DEFAULT_HOURS)
+        .clamp(1, REQUEST_FACTS_JOURNAL_RETENTION_HOURS);
+
+    let baseline = match crate::config::Config::load(store, site_id) {
+        Ok(cfg) => cfg,
+        Err(err) => return Response::new(500, err.user_message()),
+    };
+    let candidate = match crate::config::apply_persisted_patch(&baseline, &request.patch) {
+        Ok(cfg) => cfg,
+        Err(err) => return Response::new(400, err),
+    };
+
+    let rows = load_recent_rows(store, site_id, crate::admin::now_ts(), hours);
+    let report = crate::runtime::policy_simulation::simulate(&rows, site_id, &baseline, &candidate);
+    let body = serde_json::to_string(&json!({
+        "schema_version": POLICY_SIMULATION_SCHEMA_VERSION,
+        "window_hours": hours,
+        "journal_sample_percent": baseline.request_facts_journal_sample_percent,
+        "report": report
+    }))
+    .unwrap_or_else(|_| "{}".to_string());
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(body)
+        .build()
+}
@@ -12,6 +12,9 @@ pub(crate) struct OperatorSnapshotRecentChangeLedgerRow {
     pub(crate) change_reason: String,
     pub(crate) changed_families: Vec<String>,
     pub(crate) source: String,
+    /// Admin actor behind the change (`user:<name>` for named accounts).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) changed_by: Option<String>,
     pub(crate) targets: Vec<String>,
     pub(crate) change_summary: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -182,6 +185,7 @@ fn operator_snapshot_change_from_ledger_row(
         change_reason: row.change_reason.clone(),
         changed_families: row.changed_families.clone(),
         source: row.source.clone(),
+        changed_by: row.changed_by.clone(),
         targets: row.targets.clone(),
         decision_id: row.decision_id.clone(),
         decision_kind: decision.map(|decision| decision.decision_kind.clone()),
@@ -207,6 +211,11 @@ fn operator_snapshot_recent_change_source(admin_id: &str) -> String {
     }
 }
 
+fn operator_snapshot_recent_change_actor(admin_id: &str) -> Option<String> {
+    let admin_id = admin_id.trim();
+    (!admin_id.is_empty() && admin_id != "-").then(|| admin_id.to_string())
+}
+
 fn operator_snapshot_patch_requested_families(patch: &serde_json::Value) -> Vec<&'static str> {
     let Some(object) = patch.as_object() else {
         return Vec::new();
@@ -230,6 +239,7 @@ fn operator_snapshot_family_snapshot(
     match family {
         "shadow_mode" => json!({
             "shadow_mode": cfg.shadow_mode,
+            "shadow_policy_sources": cfg.shadow_policy_sources,
         }),
         "adversary_sim_config" => json!({
             "adversary_sim_duration_seconds": cfg.adversary_sim_duration_seconds,
@@ -439,6 +449,7 @@ pub(crate) fn operator_snapshot_config_patch_recent_change_row(
         change_reason: "config_patch".to_string(),
         changed_families: changed_families.clone(),
         source: operator_snapshot_recent_change_source(admin_id),
+        changed_by: operator_snapshot_recent_change_actor(admin_id),
         targets: operator_snapshot_targets_for_families(&changed_families),
         change_summary: truncate_operator_snapshot_change_summary(
             format!("config families updated: {}", changed_families.join(", ")).as_str(),
@@ -463,6 +474,7 @@ pub(crate) fn operator_snapshot_manual_change_row(
             .map(|family| family.to_string())
             .collect(),
         source: operator_snapshot_recent_change_source(admin_id),
+        changed_by: operator_snapshot_recent_change_actor(admin_id),
         targets: targets.iter().map(|target| target.to_string()).collect(),
         change_summary: truncate_operator_snapshot_change_summary(change_summary),
         decision_id: None,
@@ -95,7 +95,7 @@ fn handle_admin_replay_promotion_post(
             } else {
                 "materialized".to_string()
             }),
-            admin: Some(crate::admin::auth::get_admin_id(req)),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
         },
     );
 
@@ -0,0 +1,182 @@
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::bot_identity::native_http_message_signatures::{
+    directory_inventory, remove_pinned_directory, upload_pinned_directory,
+};
+use crate::bot_identity::policy::IdentityPinnedDirectory;
+
+/// GET lists every pinned or cached signature-agent directory with its freshness and key ids.
+/// POST pins a directory JWKS by upload; DELETE `?source_uri=` removes an uploaded pin.
+pub(crate) fn handle_admin_signature_directories(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    match *req.method() {
+        Method::Get => {
+            let cfg = match crate::config::load_runtime_cached(store, site_id) {
+                Ok(cfg) => cfg,
+                Err(err) => return Response::new(500, err.user_message()),
+            };
+            let directories = directory_inventory(store, site_id, &cfg, crate::admin::now_ts());
+            json_response(json!({ "directories": directories }))
+        }
+        Method::Post | Method::Delete => handle_admin_signature_directory_pin(req, store, site_id),
+        _ => Response::new(405, "Method Not Allowed"),
+    }
+}
+
+fn handle_admin_signature_directory_pin(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Response {
+    if !crate::config::admin_config_write_enabled() {
+        return Response::new(
+            403,
+            "Directory pin updates are disabled when SHUMA_ADMIN_CONFIG_WRITE_ENABLED=false",
+        );
+    }
+
+    let (reason, source_uri) = if *req.method() == Method::Delete {
+        let Some(source_uri) = crate::request_validation::query_param(req.query(), "source_uri")
+        else {
+            return Response::new(400, "source_uri query parameter is required");
+        };
+        if !remove_pinned_directory(store, site_id, source_uri.as_str()) {
+            return Response::new(404, "No uploaded pin for that directory");
+        }
+        ("verified_identity_directory_unpin", source_uri)
+    } else {
+        let payload = match crate::request_validation::parse_json_body(
+            req.body(),
+            crate::request_validation::MAX_ADMIN_JSON_BYTES,
+        ) {
+            Ok(value) => value,
+            Err(err) => return Response::new(400, format!("Invalid directory pin: {}", err)),
+        };
+        let pin = match serde_json::from_value::<IdentityPinnedDirectory>(payload) {
+            Ok(pin) => pin,
+            Err(err) => return Response::new(400, format!("Invalid directory pin: {}", err)),
+        };
+        if let Err(err) = upload_pinned_directory(store, site_id, &pin) {
+            return Response::new(400, format!("Invalid directory pin: {}", err));
+        }
+        ("verified_identity_directory_pin", pin.source_uri)
+    };
+
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: crate::admin::now_ts(),
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some(reason.to_string()),
+            outcome: Some(source_uri),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
+        },
+    );
+    json_response(json!({ "updated": true }))
+}
+
+fn json_response(body: serde_json::Value) -> Response {
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string()))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::handle_admin_signature_directories;
+    use crate::challenge::KeyValueStore;
+    use crate::test_support::InMemoryStore;
+    use spin_sdk::http::{Method, Request};
+
+    const SOURCE_URI: &str = "https://agents.example/.well-known/http-message-signatures-directory";
+
+    fn directories_request(method: Method, uri: &str, body: serde_json::Value) -> Request {
+        let mut builder = Request::builder();
+        builder
+            .method(method)
+            .uri(uri)
+            .body(serde_json::to_vec(&body).expect("body serializes"));
+        builder.build()
+    }
+
+    #[test]
+    fn uploaded_pins_are_listed_and_removed() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED", "true");
+        let store = InMemoryStore::default();
+        store
+            .set(
+                "config:default",
+                &serde_json::to_vec(crate::config::defaults()).expect("config serializes"),
+            )
+            .expect("config persists");
+        let path = "/shuma/admin/verified-identity/directories";
+
+        let pin = serde_json::json!({
+            "source_uri": SOURCE_URI,
+            "jwks": { "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": "JrQLj5P_89iXES9-vFgrIy29clF9CC_oPPsw3c5D0bs"
+            }] },
+            "operator": "Example Agents"
+        });
+        let resp = handle_admin_signature_directories(
+            &directories_request(Method::Post, path, pin),
+            &store,
+            "default",
+        );
+        assert_eq!(*resp.status(), 200);
+
+        let resp = handle_admin_signature_directories(
+            &directories_request(Method::Get, path, serde_json::Value::Null),
+            &store,
+            "default",
+        );
+        assert_eq!(*resp.status(), 200);
+        let payload: serde_json::Value = serde_json::from_slice(resp.body()).expect("json body");
+        let directory = &payload["directories"][0];
+        assert_eq!(directory["source_uri"], SOURCE_URI);
+        assert_eq!(directory["pin"], "upload");
+        assert_eq!(directory["freshness"], "pinned");
+        assert_eq!(directory["operator"], "Example Agents");
+        assert_eq!(
+            directory["key_ids"][0],
+            "poqkLGiymh_W0uP6PZFw-dvez3QJT5SolqXBCW38r0U"
+        );
+
+        let delete_uri = format!("{}?source_uri={}", path, SOURCE_URI);
+        let resp = handle_admin_signature_directories(
+            &directories_request(Method::Delete, delete_uri.as_str(), serde_json::Value::Null),
+            &store,
+            "default",
+        );
+        assert_eq!(*resp.status(), 200);
+        let resp = handle_admin_signature_directories(
+            &directories_request(Method::Delete, delete_uri.as_str(), serde_json::Value::Null),
+            &store,
+            "default",
+        );
+        assert_eq!(*resp.status(), 404);
+
+        let resp = handle_admin_signature_directories(
+            &directories_request(
+                Method::Post,
+                path,
+                serde_json::json!({ "source_uri": "http://agents.example/", "jwks": { "keys": [] } }),
+            ),
+            &store,
+            "default",
+        );
+        assert_eq!(*resp.status(), 400);
+        std::env::remove_var("SHUMA_ADMIN_CONFIG_WRITE_ENABLED");
+    }
+}
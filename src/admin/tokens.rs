@@ -0,0 +1,478 @@
+//! Scoped, expiring admin API tokens for automation.
+//!
+//! Tokens look like `shuma_at_<id>_<secret>`. Only the SHA-256 of the full token is stored, under
+//! `admin_token:v1:<id>`, so a KV read never yields a usable credential; the id lets a bearer
+//! lookup go straight to its record. Revoked tokens keep their record for the audit trail.
+
+use rand::Rng as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::challenge::KeyValueStore;
+
+const ADMIN_TOKEN_KEY_PREFIX: &str = "admin_token:v1:";
+const ADMIN_TOKEN_PREFIX: &str = "shuma_at_";
+const ADMIN_TOKEN_ID_BYTES: usize = 8;
+const ADMIN_TOKEN_SECRET_BYTES: usize = 32;
+const ADMIN_TOKEN_NAME_MAX_CHARS: usize = 64;
+const ADMIN_TOKEN_DEFAULT_TTL_SECONDS: u64 = 90 * 24 * 3600;
+const ADMIN_TOKEN_MAX_TTL_SECONDS: u64 = 365 * 24 * 3600;
+const ADMIN_TOKEN_MAX_ACTIVE: usize = 64;
+const ADMIN_TOKEN_MAX_IP_ALLOWLIST_ENTRIES: usize = 32;
+/// Last-used stamps are coalesced so a busy token does not write KV on every request.
+const ADMIN_TOKEN_LAST_USED_RESOLUTION_SECONDS: u64 = 60;
+
+/// Scopes a token may carry. `<resource>:write` implies `<resource>:read`.
+pub(crate) const ADMIN_TOKEN_SCOPES: [&str; 7] = [
+    "bans:read",
+    "bans:write",
+    "monitoring:read",
+    "config:read",
+    "config:write",
+    "sim:read",
+    "sim:write",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AdminApiToken {
+    pub token_id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub created_at_ts: u64,
+    pub expires_at_ts: u64,
+    #[serde(default)]
+    pub ip_allowlist: Vec<String>,
+    #[serde(default)]
+    pub last_used_ts: Option<u64>,
+    #[serde(default)]
+    pub last_used_ip: Option<String>,
+    #[serde(default)]
+    pub revoked_at_ts: Option<u64>,
+}
+
+impl AdminApiToken {
+    pub(crate) fn status(&self, now: u64) -> &'static str {
+        if self.revoked_at_ts.is_some() {
+            "revoked"
+        } else if self.expires_at_ts <= now {
+            "expired"
+        } else {
+            "active"
+        }
+    }
+}
+
+/// Token view returned by the admin API; never carries the token hash.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AdminApiTokenSummary {
+    pub token_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub status: &'static str,
+    pub created_at_ts: u64,
+    pub expires_at_ts: u64,
+    pub ip_allowlist: Vec<String>,
+    pub last_used_ts: Option<u64>,
+    pub last_used_ip: Option<String>,
+    pub revoked_at_ts: Option<u64>,
+}
+
+impl AdminApiTokenSummary {
+    pub(crate) fn new(token: &AdminApiToken, now: u64) -> Self {
+        Self {
+            token_id: token.token_id.clone(),
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+            status: token.status(now),
+            created_at_ts: token.created_at_ts,
+            expires_at_ts: token.expires_at_ts,
+            ip_allowlist: token.ip_allowlist.clone(),
+            last_used_ts: token.last_used_ts,
+            last_used_ip: token.last_used_ip.clone(),
+            revoked_at_ts: token.revoked_at_ts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AdminApiTokenCreateRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub expires_in_seconds: Option<u64>,
+    #[serde(default)]
+    pub ip_allowlist: Vec<String>,
+}
+
+/// What an authenticated token may do, carried on `AdminAuthResult`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminTokenGrant {
+    pub token_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+impl AdminTokenGrant {
+    pub fn grants_scope(&self, required: &str) -> bool {
+        if self.scopes.iter().any(|scope| scope == required) {
+            return true;
+        }
+        required
+            .strip_suffix(":read")
+            .map(|resource| {
+                self.scopes
+                    .iter()
+                    .any(|scope| scope.strip_suffix(":write") == Some(resource))
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn has_write_scope(&self) -> bool {
+        self.scopes.iter().any(|scope| scope.ends_with(":write"))
+    }
+}
+
+impl From<&AdminApiToken> for AdminTokenGrant {
+    fn from(token: &AdminApiToken) -> Self {
+        Self {
+            token_id: token.token_id.clone(),
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+        }
+    }
+}
+
+fn token_key(token_id: &str) -> String {
+    format!("{}{}", ADMIN_TOKEN_KEY_PREFIX, token_id)
+}
+
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn random_hex(num_bytes: usize) -> String {
+    let mut bytes = vec![0u8; num_bytes];
+    rand::rng().fill(bytes.as_mut_slice());
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Cheap shape check used before the KV lookup.
+pub(crate) fn looks_like_admin_api_token(candidate: &str) -> bool {
+    parse_token_id(candidate).is_some()
+}
+
+fn parse_token_id(candidate: &str) -> Option<&str> {
+    let rest = candidate.strip_prefix(ADMIN_TOKEN_PREFIX)?;
+    let (token_id, secret) = rest.split_once('_')?;
+    let is_hex = |value: &str, bytes: usize| {
+        value.len() == bytes * 2 && value.chars().all(|ch| ch.is_ascii_hexdigit())
+    };
+    (is_hex(token_id, ADMIN_TOKEN_ID_BYTES) && is_hex(secret, ADMIN_TOKEN_SECRET_BYTES))
+        .then_some(token_id)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes()
+        .zip(b.bytes())
+        .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+        == 0
+}
+
+pub(crate) fn load_admin_api_token<S: KeyValueStore>(
+    store: &S,
+    token_id: &str,
+) -> Option<AdminApiToken> {
+    let raw = store.get(token_key(token_id).as_str()).ok()??;
+    serde_json::from_slice::<AdminApiToken>(raw.as_slice()).ok()
+}
+
+fn save_admin_api_token<S: KeyValueStore>(store: &S, token: &AdminApiToken) -> Result<(), ()> {
+    let value = serde_json::to_vec(token).map_err(|_| ())?;
+    store.set(token_key(token.token_id.as_str()).as_str(), value.as_slice())
+}
+
+pub(crate) fn list_admin_api_tokens<S: KeyValueStore>(store: &S) -> Vec<AdminApiToken> {
+    let mut tokens: Vec<AdminApiToken> = store
+        .get_keys()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|key| key.strip_prefix(ADMIN_TOKEN_KEY_PREFIX))
+        .filter_map(|token_id| load_admin_api_token(store, token_id))
+        .collect();
+    tokens.sort_by(|a, b| {
+        a.created_at_ts
+            .cmp(&b.created_at_ts)
+            .then_with(|| a.token_id.cmp(&b.token_id))
+    });
+    tokens
+}
+
+fn validate_ip_allowlist_entry(entry: &str) -> bool {
+    entry.parse::<std::net::IpAddr>().is_ok() || entry.parse::<ipnet::IpNet>().is_ok()
+}
+
+/// Issues a token and returns its record together with the plaintext, which is shown only once.
+pub(crate) fn create_admin_api_token<S: KeyValueStore>(
+    store: &S,
+    request: AdminApiTokenCreateRequest,
+    now: u64,
+) -> Result<(AdminApiToken, String), String> {
+    let name = request.name.trim().to_string();
+    if name.is_empty()
+        || name.chars().count() > ADMIN_TOKEN_NAME_MAX_CHARS
+        || name.chars().any(char::is_control)
+    {
+        return Err(format!(
+            "name must be 1-{} printable characters",
+            ADMIN_TOKEN_NAME_MAX_CHARS
+        ));
+    }
+    let mut scopes: Vec<String> = request
+        .scopes
+        .iter()
+        .map(|scope| scope.trim().to_ascii_lowercase())
+        .collect();
+    scopes.sort();
+    scopes.dedup();
+    if scopes.is_empty() {
+        return Err("at least one scope is required".to_string());
+    }
+    if let Some(unknown) = scopes
+        .iter()
+        .find(|scope| !ADMIN_TOKEN_SCOPES.contains(&scope.as_str()))
+    {
+        return Err(format!(
+            "unknown scope {} (expected one of {})",
+            unknown,
+            ADMIN_TOKEN_SCOPES.join(", ")
+        ));
+    }
+    let ttl = request
+        .expires_in_seconds
+        .unwrap_or(ADMIN_TOKEN_DEFAULT_TTL_SECONDS);
+    if ttl == 0 || ttl > ADMIN_TOKEN_MAX_TTL_SECONDS {
+        return Err(format!(
+            "expires_in_seconds must be 1-{}",
+            ADMIN_TOKEN_MAX_TTL_SECONDS
+        ));
+    }
+    let ip_allowlist: Vec<String> = request
+        .ip_allowlist
+        .iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    if ip_allowlist.len() > ADMIN_TOKEN_MAX_IP_ALLOWLIST_ENTRIES {
+        return Err(format!(
+            "ip_allowlist supports at most {} entries",
+            ADMIN_TOKEN_MAX_IP_ALLOWLIST_ENTRIES
+        ));
+    }
+    if let Some(invalid) = ip_allowlist
+        .iter()
+        .find(|entry| !validate_ip_allowlist_entry(entry))
+    {
+        return Err(format!("invalid ip_allowlist entry {}", invalid));
+    }
+    let active = list_admin_api_tokens(store)
+        .iter()
+        .filter(|token| token.status(now) == "active")
+        .count();
+    if active >= ADMIN_TOKEN_MAX_ACTIVE {
+        return Err(format!(
+            "at most {} active admin tokens are supported",
+            ADMIN_TOKEN_MAX_ACTIVE
+        ));
+    }
+
+    let token_id = random_hex(ADMIN_TOKEN_ID_BYTES);
+    let plaintext = format!(
+        "{}{}_{}",
+        ADMIN_TOKEN_PREFIX,
+        token_id,
+        random_hex(ADMIN_TOKEN_SECRET_BYTES)
+    );
+    let token = AdminApiToken {
+        token_id,
+        name,
+        token_hash: token_hash(plaintext.as_str()),
+        scopes,
+        created_at_ts: now,
+        expires_at_ts: now.saturating_add(ttl),
+        ip_allowlist,
+        last_used_ts: None,
+        last_used_ip: None,
+        revoked_at_ts: None,
+    };
+    save_admin_api_token(store, &token).map_err(|_| "failed persisting admin token".to_string())?;
+    Ok((token, plaintext))
+}
+
+/// Marks a token revoked. Returns `Ok(false)` when no such token exists.
+pub(crate) fn revoke_admin_api_token<S: KeyValueStore>(
+    store: &S,
+    token_id: &str,
+    now: u64,
+) -> Result<bool, ()> {
+    let Some(mut token) = load_admin_api_token(store, token_id) else {
+        return Ok(false);
+    };
+    if token.revoked_at_ts.is_none() {
+        token.revoked_at_ts = Some(now);
+        save_admin_api_token(store, &token)?;
+    }
+    Ok(true)
+}
+
+/// Resolves a bearer candidate to an active token usable from `client_ip`.
+pub(crate) fn verify_admin_api_token<S: KeyValueStore>(
+    store: &S,
+    candidate: &str,
+    client_ip: &str,
+    now: u64,
+) -> Option<AdminApiToken> {
+    let token_id = parse_token_id(candidate)?;
+    let token = load_admin_api_token(store, token_id)?;
+    if !constant_time_eq(token_hash(candidate).as_str(), token.token_hash.as_str()) {
+        return None;
+    }
+    if token.status(now) != "active" {
+        return None;
+    }
+    if !token.ip_allowlist.is_empty()
+        && !crate::signals::allowlist::is_allowlisted(client_ip, &token.ip_allowlist)
+    {
+        return None;
+    }
+    Some(token)
+}
+
+pub(crate) fn record_admin_api_token_use<S: KeyValueStore>(
+    store: &S,
+    token: &AdminApiToken,
+    client_ip: &str,
+    now: u64,
+) {
+    // At most one write per token per resolution window, whichever address used it.
+    let recent = token
+        .last_used_ts
+        .map(|ts| now.saturating_sub(ts) < ADMIN_TOKEN_LAST_USED_RESOLUTION_SECONDS)
+        .unwrap_or(false);
+    if recent {
+        return;
+    }
+    let mut updated = token.clone();
+    updated.last_used_ts = Some(now);
+    updated.last_used_ip = Some(client_ip.to_string());
+    if save_admin_api_token(store, &updated).is_err() {
+        eprintln!(
+            "[admin] failed recording last use for admin token {}",
+            token.token_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStore;
+
+    fn create_request(scopes: &[&str], ip_allowlist: &[&str]) -> AdminApiTokenCreateRequest {
+        AdminApiTokenCreateRequest {
+            name: "ci deploy".to_string(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_in_seconds: Some(3600),
+            ip_allowlist: ip_allowlist.iter().map(|entry| entry.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn tokens_are_stored_hashed_and_verified_until_expiry_or_revocation() {
+        let store = InMemoryStore::default();
+        let (token, plaintext) =
+            create_admin_api_token(&store, create_request(&["bans:write"], &[]), 1_000)
+                .expect("token created");
+        assert!(plaintext.starts_with("shuma_at_"));
+        assert!(looks_like_admin_api_token(plaintext.as_str()));
+        let raw = store
+            .get(token_key(token.token_id.as_str()).as_str())
+            .unwrap()
+            .unwrap();
+        assert!(!String::from_utf8(raw).unwrap().contains(plaintext.as_str()));
+
+        assert!(verify_admin_api_token(&store, plaintext.as_str(), "198.51.100.1", 1_500).is_some());
+        let last = if plaintext.ends_with('0') { '1' } else { '0' };
+        let forged = format!("{}{}", &plaintext[..plaintext.len() - 1], last);
+        assert!(verify_admin_api_token(&store, forged.as_str(), "198.51.100.1", 1_500).is_none());
+        assert!(verify_admin_api_token(&store, plaintext.as_str(), "198.51.100.1", 4_600).is_none());
+
+        assert_eq!(
+            revoke_admin_api_token(&store, token.token_id.as_str(), 2_000),
+            Ok(true)
+        );
+        assert!(verify_admin_api_token(&store, plaintext.as_str(), "198.51.100.1", 2_001).is_none());
+        assert_eq!(
+            load_admin_api_token(&store, token.token_id.as_str())
+                .map(|token| token.status(2_001)),
+            Some("revoked")
+        );
+    }
+
+    #[test]
+    fn token_ip_allowlist_and_last_use_are_enforced_and_tracked() {
+        let store = InMemoryStore::default();
+        let (token, plaintext) = create_admin_api_token(
+            &store,
+            create_request(&["monitoring:read"], &["203.0.113.0/24"]),
+            1_000,
+        )
+        .expect("token created");
+        assert!(verify_admin_api_token(&store, plaintext.as_str(), "198.51.100.1", 1_100).is_none());
+        let verified = verify_admin_api_token(&store, plaintext.as_str(), "203.0.113.9", 1_100)
+            .expect("allowlisted ip");
+        record_admin_api_token_use(&store, &verified, "203.0.113.9", 1_100);
+        let stored = load_admin_api_token(&store, token.token_id.as_str()).unwrap();
+        assert_eq!(stored.last_used_ts, Some(1_100));
+        assert_eq!(stored.last_used_ip.as_deref(), Some("203.0.113.9"));
+
+        // Uses inside the resolution window are not written, even from another address.
+        record_admin_api_token_use(&store, &stored, "203.0.113.10", 1_130);
+        let stored = load_admin_api_token(&store, token.token_id.as_str()).unwrap();
+        assert_eq!(stored.last_used_ts, Some(1_100));
+        assert_eq!(stored.last_used_ip.as_deref(), Some("203.0.113.9"));
+
+        record_admin_api_token_use(&store, &stored, "203.0.113.10", 1_160);
+        let stored = load_admin_api_token(&store, token.token_id.as_str()).unwrap();
+        assert_eq!(stored.last_used_ts, Some(1_160));
+        assert_eq!(stored.last_used_ip.as_deref(), Some("203.0.113.10"));
+    }
+
+    #[test]
+    fn token_requests_reject_unknown_scopes_and_bad_allowlists() {
+        let store = InMemoryStore::default();
+        assert!(create_admin_api_token(&store, create_request(&["accounts:write"], &[]), 1).is_err());
+        assert!(create_admin_api_token(&store, create_request(&[], &[]), 1).is_err());
+        assert!(
+            create_admin_api_token(&store, create_request(&["bans:read"], &["not-an-ip"]), 1)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn write_scope_implies_read_of_the_same_resource_only() {
+        let grant = AdminTokenGrant {
+            token_id: "a".to_string(),
+            name: "a".to_string(),
+            scopes: vec!["config:write".to_string()],
+        };
+        assert!(grant.grants_scope("config:write"));
+        assert!(grant.grants_scope("config:read"));
+        assert!(!grant.grants_scope("bans:read"));
+        assert!(grant.has_write_scope());
+    }
+}
@@ -0,0 +1,180 @@
+use serde_json::json;
+use spin_sdk::http::{Method, Request, Response};
+
+use crate::admin::tokens::{
+    create_admin_api_token, list_admin_api_tokens, revoke_admin_api_token,
+    AdminApiTokenCreateRequest, AdminApiTokenSummary, ADMIN_TOKEN_SCOPES,
+};
+
+pub(crate) fn handle_admin_tokens(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+) -> Response {
+    match *req.method() {
+        Method::Get => {
+            let now = crate::admin::now_ts();
+            let tokens: Vec<AdminApiTokenSummary> = list_admin_api_tokens(store)
+                .iter()
+                .map(|token| AdminApiTokenSummary::new(token, now))
+                .collect();
+            json_response(json!({
+                "tokens": tokens,
+                "available_scopes": ADMIN_TOKEN_SCOPES,
+            }))
+        }
+        Method::Post => handle_admin_tokens_create(req, store),
+        Method::Delete => handle_admin_tokens_revoke(req, store),
+        _ => Response::new(405, "Method Not Allowed"),
+    }
+}
+
+fn json_response(body: serde_json::Value) -> Response {
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .body(serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string()))
+        .build()
+}
+
+fn log_token_action(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+    reason: &str,
+    outcome: String,
+) {
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: crate::admin::now_ts(),
+            event: crate::admin::EventType::AdminAction,
+            ip: None,
+            reason: Some(reason.to_string()),
+            outcome: Some(outcome),
+            admin: Some(crate::admin::auth::get_admin_id(req, store)),
+        },
+    );
+}
+
+fn handle_admin_tokens_create(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+) -> Response {
+    let payload = match crate::request_validation::parse_json_body(
+        req.body(),
+        crate::request_validation::MAX_ADMIN_JSON_BYTES,
+    ) {
+        Ok(value) => value,
+        Err(err) => return Response::new(400, format!("Invalid token payload: {}", err)),
+    };
+    let request = match serde_json::from_value::<AdminApiTokenCreateRequest>(payload) {
+        Ok(request) => request,
+        Err(err) => return Response::new(400, format!("Invalid token payload: {}", err)),
+    };
+    let now = crate::admin::now_ts();
+    let (token, plaintext) = match create_admin_api_token(store, request, now) {
+        Ok(created) => created,
+        Err(err) => return Response::new(400, err),
+    };
+
+    log_token_action(
+        req,
+        store,
+        "admin_token_create",
+        format!(
+            "token_id={} name={} scopes={} expires_at={}",
+            token.token_id,
+            token.name,
+            token.scopes.join(","),
+            token.expires_at_ts
+        ),
+    );
+    json_response(json!({
+        "created": true,
+        "token": plaintext,
+        "details": AdminApiTokenSummary::new(&token, now),
+    }))
+}
+
+fn handle_admin_tokens_revoke(
+    req: &Request,
+    store: &impl crate::challenge::KeyValueStore,
+) -> Response {
+    let Some(token_id) = crate::request_validation::query_param(req.query(), "token_id") else {
+        return Response::new(400, "Missing token_id");
+    };
+    let token_id = token_id.trim().to_ascii_lowercase();
+    match revoke_admin_api_token(store, token_id.as_str(), crate::admin::now_ts()) {
+        Ok(true) => {
+            log_token_action(
+                req,
+                store,
+                "admin_token_revoke",
+                format!("token_id={}", token_id),
+            );
+            json_response(json!({ "revoked": true, "token_id": token_id }))
+        }
+        Ok(false) => Response::new(404, "Token not found"),
+        Err(()) => Response::new(500, "Failed revoking admin token"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::handle_admin_tokens;
+    use crate::test_support::InMemoryStore;
+    use spin_sdk::http::{Method, Request};
+
+    fn tokens_request(method: Method, uri: &str, body: serde_json::Value) -> Request {
+        let mut builder = Request::builder();
+        builder
+            .method(method)
+            .uri(uri)
+            .body(serde_json::to_vec(&body).expect("body serializes"));
+        builder.build()
+    }
+
+    #[test]
+    fn tokens_endpoint_issues_once_lists_without_secrets_and_revokes() {
+        let _lock = crate::test_support::lock_env();
+        let store = InMemoryStore::default();
+
+        let req = tokens_request(
+            Method::Post,
+            "/shuma/admin/tokens",
+            serde_json::json!({
+                "name": "soar playbook",
+                "scopes": ["bans:write", "monitoring:read"],
+                "expires_in_seconds": 86400,
+                "ip_allowlist": ["198.51.100.0/24"]
+            }),
+        );
+        let resp = handle_admin_tokens(&req, &store);
+        assert_eq!(*resp.status(), 200);
+        let payload: serde_json::Value = serde_json::from_slice(resp.body()).expect("json body");
+        let plaintext = payload["token"].as_str().expect("plaintext token").to_string();
+        let token_id = payload["details"]["token_id"]
+            .as_str()
+            .expect("token id")
+            .to_string();
+        assert_eq!(payload["details"]["status"], "active");
+
+        let req = tokens_request(Method::Get, "/shuma/admin/tokens", serde_json::Value::Null);
+        let body = String::from_utf8(handle_admin_tokens(&req, &store).body().to_vec())
+            .expect("utf8 body");
+        assert!(body.contains(token_id.as_str()));
+        assert!(!body.contains(plaintext.as_str()));
+        assert!(!body.contains("token_hash"));
+
+        let req = tokens_request(
+            Method::Delete,
+            format!("/shuma/admin/tokens?token_id={}", token_id).as_str(),
+            serde_json::Value::Null,
+        );
+        assert_eq!(*handle_admin_tokens(&req, &store).status(), 200);
+        let req = tokens_request(Method::Get, "/shuma/admin/tokens", serde_json::Value::Null);
+        let payload: serde_json::Value =
+            serde_json::from_slice(handle_admin_tokens(&req, &store).body()).expect("json body");
+        assert_eq!(payload["tokens"][0]["status"], "revoked");
+    }
+}
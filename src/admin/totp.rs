@@ -0,0 +1,492 @@
+//! Optional TOTP (RFC 6238) second factor for admin principals.
+//!
+//! A principal is the identity a credential speaks for: `user:<username>` for named accounts and
+//! OIDC users, or `shared_key` for `SHUMA_API_KEY`. Scoped API tokens and the read-only key have no
+//! principal and cannot enrol. Enrolments live in KV under `admin_totp:v1:<principal>` and only
+//! take effect once a first code confirms them. Codes are the authenticator-app default: HMAC-SHA1,
+//! 6 digits and 30-second steps, with one step of drift allowed either way. A step can be used
+//! only once. Ten single-use recovery codes are issued at confirmation and stored as SHA-256
+//! hashes.
+
+use rand::Rng as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::auth::{AdminAuthMethod, AdminAuthResult};
+use crate::challenge::KeyValueStore;
+
+const ADMIN_TOTP_KEY_PREFIX: &str = "admin_totp:v1:";
+pub(crate) const SHARED_KEY_PRINCIPAL: &str = "shared_key";
+const TOTP_ISSUER: &str = "Shuma-Gorath";
+const TOTP_SECRET_BYTES: usize = 20;
+const TOTP_STEP_SECONDS: u64 = 30;
+const TOTP_DIGITS: u32 = 6;
+const TOTP_ALLOWED_DRIFT_STEPS: u64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_BYTES: usize = 5;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AdminTotpEnrollment {
+    pub principal: String,
+    /// Base32 shared secret, as shown to the authenticator app.
+    pub secret: String,
+    pub confirmed: bool,
+    pub created_at_ts: u64,
+    #[serde(default)]
+    pub confirmed_at_ts: Option<u64>,
+    #[serde(default)]
+    pub recovery_code_hashes: Vec<String>,
+    /// Highest TOTP step accepted so far; earlier or equal steps are replays.
+    #[serde(default)]
+    pub last_used_step: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct AdminTotpStatus {
+    pub principal: String,
+    pub enrolled: bool,
+    pub pending_confirmation: bool,
+    pub recovery_codes_remaining: usize,
+}
+
+/// The principal whose enrolment governs `auth`, if the credential can carry a second factor.
+pub(crate) fn admin_mfa_principal(auth: &AdminAuthResult) -> Option<String> {
+    if auth.token.is_some() || auth.method.is_none() {
+        return None;
+    }
+    if let Some(username) = auth.username.as_deref() {
+        return Some(account_principal(username));
+    }
+    match (auth.method, auth.is_write_authorized()) {
+        (Some(AdminAuthMethod::BearerToken | AdminAuthMethod::SessionCookie), true) => {
+            Some(SHARED_KEY_PRINCIPAL.to_string())
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn account_principal(username: &str) -> String {
+    format!("user:{}", username)
+}
+
+fn enrollment_key(principal: &str) -> String {
+    format!("{}{}", ADMIN_TOTP_KEY_PREFIX, principal)
+}
+
+pub(crate) fn load_admin_totp_enrollment<S: KeyValueStore>(
+    store: &S,
+    principal: &str,
+) -> Option<AdminTotpEnrollment> {
+    let raw = store.get(enrollment_key(principal).as_str()).ok()??;
+    serde_json::from_slice(raw.as_slice()).ok()
+}
+
+fn save_enrollment<S: KeyValueStore>(
+    store: &S,
+    enrollment: &AdminTotpEnrollment,
+) -> Result<(), ()> {
+    let value = serde_json::to_vec(enrollment).map_err(|_| ())?;
+    store.set(
+        enrollment_key(enrollment.principal.as_str()).as_str(),
+        value.as_slice(),
+    )
+}
+
+pub(crate) fn is_admin_totp_enrolled<S: KeyValueStore>(store: &S, principal: &str) -> bool {
+    load_admin_totp_enrollment(store, principal)
+        .map(|enrollment| enrollment.confirmed)
+        .unwrap_or(false)
+}
+
+pub(crate) fn admin_totp_status<S: KeyValueStore>(store: &S, principal: &str) -> AdminTotpStatus {
+    let enrollment = load_admin_totp_enrollment(store, principal);
+    AdminTotpStatus {
+        principal: principal.to_string(),
+        enrolled: enrollment.as_ref().is_some_and(|e| e.confirmed),
+        pending_confirmation: enrollment.as_ref().is_some_and(|e| !e.confirmed),
+        recovery_codes_remaining: enrollment
+            .as_ref()
+            .map(|e| e.recovery_code_hashes.len())
+            .unwrap_or(0),
+    }
+}
+
+/// Starts (or restarts) an unconfirmed enrolment and returns `(secret, otpauth_uri)`.
+pub(crate) fn begin_admin_totp_enrollment<S: KeyValueStore>(
+    store: &S,
+    principal: &str,
+    now: u64,
+) -> Result<(String, String), String> {
+    if is_admin_totp_enrolled(store, principal) {
+        return Err("a second factor is already enrolled; disable it first".to_string());
+    }
+    let mut secret = [0u8; TOTP_SECRET_BYTES];
+    rand::rng().fill(&mut secret[..]);
+    let enrollment = AdminTotpEnrollment {
+        principal: principal.to_string(),
+        secret: base32_encode(&secret),
+        confirmed: false,
+        created_at_ts: now,
+        confirmed_at_ts: None,
+        recovery_code_hashes: Vec::new(),
+        last_used_step: 0,
+    };
+    save_enrollment(store, &enrollment).map_err(|_| "failed to store enrolment".to_string())?;
+    let label = percent_encoding::utf8_percent_encode(
+        format!("{}:{}", TOTP_ISSUER, principal).as_str(),
+        percent_encoding::NON_ALPHANUMERIC,
+    )
+    .to_string();
+    let uri = format!(
+        "otpauth://totp/{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        label, enrollment.secret, TOTP_ISSUER, TOTP_DIGITS, TOTP_STEP_SECONDS
+    );
+    Ok((enrollment.secret, uri))
+}
+
+/// Confirms a pending enrolment with a first code and returns the plaintext recovery codes.
+pub(crate) fn confirm_admin_totp_enrollment<S: KeyValueStore>(
+    store: &S,
+    principal: &str,
+    code: &str,
+    now: u64,
+) -> Result<Vec<String>, String> {
+    let Some(mut enrollment) = load_admin_totp_enrollment(store, principal) else {
+        return Err("no pending enrolment; start one first".to_string());
+    };
+    if enrollment.confirmed {
+        return Err("a second factor is already enrolled".to_string());
+    }
+    let Some(step) = matching_totp_step(&enrollment, code, now) else {
+        return Err("invalid code".to_string());
+    };
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+            rand::rng().fill(&mut bytes[..]);
+            let encoded = base32_encode(&bytes).to_ascii_lowercase();
+            format!("{}-{}", &encoded[..4], &encoded[4..])
+        })
+        .collect();
+    enrollment.confirmed = true;
+    enrollment.confirmed_at_ts = Some(now);
+    enrollment.last_used_step = step;
+    enrollment.recovery_code_hashes = recovery_codes
+        .iter()
+        .map(|code| recovery_code_hash(code))
+        .collect();
+    save_enrollment(store, &enrollment).map_err(|_| "failed to store enrolment".to_string())?;
+    Ok(recovery_codes)
+}
+
+/// Checks a TOTP or recovery code for an enrolled principal, consuming it on success.
+pub(crate) fn verify_admin_second_factor<S: KeyValueStore>(
+    store: &S,
+    principal: &str,
+    code: &str,
+    now: u64,
+) -> bool {
+    let Some(mut enrollment) = load_admin_totp_enrollment(store, principal) else {
+        return false;
+    };
+    if !enrollment.confirmed {
+        return false;
+    }
+    if let Some(step) = matching_totp_step(&enrollment, code, now) {
+        enrollment.last_used_step = step;
+        return save_enrollment(store, &enrollment).is_ok();
+    }
+    let candidate = recovery_code_hash(code);
+    let before = enrollment.recovery_code_hashes.len();
+    enrollment
+        .recovery_code_hashes
+        .retain(|hash| !super::auth::constant_time_eq(hash.as_str(), candidate.as_str()));
+    if enrollment.recovery_code_hashes.len() == before {
+        return false;
+    }
+    save_enrollment(store, &enrollment).is_ok()
+}
+
+/// Removes a principal's enrolment. Returns `Ok(false)` when there was none.
+pub(crate) fn delete_admin_totp_enrollment<S: KeyValueStore>(
+    store: &S,
+    principal: &str,
+) -> Result<bool, ()> {
+    if load_admin_totp_enrollment(store, principal).is_none() {
+        return Ok(false);
+    }
+    store.delete(enrollment_key(principal).as_str())?;
+    Ok(true)
+}
+
+fn recovery_code_hash(code: &str) -> String {
+    let normalized: String = code
+        .trim()
+        .chars()
+        .filter(|ch| *ch != '-' && !ch.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn matching_totp_step(enrollment: &AdminTotpEnrollment, code: &str, now: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    let secret = base32_decode(enrollment.secret.as_str())?;
+    let current = now / TOTP_STEP_SECONDS;
+    let first = current.saturating_sub(TOTP_ALLOWED_DRIFT_STEPS);
+    (first..=current + TOTP_ALLOWED_DRIFT_STEPS)
+        .filter(|step| *step > enrollment.last_used_step)
+        .find(|step| {
+            let expected = format!(
+                "{:0width$}",
+                totp_code(secret.as_slice(), *step, TOTP_DIGITS),
+                width = TOTP_DIGITS as usize
+            );
+            super::auth::constant_time_eq(expected.as_str(), code)
+        })
+}
+
+fn totp_code(secret: &[u8], step: u64, digits: u32) -> u32 {
+    let mac = hmac_sha1(secret, &step.to_be_bytes());
+    let offset = (mac[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        mac[offset],
+        mac[offset + 1],
+        mac[offset + 2],
+        mac[offset + 3],
+    ]) & 0x7fff_ffff;
+    binary % 10u32.pow(digits)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for ch in encoded.trim_end_matches('=').chars() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|candidate| *candidate as char == ch.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+// Authenticator apps only universally support SHA-1 TOTP, and no SHA-1 crate is vendored here,
+// so the hash and HMAC are implemented directly (RFC 3174 / RFC 2104).
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [
+        0x6745_2301,
+        0xefcd_ab89,
+        0x98ba_dcfe,
+        0x1032_5476,
+        0xc3d2_e1f0,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_be_bytes());
+    for block in message.chunks_exact(64) {
+        let mut schedule = [0u32; 80];
+        for (slot, word) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *slot = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for index in 16..80 {
+            schedule[index] = (schedule[index - 3]
+                ^ schedule[index - 8]
+                ^ schedule[index - 14]
+                ^ schedule[index - 16])
+                .rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (index, word) in schedule.iter().enumerate() {
+            let (f, k) = match index {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (slot, value) in state.iter_mut().zip([a, b, c, d, e]) {
+            *slot = slot.wrapping_add(value);
+        }
+    }
+    let mut digest = [0u8; 20];
+    for (chunk, value) in digest.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; 20] {
+    let mut block_key = [0u8; 64];
+    if key.len() > 64 {
+        block_key[..20].copy_from_slice(&sha1(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let mut inner: Vec<u8> = block_key.iter().map(|byte| byte ^ 0x36).collect();
+    inner.extend_from_slice(message);
+    let mut outer: Vec<u8> = block_key.iter().map(|byte| byte ^ 0x5c).collect();
+    outer.extend_from_slice(&sha1(inner.as_slice()));
+    sha1(outer.as_slice())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{
+        base32_decode, base32_encode, begin_admin_totp_enrollment, confirm_admin_totp_enrollment,
+        hmac_sha1, is_admin_totp_enrolled, sha1, totp_code, verify_admin_second_factor,
+        TOTP_DIGITS, TOTP_STEP_SECONDS,
+    };
+    use crate::test_support::InMemoryStore;
+
+    /// Computes the code an authenticator app would show for `secret` at `now`.
+    pub(crate) fn current_totp_code(secret: &str, now: u64) -> String {
+        let secret = base32_decode(secret).expect("base32 secret");
+        format!(
+            "{:06}",
+            totp_code(secret.as_slice(), now / TOTP_STEP_SECONDS, TOTP_DIGITS)
+        )
+    }
+
+    #[test]
+    fn sha1_hmac_and_totp_match_rfc_vectors() {
+        let hex = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        };
+        assert_eq!(
+            hex(&sha1(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hex(&hmac_sha1(b"Jefe", b"what do ya want for nothing?")),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        );
+        // RFC 6238 appendix B, SHA-1 column.
+        let secret = b"12345678901234567890";
+        for (time, expected) in [
+            (59u64, 94_287_082u32),
+            (1_111_111_109, 7_081_804),
+            (1_234_567_890, 89_005_924),
+            (20_000_000_000, 65_353_130),
+        ] {
+            assert_eq!(totp_code(secret, time / 30, 8), expected);
+        }
+        assert_eq!(base32_encode(secret), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(
+            base32_decode("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").as_deref(),
+            Some(&secret[..])
+        );
+    }
+
+    #[test]
+    fn enrolment_confirms_rejects_replays_and_consumes_recovery_codes() {
+        let store = InMemoryStore::default();
+        let now = 1_800_000_000u64;
+        let (secret, uri) =
+            begin_admin_totp_enrollment(&store, "user:alice", now).expect("enrolment starts");
+        assert!(uri.starts_with("otpauth://totp/Shuma%2DGorath%3Auser%3Aalice?secret="));
+        assert!(!is_admin_totp_enrolled(&store, "user:alice"));
+
+        assert!(confirm_admin_totp_enrollment(&store, "user:alice", "000000", now).is_err());
+        let code = current_totp_code(secret.as_str(), now);
+        let recovery = confirm_admin_totp_enrollment(&store, "user:alice", code.as_str(), now)
+            .expect("enrolment confirmed");
+        assert_eq!(recovery.len(), 10);
+        assert!(is_admin_totp_enrolled(&store, "user:alice"));
+
+        // The confirming code's step is spent; the next step's code works once.
+        assert!(!verify_admin_second_factor(
+            &store,
+            "user:alice",
+            code.as_str(),
+            now
+        ));
+        let next = current_totp_code(secret.as_str(), now + 30);
+        assert!(verify_admin_second_factor(
+            &store,
+            "user:alice",
+            next.as_str(),
+            now + 30
+        ));
+        assert!(!verify_admin_second_factor(
+            &store,
+            "user:alice",
+            next.as_str(),
+            now + 30
+        ));
+        let late = current_totp_code(secret.as_str(), now + 300);
+        assert!(!verify_admin_second_factor(
+            &store,
+            "user:alice",
+            late.as_str(),
+            now + 30
+        ));
+
+        let recovery_code = recovery[0].to_ascii_uppercase();
+        assert!(verify_admin_second_factor(
+            &store,
+            "user:alice",
+            recovery_code.as_str(),
+            now
+        ));
+        assert!(!verify_admin_second_factor(
+            &store,
+            "user:alice",
+            recovery_code.as_str(),
+            now
+        ));
+        assert!(!verify_admin_second_factor(
+            &store,
+            "user:bob",
+            recovery[1].as_str(),
+            now
+        ));
+    }
+}
@@ -1,9 +1,14 @@
 #![allow(dead_code)]
 
 pub(crate) mod contracts;
+pub(crate) mod delegation;
+mod kv_json;
+pub(crate) mod licensing;
+pub(crate) mod mtls;
 pub(crate) mod native_http_message_signatures;
 pub(crate) mod policy;
 pub(crate) mod telemetry;
+pub(crate) mod usage;
 pub(crate) mod verification;
 
 #[cfg(test)]
@@ -84,6 +84,16 @@ pub(crate) struct IdentityDirectorySource {
     pub source_uri: Option<String>,
 }
 
+/// A verified agent-delegation assertion: the site's own auth issued it for a human session and
+/// bound it to the key that signed this request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityDelegation {
+    pub key_id: String,
+    pub subject_digest: String,
+    pub expires_at: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct VerifiedIdentityEvidence {
@@ -97,4 +107,6 @@ pub(crate) struct VerifiedIdentityEvidence {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub directory_source: Option<IdentityDirectorySource>,
     pub provenance: IdentityProvenance,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegation: Option<Box<IdentityDelegation>>,
 }
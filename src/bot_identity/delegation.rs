@@ -0,0 +1,325 @@
+//! Agent-delegation assertions for user-triggered agents.
+//!
+//! An agent acting for a signed-in human presents an `Agent-Delegation` header next to its
+//! web-bot-auth signature. The header is a compact JWS (`EdDSA`) issued by the site's own auth for
+//! that human session, whose `cnf.jkt` claim names the JWK thumbprint of the agent's signing key.
+//! Because web-bot-auth key ids are those thumbprints, the assertion only verifies on requests the
+//! delegated agent itself signed. The assertion also names its issuer (`iss`, pinned per key) and
+//! the site it was minted for (`aud`, matched against the request authority), so a token issued
+//! for one site cannot be replayed at another that trusts the same key.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use spin_sdk::http::Request;
+
+use super::contracts::{IdentityCategory, IdentityDelegation, VerifiedIdentityEvidence};
+use super::policy::IdentityDelegationKey;
+use crate::crypto::{decode_jws_segment, verify_eddsa_compact_jws, EddsaJwsError};
+
+pub(crate) const DELEGATION_HEADER: &str = "agent-delegation";
+const MAX_DELEGATION_TOKEN_BYTES: usize = 4 * 1024;
+/// Longest `exp - iat` accepted, so a leaked assertion cannot outlive the session by much.
+const MAX_DELEGATION_LIFETIME_SECONDS: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DelegationRejection {
+    Malformed,
+    UnsupportedAlgorithm,
+    UnknownKey,
+    SignatureInvalid,
+    IssuerMismatch,
+    AudienceMismatch,
+    KeyBindingMismatch,
+    LifetimeTooLong,
+    NotYetValid,
+    Expired,
+}
+
+impl DelegationRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DelegationRejection::Malformed => "malformed",
+            DelegationRejection::UnsupportedAlgorithm => "unsupported_algorithm",
+            DelegationRejection::UnknownKey => "unknown_key",
+            DelegationRejection::SignatureInvalid => "signature_invalid",
+            DelegationRejection::IssuerMismatch => "issuer_mismatch",
+            DelegationRejection::AudienceMismatch => "audience_mismatch",
+            DelegationRejection::KeyBindingMismatch => "key_binding_mismatch",
+            DelegationRejection::LifetimeTooLong => "lifetime_too_long",
+            DelegationRejection::NotYetValid => "not_yet_valid",
+            DelegationRejection::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DelegationConfirmation {
+    jkt: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DelegationClaims {
+    iss: String,
+    aud: DelegationAudience,
+    sub: String,
+    cnf: DelegationConfirmation,
+    iat: u64,
+    exp: u64,
+}
+
+/// JWT `aud`: a single string or an array of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DelegationAudience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl DelegationAudience {
+    /// Accepts the bare host (`example.com`) or its origin (`https://example.com`).
+    fn names(&self, site_authority: &str) -> bool {
+        let site_authority = site_authority.trim().to_ascii_lowercase();
+        let matches = |aud: &String| {
+            let aud = aud.trim().to_ascii_lowercase();
+            let aud = aud.strip_prefix("https://").unwrap_or(aud.as_str());
+            aud.trim_end_matches('/') == site_authority
+        };
+        match self {
+            DelegationAudience::One(aud) => matches(aud),
+            DelegationAudience::Many(auds) => auds.iter().any(matches),
+        }
+    }
+}
+
+/// Verify the delegation presented on `req`, if any, against the key id that signed the request.
+/// Invalid assertions are treated as absent: the agent stays verified but undelegated.
+pub(crate) fn presented_delegation(
+    req: &Request,
+    cfg: &crate::config::VerifiedIdentityConfig,
+    signature_key_id: Option<&str>,
+    site_authority: Option<&str>,
+    now: u64,
+) -> Option<IdentityDelegation> {
+    if cfg.delegation_keys.is_empty() {
+        return None;
+    }
+    let token = req.header(DELEGATION_HEADER)?.as_str()?.trim();
+    verify_delegation_token(
+        token,
+        &cfg.delegation_keys,
+        signature_key_id?,
+        site_authority?,
+        now,
+        cfg.clock_skew_seconds,
+    )
+    .ok()
+}
+
+pub(crate) fn verify_delegation_token(
+    token: &str,
+    keys: &[IdentityDelegationKey],
+    signature_key_id: &str,
+    site_authority: &str,
+    now: u64,
+    clock_skew_seconds: u64,
+) -> Result<IdentityDelegation, DelegationRejection> {
+    let verified = verify_eddsa_compact_jws(token, MAX_DELEGATION_TOKEN_BYTES, |kid| {
+        keys.iter()
+            .find(|key| key.key_id == kid)
+            .and_then(|key| STANDARD.decode(key.public_key.trim()).ok())
+    })
+    .map_err(|err| match err {
+        EddsaJwsError::Malformed => DelegationRejection::Malformed,
+        EddsaJwsError::UnsupportedAlgorithm => DelegationRejection::UnsupportedAlgorithm,
+        EddsaJwsError::UnknownKey => DelegationRejection::UnknownKey,
+        EddsaJwsError::SignatureInvalid => DelegationRejection::SignatureInvalid,
+    })?;
+    let key = keys
+        .iter()
+        .find(|key| key.key_id == verified.key_id)
+        .ok_or(DelegationRejection::UnknownKey)?;
+
+    let claims: DelegationClaims =
+        decode_jws_segment(verified.payload_segment).ok_or(DelegationRejection::Malformed)?;
+    if claims.sub.is_empty() || claims.exp <= claims.iat {
+        return Err(DelegationRejection::Malformed);
+    }
+    if claims.iss != key.issuer {
+        return Err(DelegationRejection::IssuerMismatch);
+    }
+    if !claims.aud.names(site_authority) {
+        return Err(DelegationRejection::AudienceMismatch);
+    }
+    if claims.cnf.jkt != signature_key_id {
+        return Err(DelegationRejection::KeyBindingMismatch);
+    }
+    if claims.exp - claims.iat > MAX_DELEGATION_LIFETIME_SECONDS {
+        return Err(DelegationRejection::LifetimeTooLong);
+    }
+    if claims.iat > now.saturating_add(clock_skew_seconds) {
+        return Err(DelegationRejection::NotYetValid);
+    }
+    if claims.exp.saturating_add(clock_skew_seconds) <= now {
+        return Err(DelegationRejection::Expired);
+    }
+    Ok(IdentityDelegation {
+        key_id: key.key_id.clone(),
+        subject_digest: format!("{:x}", Sha256::digest(claims.sub.as_bytes()))[..24].to_string(),
+        expires_at: claims.exp,
+    })
+}
+
+/// A delegated agent acts for a human, whatever its directory says about it otherwise.
+pub(crate) fn apply_delegation(
+    identity: &mut VerifiedIdentityEvidence,
+    delegation: IdentityDelegation,
+) {
+    identity.category = IdentityCategory::UserTriggeredAgent;
+    identity.end_user_controlled = true;
+    identity.delegation = Some(Box::new(delegation));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use ed25519_dalek::Signer;
+
+    const NOW: u64 = 1_792_411_200;
+    const AGENT_KEY_ID: &str = "poqkLGiymh_W0uP6PZFw-dvez3QJT5SolqXBCW38r0U";
+
+    fn signing_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[23u8; 32])
+    }
+
+    fn keys() -> Vec<IdentityDelegationKey> {
+        vec![IdentityDelegationKey {
+            key_id: "session-2026".to_string(),
+            issuer: "https://auth.example.com".to_string(),
+            public_key: STANDARD.encode(signing_key().verifying_key().as_bytes()),
+        }]
+    }
+
+    fn mint(header: serde_json::Value, claims: serde_json::Value) -> String {
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = signing_key().sign(signing_input.as_bytes());
+        format!(
+            "{}.{}",
+            signing_input,
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        )
+    }
+
+    fn claims(jkt: &str, iat: u64, exp: u64) -> serde_json::Value {
+        serde_json::json!({
+            "iss": "https://auth.example.com",
+            "aud": "example.com",
+            "sub": "session:42",
+            "cnf": { "jkt": jkt },
+            "iat": iat,
+            "exp": exp
+        })
+    }
+
+    fn delegation(jkt: &str, iat: u64, exp: u64) -> String {
+        mint(
+            serde_json::json!({ "alg": "EdDSA", "kid": "session-2026" }),
+            claims(jkt, iat, exp),
+        )
+    }
+
+    #[test]
+    fn delegation_tokens_bind_the_request_signing_key_and_validity_window() {
+        let verified = verify_delegation_token(
+            delegation(AGENT_KEY_ID, NOW - 60, NOW + 3_600).as_str(),
+            &keys(),
+            AGENT_KEY_ID,
+            "example.com",
+            NOW,
+            30,
+        )
+        .expect("valid delegation");
+        assert_eq!(verified.key_id, "session-2026");
+        assert_eq!(verified.expires_at, NOW + 3_600);
+        assert_eq!(verified.subject_digest.len(), 24);
+        assert!(!verified.subject_digest.contains("session"));
+
+        let verify_at = |token: String, site: &str| {
+            verify_delegation_token(token.as_str(), &keys(), AGENT_KEY_ID, site, NOW, 30)
+        };
+        let verify = |token: String| verify_at(token, "example.com");
+        let header = || serde_json::json!({ "alg": "EdDSA", "kid": "session-2026" });
+        let with_claim = |name: &str, value: serde_json::Value| {
+            let mut claims = claims(AGENT_KEY_ID, NOW - 60, NOW + 3_600);
+            claims[name] = value;
+            mint(header(), claims)
+        };
+        assert!(verify_at(
+            delegation(AGENT_KEY_ID, NOW - 60, NOW + 3_600),
+            "EXAMPLE.com"
+        )
+        .is_ok());
+        assert!(verify(with_claim(
+            "aud",
+            serde_json::json!(["https://other.example", "https://example.com"])
+        ))
+        .is_ok());
+        assert_eq!(
+            verify_at(
+                delegation(AGENT_KEY_ID, NOW - 60, NOW + 3_600),
+                "other.example"
+            ),
+            Err(DelegationRejection::AudienceMismatch)
+        );
+        assert_eq!(
+            verify(with_claim("aud", serde_json::json!(["other.example"]))),
+            Err(DelegationRejection::AudienceMismatch)
+        );
+        assert_eq!(
+            verify(with_claim("iss", serde_json::json!("https://evil.example"))),
+            Err(DelegationRejection::IssuerMismatch)
+        );
+        let mut unscoped = claims(AGENT_KEY_ID, NOW - 60, NOW + 3_600);
+        unscoped
+            .as_object_mut()
+            .expect("claims object")
+            .remove("aud");
+        assert_eq!(
+            verify(mint(header(), unscoped)),
+            Err(DelegationRejection::Malformed)
+        );
+        assert_eq!(
+            verify(delegation("another-agent-key", NOW - 60, NOW + 3_600)),
+            Err(DelegationRejection::KeyBindingMismatch)
+        );
+        assert_eq!(
+            verify(delegation(AGENT_KEY_ID, NOW - 60, NOW + 2 * 86_400)),
+            Err(DelegationRejection::LifetimeTooLong)
+        );
+        assert_eq!(
+            verify(delegation(AGENT_KEY_ID, NOW - 7_200, NOW - 3_600)),
+            Err(DelegationRejection::Expired)
+        );
+        assert_eq!(
+            verify(delegation(AGENT_KEY_ID, NOW + 600, NOW + 3_600)),
+            Err(DelegationRejection::NotYetValid)
+        );
+        assert_eq!(
+            verify(mint(
+                serde_json::json!({ "alg": "EdDSA", "kid": "retired" }),
+                claims(AGENT_KEY_ID, NOW, NOW + 60),
+            )),
+            Err(DelegationRejection::UnknownKey)
+        );
+
+        let token = delegation(AGENT_KEY_ID, NOW - 60, NOW + 3_600);
+        let (signing_input, _) = token.rsplit_once('.').expect("signature segment");
+        let forged = format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode([0u8; 64]));
+        assert_eq!(verify(forged), Err(DelegationRejection::SignatureInvalid));
+    }
+}
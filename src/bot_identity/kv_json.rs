@@ -0,0 +1,33 @@
+//! JSON records in the key-value store, shared by the verified-identity usage rollups and
+//! licence ledgers.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::challenge::KeyValueStore;
+
+/// Reads and decodes one record; a missing, unreadable or malformed record reads as `None`.
+pub(crate) fn read_json<S: KeyValueStore, T: DeserializeOwned>(store: &S, key: &str) -> Option<T> {
+    store
+        .get(key)
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_slice(value.as_slice()).ok())
+}
+
+/// Best-effort write: failures are logged with `record` naming what was lost, never returned.
+pub(crate) fn write_json<S: KeyValueStore, T: Serialize>(
+    store: &S,
+    key: &str,
+    value: &T,
+    record: &str,
+) {
+    let Ok(encoded) = serde_json::to_vec(value) else {
+        return;
+    };
+    if let Err(err) = store.set(key, encoded.as_slice()) {
+        eprintln!(
+            "[verified_identity] failed to persist {} for key {}: {:?}",
+            record, key, err
+        );
+    }
+}
@@ -0,0 +1,489 @@
+//! Pay-per-crawl licensing for verified identities.
+//!
+//! A `require_licence` policy answers verified crawlers with `402 Payment Required` and the
+//! offer's price and terms. A crawler that has bought a licence presents it in the
+//! `Crawler-Licence` header as a compact JWS (`EdDSA`, signed by an operator-provisioned key) and
+//! is allowed through. Accepted licences are kept in a per-identity ledger.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use spin_sdk::http::Request;
+
+use super::contracts::{IdentityScheme, VerifiedIdentityEvidence};
+use super::kv_json::{read_json, write_json};
+use super::policy::{IdentityLicenceKey, IdentityLicenceOffer};
+use crate::challenge::KeyValueStore;
+use crate::crypto::{decode_jws_segment, verify_eddsa_compact_jws, EddsaJwsError};
+
+pub(crate) const LICENCE_HEADER: &str = "crawler-licence";
+pub(crate) const PRICE_HEADER: &str = "crawler-price";
+pub(crate) const OFFER_HEADER: &str = "crawler-licence-offer";
+pub(crate) const TERMS_HEADER: &str = "crawler-licence-terms";
+const MAX_LICENCE_TOKEN_BYTES: usize = 4 * 1024;
+const MAX_LICENCE_ID_CHARS: usize = 128;
+/// Licences kept per identity; the least recently used entry is dropped first.
+const LEDGER_MAX_LICENCES_PER_IDENTITY: usize = 100;
+/// Identities listed in the ledger index. Identities beyond the cap are not recorded.
+const LEDGER_MAX_IDENTITIES: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LicenceRejection {
+    Malformed,
+    UnsupportedAlgorithm,
+    UnknownKey,
+    SignatureInvalid,
+    SubjectMismatch,
+    NotYetValid,
+    Expired,
+}
+
+impl LicenceRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LicenceRejection::Malformed => "malformed",
+            LicenceRejection::UnsupportedAlgorithm => "unsupported_algorithm",
+            LicenceRejection::UnknownKey => "unknown_key",
+            LicenceRejection::SignatureInvalid => "signature_invalid",
+            LicenceRejection::SubjectMismatch => "subject_mismatch",
+            LicenceRejection::NotYetValid => "not_yet_valid",
+            LicenceRejection::Expired => "expired",
+        }
+    }
+}
+
+/// A licence token whose signature, subject and validity window have been checked. Whether it
+/// covers the offer a policy asks for is the policy graph's call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct VerifiedCrawlLicence {
+    pub licence_id: String,
+    pub offer_id: String,
+    pub key_id: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LicenceClaims {
+    jti: String,
+    sub: String,
+    offer: String,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct LicenceLedgerEntry {
+    pub licence_id: String,
+    pub offer_id: String,
+    pub key_id: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub first_used_at: u64,
+    pub last_used_at: u64,
+    pub requests: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct IdentityLicenceLedger {
+    pub scheme: IdentityScheme,
+    pub stable_identity: String,
+    pub operator: String,
+    pub licences: Vec<LicenceLedgerEntry>,
+}
+
+/// Verify the licence presented on `req`, if any. Invalid tokens are treated as absent so the
+/// crawler is quoted the offer again.
+pub(crate) fn presented_licence(
+    req: &Request,
+    cfg: &crate::config::VerifiedIdentityConfig,
+    identity: &VerifiedIdentityEvidence,
+    now: u64,
+) -> Option<VerifiedCrawlLicence> {
+    if cfg.licence_offers.is_empty() {
+        return None;
+    }
+    let token = req.header(LICENCE_HEADER)?.as_str()?.trim();
+    verify_licence_token(
+        token,
+        &cfg.licence_keys,
+        identity,
+        now,
+        cfg.clock_skew_seconds,
+    )
+    .ok()
+}
+
+pub(crate) fn verify_licence_token(
+    token: &str,
+    keys: &[IdentityLicenceKey],
+    identity: &VerifiedIdentityEvidence,
+    now: u64,
+    clock_skew_seconds: u64,
+) -> Result<VerifiedCrawlLicence, LicenceRejection> {
+    let verified = verify_eddsa_compact_jws(token, MAX_LICENCE_TOKEN_BYTES, |kid| {
+        keys.iter()
+            .find(|key| key.key_id == kid)
+            .and_then(|key| STANDARD.decode(key.public_key.trim()).ok())
+    })
+    .map_err(|err| match err {
+        EddsaJwsError::Malformed => LicenceRejection::Malformed,
+        EddsaJwsError::UnsupportedAlgorithm => LicenceRejection::UnsupportedAlgorithm,
+        EddsaJwsError::UnknownKey => LicenceRejection::UnknownKey,
+        EddsaJwsError::SignatureInvalid => LicenceRejection::SignatureInvalid,
+    })?;
+
+    let claims: LicenceClaims =
+        decode_jws_segment(verified.payload_segment).ok_or(LicenceRejection::Malformed)?;
+    if claims.jti.is_empty()
+        || claims.jti.chars().count() > MAX_LICENCE_ID_CHARS
+        || claims.offer.is_empty()
+        || claims.exp <= claims.iat
+    {
+        return Err(LicenceRejection::Malformed);
+    }
+    if claims.sub != identity.stable_identity {
+        return Err(LicenceRejection::SubjectMismatch);
+    }
+    if claims.iat > now.saturating_add(clock_skew_seconds) {
+        return Err(LicenceRejection::NotYetValid);
+    }
+    if claims.exp.saturating_add(clock_skew_seconds) <= now {
+        return Err(LicenceRejection::Expired);
+    }
+    Ok(VerifiedCrawlLicence {
+        licence_id: claims.jti,
+        offer_id: claims.offer,
+        key_id: verified.key_id,
+        issued_at: claims.iat,
+        expires_at: claims.exp,
+    })
+}
+
+/// Headers that quote `offer` on a `402` answer.
+pub(crate) fn offer_headers(offer: &IdentityLicenceOffer) -> Vec<(&'static str, String)> {
+    vec![
+        (PRICE_HEADER, format!("{} {}", offer.currency, offer.price)),
+        (OFFER_HEADER, offer.offer_id.clone()),
+        (TERMS_HEADER, offer.terms_url.clone()),
+        ("Link", format!("<{}>; rel=\"license\"", offer.terms_url)),
+    ]
+}
+
+/// Machine-readable body of a `402` answer.
+pub(crate) fn offer_body(offer: &IdentityLicenceOffer) -> String {
+    serde_json::json!({
+        "error": "licence_required",
+        "offer_id": offer.offer_id,
+        "description": offer.description,
+        "price": offer.price,
+        "currency": offer.currency,
+        "terms_url": offer.terms_url,
+        "licence_header": "Crawler-Licence",
+    })
+    .to_string()
+}
+
+/// Offers that some policy can actually quote, for advertising in robots.txt.
+pub(crate) fn advertised_offers(
+    cfg: &crate::config::VerifiedIdentityConfig,
+) -> Vec<&IdentityLicenceOffer> {
+    if !cfg.enabled {
+        return Vec::new();
+    }
+    let referenced: Vec<&str> = cfg
+        .named_policies
+        .iter()
+        .map(|policy| &policy.action)
+        .chain(cfg.category_defaults.iter().map(|default| &default.action))
+        .filter_map(|action| action.referenced_licence_offer_id())
+        .collect();
+    cfg.licence_offers
+        .iter()
+        .filter(|offer| referenced.contains(&offer.offer_id.as_str()))
+        .collect()
+}
+
+/// Comment lines describing the licensing contract, for the robots.txt header.
+pub(crate) fn robots_comment_lines(cfg: &crate::config::VerifiedIdentityConfig) -> Vec<String> {
+    let offers = advertised_offers(cfg);
+    if offers.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        "# Licensing: verified crawlers may be answered with HTTP 402 Payment Required and"
+            .to_string(),
+        "#   crawler-price, crawler-licence-offer and crawler-licence-terms headers.".to_string(),
+        "#   Retry with a signed licence for that offer in the Crawler-Licence header.".to_string(),
+    ];
+    for offer in offers {
+        lines.push(format!(
+            "# Licence-Offer: {} price={} {} terms={}",
+            offer.offer_id, offer.currency, offer.price, offer.terms_url
+        ));
+    }
+    lines.push("#".to_string());
+    lines
+}
+
+/// Record a request served under `licence`. Returns true the first time the licence is seen.
+pub(crate) fn record_accepted_licence<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    identity: &VerifiedIdentityEvidence,
+    licence: &VerifiedCrawlLicence,
+    now: u64,
+) -> bool {
+    let identity_key = identity_key(identity);
+    let index_key = ledger_index_key(site_id);
+    let mut index: Vec<String> = read_json(store, index_key.as_str()).unwrap_or_default();
+    if !index.contains(&identity_key) {
+        if index.len() >= LEDGER_MAX_IDENTITIES {
+            return false;
+        }
+        index.push(identity_key.clone());
+        write_json(store, index_key.as_str(), &index, "licence ledger");
+    }
+
+    let ledger_key = ledger_key(site_id, identity_key.as_str());
+    let mut ledger = read_json::<_, IdentityLicenceLedger>(store, ledger_key.as_str())
+        .unwrap_or_else(|| IdentityLicenceLedger {
+            scheme: identity.scheme,
+            stable_identity: identity.stable_identity.clone(),
+            operator: identity.operator.clone(),
+            licences: Vec::new(),
+        });
+    ledger.operator = identity.operator.clone();
+    let first_use = match ledger
+        .licences
+        .iter_mut()
+        .find(|entry| entry.licence_id == licence.licence_id && entry.key_id == licence.key_id)
+    {
+        Some(entry) => {
+            entry.last_used_at = now;
+            entry.requests = entry.requests.saturating_add(1);
+            false
+        }
+        None => {
+            if ledger.licences.len() >= LEDGER_MAX_LICENCES_PER_IDENTITY {
+                if let Some(oldest) = ledger
+                    .licences
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, entry)| entry.last_used_at)
+                    .map(|(position, _)| position)
+                {
+                    ledger.licences.remove(oldest);
+                }
+            }
+            ledger.licences.push(LicenceLedgerEntry {
+                licence_id: licence.licence_id.clone(),
+                offer_id: licence.offer_id.clone(),
+                key_id: licence.key_id.clone(),
+                issued_at: licence.issued_at,
+                expires_at: licence.expires_at,
+                first_used_at: now,
+                last_used_at: now,
+                requests: 1,
+            });
+            true
+        }
+    };
+    write_json(store, ledger_key.as_str(), &ledger, "licence ledger");
+    first_use
+}
+
+/// Every identity's ledger, most recently active first.
+pub(crate) fn licence_ledgers<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+) -> Vec<IdentityLicenceLedger> {
+    let index: Vec<String> =
+        read_json(store, ledger_index_key(site_id).as_str()).unwrap_or_default();
+    let mut ledgers: Vec<IdentityLicenceLedger> = index
+        .iter()
+        .filter_map(|identity_key| {
+            read_json(store, ledger_key(site_id, identity_key.as_str()).as_str())
+        })
+        .collect();
+    for ledger in &mut ledgers {
+        ledger
+            .licences
+            .sort_by_key(|licence| std::cmp::Reverse(licence.last_used_at));
+    }
+    ledgers.sort_by(|left, right| {
+        let last_used = |ledger: &IdentityLicenceLedger| {
+            ledger
+                .licences
+                .first()
+                .map_or(0, |entry| entry.last_used_at)
+        };
+        last_used(right)
+            .cmp(&last_used(left))
+            .then_with(|| left.stable_identity.cmp(&right.stable_identity))
+    });
+    ledgers
+}
+
+fn identity_key(identity: &VerifiedIdentityEvidence) -> String {
+    let subject = format!(
+        "identity:{}:{}",
+        identity.scheme.as_str(),
+        identity.stable_identity
+    );
+    format!("{:x}", Sha256::digest(subject.as_bytes()))[..24].to_string()
+}
+
+fn ledger_index_key(site_id: &str) -> String {
+    format!("verified_identity_licence_ledger_index:{}", site_id)
+}
+
+fn ledger_key(site_id: &str, identity_key: &str) -> String {
+    format!(
+        "verified_identity_licence_ledger:{}:{}",
+        site_id, identity_key
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot_identity::contracts::{
+        IdentityCategory, IdentityProvenance, VerificationStrength,
+    };
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use ed25519_dalek::Signer;
+
+    const NOW: u64 = 1_792_411_200;
+
+    fn identity() -> VerifiedIdentityEvidence {
+        VerifiedIdentityEvidence {
+            scheme: IdentityScheme::ProviderSignedAgent,
+            stable_identity: "gptbot".to_string(),
+            operator: "openai".to_string(),
+            category: IdentityCategory::Training,
+            verification_strength: VerificationStrength::ProviderAsserted,
+            end_user_controlled: false,
+            directory_source: None,
+            provenance: IdentityProvenance::Provider,
+            delegation: None,
+        }
+    }
+
+    fn signing_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[11u8; 32])
+    }
+
+    fn keys() -> Vec<IdentityLicenceKey> {
+        vec![IdentityLicenceKey {
+            key_id: "billing-2026".to_string(),
+            public_key: STANDARD.encode(signing_key().verifying_key().as_bytes()),
+        }]
+    }
+
+    fn mint(header: serde_json::Value, claims: serde_json::Value) -> String {
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = signing_key().sign(signing_input.as_bytes());
+        format!(
+            "{}.{}",
+            signing_input,
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        )
+    }
+
+    fn licence(sub: &str, iat: u64, exp: u64) -> String {
+        mint(
+            serde_json::json!({ "alg": "EdDSA", "kid": "billing-2026" }),
+            serde_json::json!({ "jti": "lic-1", "sub": sub, "offer": "archive", "iat": iat, "exp": exp }),
+        )
+    }
+
+    #[test]
+    fn licence_tokens_bind_key_subject_and_validity_window() {
+        let verified = verify_licence_token(
+            licence("gptbot", NOW - 60, NOW + 3_600).as_str(),
+            &keys(),
+            &identity(),
+            NOW,
+            30,
+        )
+        .expect("valid licence");
+        assert_eq!(verified.licence_id, "lic-1");
+        assert_eq!(verified.offer_id, "archive");
+        assert_eq!(verified.key_id, "billing-2026");
+
+        let verify =
+            |token: String| verify_licence_token(token.as_str(), &keys(), &identity(), NOW, 30);
+        assert_eq!(
+            verify(licence("other-bot", NOW - 60, NOW + 3_600)),
+            Err(LicenceRejection::SubjectMismatch)
+        );
+        assert_eq!(
+            verify(licence("gptbot", NOW - 7_200, NOW - 3_600)),
+            Err(LicenceRejection::Expired)
+        );
+        assert_eq!(
+            verify(licence("gptbot", NOW + 600, NOW + 3_600)),
+            Err(LicenceRejection::NotYetValid)
+        );
+        assert_eq!(
+            verify(mint(
+                serde_json::json!({ "alg": "EdDSA", "kid": "retired" }),
+                serde_json::json!({ "jti": "lic-1", "sub": "gptbot", "offer": "archive", "iat": NOW, "exp": NOW + 60 }),
+            )),
+            Err(LicenceRejection::UnknownKey)
+        );
+        assert_eq!(
+            verify(mint(
+                serde_json::json!({ "alg": "HS256", "kid": "billing-2026" }),
+                serde_json::json!({ "jti": "lic-1", "sub": "gptbot", "offer": "archive", "iat": NOW, "exp": NOW + 60 }),
+            )),
+            Err(LicenceRejection::UnsupportedAlgorithm)
+        );
+
+        let token = licence("gptbot", NOW - 60, NOW + 3_600);
+        let (signing_input, _) = token.rsplit_once('.').expect("signature segment");
+        let forged = format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode([0u8; 64]));
+        assert_eq!(verify(forged), Err(LicenceRejection::SignatureInvalid));
+    }
+
+    #[test]
+    fn ledger_records_each_licence_once_and_counts_requests() {
+        let store = crate::test_support::InMemoryStore::default();
+        let licence = VerifiedCrawlLicence {
+            licence_id: "lic-1".to_string(),
+            offer_id: "archive".to_string(),
+            key_id: "billing-2026".to_string(),
+            issued_at: NOW - 60,
+            expires_at: NOW + 3_600,
+        };
+
+        assert!(record_accepted_licence(
+            &store,
+            "default",
+            &identity(),
+            &licence,
+            NOW
+        ));
+        assert!(!record_accepted_licence(
+            &store,
+            "default",
+            &identity(),
+            &licence,
+            NOW + 5
+        ));
+
+        let ledgers = licence_ledgers(&store, "default");
+        assert_eq!(ledgers.len(), 1);
+        assert_eq!(ledgers[0].stable_identity, "gptbot");
+        assert_eq!(ledgers[0].licences.len(), 1);
+        assert_eq!(ledgers[0].licences[0].requests, 2);
+        assert_eq!(ledgers[0].licences[0].first_used_at, NOW);
+        assert_eq!(ledgers[0].licences[0].last_used_at, NOW + 5);
+        assert!(licence_ledgers(&store, "other-site").is_empty());
+    }
+}
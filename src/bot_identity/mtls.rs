@@ -0,0 +1,669 @@
+//! Client-certificate (mTLS) identity verification.
+//!
+//! TLS terminates in front of Shuma, so the client certificate arrives as proxy-forwarded
+//! headers: RFC 9440 `Client-Cert`/`Client-Cert-Chain`, or a URL-encoded PEM in
+//! `X-SSL-Client-Cert`. Those headers only count behind the forwarded-secret trust gate. The chain
+//! is then re-validated here against the operator's CA bundle rather than trusting the proxy's
+//! own verification result.
+//!
+//! Path building goes through `rustls-webpki`: signatures (Ed25519, ECDSA P-256/P-384, RSA),
+//! validity periods, basic constraints and path length, unknown critical extensions and EKU
+//! chaining. On top of that the leaf must carry an explicit `clientAuth` EKU, and any keyUsage
+//! present must allow `digitalSignature` on the leaf and `keyCertSign` on the issuing CAs.
+//! Identity fields are read with `x509-parser`. Parsed trust anchors are cached per CA bundle.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use rustls_pki_types::{CertificateDer, TrustAnchor, UnixTime};
+use sha2::{Digest, Sha256};
+use spin_sdk::http::Request;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::FromDer;
+
+use super::contracts::{
+    IdentityCategory, IdentityDirectorySource, IdentityProvenance, IdentityScheme,
+    VerificationStrength, VerifiedIdentityEvidence,
+};
+use super::verification::{
+    IdentityVerificationFailure, IdentityVerificationFreshness, IdentityVerificationResult,
+};
+
+const CLIENT_CERT_HEADER: &str = "client-cert";
+const CLIENT_CERT_CHAIN_HEADER: &str = "client-cert-chain";
+const PEM_CLIENT_CERT_HEADER: &str = "x-ssl-client-cert";
+const MAX_INTERMEDIATES: usize = 4;
+const MAX_CERTIFICATE_BYTES: usize = 16 * 1024;
+const ANCHOR_CACHE_MAX_ENTRIES: usize = 4;
+
+/// Configured CA certificates, parsed once per bundle.
+pub(crate) struct TrustAnchors {
+    anchors: Vec<TrustAnchor<'static>>,
+    /// SHA-256 of each anchor's DER, index-aligned with `anchors`.
+    fingerprints: Vec<String>,
+    /// Each anchor's basicConstraints pathLen, index-aligned with `anchors`. webpki's trust
+    /// anchors do not carry basic constraints, so the anchor's own limit is enforced here.
+    path_len_limits: Vec<Option<usize>>,
+}
+
+impl TrustAnchors {
+    pub(crate) fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    fn index_of(&self, anchor: &TrustAnchor<'_>) -> Option<usize> {
+        self.anchors.iter().position(|candidate| {
+            candidate.subject == anchor.subject
+                && candidate.subject_public_key_info == anchor.subject_public_key_info
+        })
+    }
+}
+
+/// Keyed by CA-bundle hash, in insertion order so eviction drops the oldest bundle.
+static ANCHOR_CACHE: Lazy<Mutex<IndexMap<u64, Arc<TrustAnchors>>>> =
+    Lazy::new(|| Mutex::new(IndexMap::new()));
+
+pub(crate) fn verify_request(
+    req: &Request,
+    cfg: &crate::config::Config,
+) -> IdentityVerificationResult {
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0);
+    verify_request_at(req, cfg, now_secs)
+}
+
+fn verify_request_at(
+    req: &Request,
+    cfg: &crate::config::Config,
+    now_secs: i64,
+) -> IdentityVerificationResult {
+    if !cfg.verified_identity.mtls_enabled {
+        return IdentityVerificationResult::not_attempted();
+    }
+    let leaf_header = header(req, CLIENT_CERT_HEADER);
+    let pem_header = header(req, PEM_CLIENT_CERT_HEADER);
+    if leaf_header.is_none() && pem_header.is_none() {
+        return IdentityVerificationResult::not_attempted();
+    }
+    if !crate::forwarded_ip_trusted(req) {
+        return failed(IdentityVerificationFailure::ProviderRejected);
+    }
+
+    let presented = match (leaf_header, pem_header) {
+        (Some(leaf), _) => parse_rfc9440_headers(leaf, header(req, CLIENT_CERT_CHAIN_HEADER)),
+        (None, Some(pem)) => parse_pem_header(pem),
+        (None, None) => None,
+    };
+    let Some((leaf_der, intermediates)) = presented.and_then(|mut certificates| {
+        if certificates.is_empty()
+            || certificates.len() > MAX_INTERMEDIATES + 1
+            || certificates
+                .iter()
+                .any(|der| der.len() > MAX_CERTIFICATE_BYTES)
+        {
+            return None;
+        }
+        let leaf = certificates.remove(0);
+        Some((leaf, certificates))
+    }) else {
+        return failed(IdentityVerificationFailure::CertificateInvalid);
+    };
+    let Some(leaf) = parse_certificate(leaf_der.as_ref()) else {
+        return failed(IdentityVerificationFailure::CertificateInvalid);
+    };
+    if !leaf_allows_client_auth(&leaf) {
+        return failed(IdentityVerificationFailure::CertificateInvalid);
+    }
+    let anchors = match cached_anchors(&cfg.verified_identity.mtls_ca_bundle) {
+        Ok(anchors) => anchors,
+        Err(_) => return failed(IdentityVerificationFailure::CertificateUntrusted),
+    };
+    let anchor_fingerprint = match verify_chain(
+        &leaf_der,
+        intermediates.as_slice(),
+        anchors.as_ref(),
+        now_secs,
+    ) {
+        Ok(fingerprint) => fingerprint,
+        Err(failure) => return failed(failure),
+    };
+
+    let (san_uris, san_dns_names) = subject_alt_names(&leaf);
+    let common_name = name_attribute(leaf.subject().iter_common_name());
+    let Some(stable_identity) = san_uris
+        .into_iter()
+        .next()
+        .or_else(|| san_dns_names.into_iter().next())
+        .or_else(|| common_name.clone())
+    else {
+        return failed(IdentityVerificationFailure::CertificateInvalid);
+    };
+    let operator = name_attribute(leaf.subject().iter_organization())
+        .or(common_name)
+        .unwrap_or_else(|| stable_identity.clone());
+
+    IdentityVerificationResult::verified(
+        VerifiedIdentityEvidence {
+            scheme: IdentityScheme::Mtls,
+            stable_identity,
+            operator,
+            category: IdentityCategory::ServiceAgent,
+            verification_strength: VerificationStrength::Cryptographic,
+            end_user_controlled: false,
+            directory_source: Some(IdentityDirectorySource {
+                source_id: format!("mtls_ca:{}", &anchor_fingerprint[..16]),
+                source_uri: None,
+            }),
+            provenance: IdentityProvenance::Native,
+            delegation: None,
+        },
+        IdentityVerificationFreshness::NotApplicable,
+    )
+}
+
+/// A leaf must be an end entity with an explicit `clientAuth` EKU; a missing EKU extension does
+/// not count as permission. A keyUsage, when present, must allow `digitalSignature`.
+fn leaf_allows_client_auth(leaf: &X509Certificate<'_>) -> bool {
+    let Ok(basic_constraints) = leaf.basic_constraints() else {
+        return false;
+    };
+    if basic_constraints.is_some_and(|constraints| constraints.value.ca) {
+        return false;
+    }
+    let client_auth = matches!(
+        leaf.extended_key_usage(),
+        Ok(Some(usage)) if usage.value.client_auth
+    );
+    let digital_signature = match leaf.key_usage() {
+        Ok(Some(usage)) => usage.value.digital_signature(),
+        Ok(None) => true,
+        Err(_) => false,
+    };
+    client_auth && digital_signature
+}
+
+/// Whether a CA in the verified path may sign certificates: no keyUsage, or one with
+/// `keyCertSign`.
+fn issuer_may_sign_certificates(der: &[u8]) -> bool {
+    parse_certificate(der).is_some_and(|issuer| match issuer.key_usage() {
+        Ok(Some(usage)) => usage.value.key_cert_sign(),
+        Ok(None) => true,
+        Err(_) => false,
+    })
+}
+
+/// Build and check the path from the leaf through the presented intermediates to a configured
+/// anchor. Returns the fingerprint of the anchor the path ends at.
+fn verify_chain(
+    leaf_der: &CertificateDer<'static>,
+    intermediates: &[CertificateDer<'static>],
+    anchors: &TrustAnchors,
+    now_secs: i64,
+) -> Result<String, IdentityVerificationFailure> {
+    let end_entity = webpki::EndEntityCert::try_from(leaf_der).map_err(classify_webpki_error)?;
+    let time = UnixTime::since_unix_epoch(std::time::Duration::from_secs(
+        u64::try_from(now_secs).unwrap_or(0),
+    ));
+    let path = end_entity
+        .verify_for_usage(
+            webpki::ALL_VERIFICATION_ALGS,
+            anchors.anchors.as_slice(),
+            intermediates,
+            time,
+            webpki::KeyUsage::client_auth(),
+            None,
+            Some(&|path: &webpki::VerifiedPath<'_>| {
+                let index = anchors
+                    .index_of(path.anchor())
+                    .ok_or(webpki::Error::UnknownIssuer)?;
+                let path_len_ok = anchors.path_len_limits[index]
+                    .is_none_or(|limit| path.intermediate_certificates().count() <= limit);
+                if !path_len_ok {
+                    return Err(webpki::Error::PathLenConstraintViolated);
+                }
+                if !path
+                    .intermediate_certificates()
+                    .all(|issuer| issuer_may_sign_certificates(issuer.der().as_ref()))
+                {
+                    return Err(webpki::Error::ExtensionValueInvalid);
+                }
+                Ok(())
+            }),
+        )
+        .map_err(classify_webpki_error)?;
+    anchors
+        .index_of(path.anchor())
+        .map(|index| anchors.fingerprints[index].clone())
+        .ok_or(IdentityVerificationFailure::CertificateUntrusted)
+}
+
+fn classify_webpki_error(error: webpki::Error) -> IdentityVerificationFailure {
+    match error {
+        webpki::Error::CertExpired { .. }
+        | webpki::Error::CertNotValidYet { .. }
+        | webpki::Error::InvalidCertValidity => IdentityVerificationFailure::CertificateExpired,
+        webpki::Error::UnknownIssuer
+        | webpki::Error::InvalidSignatureForPublicKey
+        | webpki::Error::MaximumPathDepthExceeded
+        | webpki::Error::MaximumPathBuildCallsExceeded
+        | webpki::Error::MaximumSignatureChecksExceeded => {
+            IdentityVerificationFailure::CertificateUntrusted
+        }
+        webpki::Error::UnsupportedSignatureAlgorithmContext(_)
+        | webpki::Error::UnsupportedSignatureAlgorithmForPublicKeyContext(_) => {
+            IdentityVerificationFailure::UnsupportedScheme
+        }
+        _ => IdentityVerificationFailure::CertificateInvalid,
+    }
+}
+
+fn bundle_cache_key(entries: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn cached_anchors(entries: &[String]) -> Result<Arc<TrustAnchors>, String> {
+    let key = bundle_cache_key(entries);
+    if let Some(anchors) = ANCHOR_CACHE.lock().unwrap().get(&key) {
+        return Ok(anchors.clone());
+    }
+    let anchors = Arc::new(parse_ca_bundle(entries)?);
+    let mut cache = ANCHOR_CACHE.lock().unwrap();
+    if !cache.contains_key(&key) && cache.len() >= ANCHOR_CACHE_MAX_ENTRIES {
+        cache.shift_remove_index(0);
+    }
+    cache.insert(key, anchors.clone());
+    Ok(anchors)
+}
+
+/// Parse every configured CA bundle entry. Used by config validation as well as verification.
+pub(crate) fn parse_ca_bundle(entries: &[String]) -> Result<TrustAnchors, String> {
+    let mut anchors = TrustAnchors {
+        anchors: Vec::new(),
+        fingerprints: Vec::new(),
+        path_len_limits: Vec::new(),
+    };
+    for (index, entry) in entries.iter().enumerate() {
+        let certificates = decode_certificate_text(entry)
+            .filter(|certificates| !certificates.is_empty())
+            .ok_or_else(|| format!("entry {} is not a PEM or base64 DER certificate", index))?;
+        for der in certificates {
+            let Some(path_len_limit) = (der.len() <= MAX_CERTIFICATE_BYTES)
+                .then(|| anchor_path_len_limit(der.as_ref()))
+                .flatten()
+            else {
+                return Err(format!(
+                    "entry {} is not a parseable X.509 certificate",
+                    index
+                ));
+            };
+            let anchor = webpki::anchor_from_trusted_cert(&der)
+                .map_err(|_| format!("entry {} is not a usable trust anchor", index))?
+                .to_owned();
+            anchors.anchors.push(anchor);
+            anchors
+                .fingerprints
+                .push(format!("{:x}", Sha256::digest(der.as_ref())));
+            anchors.path_len_limits.push(path_len_limit);
+        }
+    }
+    Ok(anchors)
+}
+
+/// `None` when the certificate does not parse; `Some(None)` when it sets no pathLen.
+fn anchor_path_len_limit(der: &[u8]) -> Option<Option<usize>> {
+    let certificate = parse_certificate(der)?;
+    let constraints = certificate.basic_constraints().ok()?;
+    Some(constraints.and_then(|constraints| {
+        constraints
+            .value
+            .path_len_constraint
+            .map(|limit| limit as usize)
+    }))
+}
+
+fn failed(failure: IdentityVerificationFailure) -> IdentityVerificationResult {
+    IdentityVerificationResult::failed(failure, IdentityVerificationFreshness::NotApplicable)
+}
+
+fn header<'a>(req: &'a Request, name: &str) -> Option<&'a str> {
+    req.header(name)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// A complete DER certificate with no trailing bytes.
+fn parse_certificate(der: &[u8]) -> Option<X509Certificate<'_>> {
+    match X509Certificate::from_der(der) {
+        Ok(([], certificate)) => Some(certificate),
+        _ => None,
+    }
+}
+
+fn parse_rfc9440_headers(leaf: &str, chain: Option<&str>) -> Option<Vec<CertificateDer<'static>>> {
+    let item = sfv::Parser::new(leaf).parse_item().ok()?;
+    let mut certificates = vec![CertificateDer::from(
+        item.bare_item.as_byte_sequence()?.to_vec(),
+    )];
+    if let Some(chain) = chain {
+        for entry in sfv::Parser::new(chain).parse_list().ok()? {
+            let sfv::ListEntry::Item(item) = entry else {
+                return None;
+            };
+            certificates.push(CertificateDer::from(
+                item.bare_item.as_byte_sequence()?.to_vec(),
+            ));
+        }
+    }
+    Some(certificates)
+}
+
+fn parse_pem_header(value: &str) -> Option<Vec<CertificateDer<'static>>> {
+    let decoded = percent_encoding::percent_decode_str(value)
+        .decode_utf8()
+        .ok()?;
+    decode_certificate_text(decoded.as_ref())
+}
+
+/// PEM blocks (one or many), or a single bare base64 DER certificate.
+fn decode_certificate_text(text: &str) -> Option<Vec<CertificateDer<'static>>> {
+    const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+    const END: &str = "-----END CERTIFICATE-----";
+    if !text.contains(BEGIN) {
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        return STANDARD
+            .decode(compact)
+            .ok()
+            .map(|der| vec![CertificateDer::from(der)]);
+    }
+    let mut certificates = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(BEGIN) {
+        let body_start = start + BEGIN.len();
+        let end = rest[body_start..].find(END)? + body_start;
+        let compact: String = rest[body_start..end]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        certificates.push(CertificateDer::from(STANDARD.decode(compact).ok()?));
+        rest = &rest[end + END.len()..];
+    }
+    Some(certificates)
+}
+
+fn subject_alt_names(certificate: &X509Certificate<'_>) -> (Vec<String>, Vec<String>) {
+    let mut uris = Vec::new();
+    let mut dns_names = Vec::new();
+    if let Ok(Some(names)) = certificate.subject_alternative_name() {
+        for name in &names.value.general_names {
+            match name {
+                GeneralName::URI(uri) => uris.push(uri.to_string()),
+                GeneralName::DNSName(dns) => dns_names.push(dns.to_ascii_lowercase()),
+                _ => {}
+            }
+        }
+    }
+    (uris, dns_names)
+}
+
+fn name_attribute<'a>(
+    mut attributes: impl Iterator<Item = &'a x509_parser::x509::AttributeTypeAndValue<'a>>,
+) -> Option<String> {
+    attributes
+        .next()
+        .and_then(|attribute| attribute.as_str().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot_identity::verification::IdentityVerificationResultStatus;
+
+    const ROOT: &str = include_str!("testdata/mtls/root.pem");
+    const INTERMEDIATE: &str = include_str!("testdata/mtls/int.pem");
+    const LEAF: &str = include_str!("testdata/mtls/leaf.pem");
+    const SERVER_ONLY: &str = include_str!("testdata/mtls/server.pem");
+    const OTHER_ROOT: &str = include_str!("testdata/mtls/other.pem");
+    const RSA_ROOT: &str = include_str!("testdata/mtls/rsaroot.pem");
+    const RSA_LEAF: &str = include_str!("testdata/mtls/rsaleaf.pem");
+    const EC_ROOT: &str = include_str!("testdata/mtls/ecroot.pem");
+    const EC_SIGNED_LEAF: &str = include_str!("testdata/mtls/ecleaf.pem");
+    const STRICT_ROOT: &str = include_str!("testdata/mtls/strictroot.pem");
+    const NO_EKU_LEAF: &str = include_str!("testdata/mtls/noeku.pem");
+    const CRITICAL_EXTENSION_LEAF: &str = include_str!("testdata/mtls/critical.pem");
+    const SIGNATURE_ONLY_CA: &str = include_str!("testdata/mtls/signint.pem");
+    const SIGNATURE_ONLY_CA_LEAF: &str = include_str!("testdata/mtls/signleaf.pem");
+    const PATH_LEN_ROOT: &str = include_str!("testdata/mtls/pathroot.pem");
+    const PATH_LEN_INTERMEDIATE: &str = include_str!("testdata/mtls/pathint.pem");
+    const PATH_LEN_SUBORDINATE: &str = include_str!("testdata/mtls/pathsub.pem");
+    const PATH_LEN_LEAF: &str = include_str!("testdata/mtls/pathleaf.pem");
+    /// Inside every fixture's validity window.
+    const NOW: i64 = 1_900_000_000;
+
+    fn sf_binary(pem: &str) -> String {
+        let der = decode_certificate_text(pem).expect("pem")[0].clone();
+        format!(":{}:", STANDARD.encode(der))
+    }
+
+    fn cfg(bundle: &[&str]) -> crate::config::Config {
+        let mut cfg = crate::config::defaults().clone();
+        cfg.verified_identity.enabled = true;
+        cfg.verified_identity.mtls_enabled = true;
+        cfg.verified_identity.mtls_ca_bundle = bundle.iter().map(|pem| pem.to_string()).collect();
+        cfg
+    }
+
+    fn trusted_request(headers: &[(&str, &str)]) -> Request {
+        let mut all = vec![("x-shuma-forwarded-secret", "test-forwarded-secret")];
+        all.extend_from_slice(headers);
+        crate::test_support::request_with_headers("/", all.as_slice())
+    }
+
+    #[test]
+    fn rfc9440_chain_through_an_intermediate_maps_san_and_organization() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let leaf = sf_binary(LEAF);
+        let chain = sf_binary(INTERMEDIATE);
+        let req = trusted_request(&[
+            (CLIENT_CERT_HEADER, leaf.as_str()),
+            (CLIENT_CERT_CHAIN_HEADER, chain.as_str()),
+        ]);
+
+        let result = verify_request_at(&req, &cfg(&[ROOT]), NOW);
+
+        assert_eq!(result.status, IdentityVerificationResultStatus::Verified);
+        let identity = result.identity.expect("identity");
+        assert_eq!(identity.scheme, IdentityScheme::Mtls);
+        assert_eq!(identity.stable_identity, "spiffe://partner.example/crawler");
+        assert_eq!(identity.operator, "Partner Corp");
+        assert_eq!(identity.category, IdentityCategory::ServiceAgent);
+        assert_eq!(
+            identity.verification_strength,
+            VerificationStrength::Cryptographic
+        );
+        assert!(identity
+            .directory_source
+            .is_some_and(|source| source.source_id.starts_with("mtls_ca:")));
+    }
+
+    #[test]
+    fn url_encoded_pem_header_verifies_rsa_signed_certificates() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let encoded =
+            percent_encoding::utf8_percent_encode(RSA_LEAF, percent_encoding::NON_ALPHANUMERIC)
+                .to_string();
+        let req = trusted_request(&[(PEM_CLIENT_CERT_HEADER, encoded.as_str())]);
+
+        let result = verify_request_at(&req, &cfg(&[RSA_ROOT]), NOW);
+
+        assert_eq!(result.status, IdentityVerificationResultStatus::Verified);
+        let identity = result.identity.expect("identity");
+        assert_eq!(identity.stable_identity, "billing-service");
+        assert_eq!(identity.operator, "Internal");
+    }
+
+    #[test]
+    fn chain_failures_are_classified() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let leaf = sf_binary(LEAF);
+        let chain = sf_binary(INTERMEDIATE);
+        let with_chain = trusted_request(&[
+            (CLIENT_CERT_HEADER, leaf.as_str()),
+            (CLIENT_CERT_CHAIN_HEADER, chain.as_str()),
+        ]);
+        let failure = |req: &Request, bundle: &[&str], now: i64| {
+            verify_request_at(req, &cfg(bundle), now).failure
+        };
+
+        assert_eq!(
+            failure(&with_chain, &[OTHER_ROOT], NOW),
+            Some(IdentityVerificationFailure::CertificateUntrusted)
+        );
+        let leaf_only = trusted_request(&[(CLIENT_CERT_HEADER, leaf.as_str())]);
+        assert_eq!(
+            failure(&leaf_only, &[ROOT], NOW),
+            Some(IdentityVerificationFailure::CertificateUntrusted)
+        );
+        assert_eq!(
+            failure(&with_chain, &[ROOT], 5_000_000_000),
+            Some(IdentityVerificationFailure::CertificateExpired)
+        );
+        assert_eq!(
+            failure(&with_chain, &[ROOT], 1_000_000_000),
+            Some(IdentityVerificationFailure::CertificateExpired)
+        );
+        let server = sf_binary(SERVER_ONLY);
+        let server_req = trusted_request(&[
+            (CLIENT_CERT_HEADER, server.as_str()),
+            (CLIENT_CERT_CHAIN_HEADER, chain.as_str()),
+        ]);
+        assert_eq!(
+            failure(&server_req, &[ROOT], NOW),
+            Some(IdentityVerificationFailure::CertificateInvalid)
+        );
+        let garbage = trusted_request(&[(CLIENT_CERT_HEADER, ":AAEC:")]);
+        assert_eq!(
+            failure(&garbage, &[ROOT], NOW),
+            Some(IdentityVerificationFailure::CertificateInvalid)
+        );
+    }
+
+    #[test]
+    fn ecdsa_signed_chains_verify() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let ec = sf_binary(EC_SIGNED_LEAF);
+        let req = trusted_request(&[(CLIENT_CERT_HEADER, ec.as_str())]);
+
+        let result = verify_request_at(&req, &cfg(&[EC_ROOT]), NOW);
+
+        assert_eq!(result.status, IdentityVerificationResultStatus::Verified);
+        let identity = result.identity.expect("identity");
+        assert_eq!(identity.stable_identity, "ec-signed-client");
+        assert_eq!(identity.operator, "Partner Corp");
+    }
+
+    #[test]
+    fn certificate_policy_violations_are_invalid() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let failure = |leaf: &str, chain: &[&str], root: &str| {
+            let leaf = sf_binary(leaf);
+            let chain = chain
+                .iter()
+                .map(|pem| sf_binary(pem))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut headers = vec![(CLIENT_CERT_HEADER, leaf.as_str())];
+            if !chain.is_empty() {
+                headers.push((CLIENT_CERT_CHAIN_HEADER, chain.as_str()));
+            }
+            verify_request_at(&trusted_request(headers.as_slice()), &cfg(&[root]), NOW).failure
+        };
+
+        // A leaf without any EKU extension is not implicitly allowed client auth.
+        assert_eq!(
+            failure(NO_EKU_LEAF, &[], STRICT_ROOT),
+            Some(IdentityVerificationFailure::CertificateInvalid)
+        );
+        assert_eq!(
+            failure(CRITICAL_EXTENSION_LEAF, &[], STRICT_ROOT),
+            Some(IdentityVerificationFailure::CertificateInvalid)
+        );
+        // The intermediate's keyUsage lacks keyCertSign.
+        assert_eq!(
+            failure(SIGNATURE_ONLY_CA_LEAF, &[SIGNATURE_ONLY_CA], STRICT_ROOT),
+            Some(IdentityVerificationFailure::CertificateInvalid)
+        );
+        // The root allows no intermediate CAs below it (pathLenConstraint 0).
+        assert_eq!(
+            failure(
+                PATH_LEN_LEAF,
+                &[PATH_LEN_SUBORDINATE, PATH_LEN_INTERMEDIATE],
+                PATH_LEN_ROOT
+            ),
+            Some(IdentityVerificationFailure::CertificateInvalid)
+        );
+    }
+
+    #[test]
+    fn parsed_trust_anchors_are_cached_per_bundle() {
+        let bundle = vec![STRICT_ROOT.to_string()];
+        let first = cached_anchors(&bundle).expect("anchors");
+        let second = cached_anchors(&bundle).expect("anchors");
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let other = cached_anchors(&[OTHER_ROOT.to_string()]).expect("anchors");
+        assert!(!Arc::ptr_eq(&first, &other));
+    }
+
+    #[test]
+    fn headers_need_the_forwarded_secret_and_mtls_enabled() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "test-forwarded-secret");
+        let leaf = sf_binary(LEAF);
+        let untrusted =
+            crate::test_support::request_with_headers("/", &[(CLIENT_CERT_HEADER, leaf.as_str())]);
+
+        assert_eq!(
+            verify_request_at(&untrusted, &cfg(&[ROOT]), NOW).failure,
+            Some(IdentityVerificationFailure::ProviderRejected)
+        );
+        let mut disabled = cfg(&[ROOT]);
+        disabled.verified_identity.mtls_enabled = false;
+        let trusted = trusted_request(&[(CLIENT_CERT_HEADER, leaf.as_str())]);
+        assert_eq!(
+            verify_request_at(&trusted, &disabled, NOW).status,
+            IdentityVerificationResultStatus::NotAttempted
+        );
+        assert_eq!(
+            verify_request_at(&trusted_request(&[]), &cfg(&[ROOT]), NOW).status,
+            IdentityVerificationResultStatus::NotAttempted
+        );
+    }
+
+    #[test]
+    fn ca_bundle_entries_must_parse_as_certificates() {
+        assert_eq!(
+            parse_ca_bundle(&[ROOT.to_string()]).expect("bundle").len(),
+            1
+        );
+        let combined = format!("{}{}", ROOT, RSA_ROOT);
+        assert_eq!(parse_ca_bundle(&[combined]).expect("bundle").len(), 2);
+        assert!(parse_ca_bundle(&["not a certificate".to_string()]).is_err());
+    }
+}
@@ -12,8 +12,10 @@ use super::contracts::{
     IdentityCategory, IdentityDirectorySource, IdentityProvenance, IdentityScheme,
     VerificationStrength, VerifiedIdentityEvidence,
 };
+use super::policy::IdentityPinnedDirectory;
 use super::verification::{
-    IdentityVerificationFailure, IdentityVerificationFreshness, IdentityVerificationResult,
+    IdentityDirectoryKeyRotation, IdentityVerificationFailure, IdentityVerificationFreshness,
+    IdentityVerificationResult,
 };
 
 const VERIFIED_IDENTITY_REPLAY_PREFIX: &str = "verified_identity:replay";
@@ -23,6 +25,12 @@ const VERIFIED_IDENTITY_DIRECTORY_CACHE_INDEX_PREFIX: &str =
 const MAX_EXTERNAL_SIGNATURE_AGENT_LINKS_PER_REQUEST: usize = 4;
 const MAX_CACHED_EXTERNAL_DIRECTORIES_PER_SITE: usize = 64;
 const MAX_EXTERNAL_DIRECTORY_RESPONSE_BYTES: usize = 64 * 1024;
+const VERIFIED_IDENTITY_DIRECTORY_PIN_PREFIX: &str = "verified_identity:directory_pin";
+const VERIFIED_IDENTITY_DIRECTORY_PIN_INDEX_PREFIX: &str = "verified_identity:directory_pin_index";
+const MAX_UPLOADED_DIRECTORY_PINS_PER_SITE: usize = 32;
+const MAX_PINNED_DIRECTORY_KEYS: usize = 32;
+const MAX_PINNED_DIRECTORY_OPERATOR_CHARS: usize = 128;
+const MAX_PINNED_KEY_ID_CHARS: usize = 128;
 
 #[derive(Clone)]
 struct ResolvedNativeIdentity {
@@ -35,6 +43,8 @@ struct ResolvedNativeIdentity {
 }
 
 trait NativeDirectoryResolver {
+    /// Resolves the signing keys for `verifier`. A directory refetch that changes the published
+    /// key ids is reported through `key_rotation`, whatever the verification outcome.
     fn resolve(
         &self,
         store: &dyn crate::challenge::KeyValueStore,
@@ -42,6 +52,7 @@ trait NativeDirectoryResolver {
         cfg: &crate::config::Config,
         now_secs: u64,
         verifier: &WebBotAuthVerifier,
+        key_rotation: &mut Option<IdentityDirectoryKeyRotation>,
     ) -> Result<ResolvedNativeIdentity, IdentityVerificationFailure>;
 }
 
@@ -69,6 +80,11 @@ struct CachedExternalDirectoryRecord {
     source_uri: String,
     fetched_at: u64,
     jwks: JSONWebKeySet,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_rotation_at: Option<u64>,
+    /// First refresh, in the current run, whose directory no longer published a pinned key id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pinned_keys_missing_since: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -84,6 +100,47 @@ struct CachedExternalDirectoryIndexEntry {
     fetched_at: u64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectoryPinSource {
+    Config,
+    Upload,
+}
+
+impl DirectoryPinSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            DirectoryPinSource::Config => "config",
+            DirectoryPinSource::Upload => "upload",
+        }
+    }
+}
+
+/// One known signature-agent directory as shown to operators: its pin, if any, and the freshness
+/// and key ids of what verification would use right now.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct SignatureDirectoryStatus {
+    pub source_uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<&'static str>,
+    pub auto_refresh: bool,
+    pub freshness: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_rotation_at: Option<u64>,
+    /// Set while an auto-refresh pin is answering with its last known-good keys because the
+    /// directory stopped publishing every pinned key id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_keys_missing_since: Option<u64>,
+    pub key_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pinned_key_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+}
+
 struct BoundedDirectoryResolver<'a> {
     fetcher: &'a dyn DirectoryFetcher,
 }
@@ -98,6 +155,7 @@ impl NativeDirectoryResolver for InlineOnlyDirectoryResolver {
         _cfg: &crate::config::Config,
         _now_secs: u64,
         verifier: &WebBotAuthVerifier,
+        _key_rotation: &mut Option<IdentityDirectoryKeyRotation>,
     ) -> Result<ResolvedNativeIdentity, IdentityVerificationFailure> {
         for link in verifier.get_signature_agents() {
             match link {
@@ -120,6 +178,7 @@ impl NativeDirectoryResolver for BoundedDirectoryResolver<'_> {
         cfg: &crate::config::Config,
         now_secs: u64,
         verifier: &WebBotAuthVerifier,
+        key_rotation: &mut Option<IdentityDirectoryKeyRotation>,
     ) -> Result<ResolvedNativeIdentity, IdentityVerificationFailure> {
         let mut saw_signature_invalid = false;
         let mut saw_directory_stale = false;
@@ -141,6 +200,7 @@ impl NativeDirectoryResolver for BoundedDirectoryResolver<'_> {
                     now_secs,
                     uri.as_str(),
                     self.fetcher,
+                    key_rotation,
                 ) {
                     Ok(resolved) => return Ok(resolved),
                     Err(IdentityVerificationFailure::SignatureInvalid) => {
@@ -194,10 +254,33 @@ fn verify_request_with_now_and_resolver(
     cfg: &crate::config::Config,
     now_secs: u64,
     resolver: &dyn NativeDirectoryResolver,
+) -> IdentityVerificationResult {
+    let mut key_rotation = None;
+    let mut result = verify_request_with_resolved_keys(
+        store,
+        site_id,
+        req,
+        cfg,
+        now_secs,
+        resolver,
+        &mut key_rotation,
+    );
+    result.directory_key_rotation = key_rotation.map(Box::new);
+    result
+}
+
+fn verify_request_with_resolved_keys(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    req: &Request,
+    cfg: &crate::config::Config,
+    now_secs: u64,
+    resolver: &dyn NativeDirectoryResolver,
+    key_rotation: &mut Option<IdentityDirectoryKeyRotation>,
 ) -> IdentityVerificationResult {
     let verifier = match parse_request_verifier(req) {
         Ok(verifier) => verifier,
-        Err(result) => return result,
+        Err(result) => return *result,
     };
 
     let parameters = verifier.get_parsed_label().base.parameters.details.clone();
@@ -206,7 +289,7 @@ fn verify_request_with_now_and_resolver(
         Err((failure, freshness)) => return IdentityVerificationResult::failed(failure, freshness),
     };
 
-    let resolved = match resolver.resolve(store, site_id, cfg, now_secs, &verifier) {
+    let resolved = match resolver.resolve(store, site_id, cfg, now_secs, &verifier, key_rotation) {
         Ok(resolved) => resolved,
         Err(failure) => return IdentityVerificationResult::failed(failure, freshness),
     };
@@ -218,51 +301,61 @@ fn verify_request_with_now_and_resolver(
         );
     }
 
-    match enforce_replay_window(store, site_id, req, &parameters, now_secs, cfg) {
-        Ok(()) => IdentityVerificationResult::verified(
-            VerifiedIdentityEvidence {
-                scheme: IdentityScheme::HttpMessageSignatures,
-                stable_identity: resolved.stable_identity,
-                operator: resolved.operator,
-                category: resolved.category,
-                verification_strength: VerificationStrength::Cryptographic,
-                end_user_controlled: resolved.end_user_controlled,
-                directory_source: resolved.directory_source,
-                provenance: IdentityProvenance::Native,
-            },
-            freshness,
-        ),
-        Err(failure) => {
-            IdentityVerificationResult::failed(failure, IdentityVerificationFreshness::ReplayRejected)
-        }
+    if let Err(failure) = enforce_replay_window(store, site_id, req, &parameters, now_secs, cfg) {
+        return IdentityVerificationResult::failed(
+            failure,
+            IdentityVerificationFreshness::ReplayRejected,
+        );
     }
+
+    let mut identity = VerifiedIdentityEvidence {
+        scheme: IdentityScheme::HttpMessageSignatures,
+        stable_identity: resolved.stable_identity,
+        operator: resolved.operator,
+        category: resolved.category,
+        verification_strength: VerificationStrength::Cryptographic,
+        end_user_controlled: resolved.end_user_controlled,
+        directory_source: resolved.directory_source,
+        provenance: IdentityProvenance::Native,
+        delegation: None,
+    };
+    if let Some(delegation) = super::delegation::presented_delegation(
+        req,
+        &cfg.verified_identity,
+        parameters.keyid.as_deref(),
+        authority_from_request(req).as_deref().map(host_without_port).as_deref(),
+        now_secs,
+    ) {
+        super::delegation::apply_delegation(&mut identity, delegation);
+    }
+    IdentityVerificationResult::verified(identity, freshness)
 }
 
-fn parse_request_verifier(req: &Request) -> Result<WebBotAuthVerifier, IdentityVerificationResult> {
+fn parse_request_verifier(req: &Request) -> Result<WebBotAuthVerifier, Box<IdentityVerificationResult>> {
     let has_signature = has_header(req, "signature");
     let has_signature_input = has_header(req, "signature-input");
     let has_signature_agent = has_header(req, "signature-agent");
 
     if !has_signature && !has_signature_input && !has_signature_agent {
-        return Err(IdentityVerificationResult::not_attempted());
+        return Err(Box::new(IdentityVerificationResult::not_attempted()));
     }
 
     if !has_signature || !has_signature_input {
-        return Err(IdentityVerificationResult::failed(
+        return Err(Box::new(IdentityVerificationResult::failed(
             IdentityVerificationFailure::MissingSignature,
             IdentityVerificationFreshness::NotApplicable,
-        ));
+        )));
     }
 
     if !has_signature_agent {
-        return Err(IdentityVerificationResult::failed(
+        return Err(Box::new(IdentityVerificationResult::failed(
             IdentityVerificationFailure::MissingAssertion,
             IdentityVerificationFreshness::NotApplicable,
-        ));
+        )));
     }
 
     let adapter = RequestSignedMessage { req };
-    WebBotAuthVerifier::parse(&adapter).map_err(map_parse_error)
+    WebBotAuthVerifier::parse(&adapter).map_err(|err| Box::new(map_parse_error(err)))
 }
 
 fn map_parse_error(err: ImplementationError) -> IdentityVerificationResult {
@@ -339,33 +432,88 @@ fn resolve_external_identity(
     now_secs: u64,
     raw_uri: &str,
     fetcher: &dyn DirectoryFetcher,
+    key_rotation: &mut Option<IdentityDirectoryKeyRotation>,
 ) -> Result<ResolvedNativeIdentity, IdentityVerificationFailure> {
     let normalized_uri =
         normalize_https_directory_uri(raw_uri).ok_or(IdentityVerificationFailure::DirectoryUnavailable)?;
+    let pin = pinned_directory(store, site_id, cfg, normalized_uri.as_str()).map(|(pin, _)| pin);
+    if let Some(pin) = pin.as_ref().filter(|pin| !pin.auto_refresh) {
+        return build_external_resolved_identity(normalized_uri.as_str(), pin.jwks.clone(), Some(pin));
+    }
     let cached = load_cached_external_directory(store, site_id, normalized_uri.as_str());
     if let Some(record) = cached.as_ref() {
         let age = now_secs.saturating_sub(record.fetched_at);
         let within_freshness = age <= cfg.verified_identity.directory_freshness_requirement_seconds;
         let within_direct_use = age <= cfg.verified_identity.directory_cache_ttl_seconds && within_freshness;
         if within_direct_use {
-            return build_external_resolved_identity(normalized_uri.as_str(), record.jwks.clone());
+            return build_external_resolved_identity(
+                normalized_uri.as_str(),
+                record.jwks.clone(),
+                pin.as_ref(),
+            );
         }
     }
 
     match fetch_external_directory(fetcher, normalized_uri.as_str()) {
         Ok(jwks) => {
-            persist_cached_external_directory(store, site_id, normalized_uri.as_str(), now_secs, &jwks);
-            build_external_resolved_identity(normalized_uri.as_str(), jwks)
+            let previous = cached
+                .as_ref()
+                .map(|record| &record.jwks)
+                .or(pin.as_ref().map(|pin| &pin.jwks));
+            let pinned_keys_missing = pin
+                .as_ref()
+                .is_some_and(|pin| !publishes_pinned_key(pin, &jwks));
+            let mut last_rotation_at = cached.as_ref().and_then(|record| record.last_rotation_at);
+            let rotated =
+                previous.is_some_and(|previous| jwks_key_ids(previous) != jwks_key_ids(&jwks));
+            if rotated || pinned_keys_missing {
+                *key_rotation = Some(directory_key_rotation(
+                    normalized_uri.as_str(),
+                    previous.map(jwks_key_ids).unwrap_or_default(),
+                    jwks_key_ids(&jwks),
+                    pinned_keys_missing,
+                ));
+                last_rotation_at = Some(now_secs);
+            }
+            // A refresh that drops every pinned key keeps the last known-good keys cached and in
+            // use; the rotation carries the alert.
+            let jwks = match pin.as_ref() {
+                Some(pin) if pinned_keys_missing => cached
+                    .as_ref()
+                    .map(|record| &record.jwks)
+                    .filter(|jwks| publishes_pinned_key(pin, jwks))
+                    .unwrap_or(&pin.jwks)
+                    .clone(),
+                _ => jwks,
+            };
+            let pinned_keys_missing_since = pinned_keys_missing.then(|| {
+                cached
+                    .as_ref()
+                    .and_then(|record| record.pinned_keys_missing_since)
+                    .unwrap_or(now_secs)
+            });
+            let record = CachedExternalDirectoryRecord {
+                source_uri: normalized_uri.clone(),
+                fetched_at: now_secs,
+                jwks,
+                last_rotation_at,
+                pinned_keys_missing_since,
+            };
+            persist_cached_external_directory(store, site_id, &record);
+            build_external_resolved_identity(normalized_uri.as_str(), record.jwks, pin.as_ref())
         }
-        Err(()) => match cached {
-            Some(record)
+        Err(()) => match (cached, pin.as_ref()) {
+            (Some(record), _)
                 if now_secs.saturating_sub(record.fetched_at)
                     <= cfg.verified_identity.directory_freshness_requirement_seconds =>
             {
-                build_external_resolved_identity(normalized_uri.as_str(), record.jwks)
+                build_external_resolved_identity(normalized_uri.as_str(), record.jwks, pin.as_ref())
             }
-            Some(_) => Err(IdentityVerificationFailure::DirectoryStale),
-            None => Err(IdentityVerificationFailure::DirectoryUnavailable),
+            (_, Some(pin)) => {
+                build_external_resolved_identity(normalized_uri.as_str(), pin.jwks.clone(), Some(pin))
+            }
+            (Some(_), None) => Err(IdentityVerificationFailure::DirectoryStale),
+            (None, None) => Err(IdentityVerificationFailure::DirectoryUnavailable),
         },
     }
 }
@@ -373,14 +521,29 @@ fn resolve_external_identity(
 fn build_external_resolved_identity(
     normalized_uri: &str,
     jwks: JSONWebKeySet,
+    pin: Option<&IdentityPinnedDirectory>,
 ) -> Result<ResolvedNativeIdentity, IdentityVerificationFailure> {
     let authority = absolute_uri_authority(normalized_uri)
         .ok_or(IdentityVerificationFailure::DirectoryUnavailable)?;
+    let jwks = match pin {
+        Some(pin) if !pin.pinned_key_ids.is_empty() => JSONWebKeySet {
+            keys: jwks
+                .keys
+                .into_iter()
+                .filter(|key| pin.pinned_key_ids.contains(&key.b64_thumbprint()))
+                .collect(),
+        },
+        _ => jwks,
+    };
     Ok(ResolvedNativeIdentity {
         keyring: keyring_from_jwks(jwks)?,
         stable_identity: normalized_uri.to_string(),
-        operator: host_without_port(authority.as_str()),
-        category: IdentityCategory::Other,
+        operator: pin
+            .and_then(|pin| pin.operator.clone())
+            .unwrap_or_else(|| host_without_port(authority.as_str())),
+        category: pin
+            .and_then(|pin| pin.category)
+            .unwrap_or(IdentityCategory::Other),
         end_user_controlled: false,
         directory_source: Some(IdentityDirectorySource {
             source_id: directory_source_id(normalized_uri),
@@ -389,6 +552,275 @@ fn build_external_resolved_identity(
     })
 }
 
+/// Sorted JWK thumbprints, which are the key ids web-bot-auth signatures refer to.
+fn jwks_key_ids(jwks: &JSONWebKeySet) -> Vec<String> {
+    let mut key_ids: Vec<String> = jwks.keys.iter().map(|key| key.b64_thumbprint()).collect();
+    key_ids.sort();
+    key_ids.dedup();
+    key_ids
+}
+
+/// Whether `jwks` still publishes one of the pin's `pinned_key_ids`; pins without key ids accept
+/// any key set.
+fn publishes_pinned_key(pin: &IdentityPinnedDirectory, jwks: &JSONWebKeySet) -> bool {
+    pin.pinned_key_ids.is_empty()
+        || jwks
+            .keys
+            .iter()
+            .any(|key| pin.pinned_key_ids.contains(&key.b64_thumbprint()))
+}
+
+fn directory_key_rotation(
+    source_uri: &str,
+    previous: Vec<String>,
+    current: Vec<String>,
+    pinned_keys_missing: bool,
+) -> IdentityDirectoryKeyRotation {
+    IdentityDirectoryKeyRotation {
+        source_uri: source_uri.to_string(),
+        added_key_ids: current
+            .iter()
+            .filter(|key_id| !previous.contains(key_id))
+            .cloned()
+            .collect(),
+        removed_key_ids: previous
+            .iter()
+            .filter(|key_id| !current.contains(key_id))
+            .cloned()
+            .collect(),
+        pinned_keys_missing,
+    }
+}
+
+/// Config pins win over uploaded pins for the same directory.
+fn pinned_directory(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    cfg: &crate::config::Config,
+    source_uri: &str,
+) -> Option<(IdentityPinnedDirectory, DirectoryPinSource)> {
+    if let Some(pin) = cfg
+        .verified_identity
+        .pinned_directories
+        .iter()
+        .find(|pin| pin.source_uri == source_uri)
+    {
+        return Some((pin.clone(), DirectoryPinSource::Config));
+    }
+    load_uploaded_directory_pin(store, site_id, source_uri)
+        .map(|pin| (pin, DirectoryPinSource::Upload))
+}
+
+/// Shape checks shared by config pins and admin uploads. Errors name the offending field.
+pub(crate) fn validate_pinned_directory(directory: &IdentityPinnedDirectory) -> Result<(), String> {
+    if normalize_https_directory_uri(directory.source_uri.as_str()).as_deref()
+        != Some(directory.source_uri.as_str())
+    {
+        return Err(
+            "source_uri must be a normalized https URL without credentials, query, or fragment"
+                .to_string(),
+        );
+    }
+    if directory.jwks.keys.is_empty() || directory.jwks.keys.len() > MAX_PINNED_DIRECTORY_KEYS {
+        return Err(format!(
+            "jwks must contain between 1 and {} keys",
+            MAX_PINNED_DIRECTORY_KEYS
+        ));
+    }
+    if keyring_from_jwks(directory.jwks.clone()).is_err() {
+        return Err("jwks must contain at least one supported Ed25519 key".to_string());
+    }
+    if let Some(operator) = directory.operator.as_ref() {
+        if operator.trim().is_empty()
+            || operator.chars().count() > MAX_PINNED_DIRECTORY_OPERATOR_CHARS
+            || operator.chars().any(char::is_control)
+        {
+            return Err(format!(
+                "operator must be 1-{} printable characters",
+                MAX_PINNED_DIRECTORY_OPERATOR_CHARS
+            ));
+        }
+    }
+    if directory.pinned_key_ids.len() > MAX_PINNED_DIRECTORY_KEYS {
+        return Err(format!(
+            "pinned_key_ids must not contain more than {} entries",
+            MAX_PINNED_DIRECTORY_KEYS
+        ));
+    }
+    let key_ids = jwks_key_ids(&directory.jwks);
+    for key_id in &directory.pinned_key_ids {
+        if key_id.is_empty()
+            || key_id.len() > MAX_PINNED_KEY_ID_CHARS
+            || !key_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err("pinned_key_ids must be base64url JWK thumbprints".to_string());
+        }
+        if !directory.auto_refresh && !key_ids.contains(key_id) {
+            return Err(format!("pinned_key_ids entry {} is not in jwks", key_id));
+        }
+    }
+    Ok(())
+}
+
+/// Store an operator-uploaded pin, replacing any earlier upload for the same directory.
+pub(crate) fn upload_pinned_directory(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    directory: &IdentityPinnedDirectory,
+) -> Result<(), String> {
+    validate_pinned_directory(directory)?;
+    let mut index = load_uploaded_directory_pin_index(store, site_id);
+    if !index.contains(&directory.source_uri) {
+        if index.len() >= MAX_UPLOADED_DIRECTORY_PINS_PER_SITE {
+            return Err(format!(
+                "at most {} directories may be pinned by upload",
+                MAX_UPLOADED_DIRECTORY_PINS_PER_SITE
+            ));
+        }
+        index.push(directory.source_uri.clone());
+    }
+    let raw = serde_json::to_vec(directory).map_err(|_| "failed to encode pin".to_string())?;
+    store
+        .set(
+            directory_pin_key(site_id, directory.source_uri.as_str()).as_str(),
+            raw.as_slice(),
+        )
+        .map_err(|_| "failed to persist pin".to_string())?;
+    persist_uploaded_directory_pin_index(store, site_id, &index)
+        .map_err(|_| "failed to persist pin index".to_string())
+}
+
+/// Drop an uploaded pin. Returns false when there was none.
+pub(crate) fn remove_pinned_directory(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    source_uri: &str,
+) -> bool {
+    let Some(source_uri) = normalize_https_directory_uri(source_uri) else {
+        return false;
+    };
+    let mut index = load_uploaded_directory_pin_index(store, site_id);
+    let before = index.len();
+    index.retain(|entry| entry != &source_uri);
+    if index.len() == before {
+        return false;
+    }
+    let _ = store.delete(directory_pin_key(site_id, source_uri.as_str()).as_str());
+    persist_uploaded_directory_pin_index(store, site_id, &index).is_ok()
+}
+
+/// Every pinned or cached directory for the site, sorted by source URI.
+pub(crate) fn directory_inventory(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    cfg: &crate::config::Config,
+    now_secs: u64,
+) -> Vec<SignatureDirectoryStatus> {
+    let mut source_uris: Vec<String> = load_cached_external_directory_index(store, site_id)
+        .entries
+        .into_iter()
+        .map(|entry| entry.source_uri)
+        .chain(
+            cfg.verified_identity
+                .pinned_directories
+                .iter()
+                .map(|pin| pin.source_uri.clone()),
+        )
+        .chain(load_uploaded_directory_pin_index(store, site_id))
+        .collect();
+    source_uris.sort();
+    source_uris.dedup();
+
+    source_uris
+        .into_iter()
+        .map(|source_uri| {
+            let pin = pinned_directory(store, site_id, cfg, source_uri.as_str());
+            let cached = load_cached_external_directory(store, site_id, source_uri.as_str());
+            let auto_refresh = pin.as_ref().is_none_or(|(pin, _)| pin.auto_refresh);
+            let age_seconds = cached
+                .as_ref()
+                .map(|record| now_secs.saturating_sub(record.fetched_at));
+            let freshness = match (auto_refresh, age_seconds) {
+                (false, _) | (true, None) => "pinned",
+                (true, Some(age))
+                    if age <= cfg.verified_identity.directory_cache_ttl_seconds
+                        && age <= cfg.verified_identity.directory_freshness_requirement_seconds =>
+                {
+                    "fresh"
+                }
+                (true, Some(age))
+                    if age <= cfg.verified_identity.directory_freshness_requirement_seconds =>
+                {
+                    "refresh_due"
+                }
+                (true, Some(_)) if pin.is_some() => "pinned",
+                (true, Some(_)) => "stale",
+            };
+            let key_ids = match (&pin, &cached) {
+                (Some((pin, _)), _) if !pin.auto_refresh => jwks_key_ids(&pin.jwks),
+                (_, Some(record)) => jwks_key_ids(&record.jwks),
+                (Some((pin, _)), None) => jwks_key_ids(&pin.jwks),
+                (None, None) => Vec::new(),
+            };
+            SignatureDirectoryStatus {
+                source_uri,
+                pin: pin.as_ref().map(|(_, source)| source.as_str()),
+                auto_refresh,
+                freshness,
+                fetched_at: cached.as_ref().map(|record| record.fetched_at),
+                age_seconds,
+                last_rotation_at: cached.as_ref().and_then(|record| record.last_rotation_at),
+                pinned_keys_missing_since: cached
+                    .as_ref()
+                    .and_then(|record| record.pinned_keys_missing_since),
+                key_ids,
+                pinned_key_ids: pin
+                    .as_ref()
+                    .map(|(pin, _)| pin.pinned_key_ids.clone())
+                    .unwrap_or_default(),
+                operator: pin.and_then(|(pin, _)| pin.operator),
+            }
+        })
+        .collect()
+}
+
+fn load_uploaded_directory_pin(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    source_uri: &str,
+) -> Option<IdentityPinnedDirectory> {
+    let raw = store
+        .get(directory_pin_key(site_id, source_uri).as_str())
+        .ok()
+        .flatten()?;
+    serde_json::from_slice::<IdentityPinnedDirectory>(raw.as_slice())
+        .ok()
+        .filter(|pin| pin.source_uri == source_uri)
+}
+
+fn load_uploaded_directory_pin_index(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+) -> Vec<String> {
+    store
+        .get(directory_pin_index_key(site_id).as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice(raw.as_slice()).ok())
+        .unwrap_or_default()
+}
+
+fn persist_uploaded_directory_pin_index(
+    store: &dyn crate::challenge::KeyValueStore,
+    site_id: &str,
+    index: &[String],
+) -> Result<(), ()> {
+    let raw = serde_json::to_vec(index).map_err(|_| ())?;
+    store.set(directory_pin_index_key(site_id).as_str(), raw.as_slice())
+}
+
 fn fetch_external_directory(
     fetcher: &dyn DirectoryFetcher,
     uri: &str,
@@ -419,16 +851,11 @@ fn load_cached_external_directory(
 fn persist_cached_external_directory(
     store: &dyn crate::challenge::KeyValueStore,
     site_id: &str,
-    source_uri: &str,
-    fetched_at: u64,
-    jwks: &JSONWebKeySet,
+    record: &CachedExternalDirectoryRecord,
 ) {
-    let record = CachedExternalDirectoryRecord {
-        source_uri: source_uri.to_string(),
-        fetched_at,
-        jwks: jwks.clone(),
-    };
-    let Ok(raw_record) = serde_json::to_vec(&record) else {
+    let source_uri = record.source_uri.as_str();
+    let fetched_at = record.fetched_at;
+    let Ok(raw_record) = serde_json::to_vec(record) else {
         return;
     };
     let cache_key = external_directory_cache_key(site_id, source_uri);
@@ -651,6 +1078,17 @@ fn external_directory_cache_index_key(site_id: &str) -> String {
     format!("{VERIFIED_IDENTITY_DIRECTORY_CACHE_INDEX_PREFIX}:{site_id}")
 }
 
+fn directory_pin_key(site_id: &str, source_uri: &str) -> String {
+    format!(
+        "{VERIFIED_IDENTITY_DIRECTORY_PIN_PREFIX}:{site_id}:{}",
+        hash_external_directory_source(source_uri)
+    )
+}
+
+fn directory_pin_index_key(site_id: &str) -> String {
+    format!("{VERIFIED_IDENTITY_DIRECTORY_PIN_INDEX_PREFIX}:{site_id}")
+}
+
 fn hash_external_directory_source(source_uri: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(source_uri.as_bytes());
@@ -872,7 +1310,17 @@ fn store_cached_directory_for_tests(
     jwks: &JSONWebKeySet,
 ) {
     let normalized_uri = normalize_https_directory_uri(source_uri).expect("normalized test uri");
-    persist_cached_external_directory(store, site_id, normalized_uri.as_str(), fetched_at, jwks);
+    persist_cached_external_directory(
+        store,
+        site_id,
+        &CachedExternalDirectoryRecord {
+            source_uri: normalized_uri,
+            fetched_at,
+            jwks: jwks.clone(),
+            last_rotation_at: None,
+            pinned_keys_missing_since: None,
+        },
+    );
 }
 
 #[cfg(test)]
@@ -886,12 +1334,14 @@ fn load_directory_cache_index_for_tests(
 #[cfg(test)]
 mod tests {
     use super::{
-        external_directory_cache_index_key, load_directory_cache_index_for_tests,
-        store_cached_directory_for_tests, verify_request,
+        directory_inventory, external_directory_cache_index_key,
+        load_directory_cache_index_for_tests, remove_pinned_directory,
+        store_cached_directory_for_tests, upload_pinned_directory, verify_request,
         verify_request_with_now_and_fetcher, verify_request_with_now_and_resolver,
         DirectoryFetchResult, DirectoryFetcher, NativeDirectoryResolver, ResolvedNativeIdentity,
         MAX_CACHED_EXTERNAL_DIRECTORIES_PER_SITE,
     };
+    use crate::bot_identity::policy::IdentityPinnedDirectory;
     use base64::{engine::general_purpose, Engine as _};
     use spin_sdk::http::Request;
     use std::cell::Cell;
@@ -905,6 +1355,7 @@ mod tests {
 
     const TEST_KEY_ID: &str = "poqkLGiymh_W0uP6PZFw-dvez3QJT5SolqXBCW38r0U";
     const TEST_SIGNATURE_AGENT_URL: &str = "https://signature-agent.test";
+    const PINNED_SOURCE_URI: &str = "https://signature-agent.test/";
     const TEST_PUBLIC_KEY: [u8; 32] = [
         0x26, 0xb4, 0x0b, 0x8f, 0x93, 0xff, 0xf3, 0xd8, 0x97, 0x11, 0x2f, 0x7e, 0xbc, 0x58,
         0x2b, 0x23, 0x2d, 0xbd, 0x72, 0x51, 0x7d, 0x08, 0x2f, 0xe8, 0x3c, 0xfb, 0x30, 0xdd,
@@ -932,6 +1383,7 @@ mod tests {
             _cfg: &crate::config::Config,
             _now_secs: u64,
             _verifier: &WebBotAuthVerifier,
+            _key_rotation: &mut Option<super::IdentityDirectoryKeyRotation>,
         ) -> Result<ResolvedNativeIdentity, crate::bot_identity::verification::IdentityVerificationFailure> {
             Ok(ResolvedNativeIdentity {
                 keyring: self.keyring.clone(),
@@ -993,17 +1445,12 @@ mod tests {
 
     impl TestDirectoryFetcher {
         fn success_for_public_key() -> Self {
-            let public_key = Thumbprintable::OKP {
-                crv: "Ed25519".to_string(),
-                x: general_purpose::URL_SAFE_NO_PAD.encode(TEST_PUBLIC_KEY),
-            };
-            let jwks = serde_json::json!({
-                "keys": [public_key]
-            })
-            .to_string()
-            .into_bytes();
+            Self::success_for_jwks(successful_test_jwks())
+        }
+
+        fn success_for_jwks(jwks: JSONWebKeySet) -> Self {
             Self {
-                body: Some(jwks),
+                body: Some(serde_json::to_vec(&jwks).expect("jwks json")),
                 calls: Cell::new(0),
             }
         }
@@ -1326,6 +1773,193 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pinned_directories_verify_offline_with_operator_overrides_and_key_pins() {
+        let store = crate::test_support::InMemoryStore::default();
+        let mut cfg = native_enabled_config();
+        let mut jwks = successful_test_jwks();
+        jwks.keys.push(other_test_jwk());
+        cfg.verified_identity.pinned_directories = vec![IdentityPinnedDirectory {
+            source_uri: PINNED_SOURCE_URI.to_string(),
+            jwks,
+            auto_refresh: false,
+            operator: Some("Example Search".to_string()),
+            category: Some(crate::bot_identity::contracts::IdentityCategory::Search),
+            pinned_key_ids: vec![TEST_KEY_ID.to_string()],
+        }];
+        let req = externally_signed_request();
+        let created = request_created_at(&req);
+        let fetcher = TestDirectoryFetcher::unavailable();
+
+        let result =
+            verify_request_with_now_and_fetcher(&store, "default", &req, &cfg, created, &fetcher);
+
+        assert_eq!(
+            result.status,
+            crate::bot_identity::verification::IdentityVerificationResultStatus::Verified
+        );
+        let identity = result.identity.expect("verified identity");
+        assert_eq!(identity.operator, "Example Search");
+        assert_eq!(
+            identity.category,
+            crate::bot_identity::contracts::IdentityCategory::Search
+        );
+        assert_eq!(fetcher.call_count(), 0);
+
+        cfg.verified_identity.pinned_directories[0].pinned_key_ids =
+            vec![other_test_jwk().b64_thumbprint()];
+        let result =
+            verify_request_with_now_and_fetcher(&store, "default", &req, &cfg, created, &fetcher);
+        assert_ne!(
+            result.status,
+            crate::bot_identity::verification::IdentityVerificationResultStatus::Verified
+        );
+    }
+
+    #[test]
+    fn auto_refresh_pins_answer_when_the_directory_is_unreachable_and_record_key_rotations() {
+        let store = crate::test_support::InMemoryStore::default();
+        let mut cfg = native_enabled_config();
+        cfg.verified_identity.pinned_directories = vec![IdentityPinnedDirectory {
+            source_uri: PINNED_SOURCE_URI.to_string(),
+            jwks: successful_test_jwks(),
+            auto_refresh: true,
+            operator: None,
+            category: None,
+            pinned_key_ids: Vec::new(),
+        }];
+        let req = externally_signed_request();
+        let created = request_created_at(&req);
+
+        let unreachable = TestDirectoryFetcher::unavailable();
+        let result = verify_request_with_now_and_fetcher(
+            &store,
+            "default",
+            &req,
+            &cfg,
+            created,
+            &unreachable,
+        );
+        assert_eq!(
+            result.status,
+            crate::bot_identity::verification::IdentityVerificationResultStatus::Verified
+        );
+        assert_eq!(unreachable.call_count(), 1);
+        let inventory = directory_inventory(&store, "default", &cfg, created);
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].pin, Some("config"));
+        assert_eq!(inventory[0].freshness, "pinned");
+        assert_eq!(inventory[0].key_ids, vec![TEST_KEY_ID.to_string()]);
+
+        cfg.verified_identity.pinned_directories[0].jwks = JSONWebKeySet {
+            keys: vec![other_test_jwk()],
+        };
+        let reachable = TestDirectoryFetcher::success_for_public_key();
+        let mut key_rotation = None;
+        assert!(super::resolve_external_identity(
+            &store,
+            "default",
+            &cfg,
+            created,
+            TEST_SIGNATURE_AGENT_URL,
+            &reachable,
+            &mut key_rotation,
+        )
+        .is_ok());
+        let inventory = directory_inventory(&store, "default", &cfg, created);
+        assert_eq!(inventory[0].freshness, "fresh");
+        assert_eq!(inventory[0].fetched_at, Some(created));
+        assert_eq!(inventory[0].last_rotation_at, Some(created));
+        let rotation = key_rotation.expect("key rotation");
+        assert_eq!(rotation.added_key_ids, vec![TEST_KEY_ID.to_string()]);
+        assert_eq!(rotation.removed_key_ids, vec![other_test_jwk().b64_thumbprint()]);
+        assert!(!rotation.pinned_keys_missing);
+    }
+
+    #[test]
+    fn auto_refresh_pins_keep_the_last_good_pinned_keys_when_a_rotation_drops_them() {
+        let store = crate::test_support::InMemoryStore::default();
+        let mut cfg = native_enabled_config();
+        cfg.verified_identity.pinned_directories = vec![IdentityPinnedDirectory {
+            source_uri: PINNED_SOURCE_URI.to_string(),
+            jwks: successful_test_jwks(),
+            auto_refresh: true,
+            operator: None,
+            category: None,
+            pinned_key_ids: vec![TEST_KEY_ID.to_string()],
+        }];
+        let req = externally_signed_request();
+        let created = request_created_at(&req);
+        let rotated = TestDirectoryFetcher::success_for_jwks(JSONWebKeySet {
+            keys: vec![other_test_jwk()],
+        });
+
+        let result =
+            verify_request_with_now_and_fetcher(&store, "default", &req, &cfg, created, &rotated);
+
+        assert_eq!(
+            result.status,
+            crate::bot_identity::verification::IdentityVerificationResultStatus::Verified
+        );
+        let rotation = result.directory_key_rotation.expect("key rotation");
+        assert!(rotation.pinned_keys_missing);
+        assert_eq!(rotation.removed_key_ids, vec![TEST_KEY_ID.to_string()]);
+        let inventory = directory_inventory(&store, "default", &cfg, created);
+        assert_eq!(inventory[0].key_ids, vec![TEST_KEY_ID.to_string()]);
+        assert_eq!(inventory[0].last_rotation_at, Some(created));
+        assert_eq!(inventory[0].pinned_keys_missing_since, Some(created));
+
+        let recovered = TestDirectoryFetcher::success_for_public_key();
+        let later = created + cfg.verified_identity.directory_cache_ttl_seconds + 1;
+        let mut key_rotation = None;
+        assert!(super::resolve_external_identity(
+            &store,
+            "default",
+            &cfg,
+            later,
+            TEST_SIGNATURE_AGENT_URL,
+            &recovered,
+            &mut key_rotation,
+        )
+        .is_ok());
+        assert!(key_rotation.is_none());
+        let inventory = directory_inventory(&store, "default", &cfg, later);
+        assert_eq!(inventory[0].pinned_keys_missing_since, None);
+    }
+
+    #[test]
+    fn uploaded_pins_are_validated_listed_and_removable() {
+        let store = crate::test_support::InMemoryStore::default();
+        let cfg = native_enabled_config();
+        let mut pin = IdentityPinnedDirectory {
+            source_uri: "https://signature-agent.test".to_string(),
+            jwks: successful_test_jwks(),
+            auto_refresh: false,
+            operator: None,
+            category: None,
+            pinned_key_ids: Vec::new(),
+        };
+        assert!(upload_pinned_directory(&store, "default", &pin)
+            .expect_err("unnormalized uri")
+            .starts_with("source_uri"));
+        pin.source_uri = PINNED_SOURCE_URI.to_string();
+        pin.pinned_key_ids = vec![other_test_jwk().b64_thumbprint()];
+        assert!(upload_pinned_directory(&store, "default", &pin)
+            .expect_err("key id not in jwks")
+            .starts_with("pinned_key_ids"));
+        pin.pinned_key_ids.clear();
+        upload_pinned_directory(&store, "default", &pin).expect("valid pin");
+
+        let inventory = directory_inventory(&store, "default", &cfg, 100);
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(inventory[0].pin, Some("upload"));
+        assert!(!inventory[0].auto_refresh);
+
+        assert!(remove_pinned_directory(&store, "default", PINNED_SOURCE_URI));
+        assert!(!remove_pinned_directory(&store, "default", PINNED_SOURCE_URI));
+        assert!(directory_inventory(&store, "default", &cfg, 100).is_empty());
+    }
+
     #[test]
     fn verify_request_verifies_self_contained_inline_signature_agent_requests() {
         let store = crate::test_support::InMemoryStore::default();
@@ -1351,6 +1985,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verify_request_attaches_delegations_bound_to_the_signing_key() {
+        let mut cfg = native_enabled_config();
+        cfg.verified_identity.delegation_keys =
+            vec![crate::bot_identity::policy::IdentityDelegationKey {
+                key_id: "session-2026".to_string(),
+                issuer: "https://auth.example.com".to_string(),
+                public_key: general_purpose::STANDARD
+                    .encode(delegation_signing_key().verifying_key().as_bytes()),
+            }];
+        let signature_agent_url = inline_signature_agent_url();
+        let verify_with_delegation = |jkt: &str| {
+            let store = crate::test_support::InMemoryStore::default();
+            let assertion = delegation_assertion(jkt, super::current_unix_timestamp());
+            let req = signed_request_for_signature_agent_with_context(
+                "example.com",
+                signature_agent_url.as_str(),
+                "agent1",
+                None,
+                &[("agent-delegation", assertion.as_str())],
+            );
+            verify_request(&store, "default", &req, &cfg)
+                .identity
+                .expect("verified identity")
+        };
+
+        let delegated = verify_with_delegation(TEST_KEY_ID);
+        let delegation = delegated.delegation.expect("delegation");
+        assert_eq!(delegation.key_id, "session-2026");
+        assert_eq!(
+            delegated.category,
+            crate::bot_identity::contracts::IdentityCategory::UserTriggeredAgent
+        );
+        assert!(delegated.end_user_controlled);
+
+        let unbound = verify_with_delegation("some-other-agent-key");
+        assert!(unbound.delegation.is_none());
+        assert_eq!(
+            unbound.category,
+            crate::bot_identity::contracts::IdentityCategory::Other
+        );
+    }
+
     #[test]
     fn verify_request_accepts_trusted_forwarded_https_for_signed_scheme() {
         let _lock = crate::test_support::lock_env();
@@ -1487,6 +2164,10 @@ mod tests {
     }
 
     fn inline_signed_request() -> Request {
+        signed_request_for_signature_agent(inline_signature_agent_url().as_str(), "agent1")
+    }
+
+    fn inline_signature_agent_url() -> String {
         let public_key = Thumbprintable::OKP {
             crv: "Ed25519".to_string(),
             x: general_purpose::URL_SAFE_NO_PAD.encode(TEST_PUBLIC_KEY),
@@ -1495,11 +2176,41 @@ mod tests {
             "keys": [public_key]
         })
         .to_string();
-        let signature_agent_url = format!(
+        format!(
             "data:application/http-message-signatures-directory;base64,{}",
             general_purpose::STANDARD.encode(jwks.as_bytes())
+        )
+    }
+
+    fn delegation_assertion(jkt: &str, now: u64) -> String {
+        use ed25519_dalek::Signer;
+
+        let signing_input = format!(
+            "{}.{}",
+            general_purpose::URL_SAFE_NO_PAD
+                .encode(serde_json::json!({ "alg": "EdDSA", "kid": "session-2026" }).to_string()),
+            general_purpose::URL_SAFE_NO_PAD.encode(
+                serde_json::json!({
+                    "iss": "https://auth.example.com",
+                    "aud": "example.com",
+                    "sub": "session:42",
+                    "cnf": { "jkt": jkt },
+                    "iat": now - 60,
+                    "exp": now + 3_600
+                })
+                .to_string()
+            )
         );
-        signed_request_for_signature_agent(signature_agent_url.as_str(), "agent1")
+        let signature = delegation_signing_key().sign(signing_input.as_bytes());
+        format!(
+            "{}.{}",
+            signing_input,
+            general_purpose::URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        )
+    }
+
+    fn delegation_signing_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[23u8; 32])
     }
 
     fn signed_request_for_signature_agent(signature_agent_url: &str, signature_agent_key: &str) -> Request {
@@ -1562,6 +2273,17 @@ mod tests {
         }
     }
 
+    fn other_test_jwk() -> Thumbprintable {
+        Thumbprintable::OKP {
+            crv: "Ed25519".to_string(),
+            x: general_purpose::URL_SAFE_NO_PAD.encode(
+                ed25519_dalek::SigningKey::from_bytes(&[7u8; 32])
+                    .verifying_key()
+                    .as_bytes(),
+            ),
+        }
+    }
+
     fn request_created_at(req: &Request) -> u64 {
         super::parse_request_verifier(req)
             .expect("signed request should parse")
@@ -33,6 +33,8 @@ pub(crate) struct IdentityPolicyMatcher {
     pub operator: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category: Option<IdentityCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegated: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub path_prefixes: Vec<String>,
 }
@@ -43,6 +45,7 @@ impl IdentityPolicyMatcher {
             && self.stable_identity.is_none()
             && self.operator.is_none()
             && self.category.is_none()
+            && self.delegated.is_none()
             && self.path_prefixes.is_empty()
     }
 }
@@ -55,6 +58,7 @@ pub(crate) enum IdentityPolicyAction {
     Observe,
     Allow,
     UseServiceProfile(String),
+    RequireLicence(String),
 }
 
 impl IdentityPolicyAction {
@@ -65,6 +69,7 @@ impl IdentityPolicyAction {
             IdentityPolicyAction::Observe => "observe",
             IdentityPolicyAction::Allow => "allow",
             IdentityPolicyAction::UseServiceProfile(_) => "use_service_profile",
+            IdentityPolicyAction::RequireLicence(_) => "require_licence",
         }
     }
 
@@ -74,6 +79,13 @@ impl IdentityPolicyAction {
             _ => None,
         }
     }
+
+    pub fn referenced_licence_offer_id(&self) -> Option<&str> {
+        match self {
+            IdentityPolicyAction::RequireLicence(offer_id) => Some(offer_id.as_str()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -102,6 +114,136 @@ pub(crate) struct IdentityServiceProfileBinding {
     pub description: Option<String>,
 }
 
+/// Commercial terms offered to verified crawlers by `require_licence`. `price` is a decimal
+/// amount in `currency` (ISO 4217) and is only advertised; settlement happens off-path with
+/// whoever issues licence tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityLicenceOffer {
+    pub offer_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub price: String,
+    pub currency: String,
+    pub terms_url: String,
+}
+
+/// Ed25519 public key (base64, 32 bytes) trusted to sign licence tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityLicenceKey {
+    pub key_id: String,
+    pub public_key: String,
+}
+
+/// A signature-agent key directory pinned by the operator, for deployments that cannot reach it.
+/// Without `auto_refresh` the pinned JWKS is used as-is; with it the directory is still fetched
+/// and the pin only answers once the cache is stale. `pinned_key_ids` (JWK thumbprints) restricts
+/// which keys are trusted, and `operator`/`category` override what the directory URL implies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityPinnedDirectory {
+    pub source_uri: String,
+    pub jwks: web_bot_auth::keyring::JSONWebKeySet,
+    #[serde(default)]
+    pub auto_refresh: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<IdentityCategory>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_key_ids: Vec<String>,
+}
+
+/// Ed25519 public key (base64, 32 bytes) trusted to sign agent-delegation assertions for human
+/// sessions. Assertions under this key must carry `issuer` as their `iss` claim.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityDelegationKey {
+    pub key_id: String,
+    pub issuer: String,
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IdentityQuotaWindow {
+    Daily,
+    Monthly,
+}
+
+impl IdentityQuotaWindow {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentityQuotaWindow::Daily => "daily",
+            IdentityQuotaWindow::Monthly => "monthly",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IdentityQuotaScope {
+    #[default]
+    StableIdentity,
+    Operator,
+}
+
+impl IdentityQuotaScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentityQuotaScope::StableIdentity => "stable_identity",
+            IdentityQuotaScope::Operator => "operator",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IdentityQuotaOverageAction {
+    Observe,
+    Throttle,
+    Block,
+    Challenge,
+}
+
+impl IdentityQuotaOverageAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentityQuotaOverageAction::Observe => "observe",
+            IdentityQuotaOverageAction::Throttle => "throttle",
+            IdentityQuotaOverageAction::Block => "block",
+            IdentityQuotaOverageAction::Challenge => "challenge",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityUsageQuota {
+    pub quota_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub matcher: IdentityPolicyMatcher,
+    #[serde(default)]
+    pub scope: IdentityQuotaScope,
+    pub window: IdentityQuotaWindow,
+    pub max_requests: u64,
+    pub overage_action: IdentityQuotaOverageAction,
+}
+
+/// First configured quota whose matcher covers this identity and path. Quotas meter verified
+/// traffic whatever the stance override mode, so unlike named policies they always apply.
+pub(crate) fn matching_usage_quota<'a>(
+    quotas: &'a [IdentityUsageQuota],
+    identity: &super::contracts::VerifiedIdentityEvidence,
+    request_path: &str,
+) -> Option<&'a IdentityUsageQuota> {
+    quotas
+        .iter()
+        .find(|quota| matcher_matches(&quota.matcher, identity, request_path))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum IdentityPolicyOutcome {
@@ -111,6 +253,7 @@ pub(crate) enum IdentityPolicyOutcome {
     Observe,
     Allow,
     UseServiceProfile(ServiceProfile),
+    RequireLicence,
 }
 
 impl IdentityPolicyOutcome {
@@ -122,6 +265,7 @@ impl IdentityPolicyOutcome {
             IdentityPolicyOutcome::Observe => "observe",
             IdentityPolicyOutcome::Allow => "allow",
             IdentityPolicyOutcome::UseServiceProfile(_) => "use_service_profile",
+            IdentityPolicyOutcome::RequireLicence => "require_licence",
         }
     }
 }
@@ -152,6 +296,7 @@ pub(crate) struct IdentityPolicyContext {
 pub(crate) struct IdentityPolicyResolution {
     pub outcome: IdentityPolicyOutcome,
     pub service_profile_id: Option<String>,
+    pub licence_offer_id: Option<String>,
     pub profile_id: String,
     pub verified_identity_override_mode: String,
     pub canonical_category_id: String,
@@ -232,6 +377,7 @@ pub(crate) fn resolve_identity_policy(
     IdentityPolicyResolution {
         outcome: fallback_outcome_for_posture(context.base_posture.as_str()),
         service_profile_id: None,
+        licence_offer_id: None,
         profile_id: context.profile_id.clone(),
         verified_identity_override_mode: context.verified_identity_override_mode.clone(),
         canonical_category_id: context.canonical_category_id.clone(),
@@ -278,6 +424,12 @@ fn matcher_matches(
     {
         return false;
     }
+    if matcher
+        .delegated
+        .is_some_and(|delegated| delegated != identity.delegation.is_some())
+    {
+        return false;
+    }
     if matcher.path_prefixes.is_empty() {
         return true;
     }
@@ -297,6 +449,7 @@ fn resolution_from_action(
         IdentityPolicyAction::Deny => IdentityPolicyResolution {
             outcome: IdentityPolicyOutcome::Deny,
             service_profile_id: None,
+            licence_offer_id: None,
             profile_id: context.profile_id.clone(),
             verified_identity_override_mode: context.verified_identity_override_mode.clone(),
             canonical_category_id: context.canonical_category_id.clone(),
@@ -306,6 +459,7 @@ fn resolution_from_action(
         IdentityPolicyAction::Restrict => IdentityPolicyResolution {
             outcome: IdentityPolicyOutcome::Restrict,
             service_profile_id: None,
+            licence_offer_id: None,
             profile_id: context.profile_id.clone(),
             verified_identity_override_mode: context.verified_identity_override_mode.clone(),
             canonical_category_id: context.canonical_category_id.clone(),
@@ -315,6 +469,7 @@ fn resolution_from_action(
         IdentityPolicyAction::Observe => IdentityPolicyResolution {
             outcome: IdentityPolicyOutcome::Observe,
             service_profile_id: None,
+            licence_offer_id: None,
             profile_id: context.profile_id.clone(),
             verified_identity_override_mode: context.verified_identity_override_mode.clone(),
             canonical_category_id: context.canonical_category_id.clone(),
@@ -324,6 +479,17 @@ fn resolution_from_action(
         IdentityPolicyAction::Allow => IdentityPolicyResolution {
             outcome: IdentityPolicyOutcome::Allow,
             service_profile_id: None,
+            licence_offer_id: None,
+            profile_id: context.profile_id.clone(),
+            verified_identity_override_mode: context.verified_identity_override_mode.clone(),
+            canonical_category_id: context.canonical_category_id.clone(),
+            base_posture: context.base_posture.clone(),
+            source,
+        },
+        IdentityPolicyAction::RequireLicence(offer_id) => IdentityPolicyResolution {
+            outcome: IdentityPolicyOutcome::RequireLicence,
+            service_profile_id: None,
+            licence_offer_id: Some(offer_id.clone()),
             profile_id: context.profile_id.clone(),
             verified_identity_override_mode: context.verified_identity_override_mode.clone(),
             canonical_category_id: context.canonical_category_id.clone(),
@@ -341,6 +507,7 @@ fn resolution_from_action(
             IdentityPolicyResolution {
                 outcome: IdentityPolicyOutcome::UseServiceProfile(profile),
                 service_profile_id: Some(profile_id.clone()),
+                licence_offer_id: None,
                 profile_id: context.profile_id.clone(),
                 verified_identity_override_mode: context.verified_identity_override_mode.clone(),
                 canonical_category_id: context.canonical_category_id.clone(),
@@ -355,8 +522,8 @@ fn resolution_from_action(
 mod tests {
     use super::*;
     use crate::bot_identity::contracts::{
-        IdentityCategory, IdentityProvenance, IdentityScheme, VerificationStrength,
-        VerifiedIdentityEvidence,
+        IdentityCategory, IdentityDelegation, IdentityProvenance, IdentityScheme,
+        VerificationStrength, VerifiedIdentityEvidence,
     };
 
     fn identity() -> VerifiedIdentityEvidence {
@@ -369,6 +536,7 @@ mod tests {
             end_user_controlled: true,
             directory_source: None,
             provenance: IdentityProvenance::Provider,
+            delegation: None,
         }
     }
 
@@ -410,6 +578,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn matching_usage_quota_takes_the_first_quota_covering_identity_and_path() {
+        let quota = |quota_id: &str, matcher: IdentityPolicyMatcher| IdentityUsageQuota {
+            quota_id: quota_id.to_string(),
+            description: None,
+            matcher,
+            scope: IdentityQuotaScope::StableIdentity,
+            window: IdentityQuotaWindow::Daily,
+            max_requests: 10_000,
+            overage_action: IdentityQuotaOverageAction::Block,
+        };
+        let quotas = vec![
+            quota(
+                "openai-docs",
+                IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    path_prefixes: vec!["/docs".to_string()],
+                    ..IdentityPolicyMatcher::default()
+                },
+            ),
+            quota(
+                "openai",
+                IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    ..IdentityPolicyMatcher::default()
+                },
+            ),
+        ];
+
+        assert_eq!(
+            matching_usage_quota(&quotas, &identity(), "/docs/a").map(|q| q.quota_id.as_str()),
+            Some("openai-docs")
+        );
+        assert_eq!(
+            matching_usage_quota(&quotas, &identity(), "/blog").map(|q| q.quota_id.as_str()),
+            Some("openai")
+        );
+        let mut other = identity();
+        other.operator = "example".to_string();
+        assert!(matching_usage_quota(&quotas, &other, "/docs").is_none());
+    }
+
     #[test]
     fn resolve_identity_policy_prefers_first_matching_named_policy() {
         let policies = vec![
@@ -468,6 +678,42 @@ mod tests {
         assert_eq!(resolution.source_id(), "agent_on_behalf_of_human");
     }
 
+    #[test]
+    fn resolve_identity_policy_can_target_delegated_agents_only() {
+        let policies = vec![IdentityPolicyEntry {
+            policy_id: "delegated-browser-like".to_string(),
+            description: None,
+            matcher: IdentityPolicyMatcher {
+                delegated: Some(true),
+                ..IdentityPolicyMatcher::default()
+            },
+            action: IdentityPolicyAction::UseServiceProfile("browser_like".to_string()),
+        }];
+        let resolve = |identity: &VerifiedIdentityEvidence| {
+            resolve_identity_policy(
+                &relaxed_context(),
+                &policies,
+                &[],
+                &service_profiles(),
+                identity,
+                "/inbox",
+            )
+        };
+
+        assert_eq!(resolve(&identity()).outcome, IdentityPolicyOutcome::Deny);
+
+        let mut delegated = identity();
+        delegated.delegation = Some(Box::new(IdentityDelegation {
+            key_id: "session-2026".to_string(),
+            subject_digest: "0123456789abcdef01234567".to_string(),
+            expires_at: 1_792_414_800,
+        }));
+        assert_eq!(
+            resolve(&delegated).outcome,
+            IdentityPolicyOutcome::UseServiceProfile(ServiceProfile::BrowserLike)
+        );
+    }
+
     #[test]
     fn resolve_identity_policy_uses_category_defaults_for_category_stances() {
         let category_defaults = vec![IdentityCategoryDefaultAction {
@@ -561,6 +807,31 @@ mod tests {
         assert_eq!(restrict.outcome, IdentityPolicyOutcome::Restrict);
     }
 
+    #[test]
+    fn resolve_identity_policy_carries_the_licence_offer_for_require_licence() {
+        let resolution = resolve_identity_policy(
+            &relaxed_context(),
+            &[IdentityPolicyEntry {
+                policy_id: "licence-openai".to_string(),
+                description: None,
+                matcher: IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    ..IdentityPolicyMatcher::default()
+                },
+                action: IdentityPolicyAction::RequireLicence("archive".to_string()),
+            }],
+            &[],
+            &service_profiles(),
+            &identity(),
+            "/articles/1",
+        );
+
+        assert_eq!(resolution.outcome, IdentityPolicyOutcome::RequireLicence);
+        assert_eq!(resolution.licence_offer_id.as_deref(), Some("archive"));
+        assert_eq!(resolution.service_profile_id, None);
+        assert_eq!(resolution.source_id(), "licence-openai");
+    }
+
     #[test]
     fn resolve_identity_policy_strict_profiles_suppress_named_overrides() {
         let resolution = resolve_identity_policy(
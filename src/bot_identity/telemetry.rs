@@ -86,3 +86,39 @@ impl IdentityVerificationTelemetryRecord {
         }
     }
 }
+
+/// One request served to a verified identity, metered after the response is final so the byte
+/// count reflects what was actually returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityUsageRecord {
+    pub scheme: IdentityScheme,
+    pub category: IdentityCategory,
+    pub operator: String,
+    pub stable_identity: String,
+    #[serde(default)]
+    pub delegated: bool,
+    pub path: String,
+    pub status: u16,
+    pub bytes: u64,
+}
+
+impl IdentityUsageRecord {
+    pub fn from_response(
+        identity: &super::contracts::VerifiedIdentityEvidence,
+        path: &str,
+        status: u16,
+        bytes: u64,
+    ) -> Self {
+        Self {
+            scheme: identity.scheme,
+            category: identity.category,
+            operator: identity.operator.clone(),
+            stable_identity: identity.stable_identity.clone(),
+            delegated: identity.delegation.is_some(),
+            path: path.to_string(),
+            status,
+            bytes,
+        }
+    }
+}
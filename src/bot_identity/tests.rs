@@ -83,6 +83,7 @@ fn verification_telemetry_preserves_verified_identity_metadata() {
                 end_user_controlled: true,
                 directory_source: None,
                 provenance: IdentityProvenance::Provider,
+                delegation: None,
             },
             IdentityVerificationFreshness::Fresh,
         ),
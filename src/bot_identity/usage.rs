@@ -0,0 +1,633 @@
+//! Per-identity usage metering and quota counters for verified identities.
+//!
+//! Every response served to a verified identity is folded into a daily rollup keyed by the
+//! identity, which backs the admin usage report. Quota counters are kept separately, one per
+//! configured quota, subject, and daily or monthly window. Requests an identity makes under an
+//! agent delegation are metered apart from its own traffic.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::contracts::{IdentityCategory, IdentityScheme, VerifiedIdentityEvidence};
+use super::kv_json::{read_json, write_json};
+use super::policy::{IdentityQuotaScope, IdentityQuotaWindow, IdentityUsageQuota};
+use super::telemetry::IdentityUsageRecord;
+use crate::challenge::KeyValueStore;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+/// Distinct paths tracked per identity and day; further paths fold into `OTHER_PATHS`.
+const MAX_TRACKED_PATHS: usize = 50;
+/// Identities listed in one day's index. Identities beyond the cap are not metered that day.
+const MAX_IDENTITIES_PER_DAY: usize = 1_000;
+const MAX_PATH_CHARS: usize = 256;
+const OTHER_PATHS: &str = "(other)";
+pub(crate) const USAGE_REPORT_MAX_DAYS: u32 = 62;
+const REPORT_TOP_PATHS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+struct PathUsage {
+    requests: u64,
+    bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IdentityUsageDay {
+    scheme: IdentityScheme,
+    stable_identity: String,
+    operator: String,
+    category: IdentityCategory,
+    #[serde(default)]
+    delegated: bool,
+    requests: u64,
+    bytes: u64,
+    non_success_responses: u64,
+    paths: BTreeMap<String, PathUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct IdentityPathUsage {
+    pub path: String,
+    pub requests: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct IdentityUsageSummary {
+    pub scheme: IdentityScheme,
+    pub stable_identity: String,
+    pub operator: String,
+    pub category: IdentityCategory,
+    pub delegated: bool,
+    pub requests: u64,
+    pub bytes: u64,
+    pub non_success_responses: u64,
+    pub distinct_paths: usize,
+    pub top_paths: Vec<IdentityPathUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct OperatorUsageSummary {
+    pub operator: String,
+    pub identities: usize,
+    pub requests: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct IdentityUsageReport {
+    pub days: u32,
+    pub from_day: String,
+    pub to_day: String,
+    pub requests: u64,
+    pub bytes: u64,
+    pub identities: Vec<IdentityUsageSummary>,
+    pub operators: Vec<OperatorUsageSummary>,
+}
+
+/// Counter bucket for a quota subject. Identifiers are hashed so operator-supplied strings
+/// cannot shape KV keys.
+pub(crate) fn quota_subject_key(
+    quota: &IdentityUsageQuota,
+    identity: &VerifiedIdentityEvidence,
+) -> String {
+    let subject = match quota.scope {
+        IdentityQuotaScope::StableIdentity => identity_subject(
+            identity.scheme,
+            identity.stable_identity.as_str(),
+            identity.delegation.is_some(),
+        ),
+        IdentityQuotaScope::Operator => format!("operator:{}", identity.operator),
+    };
+    hashed_key(subject.as_str())
+}
+
+/// Counts one request against the quota window and returns the running total, including this
+/// request. The first request of a window drops the previous window's counter.
+pub(crate) fn consume_quota<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    quota_id: &str,
+    subject_key: &str,
+    window: IdentityQuotaWindow,
+    now: u64,
+) -> u64 {
+    let key = quota_counter_key(site_id, quota_id, subject_key, window_bucket(window, now));
+    let count = read_counter(store, key.as_str());
+    if count == 0 {
+        let previous = previous_window_start(window, now);
+        let _ = store.delete(
+            quota_counter_key(
+                site_id,
+                quota_id,
+                subject_key,
+                window_bucket(window, previous),
+            )
+            .as_str(),
+        );
+    }
+    let next = count.saturating_add(1);
+    if let Err(err) = store.set(key.as_str(), next.to_string().as_bytes()) {
+        eprintln!(
+            "[verified_identity] failed to persist quota counter for key {}: {:?}",
+            key, err
+        );
+    }
+    next
+}
+
+/// Seconds until the quota window resets.
+pub(crate) fn quota_retry_after_seconds(window: IdentityQuotaWindow, now: u64) -> u64 {
+    match window {
+        IdentityQuotaWindow::Daily => SECONDS_PER_DAY - now % SECONDS_PER_DAY,
+        IdentityQuotaWindow::Monthly => next_month_start(now).saturating_sub(now).max(1),
+    }
+}
+
+pub(crate) fn record_usage<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    record: &IdentityUsageRecord,
+    now: u64,
+) {
+    let day = day_label(now);
+    let identity_key = hashed_key(
+        identity_subject(
+            record.scheme,
+            record.stable_identity.as_str(),
+            record.delegated,
+        )
+        .as_str(),
+    );
+    let index_key = usage_index_key(site_id, day.as_str());
+    let mut index: Vec<String> = read_json(store, index_key.as_str()).unwrap_or_else(|| {
+        purge_expired_day(store, site_id, now);
+        Vec::new()
+    });
+    if !index.contains(&identity_key) {
+        if index.len() >= MAX_IDENTITIES_PER_DAY {
+            return;
+        }
+        index.push(identity_key.clone());
+        write_json(store, index_key.as_str(), &index, "usage rollup");
+    }
+
+    let row_key = usage_row_key(site_id, day.as_str(), identity_key.as_str());
+    let mut row = read_json::<_, IdentityUsageDay>(store, row_key.as_str()).unwrap_or_else(|| {
+        IdentityUsageDay {
+            scheme: record.scheme,
+            stable_identity: record.stable_identity.clone(),
+            operator: record.operator.clone(),
+            category: record.category,
+            delegated: record.delegated,
+            requests: 0,
+            bytes: 0,
+            non_success_responses: 0,
+            paths: BTreeMap::new(),
+        }
+    });
+    row.operator = record.operator.clone();
+    row.category = record.category;
+    row.requests = row.requests.saturating_add(1);
+    row.bytes = row.bytes.saturating_add(record.bytes);
+    if !(200..400).contains(&record.status) {
+        row.non_success_responses = row.non_success_responses.saturating_add(1);
+    }
+    let path = normalized_path(record.path.as_str());
+    let path = if row.paths.contains_key(path.as_str()) || row.paths.len() < MAX_TRACKED_PATHS {
+        path
+    } else {
+        OTHER_PATHS.to_string()
+    };
+    let path_usage = row.paths.entry(path).or_default();
+    path_usage.requests = path_usage.requests.saturating_add(1);
+    path_usage.bytes = path_usage.bytes.saturating_add(record.bytes);
+    write_json(store, row_key.as_str(), &row, "usage rollup");
+}
+
+/// Aggregate the last `days` daily rollups (including today) per identity and per operator.
+pub(crate) fn usage_report<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    days: u32,
+    now: u64,
+) -> IdentityUsageReport {
+    let days = days.clamp(1, USAGE_REPORT_MAX_DAYS);
+    let mut identities: BTreeMap<String, (IdentityUsageDay, BTreeMap<String, PathUsage>)> =
+        BTreeMap::new();
+    for offset in 0..u64::from(days) {
+        let day = day_label(now.saturating_sub(offset * SECONDS_PER_DAY));
+        let index: Vec<String> =
+            read_json(store, usage_index_key(site_id, day.as_str()).as_str()).unwrap_or_default();
+        for identity_key in index {
+            let Some(row) = read_json::<_, IdentityUsageDay>(
+                store,
+                usage_row_key(site_id, day.as_str(), identity_key.as_str()).as_str(),
+            ) else {
+                continue;
+            };
+            let (total, paths) = identities.entry(identity_key).or_insert_with(|| {
+                (
+                    IdentityUsageDay {
+                        requests: 0,
+                        bytes: 0,
+                        non_success_responses: 0,
+                        paths: BTreeMap::new(),
+                        ..row.clone()
+                    },
+                    BTreeMap::new(),
+                )
+            });
+            total.requests = total.requests.saturating_add(row.requests);
+            total.bytes = total.bytes.saturating_add(row.bytes);
+            total.non_success_responses = total
+                .non_success_responses
+                .saturating_add(row.non_success_responses);
+            for (path, usage) in row.paths {
+                let entry = paths.entry(path).or_default();
+                entry.requests = entry.requests.saturating_add(usage.requests);
+                entry.bytes = entry.bytes.saturating_add(usage.bytes);
+            }
+        }
+    }
+
+    let mut summaries: Vec<IdentityUsageSummary> = identities
+        .into_values()
+        .map(|(total, paths)| {
+            let distinct_paths = paths.len();
+            let mut top_paths: Vec<IdentityPathUsage> = paths
+                .into_iter()
+                .map(|(path, usage)| IdentityPathUsage {
+                    path,
+                    requests: usage.requests,
+                    bytes: usage.bytes,
+                })
+                .collect();
+            top_paths.sort_by(|left, right| {
+                right
+                    .requests
+                    .cmp(&left.requests)
+                    .then_with(|| left.path.cmp(&right.path))
+            });
+            top_paths.truncate(REPORT_TOP_PATHS);
+            IdentityUsageSummary {
+                scheme: total.scheme,
+                stable_identity: total.stable_identity,
+                operator: total.operator,
+                category: total.category,
+                delegated: total.delegated,
+                requests: total.requests,
+                bytes: total.bytes,
+                non_success_responses: total.non_success_responses,
+                distinct_paths,
+                top_paths,
+            }
+        })
+        .collect();
+    summaries.sort_by(|left, right| {
+        right
+            .requests
+            .cmp(&left.requests)
+            .then_with(|| left.stable_identity.cmp(&right.stable_identity))
+    });
+
+    let mut operators: BTreeMap<String, OperatorUsageSummary> = BTreeMap::new();
+    for summary in &summaries {
+        let entry = operators
+            .entry(summary.operator.clone())
+            .or_insert_with(|| OperatorUsageSummary {
+                operator: summary.operator.clone(),
+                identities: 0,
+                requests: 0,
+                bytes: 0,
+            });
+        entry.identities += 1;
+        entry.requests = entry.requests.saturating_add(summary.requests);
+        entry.bytes = entry.bytes.saturating_add(summary.bytes);
+    }
+    let mut operators: Vec<OperatorUsageSummary> = operators.into_values().collect();
+    operators.sort_by(|left, right| {
+        right
+            .requests
+            .cmp(&left.requests)
+            .then_with(|| left.operator.cmp(&right.operator))
+    });
+
+    IdentityUsageReport {
+        days,
+        from_day: day_label(now.saturating_sub(u64::from(days - 1) * SECONDS_PER_DAY)),
+        to_day: day_label(now),
+        requests: summaries.iter().map(|summary| summary.requests).sum(),
+        bytes: summaries.iter().map(|summary| summary.bytes).sum(),
+        identities: summaries,
+        operators,
+    }
+}
+
+fn identity_subject(scheme: IdentityScheme, stable_identity: &str, delegated: bool) -> String {
+    let subject = format!("identity:{}:{}", scheme.as_str(), stable_identity);
+    if delegated {
+        format!("{}:delegated", subject)
+    } else {
+        subject
+    }
+}
+
+/// Rollups are kept for the longest report window. Purging happens once per day, when the
+/// first request of a new day creates that day's index.
+fn purge_expired_day<S: KeyValueStore>(store: &S, site_id: &str, now: u64) {
+    let expired = day_label(now.saturating_sub(u64::from(USAGE_REPORT_MAX_DAYS) * SECONDS_PER_DAY));
+    let index_key = usage_index_key(site_id, expired.as_str());
+    let Some(index) = read_json::<_, Vec<String>>(store, index_key.as_str()) else {
+        return;
+    };
+    for identity_key in index {
+        let _ =
+            store.delete(usage_row_key(site_id, expired.as_str(), identity_key.as_str()).as_str());
+    }
+    let _ = store.delete(index_key.as_str());
+}
+
+fn hashed_key(subject: &str) -> String {
+    format!("{:x}", Sha256::digest(subject.as_bytes()))[..24].to_string()
+}
+
+fn normalized_path(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    let path = if path.is_empty() { "/" } else { path };
+    path.chars().take(MAX_PATH_CHARS).collect()
+}
+
+fn quota_counter_key(site_id: &str, quota_id: &str, subject_key: &str, bucket: String) -> String {
+    format!(
+        "verified_identity_quota:{}:{}:{}:{}",
+        site_id, quota_id, subject_key, bucket
+    )
+}
+
+fn usage_index_key(site_id: &str, day: &str) -> String {
+    format!("verified_identity_usage_index:{}:{}", site_id, day)
+}
+
+fn usage_row_key(site_id: &str, day: &str, identity_key: &str) -> String {
+    format!(
+        "verified_identity_usage:{}:{}:{}",
+        site_id, day, identity_key
+    )
+}
+
+fn read_counter<S: KeyValueStore>(store: &S, key: &str) -> u64 {
+    store
+        .get(key)
+        .ok()
+        .flatten()
+        .and_then(|value| String::from_utf8(value).ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+fn date_of(now: u64) -> time::Date {
+    time::OffsetDateTime::from_unix_timestamp(now as i64)
+        .unwrap_or(time::OffsetDateTime::UNIX_EPOCH)
+        .date()
+}
+
+fn day_label(now: u64) -> String {
+    let date = date_of(now);
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+fn window_bucket(window: IdentityQuotaWindow, now: u64) -> String {
+    match window {
+        IdentityQuotaWindow::Daily => format!("d{}", day_label(now)),
+        IdentityQuotaWindow::Monthly => {
+            let date = date_of(now);
+            format!("m{:04}-{:02}", date.year(), u8::from(date.month()))
+        }
+    }
+}
+
+fn previous_window_start(window: IdentityQuotaWindow, now: u64) -> u64 {
+    match window {
+        IdentityQuotaWindow::Daily => now.saturating_sub(SECONDS_PER_DAY),
+        IdentityQuotaWindow::Monthly => {
+            let date = date_of(now);
+            let month_start =
+                now - u64::from(date.day() - 1) * SECONDS_PER_DAY - now % SECONDS_PER_DAY;
+            month_start.saturating_sub(1)
+        }
+    }
+}
+
+fn next_month_start(now: u64) -> u64 {
+    let date = date_of(now);
+    let (year, month) = match date.month() {
+        time::Month::December => (date.year() + 1, time::Month::January),
+        month => (date.year(), month.next()),
+    };
+    time::Date::from_calendar_date(year, month, 1)
+        .map(|date| date.midnight().assume_utc().unix_timestamp().max(0) as u64)
+        .unwrap_or(now + SECONDS_PER_DAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot_identity::contracts::{IdentityProvenance, VerificationStrength};
+    use crate::bot_identity::policy::{IdentityPolicyMatcher, IdentityQuotaOverageAction};
+
+    /// 2026-10-19T12:00:00Z.
+    const NOW: u64 = 1_792_411_200;
+
+    fn identity(stable_identity: &str, operator: &str) -> VerifiedIdentityEvidence {
+        VerifiedIdentityEvidence {
+            scheme: IdentityScheme::HttpMessageSignatures,
+            stable_identity: stable_identity.to_string(),
+            operator: operator.to_string(),
+            category: IdentityCategory::Search,
+            verification_strength: VerificationStrength::Cryptographic,
+            end_user_controlled: false,
+            directory_source: None,
+            provenance: IdentityProvenance::Native,
+            delegation: None,
+        }
+    }
+
+    fn quota(scope: IdentityQuotaScope, window: IdentityQuotaWindow) -> IdentityUsageQuota {
+        IdentityUsageQuota {
+            quota_id: "search".to_string(),
+            description: None,
+            matcher: IdentityPolicyMatcher::default(),
+            scope,
+            window,
+            max_requests: 2,
+            overage_action: IdentityQuotaOverageAction::Block,
+        }
+    }
+
+    #[test]
+    fn quota_counters_follow_scope_and_reset_with_the_window() {
+        let store = crate::test_support::InMemoryStore::default();
+        let daily = quota(
+            IdentityQuotaScope::StableIdentity,
+            IdentityQuotaWindow::Daily,
+        );
+        let first = quota_subject_key(&daily, &identity("searchbot", "example"));
+        let second = quota_subject_key(&daily, &identity("searchbot-news", "example"));
+        assert_ne!(first, second);
+        let by_operator = quota(IdentityQuotaScope::Operator, IdentityQuotaWindow::Daily);
+        assert_eq!(
+            quota_subject_key(&by_operator, &identity("searchbot", "example")),
+            quota_subject_key(&by_operator, &identity("searchbot-news", "example"))
+        );
+
+        let consume = |now| {
+            consume_quota(
+                &store,
+                "default",
+                "search",
+                first.as_str(),
+                IdentityQuotaWindow::Daily,
+                now,
+            )
+        };
+        assert_eq!(consume(NOW), 1);
+        assert_eq!(consume(NOW + 60), 2);
+        assert_eq!(consume(NOW + 120), 3);
+        assert_eq!(consume(NOW + SECONDS_PER_DAY), 1);
+        assert_eq!(
+            quota_retry_after_seconds(IdentityQuotaWindow::Daily, NOW),
+            12 * 3_600
+        );
+        // 2026-11-01T00:00:00Z.
+        assert_eq!(
+            quota_retry_after_seconds(IdentityQuotaWindow::Monthly, NOW),
+            1_793_491_200 - NOW
+        );
+        assert_eq!(window_bucket(IdentityQuotaWindow::Monthly, NOW), "m2026-10");
+    }
+
+    #[test]
+    fn usage_report_aggregates_requests_bytes_and_paths_per_identity_and_operator() {
+        let store = crate::test_support::InMemoryStore::default();
+        let searchbot = identity("searchbot", "example");
+        let newsbot = identity("newsbot", "example");
+        let other = identity("crawler", "other-co");
+        let record = |who: &VerifiedIdentityEvidence, path: &str, status: u16, bytes: u64, now| {
+            record_usage(
+                &store,
+                "default",
+                &IdentityUsageRecord::from_response(who, path, status, bytes),
+                now,
+            );
+        };
+        record(
+            &searchbot,
+            "/docs?page=2",
+            200,
+            1_000,
+            NOW - SECONDS_PER_DAY,
+        );
+        record(&searchbot, "/docs", 200, 500, NOW);
+        record(&searchbot, "/private", 403, 50, NOW);
+        record(&newsbot, "/news", 200, 200, NOW);
+        record(&other, "/", 200, 10, NOW - 10 * SECONDS_PER_DAY);
+
+        let report = usage_report(&store, "default", 7, NOW);
+
+        assert_eq!(report.from_day, "2026-10-13");
+        assert_eq!(report.to_day, "2026-10-19");
+        assert_eq!(report.requests, 4);
+        assert_eq!(report.bytes, 1_750);
+        assert_eq!(report.identities.len(), 2);
+        let top = &report.identities[0];
+        assert_eq!(top.stable_identity, "searchbot");
+        assert_eq!(top.requests, 3);
+        assert_eq!(top.bytes, 1_550);
+        assert_eq!(top.non_success_responses, 1);
+        assert_eq!(top.top_paths[0].path, "/docs");
+        assert_eq!(top.top_paths[0].requests, 2);
+        assert_eq!(report.operators.len(), 1);
+        assert_eq!(report.operators[0].identities, 2);
+        assert_eq!(report.operators[0].requests, 4);
+        assert_eq!(usage_report(&store, "default", 30, NOW).requests, 5);
+    }
+
+    #[test]
+    fn usage_rollups_cap_tracked_paths_and_purge_expired_days() {
+        let store = crate::test_support::InMemoryStore::default();
+        let searchbot = identity("searchbot", "example");
+        let old = NOW - u64::from(USAGE_REPORT_MAX_DAYS) * SECONDS_PER_DAY;
+        for index in 0..(MAX_TRACKED_PATHS + 5) {
+            record_usage(
+                &store,
+                "default",
+                &IdentityUsageRecord::from_response(
+                    &searchbot,
+                    format!("/p/{}", index).as_str(),
+                    200,
+                    1,
+                ),
+                old,
+            );
+        }
+        let report = usage_report(&store, "default", 1, old);
+        assert_eq!(report.identities[0].distinct_paths, MAX_TRACKED_PATHS + 1);
+        assert!(report.identities[0]
+            .top_paths
+            .iter()
+            .any(|path| path.path == OTHER_PATHS && path.requests == 5));
+
+        record_usage(
+            &store,
+            "default",
+            &IdentityUsageRecord::from_response(&searchbot, "/", 200, 1),
+            NOW,
+        );
+        assert_eq!(usage_report(&store, "default", 1, old).requests, 0);
+    }
+
+    #[test]
+    fn delegated_requests_are_metered_apart_from_the_agent_itself() {
+        let store = crate::test_support::InMemoryStore::default();
+        let agent = identity("assistant", "example");
+        let mut delegated = agent.clone();
+        delegated.delegation = Some(Box::new(
+            crate::bot_identity::contracts::IdentityDelegation {
+                key_id: "session-2026".to_string(),
+                subject_digest: "0123456789abcdef01234567".to_string(),
+                expires_at: NOW + 3_600,
+            },
+        ));
+        let per_identity = quota(
+            IdentityQuotaScope::StableIdentity,
+            IdentityQuotaWindow::Daily,
+        );
+        assert_ne!(
+            quota_subject_key(&per_identity, &agent),
+            quota_subject_key(&per_identity, &delegated)
+        );
+
+        for who in [&agent, &delegated, &delegated] {
+            record_usage(
+                &store,
+                "default",
+                &IdentityUsageRecord::from_response(who, "/inbox", 200, 10),
+                NOW,
+            );
+        }
+        let report = usage_report(&store, "default", 1, NOW);
+        assert_eq!(report.identities.len(), 2);
+        assert!(report.identities[0].delegated);
+        assert_eq!(report.identities[0].requests, 2);
+        assert!(!report.identities[1].delegated);
+        assert_eq!(report.identities[1].requests, 1);
+        assert_eq!(report.operators[0].requests, 3);
+    }
+}
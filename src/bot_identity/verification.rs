@@ -35,6 +35,9 @@ pub(crate) enum IdentityVerificationFailure {
     ProviderRejected,
     ProviderUnavailable,
     UnsupportedScheme,
+    CertificateInvalid,
+    CertificateExpired,
+    CertificateUntrusted,
 }
 
 impl IdentityVerificationFailure {
@@ -50,6 +53,9 @@ impl IdentityVerificationFailure {
             IdentityVerificationFailure::ProviderRejected => "provider_rejected",
             IdentityVerificationFailure::ProviderUnavailable => "provider_unavailable",
             IdentityVerificationFailure::UnsupportedScheme => "unsupported_scheme",
+            IdentityVerificationFailure::CertificateInvalid => "certificate_invalid",
+            IdentityVerificationFailure::CertificateExpired => "certificate_expired",
+            IdentityVerificationFailure::CertificateUntrusted => "certificate_untrusted",
         }
     }
 }
@@ -76,6 +82,20 @@ impl IdentityVerificationFreshness {
     }
 }
 
+/// A change in the key ids a signature-agent directory publishes, seen when verification refetched
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdentityDirectoryKeyRotation {
+    pub source_uri: String,
+    pub added_key_ids: Vec<String>,
+    pub removed_key_ids: Vec<String>,
+    /// The refetched directory no longer publishes any of the pin's `pinned_key_ids`, so
+    /// verification kept the last known-good pinned keys instead of the new set.
+    #[serde(default)]
+    pub pinned_keys_missing: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct IdentityVerificationResult {
@@ -85,6 +105,8 @@ pub(crate) struct IdentityVerificationResult {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub failure: Option<IdentityVerificationFailure>,
     pub freshness: IdentityVerificationFreshness,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory_key_rotation: Option<Box<IdentityDirectoryKeyRotation>>,
 }
 
 impl IdentityVerificationResult {
@@ -94,6 +116,7 @@ impl IdentityVerificationResult {
             identity: None,
             failure: None,
             freshness: IdentityVerificationFreshness::NotApplicable,
+            directory_key_rotation: None,
         }
     }
 
@@ -103,6 +126,7 @@ impl IdentityVerificationResult {
             identity: None,
             failure: None,
             freshness: IdentityVerificationFreshness::NotApplicable,
+            directory_key_rotation: None,
         }
     }
 
@@ -112,6 +136,7 @@ impl IdentityVerificationResult {
             identity: Some(identity),
             failure: None,
             freshness,
+            directory_key_rotation: None,
         }
     }
 
@@ -124,6 +149,7 @@ impl IdentityVerificationResult {
             identity: None,
             failure: Some(failure),
             freshness,
+            directory_key_rotation: None,
         }
     }
 }
@@ -15,6 +15,22 @@ impl ChallengeBoundary for DefaultChallengeBoundary {
         crate::challenge::NOT_A_BOT_PATH
     }
 
+    fn not_a_bot_accessible_path(&self) -> &'static str {
+        crate::challenge::NOT_A_BOT_ACCESSIBLE_PATH
+    }
+
+    fn privacy_pass_token_request_path(&self) -> &'static str {
+        crate::challenge::PRIVACY_PASS_TOKEN_REQUEST_PATH
+    }
+
+    fn privacy_pass_redeem_path(&self) -> &'static str {
+        crate::challenge::PRIVACY_PASS_REDEEM_PATH
+    }
+
+    fn privacy_pass_issuer_directory_path(&self) -> &'static str {
+        crate::challenge::PRIVACY_PASS_ISSUER_DIRECTORY_PATH
+    }
+
     fn render_challenge(
         &self,
         req: &Request,
@@ -28,6 +44,20 @@ impl ChallengeBoundary for DefaultChallengeBoundary {
         crate::challenge::render_not_a_bot(req, cfg)
     }
 
+    fn render_not_a_bot_accessible<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        cfg: &crate::config::Config,
+        retry: bool,
+    ) -> Response {
+        crate::challenge::render_not_a_bot_accessible(store, req, cfg, retry)
+    }
+
+    fn with_not_a_bot_accessible_offer(&self, response: Response) -> Response {
+        crate::challenge::with_not_a_bot_accessible_offer(response)
+    }
+
     fn serve_challenge_page(
         &self,
         req: &Request,
@@ -75,6 +105,41 @@ impl ChallengeBoundary for DefaultChallengeBoundary {
     ) -> crate::challenge::NotABotSubmitResult {
         crate::challenge::handle_not_a_bot_submit_with_outcome(store, req, cfg)
     }
+
+    fn handle_not_a_bot_accessible_submit_with_outcome<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        cfg: &crate::config::Config,
+    ) -> crate::challenge::NotABotSubmitResult {
+        crate::challenge::handle_not_a_bot_accessible_submit_with_outcome(store, req, cfg)
+    }
+
+    fn serve_privacy_pass_issuer_directory(&self, now: u64) -> Response {
+        crate::challenge::privacy_pass::handle_issuer_directory(now)
+    }
+
+    fn handle_privacy_pass_token_request<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        ip: &str,
+        ua: &str,
+        now: u64,
+    ) -> (Response, crate::challenge::privacy_pass::IssuanceOutcome) {
+        crate::challenge::privacy_pass::handle_token_request(store, req, ip, ua, now)
+    }
+
+    fn handle_privacy_pass_redeem<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        ip: &str,
+        ua: &str,
+        now: u64,
+    ) -> (Response, crate::challenge::privacy_pass::RedemptionOutcome) {
+        crate::challenge::privacy_pass::handle_redeem_request(store, req, ip, ua, now)
+    }
 }
 
 impl MazeBoundary for DefaultMazeBoundary {
@@ -105,6 +170,22 @@ pub(crate) fn challenge_not_a_bot_path() -> &'static str {
     CHALLENGE.not_a_bot_path()
 }
 
+pub(crate) fn challenge_not_a_bot_accessible_path() -> &'static str {
+    CHALLENGE.not_a_bot_accessible_path()
+}
+
+pub(crate) fn privacy_pass_token_request_path() -> &'static str {
+    CHALLENGE.privacy_pass_token_request_path()
+}
+
+pub(crate) fn privacy_pass_redeem_path() -> &'static str {
+    CHALLENGE.privacy_pass_redeem_path()
+}
+
+pub(crate) fn privacy_pass_issuer_directory_path() -> &'static str {
+    CHALLENGE.privacy_pass_issuer_directory_path()
+}
+
 pub(crate) fn render_challenge(
     req: &Request,
     transform_count: usize,
@@ -117,6 +198,19 @@ pub(crate) fn render_not_a_bot(req: &Request, cfg: &crate::config::Config) -> Re
     CHALLENGE.render_not_a_bot(req, cfg)
 }
 
+pub(crate) fn render_not_a_bot_accessible<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    cfg: &crate::config::Config,
+    retry: bool,
+) -> Response {
+    CHALLENGE.render_not_a_bot_accessible(store, req, cfg, retry)
+}
+
+pub(crate) fn with_not_a_bot_accessible_offer(response: Response) -> Response {
+    CHALLENGE.with_not_a_bot_accessible_offer(response)
+}
+
 pub(crate) fn serve_challenge_page(
     req: &Request,
     shadow_mode: bool,
@@ -156,6 +250,40 @@ pub(crate) fn handle_not_a_bot_submit_with_outcome<S: crate::challenge::KeyValue
     CHALLENGE.handle_not_a_bot_submit_with_outcome(store, req, cfg)
 }
 
+pub(crate) fn handle_not_a_bot_accessible_submit_with_outcome<
+    S: crate::challenge::KeyValueStore,
+>(
+    store: &S,
+    req: &Request,
+    cfg: &crate::config::Config,
+) -> crate::challenge::NotABotSubmitResult {
+    CHALLENGE.handle_not_a_bot_accessible_submit_with_outcome(store, req, cfg)
+}
+
+pub(crate) fn serve_privacy_pass_issuer_directory(now: u64) -> Response {
+    CHALLENGE.serve_privacy_pass_issuer_directory(now)
+}
+
+pub(crate) fn handle_privacy_pass_token_request<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) -> (Response, crate::challenge::privacy_pass::IssuanceOutcome) {
+    CHALLENGE.handle_privacy_pass_token_request(store, req, ip, ua, now)
+}
+
+pub(crate) fn handle_privacy_pass_redeem<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) -> (Response, crate::challenge::privacy_pass::RedemptionOutcome) {
+    CHALLENGE.handle_privacy_pass_redeem(store, req, ip, ua, now)
+}
+
 pub(crate) fn is_maze_path(path: &str) -> bool {
     MAZE.is_maze_path(path)
 }
@@ -3,6 +3,10 @@ use spin_sdk::http::{Request, Response};
 pub(crate) trait ChallengeBoundary {
     fn puzzle_path(&self) -> &'static str;
     fn not_a_bot_path(&self) -> &'static str;
+    fn not_a_bot_accessible_path(&self) -> &'static str;
+    fn privacy_pass_token_request_path(&self) -> &'static str;
+    fn privacy_pass_redeem_path(&self) -> &'static str;
+    fn privacy_pass_issuer_directory_path(&self) -> &'static str;
     fn render_challenge(
         &self,
         req: &Request,
@@ -10,6 +14,14 @@ pub(crate) trait ChallengeBoundary {
         seed_ttl_seconds: u64,
     ) -> Response;
     fn render_not_a_bot(&self, req: &Request, cfg: &crate::config::Config) -> Response;
+    fn render_not_a_bot_accessible<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        cfg: &crate::config::Config,
+        retry: bool,
+    ) -> Response;
+    fn with_not_a_bot_accessible_offer(&self, response: Response) -> Response;
     fn serve_challenge_page(
         &self,
         req: &Request,
@@ -36,6 +48,29 @@ pub(crate) trait ChallengeBoundary {
         req: &Request,
         cfg: &crate::config::Config,
     ) -> crate::challenge::NotABotSubmitResult;
+    fn handle_not_a_bot_accessible_submit_with_outcome<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        cfg: &crate::config::Config,
+    ) -> crate::challenge::NotABotSubmitResult;
+    fn serve_privacy_pass_issuer_directory(&self, now: u64) -> Response;
+    fn handle_privacy_pass_token_request<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        ip: &str,
+        ua: &str,
+        now: u64,
+    ) -> (Response, crate::challenge::privacy_pass::IssuanceOutcome);
+    fn handle_privacy_pass_redeem<S: crate::challenge::KeyValueStore>(
+        &self,
+        store: &S,
+        req: &Request,
+        ip: &str,
+        ua: &str,
+        now: u64,
+    ) -> (Response, crate::challenge::privacy_pass::RedemptionOutcome);
 }
 
 pub(crate) trait MazeBoundary {
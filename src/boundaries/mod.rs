@@ -3,7 +3,12 @@ mod contracts;
 
 pub(crate) use crate::challenge::ChallengeSubmitOutcome;
 pub(crate) use adapters::{
-    challenge_not_a_bot_path, challenge_puzzle_path, handle_admin, handle_internal,
-    handle_challenge_submit_with_outcome, handle_not_a_bot_submit_with_outcome, is_maze_path,
-    render_challenge, render_not_a_bot, serve_challenge_page, serve_not_a_bot_page,
+    challenge_not_a_bot_accessible_path, challenge_not_a_bot_path, challenge_puzzle_path,
+    handle_admin, handle_internal, handle_challenge_submit_with_outcome,
+    handle_not_a_bot_accessible_submit_with_outcome, handle_not_a_bot_submit_with_outcome,
+    handle_privacy_pass_redeem, handle_privacy_pass_token_request, is_maze_path,
+    privacy_pass_issuer_directory_path, privacy_pass_redeem_path,
+    privacy_pass_token_request_path, render_challenge, render_not_a_bot,
+    render_not_a_bot_accessible, serve_challenge_page, serve_not_a_bot_page,
+    serve_privacy_pass_issuer_directory, with_not_a_bot_accessible_offer,
 };
@@ -4,12 +4,16 @@ use spin_sdk::key_value::Store;
 pub(crate) mod not_a_bot;
 pub(crate) mod operation_envelope;
 pub(crate) mod pow;
+pub(crate) mod privacy_pass;
 mod puzzle;
 
 pub(crate) use not_a_bot::{
+    handle_accessible_submit_with_outcome as handle_not_a_bot_accessible_submit_with_outcome,
     handle_not_a_bot_submit_with_outcome, has_valid_marker as has_valid_not_a_bot_marker,
-    render_not_a_bot, serve_not_a_bot_page, NotABotDecision, NotABotSubmitOutcome,
-    NotABotSubmitResult,
+    has_valid_marker_for_score as has_valid_not_a_bot_marker_for_score,
+    render_accessible_challenge as render_not_a_bot_accessible, render_not_a_bot,
+    serve_not_a_bot_page, with_accessible_offer as with_not_a_bot_accessible_offer,
+    NotABotDecision, NotABotSubmitOutcome, NotABotSubmitResult,
 };
 #[cfg(test)]
 pub use puzzle::handle_challenge_submit;
@@ -41,6 +45,10 @@ pub trait KeyValueStore {
 
 pub(crate) const PUZZLE_PATH: &str = "/challenge/puzzle";
 pub(crate) const NOT_A_BOT_PATH: &str = "/challenge/not-a-bot-checkbox";
+pub(crate) const NOT_A_BOT_ACCESSIBLE_PATH: &str = "/challenge/not-a-bot-accessible";
+pub(crate) const PRIVACY_PASS_TOKEN_REQUEST_PATH: &str = "/challenge/privacy-pass/token-request";
+pub(crate) const PRIVACY_PASS_REDEEM_PATH: &str = "/challenge/privacy-pass/redeem";
+pub(crate) const PRIVACY_PASS_ISSUER_DIRECTORY_PATH: &str = "/.well-known/private-token-issuer-directory";
 
 impl KeyValueStore for Store {
     fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ()> {
@@ -0,0 +1,637 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use spin_sdk::http::Request;
+
+use super::render::normalize_return_to;
+use super::submit::{failure_result, get_form_field, increment_and_check_attempt_limit};
+use super::token::{
+    accessible_question_digest, make_accessible_seed_token, marker_cookie_value,
+    parse_accessible_seed_token, MarkerTrust, SeedTokenError,
+};
+use super::types::{AccessibleSeed, NotABotDecision, NotABotSubmitOutcome, NotABotSubmitResult};
+
+const MAX_ANSWER_CHARS: usize = 64;
+const ACCESSIBLE_OFFER_KEY_PREFIX: &str = "not_a_bot:accessible_offer:";
+const ACCESSIBLE_OFFER_TTL_SECONDS: u64 = 900;
+/// Answers allowed per offer; a fresh offer needs another scored checkbox attempt.
+pub(crate) const ACCESSIBLE_OFFER_MAX_ATTEMPTS: u32 = 3;
+
+/// Per-session (IP bucket + UA bucket) record that a scored checkbox attempt failed and the
+/// text alternative may be served.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AccessibleOffer {
+    opened_at: u64,
+    attempts: u32,
+    return_to: String,
+}
+
+fn offer_key(ip_bucket: &str, ua_bucket: &str) -> String {
+    format!("{}{}:{}", ACCESSIBLE_OFFER_KEY_PREFIX, ip_bucket, ua_bucket)
+}
+
+fn load_offer<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    key: &str,
+    now: u64,
+) -> Option<AccessibleOffer> {
+    let raw = store.get(key).ok().flatten()?;
+    let offer = serde_json::from_slice::<AccessibleOffer>(raw.as_slice()).ok()?;
+    if now.saturating_sub(offer.opened_at) > ACCESSIBLE_OFFER_TTL_SECONDS {
+        let _ = store.delete(key);
+        return None;
+    }
+    Some(offer)
+}
+
+fn save_offer<S: crate::challenge::KeyValueStore>(store: &S, key: &str, offer: &AccessibleOffer) {
+    let Ok(raw) = serde_json::to_vec(offer) else {
+        return;
+    };
+    if store.set(key, raw.as_slice()).is_err() {
+        eprintln!("[not-a-bot] failed to persist accessible offer {}", key);
+    }
+}
+
+/// Opens the text alternative for this session after a scored checkbox attempt failed.
+///
+/// An offer that is still open keeps its attempt count, so failing the checkbox again cannot
+/// reset the per-session limit. Returns false when no question bank is configured.
+pub(crate) fn open_accessible_offer<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    cfg: &crate::config::Config,
+    ip_bucket: &str,
+    ua_bucket: &str,
+    return_to: &str,
+    now: u64,
+) -> bool {
+    if cfg.not_a_bot_accessible_questions.is_empty() {
+        return false;
+    }
+    let key = offer_key(ip_bucket, ua_bucket);
+    if let Some(offer) = load_offer(store, key.as_str(), now) {
+        return offer.attempts < ACCESSIBLE_OFFER_MAX_ATTEMPTS;
+    }
+    save_offer(
+        store,
+        key.as_str(),
+        &AccessibleOffer {
+            opened_at: now,
+            attempts: 0,
+            return_to: normalize_return_to(return_to),
+        },
+    );
+    true
+}
+
+pub(crate) fn normalize_accessible_answer(raw: &str) -> String {
+    let lowered = raw
+        .trim()
+        .trim_matches(|ch: char| ch.is_ascii_punctuation())
+        .to_lowercase();
+    lowered.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn issue_accessible_seed(
+    req: &Request,
+    cfg: &crate::config::Config,
+    return_to: &str,
+) -> Option<(String, String)> {
+    let questions = &cfg.not_a_bot_accessible_questions;
+    if questions.is_empty() {
+        return None;
+    }
+    let ip = crate::extract_client_ip(req);
+    let ua = req
+        .header("user-agent")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let now = crate::admin::now_ts();
+    let mut rng = rand::rng();
+    let question = questions[rng.random_range(0..questions.len())].question.clone();
+    let operation_id = format!("{:016x}{:016x}", rng.random::<u64>(), rng.random::<u64>());
+    let question_digest = accessible_question_digest(operation_id.as_str(), question.as_str());
+    let seed = AccessibleSeed {
+        operation_id,
+        flow_id: crate::challenge::operation_envelope::FLOW_NOT_A_BOT_ACCESSIBLE.to_string(),
+        step_id: crate::challenge::operation_envelope::STEP_NOT_A_BOT_ACCESSIBLE_SUBMIT
+            .to_string(),
+        step_index: crate::challenge::operation_envelope::STEP_INDEX_NOT_A_BOT_ACCESSIBLE_SUBMIT,
+        issued_at: now,
+        expires_at: now.saturating_add(
+            crate::challenge::operation_envelope::MAX_STEP_WINDOW_SECONDS_NOT_A_BOT_ACCESSIBLE,
+        ),
+        token_version: crate::challenge::operation_envelope::TOKEN_VERSION_V1,
+        ip_bucket: crate::signals::ip_identity::bucket_ip(&ip),
+        ua_bucket: crate::challenge::operation_envelope::user_agent_bucket(ua),
+        path_class: crate::challenge::operation_envelope::PATH_CLASS_NOT_A_BOT_ACCESSIBLE_SUBMIT
+            .to_string(),
+        return_to: normalize_return_to(return_to),
+        question_digest,
+    };
+    Some((make_accessible_seed_token(&seed), question))
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn accessible_challenge_form(seed_token: &str, question: &str) -> String {
+    format!(
+        r#"<form id="not-a-bot-accessible-form" method="POST" action="{path}">
+      <input type="hidden" name="seed" value="{seed_token}" />
+      <p id="not-a-bot-accessible-question">{question}</p>
+      <label for="not-a-bot-accessible-answer">Your answer</label>
+      <input
+        id="not-a-bot-accessible-answer"
+        name="answer"
+        type="text"
+        inputmode="text"
+        autocomplete="off"
+        autocapitalize="off"
+        spellcheck="false"
+        maxlength="{max_chars}"
+        aria-describedby="not-a-bot-accessible-question"
+        required
+      />
+      <button type="submit">Submit answer</button>
+    </form>"#,
+        path = crate::challenge::NOT_A_BOT_ACCESSIBLE_PATH,
+        seed_token = seed_token,
+        question = escape_html(question),
+        max_chars = MAX_ANSWER_CHARS,
+    )
+}
+
+/// Puts a link to the text alternative at the top of an escalation page. Only used once a
+/// failed scored checkbox attempt has opened an offer for the session.
+pub(crate) fn with_accessible_offer(response: spin_sdk::http::Response) -> spin_sdk::http::Response {
+    let status = *response.status();
+    let body = String::from_utf8_lossy(response.body()).into_owned();
+    let link = format!(
+        r#"<p class="accessible-alternative"><a href="{path}">Can't use the visual puzzle? Answer a text question instead.</a></p>"#,
+        path = crate::challenge::NOT_A_BOT_ACCESSIBLE_PATH,
+    );
+    let insert_at = body
+        .find("<body")
+        .and_then(|start| body[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(0);
+    let html = format!("{}{}{}", &body[..insert_at], link, &body[insert_at..]);
+    crate::challenge::challenge_response(status, html.as_str())
+}
+
+fn accessible_unavailable() -> spin_sdk::http::Response {
+    crate::challenge::challenge_response(
+        403,
+        r#"<html lang="en"><body><main><h1>Text question unavailable</h1><p>Reload the page you were visiting to start the check again.</p></main></body></html>"#,
+    )
+}
+
+/// Serves the text alternative to a session with an open offer that still has attempts left.
+pub(crate) fn render_accessible_challenge<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    cfg: &crate::config::Config,
+    retry: bool,
+) -> spin_sdk::http::Response {
+    let ip = crate::extract_client_ip(req);
+    let ua = req
+        .header("user-agent")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let key = offer_key(
+        crate::signals::ip_identity::bucket_ip(&ip).as_str(),
+        crate::challenge::operation_envelope::user_agent_bucket(ua).as_str(),
+    );
+    let Some(offer) = load_offer(store, key.as_str(), crate::admin::now_ts())
+        .filter(|offer| offer.attempts < ACCESSIBLE_OFFER_MAX_ATTEMPTS)
+    else {
+        return accessible_unavailable();
+    };
+    let Some((seed_token, question)) = issue_accessible_seed(req, cfg, offer.return_to.as_str())
+    else {
+        return accessible_unavailable();
+    };
+    let notice = if retry {
+        r#"<p role="alert">That answer did not match. Here is a new question.</p>"#
+    } else {
+        ""
+    };
+    let html = format!(
+        r#"
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1" />
+  <title>Answer a short question to continue</title>
+  <style>
+    body {{
+      margin: 0;
+      font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif;
+      color: #111111;
+      background: #fffafd;
+      padding: 16px;
+      line-height: 1.5;
+    }}
+    main {{ max-width: 520px; margin: 0 auto; }}
+    label {{ display: block; font-weight: 600; margin-bottom: 4px; }}
+    input[type="text"] {{ font-size: 1.1rem; padding: 8px; width: 100%; box-sizing: border-box; }}
+    button {{ margin-top: 12px; font-size: 1rem; padding: 10px 16px; }}
+    input:focus-visible, button:focus-visible {{ outline: 3px solid #a86f97; outline-offset: 2px; }}
+  </style>
+</head>
+<body>
+  <main>
+    <h1>Answer a short question to continue</h1>
+    {notice}
+    {form}
+  </main>
+</body>
+</html>
+"#,
+        notice = notice,
+        form = accessible_challenge_form(seed_token.as_str(), question.as_str()),
+    );
+    crate::challenge::challenge_response(200, html.as_str())
+}
+
+pub(crate) fn handle_accessible_submit_with_outcome<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    cfg: &crate::config::Config,
+) -> NotABotSubmitResult {
+    let ip = crate::extract_client_ip(req);
+    let ua = req
+        .header("user-agent")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let now = crate::admin::now_ts();
+    if crate::request_validation::enforce_body_size(
+        req.body(),
+        crate::request_validation::MAX_CHALLENGE_FORM_BYTES,
+    )
+    .is_err()
+    {
+        return accessible_failure(NotABotSubmitOutcome::InvalidTelemetry, "/");
+    }
+    let Ok(form) = std::str::from_utf8(req.body()) else {
+        return accessible_failure(NotABotSubmitOutcome::InvalidTelemetry, "/");
+    };
+    evaluate_accessible_submission(store, cfg, form, ip.as_str(), ua, now)
+}
+
+pub(crate) fn evaluate_accessible_submission<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    cfg: &crate::config::Config,
+    form: &str,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) -> NotABotSubmitResult {
+    let request_ip_bucket = crate::signals::ip_identity::bucket_ip(ip);
+    if increment_and_check_attempt_limit(
+        store,
+        request_ip_bucket.as_str(),
+        now,
+        cfg.not_a_bot_attempt_window_seconds,
+        cfg.not_a_bot_attempt_limit_per_window,
+    ) {
+        return accessible_failure(NotABotSubmitOutcome::AttemptLimitExceeded, "/");
+    }
+
+    let seed_token = match get_form_field(form, "seed") {
+        Some(value) if crate::request_validation::validate_seed_token(value.as_str()) => value,
+        Some(_) => return accessible_failure(NotABotSubmitOutcome::InvalidSeed, "/"),
+        None => return accessible_failure(NotABotSubmitOutcome::MissingSeed, "/"),
+    };
+    let seed = match parse_accessible_seed_token(seed_token.as_str()) {
+        Ok(value) => value,
+        Err(SeedTokenError::InvalidOperationEnvelope(_)) => {
+            return accessible_failure(NotABotSubmitOutcome::SequenceViolation, "/")
+        }
+        Err(_) => return accessible_failure(NotABotSubmitOutcome::InvalidSeed, "/"),
+    };
+    let return_to = normalize_return_to(seed.return_to.as_str());
+
+    if now > seed.expires_at {
+        return accessible_failure(NotABotSubmitOutcome::Expired, return_to.as_str());
+    }
+    if crate::challenge::operation_envelope::validate_ordering_window(
+        seed.flow_id.as_str(),
+        seed.step_id.as_str(),
+        seed.step_index,
+        seed.issued_at,
+        seed.expires_at,
+        now,
+        crate::challenge::operation_envelope::FLOW_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::STEP_NOT_A_BOT_ACCESSIBLE_SUBMIT,
+        crate::challenge::operation_envelope::STEP_INDEX_NOT_A_BOT_ACCESSIBLE_SUBMIT,
+        crate::challenge::operation_envelope::MAX_STEP_WINDOW_SECONDS_NOT_A_BOT_ACCESSIBLE,
+    )
+    .is_err()
+    {
+        return accessible_failure(NotABotSubmitOutcome::SequenceViolation, return_to.as_str());
+    }
+    if crate::challenge::operation_envelope::validate_request_binding(
+        seed.ip_bucket.as_str(),
+        seed.ua_bucket.as_str(),
+        seed.path_class.as_str(),
+        ip,
+        ua,
+        crate::challenge::operation_envelope::PATH_CLASS_NOT_A_BOT_ACCESSIBLE_SUBMIT,
+    )
+    .is_err()
+    {
+        return accessible_failure(NotABotSubmitOutcome::BindingMismatch, return_to.as_str());
+    }
+
+    let timing_bucket = format!("{}:{}", seed.ip_bucket, seed.ua_bucket);
+    if crate::challenge::operation_envelope::validate_timing_primitives(
+        store,
+        seed.flow_id.as_str(),
+        timing_bucket.as_str(),
+        seed.issued_at,
+        now,
+        crate::challenge::operation_envelope::MIN_STEP_LATENCY_SECONDS_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::MAX_STEP_LATENCY_SECONDS_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::MAX_FLOW_AGE_SECONDS_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::TIMING_REGULARITY_WINDOW_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::TIMING_REGULARITY_SPREAD_SECONDS_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::TIMING_HISTORY_TTL_SECONDS_NOT_A_BOT_ACCESSIBLE,
+    )
+    .is_err()
+    {
+        return accessible_failure(NotABotSubmitOutcome::SequenceViolation, return_to.as_str());
+    }
+
+    match crate::challenge::operation_envelope::validate_operation_replay(
+        store,
+        seed.flow_id.as_str(),
+        seed.operation_id.as_str(),
+        now,
+        seed.expires_at,
+        crate::challenge::operation_envelope::MAX_OPERATION_REPLAY_TTL_SECONDS_NOT_A_BOT_ACCESSIBLE,
+    ) {
+        Ok(_) => {}
+        Err(crate::challenge::operation_envelope::ReplayValidationError::ReplayDetected) => {
+            return accessible_failure(NotABotSubmitOutcome::Replay, return_to.as_str())
+        }
+        Err(crate::challenge::operation_envelope::ReplayValidationError::ExpiredOperation) => {
+            return accessible_failure(NotABotSubmitOutcome::Expired, return_to.as_str())
+        }
+    }
+
+    let offer_key = offer_key(seed.ip_bucket.as_str(), seed.ua_bucket.as_str());
+    let Some(mut offer) = load_offer(store, offer_key.as_str(), now) else {
+        return accessible_failure(NotABotSubmitOutcome::SequenceViolation, return_to.as_str());
+    };
+    if offer.attempts >= ACCESSIBLE_OFFER_MAX_ATTEMPTS {
+        let _ = store.delete(offer_key.as_str());
+        return accessible_failure(NotABotSubmitOutcome::AttemptLimitExceeded, return_to.as_str());
+    }
+    offer.attempts = offer.attempts.saturating_add(1);
+    save_offer(store, offer_key.as_str(), &offer);
+
+    let answer = get_form_field(form, "answer").unwrap_or_default();
+    if answer.chars().count() > MAX_ANSWER_CHARS {
+        return accessible_failure(NotABotSubmitOutcome::InvalidTelemetry, return_to.as_str());
+    }
+    // The bank may have been edited since the seed was issued; treat that like an expired seed.
+    let Some(question) = cfg.not_a_bot_accessible_questions.iter().find(|entry| {
+        accessible_question_digest(seed.operation_id.as_str(), entry.question.as_str())
+            == seed.question_digest
+    }) else {
+        return accessible_failure(NotABotSubmitOutcome::Expired, return_to.as_str());
+    };
+    let normalized = normalize_accessible_answer(answer.as_str());
+    let solve_ms = Some(now.saturating_sub(seed.issued_at).saturating_mul(1000));
+    let correct = !normalized.is_empty()
+        && question
+            .answers
+            .iter()
+            .any(|accepted| normalize_accessible_answer(accepted) == normalized);
+    if !correct {
+        if offer.attempts >= ACCESSIBLE_OFFER_MAX_ATTEMPTS {
+            let _ = store.delete(offer_key.as_str());
+            return accessible_failure(
+                NotABotSubmitOutcome::AttemptLimitExceeded,
+                return_to.as_str(),
+            );
+        }
+        return NotABotSubmitResult {
+            outcome: NotABotSubmitOutcome::AccessibleIncorrect,
+            decision: NotABotDecision::RetryAccessible,
+            return_to,
+            marker_cookie: None,
+            solve_ms,
+            accessibility_cohort: true,
+            accessible_offer: true,
+        };
+    }
+
+    let _ = store.delete(offer_key.as_str());
+    NotABotSubmitResult {
+        outcome: NotABotSubmitOutcome::Pass,
+        decision: NotABotDecision::Pass,
+        return_to,
+        marker_cookie: Some(marker_cookie_value(
+            seed.ip_bucket.as_str(),
+            seed.ua_bucket.as_str(),
+            cfg.not_a_bot_marker_ttl_seconds,
+            MarkerTrust::Accessible,
+        )),
+        solve_ms,
+        accessibility_cohort: true,
+        accessible_offer: false,
+    }
+}
+
+fn accessible_failure(outcome: NotABotSubmitOutcome, return_to: &str) -> NotABotSubmitResult {
+    NotABotSubmitResult {
+        accessibility_cohort: true,
+        ..failure_result(outcome, NotABotDecision::MazeOrBlock, return_to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: &str = "198.51.100.42";
+    const UA: &str = "Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0";
+    const QUESTION: &str = "Which month comes after the one our spring sale starts in?";
+
+    fn config_with_bank() -> crate::config::Config {
+        let mut cfg = crate::config::defaults().clone();
+        cfg.not_a_bot_attempt_limit_per_window = 20;
+        cfg.not_a_bot_accessible_questions = vec![crate::config::NotABotAccessibleQuestion {
+            question: QUESTION.to_string(),
+            answers: vec!["April".to_string(), "apr".to_string()],
+        }];
+        cfg
+    }
+
+    fn buckets() -> (String, String) {
+        (
+            crate::signals::ip_identity::bucket_ip(IP),
+            crate::challenge::operation_envelope::user_agent_bucket(UA),
+        )
+    }
+
+    fn issue_seed(issued_at: u64, operation_id: &str) -> String {
+        let (ip_bucket, ua_bucket) = buckets();
+        let seed = AccessibleSeed {
+            question_digest: accessible_question_digest(operation_id, QUESTION),
+            operation_id: operation_id.to_string(),
+            flow_id: crate::challenge::operation_envelope::FLOW_NOT_A_BOT_ACCESSIBLE.to_string(),
+            step_id: crate::challenge::operation_envelope::STEP_NOT_A_BOT_ACCESSIBLE_SUBMIT
+                .to_string(),
+            step_index:
+                crate::challenge::operation_envelope::STEP_INDEX_NOT_A_BOT_ACCESSIBLE_SUBMIT,
+            issued_at,
+            expires_at: issued_at + 600,
+            token_version: crate::challenge::operation_envelope::TOKEN_VERSION_V1,
+            ip_bucket,
+            ua_bucket,
+            path_class:
+                crate::challenge::operation_envelope::PATH_CLASS_NOT_A_BOT_ACCESSIBLE_SUBMIT
+                    .to_string(),
+            return_to: "/docs".to_string(),
+        };
+        let token = make_accessible_seed_token(&seed);
+        percent_encoding::utf8_percent_encode(
+            token.as_str(),
+            percent_encoding::NON_ALPHANUMERIC,
+        )
+        .to_string()
+    }
+
+    fn open_offer(store: &crate::test_support::InMemoryStore, cfg: &crate::config::Config, now: u64) {
+        let (ip_bucket, ua_bucket) = buckets();
+        assert!(open_accessible_offer(
+            store,
+            cfg,
+            ip_bucket.as_str(),
+            ua_bucket.as_str(),
+            "/docs",
+            now
+        ));
+    }
+
+    #[test]
+    fn accessible_answers_ignore_case_outer_punctuation_and_spacing() {
+        assert_eq!(normalize_accessible_answer("  April. "), "april");
+        assert_eq!(normalize_accessible_answer("\"New   York\""), "new york");
+        assert_eq!(normalize_accessible_answer("?!"), "");
+    }
+
+    #[test]
+    fn accessible_offer_needs_a_question_bank() {
+        let store = crate::test_support::InMemoryStore::default();
+        let cfg = crate::config::defaults().clone();
+        let (ip_bucket, ua_bucket) = buckets();
+        assert!(cfg.not_a_bot_accessible_questions.is_empty());
+        assert!(!open_accessible_offer(
+            &store,
+            &cfg,
+            ip_bucket.as_str(),
+            ua_bucket.as_str(),
+            "/docs",
+            1_700_000_000
+        ));
+    }
+
+    #[test]
+    fn accessible_submission_requires_an_offer_and_issues_a_lower_trust_marker() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_JS_SECRET", "unit-test-secret");
+        std::env::remove_var("SHUMA_CHALLENGE_SECRET");
+        let store = crate::test_support::InMemoryStore::default();
+        let cfg = config_with_bank();
+        let now = 1_700_000_000;
+
+        let unoffered = format!(
+            "seed={}&answer=April",
+            issue_seed(now, "00000000000000000000000000000001")
+        );
+        let refused =
+            evaluate_accessible_submission(&store, &cfg, unoffered.as_str(), IP, UA, now + 8);
+        assert_eq!(refused.outcome, NotABotSubmitOutcome::SequenceViolation);
+        assert!(refused.marker_cookie.is_none());
+
+        open_offer(&store, &cfg, now);
+        let form = format!(
+            "seed={}&answer=%20april!",
+            issue_seed(now, "00000000000000000000000000000002")
+        );
+        let passed = evaluate_accessible_submission(&store, &cfg, form.as_str(), IP, UA, now + 11);
+        assert_eq!(passed.outcome, NotABotSubmitOutcome::Pass);
+        assert_eq!(passed.decision, NotABotDecision::Pass);
+        assert!(passed.accessibility_cohort);
+        assert_eq!(passed.return_to, "/docs");
+        let marker = passed.marker_cookie.expect("pass should set a marker");
+        assert!(marker.ends_with("Max-Age=300"));
+
+        let replayed =
+            evaluate_accessible_submission(&store, &cfg, form.as_str(), IP, UA, now + 15);
+        assert_eq!(replayed.outcome, NotABotSubmitOutcome::Replay);
+        assert_eq!(replayed.decision, NotABotDecision::MazeOrBlock);
+    }
+
+    #[test]
+    fn accessible_submission_enforces_per_session_attempts_and_binding() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_JS_SECRET", "unit-test-secret");
+        std::env::remove_var("SHUMA_CHALLENGE_SECRET");
+        let store = crate::test_support::InMemoryStore::default();
+        let cfg = config_with_bank();
+        let now = 1_700_000_000;
+        open_offer(&store, &cfg, now);
+
+        let rebound = format!(
+            "seed={}&answer=April",
+            issue_seed(now, "0000000000000000000000000000000a")
+        );
+        let mismatch = evaluate_accessible_submission(
+            &store,
+            &cfg,
+            rebound.as_str(),
+            "203.0.113.77",
+            UA,
+            now + 8,
+        );
+        assert_eq!(mismatch.outcome, NotABotSubmitOutcome::BindingMismatch);
+
+        let wrong_answer = |operation_id: &str, latency: u64| {
+            let form = format!("seed={}&answer=May", issue_seed(now, operation_id));
+            evaluate_accessible_submission(&store, &cfg, form.as_str(), IP, UA, now + latency)
+                .outcome
+        };
+        assert_eq!(
+            wrong_answer("00000000000000000000000000000013", 8),
+            NotABotSubmitOutcome::AccessibleIncorrect
+        );
+        assert_eq!(
+            wrong_answer("00000000000000000000000000000014", 13),
+            NotABotSubmitOutcome::AccessibleIncorrect
+        );
+        // Failing the checkbox again mid-offer does not hand out a fresh set of attempts.
+        open_offer(&store, &cfg, now + 15);
+        assert_eq!(
+            wrong_answer("00000000000000000000000000000015", 21),
+            NotABotSubmitOutcome::AttemptLimitExceeded
+        );
+
+        // A spent offer is gone; further answers need another scored checkbox attempt.
+        let late = format!(
+            "seed={}&answer=April",
+            issue_seed(now, "000000000000000000000000000000ff")
+        );
+        let exhausted =
+            evaluate_accessible_submission(&store, &cfg, late.as_str(), IP, UA, now + 34);
+        assert_eq!(exhausted.outcome, NotABotSubmitOutcome::SequenceViolation);
+    }
+}
@@ -1,9 +1,13 @@
+mod accessible;
 mod render;
 mod submit;
 mod token;
 mod types;
 
+pub(crate) use accessible::{
+    handle_accessible_submit_with_outcome, render_accessible_challenge, with_accessible_offer,
+};
 pub(crate) use render::render_not_a_bot;
 pub(crate) use submit::{handle_not_a_bot_submit_with_outcome, serve_not_a_bot_page};
-pub(crate) use token::has_valid_marker;
+pub(crate) use token::{has_valid_marker, has_valid_marker_for_score};
 pub(crate) use types::{NotABotDecision, NotABotSubmitOutcome, NotABotSubmitResult};
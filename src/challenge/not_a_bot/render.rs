@@ -166,6 +166,7 @@ pub(crate) fn render_not_a_bot(
       let lastPoint = null;
       let lastAngle = null;
       let lastInputModality = 'unknown';
+      let pointerDownSeen = false;
       let submissionStarted = false;
 
       const telemetry = {{
@@ -182,7 +183,10 @@ pub(crate) fn render_not_a_bot(
         activation_method: 'unknown',
         activation_trusted: false,
         activation_count: 0,
-        control_focused: false
+        control_focused: false,
+        virtual_activation: false,
+        sequential_focus_count: 0,
+        forced_colors: !!(window.matchMedia && window.matchMedia('(forced-colors: active)').matches)
       }};
 
       function boundedIncrement(key, max) {{
@@ -207,6 +211,9 @@ pub(crate) fn render_not_a_bot(
         checked.value = '1';
         telemetry.activation_method = resolveActivationMethod();
         telemetry.activation_trusted = event && event.isTrusted === true;
+        // Screen readers, switch access and voice control dispatch a click with no pointer or key press.
+        telemetry.virtual_activation = !!event && event.detail === 0 && !pointerDownSeen
+          && lastInputModality === 'unknown';
         telemetry.activation_count = Math.min(255, (telemetry.activation_count || 0) + 1);
         telemetry.control_focused = (document.activeElement === checkbox);
         telemetry.interaction_elapsed_ms = Math.min(
@@ -244,6 +251,7 @@ pub(crate) fn render_not_a_bot(
       document.addEventListener('pointerdown', function (event) {{
         lastInputModality = event.pointerType === 'touch' ? 'touch' : 'pointer';
         telemetry.has_pointer = true;
+        pointerDownSeen = true;
         pointerDownAt = performance.now();
         if (event.pointerType === 'touch') {{
           telemetry.touch_used = true;
@@ -258,9 +266,12 @@ pub(crate) fn render_not_a_bot(
         }}
       }}, {{ passive: true }});
 
-      document.addEventListener('keydown', function () {{
+      document.addEventListener('keydown', function (event) {{
         lastInputModality = 'keyboard';
         telemetry.keyboard_used = true;
+        if (event.key === 'Tab') {{
+          boundedIncrement('sequential_focus_count', 200);
+        }}
       }});
 
       window.addEventListener('focus', function () {{
@@ -308,6 +319,7 @@ pub(crate) fn normalize_return_to(uri: &str) -> String {
         || !candidate.starts_with('/')
         || candidate.starts_with("//")
         || candidate.starts_with(crate::challenge::NOT_A_BOT_PATH)
+        || candidate.starts_with(crate::challenge::NOT_A_BOT_ACCESSIBLE_PATH)
         || candidate.len() > 512
     {
         return "/".to_string();
@@ -334,6 +346,20 @@ mod tests {
         assert!(body.contains("function submitVerification"));
     }
 
+    #[test]
+    fn render_not_a_bot_keeps_text_alternative_out_of_first_attempt() {
+        let req = Request::builder()
+            .method(Method::Get)
+            .uri("/docs/getting-started")
+            .build();
+        let response = render_not_a_bot(&req, crate::config::defaults());
+        let body = String::from_utf8(response.into_body()).expect("render body should be utf8");
+        assert!(!body.contains("accessible-alternative"));
+        assert!(!body.contains("/challenge/not-a-bot-accessible"));
+        assert!(body.contains("telemetry.virtual_activation ="));
+        assert!(body.contains("boundedIncrement('sequential_focus_count', 200)"));
+    }
+
     #[test]
     fn normalize_return_to_rejects_not_a_bot_self_route() {
         assert_eq!(
@@ -1,7 +1,7 @@
 use spin_sdk::http::Request;
 
 use super::render::normalize_return_to;
-use super::token::{marker_cookie_value, parse_seed_token, SeedTokenError};
+use super::token::{marker_cookie_value, parse_seed_token, MarkerTrust, SeedTokenError};
 use super::types::{
     NotABotDecision, NotABotSubmitOutcome, NotABotSubmitResult, NotABotTelemetry,
 };
@@ -251,12 +251,16 @@ pub(crate) fn handle_not_a_bot_submit_with_outcome<S: crate::challenge::KeyValue
         );
     }
 
+    let accessibility_cohort = matches_assistive_profile(&telemetry);
     let Some(score) = compute_not_a_bot_score(checked, &telemetry) else {
-        return failure_result(
-            NotABotSubmitOutcome::MazeOrBlock,
-            NotABotDecision::MazeOrBlock,
-            return_to.as_str(),
-        );
+        return NotABotSubmitResult {
+            accessibility_cohort,
+            ..failure_result(
+                NotABotSubmitOutcome::MazeOrBlock,
+                NotABotDecision::MazeOrBlock,
+                return_to.as_str(),
+            )
+        };
     };
 
     if score >= cfg.not_a_bot_pass_score {
@@ -268,29 +272,46 @@ pub(crate) fn handle_not_a_bot_submit_with_outcome<S: crate::challenge::KeyValue
                 seed.ip_bucket.as_str(),
                 seed.ua_bucket.as_str(),
                 cfg.not_a_bot_marker_ttl_seconds,
+                MarkerTrust::Checkbox,
             )),
             solve_ms: Some(telemetry.interaction_elapsed_ms as u64),
+            accessibility_cohort,
+            accessible_offer: false,
         };
     }
 
     if score >= cfg.not_a_bot_fail_score {
+        // Only a scored attempt that fell short opens the text alternative for this session.
+        let accessible_offer = super::accessible::open_accessible_offer(
+            store,
+            cfg,
+            seed.ip_bucket.as_str(),
+            seed.ua_bucket.as_str(),
+            return_to.as_str(),
+            now,
+        );
         return NotABotSubmitResult {
             outcome: NotABotSubmitOutcome::EscalatePuzzle,
             decision: NotABotDecision::EscalatePuzzle,
             return_to,
             marker_cookie: None,
             solve_ms: Some(telemetry.interaction_elapsed_ms as u64),
+            accessibility_cohort,
+            accessible_offer,
         };
     }
 
-    failure_result(
-        NotABotSubmitOutcome::FailedScore,
-        NotABotDecision::MazeOrBlock,
-        return_to.as_str(),
-    )
+    NotABotSubmitResult {
+        accessibility_cohort,
+        ..failure_result(
+            NotABotSubmitOutcome::FailedScore,
+            NotABotDecision::MazeOrBlock,
+            return_to.as_str(),
+        )
+    }
 }
 
-fn increment_and_check_attempt_limit<S: crate::challenge::KeyValueStore>(
+pub(super) fn increment_and_check_attempt_limit<S: crate::challenge::KeyValueStore>(
     store: &S,
     ip_bucket: &str,
     now: u64,
@@ -355,6 +376,9 @@ fn validate_telemetry_ranges(telemetry: &NotABotTelemetry) -> bool {
     if telemetry.activation_count > 10 {
         return false;
     }
+    if telemetry.sequential_focus_count > 200 {
+        return false;
+    }
     if parse_activation_method(telemetry.activation_method.as_str()).is_none() {
         return false;
     }
@@ -435,18 +459,31 @@ fn compute_not_a_bot_score(checked: bool, telemetry: &NotABotTelemetry) -> Optio
             }
             // Keep keyboard-only flows equivalent-strength without requiring pointer motion.
             score = score.saturating_add(if telemetry.control_focused { 3 } else { 2 });
+            if telemetry.sequential_focus_count > 0 {
+                score = score.saturating_add(1);
+            }
         }
         "unknown" => {
             // Assistive and synthetic browser mediation paths can legitimately hide raw modality.
-            // Keep this path pass-capable without granting a strong standalone score bump.
-            if telemetry.control_focused && telemetry.interaction_elapsed_ms >= 900 {
+            // Screen readers, switch access and voice control activate through a trusted click
+            // with no preceding pointer or key press; score that like keyboard activation.
+            if telemetry.virtual_activation
+                && telemetry.activation_trusted
+                && telemetry.control_focused
+            {
+                score = score.saturating_add(3);
+            } else if telemetry.control_focused && telemetry.interaction_elapsed_ms >= 900 {
                 score = score.saturating_add(1);
             }
         }
         _ => return None,
     }
 
-    if telemetry.keyboard_used || telemetry.touch_used || telemetry.has_pointer {
+    if telemetry.keyboard_used
+        || telemetry.touch_used
+        || telemetry.has_pointer
+        || telemetry.virtual_activation
+    {
         score = score.saturating_add(1);
     }
     if telemetry.control_focused {
@@ -463,7 +500,17 @@ fn compute_not_a_bot_score(checked: bool, telemetry: &NotABotTelemetry) -> Optio
     Some(score.min(10))
 }
 
-fn failure_result(
+/// Recognises telemetry shapes produced by assistive technology. The telemetry is
+/// self-reported, so this only labels the friction-reporting cohort and never routes.
+fn matches_assistive_profile(telemetry: &NotABotTelemetry) -> bool {
+    let keyboard_only = telemetry.keyboard_used
+        && !telemetry.has_pointer
+        && !telemetry.touch_used
+        && telemetry.sequential_focus_count > 0;
+    telemetry.virtual_activation || keyboard_only || telemetry.forced_colors
+}
+
+pub(super) fn failure_result(
     outcome: NotABotSubmitOutcome,
     decision: NotABotDecision,
     return_to: &str,
@@ -474,10 +521,12 @@ fn failure_result(
         return_to: normalize_return_to(return_to),
         marker_cookie: None,
         solve_ms: None,
+        accessibility_cohort: false,
+        accessible_offer: false,
     }
 }
 
-fn get_form_field(form: &str, name: &str) -> Option<String> {
+pub(super) fn get_form_field(form: &str, name: &str) -> Option<String> {
     for pair in form.split('&') {
         let mut parts = pair.splitn(2, '=');
         if let (Some(k), Some(v)) = (parts.next(), parts.next()) {
@@ -529,6 +578,7 @@ mod tests {
             activation_trusted: true,
             activation_count: 1,
             control_focused: true,
+            ..NotABotTelemetry::default()
         };
         let score = compute_not_a_bot_score(true, &telemetry).unwrap();
         assert!(score >= 7);
@@ -551,6 +601,7 @@ mod tests {
             activation_trusted: true,
             activation_count: 1,
             control_focused: true,
+            ..NotABotTelemetry::default()
         };
         let score = compute_not_a_bot_score(true, &telemetry).unwrap();
         assert!(
@@ -619,6 +670,7 @@ mod tests {
             activation_trusted: true,
             activation_count: 1,
             control_focused: true,
+            ..NotABotTelemetry::default()
         };
         let score = compute_not_a_bot_score(true, &telemetry).unwrap();
         assert!(score >= 7);
@@ -641,6 +693,7 @@ mod tests {
             activation_trusted: false,
             activation_count: 1,
             control_focused: true,
+            ..NotABotTelemetry::default()
         };
         let score = compute_not_a_bot_score(true, &telemetry).unwrap();
         assert!(
@@ -666,4 +719,43 @@ mod tests {
             "unknown modality should remain pass-capable for accessibility mediation paths"
         );
     }
+
+    #[test]
+    fn score_treats_screen_reader_virtual_activation_like_keyboard_activation() {
+        let telemetry = NotABotTelemetry {
+            interaction_elapsed_ms: 4200,
+            activation_method: "unknown".to_string(),
+            activation_trusted: true,
+            activation_count: 1,
+            control_focused: true,
+            virtual_activation: true,
+            ..NotABotTelemetry::default()
+        };
+        let score = compute_not_a_bot_score(true, &telemetry).unwrap();
+        assert!(
+            score >= 7,
+            "assistive-technology activation without pointer telemetry should stay pass-capable"
+        );
+        assert!(matches_assistive_profile(&telemetry));
+    }
+
+    #[test]
+    fn assistive_profile_recognises_tab_navigation_but_not_pointer_users() {
+        let keyboard = NotABotTelemetry {
+            keyboard_used: true,
+            sequential_focus_count: 3,
+            activation_method: "keyboard".to_string(),
+            ..NotABotTelemetry::default()
+        };
+        assert!(matches_assistive_profile(&keyboard));
+
+        let pointer = NotABotTelemetry {
+            has_pointer: true,
+            keyboard_used: true,
+            sequential_focus_count: 1,
+            activation_method: "pointer".to_string(),
+            ..NotABotTelemetry::default()
+        };
+        assert!(!matches_assistive_profile(&pointer));
+    }
 }
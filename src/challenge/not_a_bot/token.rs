@@ -2,7 +2,7 @@ use base64::{engine::general_purpose, Engine as _};
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
-use super::types::NotABotSeed;
+use super::types::{AccessibleSeed, NotABotSeed};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum SeedTokenError {
@@ -26,12 +26,29 @@ pub(crate) enum MarkerTokenError {
     SignatureMismatch,
 }
 
+/// How a Not-a-Bot pass marker was earned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MarkerTrust {
+    #[default]
+    Checkbox,
+    /// Earned through the text alternative; short-lived and only honoured near the threshold.
+    Accessible,
+}
+
+/// Accessible-pass markers never outlive this, whatever `not_a_bot_marker_ttl_seconds` says.
+const ACCESSIBLE_MARKER_MAX_TTL_SECONDS: u64 = 300;
+/// Botness points above `not_a_bot_risk_threshold` an accessible-pass marker still waives.
+const ACCESSIBLE_MARKER_SCORE_HEADROOM: u8 = 1;
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct NotABotMarker {
     token_version: u8,
     ip_bucket: String,
     ua_bucket: String,
     expires_at: u64,
+    #[serde(default)]
+    trust: MarkerTrust,
 }
 
 fn get_challenge_secret() -> String {
@@ -114,13 +131,63 @@ pub(crate) fn parse_seed_token(token: &str) -> Result<NotABotSeed, SeedTokenErro
     Ok(payload)
 }
 
-pub(crate) fn marker_cookie_value(ip_bucket: &str, ua_bucket: &str, ttl_seconds: u64) -> String {
+pub(crate) fn make_accessible_seed_token(payload: &AccessibleSeed) -> String {
+    let payload_json = serde_json::to_string(payload).unwrap();
+    encode_signed_payload(&payload_json)
+}
+
+pub(crate) fn parse_accessible_seed_token(token: &str) -> Result<AccessibleSeed, SeedTokenError> {
+    let payload_json = decode_signed_payload(token).map_err(|err| match err {
+        MarkerTokenError::MissingPayload => SeedTokenError::MissingPayload,
+        MarkerTokenError::MissingSignature => SeedTokenError::MissingSignature,
+        MarkerTokenError::InvalidPayloadEncoding => SeedTokenError::InvalidPayloadEncoding,
+        MarkerTokenError::InvalidSignatureEncoding => SeedTokenError::InvalidSignatureEncoding,
+        MarkerTokenError::InvalidPayloadUtf8 => SeedTokenError::InvalidPayloadUtf8,
+        MarkerTokenError::SignatureMismatch => SeedTokenError::SignatureMismatch,
+    })?;
+    let payload = serde_json::from_str::<AccessibleSeed>(&payload_json)
+        .map_err(|_| SeedTokenError::InvalidPayloadJson)?;
+    crate::challenge::operation_envelope::validate_signed_operation_envelope(
+        payload.operation_id.as_str(),
+        payload.flow_id.as_str(),
+        payload.step_id.as_str(),
+        payload.issued_at,
+        payload.expires_at,
+        payload.token_version,
+        crate::challenge::operation_envelope::FLOW_NOT_A_BOT_ACCESSIBLE,
+        crate::challenge::operation_envelope::STEP_NOT_A_BOT_ACCESSIBLE_SUBMIT,
+    )
+    .map_err(SeedTokenError::InvalidOperationEnvelope)?;
+    Ok(payload)
+}
+
+/// Keyed digest naming the bank question a seed was issued for, so the signed (but readable)
+/// seed carries neither the question index nor its answers.
+pub(crate) fn accessible_question_digest(operation_id: &str, question: &str) -> String {
+    let sig = sign_payload(format!("accessible:{}:{}", operation_id, question).as_str());
+    sig.iter()
+        .take(16)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub(crate) fn marker_cookie_value(
+    ip_bucket: &str,
+    ua_bucket: &str,
+    ttl_seconds: u64,
+    trust: MarkerTrust,
+) -> String {
+    let ttl_seconds = match trust {
+        MarkerTrust::Checkbox => ttl_seconds,
+        MarkerTrust::Accessible => ttl_seconds.min(ACCESSIBLE_MARKER_MAX_TTL_SECONDS),
+    };
     let expires_at = crate::admin::now_ts().saturating_add(ttl_seconds);
     let marker = NotABotMarker {
         token_version: crate::challenge::operation_envelope::TOKEN_VERSION_V1,
         ip_bucket: ip_bucket.to_string(),
         ua_bucket: ua_bucket.to_string(),
         expires_at,
+        trust,
     };
     let payload_json = serde_json::to_string(&marker).unwrap();
     let token = encode_signed_payload(&payload_json);
@@ -130,36 +197,51 @@ pub(crate) fn marker_cookie_value(ip_bucket: &str, ua_bucket: &str, ttl_seconds:
     )
 }
 
-pub(crate) fn has_valid_marker(req: &spin_sdk::http::Request, ip: &str, ua: &str) -> bool {
-    let cookie_header = match req.header("cookie").and_then(|value| value.as_str()) {
-        Some(value) => value,
-        None => return false,
-    };
-    let token = match extract_cookie(cookie_header, "shuma_not_a_bot") {
-        Some(value) if !value.trim().is_empty() => value,
-        _ => return false,
-    };
-    let payload_json = match decode_signed_payload(token.as_str()) {
-        Ok(value) => value,
-        Err(_) => return false,
-    };
-    let marker = match serde_json::from_str::<NotABotMarker>(&payload_json) {
-        Ok(value) => value,
-        Err(_) => return false,
-    };
+fn valid_marker_trust(req: &spin_sdk::http::Request, ip: &str, ua: &str) -> Option<MarkerTrust> {
+    let cookie_header = req.header("cookie").and_then(|value| value.as_str())?;
+    let token = extract_cookie(cookie_header, "shuma_not_a_bot")
+        .filter(|value| !value.trim().is_empty())?;
+    let payload_json = decode_signed_payload(token.as_str()).ok()?;
+    let marker = serde_json::from_str::<NotABotMarker>(&payload_json).ok()?;
     if marker.token_version != crate::challenge::operation_envelope::TOKEN_VERSION_V1 {
-        return false;
+        return None;
     }
     let now = crate::admin::now_ts();
     if now > marker.expires_at {
-        return false;
+        return None;
     }
     let expected_ip_bucket = crate::signals::ip_identity::bucket_ip(ip);
     if marker.ip_bucket != expected_ip_bucket {
-        return false;
+        return None;
     }
     let expected_ua_bucket = crate::challenge::operation_envelope::user_agent_bucket(ua);
-    marker.ua_bucket == expected_ua_bucket
+    (marker.ua_bucket == expected_ua_bucket).then_some(marker.trust)
+}
+
+/// True only for markers earned through the scored checkbox.
+pub(crate) fn has_valid_marker(req: &spin_sdk::http::Request, ip: &str, ua: &str) -> bool {
+    valid_marker_trust(req, ip, ua) == Some(MarkerTrust::Checkbox)
+}
+
+/// Whether a marker waives the Not-a-Bot step at this botness score. Accessible-pass markers
+/// keep the normal score in charge: they only cover scores just over the threshold.
+pub(crate) fn has_valid_marker_for_score(
+    req: &spin_sdk::http::Request,
+    ip: &str,
+    ua: &str,
+    botness_score: u8,
+    cfg: &crate::config::Config,
+) -> bool {
+    match valid_marker_trust(req, ip, ua) {
+        Some(MarkerTrust::Checkbox) => true,
+        Some(MarkerTrust::Accessible) => {
+            botness_score
+                <= cfg
+                    .not_a_bot_risk_threshold
+                    .saturating_add(ACCESSIBLE_MARKER_SCORE_HEADROOM)
+        }
+        None => false,
+    }
 }
 
 fn extract_cookie(cookie_header: &str, key: &str) -> Option<String> {
@@ -221,7 +303,7 @@ mod tests {
         let ip_bucket = "198.51.100.0";
         let ua = "Mozilla/5.0";
         let ua_bucket = crate::challenge::operation_envelope::user_agent_bucket(ua);
-        let cookie = marker_cookie_value(ip_bucket, ua_bucket.as_str(), 600);
+        let cookie = marker_cookie_value(ip_bucket, ua_bucket.as_str(), 600, MarkerTrust::Checkbox);
         let token = cookie
             .split(';')
             .next()
@@ -243,4 +325,28 @@ mod tests {
         assert!(has_valid_marker(&req, "198.51.100.42", ua));
         assert!(!has_valid_marker(&req, "203.0.113.10", ua));
     }
+
+    #[test]
+    fn accessible_marker_is_short_lived_and_only_waives_near_threshold_scores() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_JS_SECRET", "unit-test-secret");
+        std::env::remove_var("SHUMA_CHALLENGE_SECRET");
+        let ua = "Mozilla/5.0";
+        let ua_bucket = crate::challenge::operation_envelope::user_agent_bucket(ua);
+        let cookie =
+            marker_cookie_value("198.51.100.0", ua_bucket.as_str(), 3600, MarkerTrust::Accessible);
+        assert!(cookie.ends_with("Max-Age=300"));
+
+        let req = Request::builder()
+            .method(spin_sdk::http::Method::Get)
+            .uri("/")
+            .header("cookie", cookie)
+            .header("user-agent", ua)
+            .build();
+        let cfg = crate::config::defaults();
+        let threshold = cfg.not_a_bot_risk_threshold;
+        assert!(!has_valid_marker(&req, "198.51.100.42", ua));
+        assert!(has_valid_marker_for_score(&req, "198.51.100.42", ua, threshold + 1, cfg));
+        assert!(!has_valid_marker_for_score(&req, "198.51.100.42", ua, threshold + 2, cfg));
+    }
 }
@@ -32,12 +32,32 @@ pub(crate) struct NotABotTelemetry {
     pub activation_trusted: bool,
     pub activation_count: u8,
     pub control_focused: bool,
+    pub virtual_activation: bool,
+    pub sequential_focus_count: u8,
+    pub forced_colors: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AccessibleSeed {
+    pub operation_id: String,
+    pub flow_id: String,
+    pub step_id: String,
+    pub step_index: u8,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub token_version: u8,
+    pub ip_bucket: String,
+    pub ua_bucket: String,
+    pub path_class: String,
+    pub return_to: String,
+    pub question_digest: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum NotABotDecision {
     Pass,
     EscalatePuzzle,
+    RetryAccessible,
     MazeOrBlock,
 }
 
@@ -55,6 +75,7 @@ pub(crate) enum NotABotSubmitOutcome {
     BindingMismatch,
     InvalidTelemetry,
     AttemptLimitExceeded,
+    AccessibleIncorrect,
 }
 
 #[derive(Debug, Clone)]
@@ -64,4 +85,7 @@ pub(crate) struct NotABotSubmitResult {
     pub return_to: String,
     pub marker_cookie: Option<String>,
     pub solve_ms: Option<u64>,
+    pub accessibility_cohort: bool,
+    /// Set when this attempt opened (or kept open) the text alternative for the session.
+    pub accessible_offer: bool,
 }
@@ -26,6 +26,19 @@ pub(crate) const TIMING_REGULARITY_SPREAD_SECONDS_NOT_A_BOT: u64 = 1;
 pub(crate) const TIMING_HISTORY_TTL_SECONDS_NOT_A_BOT: u64 = 1200;
 pub(crate) const MAX_OPERATION_REPLAY_TTL_SECONDS_NOT_A_BOT: u64 = 600;
 
+pub(crate) const FLOW_NOT_A_BOT_ACCESSIBLE: &str = "not_a_bot_accessible";
+pub(crate) const STEP_NOT_A_BOT_ACCESSIBLE_SUBMIT: &str = "not_a_bot_accessible_submit";
+pub(crate) const PATH_CLASS_NOT_A_BOT_ACCESSIBLE_SUBMIT: &str = "not_a_bot_accessible_submit";
+pub(crate) const STEP_INDEX_NOT_A_BOT_ACCESSIBLE_SUBMIT: u8 = 2;
+pub(crate) const MAX_STEP_WINDOW_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 600;
+pub(crate) const MIN_STEP_LATENCY_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 1;
+pub(crate) const MAX_STEP_LATENCY_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 900;
+pub(crate) const MAX_FLOW_AGE_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 900;
+pub(crate) const TIMING_REGULARITY_WINDOW_NOT_A_BOT_ACCESSIBLE: usize = 4;
+pub(crate) const TIMING_REGULARITY_SPREAD_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 1;
+pub(crate) const TIMING_HISTORY_TTL_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 1800;
+pub(crate) const MAX_OPERATION_REPLAY_TTL_SECONDS_NOT_A_BOT_ACCESSIBLE: u64 = 900;
+
 pub(crate) const FLOW_JS_VERIFICATION: &str = "js_verification";
 pub(crate) const STEP_JS_POW_VERIFY: &str = "pow_verify";
 pub(crate) const PATH_CLASS_JS_POW_VERIFY: &str = "pow_verify";
@@ -39,6 +52,13 @@ pub(crate) const TIMING_REGULARITY_SPREAD_SECONDS_JS_POW_VERIFY: u64 = 1;
 pub(crate) const TIMING_HISTORY_TTL_SECONDS_JS_POW_VERIFY: u64 = 1200;
 pub(crate) const MAX_OPERATION_REPLAY_TTL_SECONDS_JS_POW_VERIFY: u64 = 600;
 
+pub(crate) const FLOW_CDP_REPORT: &str = "cdp_report";
+pub(crate) const STEP_CDP_REPORT_SUBMIT: &str = "cdp_report_submit";
+pub(crate) const PATH_CLASS_CDP_REPORT_SUBMIT: &str = "cdp_report_submit";
+pub(crate) const MAX_STEP_WINDOW_SECONDS_CDP_REPORT: u64 = 300;
+pub(crate) const MIN_STEP_LATENCY_MS_CDP_REPORT: u64 = 150;
+pub(crate) const MAX_OPERATION_REPLAY_TTL_SECONDS_CDP_REPORT: u64 = 300;
+
 const MAX_OPERATION_ID_LEN: usize = 64;
 const UA_BUCKET_HEX_LEN: usize = 16;
 const CADENCE_KEY_PREFIX: &str = "seq:cadence";
@@ -0,0 +1,194 @@
+use spin_sdk::http::{Request, Response};
+
+use super::keys::{current_epoch, issuer_key_for_epoch, privacy_pass_enabled};
+use super::wire::{
+    encode_token_response, parse_token_request, TOKEN_REQUEST_CONTENT_TYPE,
+    TOKEN_RESPONSE_CONTENT_TYPE,
+};
+
+const ISSUANCE_GRANT_PREFIX: &str = "privacy_pass:issuance";
+/// Tokens a client may have signed after one successful challenge.
+pub(crate) const TOKENS_PER_CHALLENGE_PASS: u32 = 5;
+/// How long after a challenge pass the client may request its tokens.
+const ISSUANCE_GRANT_TTL_SECONDS: u64 = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IssuanceOutcome {
+    Issued,
+    Disabled,
+    InvalidRequest,
+    UnknownKey,
+    NoGrant,
+}
+
+impl IssuanceOutcome {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            IssuanceOutcome::Issued => "issued",
+            IssuanceOutcome::Disabled => "disabled",
+            IssuanceOutcome::InvalidRequest => "invalid_request",
+            IssuanceOutcome::UnknownKey => "unknown_key",
+            IssuanceOutcome::NoGrant => "no_grant",
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct IssuanceGrant {
+    remaining: u32,
+    expires_at: u64,
+}
+
+fn grant_key(ip: &str, ua: &str) -> String {
+    format!(
+        "{}:{}:{}",
+        ISSUANCE_GRANT_PREFIX,
+        crate::signals::ip_identity::bucket_ip(ip),
+        crate::challenge::operation_envelope::user_agent_bucket(ua)
+    )
+}
+
+/// Record that this IP/UA bucket just passed a challenge and may request blind-signed tokens.
+///
+/// The grant is the only link between the challenge pass and issuance; the tokens themselves are
+/// blinded, so the issuer cannot connect a later redemption back to this grant.
+pub(crate) fn grant_issuance<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) {
+    if !privacy_pass_enabled() {
+        return;
+    }
+    let grant = IssuanceGrant {
+        remaining: TOKENS_PER_CHALLENGE_PASS,
+        expires_at: now.saturating_add(ISSUANCE_GRANT_TTL_SECONDS),
+    };
+    let key = grant_key(ip, ua);
+    let payload = serde_json::to_vec(&grant).unwrap_or_default();
+    if store.set(key.as_str(), payload.as_slice()).is_err() {
+        eprintln!("[privacy_pass] failed to persist issuance grant {}", key);
+    }
+}
+
+fn consume_grant<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) -> bool {
+    let key = grant_key(ip, ua);
+    let Some(mut grant) = store
+        .get(key.as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<IssuanceGrant>(&raw).ok())
+    else {
+        return false;
+    };
+    if now > grant.expires_at || grant.remaining == 0 {
+        let _ = store.delete(key.as_str());
+        return false;
+    }
+    grant.remaining -= 1;
+    let persisted = if grant.remaining == 0 {
+        store.delete(key.as_str())
+    } else {
+        store.set(
+            key.as_str(),
+            serde_json::to_vec(&grant).unwrap_or_default().as_slice(),
+        )
+    };
+    if persisted.is_err() {
+        eprintln!("[privacy_pass] failed to update issuance grant {}", key);
+    }
+    true
+}
+
+fn issuance_error(status: u16, body: &str) -> Response {
+    Response::builder()
+        .status(status)
+        .header("Cache-Control", "no-store")
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .body(body)
+        .build()
+}
+
+/// Handle an RFC 9578 `TokenRequest`, returning a `TokenResponse` when the caller holds an
+/// issuance grant from a recent challenge pass.
+pub(crate) fn handle_token_request<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) -> (Response, IssuanceOutcome) {
+    let Some(key) = privacy_pass_enabled()
+        .then(|| issuer_key_for_epoch(current_epoch(now)))
+        .flatten()
+    else {
+        return (
+            issuance_error(404, "Not Found"),
+            IssuanceOutcome::Disabled,
+        );
+    };
+    let content_type = req
+        .header("content-type")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    if !content_type
+        .trim()
+        .eq_ignore_ascii_case(TOKEN_REQUEST_CONTENT_TYPE)
+    {
+        return (
+            issuance_error(415, "Unsupported Media Type"),
+            IssuanceOutcome::InvalidRequest,
+        );
+    }
+    let Some(token_request) = parse_token_request(req.body()) else {
+        return (
+            issuance_error(400, "Invalid token request"),
+            IssuanceOutcome::InvalidRequest,
+        );
+    };
+    if token_request.truncated_token_key_id != key.truncated_token_key_id() {
+        return (
+            issuance_error(400, "Unknown token key"),
+            IssuanceOutcome::UnknownKey,
+        );
+    }
+    let Some(blinded) = super::voprf::deserialize_element(&token_request.blinded_msg) else {
+        return (
+            issuance_error(400, "Invalid token request"),
+            IssuanceOutcome::InvalidRequest,
+        );
+    };
+    if !consume_grant(store, ip, ua, now) {
+        return (
+            issuance_error(403, "Token issuance requires a recent challenge pass"),
+            IssuanceOutcome::NoGrant,
+        );
+    }
+    let (evaluated, proof) = super::voprf::blind_evaluate(&key.key_pair, &blinded);
+    let response = Response::builder()
+        .status(200)
+        .header("Cache-Control", "no-store")
+        .header("Content-Type", TOKEN_RESPONSE_CONTENT_TYPE)
+        .body(encode_token_response(&evaluated, &proof))
+        .build();
+    (response, IssuanceOutcome::Issued)
+}
+
+/// Serve the issuer directory so clients can discover the current token keys.
+pub(crate) fn handle_issuer_directory(now: u64) -> Response {
+    match super::keys::issuer_directory_body(now) {
+        Some(body) => Response::builder()
+            .status(200)
+            .header("Cache-Control", "public, max-age=3600")
+            .header("Content-Type", super::wire::ISSUER_DIRECTORY_CONTENT_TYPE)
+            .body(body)
+            .build(),
+        None => issuance_error(404, "Not Found"),
+    }
+}
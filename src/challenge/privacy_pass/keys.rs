@@ -0,0 +1,94 @@
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use super::voprf::KeyPair;
+
+pub(super) const ISSUER_SECRET_ENV: &str = "SHUMA_PRIVACY_PASS_ISSUER_SECRET";
+/// Issuer keys rotate every epoch; redemption accepts the current and previous epoch keys.
+pub(super) const KEY_EPOCH_SECONDS: u64 = 7 * 24 * 60 * 60;
+const KEY_SEED_LABEL: &[u8] = b"shuma-privacy-pass:issuer-seed:v1";
+
+pub(super) struct IssuerKey {
+    pub epoch: u64,
+    pub key_pair: KeyPair,
+    pub public_key: [u8; super::voprf::ELEMENT_LEN],
+    pub token_key_id: [u8; 32],
+}
+
+impl IssuerKey {
+    pub(super) fn truncated_token_key_id(&self) -> u8 {
+        self.token_key_id[self.token_key_id.len() - 1]
+    }
+
+    pub(super) fn not_before(&self) -> u64 {
+        self.epoch.saturating_mul(KEY_EPOCH_SECONDS)
+    }
+}
+
+fn issuer_secret() -> Option<String> {
+    crate::config::runtime_var_trimmed_optional(ISSUER_SECRET_ENV)
+}
+
+/// Privacy Pass issuance and redemption are only active when an issuer secret is configured.
+pub(crate) fn privacy_pass_enabled() -> bool {
+    issuer_secret().is_some()
+}
+
+pub(super) fn current_epoch(now: u64) -> u64 {
+    now / KEY_EPOCH_SECONDS
+}
+
+pub(super) fn issuer_key_for_epoch(epoch: u64) -> Option<IssuerKey> {
+    let secret = issuer_secret()?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+    mac.update(KEY_SEED_LABEL);
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(mac.finalize().into_bytes().as_slice());
+    let info = format!("shuma-privacy-pass:epoch:{}", epoch);
+    let key_pair = KeyPair::derive(&seed, info.as_bytes())?;
+    let public_key = key_pair.public_key_bytes();
+    let mut token_key_id = [0u8; 32];
+    token_key_id.copy_from_slice(Sha256::digest(public_key).as_slice());
+    Some(IssuerKey {
+        epoch,
+        key_pair,
+        public_key,
+        token_key_id,
+    })
+}
+
+/// Keys accepted for redemption, newest first.
+pub(super) fn redemption_keys(now: u64) -> Vec<IssuerKey> {
+    let epoch = current_epoch(now);
+    [Some(epoch), epoch.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .filter_map(issuer_key_for_epoch)
+        .collect()
+}
+
+/// JSON body for `/.well-known/private-token-issuer-directory` (RFC 9578 section 4).
+pub(crate) fn issuer_directory_body(now: u64) -> Option<String> {
+    let keys = redemption_keys(now);
+    if keys.is_empty() {
+        return None;
+    }
+    let token_keys: Vec<serde_json::Value> = keys
+        .iter()
+        .map(|key| {
+            serde_json::json!({
+                "token-type": super::wire::TOKEN_TYPE,
+                "token-key": general_purpose::URL_SAFE_NO_PAD.encode(key.public_key),
+                "not-before": key.not_before(),
+            })
+        })
+        .collect();
+    Some(
+        serde_json::json!({
+            "issuer-request-uri": crate::challenge::PRIVACY_PASS_TOKEN_REQUEST_PATH,
+            "token-keys": token_keys,
+        })
+        .to_string(),
+    )
+}
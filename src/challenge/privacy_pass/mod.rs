@@ -0,0 +1,18 @@
+//! Privacy Pass (RFC 9576/9578) style anonymous tokens used as a challenge bypass.
+//!
+//! Clients that pass a challenge may request a small batch of blind-signed tokens. Redeeming a
+//! token later proves a prior challenge pass without letting the issuer link the two visits.
+
+mod issuance;
+mod keys;
+mod redemption;
+mod voprf;
+mod wire;
+
+pub(crate) use issuance::{
+    grant_issuance, handle_issuer_directory, handle_token_request, IssuanceOutcome,
+};
+pub(crate) use redemption::{
+    handle_redeem_request, has_valid_redemption_marker, redeem_request_token,
+    with_token_challenge, RedemptionOutcome,
+};
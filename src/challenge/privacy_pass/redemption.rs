@@ -0,0 +1,389 @@
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use spin_sdk::http::{Request, Response};
+
+use super::keys::{current_epoch, issuer_key_for_epoch, privacy_pass_enabled, redemption_keys};
+use super::wire::{
+    parse_token, token_challenge_digest, token_from_authorization, www_authenticate_value,
+};
+
+const SPENT_TOKEN_PREFIX: &str = "privacy_pass:spent";
+const REDEMPTION_MARKER_COOKIE: &str = "shuma_privacy_pass";
+/// A redeemed token is remembered for the same IP/UA bucket for this long, so a browser does not
+/// have to spend one token per request.
+const REDEMPTION_MARKER_TTL_SECONDS: u64 = 600;
+const REDEMPTION_MARKER_LABEL: &[u8] = b"shuma-privacy-pass:redemption-marker:v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RedemptionOutcome {
+    Redeemed,
+    Disabled,
+    Missing,
+    Malformed,
+    ChallengeMismatch,
+    UnknownKey,
+    InvalidAuthenticator,
+    DoubleSpend,
+}
+
+impl RedemptionOutcome {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            RedemptionOutcome::Redeemed => "redeemed",
+            RedemptionOutcome::Disabled => "disabled",
+            RedemptionOutcome::Missing => "missing",
+            RedemptionOutcome::Malformed => "malformed",
+            RedemptionOutcome::ChallengeMismatch => "challenge_mismatch",
+            RedemptionOutcome::UnknownKey => "unknown_key",
+            RedemptionOutcome::InvalidAuthenticator => "invalid_authenticator",
+            RedemptionOutcome::DoubleSpend => "double_spend",
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct RedemptionMarker {
+    ip_bucket: String,
+    ua_bucket: String,
+    expires_at: u64,
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn spent_token_key(epoch: u64, nonce: &[u8]) -> String {
+    let nonce_hex: String = nonce.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}:{}:{}", SPENT_TOKEN_PREFIX, epoch, nonce_hex)
+}
+
+/// Verify and spend the `PrivateToken` presented in the request's `Authorization` header.
+///
+/// A token is spent as soon as it verifies, whether or not the request would have been
+/// challenged; clients are expected to present tokens only in response to a challenge.
+pub(crate) fn redeem_request_token<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    now: u64,
+) -> RedemptionOutcome {
+    if !privacy_pass_enabled() {
+        return RedemptionOutcome::Disabled;
+    }
+    let Some(header) = req.header("authorization").and_then(|value| value.as_str()) else {
+        return RedemptionOutcome::Missing;
+    };
+    let Some(token_bytes) = token_from_authorization(header) else {
+        return RedemptionOutcome::Missing;
+    };
+    let Some(token) = parse_token(token_bytes.as_slice()) else {
+        return RedemptionOutcome::Malformed;
+    };
+    if !constant_time_eq(&token.challenge_digest, &token_challenge_digest(req)) {
+        return RedemptionOutcome::ChallengeMismatch;
+    }
+    let Some(key) = redemption_keys(now)
+        .into_iter()
+        .find(|key| key.token_key_id == token.token_key_id)
+    else {
+        return RedemptionOutcome::UnknownKey;
+    };
+    let expected = super::voprf::evaluate(&key.key_pair, token.authenticator_input().as_slice());
+    if !constant_time_eq(&expected, &token.authenticator) {
+        return RedemptionOutcome::InvalidAuthenticator;
+    }
+
+    // Spent nonces are tracked until the key that signed them stops being accepted.
+    let spent_key = spent_token_key(key.epoch, &token.nonce);
+    if let Ok(Some(raw)) = store.get(spent_key.as_str()) {
+        let still_tracked = String::from_utf8(raw)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(|tracked_until| now <= tracked_until)
+            .unwrap_or(true);
+        if still_tracked {
+            return RedemptionOutcome::DoubleSpend;
+        }
+    }
+    let tracked_until = key
+        .epoch
+        .saturating_add(2)
+        .saturating_mul(super::keys::KEY_EPOCH_SECONDS);
+    if store
+        .set(spent_key.as_str(), tracked_until.to_string().as_bytes())
+        .is_err()
+    {
+        eprintln!("[privacy_pass] failed to persist spent token {}", spent_key);
+    }
+    RedemptionOutcome::Redeemed
+}
+
+fn marker_signature(payload: &str) -> Option<Vec<u8>> {
+    let secret = crate::config::runtime_var_trimmed_optional(super::keys::ISSUER_SECRET_ENV)?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+    mac.update(REDEMPTION_MARKER_LABEL);
+    mac.update(payload.as_bytes());
+    Some(mac.finalize().into_bytes().to_vec())
+}
+
+fn redemption_marker_cookie(ip: &str, ua: &str, now: u64) -> Option<String> {
+    let marker = RedemptionMarker {
+        ip_bucket: crate::signals::ip_identity::bucket_ip(ip),
+        ua_bucket: crate::challenge::operation_envelope::user_agent_bucket(ua),
+        expires_at: now.saturating_add(REDEMPTION_MARKER_TTL_SECONDS),
+    };
+    let payload_json = serde_json::to_string(&marker).ok()?;
+    let sig = marker_signature(payload_json.as_str())?;
+    Some(format!(
+        "{}={}.{}; path=/; HttpOnly; SameSite=Strict; Max-Age={}",
+        REDEMPTION_MARKER_COOKIE,
+        general_purpose::STANDARD.encode(payload_json.as_bytes()),
+        general_purpose::STANDARD.encode(sig),
+        REDEMPTION_MARKER_TTL_SECONDS
+    ))
+}
+
+/// True when the request carries an unexpired redemption marker for the same IP/UA bucket.
+pub(crate) fn has_valid_redemption_marker(req: &Request, ip: &str, ua: &str, now: u64) -> bool {
+    let Some(cookie_header) = req.header("cookie").and_then(|value| value.as_str()) else {
+        return false;
+    };
+    let Some(token) = cookie_header.split(';').find_map(|part| {
+        let (name, value) = part.trim().split_once('=')?;
+        (name.trim() == REDEMPTION_MARKER_COOKIE).then(|| value.trim())
+    }) else {
+        return false;
+    };
+    let Some((payload_b64, sig_b64)) = token.split_once('.') else {
+        return false;
+    };
+    let (Ok(payload_bytes), Ok(sig)) = (
+        general_purpose::STANDARD.decode(payload_b64),
+        general_purpose::STANDARD.decode(sig_b64),
+    ) else {
+        return false;
+    };
+    let Ok(payload_json) = String::from_utf8(payload_bytes) else {
+        return false;
+    };
+    let Some(expected_sig) = marker_signature(payload_json.as_str()) else {
+        return false;
+    };
+    if !constant_time_eq(&expected_sig, &sig) {
+        return false;
+    }
+    let Ok(marker) = serde_json::from_str::<RedemptionMarker>(&payload_json) else {
+        return false;
+    };
+    now <= marker.expires_at
+        && marker.ip_bucket == crate::signals::ip_identity::bucket_ip(ip)
+        && marker.ua_bucket == crate::challenge::operation_envelope::user_agent_bucket(ua)
+}
+
+/// Attach a `PrivateToken` challenge to a challenge response so token-holding clients can
+/// retry with a token instead of solving it.
+pub(crate) fn with_token_challenge(req: &Request, response: Response) -> Response {
+    if !privacy_pass_enabled() {
+        return response;
+    }
+    let Some(key) = issuer_key_for_epoch(current_epoch(crate::admin::now_ts())) else {
+        return response;
+    };
+    let mut builder = response.into_builder();
+    builder.header(
+        "WWW-Authenticate",
+        www_authenticate_value(req, &key.public_key),
+    );
+    builder.build()
+}
+
+/// Redemption endpoint: spends a presented token and sets a short-lived redemption marker.
+pub(crate) fn handle_redeem_request<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    req: &Request,
+    ip: &str,
+    ua: &str,
+    now: u64,
+) -> (Response, RedemptionOutcome) {
+    let outcome = redeem_request_token(store, req, now);
+    let response = match outcome {
+        RedemptionOutcome::Redeemed => {
+            let mut builder = Response::builder();
+            builder.status(204);
+            builder.header("Cache-Control", "no-store");
+            if let Some(cookie) = redemption_marker_cookie(ip, ua, now) {
+                builder.header("Set-Cookie", cookie);
+            }
+            builder.body(Vec::new()).build()
+        }
+        RedemptionOutcome::Disabled => Response::new(404, "Not Found"),
+        _ => with_token_challenge(
+            req,
+            Response::builder()
+                .status(401)
+                .header("Cache-Control", "no-store")
+                .header("Content-Type", "text/plain; charset=utf-8")
+                .body(format!("Token rejected: {}", outcome.as_str()))
+                .build(),
+        ),
+    };
+    (response, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::issuance::{grant_issuance, handle_token_request, IssuanceOutcome};
+    use super::super::keys::{current_epoch, issuer_key_for_epoch};
+    use super::super::{voprf, wire};
+    use super::*;
+    use crate::test_support::InMemoryStore;
+    use spin_sdk::http::Method;
+
+    const IP: &str = "203.0.113.7";
+    const UA: &str = "Mozilla/5.0 privacy-pass-test";
+    const NOW: u64 = 1_800_000_000;
+
+    fn request_for_host(host: &str) -> Request {
+        Request::builder()
+            .method(Method::Get)
+            .uri("/")
+            .header("host", host)
+            .build()
+    }
+
+    fn token_request(body: Vec<u8>) -> Request {
+        Request::builder()
+            .method(Method::Post)
+            .uri(crate::challenge::PRIVACY_PASS_TOKEN_REQUEST_PATH)
+            .header("content-type", wire::TOKEN_REQUEST_CONTENT_TYPE)
+            .body(body)
+            .build()
+    }
+
+    fn redeem_request(host: &str, token: &[u8]) -> Request {
+        Request::builder()
+            .method(Method::Post)
+            .uri(crate::challenge::PRIVACY_PASS_REDEEM_PATH)
+            .header("host", host)
+            .header(
+                "authorization",
+                format!(
+                    "PrivateToken token=\"{}\"",
+                    general_purpose::URL_SAFE_NO_PAD.encode(token)
+                ),
+            )
+            .build()
+    }
+
+    /// Run the client side of issuance against the issuer and return an encoded token.
+    fn issue_token(
+        store: &InMemoryStore,
+        host: &str,
+    ) -> Result<Vec<u8>, IssuanceOutcome> {
+        let key = issuer_key_for_epoch(current_epoch(NOW)).expect("issuer key");
+        let mut token = wire::Token {
+            nonce: rand::random::<[u8; wire::NONCE_LEN]>(),
+            challenge_digest: token_challenge_digest(&request_for_host(host)),
+            token_key_id: key.token_key_id,
+            authenticator: [0u8; voprf::OUTPUT_LEN],
+        };
+        let input = token.authenticator_input();
+        let blind = voprf::random_scalar();
+        let blinded = voprf::blind(input.as_slice(), &blind);
+        let mut body = wire::TOKEN_TYPE.to_be_bytes().to_vec();
+        body.push(key.truncated_token_key_id());
+        body.extend_from_slice(&blinded.compress().to_bytes());
+
+        let (response, outcome) = handle_token_request(store, &token_request(body), IP, UA, NOW);
+        if outcome != IssuanceOutcome::Issued {
+            return Err(outcome);
+        }
+        let response_body = response.body();
+        let proof: [u8; voprf::PROOF_LEN] = response_body[voprf::ELEMENT_LEN..].try_into().unwrap();
+        token.authenticator = voprf::finalize(
+            input.as_slice(),
+            &blind,
+            &blinded,
+            &response_body[..voprf::ELEMENT_LEN],
+            &key.public_key,
+            &proof,
+        )
+        .expect("issuer proof verifies");
+        Ok(token.encode())
+    }
+
+    #[test]
+    fn issued_token_redeems_once_and_sets_a_bound_redemption_marker() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var(super::super::keys::ISSUER_SECRET_ENV, "unit-test-issuer-secret");
+        let store = InMemoryStore::default();
+        grant_issuance(&store, IP, UA, NOW);
+
+        let token = issue_token(&store, "example.com").expect("token issued");
+        let (response, outcome) =
+            handle_redeem_request(&store, &redeem_request("example.com", &token), IP, UA, NOW);
+        assert_eq!(outcome, RedemptionOutcome::Redeemed);
+        let cookie = response
+            .header("set-cookie")
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.split(';').next())
+            .expect("redemption marker cookie")
+            .to_string();
+        let marked = Request::builder()
+            .method(Method::Get)
+            .uri("/")
+            .header("cookie", cookie)
+            .build();
+        assert!(has_valid_redemption_marker(&marked, IP, UA, NOW + 1));
+        assert!(!has_valid_redemption_marker(&marked, "198.51.100.1", UA, NOW + 1));
+
+        assert_eq!(
+            redeem_request_token(&store, &redeem_request("example.com", &token), NOW),
+            RedemptionOutcome::DoubleSpend
+        );
+        std::env::remove_var(super::super::keys::ISSUER_SECRET_ENV);
+    }
+
+    #[test]
+    fn issuance_requires_a_challenge_grant_and_tokens_are_origin_scoped() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var(super::super::keys::ISSUER_SECRET_ENV, "unit-test-issuer-secret");
+        let store = InMemoryStore::default();
+        assert_eq!(
+            issue_token(&store, "example.com").err(),
+            Some(IssuanceOutcome::NoGrant)
+        );
+
+        grant_issuance(&store, IP, UA, NOW);
+        for _ in 0..super::super::issuance::TOKENS_PER_CHALLENGE_PASS - 1 {
+            issue_token(&store, "example.com").expect("token within grant");
+        }
+        let token = issue_token(&store, "example.com").expect("last token within grant");
+        assert_eq!(
+            issue_token(&store, "example.com").err(),
+            Some(IssuanceOutcome::NoGrant)
+        );
+
+        assert_eq!(
+            redeem_request_token(&store, &redeem_request("other.example", &token), NOW),
+            RedemptionOutcome::ChallengeMismatch
+        );
+        let mut forged = token.clone();
+        let last = forged.len() - 1;
+        forged[last] ^= 0x01;
+        assert_eq!(
+            redeem_request_token(&store, &redeem_request("example.com", &forged), NOW),
+            RedemptionOutcome::InvalidAuthenticator
+        );
+        std::env::remove_var(super::super::keys::ISSUER_SECRET_ENV);
+        assert_eq!(
+            redeem_request_token(&store, &redeem_request("example.com", &token), NOW),
+            RedemptionOutcome::Disabled
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! VOPRF (RFC 9497, mode 0x01) over ristretto255 with SHA-512.
+//!
+//! Only the issuer-side operations run in production: key derivation, blind evaluation with a
+//! DLEQ proof, and unblinded evaluation for redemption checks. The client-side blind/finalize
+//! helpers exist so tests can exercise the full issuance round trip.
+
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use sha2::{Digest, Sha512};
+
+pub(super) const ELEMENT_LEN: usize = 32;
+pub(super) const SCALAR_LEN: usize = 32;
+pub(super) const PROOF_LEN: usize = SCALAR_LEN * 2;
+pub(super) const OUTPUT_LEN: usize = 64;
+
+const CONTEXT_STRING: &[u8] = b"OPRFV1-\x01-ristretto255-SHA512";
+const SHA512_BLOCK_LEN: usize = 128;
+
+pub(super) struct KeyPair {
+    secret: Scalar,
+    public: RistrettoPoint,
+}
+
+impl KeyPair {
+    /// RFC 9497 `DeriveKeyPair`: deterministic key derivation from a 32-byte seed and key info.
+    pub(super) fn derive(seed: &[u8; 32], info: &[u8]) -> Option<Self> {
+        let info_len = u16::try_from(info.len()).ok()?;
+        let mut derive_input = Vec::with_capacity(seed.len() + 2 + info.len() + 1);
+        derive_input.extend_from_slice(seed);
+        derive_input.extend_from_slice(&info_len.to_be_bytes());
+        derive_input.extend_from_slice(info);
+        let dst = domain_separation_tag(b"DeriveKeyPair");
+        for counter in 0u8..=u8::MAX {
+            derive_input.push(counter);
+            let secret = hash_to_scalar(derive_input.as_slice(), dst.as_slice());
+            derive_input.pop();
+            if secret != Scalar::ZERO {
+                return Some(Self {
+                    secret,
+                    public: secret * RISTRETTO_BASEPOINT_POINT,
+                });
+            }
+        }
+        None
+    }
+
+    pub(super) fn public_key_bytes(&self) -> [u8; ELEMENT_LEN] {
+        self.public.compress().to_bytes()
+    }
+}
+
+/// Decode a serialized group element, rejecting non-canonical encodings and the identity.
+pub(super) fn deserialize_element(bytes: &[u8]) -> Option<RistrettoPoint> {
+    let compressed = CompressedRistretto::from_slice(bytes).ok()?;
+    let point = compressed.decompress()?;
+    if point == RistrettoPoint::identity() {
+        return None;
+    }
+    Some(point)
+}
+
+/// RFC 9497 `BlindEvaluate` plus `GenerateProof` for a single blinded element.
+pub(super) fn blind_evaluate(
+    key: &KeyPair,
+    blinded: &RistrettoPoint,
+) -> ([u8; ELEMENT_LEN], [u8; PROOF_LEN]) {
+    let evaluated = key.secret * blinded;
+    let proof_nonce = Scalar::from_bytes_mod_order_wide(&rand::random::<[u8; 64]>());
+    let proof = generate_proof(key, blinded, &evaluated, proof_nonce);
+    (evaluated.compress().to_bytes(), proof)
+}
+
+/// RFC 9497 `Evaluate`: the unblinded PRF output the client should have finalized to.
+pub(super) fn evaluate(key: &KeyPair, input: &[u8]) -> [u8; OUTPUT_LEN] {
+    let evaluated = key.secret * hash_to_group(input);
+    finalize_output(input, &evaluated)
+}
+
+fn generate_proof(
+    key: &KeyPair,
+    blinded: &RistrettoPoint,
+    evaluated: &RistrettoPoint,
+    nonce: Scalar,
+) -> [u8; PROOF_LEN] {
+    let (m, z) = compute_composites(&key.public, blinded, evaluated, Some(&key.secret));
+    let t2 = nonce * RISTRETTO_BASEPOINT_POINT;
+    let t3 = nonce * m;
+    let c = challenge_scalar(&key.public, &m, &z, &t2, &t3);
+    let s = nonce - c * key.secret;
+    let mut proof = [0u8; PROOF_LEN];
+    proof[..SCALAR_LEN].copy_from_slice(c.as_bytes());
+    proof[SCALAR_LEN..].copy_from_slice(s.as_bytes());
+    proof
+}
+
+fn compute_composites(
+    public: &RistrettoPoint,
+    blinded: &RistrettoPoint,
+    evaluated: &RistrettoPoint,
+    secret: Option<&Scalar>,
+) -> (RistrettoPoint, RistrettoPoint) {
+    let public_bytes = public.compress().to_bytes();
+    let seed_dst = domain_separation_tag(b"Seed-");
+    let mut seed_transcript = Vec::new();
+    push_length_prefixed(&mut seed_transcript, &public_bytes);
+    push_length_prefixed(&mut seed_transcript, seed_dst.as_slice());
+    let seed = Sha512::digest(seed_transcript.as_slice());
+
+    let mut composite_transcript = Vec::new();
+    push_length_prefixed(&mut composite_transcript, seed.as_slice());
+    composite_transcript.extend_from_slice(&0u16.to_be_bytes());
+    push_length_prefixed(&mut composite_transcript, &blinded.compress().to_bytes());
+    push_length_prefixed(&mut composite_transcript, &evaluated.compress().to_bytes());
+    composite_transcript.extend_from_slice(b"Composite");
+    let d = hash_to_scalar(
+        composite_transcript.as_slice(),
+        domain_separation_tag(b"HashToScalar-").as_slice(),
+    );
+
+    let m = d * blinded;
+    let z = match secret {
+        Some(secret) => secret * m,
+        None => d * evaluated,
+    };
+    (m, z)
+}
+
+fn challenge_scalar(
+    public: &RistrettoPoint,
+    m: &RistrettoPoint,
+    z: &RistrettoPoint,
+    t2: &RistrettoPoint,
+    t3: &RistrettoPoint,
+) -> Scalar {
+    let mut transcript = Vec::new();
+    for element in [public, m, z, t2, t3] {
+        push_length_prefixed(&mut transcript, &element.compress().to_bytes());
+    }
+    transcript.extend_from_slice(b"Challenge");
+    hash_to_scalar(
+        transcript.as_slice(),
+        domain_separation_tag(b"HashToScalar-").as_slice(),
+    )
+}
+
+fn finalize_output(input: &[u8], unblinded: &RistrettoPoint) -> [u8; OUTPUT_LEN] {
+    let mut transcript = Vec::new();
+    push_length_prefixed(&mut transcript, input);
+    push_length_prefixed(&mut transcript, &unblinded.compress().to_bytes());
+    transcript.extend_from_slice(b"Finalize");
+    let digest = Sha512::digest(transcript.as_slice());
+    let mut output = [0u8; OUTPUT_LEN];
+    output.copy_from_slice(digest.as_slice());
+    output
+}
+
+fn hash_to_group(input: &[u8]) -> RistrettoPoint {
+    let uniform = expand_message_xmd(input, domain_separation_tag(b"HashToGroup-").as_slice());
+    RistrettoPoint::from_uniform_bytes(&uniform)
+}
+
+fn hash_to_scalar(input: &[u8], dst: &[u8]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&expand_message_xmd(input, dst))
+}
+
+/// RFC 9380 `expand_message_xmd` with SHA-512, fixed to a 64-byte output (one hash block).
+fn expand_message_xmd(msg: &[u8], dst: &[u8]) -> [u8; 64] {
+    let dst_len = dst.len() as u8;
+    let b0 = Sha512::new()
+        .chain_update([0u8; SHA512_BLOCK_LEN])
+        .chain_update(msg)
+        .chain_update(64u16.to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update([dst_len])
+        .finalize();
+    let b1 = Sha512::new()
+        .chain_update(b0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update([dst_len])
+        .finalize();
+    let mut out = [0u8; 64];
+    out.copy_from_slice(b1.as_slice());
+    out
+}
+
+fn domain_separation_tag(prefix: &[u8]) -> Vec<u8> {
+    [prefix, CONTEXT_STRING].concat()
+}
+
+fn push_length_prefixed(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+pub(super) fn random_scalar() -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&rand::random::<[u8; 64]>())
+}
+
+#[cfg(test)]
+pub(super) fn blind(input: &[u8], blind: &Scalar) -> RistrettoPoint {
+    blind * hash_to_group(input)
+}
+
+/// Client-side `Finalize`: verifies the DLEQ proof, then unblinds the evaluated element.
+#[cfg(test)]
+pub(super) fn finalize(
+    input: &[u8],
+    blind: &Scalar,
+    blinded: &RistrettoPoint,
+    evaluated_bytes: &[u8],
+    public_key_bytes: &[u8],
+    proof: &[u8; PROOF_LEN],
+) -> Option<[u8; OUTPUT_LEN]> {
+    let evaluated = deserialize_element(evaluated_bytes)?;
+    let public = deserialize_element(public_key_bytes)?;
+    let c = Option::<Scalar>::from(Scalar::from_canonical_bytes(
+        proof[..SCALAR_LEN].try_into().ok()?,
+    ))?;
+    let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(
+        proof[SCALAR_LEN..].try_into().ok()?,
+    ))?;
+    let (m, z) = compute_composites(&public, blinded, &evaluated, None);
+    let t2 = s * RISTRETTO_BASEPOINT_POINT + c * public;
+    let t3 = s * m + c * z;
+    if challenge_scalar(&public, &m, &z, &t2, &t3) != c {
+        return None;
+    }
+    Some(finalize_output(input, &(blind.invert() * evaluated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn derive_key_pair_matches_rfc9497_voprf_test_vector() {
+        let key = KeyPair::derive(&[0xa3; 32], b"test key").expect("key derivation");
+        assert_eq!(
+            hex(key.secret.as_bytes()),
+            "e6f73f344b79b379f1a0dd37e07ff62e38d9f71345ce62ae3a9bc60b04ccd909"
+        );
+        assert_eq!(
+            hex(&key.public_key_bytes()),
+            "c803e2cc6b05fc15064549b5920659ca4a77b2cca6f04f6b357009335476ad4e"
+        );
+    }
+
+    #[test]
+    fn blind_evaluation_round_trip_matches_direct_evaluation_and_rejects_wrong_key() {
+        let key = KeyPair::derive(&[7u8; 32], b"round-trip").unwrap();
+        let other = KeyPair::derive(&[8u8; 32], b"round-trip").unwrap();
+        let input = b"privacy-pass-input";
+        let r = random_scalar();
+        let blinded = blind(input, &r);
+        let (evaluated, proof) = blind_evaluate(&key, &blinded);
+
+        let output = finalize(input, &r, &blinded, &evaluated, &key.public_key_bytes(), &proof)
+            .expect("proof verifies");
+        assert_eq!(output, evaluate(&key, input));
+        assert!(
+            finalize(input, &r, &blinded, &evaluated, &other.public_key_bytes(), &proof).is_none()
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! RFC 9577/9578 wire structures: `TokenChallenge`, `TokenRequest`, `TokenResponse`, `Token`,
+//! and the `PrivateToken` HTTP authentication scheme.
+
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+
+use super::voprf::{ELEMENT_LEN, OUTPUT_LEN, PROOF_LEN};
+
+/// Deployment-private token type for VOPRF(ristretto255, SHA-512).
+///
+/// This is not one of the RFC 9578 registered token types, so only Shuma-aware clients can
+/// request and redeem it; the issuance and redemption flows otherwise follow RFC 9578.
+pub(crate) const TOKEN_TYPE: u16 = 0xF91A;
+pub(super) const NONCE_LEN: usize = 32;
+const DIGEST_LEN: usize = 32;
+const TOKEN_KEY_ID_LEN: usize = 32;
+pub(super) const TOKEN_LEN: usize = 2 + NONCE_LEN + DIGEST_LEN + TOKEN_KEY_ID_LEN + OUTPUT_LEN;
+pub(super) const TOKEN_REQUEST_LEN: usize = 2 + 1 + ELEMENT_LEN;
+pub(super) const TOKEN_REQUEST_CONTENT_TYPE: &str = "application/private-token-request";
+pub(super) const TOKEN_RESPONSE_CONTENT_TYPE: &str = "application/private-token-response";
+pub(super) const ISSUER_DIRECTORY_CONTENT_TYPE: &str = "application/private-token-issuer-directory";
+const AUTH_SCHEME: &str = "PrivateToken";
+
+pub(super) struct TokenRequest {
+    pub truncated_token_key_id: u8,
+    pub blinded_msg: [u8; ELEMENT_LEN],
+}
+
+pub(super) struct Token {
+    pub nonce: [u8; NONCE_LEN],
+    pub challenge_digest: [u8; DIGEST_LEN],
+    pub token_key_id: [u8; TOKEN_KEY_ID_LEN],
+    pub authenticator: [u8; OUTPUT_LEN],
+}
+
+impl Token {
+    /// The VOPRF input the authenticator is computed over (RFC 9578 section 5.5).
+    pub(super) fn authenticator_input(&self) -> Vec<u8> {
+        let mut input = Vec::with_capacity(TOKEN_LEN - OUTPUT_LEN);
+        input.extend_from_slice(&TOKEN_TYPE.to_be_bytes());
+        input.extend_from_slice(&self.nonce);
+        input.extend_from_slice(&self.challenge_digest);
+        input.extend_from_slice(&self.token_key_id);
+        input
+    }
+
+    #[cfg(test)]
+    pub(super) fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.authenticator_input();
+        bytes.extend_from_slice(&self.authenticator);
+        bytes
+    }
+}
+
+pub(super) fn parse_token_request(bytes: &[u8]) -> Option<TokenRequest> {
+    if bytes.len() != TOKEN_REQUEST_LEN {
+        return None;
+    }
+    if u16::from_be_bytes([bytes[0], bytes[1]]) != TOKEN_TYPE {
+        return None;
+    }
+    let mut blinded_msg = [0u8; ELEMENT_LEN];
+    blinded_msg.copy_from_slice(&bytes[3..]);
+    Some(TokenRequest {
+        truncated_token_key_id: bytes[2],
+        blinded_msg,
+    })
+}
+
+pub(super) fn encode_token_response(
+    evaluated: &[u8; ELEMENT_LEN],
+    proof: &[u8; PROOF_LEN],
+) -> Vec<u8> {
+    let mut body = Vec::with_capacity(ELEMENT_LEN + PROOF_LEN);
+    body.extend_from_slice(evaluated);
+    body.extend_from_slice(proof);
+    body
+}
+
+pub(super) fn parse_token(bytes: &[u8]) -> Option<Token> {
+    if bytes.len() != TOKEN_LEN {
+        return None;
+    }
+    if u16::from_be_bytes([bytes[0], bytes[1]]) != TOKEN_TYPE {
+        return None;
+    }
+    let mut offset = 2;
+    let mut take = |len: usize| {
+        let slice = &bytes[offset..offset + len];
+        offset += len;
+        slice
+    };
+    Some(Token {
+        nonce: take(NONCE_LEN).try_into().ok()?,
+        challenge_digest: take(DIGEST_LEN).try_into().ok()?,
+        token_key_id: take(TOKEN_KEY_ID_LEN).try_into().ok()?,
+        authenticator: take(OUTPUT_LEN).try_into().ok()?,
+    })
+}
+
+/// Host the token challenge is scoped to; used as both issuer name and origin info.
+fn challenge_host(req: &spin_sdk::http::Request) -> String {
+    let host = req
+        .header("host")
+        .and_then(|value| value.as_str())
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if !name.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => {
+            name.to_string()
+        }
+        _ => host,
+    };
+    if host.is_empty() {
+        "localhost".to_string()
+    } else {
+        host
+    }
+}
+
+/// Serialized `TokenChallenge` with an empty redemption context, so tokens are not tied to
+/// any one visit and stay unlinkable across redemptions.
+pub(super) fn token_challenge(req: &spin_sdk::http::Request) -> Vec<u8> {
+    let host = challenge_host(req);
+    let host_bytes = &host.as_bytes()[..host.len().min(u16::MAX as usize)];
+    let host_len = host_bytes.len() as u16;
+    let mut challenge = Vec::with_capacity(2 + 2 + host_bytes.len() + 1 + 2 + host_bytes.len());
+    challenge.extend_from_slice(&TOKEN_TYPE.to_be_bytes());
+    challenge.extend_from_slice(&host_len.to_be_bytes());
+    challenge.extend_from_slice(host_bytes);
+    challenge.push(0);
+    challenge.extend_from_slice(&host_len.to_be_bytes());
+    challenge.extend_from_slice(host_bytes);
+    challenge
+}
+
+pub(super) fn token_challenge_digest(req: &spin_sdk::http::Request) -> [u8; DIGEST_LEN] {
+    let mut digest = [0u8; DIGEST_LEN];
+    digest.copy_from_slice(Sha256::digest(token_challenge(req)).as_slice());
+    digest
+}
+
+/// `WWW-Authenticate` value advertising a token challenge and the current issuer key.
+pub(super) fn www_authenticate_value(req: &spin_sdk::http::Request, public_key: &[u8]) -> String {
+    format!(
+        "{} challenge=\"{}\", token-key=\"{}\"",
+        AUTH_SCHEME,
+        general_purpose::URL_SAFE_NO_PAD.encode(token_challenge(req)),
+        general_purpose::URL_SAFE_NO_PAD.encode(public_key)
+    )
+}
+
+/// Extract and decode the `token` parameter of a `PrivateToken` authorization header.
+pub(super) fn token_from_authorization(header: &str) -> Option<Vec<u8>> {
+    let (scheme, params) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case(AUTH_SCHEME) {
+        return None;
+    }
+    params.split(',').find_map(|param| {
+        let (name, value) = param.trim().split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("token") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"');
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(value.trim_end_matches('='))
+            .ok()
+    })
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+
+const CONFIG_PROFILES_MAX: usize = 16;
+const CONFIG_PROFILE_WINDOWS_MAX: usize = 16;
+const CONFIG_PROFILE_WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+// Keys an overlay may never carry: profiles cannot schedule other profiles, and adversary-sim
+// lifecycle has its own control endpoint.
+const CONFIG_PROFILE_FORBIDDEN_OVERLAY_KEYS: &[&str] =
+    &["config_profiles", "adversary_sim_enabled"];
+
+/// When a profile is active. Times are UTC.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigProfileWindow {
+    /// Recurring `HH:MM`-`HH:MM` window on the listed weekdays (`mon`..`sun`, empty for every
+    /// day). An `end` before `start` runs past midnight into the following day.
+    Weekly {
+        #[serde(default)]
+        days: Vec<String>,
+        start: String,
+        end: String,
+    },
+    /// One-off window from `start_ts` (inclusive) to `end_ts` (exclusive), unix seconds.
+    Between { start_ts: u64, end_ts: u64 },
+}
+
+/// Named config overlay applied over the persisted config while one of its windows is open.
+/// `overlay` is a partial config object merged the same way as a config patch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ConfigProfile {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default = "default_config_profile_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub windows: Vec<ConfigProfileWindow>,
+    #[serde(default)]
+    pub overlay: serde_json::Map<String, serde_json::Value>,
+}
+
+fn default_config_profile_enabled() -> bool {
+    true
+}
+
+fn parse_minute_of_day(raw: &str) -> Option<u32> {
+    let (hours, minutes) = raw.trim().split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours = hours.parse::<u32>().ok()?;
+    let minutes = minutes.parse::<u32>().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+fn weekday_index(raw: &str) -> Option<u64> {
+    CONFIG_PROFILE_WEEKDAYS
+        .iter()
+        .position(|day| day.eq_ignore_ascii_case(raw.trim()))
+        .map(|index| index as u64)
+}
+
+fn weekly_day_listed(days: &[String], weekday: u64) -> bool {
+    days.is_empty() || days.iter().any(|day| weekday_index(day) == Some(weekday))
+}
+
+impl ConfigProfileWindow {
+    pub(crate) fn contains(&self, now: u64) -> bool {
+        match self {
+            ConfigProfileWindow::Between { start_ts, end_ts } => *start_ts <= now && now < *end_ts,
+            ConfigProfileWindow::Weekly { days, start, end } => {
+                let (Some(start), Some(end)) =
+                    (parse_minute_of_day(start), parse_minute_of_day(end))
+                else {
+                    return false;
+                };
+                // 1970-01-01 was a Thursday; index weekdays from Monday.
+                let weekday = (now / 86_400 + 3) % 7;
+                let previous_weekday = (weekday + 6) % 7;
+                let minute = ((now % 86_400) / 60) as u32;
+                if start < end {
+                    weekly_day_listed(days, weekday) && start <= minute && minute < end
+                } else {
+                    (weekly_day_listed(days, weekday) && minute >= start)
+                        || (weekly_day_listed(days, previous_weekday) && minute < end)
+                }
+            }
+        }
+    }
+
+    fn validate(&self, field: &str) -> Result<(), String> {
+        match self {
+            ConfigProfileWindow::Between { start_ts, end_ts } => {
+                if start_ts >= end_ts {
+                    return Err(format!("{field}.start_ts must be before end_ts"));
+                }
+            }
+            ConfigProfileWindow::Weekly { days, start, end } => {
+                for (index, day) in days.iter().enumerate() {
+                    if weekday_index(day).is_none() {
+                        return Err(format!(
+                            "{field}.days[{index}] must be one of mon, tue, wed, thu, fri, sat, sun"
+                        ));
+                    }
+                }
+                let start_minute = parse_minute_of_day(start)
+                    .ok_or_else(|| format!("{field}.start must be HH:MM"))?;
+                let end_minute =
+                    parse_minute_of_day(end).ok_or_else(|| format!("{field}.end must be HH:MM"))?;
+                if start_minute == end_minute {
+                    return Err(format!("{field}.start and end must differ"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigProfile {
+    pub(crate) fn active_at(&self, now: u64) -> bool {
+        self.enabled && self.windows.iter().any(|window| window.contains(now))
+    }
+}
+
+/// First enabled profile with an open window at `now`, in list order.
+pub(crate) fn active_config_profile(cfg: &Config, now: u64) -> Option<&ConfigProfile> {
+    cfg.config_profiles
+        .iter()
+        .find(|profile| profile.active_at(now))
+}
+
+/// `cfg` with `profile`'s overlay merged in. The profile list is carried over unchanged and the
+/// result records which profile produced it.
+pub(crate) fn apply_config_profile(
+    cfg: &Config,
+    profile: &ConfigProfile,
+) -> Result<Config, String> {
+    let mut base = cfg.clone();
+    let profiles = std::mem::take(&mut base.config_profiles);
+    let mut overlaid =
+        super::apply_persisted_patch(&base, &serde_json::Value::Object(profile.overlay.clone()))?;
+    overlaid.config_profiles = profiles;
+    overlaid.active_config_profile = Some(profile.id.clone());
+    Ok(overlaid)
+}
+
+/// Profiles are checked against the config they would overlay, so an overlay that would leave
+/// the runtime config invalid is refused at write time rather than skipped when its window opens.
+pub(crate) fn validate_config_profiles(cfg: &Config) -> Result<(), String> {
+    if cfg.config_profiles.len() > CONFIG_PROFILES_MAX {
+        return Err(format!(
+            "config_profiles supports at most {CONFIG_PROFILES_MAX} profiles"
+        ));
+    }
+    let mut base = cfg.clone();
+    base.config_profiles.clear();
+    let known_keys = match serde_json::to_value(&base) {
+        Ok(serde_json::Value::Object(object)) => object,
+        _ => return Err("Unable to serialize config for profile validation".to_string()),
+    };
+    let mut seen_ids = HashSet::new();
+    for (index, profile) in cfg.config_profiles.iter().enumerate() {
+        let field = format!("config_profiles[{index}]");
+        let id = profile.id.trim();
+        if id.is_empty() || id.len() > 64 {
+            return Err(format!("{field}.id must be 1-64 characters"));
+        }
+        if !seen_ids.insert(id) {
+            return Err(format!("{field}.id '{id}' is duplicated"));
+        }
+        if profile.windows.is_empty() || profile.windows.len() > CONFIG_PROFILE_WINDOWS_MAX {
+            return Err(format!(
+                "{field}.windows must list 1-{CONFIG_PROFILE_WINDOWS_MAX} windows"
+            ));
+        }
+        for (window_index, window) in profile.windows.iter().enumerate() {
+            window.validate(format!("{field}.windows[{window_index}]").as_str())?;
+        }
+        if profile.overlay.is_empty() {
+            return Err(format!(
+                "{field}.overlay must set at least one config field"
+            ));
+        }
+        for key in profile.overlay.keys() {
+            if CONFIG_PROFILE_FORBIDDEN_OVERLAY_KEYS.contains(&key.as_str())
+                || !known_keys.contains_key(key)
+            {
+                return Err(format!(
+                    "{field}.overlay.{key} is not an overlayable config field"
+                ));
+            }
+        }
+        apply_config_profile(&base, profile)
+            .map_err(|err| format!("{field}.overlay is invalid: {err}"))?;
+    }
+    Ok(())
+}
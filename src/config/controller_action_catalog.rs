@@ -267,6 +267,38 @@ const BOTNESS_CONSTRAINTS: &[AllowedActionValueConstraintDefinition] = &[
         allowed_values: &[],
         rule: None,
     },
+    AllowedActionValueConstraintDefinition {
+        path: "botness_weights.session_asset_ratio",
+        value_kind: "u8",
+        min_inclusive: Some(BOTNESS_WEIGHT_MIN as f64),
+        max_inclusive: Some(BOTNESS_WEIGHT_MAX as f64),
+        allowed_values: &[],
+        rule: None,
+    },
+    AllowedActionValueConstraintDefinition {
+        path: "botness_weights.session_traversal",
+        value_kind: "u8",
+        min_inclusive: Some(BOTNESS_WEIGHT_MIN as f64),
+        max_inclusive: Some(BOTNESS_WEIGHT_MAX as f64),
+        allowed_values: &[],
+        rule: None,
+    },
+    AllowedActionValueConstraintDefinition {
+        path: "botness_weights.session_timing",
+        value_kind: "u8",
+        min_inclusive: Some(BOTNESS_WEIGHT_MIN as f64),
+        max_inclusive: Some(BOTNESS_WEIGHT_MAX as f64),
+        allowed_values: &[],
+        rule: None,
+    },
+    AllowedActionValueConstraintDefinition {
+        path: "botness_weights.session_referer",
+        value_kind: "u8",
+        min_inclusive: Some(BOTNESS_WEIGHT_MIN as f64),
+        max_inclusive: Some(BOTNESS_WEIGHT_MAX as f64),
+        allowed_values: &[],
+        rule: None,
+    },
 ];
 
 const CDP_POLICY_CONSTRAINTS: &[AllowedActionValueConstraintDefinition] = &[
@@ -394,7 +426,7 @@ pub(super) const ALLOWED_ACTION_GROUP_DEFINITIONS: &[AllowedActionGroupDefinitio
         group_id: "shadow_mode.state",
         family: "shadow_mode",
         canary_requirement: "not_applicable",
-        patch_paths: &["shadow_mode"],
+        patch_paths: &["shadow_mode", "shadow_policy_sources"],
         targets: &[],
         value_constraints: &[],
         note: "Execution-mode switches must remain a deliberate manual operator decision.",
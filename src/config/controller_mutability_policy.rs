@@ -98,9 +98,23 @@ const ADMIN_CONFIG_MUTABILITY_GROUP_DEFINITIONS: &[ControllerMutabilityGroupDefi
         scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
         group_id: "shadow_mode.state",
         ring: ControllerMutabilityRing::Never,
-        paths: &["shadow_mode"],
+        paths: &["shadow_mode", "shadow_policy_sources"],
         note: "Execution-mode switches change the measurement harness and must remain permanently outside controller tuning.",
     },
+    ControllerMutabilityGroupDefinition {
+        scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
+        group_id: "request_facts_journal.sampling",
+        ring: ControllerMutabilityRing::Never,
+        paths: &["request_facts_journal_sample_percent"],
+        note: "Journal sampling decides how much pseudonymized live traffic is retained and stays an operator privacy decision.",
+    },
+    ControllerMutabilityGroupDefinition {
+        scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
+        group_id: "config_profiles",
+        ring: ControllerMutabilityRing::Never,
+        paths: &["config_profiles"],
+        note: "Scheduled overlays encode operator calendars and would let the loop stage changes outside its own watch windows.",
+    },
     ControllerMutabilityGroupDefinition {
         scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
         group_id: "adversary_sim.duration",
@@ -192,6 +206,29 @@ const ADMIN_CONFIG_MUTABILITY_GROUP_DEFINITIONS: &[ControllerMutabilityGroupDefi
         ],
         note: "IP-range policy carries high collateral-risk and must remain permanently controller-forbidden.",
     },
+    ControllerMutabilityGroupDefinition {
+        scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
+        group_id: "ip_range_suggestions.thresholds",
+        ring: ControllerMutabilityRing::ManualOnly,
+        paths: &[
+            "ip_range_suggestions_min_observations",
+            "ip_range_suggestions_min_bot_events",
+            "ip_range_suggestions_min_confidence_percent",
+            "ip_range_suggestions_low_collateral_percent",
+            "ip_range_suggestions_high_collateral_percent",
+            "ip_range_suggestions_ipv4_min_prefix_len",
+            "ip_range_suggestions_ipv6_min_prefix_len",
+            "ip_range_suggestions_likely_human_sample_percent",
+        ],
+        note: "Suggestion thresholds only shape operator recommendations, but they are not ratified controller moves.",
+    },
+    ControllerMutabilityGroupDefinition {
+        scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
+        group_id: "custom_rules",
+        ring: ControllerMutabilityRing::Never,
+        paths: &["custom_rules"],
+        note: "Operator-authored policy rules express site intent and must never be loop-mutable.",
+    },
     ControllerMutabilityGroupDefinition {
         scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
         group_id: "maze_core.rollout",
@@ -298,6 +335,13 @@ const ADMIN_CONFIG_MUTABILITY_GROUP_DEFINITIONS: &[ControllerMutabilityGroupDefi
         ],
         note: "Not-a-bot posture is bounded and directly tied to human-friction tradeoffs, so it belongs in the controller-tunable ring.",
     },
+    ControllerMutabilityGroupDefinition {
+        scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
+        group_id: "not_a_bot.accessible_questions",
+        ring: ControllerMutabilityRing::Never,
+        paths: &["not_a_bot_accessible_questions"],
+        note: "The accessible question bank is operator-written content, not a tunable threshold.",
+    },
     ControllerMutabilityGroupDefinition {
         scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
         group_id: "botness.thresholds",
@@ -310,6 +354,10 @@ const ADMIN_CONFIG_MUTABILITY_GROUP_DEFINITIONS: &[ControllerMutabilityGroupDefi
             "botness_weights.rate_medium",
             "botness_weights.rate_high",
             "botness_weights.maze_behavior",
+            "botness_weights.session_asset_ratio",
+            "botness_weights.session_traversal",
+            "botness_weights.session_timing",
+            "botness_weights.session_referer",
         ],
         note: "Botness thresholds and weights are bounded sensitivity controls and belong in the controller-tunable ring.",
     },
@@ -377,6 +425,13 @@ const ADMIN_CONFIG_MUTABILITY_GROUP_DEFINITIONS: &[ControllerMutabilityGroupDefi
         paths: &["fingerprint_pseudonymize"],
         note: "Fingerprint privacy posture changes observability policy and must remain controller-forbidden.",
     },
+    ControllerMutabilityGroupDefinition {
+        scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
+        group_id: "fingerprint_signal.transport_profiles",
+        ring: ControllerMutabilityRing::ManualOnly,
+        paths: &["fingerprint_transport_profiles"],
+        note: "Browser transport fingerprint profiles are operator-maintained reference data, not a tuning knob.",
+    },
     ControllerMutabilityGroupDefinition {
         scope: CONTROLLER_MUTABILITY_SCOPE_ADMIN_CONFIG,
         group_id: "provider_selection.backends",
@@ -406,6 +461,15 @@ const ADMIN_CONFIG_MUTABILITY_GROUP_DEFINITIONS: &[ControllerMutabilityGroupDefi
             "verified_identity.named_policies",
             "verified_identity.category_defaults",
             "verified_identity.service_profiles",
+            "verified_identity.restrict_requests_per_minute",
+            "verified_identity.restrict_denied_path_prefixes",
+            "verified_identity.mtls_enabled",
+            "verified_identity.mtls_ca_bundle",
+            "verified_identity.usage_quotas",
+            "verified_identity.licence_offers",
+            "verified_identity.licence_keys",
+            "verified_identity.delegation_keys",
+            "verified_identity.pinned_directories",
         ],
         note: "Verified-identity trust posture and authorization policy must remain permanently controller-forbidden.",
     },
@@ -2,7 +2,6 @@
 // Configuration and site settings for WASM Bot Defence.
 // Tunables are loaded from KV; defaults are defined in config/defaults.env.
 
-#[cfg(not(test))]
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{
     collections::{HashMap, HashSet},
@@ -16,6 +15,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::challenge::KeyValueStore;
 
+mod config_profiles;
 mod controller_action_catalog;
 mod controller_action_guardrails;
 mod controller_action_surface;
@@ -29,12 +29,16 @@ pub(crate) use controller_action_surface::{
     controller_action_family_risk_profile, controller_legal_move_ring_v1, AllowedActionsSurface,
     ControllerLegalMoveRingSurface,
 };
+pub(crate) use controller_mutability_policy::{
+    controller_mutability_ring_for_admin_config_path, ControllerMutabilityRing,
+};
 #[cfg(test)]
 pub(crate) use controller_mutability_policy::{
-    controller_mutability_policy_v1, controller_mutability_ring_for_admin_config_path,
-    controller_mutability_ring_for_operator_objectives_path, ControllerMutabilityRing,
+    controller_mutability_policy_v1, controller_mutability_ring_for_operator_objectives_path,
 };
 pub(crate) use runtime_env::{runtime_var_raw_optional, runtime_var_trimmed_optional};
+pub use config_profiles::ConfigProfile;
+pub(crate) use config_profiles::{active_config_profile, apply_config_profile};
 
 const DEFAULTS_ENV_TEXT: &str = include_str!("../../config/defaults.env");
 
@@ -127,6 +131,16 @@ const VERIFIED_IDENTITY_DIRECTORY_CACHE_TTL_SECONDS_MIN: u64 = 60;
 const VERIFIED_IDENTITY_DIRECTORY_CACHE_TTL_SECONDS_MAX: u64 = 86_400;
 const VERIFIED_IDENTITY_DIRECTORY_FRESHNESS_REQUIREMENT_SECONDS_MIN: u64 = 60;
 const VERIFIED_IDENTITY_DIRECTORY_FRESHNESS_REQUIREMENT_SECONDS_MAX: u64 = 604_800;
+const VERIFIED_IDENTITY_RESTRICT_REQUESTS_PER_MINUTE_MAX: u32 = 100_000;
+const VERIFIED_IDENTITY_USAGE_QUOTAS_MAX: usize = 64;
+const VERIFIED_IDENTITY_USAGE_QUOTA_ID_MAX_CHARS: usize = 64;
+const VERIFIED_IDENTITY_USAGE_QUOTA_MAX_REQUESTS_MAX: u64 = 1_000_000_000;
+const VERIFIED_IDENTITY_LICENCE_OFFERS_MAX: usize = 32;
+const VERIFIED_IDENTITY_LICENCE_KEYS_MAX: usize = 16;
+const VERIFIED_IDENTITY_LICENCE_ID_MAX_CHARS: usize = 64;
+const VERIFIED_IDENTITY_DELEGATION_KEYS_MAX: usize = 16;
+const VERIFIED_IDENTITY_DELEGATION_ISSUER_MAX_CHARS: usize = 256;
+const VERIFIED_IDENTITY_PINNED_DIRECTORIES_MAX: usize = 32;
 #[cfg(not(test))]
 const CONFIG_CACHE_TTL_SECONDS: u64 = 2;
 
@@ -162,6 +176,14 @@ pub struct BotnessWeights {
     pub rate_high: u8,
     #[serde(default = "default_botness_weight_maze_behavior")]
     pub maze_behavior: u8,
+    #[serde(default = "default_botness_weight_session_asset_ratio")]
+    pub session_asset_ratio: u8,
+    #[serde(default = "default_botness_weight_session_traversal")]
+    pub session_traversal: u8,
+    #[serde(default = "default_botness_weight_session_timing")]
+    pub session_timing: u8,
+    #[serde(default = "default_botness_weight_session_referer")]
+    pub session_referer: u8,
 }
 
 impl Default for BotnessWeights {
@@ -172,6 +194,10 @@ impl Default for BotnessWeights {
             rate_medium: default_botness_weight_rate_medium(),
             rate_high: default_botness_weight_rate_high(),
             maze_behavior: default_botness_weight_maze_behavior(),
+            session_asset_ratio: default_botness_weight_session_asset_ratio(),
+            session_traversal: default_botness_weight_session_traversal(),
+            session_timing: default_botness_weight_session_timing(),
+            session_referer: default_botness_weight_session_referer(),
         }
     }
 }
@@ -511,6 +537,85 @@ pub struct IpRangePolicyRule {
     pub redirect_url: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub custom_message: Option<String>,
+    #[serde(default)]
+    pub execution_mode: PolicyExecutionMode,
+}
+
+/// Whether a single policy source acts on its matches or only records what it would have done.
+/// Global `shadow_mode` still overrides every source.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyExecutionMode {
+    #[default]
+    Enforced,
+    Shadow,
+}
+
+/// Policy source families that `shadow_policy_sources` may name. Families marked `true` also
+/// accept a `family:<id>` entry that shadows a single rule or honeypot path.
+pub(crate) const SHADOWABLE_POLICY_SOURCES: &[(&str, bool)] = &[
+    ("ip_range", true),
+    ("honeypot", true),
+    ("custom_rule", true),
+    ("geo_block", false),
+    ("geo_maze", false),
+    ("geo_challenge", false),
+    ("botness_maze", false),
+    ("botness_not_a_bot", false),
+    ("botness_challenge", false),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomRuleAction {
+    Allow,
+    Block,
+    #[default]
+    Challenge,
+    NotABot,
+    JsChallenge,
+    Maze,
+}
+
+impl CustomRuleAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CustomRuleAction::Allow => "allow",
+            CustomRuleAction::Block => "block",
+            CustomRuleAction::Challenge => "challenge",
+            CustomRuleAction::NotABot => "not_a_bot",
+            CustomRuleAction::JsChallenge => "js_challenge",
+            CustomRuleAction::Maze => "maze",
+        }
+    }
+}
+
+/// Operator-defined rule evaluated against request facts ahead of the geo/botness/JS tranche.
+/// `when` is written in the expression language compiled by `runtime::custom_rules`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct CustomPolicyRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub when: String,
+    #[serde(default)]
+    pub action: CustomRuleAction,
+    #[serde(default)]
+    pub execution_mode: PolicyExecutionMode,
+}
+
+/// Operator-written question for the accessible Not-a-Bot alternative.
+/// Any entry in `answers` is accepted; matching ignores case, outer punctuation and spacing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct NotABotAccessibleQuestion {
+    #[serde(default)]
+    pub question: String,
+    #[serde(default)]
+    pub answers: Vec<String>,
 }
 
 /// Per-capability provider backend selections.
@@ -562,6 +667,24 @@ pub struct VerifiedIdentityConfig {
     pub category_defaults: Vec<crate::bot_identity::policy::IdentityCategoryDefaultAction>,
     #[serde(default = "default_verified_identity_service_profiles")]
     pub service_profiles: Vec<crate::bot_identity::policy::IdentityServiceProfileBinding>,
+    #[serde(default = "default_verified_identity_restrict_requests_per_minute")]
+    pub restrict_requests_per_minute: u32,
+    #[serde(default = "default_verified_identity_restrict_denied_path_prefixes")]
+    pub restrict_denied_path_prefixes: Vec<String>,
+    #[serde(default = "default_verified_identity_mtls_enabled")]
+    pub mtls_enabled: bool,
+    #[serde(default = "default_verified_identity_mtls_ca_bundle")]
+    pub mtls_ca_bundle: Vec<String>,
+    #[serde(default = "default_verified_identity_usage_quotas")]
+    pub usage_quotas: Vec<crate::bot_identity::policy::IdentityUsageQuota>,
+    #[serde(default = "default_verified_identity_licence_offers")]
+    pub licence_offers: Vec<crate::bot_identity::policy::IdentityLicenceOffer>,
+    #[serde(default = "default_verified_identity_licence_keys")]
+    pub licence_keys: Vec<crate::bot_identity::policy::IdentityLicenceKey>,
+    #[serde(default = "default_verified_identity_delegation_keys")]
+    pub delegation_keys: Vec<crate::bot_identity::policy::IdentityDelegationKey>,
+    #[serde(default = "default_verified_identity_pinned_directories")]
+    pub pinned_directories: Vec<crate::bot_identity::policy::IdentityPinnedDirectory>,
 }
 
 impl Default for VerifiedIdentityConfig {
@@ -578,6 +701,15 @@ impl Default for VerifiedIdentityConfig {
             named_policies: default_verified_identity_named_policies(),
             category_defaults: default_verified_identity_category_defaults(),
             service_profiles: default_verified_identity_service_profiles(),
+            restrict_requests_per_minute: default_verified_identity_restrict_requests_per_minute(),
+            restrict_denied_path_prefixes: default_verified_identity_restrict_denied_path_prefixes(),
+            mtls_enabled: default_verified_identity_mtls_enabled(),
+            mtls_ca_bundle: default_verified_identity_mtls_ca_bundle(),
+            usage_quotas: default_verified_identity_usage_quotas(),
+            licence_offers: default_verified_identity_licence_offers(),
+            licence_keys: default_verified_identity_licence_keys(),
+            delegation_keys: default_verified_identity_delegation_keys(),
+            pinned_directories: default_verified_identity_pinned_directories(),
         }
     }
 }
@@ -706,6 +838,8 @@ pub struct Config {
     pub ip_range_emergency_allowlist: Vec<String>,
     #[serde(default = "default_ip_range_custom_rules")]
     pub ip_range_custom_rules: Vec<IpRangePolicyRule>,
+    #[serde(default = "default_custom_rules")]
+    pub custom_rules: Vec<CustomPolicyRule>,
     #[serde(default = "default_ip_range_suggestions_min_observations")]
     pub ip_range_suggestions_min_observations: u32,
     #[serde(default = "default_ip_range_suggestions_min_bot_events")]
@@ -724,6 +858,15 @@ pub struct Config {
     pub ip_range_suggestions_likely_human_sample_percent: u8,
     #[serde(default = "default_shadow_mode")]
     pub shadow_mode: bool,
+    #[serde(default = "default_shadow_policy_sources")]
+    pub shadow_policy_sources: Vec<String>,
+    #[serde(default = "default_request_facts_journal_sample_percent")]
+    pub request_facts_journal_sample_percent: u8,
+    #[serde(default = "default_config_profiles")]
+    pub config_profiles: Vec<ConfigProfile>,
+    /// Scheduled profile whose overlay produced this runtime config; never persisted.
+    #[serde(skip)]
+    pub active_config_profile: Option<String>,
     #[serde(default = "default_adversary_sim_enabled")]
     pub adversary_sim_enabled: bool,
     #[serde(default = "default_adversary_sim_duration_seconds")]
@@ -870,6 +1013,8 @@ pub struct Config {
     pub fingerprint_family_cap_persistence: u8,
     #[serde(default = "default_fingerprint_family_cap_behavior")]
     pub fingerprint_family_cap_behavior: u8,
+    #[serde(default = "default_fingerprint_transport_profiles")]
+    pub fingerprint_transport_profiles: Vec<crate::signals::fingerprint::TransportFingerprintProfile>,
     #[serde(default = "default_js_required_enforced")]
     pub js_required_enforced: bool,
     #[serde(default = "default_pow_enabled")]
@@ -906,6 +1051,8 @@ pub struct Config {
     pub not_a_bot_attempt_limit_per_window: u32,
     #[serde(default = "default_not_a_bot_attempt_window_seconds")]
     pub not_a_bot_attempt_window_seconds: u64,
+    #[serde(default = "default_not_a_bot_accessible_questions")]
+    pub not_a_bot_accessible_questions: Vec<NotABotAccessibleQuestion>,
     #[serde(default = "default_maze_threshold")]
     pub botness_maze_threshold: u8,
     #[serde(default)]
@@ -924,6 +1071,7 @@ pub struct Config {
 struct CachedConfig {
     loaded_at: u64,
     config: Config,
+    scheduled: Option<Config>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -976,6 +1124,25 @@ impl Config {
         self.js_required_enforced && self.defence_modes.js.action_enabled()
     }
 
+    /// Resolves a per-source execution mode. `source_id` is a family (`geo_block`) or a scoped
+    /// id (`ip_range:dc_block`); listing the bare family shadows every scoped id under it.
+    pub fn policy_source_execution_mode(
+        &self,
+        source_id: &str,
+        declared: PolicyExecutionMode,
+    ) -> PolicyExecutionMode {
+        let family = source_id.split_once(':').map_or(source_id, |(family, _)| family);
+        let listed = self
+            .shadow_policy_sources
+            .iter()
+            .any(|entry| entry == source_id || entry == family);
+        if listed {
+            PolicyExecutionMode::Shadow
+        } else {
+            declared
+        }
+    }
+
     pub fn defence_mode_warnings(&self) -> Vec<String> {
         let mut warnings = Vec::new();
         if !self.js_required_enforced && self.defence_modes.js != ComposabilityMode::Off {
@@ -1142,13 +1309,82 @@ fn merge_json_value(target: &mut serde_json::Value, patch: &serde_json::Value) -
 }
 
 pub(crate) fn validate_persisted_config(cfg: &Config) -> Result<(), String> {
-    validate_verified_identity_config(&cfg.verified_identity)
+    validate_verified_identity_config(&cfg.verified_identity)?;
+    crate::signals::fingerprint::validate_transport_profiles(&cfg.fingerprint_transport_profiles)?;
+    validate_shadow_policy_sources(&cfg.shadow_policy_sources)?;
+    crate::runtime::custom_rules::validate_custom_rules(&cfg.custom_rules)?;
+    validate_not_a_bot_accessible_questions(&cfg.not_a_bot_accessible_questions)?;
+    config_profiles::validate_config_profiles(cfg)
+}
+
+const MAX_NOT_A_BOT_ACCESSIBLE_QUESTIONS: usize = 200;
+const MAX_NOT_A_BOT_ACCESSIBLE_QUESTION_CHARS: usize = 300;
+const MAX_NOT_A_BOT_ACCESSIBLE_ANSWERS: usize = 8;
+const MAX_NOT_A_BOT_ACCESSIBLE_ANSWER_CHARS: usize = 64;
+
+pub(crate) fn validate_not_a_bot_accessible_questions(
+    questions: &[NotABotAccessibleQuestion],
+) -> Result<(), String> {
+    if questions.len() > MAX_NOT_A_BOT_ACCESSIBLE_QUESTIONS {
+        return Err(format!(
+            "not_a_bot_accessible_questions supports at most {} questions",
+            MAX_NOT_A_BOT_ACCESSIBLE_QUESTIONS
+        ));
+    }
+    for (index, entry) in questions.iter().enumerate() {
+        let question_chars = entry.question.trim().chars().count();
+        if question_chars == 0 || question_chars > MAX_NOT_A_BOT_ACCESSIBLE_QUESTION_CHARS {
+            return Err(format!(
+                "not_a_bot_accessible_questions[{}].question must be 1-{} characters",
+                index, MAX_NOT_A_BOT_ACCESSIBLE_QUESTION_CHARS
+            ));
+        }
+        if entry.answers.is_empty() || entry.answers.len() > MAX_NOT_A_BOT_ACCESSIBLE_ANSWERS {
+            return Err(format!(
+                "not_a_bot_accessible_questions[{}].answers must list 1-{} answers",
+                index, MAX_NOT_A_BOT_ACCESSIBLE_ANSWERS
+            ));
+        }
+        for (answer_index, answer) in entry.answers.iter().enumerate() {
+            let answer_chars = answer.trim().chars().count();
+            if answer_chars == 0 || answer_chars > MAX_NOT_A_BOT_ACCESSIBLE_ANSWER_CHARS {
+                return Err(format!(
+                    "not_a_bot_accessible_questions[{}].answers[{}] must be 1-{} characters",
+                    index, answer_index, MAX_NOT_A_BOT_ACCESSIBLE_ANSWER_CHARS
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_shadow_policy_sources(sources: &[String]) -> Result<(), String> {
+    for (index, source) in sources.iter().enumerate() {
+        let (family, id) = match source.split_once(':') {
+            Some((family, id)) => (family, Some(id)),
+            None => (source.as_str(), None),
+        };
+        let known = SHADOWABLE_POLICY_SOURCES
+            .iter()
+            .any(|(name, scoped)| *name == family && (id.is_none() || *scoped));
+        if !known || id.is_some_and(|id| id.trim().is_empty()) {
+            return Err(format!(
+                "shadow_policy_sources[{}] '{}' is not a shadowable policy source",
+                index, source
+            ));
+        }
+    }
+    Ok(())
 }
 
 fn validate_verified_identity_config(cfg: &VerifiedIdentityConfig) -> Result<(), String> {
-    if cfg.enabled && !cfg.native_web_bot_auth_enabled && !cfg.provider_assertions_enabled {
+    if cfg.enabled
+        && !cfg.native_web_bot_auth_enabled
+        && !cfg.provider_assertions_enabled
+        && !cfg.mtls_enabled
+    {
         return Err(
-            "verified_identity.enabled=true requires at least one verifier path: native_web_bot_auth_enabled or provider_assertions_enabled"
+            "verified_identity.enabled=true requires at least one verifier path: native_web_bot_auth_enabled, provider_assertions_enabled, or mtls_enabled"
                 .to_string(),
         );
     }
@@ -1194,6 +1430,208 @@ fn validate_verified_identity_config(cfg: &VerifiedIdentityConfig) -> Result<(),
             VERIFIED_IDENTITY_DIRECTORY_FRESHNESS_REQUIREMENT_SECONDS_MAX
         ));
     }
+    if cfg.restrict_requests_per_minute > VERIFIED_IDENTITY_RESTRICT_REQUESTS_PER_MINUTE_MAX {
+        return Err(format!(
+            "verified_identity.restrict_requests_per_minute out of range (0-{})",
+            VERIFIED_IDENTITY_RESTRICT_REQUESTS_PER_MINUTE_MAX
+        ));
+    }
+    for (index, prefix) in cfg.restrict_denied_path_prefixes.iter().enumerate() {
+        if !prefix.starts_with('/') {
+            return Err(format!(
+                "verified_identity.restrict_denied_path_prefixes[{}] must start with /",
+                index
+            ));
+        }
+    }
+    if let Err(err) = crate::bot_identity::mtls::parse_ca_bundle(&cfg.mtls_ca_bundle) {
+        return Err(format!("verified_identity.mtls_ca_bundle {}", err));
+    }
+    if cfg.mtls_enabled && cfg.mtls_ca_bundle.is_empty() {
+        return Err(
+            "verified_identity.mtls_ca_bundle must not be empty when mtls_enabled is true"
+                .to_string(),
+        );
+    }
+    if cfg.usage_quotas.len() > VERIFIED_IDENTITY_USAGE_QUOTAS_MAX {
+        return Err(format!(
+            "verified_identity.usage_quotas must not contain more than {} entries",
+            VERIFIED_IDENTITY_USAGE_QUOTAS_MAX
+        ));
+    }
+    let mut quota_ids = HashSet::new();
+    for (index, quota) in cfg.usage_quotas.iter().enumerate() {
+        let quota_id = quota.quota_id.as_str();
+        if quota_id.is_empty()
+            || quota_id.len() > VERIFIED_IDENTITY_USAGE_QUOTA_ID_MAX_CHARS
+            || !quota_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(format!(
+                "verified_identity.usage_quotas[{}].quota_id must be 1-{} characters of [A-Za-z0-9._-]",
+                index, VERIFIED_IDENTITY_USAGE_QUOTA_ID_MAX_CHARS
+            ));
+        }
+        if !quota_ids.insert(quota_id.to_string()) {
+            return Err(format!(
+                "verified_identity.usage_quotas[{}].quota_id duplicates {}",
+                index, quota_id
+            ));
+        }
+        validate_verified_identity_policy_matcher(
+            format!("verified_identity.usage_quotas[{}].matcher", index).as_str(),
+            &quota.matcher,
+        )?;
+        if quota.max_requests == 0
+            || quota.max_requests > VERIFIED_IDENTITY_USAGE_QUOTA_MAX_REQUESTS_MAX
+        {
+            return Err(format!(
+                "verified_identity.usage_quotas[{}].max_requests out of range (1-{})",
+                index, VERIFIED_IDENTITY_USAGE_QUOTA_MAX_REQUESTS_MAX
+            ));
+        }
+    }
+
+    if cfg.licence_offers.len() > VERIFIED_IDENTITY_LICENCE_OFFERS_MAX {
+        return Err(format!(
+            "verified_identity.licence_offers must not contain more than {} entries",
+            VERIFIED_IDENTITY_LICENCE_OFFERS_MAX
+        ));
+    }
+    let mut offer_ids = HashSet::new();
+    for (index, offer) in cfg.licence_offers.iter().enumerate() {
+        if !is_verified_identity_licence_id(offer.offer_id.as_str()) {
+            return Err(format!(
+                "verified_identity.licence_offers[{}].offer_id must be 1-{} characters of [A-Za-z0-9._-]",
+                index, VERIFIED_IDENTITY_LICENCE_ID_MAX_CHARS
+            ));
+        }
+        if !offer_ids.insert(offer.offer_id.clone()) {
+            return Err(format!(
+                "verified_identity.licence_offers[{}].offer_id duplicates {}",
+                index, offer.offer_id
+            ));
+        }
+        if !is_decimal_price(offer.price.as_str()) {
+            return Err(format!(
+                "verified_identity.licence_offers[{}].price must be a non-negative decimal amount",
+                index
+            ));
+        }
+        if offer.currency.len() != 3 || !offer.currency.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(format!(
+                "verified_identity.licence_offers[{}].currency must be an ISO 4217 code",
+                index
+            ));
+        }
+        if !offer.terms_url.starts_with("https://")
+            || offer.terms_url.len() > 512
+            || offer
+                .terms_url
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | '"'))
+        {
+            return Err(format!(
+                "verified_identity.licence_offers[{}].terms_url must be an https URL",
+                index
+            ));
+        }
+    }
+    if cfg.licence_keys.len() > VERIFIED_IDENTITY_LICENCE_KEYS_MAX {
+        return Err(format!(
+            "verified_identity.licence_keys must not contain more than {} entries",
+            VERIFIED_IDENTITY_LICENCE_KEYS_MAX
+        ));
+    }
+    let mut key_ids = HashSet::new();
+    for (index, key) in cfg.licence_keys.iter().enumerate() {
+        if !is_verified_identity_licence_id(key.key_id.as_str()) {
+            return Err(format!(
+                "verified_identity.licence_keys[{}].key_id must be 1-{} characters of [A-Za-z0-9._-]",
+                index, VERIFIED_IDENTITY_LICENCE_ID_MAX_CHARS
+            ));
+        }
+        if !key_ids.insert(key.key_id.clone()) {
+            return Err(format!(
+                "verified_identity.licence_keys[{}].key_id duplicates {}",
+                index, key.key_id
+            ));
+        }
+        let decoded = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            key.public_key.trim(),
+        );
+        if !decoded.is_ok_and(|bytes| bytes.len() == 32) {
+            return Err(format!(
+                "verified_identity.licence_keys[{}].public_key must be a base64 Ed25519 public key",
+                index
+            ));
+        }
+    }
+    if cfg.delegation_keys.len() > VERIFIED_IDENTITY_DELEGATION_KEYS_MAX {
+        return Err(format!(
+            "verified_identity.delegation_keys must not contain more than {} entries",
+            VERIFIED_IDENTITY_DELEGATION_KEYS_MAX
+        ));
+    }
+    let mut delegation_key_ids = HashSet::new();
+    for (index, key) in cfg.delegation_keys.iter().enumerate() {
+        if !is_verified_identity_licence_id(key.key_id.as_str()) {
+            return Err(format!(
+                "verified_identity.delegation_keys[{}].key_id must be 1-{} characters of [A-Za-z0-9._-]",
+                index, VERIFIED_IDENTITY_LICENCE_ID_MAX_CHARS
+            ));
+        }
+        if !delegation_key_ids.insert(key.key_id.clone()) {
+            return Err(format!(
+                "verified_identity.delegation_keys[{}].key_id duplicates {}",
+                index, key.key_id
+            ));
+        }
+        if key.issuer.is_empty()
+            || key.issuer.len() > VERIFIED_IDENTITY_DELEGATION_ISSUER_MAX_CHARS
+            || key.issuer.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(format!(
+                "verified_identity.delegation_keys[{}].issuer must be 1-{} characters without whitespace",
+                index, VERIFIED_IDENTITY_DELEGATION_ISSUER_MAX_CHARS
+            ));
+        }
+        let decoded = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            key.public_key.trim(),
+        );
+        if !decoded.is_ok_and(|bytes| bytes.len() == 32) {
+            return Err(format!(
+                "verified_identity.delegation_keys[{}].public_key must be a base64 Ed25519 public key",
+                index
+            ));
+        }
+    }
+    if cfg.pinned_directories.len() > VERIFIED_IDENTITY_PINNED_DIRECTORIES_MAX {
+        return Err(format!(
+            "verified_identity.pinned_directories must not contain more than {} entries",
+            VERIFIED_IDENTITY_PINNED_DIRECTORIES_MAX
+        ));
+    }
+    let mut pinned_source_uris = HashSet::new();
+    for (index, directory) in cfg.pinned_directories.iter().enumerate() {
+        if let Err(err) =
+            crate::bot_identity::native_http_message_signatures::validate_pinned_directory(directory)
+        {
+            return Err(format!(
+                "verified_identity.pinned_directories[{}].{}",
+                index, err
+            ));
+        }
+        if !pinned_source_uris.insert(directory.source_uri.clone()) {
+            return Err(format!(
+                "verified_identity.pinned_directories[{}].source_uri duplicates {}",
+                index, directory.source_uri
+            ));
+        }
+    }
 
     let mut profile_ids = HashSet::new();
     for (index, profile) in cfg.service_profiles.iter().enumerate() {
@@ -1225,6 +1663,8 @@ fn validate_verified_identity_config(cfg: &VerifiedIdentityConfig) -> Result<(),
             format!("verified_identity.category_defaults[{}].action", index).as_str(),
             &category_default.action,
             &profile_ids,
+            &offer_ids,
+            !cfg.licence_keys.is_empty(),
         )?;
     }
 
@@ -1251,6 +1691,8 @@ fn validate_verified_identity_config(cfg: &VerifiedIdentityConfig) -> Result<(),
             format!("verified_identity.named_policies[{}].action", index).as_str(),
             &policy.action,
             &profile_ids,
+            &offer_ids,
+            !cfg.licence_keys.is_empty(),
         )?;
     }
 
@@ -1300,7 +1742,24 @@ fn validate_verified_identity_policy_action(
     field: &str,
     action: &crate::bot_identity::policy::IdentityPolicyAction,
     profile_ids: &HashSet<String>,
+    offer_ids: &HashSet<String>,
+    licence_keys_configured: bool,
 ) -> Result<(), String> {
+    if let Some(offer_id) = action.referenced_licence_offer_id() {
+        if !offer_ids.contains(offer_id) {
+            return Err(format!(
+                "{} references unknown licence offer {}",
+                field, offer_id
+            ));
+        }
+        if !licence_keys_configured {
+            return Err(format!(
+                "{} requires at least one verified_identity.licence_keys entry",
+                field
+            ));
+        }
+        return Ok(());
+    }
     let Some(profile_id) = action.referenced_service_profile_id() else {
         return Ok(());
     };
@@ -1317,6 +1776,22 @@ fn validate_verified_identity_policy_action(
     Ok(())
 }
 
+fn is_verified_identity_licence_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= VERIFIED_IDENTITY_LICENCE_ID_MAX_CHARS
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn is_decimal_price(value: &str) -> bool {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    (1..=12).contains(&whole.len())
+        && (1..=8).contains(&fraction.len())
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+}
+
 static RUNTIME_CONFIG_CACHE: Lazy<Mutex<HashMap<String, CachedConfig>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 static RUNTIME_EPHEMERAL_FLAGS: Lazy<Mutex<HashMap<String, RuntimeEphemeralFlags>>> =
@@ -1349,7 +1824,6 @@ pub fn apply_runtime_ephemeral_overrides(site_id: &str, cfg: &mut Config) {
     }
 }
 
-#[cfg(not(test))]
 fn now_ts() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -1364,38 +1838,67 @@ fn load_cached_with_now(
     ttl_seconds: u64,
 ) -> Result<Config, ConfigLoadError> {
     {
-        let cache = RUNTIME_CONFIG_CACHE.lock().unwrap();
-        if let Some(entry) = cache.get(site_id) {
+        let mut cache = RUNTIME_CONFIG_CACHE.lock().unwrap();
+        if let Some(entry) = cache.get_mut(site_id) {
             let age = now.saturating_sub(entry.loaded_at);
             if age <= ttl_seconds {
-                let mut effective = entry.config.clone();
+                let mut effective = cached_scheduled_config(site_id, entry, now);
                 apply_runtime_ephemeral_overrides(site_id, &mut effective);
                 return Ok(effective);
             }
         }
     }
 
-    let config = Config::load(store, site_id)?;
+    let mut entry = CachedConfig {
+        loaded_at: now,
+        config: Config::load(store, site_id)?,
+        scheduled: None,
+    };
+    let mut effective = cached_scheduled_config(site_id, &mut entry, now);
     let mut cache = RUNTIME_CONFIG_CACHE.lock().unwrap();
-    cache.insert(
-        site_id.to_string(),
-        CachedConfig {
-            loaded_at: now,
-            config: config.clone(),
-        },
-    );
-    let mut effective = config;
+    cache.insert(site_id.to_string(), entry);
     apply_runtime_ephemeral_overrides(site_id, &mut effective);
     Ok(effective)
 }
 
+/// Persisted config with the scheduled profile active at `now` merged in. A profile that no
+/// longer applies cleanly is logged and skipped so the base config keeps serving.
+pub(crate) fn apply_scheduled_config_profile(site_id: &str, cfg: Config, now: u64) -> Config {
+    let Some(profile) = active_config_profile(&cfg, now) else {
+        return cfg;
+    };
+    match apply_config_profile(&cfg, profile) {
+        Ok(scheduled) => scheduled,
+        Err(err) => {
+            eprintln!(
+                "[config] skipping config profile {} for site {}: {}",
+                profile.id, site_id, err
+            );
+            cfg
+        }
+    }
+}
+
+fn cached_scheduled_config(site_id: &str, entry: &mut CachedConfig, now: u64) -> Config {
+    let active_id = active_config_profile(&entry.config, now).map(|profile| profile.id.as_str());
+    if let Some(scheduled) = entry.scheduled.as_ref() {
+        if scheduled.active_config_profile.as_deref() == active_id {
+            return scheduled.clone();
+        }
+    }
+    let scheduled = apply_scheduled_config_profile(site_id, entry.config.clone(), now);
+    entry.scheduled = Some(scheduled.clone());
+    scheduled
+}
+
 pub fn load_runtime_cached(
     store: &impl KeyValueStore,
     site_id: &str,
 ) -> Result<Config, ConfigLoadError> {
     #[cfg(test)]
     {
-        let mut cfg = Config::load(store, site_id)?;
+        let mut cfg =
+            apply_scheduled_config_profile(site_id, Config::load(store, site_id)?, now_ts());
         apply_runtime_ephemeral_overrides(site_id, &mut cfg);
         return Ok(cfg);
     }
@@ -1465,6 +1968,7 @@ static DEFAULT_CONFIG: Lazy<Config> = Lazy::new(|| {
         ip_range_policy_mode: default_ip_range_policy_mode(),
         ip_range_emergency_allowlist: defaults_string_list("SHUMA_IP_RANGE_EMERGENCY_ALLOWLIST"),
         ip_range_custom_rules: defaults_json("SHUMA_IP_RANGE_CUSTOM_RULES"),
+        custom_rules: defaults_json("SHUMA_CUSTOM_RULES"),
         ip_range_suggestions_min_observations: default_ip_range_suggestions_min_observations(),
         ip_range_suggestions_min_bot_events: default_ip_range_suggestions_min_bot_events(),
         ip_range_suggestions_min_confidence_percent:
@@ -1480,6 +1984,10 @@ static DEFAULT_CONFIG: Lazy<Config> = Lazy::new(|| {
         ip_range_suggestions_likely_human_sample_percent:
             default_ip_range_suggestions_likely_human_sample_percent(),
         shadow_mode: defaults_bool("SHUMA_SHADOW_MODE"),
+        shadow_policy_sources: defaults_string_list("SHUMA_SHADOW_POLICY_SOURCES"),
+        request_facts_journal_sample_percent: default_request_facts_journal_sample_percent(),
+        config_profiles: defaults_json("SHUMA_CONFIG_PROFILES"),
+        active_config_profile: None,
         adversary_sim_enabled: defaults_bool("SHUMA_ADVERSARY_SIM_ENABLED"),
         adversary_sim_duration_seconds: default_adversary_sim_duration_seconds(),
         maze_enabled: defaults_bool("SHUMA_MAZE_ENABLED"),
@@ -1573,6 +2081,7 @@ static DEFAULT_CONFIG: Lazy<Config> = Lazy::new(|| {
             "SHUMA_FINGERPRINT_FAMILY_CAP_PERSISTENCE",
         ),
         fingerprint_family_cap_behavior: defaults_u8("SHUMA_FINGERPRINT_FAMILY_CAP_BEHAVIOR"),
+        fingerprint_transport_profiles: default_fingerprint_transport_profiles(),
         js_required_enforced: defaults_bool("SHUMA_JS_REQUIRED_ENFORCED"),
         pow_enabled: defaults_bool("SHUMA_POW_ENABLED"),
         pow_difficulty: defaults_u8("SHUMA_POW_DIFFICULTY"),
@@ -1595,6 +2104,7 @@ static DEFAULT_CONFIG: Lazy<Config> = Lazy::new(|| {
         not_a_bot_marker_ttl_seconds: defaults_u64("SHUMA_NOT_A_BOT_MARKER_TTL_SECONDS"),
         not_a_bot_attempt_limit_per_window: defaults_u32("SHUMA_NOT_A_BOT_ATTEMPT_LIMIT_PER_WINDOW"),
         not_a_bot_attempt_window_seconds: defaults_u64("SHUMA_NOT_A_BOT_ATTEMPT_WINDOW_SECONDS"),
+        not_a_bot_accessible_questions: default_not_a_bot_accessible_questions(),
         botness_maze_threshold: defaults_u8("SHUMA_BOTNESS_MAZE_THRESHOLD"),
         botness_weights: BotnessWeights {
             js_required: defaults_u8("SHUMA_BOTNESS_WEIGHT_JS_REQUIRED"),
@@ -1602,6 +2112,10 @@ static DEFAULT_CONFIG: Lazy<Config> = Lazy::new(|| {
             rate_medium: defaults_u8("SHUMA_BOTNESS_WEIGHT_RATE_MEDIUM"),
             rate_high: defaults_u8("SHUMA_BOTNESS_WEIGHT_RATE_HIGH"),
             maze_behavior: defaults_u8("SHUMA_BOTNESS_WEIGHT_MAZE_BEHAVIOR"),
+            session_asset_ratio: defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_ASSET_RATIO"),
+            session_traversal: defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_TRAVERSAL"),
+            session_timing: defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_TIMING"),
+            session_referer: defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_REFERER"),
         },
         defence_modes: DefenceModes::default(),
         provider_backends: ProviderBackends::default(),
@@ -1685,6 +2199,7 @@ fn validate_env_only_impl() -> Result<(), String> {
     validate_optional_rate_limiter_outage_mode_var("SHUMA_RATE_LIMITER_OUTAGE_MODE_MAIN")?;
     validate_optional_rate_limiter_outage_mode_var("SHUMA_RATE_LIMITER_OUTAGE_MODE_ADMIN_AUTH")?;
     validate_optional_ban_store_outage_mode_var("SHUMA_BAN_STORE_OUTAGE_MODE")?;
+    crate::observability::event_export::validate_syslog_target_env()?;
     validate_gateway_contract_env()?;
 
     Ok(())
@@ -3205,6 +3720,10 @@ fn clamp_config_values(cfg: &mut Config) {
     cfg.botness_weights.rate_medium = clamp_botness_weight(cfg.botness_weights.rate_medium);
     cfg.botness_weights.rate_high = clamp_botness_weight(cfg.botness_weights.rate_high);
     cfg.botness_weights.maze_behavior = clamp_botness_weight(cfg.botness_weights.maze_behavior);
+    cfg.botness_weights.session_asset_ratio = clamp_botness_weight(cfg.botness_weights.session_asset_ratio);
+    cfg.botness_weights.session_traversal = clamp_botness_weight(cfg.botness_weights.session_traversal);
+    cfg.botness_weights.session_timing = clamp_botness_weight(cfg.botness_weights.session_timing);
+    cfg.botness_weights.session_referer = clamp_botness_weight(cfg.botness_weights.session_referer);
     cfg.maze_token_ttl_seconds = cfg.maze_token_ttl_seconds.clamp(30, 600);
     cfg.maze_token_max_depth = cfg.maze_token_max_depth.clamp(1, 32);
     cfg.maze_token_branch_budget = cfg.maze_token_branch_budget.clamp(1, 12);
@@ -3233,6 +3752,7 @@ fn clamp_config_values(cfg: &mut Config) {
     cfg.maze_seed_refresh_max_sources = cfg.maze_seed_refresh_max_sources.clamp(1, 500);
     cfg.cdp_detection_threshold = cfg.cdp_detection_threshold.clamp(0.0, 1.0);
     cfg.cdp_probe_rollout_percent = cfg.cdp_probe_rollout_percent.clamp(0, 100);
+    cfg.request_facts_journal_sample_percent = cfg.request_facts_journal_sample_percent.clamp(0, 100);
     cfg.fingerprint_state_ttl_seconds = cfg.fingerprint_state_ttl_seconds.clamp(30, 24 * 3600);
     cfg.fingerprint_flow_window_seconds = cfg.fingerprint_flow_window_seconds.clamp(10, 3600);
     cfg.fingerprint_flow_violation_threshold = cfg.fingerprint_flow_violation_threshold.clamp(1, 20);
@@ -3494,6 +4014,18 @@ fn default_honeypot_enabled() -> bool {
     defaults_bool("SHUMA_HONEYPOT_ENABLED")
 }
 
+fn default_shadow_policy_sources() -> Vec<String> {
+    defaults_string_list("SHUMA_SHADOW_POLICY_SOURCES")
+}
+
+fn default_config_profiles() -> Vec<ConfigProfile> {
+    defaults_json("SHUMA_CONFIG_PROFILES")
+}
+
+fn default_request_facts_journal_sample_percent() -> u8 {
+    defaults_u8("SHUMA_REQUEST_FACTS_JOURNAL_SAMPLE_PERCENT").clamp(0, 100)
+}
+
 fn default_honeypots() -> Vec<String> {
     defaults_string_list("SHUMA_HONEYPOTS")
 }
@@ -3564,6 +4096,14 @@ fn default_ip_range_custom_rules() -> Vec<IpRangePolicyRule> {
     defaults_json("SHUMA_IP_RANGE_CUSTOM_RULES")
 }
 
+fn default_custom_rules() -> Vec<CustomPolicyRule> {
+    defaults_json("SHUMA_CUSTOM_RULES")
+}
+
+fn default_not_a_bot_accessible_questions() -> Vec<NotABotAccessibleQuestion> {
+    defaults_json("SHUMA_NOT_A_BOT_ACCESSIBLE_QUESTIONS")
+}
+
 fn default_ip_range_suggestions_min_observations() -> u32 {
     clamp_ip_range_suggestions_min_observations(defaults_u32(
         "SHUMA_IP_RANGE_SUGGESTIONS_MIN_OBSERVATIONS",
@@ -3940,6 +4480,11 @@ fn default_fingerprint_family_cap_behavior() -> u8 {
     defaults_u8("SHUMA_FINGERPRINT_FAMILY_CAP_BEHAVIOR")
 }
 
+fn default_fingerprint_transport_profiles(
+) -> Vec<crate::signals::fingerprint::TransportFingerprintProfile> {
+    defaults_json("SHUMA_FINGERPRINT_TRANSPORT_PROFILES")
+}
+
 fn default_js_required_enforced() -> bool {
     defaults_bool("SHUMA_JS_REQUIRED_ENFORCED")
 }
@@ -3987,6 +4532,47 @@ fn default_verified_identity_service_profiles(
     defaults_json("SHUMA_VERIFIED_IDENTITY_SERVICE_PROFILES")
 }
 
+fn default_verified_identity_restrict_requests_per_minute() -> u32 {
+    defaults_u32("SHUMA_VERIFIED_IDENTITY_RESTRICT_REQUESTS_PER_MINUTE")
+}
+
+fn default_verified_identity_restrict_denied_path_prefixes() -> Vec<String> {
+    defaults_string_list("SHUMA_VERIFIED_IDENTITY_RESTRICT_DENIED_PATH_PREFIXES")
+}
+
+fn default_verified_identity_mtls_enabled() -> bool {
+    defaults_bool("SHUMA_VERIFIED_IDENTITY_MTLS_ENABLED")
+}
+
+fn default_verified_identity_mtls_ca_bundle() -> Vec<String> {
+    defaults_string_list("SHUMA_VERIFIED_IDENTITY_MTLS_CA_BUNDLE")
+}
+
+fn default_verified_identity_usage_quotas() -> Vec<crate::bot_identity::policy::IdentityUsageQuota>
+{
+    defaults_json("SHUMA_VERIFIED_IDENTITY_USAGE_QUOTAS")
+}
+
+fn default_verified_identity_licence_offers(
+) -> Vec<crate::bot_identity::policy::IdentityLicenceOffer> {
+    defaults_json("SHUMA_VERIFIED_IDENTITY_LICENCE_OFFERS")
+}
+
+fn default_verified_identity_licence_keys() -> Vec<crate::bot_identity::policy::IdentityLicenceKey>
+{
+    defaults_json("SHUMA_VERIFIED_IDENTITY_LICENCE_KEYS")
+}
+
+fn default_verified_identity_delegation_keys(
+) -> Vec<crate::bot_identity::policy::IdentityDelegationKey> {
+    defaults_json("SHUMA_VERIFIED_IDENTITY_DELEGATION_KEYS")
+}
+
+fn default_verified_identity_pinned_directories(
+) -> Vec<crate::bot_identity::policy::IdentityPinnedDirectory> {
+    defaults_json("SHUMA_VERIFIED_IDENTITY_PINNED_DIRECTORIES")
+}
+
 fn default_pow_enabled() -> bool {
     defaults_bool("SHUMA_POW_ENABLED")
 }
@@ -4083,6 +4669,22 @@ fn default_botness_weight_maze_behavior() -> u8 {
     clamp_botness_weight(defaults_u8("SHUMA_BOTNESS_WEIGHT_MAZE_BEHAVIOR"))
 }
 
+fn default_botness_weight_session_asset_ratio() -> u8 {
+    clamp_botness_weight(defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_ASSET_RATIO"))
+}
+
+fn default_botness_weight_session_traversal() -> u8 {
+    clamp_botness_weight(defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_TRAVERSAL"))
+}
+
+fn default_botness_weight_session_timing() -> u8 {
+    clamp_botness_weight(defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_TIMING"))
+}
+
+fn default_botness_weight_session_referer() -> u8 {
+    clamp_botness_weight(defaults_u8("SHUMA_BOTNESS_WEIGHT_SESSION_REFERER"))
+}
+
 fn defaults_composability_mode(key: &str) -> ComposabilityMode {
     let raw = defaults_raw(key);
     parse_composability_mode(raw.as_str())
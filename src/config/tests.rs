@@ -534,6 +534,10 @@ fn defaults_enable_both_signal_and_action_paths() {
     assert_eq!(cfg.ip_range_policy_mode, IpRangePolicyMode::Off);
     assert!(cfg.ip_range_emergency_allowlist.is_empty());
     assert!(cfg.ip_range_custom_rules.is_empty());
+    assert!(cfg.shadow_policy_sources.is_empty());
+    assert_eq!(cfg.request_facts_journal_sample_percent, 5);
+    assert!(cfg.config_profiles.is_empty());
+    assert!(cfg.active_config_profile.is_none());
     assert_eq!(cfg.ip_range_suggestions_min_observations, 30);
     assert_eq!(cfg.ip_range_suggestions_min_bot_events, 8);
     assert_eq!(cfg.ip_range_suggestions_min_confidence_percent, 60);
@@ -617,6 +621,11 @@ fn verified_identity_validation_rejects_enabled_config_without_any_verifier() {
 
     let error = validate_persisted_config(&cfg).expect_err("expected invalid config");
     assert!(error.contains("verified_identity.enabled=true"));
+
+    cfg.verified_identity.mtls_enabled = true;
+    cfg.verified_identity.mtls_ca_bundle =
+        vec![include_str!("../bot_identity/testdata/mtls/root.pem").to_string()];
+    assert!(validate_persisted_config(&cfg).is_ok());
 }
 
 #[test]
@@ -635,6 +644,102 @@ fn verified_identity_validation_rejects_empty_policy_matcher_and_unknown_profile
     assert!(error.contains("verified_identity.named_policies[0].matcher"));
 }
 
+#[test]
+fn verified_identity_validation_requires_known_offers_and_keys_for_require_licence() {
+    let mut cfg = defaults().clone();
+    cfg.verified_identity.named_policies = vec![crate::bot_identity::policy::IdentityPolicyEntry {
+        policy_id: "licence-openai".to_string(),
+        description: None,
+        matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+            operator: Some("openai".to_string()),
+            ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+        },
+        action: crate::bot_identity::policy::IdentityPolicyAction::RequireLicence(
+            "archive".to_string(),
+        ),
+    }];
+
+    let error = validate_persisted_config(&cfg).expect_err("expected unknown offer");
+    assert!(error.contains("unknown licence offer archive"));
+
+    cfg.verified_identity.licence_offers = vec![crate::bot_identity::policy::IdentityLicenceOffer {
+        offer_id: "archive".to_string(),
+        description: None,
+        price: "0.002".to_string(),
+        currency: "USD".to_string(),
+        terms_url: "https://example.com/licensing".to_string(),
+    }];
+    let error = validate_persisted_config(&cfg).expect_err("expected missing keys");
+    assert!(error.contains("requires at least one verified_identity.licence_keys entry"));
+
+    cfg.verified_identity.licence_keys = vec![crate::bot_identity::policy::IdentityLicenceKey {
+        key_id: "billing".to_string(),
+        public_key: "not-a-key".to_string(),
+    }];
+    let error = validate_persisted_config(&cfg).expect_err("expected invalid key");
+    assert!(error.contains("verified_identity.licence_keys[0].public_key"));
+
+    cfg.verified_identity.licence_keys[0].public_key =
+        "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik=".to_string();
+    assert!(validate_persisted_config(&cfg).is_ok());
+
+    cfg.verified_identity.licence_offers[0].terms_url = "http://example.com/licensing".to_string();
+    let error = validate_persisted_config(&cfg).expect_err("expected https terms");
+    assert!(error.contains("verified_identity.licence_offers[0].terms_url"));
+}
+
+#[test]
+fn verified_identity_validation_requires_delegation_key_issuers() {
+    let mut cfg = defaults().clone();
+    cfg.verified_identity.delegation_keys =
+        vec![crate::bot_identity::policy::IdentityDelegationKey {
+            key_id: "session".to_string(),
+            issuer: String::new(),
+            public_key: "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik=".to_string(),
+        }];
+    let error = validate_persisted_config(&cfg).expect_err("expected missing issuer");
+    assert!(error.contains("verified_identity.delegation_keys[0].issuer"));
+
+    cfg.verified_identity.delegation_keys[0].issuer = "https://auth example.com".to_string();
+    assert!(validate_persisted_config(&cfg).is_err());
+
+    cfg.verified_identity.delegation_keys[0].issuer = "https://auth.example.com".to_string();
+    assert!(validate_persisted_config(&cfg).is_ok());
+}
+
+#[test]
+fn verified_identity_validation_checks_pinned_directories() {
+    let mut cfg = defaults().clone();
+    let pin: crate::bot_identity::policy::IdentityPinnedDirectory =
+        serde_json::from_value(serde_json::json!({
+            "source_uri": "https://agents.example/",
+            "jwks": { "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": "JrQLj5P_89iXES9-vFgrIy29clF9CC_oPPsw3c5D0bs"
+            }] },
+            "pinned_key_ids": ["poqkLGiymh_W0uP6PZFw-dvez3QJT5SolqXBCW38r0U"]
+        }))
+        .expect("pin parses");
+    cfg.verified_identity.pinned_directories = vec![pin.clone()];
+    assert!(validate_persisted_config(&cfg).is_ok());
+
+    cfg.verified_identity.pinned_directories[0].pinned_key_ids = vec!["retired-key".to_string()];
+    let error = validate_persisted_config(&cfg).expect_err("expected unknown pinned key");
+    assert!(error.contains("verified_identity.pinned_directories[0].pinned_key_ids"));
+    cfg.verified_identity.pinned_directories[0].auto_refresh = true;
+    assert!(validate_persisted_config(&cfg).is_ok());
+
+    cfg.verified_identity.pinned_directories = vec![pin.clone(), pin];
+    let error = validate_persisted_config(&cfg).expect_err("expected duplicate pin");
+    assert!(error.contains("verified_identity.pinned_directories[1].source_uri duplicates"));
+
+    cfg.verified_identity.pinned_directories.truncate(1);
+    cfg.verified_identity.pinned_directories[0].source_uri = "http://agents.example/".to_string();
+    let error = validate_persisted_config(&cfg).expect_err("expected https source");
+    assert!(error.contains("verified_identity.pinned_directories[0].source_uri"));
+}
+
 #[test]
 fn verified_identity_allowed_actions_surface_is_forbidden() {
     let surface = allowed_actions_v1();
@@ -2525,3 +2630,155 @@ fn default_seeded_config_matches_defaults_snapshot() {
     );
     assert_eq!(cfg.verified_identity, defaults_cfg.verified_identity);
 }
+
+#[test]
+fn shadow_policy_sources_resolve_per_source_and_reject_unknown_entries() {
+    let mut cfg = defaults().clone();
+    cfg.shadow_policy_sources = vec!["geo_block".to_string(), "honeypot:/trap".to_string()];
+
+    let enforced = PolicyExecutionMode::Enforced;
+    assert_eq!(
+        cfg.policy_source_execution_mode("geo_block", enforced),
+        PolicyExecutionMode::Shadow
+    );
+    assert_eq!(
+        cfg.policy_source_execution_mode("honeypot:/trap", enforced),
+        PolicyExecutionMode::Shadow
+    );
+    assert_eq!(
+        cfg.policy_source_execution_mode("honeypot:/instaban", enforced),
+        PolicyExecutionMode::Enforced
+    );
+    assert_eq!(
+        cfg.policy_source_execution_mode("ip_range:dc", PolicyExecutionMode::Shadow),
+        PolicyExecutionMode::Shadow
+    );
+    assert!(validate_persisted_config(&cfg).is_ok());
+
+    for invalid in ["geo_block:XX", "rate_limit", "ip_range:", ""] {
+        cfg.shadow_policy_sources = vec![invalid.to_string()];
+        assert!(
+            validate_persisted_config(&cfg).is_err(),
+            "{invalid} should be rejected"
+        );
+    }
+}
+
+// 2023-11-20 00:00:00 UTC, a Monday.
+const MONDAY_MIDNIGHT_TS: u64 = 1_700_438_400;
+
+fn night_profile() -> ConfigProfile {
+    serde_json::from_value(serde_json::json!({
+        "id": "overnight",
+        "windows": [{"kind": "weekly", "days": ["fri"], "start": "22:00", "end": "06:00"}],
+        "overlay": {"rate_limit": 40, "js_required_enforced": false}
+    }))
+    .unwrap()
+}
+
+#[test]
+fn config_profile_windows_match_weekdays_wrap_midnight_and_bound_one_off_ranges() {
+    let friday = MONDAY_MIDNIGHT_TS + 4 * 86_400;
+    let overnight = night_profile();
+    assert!(!overnight.active_at(friday + 21 * 3_600 + 59 * 60));
+    assert!(overnight.active_at(friday + 22 * 3_600));
+    assert!(overnight.active_at(friday + 86_400 + 5 * 3_600 + 59 * 60));
+    assert!(!overnight.active_at(friday + 86_400 + 6 * 3_600));
+    assert!(!overnight.active_at(friday + 86_400 + 22 * 3_600));
+
+    let every_day = config_profiles::ConfigProfileWindow::Weekly {
+        days: vec![],
+        start: "09:00".to_string(),
+        end: "17:00".to_string(),
+    };
+    assert!(every_day.contains(MONDAY_MIDNIGHT_TS + 2 * 86_400 + 9 * 3_600));
+    assert!(!every_day.contains(MONDAY_MIDNIGHT_TS + 17 * 3_600));
+
+    let launch = config_profiles::ConfigProfileWindow::Between {
+        start_ts: 1_000,
+        end_ts: 2_000,
+    };
+    assert!(launch.contains(1_000));
+    assert!(!launch.contains(2_000));
+
+    let mut disabled = night_profile();
+    disabled.enabled = false;
+    assert!(!disabled.active_at(friday + 23 * 3_600));
+}
+
+#[test]
+fn validate_persisted_config_rejects_malformed_config_profiles() {
+    let cases = [
+        (
+            serde_json::json!({"id": "p", "windows": [{"kind": "weekly", "start": "25:00", "end": "06:00"}], "overlay": {"rate_limit": 10}}),
+            "config_profiles[0].windows[0].start must be HH:MM",
+        ),
+        (
+            serde_json::json!({"id": "p", "windows": [{"kind": "weekly", "days": ["funday"], "start": "01:00", "end": "02:00"}], "overlay": {"rate_limit": 10}}),
+            "config_profiles[0].windows[0].days[0]",
+        ),
+        (
+            serde_json::json!({"id": "p", "windows": [{"kind": "between", "start_ts": 5, "end_ts": 5}], "overlay": {"rate_limit": 10}}),
+            "start_ts must be before end_ts",
+        ),
+        (
+            serde_json::json!({"id": "p", "windows": [], "overlay": {"rate_limit": 10}}),
+            "config_profiles[0].windows must list",
+        ),
+        (
+            serde_json::json!({"id": "p", "windows": [{"kind": "between", "start_ts": 1, "end_ts": 5}], "overlay": {"config_profiles": []}}),
+            "overlay.config_profiles is not an overlayable config field",
+        ),
+        (
+            serde_json::json!({"id": "p", "windows": [{"kind": "between", "start_ts": 1, "end_ts": 5}], "overlay": {"no_such_field": 1}}),
+            "overlay.no_such_field is not an overlayable config field",
+        ),
+        (
+            serde_json::json!({"id": "p", "windows": [{"kind": "between", "start_ts": 1, "end_ts": 5}], "overlay": {"rate_limit": "fast"}}),
+            "config_profiles[0].overlay is invalid",
+        ),
+    ];
+    for (profile, expected) in cases {
+        let mut cfg = defaults().clone();
+        cfg.config_profiles = vec![serde_json::from_value(profile).unwrap()];
+        let err = validate_persisted_config(&cfg).unwrap_err();
+        assert!(err.contains(expected), "{err} should mention {expected}");
+    }
+
+    let mut duplicated = defaults().clone();
+    duplicated.config_profiles = vec![night_profile(), night_profile()];
+    assert!(validate_persisted_config(&duplicated)
+        .unwrap_err()
+        .contains("is duplicated"));
+
+    let mut valid = defaults().clone();
+    valid.config_profiles = vec![night_profile()];
+    assert!(validate_persisted_config(&valid).is_ok());
+}
+
+#[test]
+fn runtime_config_applies_the_scheduled_profile_only_while_its_window_is_open() {
+    let _lock = crate::test_support::lock_env();
+    clear_runtime_cache_for_tests();
+    let store = CountingStore::default();
+    let mut cfg = defaults().clone();
+    cfg.rate_limit = 300;
+    cfg.config_profiles = vec![night_profile()];
+    store
+        .set("config:default", &serde_json::to_vec(&cfg).unwrap())
+        .unwrap();
+
+    let friday_night = MONDAY_MIDNIGHT_TS + 4 * 86_400 + 23 * 3_600;
+    let scheduled = load_runtime_cached_for_tests(&store, "default", friday_night, 60).unwrap();
+    assert_eq!(scheduled.rate_limit, 40);
+    assert!(!scheduled.js_required_enforced);
+    assert_eq!(scheduled.active_config_profile.as_deref(), Some("overnight"));
+    assert_eq!(scheduled.config_profiles.len(), 1);
+
+    let saturday_noon = friday_night + 13 * 3_600;
+    let base = load_runtime_cached_for_tests(&store, "default", saturday_noon, 86_400).unwrap();
+    assert_eq!(base.rate_limit, 300);
+    assert!(base.active_config_profile.is_none());
+    assert_eq!(store.get_count(), 1);
+    clear_runtime_cache_for_tests();
+}
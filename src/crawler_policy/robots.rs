@@ -3,6 +3,7 @@
 //! Generates configurable robots.txt that:
 //! - Blocks known AI training crawlers
 //! - Supports Cloudflare Content-Signal directive
+//! - Advertises pay-per-crawl licence offers quoted to verified crawlers
 //! - Allows legitimate search engine crawlers
 
 use crate::{config::Config, http_route_namespace::PUBLIC_SITEMAP_XML_PATH};
@@ -96,6 +97,10 @@ pub fn generate_robots_txt_for_public_origin(cfg: &Config, public_origin: Option
         ai_train, search, ai_input
     ));
     lines.push("#".to_string());
+    // Licensing contract for verified crawlers answered with 402 by require_licence policies
+    lines.extend(crate::bot_identity::licensing::robots_comment_lines(
+        &cfg.verified_identity,
+    ));
     lines.push("".to_string());
 
     // Block AI training bots
@@ -337,4 +342,42 @@ mod tests {
 
         assert!(robots.contains("Sitemap: https://shuma.test/sitemap.xml"));
     }
+
+    #[test]
+    fn test_robots_advertises_licence_offers_quoted_by_policies() {
+        let mut cfg = test_config();
+        cfg.verified_identity.enabled = true;
+        cfg.verified_identity.licence_offers = vec![
+            crate::bot_identity::policy::IdentityLicenceOffer {
+                offer_id: "archive".to_string(),
+                description: None,
+                price: "0.002".to_string(),
+                currency: "USD".to_string(),
+                terms_url: "https://shuma.test/licensing".to_string(),
+            },
+            crate::bot_identity::policy::IdentityLicenceOffer {
+                offer_id: "unused".to_string(),
+                description: None,
+                price: "1".to_string(),
+                currency: "EUR".to_string(),
+                terms_url: "https://shuma.test/unused".to_string(),
+            },
+        ];
+        assert!(!generate_robots_txt(&cfg).contains("Licence-Offer"));
+
+        cfg.verified_identity.category_defaults =
+            vec![crate::bot_identity::policy::IdentityCategoryDefaultAction {
+                category: crate::bot_identity::contracts::IdentityCategory::Training,
+                action: crate::bot_identity::policy::IdentityPolicyAction::RequireLicence(
+                    "archive".to_string(),
+                ),
+            }];
+        let robots = generate_robots_txt(&cfg);
+
+        assert!(robots.contains("# Licensing: verified crawlers may be answered with HTTP 402"));
+        assert!(robots.contains(
+            "# Licence-Offer: archive price=USD 0.002 terms=https://shuma.test/licensing"
+        ));
+        assert!(!robots.contains("Licence-Offer: unused"));
+    }
 }
@@ -0,0 +1,99 @@
+//! Signature primitives shared by admin sign-in and verified-identity tokens.
+//!
+//! Ed25519 verification is strict (`ed25519-dalek`'s `verify_strict`). Compact JWS support is
+//! limited to `EdDSA` over Ed25519, which is all the operator-issued tokens use.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::Deserialize;
+
+/// Strict Ed25519 verification against a raw 32-byte public key.
+pub(crate) fn verify_ed25519_signature(
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> bool {
+    let Ok(public_key) = <[u8; 32]>::try_from(public_key) else {
+        return false;
+    };
+    let Ok(signature) = <[u8; 64]>::try_from(signature) else {
+        return false;
+    };
+    let Ok(verifying_key) = ed25519_dalek::VerifyingKey::from_bytes(&public_key) else {
+        return false;
+    };
+    verifying_key
+        .verify_strict(message, &ed25519_dalek::Signature::from_bytes(&signature))
+        .is_ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EddsaJwsError {
+    Malformed,
+    UnsupportedAlgorithm,
+    UnknownKey,
+    SignatureInvalid,
+}
+
+/// A compact JWS whose `EdDSA` signature verified under the key its `kid` names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VerifiedEddsaJws<'a> {
+    pub key_id: String,
+    pub payload_segment: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct EddsaJwsHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// Verifies a compact JWS of at most `max_bytes`. `public_key_for` maps a `kid` to its raw
+/// Ed25519 public key; claims are left to the caller, via [`decode_jws_segment`].
+pub(crate) fn verify_eddsa_compact_jws<'a>(
+    token: &'a str,
+    max_bytes: usize,
+    public_key_for: impl Fn(&str) -> Option<Vec<u8>>,
+) -> Result<VerifiedEddsaJws<'a>, EddsaJwsError> {
+    if token.len() > max_bytes {
+        return Err(EddsaJwsError::Malformed);
+    }
+    let mut segments = token.split('.');
+    let (Some(header_segment), Some(payload_segment), Some(signature_segment), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return Err(EddsaJwsError::Malformed);
+    };
+    let header: EddsaJwsHeader =
+        decode_jws_segment(header_segment).ok_or(EddsaJwsError::Malformed)?;
+    if header.alg != "EdDSA" {
+        return Err(EddsaJwsError::UnsupportedAlgorithm);
+    }
+    let key_id = header.kid.ok_or(EddsaJwsError::UnknownKey)?;
+    let public_key = public_key_for(key_id.as_str()).ok_or(EddsaJwsError::UnknownKey)?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature_segment)
+        .map_err(|_| EddsaJwsError::Malformed)?;
+    let signing_input = &token[..header_segment.len() + 1 + payload_segment.len()];
+    if !verify_ed25519_signature(
+        public_key.as_slice(),
+        signing_input.as_bytes(),
+        signature.as_slice(),
+    ) {
+        return Err(EddsaJwsError::SignatureInvalid);
+    }
+    Ok(VerifiedEddsaJws {
+        key_id,
+        payload_segment,
+    })
+}
+
+/// Decodes one base64url JWS segment as JSON.
+pub(crate) fn decode_jws_segment<T: serde::de::DeserializeOwned>(segment: &str) -> Option<T> {
+    URL_SAFE_NO_PAD
+        .decode(segment)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(raw.as_slice()).ok())
+}
@@ -7,6 +7,7 @@ pub enum BlockReason {
     GeoPolicy,
     IpRangePolicy,
     VerifiedIdentityPolicy,
+    CustomRule,
 }
 
 pub fn render_block_page(reason: BlockReason) -> String {
@@ -16,6 +17,7 @@ pub fn render_block_page(reason: BlockReason) -> String {
         BlockReason::GeoPolicy => BLOCK_GEO_HTML.to_string(),
         BlockReason::IpRangePolicy => BLOCK_IP_RANGE_HTML.to_string(),
         BlockReason::VerifiedIdentityPolicy => BLOCK_VERIFIED_IDENTITY_HTML.to_string(),
+        BlockReason::CustomRule => BLOCK_CUSTOM_RULE_HTML.to_string(),
     }
 }
 
@@ -128,3 +130,25 @@ const BLOCK_VERIFIED_IDENTITY_HTML: &str = r#"
 </body>
 </html>
 "#;
+
+const BLOCK_CUSTOM_RULE_HTML: &str = r#"
+<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+  <meta charset=\"UTF-8\">
+  <title>Access Restricted</title>
+  <style>
+    body { font-family: sans-serif; background: #f9f9f9; margin: 2em; }
+    .block-container { background: #fff; padding: 2em; border-radius: 8px; box-shadow: 0 2px 8px #ccc; max-width: 480px; margin: auto; }
+    h1 { color: #c00; }
+  </style>
+</head>
+<body>
+  <div class=\"block-container\">
+    <h1>Access Restricted</h1>
+    <p>Your request was blocked by this site's access rules.</p>
+    <p>If you believe this is an error, contact the site administrator.</p>
+  </div>
+</body>
+</html>
+"#;
@@ -18,6 +18,8 @@ pub(crate) const SHUMA_INTERNAL_ADVERSARY_SIM_WORKER_RESULT_PATH: &str =
     "/shuma/internal/adversary-sim/worker-result";
 pub(crate) const SHUMA_INTERNAL_OVERSIGHT_AGENT_RUN_PATH: &str =
     "/shuma/internal/oversight/agent/run";
+pub(crate) const SHUMA_INTERNAL_ALERT_RULES_EVALUATE_PATH: &str =
+    "/shuma/internal/alerts/evaluate";
 pub(crate) const SHUMA_HEALTH_PATH: &str = "/shuma/health";
 pub(crate) const SHUMA_METRICS_PATH: &str = "/shuma/metrics";
 
@@ -90,6 +92,7 @@ mod tests {
             SHUMA_INTERNAL_ADVERSARY_SIM_BEAT_PATH,
             SHUMA_INTERNAL_ADVERSARY_SIM_WORKER_RESULT_PATH,
             SHUMA_INTERNAL_OVERSIGHT_AGENT_RUN_PATH,
+            SHUMA_INTERNAL_ALERT_RULES_EVALUATE_PATH,
             SHUMA_HEALTH_PATH,
             SHUMA_METRICS_PATH,
         ] {
@@ -20,6 +20,7 @@ mod bot_identity; // Verified bot and signed-agent identity domain
 mod boundaries; // Domain boundary adapters for future repo splits
 mod challenge; // Interactive math challenge for banned users
 mod config; // Config loading and defaults
+mod crypto; // Shared signature primitives (Ed25519, compact EdDSA JWS)
 mod crawler_policy; // Crawler-facing policy surfaces (robots.txt)
 mod deception; // Shared deception primitives (maze+tarpit)
 mod enforcement; // Enforcement actions (ban, block page, honeypot, rate limiting)
@@ -431,6 +432,8 @@ pub struct BotnessSignalContext {
     pub rate_limit: u32,
     pub maze_behavior_score: u8,
     pub fingerprint_signals: Vec<BotnessContribution>,
+    pub cdp_report_signals: Vec<BotnessContribution>,
+    pub session_behavior_signals: Vec<BotnessContribution>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -465,7 +468,10 @@ pub(crate) fn collect_botness_contributions(
     context: BotnessSignalContext,
     cfg: &config::Config,
 ) -> Vec<BotnessContribution> {
-    let signal_capacity = 6 + context.fingerprint_signals.len();
+    let signal_capacity = 6
+        + context.fingerprint_signals.len()
+        + context.cdp_report_signals.len()
+        + context.session_behavior_signals.len();
     let mut accumulator = crate::signals::botness::SignalAccumulator::with_capacity_and_policy(
         signal_capacity,
         crate::signals::botness::SignalBudgetPolicy {
@@ -556,6 +562,14 @@ pub(crate) fn collect_botness_contributions(
         accumulator.push(fingerprint_signal);
     }
 
+    for cdp_report_signal in context.cdp_report_signals {
+        accumulator.push(cdp_report_signal);
+    }
+
+    for session_behavior_signal in context.session_behavior_signals {
+        accumulator.push(session_behavior_signal);
+    }
+
     let (_score, contributions) = accumulator.finish();
     contributions
 }
@@ -630,11 +644,15 @@ pub(crate) fn defence_modes_effective_summary(cfg: &config::Config) -> String {
 }
 
 pub(crate) fn defence_runtime_metadata_summary(cfg: &config::Config) -> String {
-    format!(
+    let summary = format!(
         "modes={} edge={}",
         defence_modes_effective_summary(cfg),
         cfg.edge_integration_mode.as_str()
-    )
+    );
+    match cfg.active_config_profile.as_deref() {
+        Some(profile) => format!("{} profile={}", summary, profile),
+        None => summary,
+    }
 }
 
 pub(crate) fn provider_implementations_summary(
@@ -884,6 +902,14 @@ pub(crate) fn serve_maze_with_tracking(
                     ]);
                     let report_endpoint =
                         provider_registry.fingerprint_signal_provider().report_path();
+                    if cfg.cdp_detection_enabled {
+                        crate::signals::cdp::record_report_expected(
+                            store,
+                            ip,
+                            user_agent,
+                            admin::now_ts(),
+                        );
+                    }
                     return crate::signals::js_verification::inject_js_challenge(
                         ip,
                         user_agent,
@@ -1042,7 +1068,10 @@ pub fn spin_entrypoint(req: Request) -> Response {
             LibCapabilityToken::new(),
         );
         runtime::effect_intents::execute_monitoring_store_intents(
-            vec![runtime::effect_intents::EffectIntent::FlushPendingMonitoringCounters],
+            vec![
+                runtime::effect_intents::EffectIntent::FlushPendingMonitoringCounters,
+                runtime::effect_intents::EffectIntent::FlushEventExportBatches,
+            ],
             &store,
             &capabilities,
         );
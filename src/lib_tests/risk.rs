@@ -69,6 +69,8 @@ mod tests {
             rate_limit,
             maze_behavior_score,
             fingerprint_signals: Vec::new(),
+            cdp_report_signals: Vec::new(),
+            session_behavior_signals: Vec::new(),
         }
     }
 
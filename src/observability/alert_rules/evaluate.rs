@@ -0,0 +1,488 @@
+use crate::challenge::KeyValueStore;
+use crate::observability::operator_snapshot::OperatorBudgetDistanceSummary;
+
+use super::{
+    alert_summary, load_alert_state, load_or_seed_alert_rules, notify, save_alert_state,
+    AlertMetric, AlertRule, AlertRuleState, AlertStatus, CounterSample, OperatorSnapshotAlerts,
+    MAX_WINDOW_SECONDS,
+};
+
+/// Upper bound on retained window samples per rule; longer windows sample less often.
+const MAX_SAMPLES_PER_RULE: u64 = 48;
+const MIN_SAMPLE_SPACING_SECONDS: u64 = 15;
+const HUMAN_FRICTION_METRIC: &str = "likely_human_friction_rate";
+const PROVIDER_OUTAGE_OUTCOMES: [&str; 2] = ["fallback_allow", "fallback_deny"];
+
+fn hourly_count<S: KeyValueStore>(store: &S, metric: AlertMetric, hour: u64) -> u64 {
+    use crate::observability::monitoring::live_hourly_counter;
+
+    match metric {
+        AlertMetric::RateLimitHits => live_hourly_counter(store, "rate", "total", None, hour),
+        AlertMetric::Bans => live_hourly_counter(store, "ban", "total", None, hour),
+        AlertMetric::ProviderOutageDecisions => PROVIDER_OUTAGE_OUTCOMES
+            .iter()
+            .map(|outcome| live_hourly_counter(store, "rate", "outcome", Some(outcome), hour))
+            .sum(),
+        AlertMetric::DirectoryKeyRotations => [
+            crate::observability::monitoring::VERIFIED_IDENTITY_DIRECTORY_ROTATED,
+            crate::observability::monitoring::VERIFIED_IDENTITY_DIRECTORY_PINNED_KEYS_MISSING,
+        ]
+        .iter()
+        .map(|outcome| {
+            live_hourly_counter(
+                store,
+                "verified_identity",
+                "directory_key_rotation",
+                Some(outcome),
+                hour,
+            )
+        })
+        .sum(),
+        AlertMetric::PinnedDirectoryKeysMissing => live_hourly_counter(
+            store,
+            "verified_identity",
+            "directory_key_rotation",
+            Some(crate::observability::monitoring::VERIFIED_IDENTITY_DIRECTORY_PINNED_KEYS_MISSING),
+            hour,
+        ),
+        AlertMetric::HumanFrictionOverObjective => 0,
+    }
+}
+
+/// Events counted since `baseline`: growth of the baseline's hour bucket plus every later bucket.
+fn count_since<S: KeyValueStore>(
+    store: &S,
+    metric: AlertMetric,
+    baseline: &CounterSample,
+    current_hour: u64,
+) -> u64 {
+    let mut total = hourly_count(store, metric, baseline.hour).saturating_sub(baseline.value);
+    for hour in baseline.hour.saturating_add(1)..=current_hour {
+        total = total.saturating_add(hourly_count(store, metric, hour));
+    }
+    total
+}
+
+fn sample_spacing_seconds(span_seconds: u64) -> u64 {
+    (span_seconds / MAX_SAMPLES_PER_RULE).max(MIN_SAMPLE_SPACING_SECONDS)
+}
+
+/// Value of a windowed rule, measured from the newest samples at or before the window and
+/// baseline starts. While evaluations keep pace each measured span exceeds its nominal length by
+/// at most one sample spacing.
+///
+/// Without a baseline window the value is the count over the window; a rule without that much
+/// history counts from its oldest sample instead. With a baseline window the value is the
+/// window count divided by the trailing baseline rate scaled to the window, floored at one
+/// event, and it stays `None` until the full baseline has been observed.
+fn windowed_value<S: KeyValueStore>(
+    store: &S,
+    rule: &AlertRule,
+    state: &mut AlertRuleState,
+    now: u64,
+) -> Option<f64> {
+    let current_hour = now / 3600;
+    if state.samples_metric != Some(rule.metric) {
+        state.samples.clear();
+        state.samples_metric = Some(rule.metric);
+    }
+    let span_seconds = rule
+        .window_seconds
+        .saturating_add(rule.baseline_window_seconds.unwrap_or(0));
+    let max_sample_age_hours = 2 * MAX_WINDOW_SECONDS / 3600 + 1;
+    state.samples.retain(|sample| {
+        sample.ts <= now && current_hour.saturating_sub(sample.hour) <= max_sample_age_hours
+    });
+    let window_start = now.saturating_sub(rule.window_seconds);
+    let span_start = now.saturating_sub(span_seconds);
+    let window_index = state
+        .samples
+        .iter()
+        .rposition(|sample| sample.ts <= window_start);
+    let span_index = state
+        .samples
+        .iter()
+        .rposition(|sample| sample.ts <= span_start);
+    let value = match rule.baseline_window_seconds {
+        None => Some(
+            state
+                .samples
+                .get(window_index.unwrap_or(0))
+                .map(|baseline| count_since(store, rule.metric, baseline, current_hour))
+                .unwrap_or(0) as f64,
+        ),
+        Some(_) => span_index.zip(window_index).map(|(span_index, window_index)| {
+            let trailing_start = state.samples[span_index];
+            let window_baseline = state.samples[window_index];
+            let current = count_since(store, rule.metric, &window_baseline, current_hour);
+            let trailing = count_since(store, rule.metric, &trailing_start, current_hour)
+                .saturating_sub(current);
+            let trailing_seconds = window_baseline.ts.saturating_sub(trailing_start.ts).max(1);
+            let expected =
+                trailing as f64 * rule.window_seconds as f64 / trailing_seconds as f64;
+            current as f64 / expected.max(1.0)
+        }),
+    };
+    state.samples.drain(..span_index.unwrap_or(0));
+    let sample_due = state
+        .samples
+        .last()
+        .map(|sample| now.saturating_sub(sample.ts) >= sample_spacing_seconds(span_seconds))
+        .unwrap_or(true);
+    if sample_due {
+        state.samples.push(CounterSample {
+            ts: now,
+            hour: current_hour,
+            value: hourly_count(store, rule.metric, current_hour),
+        });
+    }
+    value
+}
+
+/// Friction rate above the objective target, or `None` while the budget row lacks evidence.
+fn human_friction_excess(budget_distance: &OperatorBudgetDistanceSummary) -> Option<f64> {
+    budget_distance
+        .rows
+        .iter()
+        .find(|row| row.metric == HUMAN_FRICTION_METRIC && row.status != "insufficient_evidence")
+        .map(|row| row.current - row.target)
+}
+
+fn set_status(state: &mut AlertRuleState, status: AlertStatus, now: u64) {
+    if state.status != status {
+        state.status = status;
+        state.last_transition_ts = now;
+    }
+}
+
+/// Advance one rule's state machine and return the notification it owes, if any.
+fn advance(
+    rule: &AlertRule,
+    state: &mut AlertRuleState,
+    value: Option<f64>,
+    now: u64,
+) -> Option<notify::AlertNotificationKind> {
+    state.value = value.unwrap_or(0.0);
+    let breached = value.map(|value| value > rule.threshold).unwrap_or(false);
+    if !breached {
+        let owes_resolution = state.status == AlertStatus::Firing && state.firing_notified;
+        state.condition_since_ts = None;
+        state.firing_since_ts = None;
+        state.firing_notified = false;
+        set_status(state, AlertStatus::Ok, now);
+        return owes_resolution.then_some(notify::AlertNotificationKind::Resolved);
+    }
+    let condition_since = *state.condition_since_ts.get_or_insert(now);
+    if now.saturating_sub(condition_since) < rule.for_seconds {
+        set_status(state, AlertStatus::Pending, now);
+        return None;
+    }
+    let entered_firing = state.status != AlertStatus::Firing;
+    if entered_firing {
+        state.firing_since_ts = Some(now);
+        set_status(state, AlertStatus::Firing, now);
+    }
+    if state.firing_notified {
+        return None;
+    }
+    let cooled_down = state
+        .last_notified_ts
+        .map(|ts| now.saturating_sub(ts) >= rule.cooldown_seconds)
+        .unwrap_or(true);
+    if cooled_down {
+        state.firing_notified = true;
+        return Some(notify::AlertNotificationKind::Firing);
+    }
+    if entered_firing {
+        state.notifications_suppressed = state.notifications_suppressed.saturating_add(1);
+    }
+    None
+}
+
+/// Evaluate every rule for the site from the scheduled internal hook, send owed notifications,
+/// persist the updated state and return the summary of it. The human-friction rule reads the
+/// budget distance of the last projected operator snapshot.
+pub(crate) fn evaluate_alert_rules<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    now: u64,
+) -> OperatorSnapshotAlerts {
+    let budget_distance =
+        crate::observability::hot_read_projection::load_operator_snapshot_hot_read(store, site_id)
+            .map(|document| document.payload.budget_distance);
+    evaluate_alert_rules_with_budget(store, site_id, now, budget_distance.as_ref())
+}
+
+fn evaluate_alert_rules_with_budget<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    now: u64,
+    budget_distance: Option<&OperatorBudgetDistanceSummary>,
+) -> OperatorSnapshotAlerts {
+    let rule_set = load_or_seed_alert_rules(store, site_id, now);
+    let mut state = load_alert_state(store, site_id);
+    state
+        .rules
+        .retain(|rule_id, _| rule_set.rules.iter().any(|rule| &rule.rule_id == rule_id));
+    let mut notifications = Vec::new();
+    for rule in &rule_set.rules {
+        let rule_state = state.rules.entry(rule.rule_id.clone()).or_default();
+        if !rule.enabled {
+            *rule_state = AlertRuleState {
+                status: AlertStatus::Disabled,
+                last_transition_ts: if rule_state.status == AlertStatus::Disabled {
+                    rule_state.last_transition_ts
+                } else {
+                    now
+                },
+                ..AlertRuleState::default()
+            };
+            continue;
+        }
+        let value = if rule.metric.is_windowed() {
+            windowed_value(store, rule, rule_state, now)
+        } else {
+            budget_distance.and_then(human_friction_excess)
+        };
+        if let Some(kind) = advance(rule, rule_state, value, now) {
+            notifications.push(notify::AlertNotification::new(site_id, rule, rule_state, kind, now));
+        }
+    }
+    for notification in notifications {
+        let outcome = notify::deliver(&notification);
+        if let Some(rule_state) = state.rules.get_mut(notification.rule_id.as_str()) {
+            rule_state.last_notified_ts = Some(now);
+            if outcome.delivered > 0 {
+                rule_state.notifications_sent = rule_state.notifications_sent.saturating_add(1);
+            }
+            rule_state.last_notification_error =
+                (!outcome.errors.is_empty()).then(|| outcome.errors.join("; "));
+        }
+    }
+    state.evaluated_at_ts = now;
+    save_alert_state(store, site_id, &state);
+    alert_summary(&rule_set, &state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::operator_snapshot::OperatorBudgetDistanceRow;
+    use crate::test_support::InMemoryStore;
+
+    fn rate_limit_rule(threshold: f64, for_seconds: u64, cooldown_seconds: u64) -> AlertRule {
+        AlertRule {
+            rule_id: "rate_limit_hits_5m".to_string(),
+            metric: AlertMetric::RateLimitHits,
+            threshold,
+            window_seconds: 300,
+            for_seconds,
+            cooldown_seconds,
+            enabled: true,
+            baseline_window_seconds: None,
+        }
+    }
+
+    fn ban_spike_rule() -> AlertRule {
+        AlertRule {
+            rule_id: "ban_spike_5m".to_string(),
+            metric: AlertMetric::Bans,
+            threshold: 3.0,
+            window_seconds: 300,
+            for_seconds: 0,
+            cooldown_seconds: 900,
+            enabled: true,
+            baseline_window_seconds: Some(3600),
+        }
+    }
+
+    fn live_total_key(family: &str, hour: u64) -> String {
+        format!(
+            "monitoring:v1:{}:total:{}:{}",
+            family,
+            base64::Engine::encode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, "live"),
+            hour
+        )
+    }
+
+    fn set_live_rate_total(store: &InMemoryStore, hour: u64, value: u64) {
+        let key = live_total_key("rate", hour);
+        store.set(key.as_str(), value.to_string().as_bytes()).unwrap();
+    }
+
+    fn add_live_bans(store: &InMemoryStore, now: u64, delta: u64) {
+        let key = live_total_key("ban", now / 3600);
+        let current = store
+            .get(key.as_str())
+            .unwrap()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(0);
+        store
+            .set(key.as_str(), (current + delta).to_string().as_bytes())
+            .unwrap();
+    }
+
+    #[test]
+    fn windowed_value_counts_growth_since_baseline_across_hour_boundary() {
+        let store = InMemoryStore::default();
+        let rule = rate_limit_rule(10.0, 0, 900);
+        let mut state = AlertRuleState::default();
+        let start = 1_700_006_300;
+        let hour = start / 3600;
+
+        set_live_rate_total(&store, hour, 40);
+        assert_eq!(windowed_value(&store, &rule, &mut state, start), Some(0.0));
+
+        set_live_rate_total(&store, hour, 55);
+        set_live_rate_total(&store, hour + 1, 7);
+        assert_eq!(windowed_value(&store, &rule, &mut state, start + 301), Some(22.0));
+    }
+
+    #[test]
+    fn ban_spike_is_measured_against_the_trailing_baseline_rate() {
+        let store = InMemoryStore::default();
+        let rule = ban_spike_rule();
+        let mut state = AlertRuleState::default();
+        let start = 1_700_006_300;
+
+        // One ban a minute is the steady baseline; the ratio stays unknown until a full
+        // baseline window plus the alert window has been sampled.
+        for step in 0..65 {
+            let now = start + step * 60;
+            add_live_bans(&store, now, 1);
+            assert_eq!(windowed_value(&store, &rule, &mut state, now), None);
+        }
+        let now = start + 65 * 60;
+        add_live_bans(&store, now, 1);
+        let steady = windowed_value(&store, &rule, &mut state, now).expect("full history");
+        assert!((steady - 1.0).abs() < 1e-9, "steady ratio {steady}");
+        assert_eq!(advance(&rule, &mut state, Some(steady), now), None);
+
+        let now = start + 66 * 60;
+        add_live_bans(&store, now, 41);
+        let spike = windowed_value(&store, &rule, &mut state, now).expect("full history");
+        assert!((spike - 46.0 / 5.0).abs() < 1e-9, "spike ratio {spike}");
+        assert_eq!(
+            advance(&rule, &mut state, Some(spike), now),
+            Some(notify::AlertNotificationKind::Firing)
+        );
+        assert!(state.samples.len() as u64 <= MAX_SAMPLES_PER_RULE + 2);
+    }
+
+    #[test]
+    fn ban_spike_ratio_floors_an_empty_baseline_at_one_event() {
+        let store = InMemoryStore::default();
+        let rule = ban_spike_rule();
+        let mut state = AlertRuleState::default();
+        let start = 1_700_006_300;
+
+        for step in 0..=65 {
+            windowed_value(&store, &rule, &mut state, start + step * 60);
+        }
+        let now = start + 66 * 60;
+        add_live_bans(&store, now, 2);
+        assert_eq!(windowed_value(&store, &rule, &mut state, now), Some(2.0));
+    }
+
+    #[test]
+    fn pinned_directory_key_loss_is_counted_apart_from_plain_rotations() {
+        use crate::observability::monitoring::record_verified_identity_directory_key_rotation;
+
+        let store = InMemoryStore::default();
+        record_verified_identity_directory_key_rotation(&store, false);
+        record_verified_identity_directory_key_rotation(&store, true);
+        let hour = crate::admin::now_ts() / 3600;
+
+        assert_eq!(hourly_count(&store, AlertMetric::DirectoryKeyRotations, hour), 2);
+        assert_eq!(hourly_count(&store, AlertMetric::PinnedDirectoryKeysMissing, hour), 1);
+    }
+
+    #[test]
+    fn advance_waits_for_duration_then_fires_once_per_cooldown() {
+        let rule = rate_limit_rule(10.0, 60, 600);
+        let mut state = AlertRuleState::default();
+
+        assert_eq!(advance(&rule, &mut state, Some(20.0), 1_000), None);
+        assert_eq!(state.status, AlertStatus::Pending);
+        assert_eq!(
+            advance(&rule, &mut state, Some(20.0), 1_060),
+            Some(notify::AlertNotificationKind::Firing)
+        );
+        assert_eq!(state.status, AlertStatus::Firing);
+        assert_eq!(advance(&rule, &mut state, Some(20.0), 1_070), None);
+        state.last_notified_ts = Some(1_060);
+
+        assert_eq!(
+            advance(&rule, &mut state, Some(1.0), 1_100),
+            Some(notify::AlertNotificationKind::Resolved)
+        );
+        assert_eq!(state.status, AlertStatus::Ok);
+
+        // Re-breaching inside the cooldown fires without a notification.
+        assert_eq!(advance(&rule, &mut state, Some(20.0), 1_200), None);
+        assert_eq!(advance(&rule, &mut state, Some(20.0), 1_260), None);
+        assert_eq!(state.status, AlertStatus::Firing);
+        assert_eq!(state.notifications_suppressed, 1);
+        assert_eq!(
+            advance(&rule, &mut state, Some(20.0), 1_660),
+            Some(notify::AlertNotificationKind::Firing)
+        );
+    }
+
+    #[test]
+    fn human_friction_rule_compares_budget_row_against_objective() {
+        let _lock = crate::test_support::lock_env();
+        let store = InMemoryStore::default();
+        let budget_distance = OperatorBudgetDistanceSummary {
+            rows: vec![OperatorBudgetDistanceRow {
+                budget_id: "likely_human_friction".to_string(),
+                metric: "likely_human_friction_rate".to_string(),
+                eligible_requests: 200,
+                current: 0.05,
+                target: 0.02,
+                delta: 0.03,
+                near_limit: 0.016,
+                status: "outside_budget".to_string(),
+                exactness: "derived".to_string(),
+                basis: "observed".to_string(),
+            }],
+        };
+
+        let summary =
+            evaluate_alert_rules_with_budget(&store, "default", 1_700_000_000, Some(&budget_distance));
+        let row = summary
+            .rows
+            .iter()
+            .find(|row| row.metric == AlertMetric::HumanFrictionOverObjective)
+            .expect("seeded friction rule");
+        assert_eq!(row.status, AlertStatus::Pending);
+        assert!((row.value - 0.03).abs() < 1e-9);
+
+        let summary =
+            evaluate_alert_rules_with_budget(&store, "default", 1_700_000_900, Some(&budget_distance));
+        assert_eq!(summary.firing_total, 1);
+        let state = load_alert_state(&store, "default");
+        assert_eq!(
+            state.rules["human_friction_over_objective"].last_notified_ts,
+            Some(1_700_000_900)
+        );
+    }
+
+    #[test]
+    fn scheduled_evaluation_without_a_projected_snapshot_keeps_friction_rule_ok() {
+        let _lock = crate::test_support::lock_env();
+        let store = InMemoryStore::default();
+
+        let summary = evaluate_alert_rules(&store, "default", 1_700_000_000);
+
+        let row = summary
+            .rows
+            .iter()
+            .find(|row| row.metric == AlertMetric::HumanFrictionOverObjective)
+            .expect("seeded friction rule");
+        assert_eq!(row.status, AlertStatus::Ok);
+        assert_eq!(summary.evaluated_at_ts, 1_700_000_000);
+        assert_eq!(load_alert_state(&store, "default").evaluated_at_ts, 1_700_000_000);
+    }
+}
@@ -0,0 +1,525 @@
+//! Operator alert rules evaluated on the hourly monitoring rollups.
+//!
+//! A rule compares one alert metric against a threshold. Windowed metrics (rate-limit hits, bans,
+//! provider outage decisions) are counted over `window_seconds` by diffing the live hourly
+//! counters against a baseline sample kept in the rule state, so windows shorter than an hour
+//! bucket still see sub-hour deltas. A windowed rule with `baseline_window_seconds` compares
+//! that count against the rate of the trailing baseline window before it, so the value is a
+//! spike ratio rather than an absolute count. The human-friction metric is the distance of the
+//! likely-human friction rate above its objective budget. A rule fires once its condition has
+//! held for `for_seconds`, and a rule sends at most one firing notification per
+//! `cooldown_seconds`.
+//!
+//! Rules are evaluated by the scheduled internal hook (`/shuma/internal/alerts/evaluate`), which
+//! the host supervisor and the edge cron call once a minute, so evaluation and notification
+//! delivery stay off the request path. The operator snapshot only reports the persisted state.
+
+mod evaluate;
+mod notify;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::challenge::KeyValueStore;
+
+pub(crate) use evaluate::evaluate_alert_rules;
+
+const ALERT_RULES_PREFIX: &str = "alert_rules:v1";
+pub(crate) const ALERT_RULES_SCHEMA_VERSION: &str = "alert_rules_v1";
+const MAX_ALERT_RULES: usize = 32;
+const MAX_RULE_ID_LEN: usize = 64;
+const MIN_WINDOW_SECONDS: u64 = 60;
+const MAX_WINDOW_SECONDS: u64 = 24 * 3600;
+const MAX_FOR_SECONDS: u64 = 24 * 3600;
+const MAX_COOLDOWN_SECONDS: u64 = 7 * 24 * 3600;
+const DEFAULT_WINDOW_SECONDS: u64 = 300;
+const DEFAULT_COOLDOWN_SECONDS: u64 = 900;
+const DEFAULT_SPIKE_BASELINE_WINDOW_SECONDS: u64 = 3600;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AlertMetric {
+    /// Live rate-limit violations inside the window.
+    RateLimitHits,
+    /// Live bans inside the window, across every ban reason.
+    Bans,
+    /// Likely-human friction rate minus its objective budget target.
+    HumanFrictionOverObjective,
+    /// Live rate-limiter decisions taken under provider outage mode inside the window.
+    ProviderOutageDecisions,
+    /// Signature-agent directory refetches inside the window that changed the published key ids.
+    DirectoryKeyRotations,
+    /// Pinned directory refreshes inside the window that no longer published any pinned key id,
+    /// so verification kept the last known-good keys.
+    PinnedDirectoryKeysMissing,
+}
+
+impl AlertMetric {
+    fn is_windowed(self) -> bool {
+        !matches!(self, AlertMetric::HumanFrictionOverObjective)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AlertStatus {
+    #[default]
+    Ok,
+    Pending,
+    Firing,
+    Disabled,
+}
+
+fn default_window_seconds() -> u64 {
+    DEFAULT_WINDOW_SECONDS
+}
+
+fn default_cooldown_seconds() -> u64 {
+    DEFAULT_COOLDOWN_SECONDS
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AlertRule {
+    pub rule_id: String,
+    pub metric: AlertMetric,
+    /// The rule's condition holds while the metric value is strictly above this threshold.
+    pub threshold: f64,
+    #[serde(default = "default_window_seconds")]
+    pub window_seconds: u64,
+    #[serde(default)]
+    pub for_seconds: u64,
+    #[serde(default = "default_cooldown_seconds")]
+    pub cooldown_seconds: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Trailing window, ending where `window_seconds` starts, whose rate is the spike baseline.
+    /// When set the rule value is the window count divided by the baseline rate scaled to the
+    /// window (floored at one event), so `threshold` is a spike ratio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_window_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct AlertRuleSet {
+    pub schema_version: String,
+    pub revision: String,
+    pub updated_at_ts: u64,
+    pub source: String,
+    pub rules: Vec<AlertRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AlertRulesUpsertRequest {
+    pub rules: Vec<AlertRule>,
+}
+
+/// Counter reading used as the start of a window: `value` is the live count of hour bucket `hour`
+/// as seen at `ts`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub(super) struct CounterSample {
+    pub ts: u64,
+    pub hour: u64,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub(crate) struct AlertRuleState {
+    #[serde(default)]
+    pub status: AlertStatus,
+    #[serde(default)]
+    pub value: f64,
+    #[serde(default)]
+    pub condition_since_ts: Option<u64>,
+    #[serde(default)]
+    pub firing_since_ts: Option<u64>,
+    #[serde(default)]
+    pub last_transition_ts: u64,
+    #[serde(default)]
+    pub last_notified_ts: Option<u64>,
+    #[serde(default)]
+    pub notifications_sent: u64,
+    #[serde(default)]
+    pub notifications_suppressed: u64,
+    #[serde(default)]
+    pub last_notification_error: Option<String>,
+    /// Whether the current firing episode has been announced, so recovery is announced too.
+    #[serde(default)]
+    firing_notified: bool,
+    #[serde(default)]
+    samples_metric: Option<AlertMetric>,
+    #[serde(default)]
+    samples: Vec<CounterSample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub(crate) struct AlertStateDocument {
+    #[serde(default)]
+    pub evaluated_at_ts: u64,
+    #[serde(default)]
+    pub rules: BTreeMap<String, AlertRuleState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct OperatorSnapshotAlertRow {
+    pub rule_id: String,
+    pub metric: AlertMetric,
+    pub status: AlertStatus,
+    pub value: f64,
+    pub threshold: f64,
+    pub window_seconds: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_window_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firing_since_ts: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_notified_ts: Option<u64>,
+    pub notifications_sent: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub(crate) struct OperatorSnapshotAlerts {
+    pub rules_revision: String,
+    pub evaluated_at_ts: u64,
+    pub firing_total: u64,
+    pub pending_total: u64,
+    pub notification_sinks: Vec<String>,
+    pub rows: Vec<OperatorSnapshotAlertRow>,
+}
+
+fn alert_rules_key(site_id: &str) -> String {
+    format!("{ALERT_RULES_PREFIX}:rules:{site_id}")
+}
+
+fn alert_state_key(site_id: &str) -> String {
+    format!("{ALERT_RULES_PREFIX}:state:{site_id}")
+}
+
+fn seeded_rule(
+    rule_id: &str,
+    metric: AlertMetric,
+    threshold: f64,
+    for_seconds: u64,
+    cooldown_seconds: u64,
+) -> AlertRule {
+    AlertRule {
+        rule_id: rule_id.to_string(),
+        metric,
+        threshold,
+        window_seconds: DEFAULT_WINDOW_SECONDS,
+        for_seconds,
+        cooldown_seconds,
+        enabled: true,
+        baseline_window_seconds: None,
+    }
+}
+
+pub(crate) fn default_alert_rules(updated_at_ts: u64) -> AlertRuleSet {
+    AlertRuleSet {
+        schema_version: ALERT_RULES_SCHEMA_VERSION.to_string(),
+        revision: format!("rev-{}", updated_at_ts),
+        updated_at_ts,
+        source: "seeded_default_rules".to_string(),
+        rules: vec![
+            seeded_rule("rate_limit_hits_5m", AlertMetric::RateLimitHits, 100.0, 0, 900),
+            AlertRule {
+                baseline_window_seconds: Some(DEFAULT_SPIKE_BASELINE_WINDOW_SECONDS),
+                ..seeded_rule("ban_spike_5m", AlertMetric::Bans, 3.0, 0, 900)
+            },
+            seeded_rule(
+                "human_friction_over_objective",
+                AlertMetric::HumanFrictionOverObjective,
+                0.0,
+                900,
+                3600,
+            ),
+            seeded_rule(
+                "provider_outage_mode_engaged",
+                AlertMetric::ProviderOutageDecisions,
+                0.0,
+                0,
+                900,
+            ),
+            // An hour covers the default directory cache TTL, so the rule keeps firing while
+            // every refresh still misses the pinned keys.
+            AlertRule {
+                window_seconds: 3600,
+                ..seeded_rule(
+                    "pinned_directory_keys_missing",
+                    AlertMetric::PinnedDirectoryKeysMissing,
+                    0.0,
+                    0,
+                    3600,
+                )
+            },
+        ],
+    }
+}
+
+fn validate_rule_id(rule_id: &str) -> Result<(), String> {
+    if rule_id.is_empty() || rule_id.len() > MAX_RULE_ID_LEN {
+        return Err(format!(
+            "rule_id must be 1-{} characters",
+            MAX_RULE_ID_LEN
+        ));
+    }
+    if !rule_id
+        .chars()
+        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_' || ch == '-')
+    {
+        return Err(format!(
+            "rule_id {} must use lowercase letters, digits, '_' or '-'",
+            rule_id
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_alert_rules(rule_set: &AlertRuleSet) -> Result<(), String> {
+    if rule_set.schema_version != ALERT_RULES_SCHEMA_VERSION {
+        return Err(format!(
+            "schema_version must be {}",
+            ALERT_RULES_SCHEMA_VERSION
+        ));
+    }
+    if rule_set.rules.len() > MAX_ALERT_RULES {
+        return Err(format!("at most {} alert rules are allowed", MAX_ALERT_RULES));
+    }
+    let mut seen = BTreeSet::new();
+    for rule in &rule_set.rules {
+        validate_rule_id(rule.rule_id.as_str())?;
+        if !seen.insert(rule.rule_id.as_str()) {
+            return Err(format!("duplicate rule_id {}", rule.rule_id));
+        }
+        if !rule.threshold.is_finite() {
+            return Err(format!("rule {} threshold must be finite", rule.rule_id));
+        }
+        if rule.metric.is_windowed() && rule.threshold < 0.0 {
+            return Err(format!(
+                "rule {} threshold must not be negative for a counted metric",
+                rule.rule_id
+            ));
+        }
+        if !(MIN_WINDOW_SECONDS..=MAX_WINDOW_SECONDS).contains(&rule.window_seconds) {
+            return Err(format!(
+                "rule {} window_seconds must be between {} and {}",
+                rule.rule_id, MIN_WINDOW_SECONDS, MAX_WINDOW_SECONDS
+            ));
+        }
+        if let Some(baseline_window_seconds) = rule.baseline_window_seconds {
+            if !rule.metric.is_windowed() {
+                return Err(format!(
+                    "rule {} baseline_window_seconds is only valid for a counted metric",
+                    rule.rule_id
+                ));
+            }
+            if !(MIN_WINDOW_SECONDS..=MAX_WINDOW_SECONDS).contains(&baseline_window_seconds) {
+                return Err(format!(
+                    "rule {} baseline_window_seconds must be between {} and {}",
+                    rule.rule_id, MIN_WINDOW_SECONDS, MAX_WINDOW_SECONDS
+                ));
+            }
+        }
+        if rule.for_seconds > MAX_FOR_SECONDS {
+            return Err(format!(
+                "rule {} for_seconds must be at most {}",
+                rule.rule_id, MAX_FOR_SECONDS
+            ));
+        }
+        if rule.cooldown_seconds > MAX_COOLDOWN_SECONDS {
+            return Err(format!(
+                "rule {} cooldown_seconds must be at most {}",
+                rule.rule_id, MAX_COOLDOWN_SECONDS
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn alert_rules_from_request(
+    request: AlertRulesUpsertRequest,
+    updated_at_ts: u64,
+    source: &str,
+) -> Result<AlertRuleSet, String> {
+    let rule_set = AlertRuleSet {
+        schema_version: ALERT_RULES_SCHEMA_VERSION.to_string(),
+        revision: format!("rev-{}", updated_at_ts),
+        updated_at_ts,
+        source: source.to_string(),
+        rules: request.rules,
+    };
+    validate_alert_rules(&rule_set)?;
+    Ok(rule_set)
+}
+
+pub(crate) fn load_alert_rules<S: KeyValueStore>(store: &S, site_id: &str) -> Option<AlertRuleSet> {
+    let bytes = store.get(alert_rules_key(site_id).as_str()).ok().flatten()?;
+    let rule_set = serde_json::from_slice::<AlertRuleSet>(bytes.as_slice()).ok()?;
+    validate_alert_rules(&rule_set).ok()?;
+    Some(rule_set)
+}
+
+pub(crate) fn load_or_seed_alert_rules<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    generated_at_ts: u64,
+) -> AlertRuleSet {
+    if let Some(rule_set) = load_alert_rules(store, site_id) {
+        return rule_set;
+    }
+    let rule_set = default_alert_rules(generated_at_ts);
+    // Evaluation keeps working from the seeded rules even if this first write fails.
+    let _ = save_alert_rules(store, site_id, &rule_set);
+    rule_set
+}
+
+pub(crate) fn save_alert_rules<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    rule_set: &AlertRuleSet,
+) -> Result<(), ()> {
+    validate_alert_rules(rule_set).map_err(|_| ())?;
+    let payload = serde_json::to_vec(rule_set).map_err(|_| ())?;
+    store
+        .set(alert_rules_key(site_id).as_str(), payload.as_slice())
+        .map_err(|_| ())
+}
+
+pub(crate) fn load_alert_state<S: KeyValueStore>(store: &S, site_id: &str) -> AlertStateDocument {
+    store
+        .get(alert_state_key(site_id).as_str())
+        .ok()
+        .flatten()
+        .and_then(|bytes| serde_json::from_slice::<AlertStateDocument>(bytes.as_slice()).ok())
+        .unwrap_or_default()
+}
+
+fn save_alert_state<S: KeyValueStore>(store: &S, site_id: &str, state: &AlertStateDocument) {
+    let key = alert_state_key(site_id);
+    let Ok(payload) = serde_json::to_vec(state) else {
+        eprintln!("[alert_rules] failed serializing alert state site={}", site_id);
+        return;
+    };
+    if store.set(key.as_str(), payload.as_slice()).is_err() {
+        eprintln!("[alert_rules] failed persisting alert state site={}", site_id);
+    }
+}
+
+/// Bounded per-rule view of the persisted alert state, shared by the admin API and the
+/// operator snapshot.
+pub(crate) fn alert_summary(
+    rule_set: &AlertRuleSet,
+    state: &AlertStateDocument,
+) -> OperatorSnapshotAlerts {
+    let rows: Vec<OperatorSnapshotAlertRow> = rule_set
+        .rules
+        .iter()
+        .map(|rule| {
+            let rule_state = state.rules.get(rule.rule_id.as_str());
+            let status = if !rule.enabled {
+                AlertStatus::Disabled
+            } else {
+                rule_state.map(|entry| entry.status).unwrap_or_default()
+            };
+            OperatorSnapshotAlertRow {
+                rule_id: rule.rule_id.clone(),
+                metric: rule.metric,
+                status,
+                value: rule_state.map(|entry| entry.value).unwrap_or(0.0),
+                threshold: rule.threshold,
+                window_seconds: rule.window_seconds,
+                baseline_window_seconds: rule.baseline_window_seconds,
+                firing_since_ts: rule_state.and_then(|entry| entry.firing_since_ts),
+                last_notified_ts: rule_state.and_then(|entry| entry.last_notified_ts),
+                notifications_sent: rule_state
+                    .map(|entry| entry.notifications_sent)
+                    .unwrap_or(0),
+            }
+        })
+        .collect();
+    OperatorSnapshotAlerts {
+        rules_revision: rule_set.revision.clone(),
+        evaluated_at_ts: state.evaluated_at_ts,
+        firing_total: rows
+            .iter()
+            .filter(|row| row.status == AlertStatus::Firing)
+            .count() as u64,
+        pending_total: rows
+            .iter()
+            .filter(|row| row.status == AlertStatus::Pending)
+            .count() as u64,
+        notification_sinks: notify::configured_sink_labels(),
+        rows,
+    }
+}
+
+/// Read-only view of the last scheduled evaluation, used by the operator snapshot so that
+/// rebuilding the snapshot neither evaluates rules nor sends notifications.
+pub(crate) fn persisted_alert_summary<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    generated_at_ts: u64,
+) -> OperatorSnapshotAlerts {
+    let rule_set =
+        load_alert_rules(store, site_id).unwrap_or_else(|| default_alert_rules(generated_at_ts));
+    alert_summary(&rule_set, &load_alert_state(store, site_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStore;
+
+    #[test]
+    fn load_or_seed_alert_rules_persists_default_rules_once() {
+        let store = InMemoryStore::default();
+
+        let seeded = load_or_seed_alert_rules(&store, "default", 1_700_000_000);
+        let loaded = load_or_seed_alert_rules(&store, "default", 1_700_000_100);
+
+        assert_eq!(seeded.revision, "rev-1700000000");
+        assert_eq!(loaded, seeded);
+        assert_eq!(seeded.rules.len(), 5);
+    }
+
+    #[test]
+    fn validate_alert_rules_rejects_duplicates_and_out_of_range_windows() {
+        let mut rule_set = default_alert_rules(1);
+        rule_set.rules.push(rule_set.rules[0].clone());
+        assert!(validate_alert_rules(&rule_set)
+            .unwrap_err()
+            .contains("duplicate rule_id"));
+
+        let mut rule_set = default_alert_rules(1);
+        rule_set.rules[0].window_seconds = 30;
+        assert!(validate_alert_rules(&rule_set)
+            .unwrap_err()
+            .contains("window_seconds"));
+
+        let mut rule_set = default_alert_rules(1);
+        rule_set.rules[0].rule_id = "Rate Limit".to_string();
+        assert!(validate_alert_rules(&rule_set).is_err());
+
+        let mut rule_set = default_alert_rules(1);
+        rule_set.rules[2].baseline_window_seconds = Some(3600);
+        assert!(validate_alert_rules(&rule_set)
+            .unwrap_err()
+            .contains("baseline_window_seconds"));
+    }
+
+    #[test]
+    fn persisted_alert_summary_reads_state_without_seeding_rules() {
+        let store = InMemoryStore::default();
+
+        let summary = persisted_alert_summary(&store, "default", 1_700_000_000);
+
+        assert_eq!(summary.rows.len(), 5);
+        assert_eq!(summary.evaluated_at_ts, 0);
+        assert!(load_alert_rules(&store, "default").is_none());
+    }
+}
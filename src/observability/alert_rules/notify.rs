@@ -0,0 +1,219 @@
+//! Alert notification sinks.
+//!
+//! The webhook sink posts one signed JSON document per notification, using the same
+//! `sha256=<hex>` HMAC scheme as the event-export webhook. The email-relay sink posts
+//! `{to, subject, text}` JSON to an operator-run HTTP mail relay, because the Spin component
+//! cannot speak SMTP. Delivery is attempted once; a failure is kept on the rule state rather than
+//! retried, since the next transition produces a fresh notification.
+
+use serde::Serialize;
+
+use super::{AlertMetric, AlertRule, AlertRuleState};
+use crate::observability::event_export::{format, transport};
+
+const WEBHOOK_URL_ENV: &str = "SHUMA_ALERT_WEBHOOK_URL";
+const WEBHOOK_SECRET_ENV: &str = "SHUMA_ALERT_WEBHOOK_SECRET";
+const EMAIL_RELAY_URL_ENV: &str = "SHUMA_ALERT_EMAIL_RELAY_URL";
+const EMAIL_RELAY_TOKEN_ENV: &str = "SHUMA_ALERT_EMAIL_RELAY_TOKEN";
+const EMAIL_TO_ENV: &str = "SHUMA_ALERT_EMAIL_TO";
+const WEBHOOK_TIMESTAMP_HEADER: &str = "X-Shuma-Alert-Timestamp";
+const WEBHOOK_SIGNATURE_HEADER: &str = "X-Shuma-Alert-Signature";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum AlertNotificationKind {
+    Firing,
+    Resolved,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct AlertNotification {
+    pub site_id: String,
+    pub rule_id: String,
+    pub metric: AlertMetric,
+    pub status: AlertNotificationKind,
+    pub value: f64,
+    pub threshold: f64,
+    pub window_seconds: u64,
+    pub for_seconds: u64,
+    pub ts: u64,
+}
+
+impl AlertNotification {
+    pub(super) fn new(
+        site_id: &str,
+        rule: &AlertRule,
+        state: &AlertRuleState,
+        status: AlertNotificationKind,
+        ts: u64,
+    ) -> Self {
+        Self {
+            site_id: site_id.to_string(),
+            rule_id: rule.rule_id.clone(),
+            metric: rule.metric,
+            status,
+            value: state.value,
+            threshold: rule.threshold,
+            window_seconds: rule.window_seconds,
+            for_seconds: rule.for_seconds,
+            ts,
+        }
+    }
+
+    fn status_label(&self) -> &'static str {
+        match self.status {
+            AlertNotificationKind::Firing => "FIRING",
+            AlertNotificationKind::Resolved => "RESOLVED",
+        }
+    }
+}
+
+enum AlertSink {
+    Webhook {
+        url: String,
+        secret: String,
+    },
+    EmailRelay {
+        url: String,
+        token: Option<String>,
+        recipients: Vec<String>,
+    },
+}
+
+impl AlertSink {
+    fn label(&self) -> &'static str {
+        match self {
+            AlertSink::Webhook { .. } => "webhook",
+            AlertSink::EmailRelay { .. } => "email_relay",
+        }
+    }
+}
+
+fn configured_sinks() -> Vec<AlertSink> {
+    let mut sinks = Vec::new();
+    if let Some(url) = crate::config::runtime_var_trimmed_optional(WEBHOOK_URL_ENV) {
+        match crate::config::runtime_var_trimmed_optional(WEBHOOK_SECRET_ENV) {
+            Some(secret) => sinks.push(AlertSink::Webhook { url, secret }),
+            None => eprintln!(
+                "[alert_rules] ignoring {}: {} is required to sign alert notifications",
+                WEBHOOK_URL_ENV, WEBHOOK_SECRET_ENV
+            ),
+        }
+    }
+    if let Some(url) = crate::config::runtime_var_trimmed_optional(EMAIL_RELAY_URL_ENV) {
+        let recipients: Vec<String> = crate::config::runtime_var_trimmed_optional(EMAIL_TO_ENV)
+            .map(|raw| {
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|address| !address.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if recipients.is_empty() {
+            eprintln!(
+                "[alert_rules] ignoring {}: {} must name at least one recipient",
+                EMAIL_RELAY_URL_ENV, EMAIL_TO_ENV
+            );
+        } else {
+            sinks.push(AlertSink::EmailRelay {
+                url,
+                token: crate::config::runtime_var_trimmed_optional(EMAIL_RELAY_TOKEN_ENV),
+                recipients,
+            });
+        }
+    }
+    sinks
+}
+
+pub(super) fn configured_sink_labels() -> Vec<String> {
+    configured_sinks()
+        .iter()
+        .map(|sink| sink.label().to_string())
+        .collect()
+}
+
+fn email_subject(notification: &AlertNotification) -> String {
+    format!(
+        "[shuma-gorath] {} {} ({})",
+        notification.status_label(),
+        notification.rule_id,
+        notification.site_id
+    )
+}
+
+fn email_text(notification: &AlertNotification) -> String {
+    format!(
+        "Alert rule {} is {} for site {}.\nvalue: {}\nthreshold: {}\nwindow_seconds: {}\nfor_seconds: {}\nevaluated_at: {}\n",
+        notification.rule_id,
+        notification.status_label().to_ascii_lowercase(),
+        notification.site_id,
+        notification.value,
+        notification.threshold,
+        notification.window_seconds,
+        notification.for_seconds,
+        notification.ts
+    )
+}
+
+fn send(sink: &AlertSink, notification: &AlertNotification) -> Result<(), String> {
+    match sink {
+        AlertSink::Webhook { url, secret } => {
+            let body = serde_json::to_vec(notification).map_err(|err| err.to_string())?;
+            let signature =
+                format::webhook_signature(secret.as_str(), notification.ts, body.as_slice());
+            let headers = vec![
+                (
+                    WEBHOOK_TIMESTAMP_HEADER.to_string(),
+                    notification.ts.to_string(),
+                ),
+                (WEBHOOK_SIGNATURE_HEADER.to_string(), signature),
+            ];
+            transport::post(url.as_str(), "application/json", &headers, body)
+        }
+        AlertSink::EmailRelay {
+            url,
+            token,
+            recipients,
+        } => {
+            let body = serde_json::json!({
+                "to": recipients,
+                "subject": email_subject(notification),
+                "text": email_text(notification),
+            })
+            .to_string()
+            .into_bytes();
+            let headers: Vec<(String, String)> = token
+                .iter()
+                .map(|token| ("Authorization".to_string(), format!("Bearer {}", token)))
+                .collect();
+            transport::post(url.as_str(), "application/json", &headers, body)
+        }
+    }
+}
+
+pub(super) struct DeliveryOutcome {
+    pub delivered: usize,
+    pub errors: Vec<String>,
+}
+
+pub(super) fn deliver(notification: &AlertNotification) -> DeliveryOutcome {
+    let mut outcome = DeliveryOutcome {
+        delivered: 0,
+        errors: Vec::new(),
+    };
+    for sink in configured_sinks() {
+        let label = sink.label();
+        match send(&sink, notification) {
+            Ok(()) => outcome.delivered += 1,
+            Err(err) => {
+                eprintln!(
+                    "[alert_rules] sink={} failed delivering rule={} error={}",
+                    label, notification.rule_id, err
+                );
+                outcome.errors.push(format!("{}:{}", label, err));
+            }
+        }
+    }
+    outcome
+}
@@ -0,0 +1,160 @@
+//! Wire formats for exported event records.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub(super) const WEBHOOK_TIMESTAMP_HEADER: &str = "X-Shuma-Export-Timestamp";
+pub(super) const WEBHOOK_SIGNATURE_HEADER: &str = "X-Shuma-Export-Signature";
+const APP_NAME: &str = "shuma-gorath";
+/// RFC 5424 facility `local0`.
+const SYSLOG_FACILITY: u8 = 16;
+const SYSLOG_SEVERITY_WARNING: u8 = 4;
+const SYSLOG_SEVERITY_NOTICE: u8 = 5;
+const SYSLOG_SEVERITY_INFORMATIONAL: u8 = 6;
+
+fn event_type(event: &serde_json::Value) -> &str {
+    event.get("event").and_then(|value| value.as_str()).unwrap_or("")
+}
+
+fn event_ts(event: &serde_json::Value) -> u64 {
+    event.get("ts").and_then(|value| value.as_u64()).unwrap_or(0)
+}
+
+fn syslog_severity(event: &serde_json::Value) -> u8 {
+    match event_type(event) {
+        "Ban" | "Block" => SYSLOG_SEVERITY_WARNING,
+        "Unban" | "AdminAction" => SYSLOG_SEVERITY_NOTICE,
+        _ => SYSLOG_SEVERITY_INFORMATIONAL,
+    }
+}
+
+fn rfc3339_timestamp(ts: u64) -> Option<String> {
+    let datetime = time::OffsetDateTime::from_unix_timestamp(i64::try_from(ts).ok()?).ok()?;
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        datetime.year(),
+        u8::from(datetime.month()),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second()
+    ))
+}
+
+/// RFC 5424 message with the JSON record as MSG. Hostname and proc id are nil because the
+/// component does not know which edge host it is running on.
+pub(super) fn syslog_message(event: &serde_json::Value) -> String {
+    let msg_id = match event_type(event) {
+        "" => "-".to_string(),
+        kind => kind.to_ascii_lowercase(),
+    };
+    format!(
+        "<{}>1 {} - {} - {} - {}",
+        SYSLOG_FACILITY * 8 + syslog_severity(event),
+        rfc3339_timestamp(event_ts(event)).unwrap_or_else(|| "-".to_string()),
+        APP_NAME,
+        msg_id,
+        event
+    )
+}
+
+pub(super) fn json_lines_body(events: &[serde_json::Value]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for event in events {
+        if let Ok(line) = serde_json::to_vec(event) {
+            body.extend_from_slice(line.as_slice());
+            body.push(b'\n');
+        }
+    }
+    body
+}
+
+/// `sha256=<hex>` HMAC over `"<timestamp>.<body>"`, so receivers can reject replayed batches.
+pub(crate) fn webhook_signature(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", digest)
+}
+
+fn otlp_severity(event: &serde_json::Value) -> (u8, &'static str) {
+    match syslog_severity(event) {
+        SYSLOG_SEVERITY_WARNING => (13, "WARN"),
+        _ => (9, "INFO"),
+    }
+}
+
+fn otlp_string_attribute(key: &str, value: &str) -> serde_json::Value {
+    serde_json::json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// OTLP/HTTP JSON `ExportLogsServiceRequest` with one log record per event.
+pub(super) fn otlp_logs_body(events: &[serde_json::Value]) -> Vec<u8> {
+    let log_records: Vec<serde_json::Value> = events
+        .iter()
+        .map(|event| {
+            let (severity_number, severity_text) = otlp_severity(event);
+            let mut attributes = vec![otlp_string_attribute("event.type", event_type(event))];
+            for field in ["ip", "reason", "outcome", "outcome_code"] {
+                if let Some(value) = event.get(field).and_then(|value| value.as_str()) {
+                    attributes.push(otlp_string_attribute(
+                        format!("shuma.{}", field).as_str(),
+                        value,
+                    ));
+                }
+            }
+            serde_json::json!({
+                "timeUnixNano": (u128::from(event_ts(event)) * 1_000_000_000).to_string(),
+                "severityNumber": severity_number,
+                "severityText": severity_text,
+                "body": { "stringValue": event.to_string() },
+                "attributes": attributes,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "resourceLogs": [{
+            "resource": { "attributes": [otlp_string_attribute("service.name", APP_NAME)] },
+            "scopeLogs": [{
+                "scope": { "name": "shuma_gorath.event_log" },
+                "logRecords": log_records,
+            }],
+        }],
+    })
+    .to_string()
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn otlp_body_maps_event_fields_to_log_record_attributes() {
+        let event = serde_json::json!({
+            "ts": 2,
+            "event": "Challenge",
+            "ip": "203.0.113.0/24",
+            "reason": "botness"
+        });
+        let body: serde_json::Value =
+            serde_json::from_slice(otlp_logs_body(&[event]).as_slice()).unwrap();
+        let record = &body["resourceLogs"][0]["scopeLogs"][0]["logRecords"][0];
+        assert_eq!(record["timeUnixNano"], "2000000000");
+        assert_eq!(record["severityNumber"], 9);
+        assert_eq!(record["attributes"][0]["value"]["stringValue"], "Challenge");
+        assert_eq!(record["attributes"][1]["key"], "shuma.ip");
+        assert_eq!(
+            body["resourceLogs"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "shuma-gorath"
+        );
+    }
+}
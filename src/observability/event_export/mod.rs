@@ -0,0 +1,618 @@
+//! Forwards persisted event-log records to external sinks (syslog, signed JSON-lines webhook,
+//! OTLP/HTTP logs).
+//!
+//! Sinks are configured with env-only keys because their endpoints must also be allowed in the
+//! Spin manifest's `allowed_outbound_hosts`. Each sink has its own KV outbox, so a failing sink
+//! never holds back the others. Persisting a record only queues it. Batches are sent from the
+//! post-response flush phase, so outbound I/O never sits on the event-log write path. A failed
+//! batch is retried with exponential backoff. After `MAX_DELIVERY_ATTEMPTS` it is dropped and
+//! counted as dead-lettered.
+
+pub(super) mod format;
+pub(super) mod transport;
+
+use serde::{Deserialize, Serialize};
+
+const OUTBOX_KEY_PREFIX: &str = "event_export:v1:outbox";
+const SYSLOG_TARGET_ENV: &str = "SHUMA_EVENT_EXPORT_SYSLOG_TARGET";
+const WEBHOOK_URL_ENV: &str = "SHUMA_EVENT_EXPORT_WEBHOOK_URL";
+const WEBHOOK_SECRET_ENV: &str = "SHUMA_EVENT_EXPORT_WEBHOOK_SECRET";
+const OTLP_ENDPOINT_ENV: &str = "SHUMA_EVENT_EXPORT_OTLP_ENDPOINT";
+const OTLP_HEADERS_ENV: &str = "SHUMA_EVENT_EXPORT_OTLP_HEADERS";
+/// A batch is sent as soon as this many events are pending.
+const BATCH_MAX_EVENTS: usize = 50;
+/// A partial batch is sent once its oldest event has waited this long.
+const FLUSH_INTERVAL_SECONDS: u64 = 15;
+/// Pending events beyond this bound are dead-lettered oldest-first instead of growing the outbox.
+const PENDING_MAX_EVENTS: usize = 1000;
+const MAX_DELIVERY_ATTEMPTS: u32 = 6;
+const RETRY_BACKOFF_BASE_SECONDS: u64 = 5;
+const RETRY_BACKOFF_MAX_SECONDS: u64 = 300;
+
+pub(crate) const EVENT_EXPORT_SINKS: [&str; 3] = ["syslog", "webhook", "otlp"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum SyslogProtocol {
+    Udp,
+    Tcp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EventSink {
+    Syslog {
+        protocol: SyslogProtocol,
+        address: String,
+    },
+    Webhook {
+        url: String,
+        secret: String,
+    },
+    Otlp {
+        endpoint: String,
+        headers: Vec<(String, String)>,
+    },
+}
+
+impl EventSink {
+    fn label(&self) -> &'static str {
+        match self {
+            EventSink::Syslog { .. } => "syslog",
+            EventSink::Webhook { .. } => "webhook",
+            EventSink::Otlp { .. } => "otlp",
+        }
+    }
+}
+
+fn parse_syslog_target(raw: &str) -> Option<EventSink> {
+    let (scheme, address) = raw.split_once("://")?;
+    let protocol = match scheme.to_ascii_lowercase().as_str() {
+        "udp" => SyslogProtocol::Udp,
+        "tcp" => SyslogProtocol::Tcp,
+        _ => return None,
+    };
+    let address = address.trim_end_matches('/');
+    if address.is_empty() || !address.contains(':') {
+        return None;
+    }
+    Some(EventSink::Syslog {
+        protocol,
+        address: address.to_string(),
+    })
+}
+
+/// Parse `name=value` pairs in the `OTEL_EXPORTER_OTLP_HEADERS` comma-separated format.
+fn parse_header_list(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+            let name = name.trim();
+            let value = value.trim();
+            (!name.is_empty() && !value.is_empty()).then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Startup check for the syslog target, run with the other env-only guardrails. The Spin
+/// component cannot open sockets, so a syslog sink is rejected there instead of dead-lettering
+/// every batch at runtime.
+pub(crate) fn validate_syslog_target_env() -> Result<(), String> {
+    validate_syslog_target(
+        crate::config::runtime_var_trimmed_optional(SYSLOG_TARGET_ENV),
+        transport::SYSLOG_SOCKETS_AVAILABLE,
+    )
+}
+
+fn validate_syslog_target(raw: Option<String>, sockets_available: bool) -> Result<(), String> {
+    let Some(raw) = raw else {
+        return Ok(());
+    };
+    if !sockets_available {
+        return Err(format!(
+            "Unsupported env var {}={}: the Spin runtime cannot open syslog sockets (use {}, {}, or an HTTP-to-syslog relay)",
+            SYSLOG_TARGET_ENV, raw, WEBHOOK_URL_ENV, OTLP_ENDPOINT_ENV
+        ));
+    }
+    if parse_syslog_target(raw.as_str()).is_none() {
+        return Err(format!(
+            "Invalid syslog target env var {}={} (expected udp://host:port or tcp://host:port)",
+            SYSLOG_TARGET_ENV, raw
+        ));
+    }
+    Ok(())
+}
+
+fn configured_sinks() -> Vec<EventSink> {
+    let mut sinks = Vec::new();
+    if let Some(raw) = crate::config::runtime_var_trimmed_optional(SYSLOG_TARGET_ENV) {
+        match parse_syslog_target(raw.as_str()) {
+            Some(sink) => sinks.push(sink),
+            None => eprintln!(
+                "[event_export] ignoring {}: expected udp://host:port or tcp://host:port",
+                SYSLOG_TARGET_ENV
+            ),
+        }
+    }
+    if let Some(url) = crate::config::runtime_var_trimmed_optional(WEBHOOK_URL_ENV) {
+        match crate::config::runtime_var_trimmed_optional(WEBHOOK_SECRET_ENV) {
+            Some(secret) => sinks.push(EventSink::Webhook { url, secret }),
+            None => eprintln!(
+                "[event_export] ignoring {}: {} is required to sign webhook batches",
+                WEBHOOK_URL_ENV, WEBHOOK_SECRET_ENV
+            ),
+        }
+    }
+    if let Some(endpoint) = crate::config::runtime_var_trimmed_optional(OTLP_ENDPOINT_ENV) {
+        let headers = crate::config::runtime_var_trimmed_optional(OTLP_HEADERS_ENV)
+            .map(|raw| parse_header_list(raw.as_str()))
+            .unwrap_or_default();
+        sinks.push(EventSink::Otlp { endpoint, headers });
+    }
+    sinks
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RetryBatch {
+    events: Vec<serde_json::Value>,
+    attempts: u32,
+    next_attempt_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SinkOutbox {
+    #[serde(default)]
+    pending: Vec<serde_json::Value>,
+    #[serde(default)]
+    oldest_pending_ts: u64,
+    #[serde(default)]
+    retry: Option<RetryBatch>,
+    #[serde(default)]
+    delivered_total: u64,
+    #[serde(default)]
+    dead_letter_total: u64,
+    #[serde(default)]
+    last_success_ts: u64,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+/// Delivery counters for one sink, rendered as Prometheus metrics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SinkCounters {
+    pub delivered_total: u64,
+    pub dead_letter_total: u64,
+    pub pending_events: u64,
+}
+
+fn outbox_key(sink: &str) -> String {
+    format!("{}:{}", OUTBOX_KEY_PREFIX, sink)
+}
+
+fn load_outbox(store: &impl crate::challenge::KeyValueStore, sink: &str) -> SinkOutbox {
+    store
+        .get(outbox_key(sink).as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<SinkOutbox>(raw.as_slice()).ok())
+        .unwrap_or_default()
+}
+
+fn save_outbox(store: &impl crate::challenge::KeyValueStore, sink: &str, outbox: &SinkOutbox) {
+    let key = outbox_key(sink);
+    let payload = match serde_json::to_vec(outbox) {
+        Ok(payload) => payload,
+        Err(_) => {
+            eprintln!("[event_export] serialization error for outbox {}", key);
+            return;
+        }
+    };
+    if store.set(key.as_str(), payload.as_slice()).is_err() {
+        eprintln!("[event_export] KV error writing outbox {}", key);
+    }
+}
+
+fn retry_backoff_seconds(attempts: u32) -> u64 {
+    let exponent = attempts.saturating_sub(1).min(16);
+    RETRY_BACKOFF_BASE_SECONDS
+        .saturating_mul(1u64 << exponent)
+        .min(RETRY_BACKOFF_MAX_SECONDS)
+}
+
+fn deliver(sink: &EventSink, events: &[serde_json::Value], now: u64) -> Result<(), String> {
+    match sink {
+        EventSink::Syslog { protocol, address } => {
+            let messages: Vec<String> = events.iter().map(format::syslog_message).collect();
+            transport::send_syslog(protocol, address.as_str(), messages.as_slice())
+        }
+        EventSink::Webhook { url, secret } => {
+            let body = format::json_lines_body(events);
+            let signature = format::webhook_signature(secret.as_str(), now, body.as_slice());
+            let headers = vec![
+                (format::WEBHOOK_TIMESTAMP_HEADER.to_string(), now.to_string()),
+                (format::WEBHOOK_SIGNATURE_HEADER.to_string(), signature),
+            ];
+            transport::post(url.as_str(), "application/x-ndjson", &headers, body)
+        }
+        EventSink::Otlp { endpoint, headers } => transport::post(
+            endpoint.as_str(),
+            "application/json",
+            headers,
+            format::otlp_logs_body(events),
+        ),
+    }
+}
+
+fn attempt_batch(
+    sink: &EventSink,
+    outbox: &mut SinkOutbox,
+    events: Vec<serde_json::Value>,
+    previous_attempts: u32,
+    now: u64,
+) {
+    match deliver(sink, events.as_slice(), now) {
+        Ok(()) => {
+            outbox.delivered_total = outbox.delivered_total.saturating_add(events.len() as u64);
+            outbox.last_success_ts = now;
+            outbox.last_error = None;
+        }
+        Err(err) => {
+            let attempts = previous_attempts.saturating_add(1);
+            outbox.last_error = Some(err.clone());
+            if attempts >= MAX_DELIVERY_ATTEMPTS {
+                outbox.dead_letter_total =
+                    outbox.dead_letter_total.saturating_add(events.len() as u64);
+                eprintln!(
+                    "[event_export] sink={} dead-lettered {} events after {} attempts error={}",
+                    sink.label(),
+                    events.len(),
+                    attempts,
+                    err
+                );
+            } else {
+                outbox.retry = Some(RetryBatch {
+                    events,
+                    attempts,
+                    next_attempt_at: now.saturating_add(retry_backoff_seconds(attempts)),
+                });
+            }
+        }
+    }
+}
+
+/// Send at most one batch for this sink: a due retry takes priority over new pending events.
+/// Returns whether a batch was attempted, i.e. whether the outbox changed.
+fn flush_if_due(sink: &EventSink, outbox: &mut SinkOutbox, now: u64) -> bool {
+    if let Some(retry) = outbox.retry.take() {
+        if now < retry.next_attempt_at {
+            outbox.retry = Some(retry);
+            return false;
+        }
+        attempt_batch(sink, outbox, retry.events, retry.attempts, now);
+        return true;
+    }
+    if outbox.pending.is_empty() {
+        return false;
+    }
+    let batch_full = outbox.pending.len() >= BATCH_MAX_EVENTS;
+    let interval_elapsed = now.saturating_sub(outbox.oldest_pending_ts) >= FLUSH_INTERVAL_SECONDS;
+    if !batch_full && !interval_elapsed {
+        return false;
+    }
+    let take = outbox.pending.len().min(BATCH_MAX_EVENTS);
+    let batch: Vec<serde_json::Value> = outbox.pending.drain(..take).collect();
+    outbox.oldest_pending_ts = if outbox.pending.is_empty() { 0 } else { now };
+    attempt_batch(sink, outbox, batch, 0, now);
+    true
+}
+
+fn enqueue(outbox: &mut SinkOutbox, event: serde_json::Value, now: u64) {
+    if outbox.pending.is_empty() {
+        outbox.oldest_pending_ts = now;
+    }
+    outbox.pending.push(event);
+    if outbox.pending.len() > PENDING_MAX_EVENTS {
+        let overflow = outbox.pending.len() - PENDING_MAX_EVENTS;
+        outbox.pending.drain(..overflow);
+        outbox.dead_letter_total = outbox.dead_letter_total.saturating_add(overflow as u64);
+    }
+}
+
+/// Queue an already-sanitized, pseudonymized event record for every configured sink. Delivery
+/// happens later in `flush_due_batches`.
+pub(crate) fn export_event(
+    store: &impl crate::challenge::KeyValueStore,
+    event: serde_json::Value,
+    now: u64,
+) {
+    for sink in configured_sinks() {
+        let label = sink.label();
+        let mut outbox = load_outbox(store, label);
+        enqueue(&mut outbox, event.clone(), now);
+        save_outbox(store, label, &outbox);
+    }
+}
+
+/// Send each configured sink's due batch, if any. Called from the post-response flush phase; the
+/// outbox is only rewritten when a batch was actually attempted.
+pub(crate) fn flush_due_batches(store: &impl crate::challenge::KeyValueStore, now: u64) {
+    for sink in configured_sinks() {
+        let label = sink.label();
+        let mut outbox = load_outbox(store, label);
+        if flush_if_due(&sink, &mut outbox, now) {
+            save_outbox(store, label, &outbox);
+        }
+    }
+}
+
+pub(crate) fn sink_counters(
+    store: &impl crate::challenge::KeyValueStore,
+    sink: &str,
+) -> SinkCounters {
+    let outbox = load_outbox(store, sink);
+    let retrying = outbox
+        .retry
+        .as_ref()
+        .map(|retry| retry.events.len())
+        .unwrap_or(0);
+    SinkCounters {
+        delivered_total: outbox.delivered_total,
+        dead_letter_total: outbox.dead_letter_total,
+        pending_events: (outbox.pending.len() + retrying) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStore;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    struct CapturedRequest {
+        request_line: String,
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl CapturedRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Local HTTP stand-in that answers each connection with the next status in `statuses`.
+    fn spawn_http_stand_in(statuses: Vec<u16>) -> (String, mpsc::Receiver<CapturedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stand-in");
+        let address = listener.local_addr().expect("stand-in address");
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for status in statuses {
+                let Ok((stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_string();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.push((name.trim().to_string(), value.trim().to_string()));
+                    }
+                }
+                let length = headers
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    .and_then(|(_, value)| value.parse::<usize>().ok())
+                    .unwrap_or(0);
+                let mut body = vec![0u8; length];
+                reader.read_exact(&mut body).unwrap();
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Stand-In\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+                let _ = sender.send(CapturedRequest {
+                    request_line: request_line.trim_end().to_string(),
+                    headers,
+                    body,
+                });
+            }
+        });
+        (format!("http://{}/ingest/events", address), receiver)
+    }
+
+    fn sample_event(ts: u64) -> serde_json::Value {
+        serde_json::json!({
+            "ts": ts,
+            "event": "Ban",
+            "ip": "198.51.100.0/24",
+            "reason": "honeypot",
+            "outcome": "banned"
+        })
+    }
+
+    fn clear_export_env() {
+        for name in [
+            SYSLOG_TARGET_ENV,
+            WEBHOOK_URL_ENV,
+            WEBHOOK_SECRET_ENV,
+            OTLP_ENDPOINT_ENV,
+            OTLP_HEADERS_ENV,
+        ] {
+            std::env::remove_var(name);
+        }
+    }
+
+    #[test]
+    fn webhook_sink_batches_signed_json_lines_after_flush_interval() {
+        let _lock = crate::test_support::lock_env();
+        clear_export_env();
+        let (url, requests) = spawn_http_stand_in(vec![200]);
+        std::env::set_var(WEBHOOK_URL_ENV, url.as_str());
+        std::env::set_var(WEBHOOK_SECRET_ENV, "webhook-secret");
+        let store = InMemoryStore::default();
+
+        export_event(&store, sample_event(1_000), 1_000);
+        export_event(&store, sample_event(1_005), 1_005);
+        flush_due_batches(&store, 1_005);
+        assert_eq!(sink_counters(&store, "webhook").pending_events, 2);
+
+        // Queueing never delivers, even once the batch is due; the flush phase does.
+        export_event(&store, sample_event(1_020), 1_000 + FLUSH_INTERVAL_SECONDS);
+        assert_eq!(sink_counters(&store, "webhook").pending_events, 3);
+        flush_due_batches(&store, 1_000 + FLUSH_INTERVAL_SECONDS);
+        let request = requests
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("webhook batch delivered");
+        assert_eq!(request.request_line, "POST /ingest/events HTTP/1.1");
+        assert_eq!(request.header("content-type"), Some("application/x-ndjson"));
+        let lines: Vec<serde_json::Value> = String::from_utf8(request.body.clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["reason"], "honeypot");
+
+        let timestamp: u64 = request
+            .header(format::WEBHOOK_TIMESTAMP_HEADER)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            request.header(format::WEBHOOK_SIGNATURE_HEADER),
+            Some(format::webhook_signature("webhook-secret", timestamp, request.body.as_slice()).as_str())
+        );
+        assert_eq!(
+            sink_counters(&store, "webhook"),
+            SinkCounters {
+                delivered_total: 3,
+                dead_letter_total: 0,
+                pending_events: 0,
+            }
+        );
+        clear_export_env();
+    }
+
+    #[test]
+    fn failed_batches_back_off_and_dead_letter_after_max_attempts() {
+        let _lock = crate::test_support::lock_env();
+        clear_export_env();
+        let (endpoint, requests) =
+            spawn_http_stand_in(vec![503; MAX_DELIVERY_ATTEMPTS as usize]);
+        std::env::set_var(OTLP_ENDPOINT_ENV, endpoint.as_str());
+        let store = InMemoryStore::default();
+
+        let mut now = 5_000;
+        for offset in 0..BATCH_MAX_EVENTS as u64 {
+            export_event(&store, sample_event(now + offset), now);
+        }
+        flush_due_batches(&store, now);
+        requests
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("first attempt");
+        let outbox = load_outbox(&store, "otlp");
+        let retry = outbox.retry.as_ref().expect("batch kept for retry");
+        assert_eq!(retry.attempts, 1);
+        assert_eq!(retry.next_attempt_at, now + RETRY_BACKOFF_BASE_SECONDS);
+        assert!(outbox.last_error.as_deref().unwrap_or("").contains("503"));
+
+        // Not yet due: queued behind the retry without another attempt.
+        export_event(&store, sample_event(now + 1), now + 1);
+        flush_due_batches(&store, now + 1);
+        assert_eq!(load_outbox(&store, "otlp").retry.unwrap().attempts, 1);
+
+        for attempt in 1..MAX_DELIVERY_ATTEMPTS {
+            now += retry_backoff_seconds(attempt);
+            export_event(&store, sample_event(now), now);
+            flush_due_batches(&store, now);
+            requests
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .expect("retry attempt");
+        }
+        let counters = sink_counters(&store, "otlp");
+        assert_eq!(counters.dead_letter_total, BATCH_MAX_EVENTS as u64);
+        assert_eq!(counters.delivered_total, 0);
+        assert!(load_outbox(&store, "otlp").retry.is_none());
+        clear_export_env();
+    }
+
+    #[test]
+    fn syslog_sink_sends_rfc5424_datagrams() {
+        let _lock = crate::test_support::lock_env();
+        clear_export_env();
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        std::env::set_var(
+            SYSLOG_TARGET_ENV,
+            format!("udp://{}", socket.local_addr().unwrap()),
+        );
+        let store = InMemoryStore::default();
+
+        export_event(&store, sample_event(1_700_000_000), 1_700_000_000);
+        export_event(
+            &store,
+            sample_event(1_700_000_001),
+            1_700_000_000 + FLUSH_INTERVAL_SECONDS,
+        );
+        flush_due_batches(&store, 1_700_000_000 + FLUSH_INTERVAL_SECONDS);
+        let mut buffer = [0u8; 4096];
+        let (len, _) = socket.recv_from(&mut buffer).expect("syslog datagram");
+        let message = String::from_utf8_lossy(&buffer[..len]).to_string();
+        assert!(message.starts_with("<132>1 2023-11-14T22:13:20Z - shuma-gorath - ban - "));
+        assert!(message.contains("\"reason\":\"honeypot\""));
+        assert_eq!(sink_counters(&store, "syslog").delivered_total, 2);
+        clear_export_env();
+    }
+
+    #[test]
+    fn sink_configuration_requires_valid_targets_and_webhook_secret() {
+        let _lock = crate::test_support::lock_env();
+        clear_export_env();
+        std::env::set_var(SYSLOG_TARGET_ENV, "syslog.example:514");
+        std::env::set_var(WEBHOOK_URL_ENV, "https://siem.example/hook");
+        std::env::set_var(OTLP_ENDPOINT_ENV, "https://otel.example/v1/logs");
+        std::env::set_var(OTLP_HEADERS_ENV, "authorization=Bearer abc, x-tenant = blue,bad");
+        assert_eq!(
+            configured_sinks(),
+            vec![EventSink::Otlp {
+                endpoint: "https://otel.example/v1/logs".to_string(),
+                headers: vec![
+                    ("authorization".to_string(), "Bearer abc".to_string()),
+                    ("x-tenant".to_string(), "blue".to_string()),
+                ],
+            }]
+        );
+        clear_export_env();
+    }
+
+    #[test]
+    fn syslog_target_validation_rejects_runtimes_without_sockets() {
+        assert_eq!(validate_syslog_target(None, false), Ok(()));
+        assert_eq!(
+            validate_syslog_target(Some("udp://syslog.example:514".to_string()), true),
+            Ok(())
+        );
+        let unsupported =
+            validate_syslog_target(Some("udp://syslog.example:514".to_string()), false)
+                .unwrap_err();
+        assert!(unsupported.contains("cannot open syslog sockets"));
+        assert!(unsupported.contains(WEBHOOK_URL_ENV));
+        let malformed =
+            validate_syslog_target(Some("syslog.example:514".to_string()), true).unwrap_err();
+        assert!(malformed.contains("expected udp://host:port or tcp://host:port"));
+    }
+}
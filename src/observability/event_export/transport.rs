@@ -0,0 +1,159 @@
+//! Outbound delivery for export sinks.
+//!
+//! HTTP sinks go through Spin's outbound HTTP on wasm32. Native builds use a minimal
+//! plain-HTTP client so the pipeline can be exercised against a local stand-in. Syslog needs raw
+//! UDP/TCP sockets, which the Spin component cannot open, so env validation rejects a syslog
+//! target on wasm32 (see `SYSLOG_SOCKETS_AVAILABLE`). Spin deployments use the webhook or OTLP
+//! sink, or an HTTP-to-syslog relay.
+
+use super::SyslogProtocol;
+
+/// Whether this build can open the UDP/TCP sockets the syslog sink needs.
+pub(super) const SYSLOG_SOCKETS_AVAILABLE: bool = !cfg!(target_arch = "wasm32");
+
+fn status_result(status: u16) -> Result<(), String> {
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("http_status_{}", status))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn post(
+    url: &str,
+    content_type: &str,
+    headers: &[(String, String)],
+    body: Vec<u8>,
+) -> Result<(), String> {
+    let mut builder = spin_sdk::http::Request::builder();
+    builder
+        .method(spin_sdk::http::Method::Post)
+        .uri(url)
+        .header("content-type", content_type);
+    for (name, value) in headers {
+        builder.header(name.as_str(), value.as_str());
+    }
+    let request = builder.body(body).build();
+    let response: spin_sdk::http::Response =
+        spin_sdk::http::run(spin_sdk::http::send(request)).map_err(|err| err.to_string())?;
+    status_result(*response.status())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const NATIVE_IO_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn post(
+    url: &str,
+    content_type: &str,
+    headers: &[(String, String)],
+    body: Vec<u8>,
+) -> Result<(), String> {
+    use std::io::{Read, Write};
+
+    let Some(rest) = url.strip_prefix("http://") else {
+        return Err("native_transport_supports_plain_http_only".to_string());
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    let mut stream = connect_tcp(address.as_str())?;
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        path,
+        authority,
+        content_type,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(format!("{}: {}\r\n", name, value).as_str());
+    }
+    request.push_str("\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|_| stream.write_all(body.as_slice()))
+        .map_err(|err| format!("http_write_failed:{}", err))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|err| format!("http_read_failed:{}", err))?;
+    let status = String::from_utf8_lossy(response.as_slice())
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| "http_response_malformed".to_string())?;
+    status_result(status)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn connect_tcp(address: &str) -> Result<std::net::TcpStream, String> {
+    use std::net::ToSocketAddrs;
+
+    let socket_address = address
+        .to_socket_addrs()
+        .map_err(|err| format!("resolve_failed:{}", err))?
+        .next()
+        .ok_or_else(|| "resolve_failed:no_address".to_string())?;
+    let stream = std::net::TcpStream::connect_timeout(&socket_address, NATIVE_IO_TIMEOUT)
+        .map_err(|err| format!("connect_failed:{}", err))?;
+    stream
+        .set_read_timeout(Some(NATIVE_IO_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(NATIVE_IO_TIMEOUT)))
+        .map_err(|err| format!("socket_config_failed:{}", err))?;
+    Ok(stream)
+}
+
+/// Unreachable once env validation has passed; kept so a misconfigured sink still fails closed.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn send_syslog(
+    _protocol: &SyslogProtocol,
+    _address: &str,
+    _messages: &[String],
+) -> Result<(), String> {
+    Err("syslog_sockets_unavailable_in_spin_runtime".to_string())
+}
+
+/// UDP sends one datagram per message (RFC 5426); TCP uses octet-counting framing (RFC 6587).
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn send_syslog(
+    protocol: &SyslogProtocol,
+    address: &str,
+    messages: &[String],
+) -> Result<(), String> {
+    use std::io::Write;
+
+    match protocol {
+        SyslogProtocol::Udp => {
+            let bind_address = if address.starts_with('[') {
+                "[::]:0"
+            } else {
+                "0.0.0.0:0"
+            };
+            let socket = std::net::UdpSocket::bind(bind_address)
+                .map_err(|err| format!("udp_bind_failed:{}", err))?;
+            for message in messages {
+                socket
+                    .send_to(message.as_bytes(), address)
+                    .map_err(|err| format!("udp_send_failed:{}", err))?;
+            }
+            Ok(())
+        }
+        SyslogProtocol::Tcp => {
+            let mut stream = connect_tcp(address)?;
+            for message in messages {
+                stream
+                    .write_all(format!("{} {}", message.len(), message).as_bytes())
+                    .map_err(|err| format!("tcp_write_failed:{}", err))?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -129,7 +129,7 @@ const MONITORING_BOOTSTRAP_COMPONENTS: [HotReadComponentContract; 7] = [
     },
 ];
 
-const OPERATOR_SNAPSHOT_COMPONENTS: [HotReadComponentContract; 14] = [
+const OPERATOR_SNAPSHOT_COMPONENTS: [HotReadComponentContract; 15] = [
     HotReadComponentContract {
         key: "objectives",
         exactness: TelemetryExactness::Exact,
@@ -256,6 +256,15 @@ const OPERATOR_SNAPSHOT_COMPONENTS: [HotReadComponentContract; 14] = [
         projection_model: HotReadProjectionModel::DeterministicRebuild,
         note: "Replay-promotion summary is a bounded typed projection over persisted emergent-finding and deterministic-replay lineage materialized by the promotion triage lane, rather than a sidecar JSON artifact.",
     },
+    HotReadComponentContract {
+        key: "alerts",
+        exactness: TelemetryExactness::Derived,
+        basis: TelemetryBasis::Observed,
+        ownership_tier: HotReadOwnershipTier::SupportingSummary,
+        canonical_source: HotReadCanonicalSource::DirectStateSnapshot,
+        projection_model: HotReadProjectionModel::DeterministicRebuild,
+        note: "Alert section reports each operator alert rule's status, last evaluated value and notification history, evaluated from live hourly monitoring counters and the human-friction budget row on every snapshot rebuild.",
+    },
 ];
 
 const CURRENT_PROJECTION_CONTRACT: HotReadProjectionContract = HotReadProjectionContract {
@@ -391,5 +400,6 @@ mod tests {
         assert!(keys.contains(&"game_contract"));
         assert!(keys.contains(&"episode_archive"));
         assert!(keys.contains(&"replay_promotion"));
+        assert!(keys.contains(&"alerts"));
     }
 }
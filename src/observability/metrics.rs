@@ -43,6 +43,22 @@ const MAZE_CHECKPOINT_OUTCOMES: [&str; 4] = [
     "binding_mismatch",
     "method_not_allowed",
 ];
+const PRIVACY_PASS_ISSUANCE_OUTCOMES: [&str; 5] = [
+    "issued",
+    "disabled",
+    "invalid_request",
+    "unknown_key",
+    "no_grant",
+];
+const PRIVACY_PASS_REDEMPTION_OUTCOMES: [&str; 7] = [
+    "redeemed",
+    "missing",
+    "malformed",
+    "challenge_mismatch",
+    "unknown_key",
+    "invalid_authenticator",
+    "double_spend",
+];
 const MAZE_BUDGET_OUTCOMES: [&str; 3] = ["acquired", "saturated", "response_cap_exceeded"];
 const MAZE_PROOF_OUTCOMES: [&str; 3] = ["required", "passed", "failed"];
 const TARPIT_MODES: [&str; 1] = ["progressive"];
@@ -114,7 +130,7 @@ const MONITORING_RATE_OUTCOME_KEYS: [&str; 4] =
     ["limited", "banned", "fallback_allow", "fallback_deny"];
 const MONITORING_GEO_ACTION_KEYS: [&str; 3] = ["block", "challenge", "maze"];
 const MONITORING_VERIFIED_IDENTITY_OUTCOME_KEYS: [&str; 2] = ["verified", "failed"];
-const MONITORING_VERIFIED_IDENTITY_FAILURE_REASON_KEYS: [&str; 10] = [
+const MONITORING_VERIFIED_IDENTITY_FAILURE_REASON_KEYS: [&str; 13] = [
     "missing_assertion",
     "missing_signature",
     "signature_invalid",
@@ -125,6 +141,9 @@ const MONITORING_VERIFIED_IDENTITY_FAILURE_REASON_KEYS: [&str; 10] = [
     "provider_rejected",
     "provider_unavailable",
     "unsupported_scheme",
+    "certificate_invalid",
+    "certificate_expired",
+    "certificate_untrusted",
 ];
 const MONITORING_VERIFIED_IDENTITY_FRESHNESS_KEYS: [&str; 5] = [
     "not_applicable",
@@ -223,6 +242,8 @@ pub enum MetricName {
     ChallengeSolvedTotal,
     ChallengeIncorrectTotal,
     ChallengeExpiredReplayTotal,
+    PrivacyPassIssuanceOutcomes,
+    PrivacyPassRedemptionOutcomes,
     AllowlistedTotal,
     ShadowModeActions,
     MazeHits,
@@ -251,6 +272,8 @@ pub enum MetricName {
     RateLimiterStateDriftObservations,
     PolicyMatches,
     PolicySignals,
+    CustomRuleHits,
+    PolicySourceActions,
 }
 
 impl MetricName {
@@ -269,6 +292,8 @@ impl MetricName {
             MetricName::ChallengeSolvedTotal => "challenge_solved_total",
             MetricName::ChallengeIncorrectTotal => "challenge_incorrect_total",
             MetricName::ChallengeExpiredReplayTotal => "challenge_expired_replay_total",
+            MetricName::PrivacyPassIssuanceOutcomes => "privacy_pass_issuance_outcomes_total",
+            MetricName::PrivacyPassRedemptionOutcomes => "privacy_pass_redemption_outcomes_total",
             MetricName::AllowlistedTotal => "allowlisted_total",
             MetricName::ShadowModeActions => "shadow_mode_actions_total",
             MetricName::MazeHits => "maze_hits_total",
@@ -303,6 +328,8 @@ impl MetricName {
             }
             MetricName::PolicyMatches => "policy_matches_total",
             MetricName::PolicySignals => "policy_signals_total",
+            MetricName::CustomRuleHits => "custom_rule_hits_total",
+            MetricName::PolicySourceActions => "policy_source_actions_total",
         }
     }
 }
@@ -320,6 +347,9 @@ pub fn add(store: &Store, metric: MetricName, label: Option<&str>, delta: u64) {
     if delta == 0 {
         return;
     }
+    if matches!(metric, MetricName::BansTotal) {
+        crate::observability::monitoring::record_ban(store, delta);
+    }
     let key = match label {
         Some(l) => format!("{}{}:{}", METRICS_PREFIX, metric.as_str(), l),
         None => format!("{}{}", METRICS_PREFIX, metric.as_str()),
@@ -675,6 +705,38 @@ fn render_metrics_with_store(
         challenge_expired_replay
     ));
 
+    output.push_str("\n# TYPE bot_defence_privacy_pass_issuance_outcomes_total counter\n");
+    output.push_str(
+        "# HELP bot_defence_privacy_pass_issuance_outcomes_total Privacy Pass token issuance outcomes\n",
+    );
+    for outcome in PRIVACY_PASS_ISSUANCE_OUTCOMES {
+        let key = format!(
+            "{}privacy_pass_issuance_outcomes_total:{}",
+            METRICS_PREFIX, outcome
+        );
+        let count = get_counter(store, &key);
+        output.push_str(&format!(
+            "bot_defence_privacy_pass_issuance_outcomes_total{{outcome=\"{}\"}} {}\n",
+            outcome, count
+        ));
+    }
+
+    output.push_str("\n# TYPE bot_defence_privacy_pass_redemption_outcomes_total counter\n");
+    output.push_str(
+        "# HELP bot_defence_privacy_pass_redemption_outcomes_total Privacy Pass token redemption outcomes\n",
+    );
+    for outcome in PRIVACY_PASS_REDEMPTION_OUTCOMES {
+        let key = format!(
+            "{}privacy_pass_redemption_outcomes_total:{}",
+            METRICS_PREFIX, outcome
+        );
+        let count = get_counter(store, &key);
+        output.push_str(&format!(
+            "bot_defence_privacy_pass_redemption_outcomes_total{{outcome=\"{}\"}} {}\n",
+            outcome, count
+        ));
+    }
+
     output.push_str("\n# TYPE bot_defence_cdp_detections_total counter\n");
     output.push_str(
         "# HELP bot_defence_cdp_detections_total Total CDP detection reports processed\n",
@@ -1095,6 +1157,35 @@ fn render_metrics_with_store(
         ));
     }
 
+    // Operator custom rule hits
+    output.push_str("\n# TYPE bot_defence_custom_rule_hits_total counter\n");
+    output.push_str(
+        "# HELP bot_defence_custom_rule_hits_total Operator custom rule matches by rule ID and applied action\n",
+    );
+    for (label, count) in collect_labeled_counters(store, MetricName::CustomRuleHits) {
+        let (rule_id, action) = label.rsplit_once(':').unwrap_or((label.as_str(), "unknown"));
+        output.push_str(&format!(
+            "bot_defence_custom_rule_hits_total{{rule_id=\"{}\",action=\"{}\"}} {}\n",
+            rule_id, action, count
+        ));
+    }
+
+    // Per-source outcomes, split so shadowed trials can be compared with enforced sources
+    output.push_str("\n# TYPE bot_defence_policy_source_actions_total counter\n");
+    output.push_str(
+        "# HELP bot_defence_policy_source_actions_total Policy source decisions by source ID, execution mode and action\n",
+    );
+    for (label, count) in collect_labeled_counters(store, MetricName::PolicySourceActions) {
+        let mut parts = label.rsplitn(3, ':');
+        let action = parts.next().unwrap_or("unknown");
+        let mode = parts.next().unwrap_or("unknown");
+        let source = parts.next().unwrap_or("unknown");
+        output.push_str(&format!(
+            "bot_defence_policy_source_actions_total{{source=\"{}\",mode=\"{}\",action=\"{}\"}} {}\n",
+            source, mode, action, count
+        ));
+    }
+
     output.push_str("\n# TYPE bot_defence_forward_attempt_total counter\n");
     output
         .push_str("# HELP bot_defence_forward_attempt_total Total upstream forwarding attempts\n");
@@ -1326,6 +1417,46 @@ fn render_metrics_with_store(
         ));
     }
 
+    let event_export_counters: Vec<_> = crate::observability::event_export::EVENT_EXPORT_SINKS
+        .iter()
+        .map(|sink| {
+            (
+                *sink,
+                crate::observability::event_export::sink_counters(store, sink),
+            )
+        })
+        .collect();
+    output.push_str("\n# TYPE bot_defence_event_export_delivered_total counter\n");
+    output.push_str(
+        "# HELP bot_defence_event_export_delivered_total Event records delivered to export sinks\n",
+    );
+    for (sink, counters) in event_export_counters.iter() {
+        output.push_str(&format!(
+            "bot_defence_event_export_delivered_total{{sink=\"{}\"}} {}\n",
+            sink, counters.delivered_total
+        ));
+    }
+    output.push_str("\n# TYPE bot_defence_event_export_dead_letter_total counter\n");
+    output.push_str(
+        "# HELP bot_defence_event_export_dead_letter_total Event records dropped after exhausting export retries or outbox capacity\n",
+    );
+    for (sink, counters) in event_export_counters.iter() {
+        output.push_str(&format!(
+            "bot_defence_event_export_dead_letter_total{{sink=\"{}\"}} {}\n",
+            sink, counters.dead_letter_total
+        ));
+    }
+    output.push_str("\n# TYPE bot_defence_event_export_pending_events gauge\n");
+    output.push_str(
+        "# HELP bot_defence_event_export_pending_events Event records queued or awaiting retry per export sink\n",
+    );
+    for (sink, counters) in event_export_counters.iter() {
+        output.push_str(&format!(
+            "bot_defence_event_export_pending_events{{sink=\"{}\"}} {}\n",
+            sink, counters.pending_events
+        ));
+    }
+
     // Active bans (gauge)
     output.push_str("\n# TYPE bot_defence_active_bans gauge\n");
     output.push_str("# HELP bot_defence_active_bans Current number of active (non-expired) bans\n");
@@ -1437,4 +1568,40 @@ mod tests {
             "bot_defence_monitoring_verified_identity_schemes_total{scheme=\"provider_signed_agent\"} 1"
         ));
     }
+    #[test]
+    fn render_metrics_reports_custom_rule_hits_by_rule_id() {
+        let store = crate::test_support::InMemoryStore::default();
+        store
+            .set("metrics:custom_rule_hits_total:checkout-xx:challenge", b"4")
+            .unwrap();
+
+        let body = render_metrics_with_store(&store, 0);
+
+        assert!(body.contains(
+            "bot_defence_custom_rule_hits_total{rule_id=\"checkout-xx\",action=\"challenge\"} 4"
+        ));
+    }
+
+    #[test]
+    fn render_metrics_splits_policy_source_actions_by_execution_mode() {
+        let store = crate::test_support::InMemoryStore::default();
+        store
+            .set(
+                "metrics:policy_source_actions_total:ip_range:dc_block:shadow:block",
+                b"3",
+            )
+            .unwrap();
+        store
+            .set("metrics:policy_source_actions_total:geo_block:enforced:block", b"2")
+            .unwrap();
+
+        let body = render_metrics_with_store(&store, 0);
+
+        assert!(body.contains(
+            "bot_defence_policy_source_actions_total{source=\"ip_range:dc_block\",mode=\"shadow\",action=\"block\"} 3"
+        ));
+        assert!(body.contains(
+            "bot_defence_policy_source_actions_total{source=\"geo_block\",mode=\"enforced\",action=\"block\"} 2"
+        ));
+    }
 }
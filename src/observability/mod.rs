@@ -1,3 +1,4 @@
+pub(crate) mod alert_rules;
 pub(crate) mod benchmark_adversary_effectiveness;
 pub(crate) mod benchmark_comparison;
 pub(crate) mod benchmark_beneficial_non_human;
@@ -13,6 +14,7 @@ pub(crate) mod benchmark_results_comparison;
 pub(crate) mod benchmark_results_families;
 pub(crate) mod benchmark_suite;
 pub(crate) mod decision_ledger;
+pub(crate) mod event_export;
 pub(crate) mod hot_read_contract;
 pub(crate) mod hot_read_documents;
 pub(crate) mod hot_read_projection;
@@ -33,5 +35,6 @@ pub(crate) mod operator_snapshot_runtime_posture;
 pub(crate) mod operator_snapshot_verified_identity;
 pub(crate) mod operator_snapshot;
 pub(crate) mod replay_promotion;
+pub(crate) mod request_facts_journal;
 pub(crate) mod retention;
 pub(crate) mod scrapling_owned_surface;
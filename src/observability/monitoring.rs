@@ -35,10 +35,11 @@ const POW_REASON_KEYS: [&str; 5] = [
 const POW_OUTCOME_KEYS: [&str; 2] = ["success", "failure"];
 const NOT_A_BOT_OUTCOME_KEYS: [&str; 4] = ["pass", "escalate", "fail", "replay"];
 const NOT_A_BOT_SOLVE_MS_BUCKET_KEYS: [&str; 4] = ["lt_1s", "1_3s", "3_10s", "10s_plus"];
+const NOT_A_BOT_ACCESSIBLE_ALTERNATIVE_KEYS: [&str; 5] = ["served", "pass", "retry", "fail", "replay"];
 const RATE_OUTCOME_KEYS: [&str; 4] = ["limited", "banned", "fallback_allow", "fallback_deny"];
 const GEO_ACTION_KEYS: [&str; 3] = ["block", "challenge", "maze"];
 const VERIFIED_IDENTITY_OUTCOME_KEYS: [&str; 2] = ["verified", "failed"];
-const VERIFIED_IDENTITY_FAILURE_KEYS: [&str; 10] = [
+const VERIFIED_IDENTITY_FAILURE_KEYS: [&str; 13] = [
     "missing_assertion",
     "missing_signature",
     "signature_invalid",
@@ -49,6 +50,9 @@ const VERIFIED_IDENTITY_FAILURE_KEYS: [&str; 10] = [
     "provider_rejected",
     "provider_unavailable",
     "unsupported_scheme",
+    "certificate_invalid",
+    "certificate_expired",
+    "certificate_untrusted",
 ];
 const VERIFIED_IDENTITY_FRESHNESS_KEYS: [&str; 5] = [
     "not_applicable",
@@ -82,6 +86,8 @@ const SHADOW_ACTION_KEYS: [&str; 8] = [
     "redirect",
     "drop_connection",
 ];
+pub(crate) const VERIFIED_IDENTITY_DIRECTORY_ROTATED: &str = "rotated";
+pub(crate) const VERIFIED_IDENTITY_DIRECTORY_PINNED_KEYS_MISSING: &str = "pinned_keys_missing";
 const GUARDED_DIMENSION_CARDINALITY_CAP_PER_HOUR: u64 = 1000;
 const GUARDED_DIMENSION_OVERFLOW_VALUE: &str = "other";
 const UNSAMPLEABLE_SECURITY_EVENT_CLASSES: [&str; 8] = [
@@ -313,9 +319,27 @@ pub(crate) struct HumanFrictionSegmentRow {
     pub friction_rate: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub(crate) struct AccessibilityFrictionRow {
+    pub cohort_submitted: u64,
+    pub cohort_pass: u64,
+    pub cohort_escalate: u64,
+    pub cohort_fail: u64,
+    pub cohort_replay: u64,
+    pub cohort_escalation_rate: f64,
+    pub overall_escalation_rate: f64,
+    pub alternative_served: u64,
+    pub alternative_pass: u64,
+    pub alternative_retry: u64,
+    pub alternative_fail: u64,
+    pub alternative_pass_rate: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub(crate) struct HumanFrictionSummary {
     pub segments: Vec<HumanFrictionSegmentRow>,
+    #[serde(default)]
+    pub accessibility: AccessibilityFrictionRow,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -519,6 +543,16 @@ fn normalize_not_a_bot_outcome(outcome: &str) -> &'static str {
     }
 }
 
+fn normalize_not_a_bot_accessible_outcome(outcome: &str) -> &'static str {
+    match outcome {
+        "served" => "served",
+        "pass" => "pass",
+        "retry" => "retry",
+        "replay" => "replay",
+        _ => "fail",
+    }
+}
+
 fn normalize_shadow_action(action: crate::runtime::effect_intents::ShadowAction) -> &'static str {
     action.as_str()
 }
@@ -1657,6 +1691,39 @@ pub(crate) fn record_rate_outcome<S: crate::challenge::KeyValueStore>(store: &S,
     );
 }
 
+/// Hourly ban rollup across every ban reason, so alert rules can watch ban rate without summing
+/// the per-reason metrics counters.
+pub(crate) fn record_ban<S: crate::challenge::KeyValueStore>(store: &S, delta: u64) {
+    let origin = current_traffic_origin();
+    record_with_dimension_delta(
+        store,
+        "ban",
+        "total",
+        Some(origin_cohort(origin).as_str()),
+        delta,
+    );
+}
+
+/// Read one live-origin hourly counter. `outcome` selects the nested outcome cohort used by
+/// sections such as `rate/outcome`; `None` reads the plain origin cohort.
+pub(crate) fn live_hourly_counter<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    section: &str,
+    metric: &str,
+    outcome: Option<&str>,
+    hour: u64,
+) -> u64 {
+    let origin = crate::runtime::request_outcome::TrafficOrigin::Live;
+    let dimension = match outcome {
+        Some(outcome) => origin_nested_cohort(origin, outcome),
+        None => origin_cohort(origin),
+    };
+    read_counter(
+        store,
+        monitoring_key(section, metric, Some(dimension.as_str()), hour).as_str(),
+    )
+}
+
 pub(crate) fn record_geo_violation<S: crate::challenge::KeyValueStore>(
     store: &S,
     country: Option<&str>,
@@ -1756,6 +1823,26 @@ pub(crate) fn record_verified_identity_telemetry<S: crate::challenge::KeyValueSt
     }
 }
 
+/// Signature-agent directory refetches that changed the published key ids. Refreshes that no
+/// longer publish any pinned key id are counted apart, as `pinned_keys_missing`.
+pub(crate) fn record_verified_identity_directory_key_rotation<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    pinned_keys_missing: bool,
+) {
+    let origin = current_traffic_origin();
+    let outcome = if pinned_keys_missing {
+        VERIFIED_IDENTITY_DIRECTORY_PINNED_KEYS_MISSING
+    } else {
+        VERIFIED_IDENTITY_DIRECTORY_ROTATED
+    };
+    record_with_dimension(
+        store,
+        "verified_identity",
+        "directory_key_rotation",
+        Some(origin_nested_cohort(origin, outcome).as_str()),
+    );
+}
+
 pub(crate) fn record_not_a_bot_served<S: crate::challenge::KeyValueStore>(store: &S) {
     let origin = current_traffic_origin();
     record_with_dimension(
@@ -1796,6 +1883,35 @@ pub(crate) fn record_not_a_bot_submit<S: crate::challenge::KeyValueStore>(
     }
 }
 
+/// Counts checkbox outcomes for submissions whose telemetry matched an assistive-technology profile.
+pub(crate) fn record_not_a_bot_accessibility_cohort<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    outcome: &str,
+) {
+    let origin = current_traffic_origin();
+    record_with_dimension(
+        store,
+        "not_a_bot",
+        "accessibility_cohort",
+        Some(origin_nested_cohort(origin, normalize_not_a_bot_outcome(outcome)).as_str()),
+    );
+}
+
+pub(crate) fn record_not_a_bot_accessible_alternative<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    outcome: &str,
+) {
+    let origin = current_traffic_origin();
+    record_with_dimension(
+        store,
+        "not_a_bot",
+        "accessible_alternative",
+        Some(
+            origin_nested_cohort(origin, normalize_not_a_bot_accessible_outcome(outcome)).as_str(),
+        ),
+    );
+}
+
 pub(crate) fn record_shadow_action<S: crate::challenge::KeyValueStore>(
     store: &S,
     action: crate::runtime::effect_intents::ShadowAction,
@@ -1935,6 +2051,10 @@ struct MonitoringAccumulator {
     not_a_bot_submitted_by_origin: HashMap<String, u64>,
     not_a_bot_outcomes_by_origin: HashMap<String, HashMap<String, u64>>,
     not_a_bot_latency_buckets_by_origin: HashMap<String, HashMap<String, u64>>,
+    #[serde(default)]
+    not_a_bot_accessibility_cohort_by_origin: HashMap<String, HashMap<String, u64>>,
+    #[serde(default)]
+    not_a_bot_accessible_alternative_by_origin: HashMap<String, HashMap<String, u64>>,
     pow_totals_by_origin: HashMap<String, u64>,
     pow_success_totals_by_origin: HashMap<String, u64>,
     pow_ip_counts_by_origin: HashMap<String, HashMap<String, u64>>,
@@ -2158,6 +2278,30 @@ impl MonitoringAccumulator {
                         );
                     }
                 }
+                "accessibility_cohort" => {
+                    if let Some((origin, outcome)) =
+                        dimension.and_then(parse_origin_breakdown_cohort)
+                    {
+                        Self::add_nested_count(
+                            &mut self.not_a_bot_accessibility_cohort_by_origin,
+                            origin.as_str(),
+                            outcome.as_str(),
+                            count,
+                        );
+                    }
+                }
+                "accessible_alternative" => {
+                    if let Some((origin, outcome)) =
+                        dimension.and_then(parse_origin_breakdown_cohort)
+                    {
+                        Self::add_nested_count(
+                            &mut self.not_a_bot_accessible_alternative_by_origin,
+                            origin.as_str(),
+                            outcome.as_str(),
+                            count,
+                        );
+                    }
+                }
                 _ => {}
             },
             "pow" => match metric {
@@ -2597,6 +2741,14 @@ impl MonitoringAccumulator {
             &mut self.not_a_bot_latency_buckets_by_origin,
             &source.not_a_bot_latency_buckets_by_origin,
         );
+        Self::merge_nested_count_maps(
+            &mut self.not_a_bot_accessibility_cohort_by_origin,
+            &source.not_a_bot_accessibility_cohort_by_origin,
+        );
+        Self::merge_nested_count_maps(
+            &mut self.not_a_bot_accessible_alternative_by_origin,
+            &source.not_a_bot_accessible_alternative_by_origin,
+        );
         Self::merge_count_maps(&mut self.pow_totals_by_origin, &source.pow_totals_by_origin);
         Self::merge_count_maps(
             &mut self.pow_success_totals_by_origin,
@@ -2966,6 +3118,33 @@ impl MonitoringAccumulator {
             *entry = entry.saturating_add(value);
         }
 
+        let mut accessibility_cohort_map = build_seeded_map(&NOT_A_BOT_OUTCOME_KEYS);
+        for (key, value) in self
+            .not_a_bot_accessibility_cohort_by_origin
+            .get(live_origin)
+            .cloned()
+            .unwrap_or_default()
+        {
+            let entry = accessibility_cohort_map.entry(key).or_insert(0);
+            *entry = entry.saturating_add(value);
+        }
+        let mut accessible_alternative_map = build_seeded_map(&NOT_A_BOT_ACCESSIBLE_ALTERNATIVE_KEYS);
+        for (key, value) in self
+            .not_a_bot_accessible_alternative_by_origin
+            .get(live_origin)
+            .cloned()
+            .unwrap_or_default()
+        {
+            let entry = accessible_alternative_map.entry(key).or_insert(0);
+            *entry = entry.saturating_add(value);
+        }
+        let accessibility_friction = build_accessibility_friction_row(
+            &accessibility_cohort_map,
+            &accessible_alternative_map,
+            *not_a_bot_outcome_map.get("escalate").unwrap_or(&0),
+            not_a_bot_submitted_total,
+        );
+
         let not_a_bot_abandonments =
             not_a_bot_served_total.saturating_sub(not_a_bot_submitted_total);
         let not_a_bot_abandonment_ratio = if not_a_bot_served_total == 0 {
@@ -4016,6 +4195,7 @@ impl MonitoringAccumulator {
             verified_identity,
             human_friction: HumanFrictionSummary {
                 segments: human_friction_rows.into_values().collect(),
+                accessibility: accessibility_friction,
             },
             defence_funnel: DefenceFunnelSummary {
                 rows: defence_funnel_rows.into_values().collect(),
@@ -4032,6 +4212,42 @@ impl MonitoringAccumulator {
     }
 }
 
+fn build_accessibility_friction_row(
+    cohort: &BTreeMap<String, u64>,
+    alternative: &BTreeMap<String, u64>,
+    overall_escalate: u64,
+    overall_submitted: u64,
+) -> AccessibilityFrictionRow {
+    let count = |map: &BTreeMap<String, u64>, key: &str| map.get(key).copied().unwrap_or(0);
+    let ratio = |numerator: u64, denominator: u64| {
+        if denominator == 0 {
+            0.0
+        } else {
+            numerator as f64 / denominator as f64
+        }
+    };
+    let cohort_submitted = cohort.values().copied().sum::<u64>();
+    let alternative_pass = count(alternative, "pass");
+    let alternative_attempts = alternative_pass
+        .saturating_add(count(alternative, "retry"))
+        .saturating_add(count(alternative, "fail"))
+        .saturating_add(count(alternative, "replay"));
+    AccessibilityFrictionRow {
+        cohort_submitted,
+        cohort_pass: count(cohort, "pass"),
+        cohort_escalate: count(cohort, "escalate"),
+        cohort_fail: count(cohort, "fail"),
+        cohort_replay: count(cohort, "replay"),
+        cohort_escalation_rate: ratio(count(cohort, "escalate"), cohort_submitted),
+        overall_escalation_rate: ratio(overall_escalate, overall_submitted),
+        alternative_served: count(alternative, "served"),
+        alternative_pass,
+        alternative_retry: count(alternative, "retry"),
+        alternative_fail: count(alternative, "fail").saturating_add(count(alternative, "replay")),
+        alternative_pass_rate: ratio(alternative_pass, alternative_attempts),
+    }
+}
+
 fn monitoring_day_rollup_key(day_start_hour: u64) -> String {
     format!("{MONITORING_ROLLUP_KEY_PREFIX}:{day_start_hour}")
 }
@@ -5724,6 +5940,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn summarize_reports_accessibility_cohort_escalation_in_human_friction() {
+        let store = MockStore::default();
+        for outcome in ["pass", "pass", "escalate", "escalate"] {
+            record_not_a_bot_submit(&store, outcome, Some(1500));
+        }
+        record_not_a_bot_accessibility_cohort(&store, "escalate");
+        record_not_a_bot_accessibility_cohort(&store, "pass");
+        record_not_a_bot_accessible_alternative(&store, "served");
+        record_not_a_bot_accessible_alternative(&store, "retry");
+        record_not_a_bot_accessible_alternative(&store, "pass");
+
+        let summary = summarize_with_store(&store, 24, 10);
+        let accessibility = summary.human_friction.accessibility;
+        assert_eq!(accessibility.cohort_submitted, 2);
+        assert_eq!(accessibility.cohort_escalate, 1);
+        assert!((accessibility.cohort_escalation_rate - 0.5).abs() < 0.000_001);
+        assert!((accessibility.overall_escalation_rate - 0.5).abs() < 0.000_001);
+        assert_eq!(accessibility.alternative_served, 1);
+        assert_eq!(accessibility.alternative_pass, 1);
+        assert_eq!(accessibility.alternative_retry, 1);
+        assert!((accessibility.alternative_pass_rate - 0.5).abs() < 0.000_001);
+    }
+
     #[test]
     fn summarize_aggregates_pow_outcomes_and_ratio() {
         let store = MockStore::default();
@@ -42,6 +42,7 @@ pub(crate) use super::operator_snapshot_recent_changes::{
 pub(crate) use super::operator_snapshot_runtime_posture::OperatorSnapshotRuntimePosture;
 pub(crate) use super::operator_snapshot_verified_identity::OperatorSnapshotVerifiedIdentitySummary;
 pub(crate) use super::replay_promotion::ReplayPromotionSummary;
+pub(crate) use super::alert_rules::OperatorSnapshotAlerts;
 
 pub(crate) const OPERATOR_SNAPSHOT_SCHEMA_VERSION: &str = "operator_snapshot_v1";
 const DEFAULT_RECENT_CHANGE_ROWS: usize = 6;
@@ -161,6 +162,8 @@ pub(crate) struct OperatorSnapshotHotReadPayload {
     pub benchmark_results: BenchmarkResultsPayload,
     pub verified_identity: OperatorSnapshotVerifiedIdentitySummary,
     pub replay_promotion: ReplayPromotionSummary,
+    #[serde(default)]
+    pub alerts: OperatorSnapshotAlerts,
 }
 
 fn default_episode_benchmark_urgency_status() -> String {
@@ -234,6 +237,7 @@ pub(crate) fn build_operator_snapshot_payload<S: KeyValueStore>(
         suspicious_lane.as_ref(),
         human_friction.as_ref(),
     );
+    let alerts = super::alert_rules::persisted_alert_summary(store, site_id, generated_at_ts);
     let non_human_traffic =
         super::operator_snapshot_non_human::non_human_traffic_summary(summary, recent_sim_runs);
     let allowed_actions = crate::config::allowed_actions_v1();
@@ -308,6 +312,7 @@ pub(crate) fn build_operator_snapshot_payload<S: KeyValueStore>(
         benchmark_results,
         verified_identity,
         replay_promotion,
+        alerts,
     }
 }
 
@@ -8,6 +8,8 @@ pub(crate) struct OperatorSnapshotRecentChange {
     pub change_reason: String,
     pub changed_families: Vec<String>,
     pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changed_by: Option<String>,
     pub targets: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub decision_id: Option<String>,
@@ -9,6 +9,9 @@ pub(crate) struct OperatorSnapshotRuntimePosture {
     pub runtime_environment: String,
     pub gateway_deployment_profile: String,
     pub adversary_sim_available: bool,
+    /// Scheduled config profile currently overlaid on the persisted config.
+    #[serde(default)]
+    pub active_config_profile: Option<String>,
 }
 
 pub(super) fn runtime_shadow_mode<S: KeyValueStore>(store: &S, site_id: &str) -> bool {
@@ -17,6 +20,12 @@ pub(super) fn runtime_shadow_mode<S: KeyValueStore>(store: &S, site_id: &str) ->
         .unwrap_or(false)
 }
 
+fn runtime_active_config_profile<S: KeyValueStore>(store: &S, site_id: &str) -> Option<String> {
+    crate::config::load_runtime_cached(store, site_id)
+        .ok()
+        .and_then(|cfg| cfg.active_config_profile)
+}
+
 pub(super) fn runtime_posture<S: KeyValueStore>(
     store: &S,
     site_id: &str,
@@ -33,5 +42,6 @@ pub(super) fn runtime_posture<S: KeyValueStore>(
             .as_str()
             .to_string(),
         adversary_sim_available: crate::config::adversary_sim_available(),
+        active_config_profile: runtime_active_config_profile(store, site_id),
     }
 }
@@ -0,0 +1,538 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bot_identity::contracts::VerifiedIdentityEvidence;
+use crate::challenge::KeyValueStore;
+use crate::config::IpRangePolicyAction;
+use crate::runtime::request_facts::RequestFacts;
+use crate::signals::ip_range_policy::{Evaluation, MatchDetails, MatchSource};
+
+const REQUEST_FACTS_JOURNAL_SCHEMA_VERSION: &str = "request_facts_journal_v2";
+const REQUEST_FACTS_JOURNAL_PREFIX: &str = "request_facts_journal:v1";
+const REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR: usize = 120;
+pub(crate) const REQUEST_FACTS_JOURNAL_RETENTION_HOURS: u64 = 24;
+const REQUEST_FACTS_JOURNAL_MAX_TEXT_CHARS: usize = 160;
+const REQUEST_FACTS_JOURNAL_MAX_HEADERS: usize = 24;
+const USER_AGENT_FAMILY_MAX_CHARS: usize = 32;
+// Client-address and credential headers never enter the journal; custom rules that key on them
+// simply do not match during replay.
+const REQUEST_FACTS_JOURNAL_DROPPED_HEADERS: &[&str] = &[
+    "forwarded",
+    "x-forwarded-for",
+    "x-real-ip",
+    "x-client-ip",
+    "true-client-ip",
+    "cf-connecting-ip",
+    "fastly-client-ip",
+];
+const REQUEST_FACTS_JOURNAL_DROPPED_HEADER_FRAGMENTS: &[&str] =
+    &["auth", "cookie", "token", "secret", "session", "key", "signature"];
+
+/// Which policy-graph tranche the recorded facts were captured for. First-tranche rows stopped
+/// before botness scoring ran, so their botness fields are empty.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RequestFactsJournalStage {
+    FirstTranche,
+    SecondTranche,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct RecordedIpRangeMatch {
+    pub source_id: String,
+    pub action: IpRangePolicyAction,
+    pub matched_cidr: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum RecordedIpRangeEvaluation {
+    EmergencyAllowlisted { matched_cidr: String },
+    Matched(RecordedIpRangeMatch),
+}
+
+/// Compact, pseudonymized projection of `RequestFacts`. The client address is reduced to its
+/// network bucket, the user agent to its family, and identifying headers are dropped before
+/// anything is persisted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct RequestFactsJournalRow {
+    pub recorded_at_ts: u64,
+    pub stage: RequestFactsJournalStage,
+    pub method: String,
+    pub path: String,
+    pub ip_bucket: String,
+    pub user_agent_family: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_range: Option<RecordedIpRangeEvaluation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ip_range_shadow_matches: Vec<RecordedIpRangeMatch>,
+    pub honeypot_hit: bool,
+    pub rate_limit_exceeded: bool,
+    pub existing_ban: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_country: Option<String>,
+    pub needs_js: bool,
+    pub browser_navigation_like: bool,
+    pub botness_score: u8,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub botness_signal_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_identity: Option<VerifiedIdentityEvidence>,
+    pub not_a_bot_marker_valid: bool,
+    pub privacy_pass_token_valid: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crawl_licence: Option<crate::bot_identity::licensing::VerifiedCrawlLicence>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct RequestFactsJournalBucket {
+    schema_version: String,
+    hour: u64,
+    /// Rows offered to this bucket, kept or not; drives reservoir replacement once it is full.
+    #[serde(default)]
+    seen_rows: u64,
+    rows: Vec<RequestFactsJournalRow>,
+}
+
+/// Journal namespace for adversary-sim traffic. Simulated requests are kept apart so what-if
+/// replay only sees real traffic, while botness calibration can use them as known-bot labels.
+pub(crate) fn adversary_sim_journal_site_id(site_id: &str) -> String {
+    format!("{site_id}:adversary_sim")
+}
+
+fn journal_bucket_key(site_id: &str, hour: u64) -> String {
+    format!("{REQUEST_FACTS_JOURNAL_PREFIX}:{site_id}:{hour}")
+}
+
+fn load_journal_bucket<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    hour: u64,
+) -> Option<RequestFactsJournalBucket> {
+    store
+        .get(&journal_bucket_key(site_id, hour))
+        .ok()
+        .flatten()
+        .and_then(|bytes| serde_json::from_slice::<RequestFactsJournalBucket>(bytes.as_slice()).ok())
+        .filter(|bucket| {
+            bucket.schema_version == REQUEST_FACTS_JOURNAL_SCHEMA_VERSION && bucket.hour == hour
+        })
+}
+
+/// Sampling gate for journal writes; `sample_percent` is the configured 0-100 rate.
+pub(crate) fn should_journal_request(sample_percent: u8) -> bool {
+    match sample_percent {
+        0 => false,
+        100..=u8::MAX => true,
+        percent => rand::random_range(0..100u8) < percent,
+    }
+}
+
+/// Add a row to the current hour bucket. Once the bucket is full, rows are reservoir-sampled so
+/// the hour stays a uniform sample rather than its first minutes. Opening a new bucket retires the
+/// one that just fell out of the retention window.
+pub(crate) fn record_row<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    row: RequestFactsJournalRow,
+) -> Result<(), ()> {
+    let hour = row.recorded_at_ts / 3600;
+    let mut bucket = match load_journal_bucket(store, site_id, hour) {
+        Some(bucket) => bucket,
+        None => {
+            let expired_hour = hour.saturating_sub(REQUEST_FACTS_JOURNAL_RETENTION_HOURS);
+            let _ = store.delete(&journal_bucket_key(site_id, expired_hour));
+            RequestFactsJournalBucket {
+                schema_version: REQUEST_FACTS_JOURNAL_SCHEMA_VERSION.to_string(),
+                hour,
+                seen_rows: 0,
+                rows: Vec::new(),
+            }
+        }
+    };
+    bucket.seen_rows = bucket.seen_rows.saturating_add(1);
+    if bucket.rows.len() < REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR {
+        bucket.rows.push(row);
+    } else {
+        let slot = rand::random_range(0..bucket.seen_rows) as usize;
+        if let Some(kept) = bucket.rows.get_mut(slot) {
+            *kept = row;
+        }
+    }
+    let payload = serde_json::to_vec(&bucket).map_err(|_| ())?;
+    store.set(&journal_bucket_key(site_id, hour), payload.as_slice())
+}
+
+/// Rows recorded in the last `hours` hours (capped to the retention window), oldest first.
+pub(crate) fn load_recent_rows<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    now: u64,
+    hours: u64,
+) -> Vec<RequestFactsJournalRow> {
+    let end_hour = now / 3600;
+    let hours = hours.clamp(1, REQUEST_FACTS_JOURNAL_RETENTION_HOURS);
+    let start_hour = end_hour.saturating_sub(hours - 1);
+    (start_hour..=end_hour)
+        .filter_map(|hour| load_journal_bucket(store, site_id, hour))
+        .flat_map(|bucket| {
+            let mut rows = bucket.rows;
+            rows.sort_by_key(|row| row.recorded_at_ts);
+            rows
+        })
+        .collect()
+}
+
+fn truncate_text(value: &str) -> String {
+    if value.chars().count() <= REQUEST_FACTS_JOURNAL_MAX_TEXT_CHARS {
+        return value.to_string();
+    }
+    value
+        .chars()
+        .take(REQUEST_FACTS_JOURNAL_MAX_TEXT_CHARS)
+        .collect()
+}
+
+/// Coarse user-agent family journaled instead of the raw header: the crawler named in a
+/// `compatible;` comment, else the browser family for `Mozilla/` agents, else the leading product
+/// name (`curl`, `python-requests`). Versions, platform and build details are never stored.
+fn user_agent_family(user_agent: &str) -> String {
+    let lower = user_agent.trim().to_ascii_lowercase();
+    if lower.is_empty() {
+        return String::new();
+    }
+    if lower.starts_with("mozilla/") {
+        if let Some(crawler) = lower
+            .split_once("compatible;")
+            .map(|(_, rest)| product_name(rest))
+            .filter(|name| !name.is_empty())
+        {
+            return crawler;
+        }
+        return crate::signals::fingerprint::normalize_browser_family(lower.as_str()).to_string();
+    }
+    let name = product_name(lower.as_str());
+    if name.is_empty() {
+        "other".to_string()
+    } else {
+        name
+    }
+}
+
+fn product_name(raw: &str) -> String {
+    raw.trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .take(USER_AGENT_FAMILY_MAX_CHARS)
+        .collect()
+}
+
+fn header_is_journaled(name: &str) -> bool {
+    !REQUEST_FACTS_JOURNAL_DROPPED_HEADERS.contains(&name)
+        && !REQUEST_FACTS_JOURNAL_DROPPED_HEADER_FRAGMENTS
+            .iter()
+            .any(|fragment| name.contains(fragment))
+        && !name.starts_with("x-shuma-")
+}
+
+fn record_ip_range_match(details: &MatchDetails) -> RecordedIpRangeMatch {
+    RecordedIpRangeMatch {
+        source_id: details.source_id.clone(),
+        action: details.action,
+        matched_cidr: details.matched_cidr.clone(),
+        redirect_url: details.redirect_url.clone(),
+        custom_message: details.custom_message.as_deref().map(truncate_text),
+    }
+}
+
+fn replay_ip_range_match(recorded: &RecordedIpRangeMatch) -> MatchDetails {
+    MatchDetails {
+        source: MatchSource::CustomRule,
+        source_id: recorded.source_id.clone(),
+        action: recorded.action,
+        matched_cidr: recorded.matched_cidr.clone(),
+        redirect_url: recorded.redirect_url.clone(),
+        custom_message: recorded.custom_message.clone(),
+    }
+}
+
+fn replay_method(raw: &str) -> spin_sdk::http::Method {
+    use spin_sdk::http::Method;
+    match raw {
+        "GET" => Method::Get,
+        "POST" => Method::Post,
+        "PUT" => Method::Put,
+        "DELETE" => Method::Delete,
+        "PATCH" => Method::Patch,
+        "HEAD" => Method::Head,
+        "OPTIONS" => Method::Options,
+        "CONNECT" => Method::Connect,
+        "TRACE" => Method::Trace,
+        other => Method::Other(other.to_string()),
+    }
+}
+
+impl RequestFactsJournalRow {
+    pub(crate) fn from_facts(
+        facts: &RequestFacts,
+        stage: RequestFactsJournalStage,
+        recorded_at_ts: u64,
+    ) -> Self {
+        Self {
+            recorded_at_ts,
+            stage,
+            method: facts.method.to_string(),
+            path: truncate_text(facts.path.as_str()),
+            ip_bucket: crate::signals::ip_identity::bucket_ip(facts.ip.as_str()),
+            user_agent_family: user_agent_family(facts.user_agent.as_str()),
+            headers: facts
+                .request_headers
+                .iter()
+                .filter(|(name, _)| header_is_journaled(name.as_str()))
+                .take(REQUEST_FACTS_JOURNAL_MAX_HEADERS)
+                .map(|(name, value)| (name.clone(), truncate_text(value.as_str())))
+                .collect(),
+            ip_range: match &facts.ip_range_evaluation {
+                Evaluation::NoMatch => None,
+                Evaluation::EmergencyAllowlisted { matched_cidr } => {
+                    Some(RecordedIpRangeEvaluation::EmergencyAllowlisted {
+                        matched_cidr: matched_cidr.clone(),
+                    })
+                }
+                Evaluation::Matched(details) => Some(RecordedIpRangeEvaluation::Matched(
+                    record_ip_range_match(details),
+                )),
+            },
+            ip_range_shadow_matches: facts
+                .ip_range_shadow_matches
+                .iter()
+                .map(record_ip_range_match)
+                .collect(),
+            honeypot_hit: facts.honeypot_hit,
+            rate_limit_exceeded: facts.rate_limit_exceeded,
+            existing_ban: facts.existing_ban,
+            geo_country: facts.geo_country.clone(),
+            needs_js: facts.needs_js,
+            browser_navigation_like: facts.browser_navigation_like,
+            botness_score: facts.botness_score,
+            botness_signal_ids: facts
+                .botness_signal_ids
+                .iter()
+                .map(|signal_id| signal_id.as_str().to_string())
+                .collect(),
+            verified_identity: facts.verified_identity.clone(),
+            not_a_bot_marker_valid: facts.not_a_bot_marker_valid,
+            privacy_pass_token_valid: facts.privacy_pass_token_valid,
+            crawl_licence: facts.crawl_licence.clone(),
+        }
+    }
+
+    /// Rebuild request facts for replay under `cfg`. Geo routing is re-derived from the recorded
+    /// country so country-list changes take effect; every other signal is replayed as observed,
+    /// and `user_agent` rules see the journaled family.
+    pub(crate) fn to_request_facts(&self, site_id: &str, cfg: &crate::config::Config) -> RequestFacts {
+        RequestFacts {
+            method: replay_method(self.method.as_str()),
+            path: self.path.clone(),
+            request_headers: self.headers.clone(),
+            site_id: site_id.to_string(),
+            ip: self.ip_bucket.clone(),
+            user_agent: self.user_agent_family.clone(),
+            ip_range_evaluation: match &self.ip_range {
+                None => Evaluation::NoMatch,
+                Some(RecordedIpRangeEvaluation::EmergencyAllowlisted { matched_cidr }) => {
+                    Evaluation::EmergencyAllowlisted {
+                        matched_cidr: matched_cidr.clone(),
+                    }
+                }
+                Some(RecordedIpRangeEvaluation::Matched(recorded)) => {
+                    Evaluation::Matched(replay_ip_range_match(recorded))
+                }
+            },
+            ip_range_shadow_matches: self
+                .ip_range_shadow_matches
+                .iter()
+                .map(replay_ip_range_match)
+                .collect(),
+            honeypot_hit: self.honeypot_hit,
+            rate_limit_exceeded: self.rate_limit_exceeded,
+            existing_ban: self.existing_ban,
+            geo_route: crate::signals::geo::evaluate_geo_policy(self.geo_country.as_deref(), cfg),
+            geo_country: self.geo_country.clone(),
+            needs_js: self.needs_js,
+            browser_navigation_like: self.browser_navigation_like,
+            botness_score: self.botness_score,
+            botness_signal_ids: self
+                .botness_signal_ids
+                .iter()
+                .filter_map(|raw| {
+                    crate::runtime::policy_taxonomy::botness_signal_id_from_str(raw.as_str())
+                })
+                .collect(),
+            botness_summary: "journal_replay".to_string(),
+            botness_state_summary: "journal_replay".to_string(),
+            runtime_metadata_summary: "journal_replay".to_string(),
+            provider_summary: "journal_replay".to_string(),
+            verified_identity: self.verified_identity.clone(),
+            not_a_bot_marker_valid: self.not_a_bot_marker_valid,
+            privacy_pass_token_valid: self.privacy_pass_token_valid,
+            crawl_licence: self.crawl_licence.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::InMemoryStore;
+
+    fn facts() -> RequestFacts {
+        let req = spin_sdk::http::Request::builder()
+            .method(spin_sdk::http::Method::Get)
+            .uri("/catalog")
+            .header("Accept-Language", "en")
+            .header("Cookie", "session=abc")
+            .header("X-Forwarded-For", "198.51.100.7")
+            .build();
+        crate::runtime::request_facts::build_request_facts(
+            &req,
+            crate::runtime::request_facts::RequestFactInputs {
+                site_id: "default".to_string(),
+                ip: "198.51.100.7".to_string(),
+                user_agent: "Mozilla/5.0".to_string(),
+                ip_range_evaluation: Evaluation::NoMatch,
+                ip_range_shadow_matches: vec![],
+                honeypot_hit: false,
+                rate_limit_exceeded: false,
+                existing_ban: false,
+                geo_route: crate::signals::geo::GeoPolicyRoute::None,
+                geo_country: Some("BR".to_string()),
+                needs_js: true,
+                browser_navigation_like: true,
+                botness_score: 4,
+                botness_signal_ids: vec![crate::runtime::policy_taxonomy::SignalId::GeoRisk],
+                botness_summary: "geo_risk".to_string(),
+                botness_state_summary: "geo_risk:active".to_string(),
+                runtime_metadata_summary: "modes".to_string(),
+                provider_summary: "providers".to_string(),
+                verified_identity: None,
+                not_a_bot_marker_valid: false,
+                privacy_pass_token_valid: false,
+                crawl_licence: None,
+            },
+        )
+    }
+
+    #[test]
+    fn rows_are_pseudonymized_and_replay_geo_routing_under_the_given_config() {
+        let row = RequestFactsJournalRow::from_facts(
+            &facts(),
+            RequestFactsJournalStage::SecondTranche,
+            1_700_000_000,
+        );
+
+        assert_eq!(row.ip_bucket, "198.51.100.0");
+        assert_eq!(row.user_agent_family, "other");
+        assert_eq!(
+            row.headers,
+            vec![("accept-language".to_string(), "en".to_string())]
+        );
+        assert_eq!(row.botness_signal_ids, vec!["S_GEO_RISK".to_string()]);
+
+        let mut cfg = crate::config::defaults().clone();
+        cfg.geo_block = vec!["BR".to_string()];
+        let replayed = row.to_request_facts("default", &cfg);
+        assert_eq!(replayed.ip, "198.51.100.0");
+        assert_eq!(replayed.geo_route, crate::signals::geo::GeoPolicyRoute::Block);
+        assert_eq!(replayed.botness_score, 4);
+        assert_eq!(
+            replayed.botness_signal_ids,
+            vec![crate::runtime::policy_taxonomy::SignalId::GeoRisk]
+        );
+    }
+
+    #[test]
+    fn user_agents_are_journaled_as_families_without_versions() {
+        for (raw, family) in [
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0 Safari/537.36",
+                "chrome",
+            ),
+            (
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+                "googlebot",
+            ),
+            ("curl/8.4.0", "curl"),
+            ("python-requests/2.31.0", "python-requests"),
+            ("  ", ""),
+            ("(unknown)", "other"),
+        ] {
+            assert_eq!(user_agent_family(raw), family, "{raw}");
+        }
+    }
+
+    #[test]
+    fn full_hour_buckets_keep_a_reservoir_sample_of_the_whole_hour() {
+        let store = InMemoryStore::default();
+        let hour_start = 1_700_000_000u64 - (1_700_000_000 % 3600);
+        let offered = REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR as u64 * 10;
+        for offset in 0..offered {
+            let row = RequestFactsJournalRow::from_facts(
+                &facts(),
+                RequestFactsJournalStage::SecondTranche,
+                hour_start + offset,
+            );
+            record_row(&store, "default", row).expect("journal write");
+        }
+
+        let bucket = load_journal_bucket(&store, "default", hour_start / 3600).expect("bucket");
+        assert_eq!(bucket.seen_rows, offered);
+        assert_eq!(bucket.rows.len(), REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR);
+        let rows = load_recent_rows(&store, "default", hour_start, 1);
+        assert!(rows
+            .windows(2)
+            .all(|pair| pair[0].recorded_at_ts <= pair[1].recorded_at_ts));
+        // Roughly 90% of a uniform sample comes from after the first full bucket's worth.
+        let first_bucket_end = hour_start + REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR as u64;
+        let late = rows
+            .iter()
+            .filter(|row| row.recorded_at_ts >= first_bucket_end)
+            .count();
+        assert!(late > REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR / 2, "late rows {late}");
+    }
+
+    #[test]
+    fn journal_buckets_are_capped_per_hour_and_windowed_on_read() {
+        let store = InMemoryStore::default();
+        let now = 1_700_000_000u64;
+        for offset in 0..(REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR as u64 + 5) {
+            let row = RequestFactsJournalRow::from_facts(
+                &facts(),
+                RequestFactsJournalStage::SecondTranche,
+                now - (now % 3600) + offset,
+            );
+            record_row(&store, "default", row).expect("journal write");
+        }
+        let older = RequestFactsJournalRow::from_facts(
+            &facts(),
+            RequestFactsJournalStage::FirstTranche,
+            now - 3 * 3600,
+        );
+        record_row(&store, "default", older).expect("journal write");
+
+        assert_eq!(
+            load_recent_rows(&store, "default", now, 1).len(),
+            REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR
+        );
+        assert_eq!(
+            load_recent_rows(&store, "default", now, 4).len(),
+            REQUEST_FACTS_JOURNAL_MAX_ROWS_PER_HOUR + 1
+        );
+        assert!(load_recent_rows(&store, "other", now, 4).is_empty());
+    }
+}
@@ -389,6 +389,7 @@ fn normalize_verified_identity_headers(
         end_user_controlled,
         directory_source,
         provenance: crate::bot_identity::contracts::IdentityProvenance::Provider,
+        delegation: None,
     })
 }
 
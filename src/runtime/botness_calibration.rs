@@ -0,0 +1,883 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::config::BotnessWeights;
+use crate::observability::request_facts_journal::{
+    RequestFactsJournalRow, RequestFactsJournalStage,
+};
+use crate::runtime::policy_taxonomy::SignalId;
+use crate::signals::botness::SignalFamily;
+
+const FIT_ITERATIONS: usize = 400;
+const FIT_LEARNING_RATE: f64 = 0.5;
+const FIT_L2_PENALTY: f64 = 0.01;
+/// Rows needed in each class before fitted weights are proposed; smaller windows still report.
+const CALIBRATION_MIN_ROWS_PER_CLASS: u64 = 20;
+/// Labelled rows a signal must be active in before its fitted weight replaces the current one.
+const CALIBRATION_MIN_SIGNAL_SUPPORT: u64 = 5;
+const CALIBRATION_MAX_OPERATING_POINTS: usize = 16;
+/// How far either side of a honeypot hit the same session's scored rows are labelled bot.
+const CALIBRATION_HONEYPOT_SESSION_WINDOW_SECONDS: u64 = 10 * 60;
+
+struct CalibratedSignal {
+    signal_id: SignalId,
+    family: SignalFamily,
+    weight_field: Option<&'static str>,
+}
+
+/// Journaled botness signals. Only the ones backed by a `BotnessWeights` field can be moved by
+/// the proposal; the rest are fitted alongside so tunable weights are estimated net of them.
+const CALIBRATED_SIGNALS: [CalibratedSignal; 22] = [
+    CalibratedSignal {
+        signal_id: SignalId::JsRequiredMissing,
+        family: SignalFamily::RequestIntegrity,
+        weight_field: Some("js_required"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::BrowserOutdated,
+        family: SignalFamily::RequestIntegrity,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::CdpReportBindingAnomaly,
+        family: SignalFamily::RequestIntegrity,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::CdpReportMissing,
+        family: SignalFamily::RequestIntegrity,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::GeoRisk,
+        family: SignalFamily::Geo,
+        weight_field: Some("geo_risk"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::RateUsageMedium,
+        family: SignalFamily::Rate,
+        weight_field: Some("rate_medium"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::RateUsageHigh,
+        family: SignalFamily::Rate,
+        weight_field: Some("rate_high"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::MazeTraversal,
+        family: SignalFamily::Deception,
+        weight_field: Some("maze_behavior"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintUaHintMismatch,
+        family: SignalFamily::FingerprintHeaderRuntime,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintUaTransportMismatch,
+        family: SignalFamily::FingerprintTransport,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintUntrustedHeader,
+        family: SignalFamily::FingerprintTransport,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintTransportProfileMismatch,
+        family: SignalFamily::FingerprintTransport,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintHeaderShapeMismatch,
+        family: SignalFamily::FingerprintHeaderRuntime,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintHeaderOrderMismatch,
+        family: SignalFamily::FingerprintHeaderRuntime,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::EdgeFingerprintAdditive,
+        family: SignalFamily::FingerprintTransport,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintTemporalTransition,
+        family: SignalFamily::FingerprintTemporal,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintPersistenceMissing,
+        family: SignalFamily::FingerprintPersistence,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::FingerprintFlowViolation,
+        family: SignalFamily::FingerprintBehavior,
+        weight_field: None,
+    },
+    CalibratedSignal {
+        signal_id: SignalId::SessionAssetRatio,
+        family: SignalFamily::FingerprintBehavior,
+        weight_field: Some("session_asset_ratio"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::SessionTraversal,
+        family: SignalFamily::FingerprintBehavior,
+        weight_field: Some("session_traversal"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::SessionTiming,
+        family: SignalFamily::FingerprintBehavior,
+        weight_field: Some("session_timing"),
+    },
+    CalibratedSignal {
+        signal_id: SignalId::SessionReferer,
+        family: SignalFamily::FingerprintBehavior,
+        weight_field: Some("session_referer"),
+    },
+];
+
+fn current_weight(weights: &BotnessWeights, field: &str) -> Option<u8> {
+    match field {
+        "js_required" => Some(weights.js_required),
+        "geo_risk" => Some(weights.geo_risk),
+        "rate_medium" => Some(weights.rate_medium),
+        "rate_high" => Some(weights.rate_high),
+        "maze_behavior" => Some(weights.maze_behavior),
+        "session_asset_ratio" => Some(weights.session_asset_ratio),
+        "session_traversal" => Some(weights.session_traversal),
+        "session_timing" => Some(weights.session_timing),
+        "session_referer" => Some(weights.session_referer),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub(crate) struct CalibratedSignalSummary {
+    pub signal_id: String,
+    pub family: String,
+    pub active_bot_rows: u64,
+    pub active_human_rows: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coefficient: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_weight: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposed_weight: Option<u64>,
+}
+
+/// Precision and recall when every row scoring at or above `threshold` is called a bot.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub(crate) struct OperatingPoint {
+    pub threshold: f64,
+    pub precision: f64,
+    pub recall: f64,
+    pub false_positive_rate: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub(crate) struct SeparationSummary {
+    pub active_rows: u64,
+    pub roc_auc: f64,
+    pub average_precision: f64,
+    pub operating_points: Vec<OperatingPoint>,
+}
+
+/// Logistic fit of journaled botness signals against outcome labels. `families` scores each
+/// row by the fitted coefficients of one signal family; `composite` compares the recorded
+/// botness score with the full fitted model.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub(crate) struct BotnessCalibrationReport {
+    pub labelled_rows: u64,
+    pub bot_rows: u64,
+    pub human_rows: u64,
+    pub skipped_rows: BTreeMap<String, u64>,
+    pub label_sources: BTreeMap<String, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intercept: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_loss: Option<f64>,
+    pub signals: Vec<CalibratedSignalSummary>,
+    pub families: BTreeMap<String, SeparationSummary>,
+    pub composite: BTreeMap<String, SeparationSummary>,
+    pub proposed_weights: BTreeMap<String, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposal_blocker: Option<String>,
+}
+
+impl BotnessCalibrationReport {
+    /// Proposal confidence from the smaller of the two labelled classes.
+    pub(crate) fn confidence(&self) -> &'static str {
+        match self.bot_rows.min(self.human_rows) {
+            0..=99 => "low",
+            100..=499 => "medium",
+            _ => "high",
+        }
+    }
+}
+
+struct CalibrationSample {
+    active: Vec<usize>,
+    bot: bool,
+    recorded_score: u8,
+}
+
+fn active_signal_indexes(row: &RequestFactsJournalRow) -> Vec<usize> {
+    CALIBRATED_SIGNALS
+        .iter()
+        .enumerate()
+        .filter(|(_, signal)| {
+            row.botness_signal_ids
+                .iter()
+                .any(|id| id == signal.signal_id.as_str())
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn count(map: &mut BTreeMap<String, u64>, key: &str) {
+    *map.entry(key.to_string()).or_default() += 1;
+}
+
+/// Journal rows carry no session id; the IP bucket plus user-agent family is the closest match.
+fn session_of(row: &RequestFactsJournalRow) -> (&str, &str) {
+    (row.ip_bucket.as_str(), row.user_agent_family.as_str())
+}
+
+/// Label journaled rows. Honeypot hits settle in the first tranche, so they label the scored rows
+/// of the same session within the honeypot window; not-a-bot and Privacy Pass markers label their
+/// own row. Every adversary-sim row is a bot. IP buckets holding both honeypot and human evidence
+/// are dropped whole, since the two cannot be told apart behind a shared address, and sessions
+/// that presented a verified identity are left out so declared crawlers never count as positives.
+fn labelled_samples(
+    rows: &[RequestFactsJournalRow],
+    adversary_sim_rows: &[RequestFactsJournalRow],
+    report: &mut BotnessCalibrationReport,
+) -> Vec<CalibrationSample> {
+    let verified_sessions: BTreeSet<(&str, &str)> = rows
+        .iter()
+        .filter(|row| row.verified_identity.is_some())
+        .map(session_of)
+        .collect();
+    let mut honeypot_hits: BTreeMap<(&str, &str), Vec<u64>> = BTreeMap::new();
+    for row in rows
+        .iter()
+        .filter(|row| row.honeypot_hit && !verified_sessions.contains(&session_of(row)))
+    {
+        honeypot_hits
+            .entry(session_of(row))
+            .or_default()
+            .push(row.recorded_at_ts);
+    }
+    let human_buckets: BTreeSet<&str> = rows
+        .iter()
+        .filter(|row| row.not_a_bot_marker_valid || row.privacy_pass_token_valid)
+        .map(|row| row.ip_bucket.as_str())
+        .collect();
+    let conflicting_buckets: BTreeSet<&str> = honeypot_hits
+        .keys()
+        .map(|(ip_bucket, _)| *ip_bucket)
+        .filter(|ip_bucket| human_buckets.contains(ip_bucket))
+        .collect();
+
+    let mut samples = Vec::new();
+    for row in rows {
+        if row.stage == RequestFactsJournalStage::FirstTranche {
+            count(&mut report.skipped_rows, "unscored");
+            continue;
+        }
+        if verified_sessions.contains(&session_of(row)) {
+            count(&mut report.skipped_rows, "verified_identity");
+            continue;
+        }
+        if conflicting_buckets.contains(row.ip_bucket.as_str()) {
+            count(&mut report.skipped_rows, "conflicting");
+            continue;
+        }
+        let honeypot = honeypot_hits.get(&session_of(row)).is_some_and(|hits| {
+            hits.iter().any(|hit_ts| {
+                hit_ts.abs_diff(row.recorded_at_ts) <= CALIBRATION_HONEYPOT_SESSION_WINDOW_SECONDS
+            })
+        });
+        let human = row.not_a_bot_marker_valid || row.privacy_pass_token_valid;
+        if !honeypot && !human {
+            count(&mut report.skipped_rows, "unlabelled");
+            continue;
+        }
+        for (source, present) in [
+            ("honeypot", honeypot),
+            ("not_a_bot", row.not_a_bot_marker_valid),
+            ("privacy_pass", row.privacy_pass_token_valid),
+        ] {
+            if present {
+                count(&mut report.label_sources, source);
+            }
+        }
+        samples.push(CalibrationSample {
+            active: active_signal_indexes(row),
+            bot: honeypot,
+            recorded_score: row.botness_score,
+        });
+    }
+    for row in adversary_sim_rows {
+        if row.stage == RequestFactsJournalStage::FirstTranche {
+            count(&mut report.skipped_rows, "unscored");
+            continue;
+        }
+        count(&mut report.label_sources, "adversary_sim");
+        samples.push(CalibrationSample {
+            active: active_signal_indexes(row),
+            bot: true,
+            recorded_score: row.botness_score,
+        });
+    }
+    samples
+}
+
+fn sigmoid(value: f64) -> f64 {
+    1.0 / (1.0 + (-value).exp())
+}
+
+fn linear_score(coefficients: &[f64], active: &[usize]) -> f64 {
+    active.iter().map(|index| coefficients[*index]).sum()
+}
+
+struct LogisticFit {
+    intercept: f64,
+    coefficients: Vec<f64>,
+    log_loss: f64,
+}
+
+/// L2-regularised logistic regression by full-batch gradient descent. Classes are weighted to
+/// contribute equally, so a busy adversary-sim run does not drown out the human labels.
+fn fit_logistic(samples: &[CalibrationSample], bot_rows: u64, human_rows: u64) -> LogisticFit {
+    let total = samples.len() as f64;
+    let class_weight = |bot: bool| {
+        let rows = if bot { bot_rows } else { human_rows };
+        total / (2.0 * rows as f64)
+    };
+    let mut intercept = 0.0;
+    let mut coefficients = vec![0.0; CALIBRATED_SIGNALS.len()];
+    for _ in 0..FIT_ITERATIONS {
+        let mut intercept_gradient = 0.0;
+        let mut gradients = vec![0.0; CALIBRATED_SIGNALS.len()];
+        for sample in samples {
+            let predicted = sigmoid(intercept + linear_score(&coefficients, &sample.active));
+            let target = if sample.bot { 1.0 } else { 0.0 };
+            let residual = (predicted - target) * class_weight(sample.bot);
+            intercept_gradient += residual;
+            for index in &sample.active {
+                gradients[*index] += residual;
+            }
+        }
+        intercept -= FIT_LEARNING_RATE * intercept_gradient / total;
+        for (coefficient, gradient) in coefficients.iter_mut().zip(gradients) {
+            *coefficient -= FIT_LEARNING_RATE * (gradient / total + FIT_L2_PENALTY * *coefficient);
+        }
+    }
+    let log_loss = samples
+        .iter()
+        .map(|sample| {
+            let predicted = sigmoid(intercept + linear_score(&coefficients, &sample.active))
+                .clamp(1e-9, 1.0 - 1e-9);
+            let loss = if sample.bot {
+                -predicted.ln()
+            } else {
+                -(1.0 - predicted).ln()
+            };
+            loss * class_weight(sample.bot)
+        })
+        .sum::<f64>()
+        / total;
+    LogisticFit {
+        intercept,
+        coefficients,
+        log_loss,
+    }
+}
+
+fn round_metric(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+/// ROC AUC (ties count half), average precision and a bounded set of operating points for
+/// `(score, is_bot)` pairs. Both classes must be present.
+fn separation(mut scored: Vec<(f64, bool)>, active_rows: u64) -> SeparationSummary {
+    let positives = scored.iter().filter(|(_, bot)| *bot).count() as f64;
+    let negatives = scored.len() as f64 - positives;
+    scored.sort_by(|left, right| right.0.total_cmp(&left.0));
+
+    let mut true_positives = 0.0;
+    let mut false_positives = 0.0;
+    let mut roc_auc = 0.0;
+    let mut average_precision = 0.0;
+    let mut points = Vec::new();
+    let mut start = 0;
+    while start < scored.len() {
+        let threshold = scored[start].0;
+        let end = scored[start..]
+            .iter()
+            .position(|(score, _)| *score != threshold)
+            .map_or(scored.len(), |offset| start + offset);
+        let group_positives = scored[start..end].iter().filter(|(_, bot)| *bot).count() as f64;
+        let group_negatives = (end - start) as f64 - group_positives;
+        roc_auc += group_negatives * (true_positives + group_positives / 2.0);
+        true_positives += group_positives;
+        false_positives += group_negatives;
+        let precision = true_positives / (true_positives + false_positives);
+        average_precision += group_positives / positives * precision;
+        points.push(OperatingPoint {
+            threshold: round_metric(threshold),
+            precision: round_metric(precision),
+            recall: round_metric(true_positives / positives),
+            false_positive_rate: round_metric(false_positives / negatives),
+        });
+        start = end;
+    }
+
+    let operating_points = if points.len() <= CALIBRATION_MAX_OPERATING_POINTS {
+        points
+    } else {
+        let last = points.len() - 1;
+        (0..CALIBRATION_MAX_OPERATING_POINTS)
+            .map(|step| points[step * last / (CALIBRATION_MAX_OPERATING_POINTS - 1)].clone())
+            .collect()
+    };
+    SeparationSummary {
+        active_rows,
+        roc_auc: round_metric(roc_auc / (positives * negatives)),
+        average_precision: round_metric(average_precision),
+        operating_points,
+    }
+}
+
+/// Rescale positive coefficients so the supported tunable weights keep their current total,
+/// which leaves the botness thresholds meaning roughly what they meant before.
+fn proposed_weights(
+    fit: &LogisticFit,
+    support: &[(u64, u64)],
+    weights: &BotnessWeights,
+) -> BTreeMap<String, u64> {
+    let eligible: Vec<(&'static str, f64, u64)> = CALIBRATED_SIGNALS
+        .iter()
+        .zip(&fit.coefficients)
+        .zip(support)
+        .filter(|(_, (bot, human))| bot + human >= CALIBRATION_MIN_SIGNAL_SUPPORT)
+        .filter_map(|((signal, coefficient), _)| {
+            let field = signal.weight_field?;
+            let current = current_weight(weights, field)?;
+            Some((field, coefficient.max(0.0), current as u64))
+        })
+        .collect();
+    let evidence: f64 = eligible.iter().map(|(_, coefficient, _)| coefficient).sum();
+    if evidence <= 0.0 {
+        return BTreeMap::new();
+    }
+    let anchor = eligible
+        .iter()
+        .map(|(_, _, current)| current)
+        .sum::<u64>()
+        .max(1) as f64;
+    eligible
+        .into_iter()
+        .map(|(field, coefficient, _)| {
+            (
+                field.to_string(),
+                (coefficient * anchor / evidence).round() as u64,
+            )
+        })
+        .collect()
+}
+
+/// Fit botness weights from labelled journal rows. `adversary_sim_rows` come from the
+/// adversary-sim journal namespace and are all labelled bot.
+pub(crate) fn calibrate(
+    rows: &[RequestFactsJournalRow],
+    adversary_sim_rows: &[RequestFactsJournalRow],
+    weights: &BotnessWeights,
+) -> BotnessCalibrationReport {
+    let mut report = BotnessCalibrationReport::default();
+    let samples = labelled_samples(rows, adversary_sim_rows, &mut report);
+    report.labelled_rows = samples.len() as u64;
+    report.bot_rows = samples.iter().filter(|sample| sample.bot).count() as u64;
+    report.human_rows = report.labelled_rows - report.bot_rows;
+
+    let support: Vec<(u64, u64)> = (0..CALIBRATED_SIGNALS.len())
+        .map(|index| {
+            samples
+                .iter()
+                .filter(|sample| sample.active.contains(&index))
+                .fold((0, 0), |(bot, human), sample| {
+                    if sample.bot {
+                        (bot + 1, human)
+                    } else {
+                        (bot, human + 1)
+                    }
+                })
+        })
+        .collect();
+
+    let fit = (report.bot_rows > 0 && report.human_rows > 0)
+        .then(|| fit_logistic(&samples, report.bot_rows, report.human_rows));
+    if report.bot_rows.min(report.human_rows) < CALIBRATION_MIN_ROWS_PER_CLASS {
+        report.proposal_blocker = Some("insufficient_labels".to_string());
+    }
+
+    if let Some(fit) = fit.as_ref() {
+        report.intercept = Some(round_metric(fit.intercept));
+        report.log_loss = Some(round_metric(fit.log_loss));
+
+        let mut families: Vec<SignalFamily> = CALIBRATED_SIGNALS
+            .iter()
+            .map(|signal| signal.family)
+            .collect();
+        families.sort_unstable();
+        families.dedup();
+        for family in families {
+            let in_family = |index: &usize| CALIBRATED_SIGNALS[*index].family == family;
+            let scored = samples
+                .iter()
+                .map(|sample| {
+                    let family_active: Vec<usize> =
+                        sample.active.iter().copied().filter(in_family).collect();
+                    (linear_score(&fit.coefficients, &family_active), sample.bot)
+                })
+                .collect();
+            let active_rows = samples
+                .iter()
+                .filter(|sample| sample.active.iter().any(in_family))
+                .count() as u64;
+            report
+                .families
+                .insert(family.as_str().to_string(), separation(scored, active_rows));
+        }
+
+        report.composite.insert(
+            "recorded_score".to_string(),
+            separation(
+                samples
+                    .iter()
+                    .map(|sample| (sample.recorded_score as f64, sample.bot))
+                    .collect(),
+                samples
+                    .iter()
+                    .filter(|sample| sample.recorded_score > 0)
+                    .count() as u64,
+            ),
+        );
+        report.composite.insert(
+            "calibrated".to_string(),
+            separation(
+                samples
+                    .iter()
+                    .map(|sample| (linear_score(&fit.coefficients, &sample.active), sample.bot))
+                    .collect(),
+                samples
+                    .iter()
+                    .filter(|sample| !sample.active.is_empty())
+                    .count() as u64,
+            ),
+        );
+
+        if report.proposal_blocker.is_none() {
+            report.proposed_weights = proposed_weights(fit, &support, weights);
+            if report.proposed_weights.is_empty() {
+                report.proposal_blocker = Some("no_positive_evidence".to_string());
+            }
+        }
+    }
+
+    report.signals = CALIBRATED_SIGNALS
+        .iter()
+        .enumerate()
+        .map(|(index, signal)| CalibratedSignalSummary {
+            signal_id: signal.signal_id.as_str().to_string(),
+            family: signal.family.as_str().to_string(),
+            active_bot_rows: support[index].0,
+            active_human_rows: support[index].1,
+            coefficient: fit
+                .as_ref()
+                .map(|fit| round_metric(fit.coefficients[index])),
+            weight_field: signal.weight_field.map(str::to_string),
+            current_weight: signal
+                .weight_field
+                .and_then(|field| current_weight(weights, field)),
+            proposed_weight: signal
+                .weight_field
+                .and_then(|field| report.proposed_weights.get(field).copied()),
+        })
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(
+        stage: RequestFactsJournalStage,
+        ip_bucket: &str,
+        signal_ids: &[&str],
+        not_a_bot_marker_valid: bool,
+    ) -> RequestFactsJournalRow {
+        RequestFactsJournalRow {
+            recorded_at_ts: 1_700_000_000,
+            stage,
+            method: "GET".to_string(),
+            path: "/catalog".to_string(),
+            ip_bucket: ip_bucket.to_string(),
+            user_agent_family: "other".to_string(),
+            headers: vec![],
+            ip_range: None,
+            ip_range_shadow_matches: vec![],
+            honeypot_hit: false,
+            rate_limit_exceeded: false,
+            existing_ban: false,
+            geo_country: None,
+            needs_js: false,
+            browser_navigation_like: true,
+            botness_score: signal_ids.len() as u8,
+            botness_signal_ids: signal_ids.iter().map(|id| id.to_string()).collect(),
+            verified_identity: None,
+            not_a_bot_marker_valid,
+            privacy_pass_token_valid: false,
+            crawl_licence: None,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn verified_search_identity() -> crate::bot_identity::contracts::VerifiedIdentityEvidence {
+        crate::bot_identity::contracts::VerifiedIdentityEvidence {
+            scheme: crate::bot_identity::contracts::IdentityScheme::HttpMessageSignatures,
+            stable_identity: "searchbot".to_string(),
+            operator: "example".to_string(),
+            category: crate::bot_identity::contracts::IdentityCategory::Search,
+            verification_strength:
+                crate::bot_identity::contracts::VerificationStrength::Cryptographic,
+            end_user_controlled: false,
+            directory_source: None,
+            provenance: crate::bot_identity::contracts::IdentityProvenance::Native,
+            delegation: None,
+        }
+    }
+
+    #[test]
+    fn labels_follow_honeypot_sessions_markers_and_adversary_sim_rows() {
+        let mut honeypot = row(
+            RequestFactsJournalStage::FirstTranche,
+            "203.0.113.0",
+            &[],
+            false,
+        );
+        honeypot.honeypot_hit = true;
+        let mut other_session = row(
+            RequestFactsJournalStage::SecondTranche,
+            "203.0.113.0",
+            &["S_GEO_RISK"],
+            false,
+        );
+        other_session.user_agent_family = "chrome".to_string();
+        let mut much_later = row(
+            RequestFactsJournalStage::SecondTranche,
+            "203.0.113.0",
+            &["S_GEO_RISK"],
+            false,
+        );
+        much_later.recorded_at_ts += 2 * 3600;
+        let rows = vec![
+            honeypot,
+            row(
+                RequestFactsJournalStage::SecondTranche,
+                "203.0.113.0",
+                &["S_GEO_RISK"],
+                false,
+            ),
+            other_session,
+            much_later,
+            row(
+                RequestFactsJournalStage::SecondTranche,
+                "198.51.100.0",
+                &[],
+                true,
+            ),
+            row(
+                RequestFactsJournalStage::SecondTranche,
+                "192.0.2.0",
+                &[],
+                false,
+            ),
+        ];
+        let sim_rows = vec![row(
+            RequestFactsJournalStage::SecondTranche,
+            "10.0.0.0",
+            &["S_SESSION_TIMING"],
+            false,
+        )];
+
+        let report = calibrate(&rows, &sim_rows, &BotnessWeights::default());
+
+        assert_eq!((report.bot_rows, report.human_rows), (2, 1));
+        assert_eq!(report.skipped_rows["unscored"], 1);
+        assert_eq!(report.skipped_rows["unlabelled"], 3);
+        assert!(!report.skipped_rows.contains_key("conflicting"));
+        assert_eq!(report.label_sources["honeypot"], 1);
+        assert_eq!(report.label_sources["adversary_sim"], 1);
+        assert_eq!(report.label_sources["not_a_bot"], 1);
+        assert_eq!(
+            report.proposal_blocker.as_deref(),
+            Some("insufficient_labels")
+        );
+        assert!(report.proposed_weights.is_empty());
+        let timing = report
+            .signals
+            .iter()
+            .find(|signal| signal.signal_id == "S_SESSION_TIMING")
+            .unwrap();
+        assert_eq!((timing.active_bot_rows, timing.active_human_rows), (1, 0));
+        assert_eq!(timing.weight_field.as_deref(), Some("session_timing"));
+    }
+
+    #[test]
+    fn mixed_buckets_and_verified_crawlers_stay_out_of_the_labels() {
+        let mut honeypot = row(
+            RequestFactsJournalStage::FirstTranche,
+            "203.0.113.0",
+            &[],
+            false,
+        );
+        honeypot.honeypot_hit = true;
+        let mut crawler_trap = row(
+            RequestFactsJournalStage::FirstTranche,
+            "198.51.100.0",
+            &[],
+            false,
+        );
+        crawler_trap.honeypot_hit = true;
+        let mut crawler = row(
+            RequestFactsJournalStage::SecondTranche,
+            "198.51.100.0",
+            &["S_SESSION_TRAVERSAL"],
+            false,
+        );
+        crawler.verified_identity = Some(verified_search_identity());
+        let rows = vec![
+            honeypot,
+            row(
+                RequestFactsJournalStage::SecondTranche,
+                "203.0.113.0",
+                &["S_GEO_RISK"],
+                false,
+            ),
+            row(
+                RequestFactsJournalStage::SecondTranche,
+                "203.0.113.0",
+                &[],
+                true,
+            ),
+            crawler_trap,
+            crawler,
+            row(
+                RequestFactsJournalStage::SecondTranche,
+                "198.51.100.0",
+                &["S_SESSION_TRAVERSAL"],
+                false,
+            ),
+        ];
+
+        let report = calibrate(&rows, &[], &BotnessWeights::default());
+
+        assert_eq!((report.bot_rows, report.human_rows), (0, 0));
+        assert_eq!(report.skipped_rows["unscored"], 2);
+        assert_eq!(report.skipped_rows["conflicting"], 2);
+        assert_eq!(report.skipped_rows["verified_identity"], 2);
+        assert!(report.label_sources.is_empty());
+    }
+
+    #[test]
+    fn proxy_fingerprint_signals_are_calibrated_features() {
+        for signal_id in [
+            "S_FP_TRANSPORT_PROFILE_MISMATCH",
+            "S_FP_HEADER_SHAPE_MISMATCH",
+            "S_FP_HEADER_ORDER_MISMATCH",
+        ] {
+            let sim_rows = vec![row(
+                RequestFactsJournalStage::SecondTranche,
+                "10.0.0.0",
+                &[signal_id],
+                false,
+            )];
+            let report = calibrate(&[], &sim_rows, &BotnessWeights::default());
+            let summary = report
+                .signals
+                .iter()
+                .find(|signal| signal.signal_id == signal_id)
+                .expect("calibrated signal");
+            assert_eq!(summary.active_bot_rows, 1);
+            assert!(summary.weight_field.is_none());
+        }
+    }
+
+    #[test]
+    fn fitted_weights_favour_separating_signals_and_report_family_separation() {
+        let mut rows = Vec::new();
+        let mut sim_rows = Vec::new();
+        for index in 0..40 {
+            let human_signals: &[&str] = if index % 2 == 0 {
+                &["S_RATE_USAGE_MEDIUM"]
+            } else {
+                &[]
+            };
+            rows.push(row(
+                RequestFactsJournalStage::SecondTranche,
+                "198.51.100.0",
+                human_signals,
+                true,
+            ));
+            let bot_signals: &[&str] = if index % 2 == 0 {
+                &["S_GEO_RISK", "S_RATE_USAGE_MEDIUM"]
+            } else {
+                &["S_GEO_RISK"]
+            };
+            sim_rows.push(row(
+                RequestFactsJournalStage::SecondTranche,
+                "10.0.0.0",
+                bot_signals,
+                false,
+            ));
+        }
+        let weights = BotnessWeights {
+            geo_risk: 2,
+            rate_medium: 4,
+            ..BotnessWeights::default()
+        };
+
+        let report = calibrate(&rows, &sim_rows, &weights);
+
+        assert!(report.proposal_blocker.is_none());
+        assert_eq!(report.proposed_weights.get("geo_risk"), Some(&6));
+        assert_eq!(report.proposed_weights.get("rate_medium"), Some(&0));
+        assert!(!report.proposed_weights.contains_key("js_required"));
+        assert_close(report.families["geo"].roc_auc, 1.0);
+        assert_close(report.families["geo"].average_precision, 1.0);
+        assert_close(report.families["rate"].roc_auc, 0.5);
+        assert_eq!(report.families["geo"].active_rows, 40);
+        assert_close(report.composite["calibrated"].roc_auc, 1.0);
+        assert!(report.composite["recorded_score"].roc_auc < 1.0);
+        assert_eq!(report.confidence(), "low");
+        let geo_points = &report.families["geo"].operating_points;
+        assert_close(geo_points[0].precision, 1.0);
+        assert_close(geo_points[0].recall, 1.0);
+        assert_close(geo_points[0].false_positive_rate, 0.0);
+    }
+}
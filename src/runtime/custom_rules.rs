@@ -0,0 +1,906 @@
+//! Operator-defined policy rules.
+//!
+//! Each rule pairs a `when` expression over `RequestFacts` with one of the existing policy
+//! outcomes. Expressions are deliberately small: boolean `&&`, `||`, `!` and parentheses over
+//! fixed fields, with no user-defined functions, regexes or loops, so evaluation cost is bounded
+//! by the expression length. Rules are compiled once per distinct rule set and cached, the same
+//! way IP-range rules are.
+//!
+//! Fields:
+//! - text: `path`, `method`, `country`, `user_agent`, `header("name")`, `identity.operator`,
+//!   `identity.category`, `identity.stable_id`; compared with `==`, `!=`, `starts_with`,
+//!   `ends_with`, `contains` or `in ["a", "b"]`.
+//! - number: `botness`; compared with `==`, `!=`, `<`, `<=`, `>`, `>=`.
+//! - flags: `not_a_bot_marker`, `privacy_pass_token`, `verified_identity`, `browser_navigation`,
+//!   `js_required`, `signal("S_GEO_RISK")`, `has_header("name")`, `true`, `false`.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+
+use crate::config::{Config, CustomPolicyRule, CustomRuleAction, PolicyExecutionMode};
+use crate::runtime::policy_taxonomy::SignalId;
+use crate::runtime::request_facts::RequestFacts;
+
+pub(crate) const CUSTOM_RULES_MAX: usize = 64;
+const CUSTOM_RULE_ID_MAX_CHARS: usize = 64;
+const EXPRESSION_MAX_CHARS: usize = 1024;
+const EXPRESSION_MAX_DEPTH: usize = 16;
+const LIST_MAX_ITEMS: usize = 64;
+const CACHE_MAX_ENTRIES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CustomRuleMatch {
+    pub rule_id: String,
+    pub action: CustomRuleAction,
+    pub execution_mode: PolicyExecutionMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagField {
+    NotABotMarker,
+    PrivacyPassToken,
+    VerifiedIdentity,
+    BrowserNavigation,
+    JsRequired,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TextField {
+    Path,
+    Method,
+    Country,
+    UserAgent,
+    Header(String),
+    IdentityOperator,
+    IdentityCategory,
+    IdentityStableId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextOp {
+    Eq,
+    Ne,
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Literal(bool),
+    Any(Box<Condition>, Box<Condition>),
+    All(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Flag(FlagField),
+    Signal(SignalId),
+    HasHeader(String),
+    Botness(NumberOp, u32),
+    Text(TextField, TextOp, String),
+    TextIn(TextField, Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    id: String,
+    action: CustomRuleAction,
+    execution_mode: PolicyExecutionMode,
+    condition: Condition,
+}
+
+/// Keyed by rule-set hash, in insertion order so eviction drops the oldest compiled set.
+static COMPILED_RULES_CACHE: Lazy<Mutex<IndexMap<u64, Arc<Vec<CompiledRule>>>>> =
+    Lazy::new(|| Mutex::new(IndexMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(u32),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    And,
+    Or,
+    Not,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+fn describe_token(token: Option<&Token>) -> String {
+    match token {
+        None => "end of expression".to_string(),
+        Some(Token::Ident(name)) => format!("'{}'", name),
+        Some(Token::Text(value)) => format!("\"{}\"", value),
+        Some(Token::Number(value)) => value.to_string(),
+        Some(Token::LParen) => "'('".to_string(),
+        Some(Token::RParen) => "')'".to_string(),
+        Some(Token::LBracket) => "'['".to_string(),
+        Some(Token::RBracket) => "']'".to_string(),
+        Some(Token::Comma) => "','".to_string(),
+        Some(Token::And) => "'&&'".to_string(),
+        Some(Token::Or) => "'||'".to_string(),
+        Some(Token::Not) => "'!'".to_string(),
+        Some(Token::Eq) => "'=='".to_string(),
+        Some(Token::Ne) => "'!='".to_string(),
+        Some(Token::Lt) => "'<'".to_string(),
+        Some(Token::Le) => "'<='".to_string(),
+        Some(Token::Gt) => "'>'".to_string(),
+        Some(Token::Ge) => "'>='".to_string(),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let next = chars.get(index + 1).copied();
+        let (token, width) = match ch {
+            ch if ch.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '[' => (Token::LBracket, 1),
+            ']' => (Token::RBracket, 1),
+            ',' => (Token::Comma, 1),
+            '&' if next == Some('&') => (Token::And, 2),
+            '|' if next == Some('|') => (Token::Or, 2),
+            '!' if next == Some('=') => (Token::Ne, 2),
+            '!' => (Token::Not, 1),
+            '=' if next == Some('=') => (Token::Eq, 2),
+            '<' if next == Some('=') => (Token::Le, 2),
+            '<' => (Token::Lt, 1),
+            '>' if next == Some('=') => (Token::Ge, 2),
+            '>' => (Token::Gt, 1),
+            '"' => {
+                let mut value = String::new();
+                let mut cursor = index + 1;
+                loop {
+                    match chars.get(cursor) {
+                        None => return Err(format!("unterminated string at offset {}", index)),
+                        Some('"') => break,
+                        Some('\\') => match chars.get(cursor + 1) {
+                            Some(escaped @ ('"' | '\\')) => {
+                                value.push(*escaped);
+                                cursor += 1;
+                            }
+                            _ => {
+                                return Err(format!(
+                                    "unsupported escape in string at offset {}",
+                                    cursor
+                                ))
+                            }
+                        },
+                        Some(other) => value.push(*other),
+                    }
+                    cursor += 1;
+                }
+                (Token::Text(value), cursor + 1 - index)
+            }
+            ch if ch.is_ascii_digit() => {
+                let end = (index..chars.len())
+                    .find(|cursor| !chars[*cursor].is_ascii_digit())
+                    .unwrap_or(chars.len());
+                let digits = chars[index..end].iter().collect::<String>();
+                let value = digits
+                    .parse::<u32>()
+                    .map_err(|_| format!("number '{}' is out of range", digits))?;
+                (Token::Number(value), end - index)
+            }
+            ch if ch.is_ascii_alphabetic() || ch == '_' => {
+                let end = (index..chars.len())
+                    .find(|cursor| {
+                        let ch = chars[*cursor];
+                        !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.')
+                    })
+                    .unwrap_or(chars.len());
+                (
+                    Token::Ident(chars[index..end].iter().collect::<String>()),
+                    end - index,
+                )
+            }
+            other => {
+                return Err(format!(
+                    "unexpected character '{}' at offset {}",
+                    other, index
+                ))
+            }
+        };
+        tokens.push(token);
+        index += width;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        let found = self.advance();
+        if found.as_ref() == Some(&expected) {
+            return Ok(());
+        }
+        Err(format!(
+            "expected {}, found {}",
+            describe_token(Some(&expected)),
+            describe_token(found.as_ref())
+        ))
+    }
+
+    fn expect_text(&mut self) -> Result<String, String> {
+        match self.advance() {
+            Some(Token::Text(value)) => Ok(value),
+            other => Err(format!(
+                "expected a quoted string, found {}",
+                describe_token(other.as_ref())
+            )),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > EXPRESSION_MAX_DEPTH {
+            return Err(format!(
+                "expression nests deeper than {} levels",
+                EXPRESSION_MAX_DEPTH
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_any(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_all()?;
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            condition = Condition::Any(Box::new(condition), Box::new(self.parse_all()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_all(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            condition = Condition::All(Box::new(condition), Box::new(self.parse_unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, String> {
+        if self.peek() != Some(&Token::Not) {
+            return self.parse_primary();
+        }
+        self.advance();
+        self.enter()?;
+        let inner = self.parse_unary()?;
+        self.depth -= 1;
+        Ok(Condition::Not(Box::new(inner)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Condition, String> {
+        match self.advance() {
+            Some(Token::LParen) => {
+                self.enter()?;
+                let inner = self.parse_any()?;
+                self.expect(Token::RParen)?;
+                self.depth -= 1;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => self.parse_field(name.as_str()),
+            other => Err(format!(
+                "expected a condition, found {}",
+                describe_token(other.as_ref())
+            )),
+        }
+    }
+
+    fn parse_call_argument(&mut self, function: &str) -> Result<String, String> {
+        self.expect(Token::LParen)
+            .map_err(|err| format!("{}(...) {}", function, err))?;
+        let argument = self.expect_text()?;
+        self.expect(Token::RParen)?;
+        Ok(argument)
+    }
+
+    fn parse_field(&mut self, name: &str) -> Result<Condition, String> {
+        let text_field = match name {
+            "true" => return Ok(Condition::Literal(true)),
+            "false" => return Ok(Condition::Literal(false)),
+            "not_a_bot_marker" => return Ok(Condition::Flag(FlagField::NotABotMarker)),
+            "privacy_pass_token" => return Ok(Condition::Flag(FlagField::PrivacyPassToken)),
+            "verified_identity" => return Ok(Condition::Flag(FlagField::VerifiedIdentity)),
+            "browser_navigation" => return Ok(Condition::Flag(FlagField::BrowserNavigation)),
+            "js_required" => return Ok(Condition::Flag(FlagField::JsRequired)),
+            "signal" => {
+                let raw = self.parse_call_argument(name)?;
+                return crate::runtime::policy_taxonomy::botness_signal_id_from_str(raw.trim())
+                    .map(Condition::Signal)
+                    .ok_or_else(|| format!("signal(\"{}\") is not a botness signal id", raw));
+            }
+            "has_header" => {
+                let raw = self.parse_call_argument(name)?;
+                return Ok(Condition::HasHeader(normalize_header_name(raw.as_str())?));
+            }
+            "botness" => return self.parse_botness(),
+            "header" => {
+                let raw = self.parse_call_argument(name)?;
+                TextField::Header(normalize_header_name(raw.as_str())?)
+            }
+            "path" => TextField::Path,
+            "method" => TextField::Method,
+            "country" => TextField::Country,
+            "user_agent" => TextField::UserAgent,
+            "identity.operator" => TextField::IdentityOperator,
+            "identity.category" => TextField::IdentityCategory,
+            "identity.stable_id" => TextField::IdentityStableId,
+            other => return Err(format!("unknown field '{}'", other)),
+        };
+        self.parse_text_comparison(text_field)
+    }
+
+    fn parse_botness(&mut self) -> Result<Condition, String> {
+        let op = match self.advance() {
+            Some(Token::Eq) => NumberOp::Eq,
+            Some(Token::Ne) => NumberOp::Ne,
+            Some(Token::Lt) => NumberOp::Lt,
+            Some(Token::Le) => NumberOp::Le,
+            Some(Token::Gt) => NumberOp::Gt,
+            Some(Token::Ge) => NumberOp::Ge,
+            other => {
+                return Err(format!(
+                    "botness needs a numeric comparison, found {}",
+                    describe_token(other.as_ref())
+                ))
+            }
+        };
+        match self.advance() {
+            Some(Token::Number(value)) => Ok(Condition::Botness(op, value)),
+            other => Err(format!(
+                "botness must be compared with a number, found {}",
+                describe_token(other.as_ref())
+            )),
+        }
+    }
+
+    fn parse_text_comparison(&mut self, field: TextField) -> Result<Condition, String> {
+        let op = match self.advance() {
+            Some(Token::Eq) => TextOp::Eq,
+            Some(Token::Ne) => TextOp::Ne,
+            Some(Token::Ident(keyword)) if keyword == "starts_with" => TextOp::StartsWith,
+            Some(Token::Ident(keyword)) if keyword == "ends_with" => TextOp::EndsWith,
+            Some(Token::Ident(keyword)) if keyword == "contains" => TextOp::Contains,
+            Some(Token::Ident(keyword)) if keyword == "in" => {
+                return self.parse_text_list(field);
+            }
+            other => {
+                return Err(format!(
+                    "expected ==, !=, starts_with, ends_with, contains or in, found {}",
+                    describe_token(other.as_ref())
+                ))
+            }
+        };
+        let literal = normalize_text_literal(&field, self.expect_text()?);
+        Ok(Condition::Text(field, op, literal))
+    }
+
+    fn parse_text_list(&mut self, field: TextField) -> Result<Condition, String> {
+        self.expect(Token::LBracket)?;
+        let mut items = Vec::new();
+        if self.peek() != Some(&Token::RBracket) {
+            loop {
+                items.push(normalize_text_literal(&field, self.expect_text()?));
+                if items.len() > LIST_MAX_ITEMS {
+                    return Err(format!("lists are limited to {} items", LIST_MAX_ITEMS));
+                }
+                if self.peek() != Some(&Token::Comma) {
+                    break;
+                }
+                self.advance();
+            }
+        }
+        self.expect(Token::RBracket)?;
+        Ok(Condition::TextIn(field, items))
+    }
+}
+
+fn normalize_header_name(raw: &str) -> Result<String, String> {
+    let name = raw.trim().to_ascii_lowercase();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        return Err(format!("\"{}\" is not a valid header name", raw));
+    }
+    Ok(name)
+}
+
+fn normalize_text_literal(field: &TextField, literal: String) -> String {
+    match field {
+        TextField::Method | TextField::Country => literal.trim().to_ascii_uppercase(),
+        _ => literal,
+    }
+}
+
+fn compile_expression(source: &str) -> Result<Condition, String> {
+    if source.trim().is_empty() {
+        return Err("expression is empty".to_string());
+    }
+    if source.chars().count() > EXPRESSION_MAX_CHARS {
+        return Err(format!(
+            "expression exceeds {} characters",
+            EXPRESSION_MAX_CHARS
+        ));
+    }
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+        depth: 0,
+    };
+    let condition = parser.parse_any()?;
+    if parser.position < parser.tokens.len() {
+        return Err(format!(
+            "unexpected {} after a complete condition",
+            describe_token(parser.peek())
+        ));
+    }
+    Ok(condition)
+}
+
+fn compile_rules(rules: &[CustomPolicyRule]) -> Result<Vec<CompiledRule>, String> {
+    if rules.len() > CUSTOM_RULES_MAX {
+        return Err(format!(
+            "custom_rules exceeds max rules {}",
+            CUSTOM_RULES_MAX
+        ));
+    }
+    let mut seen_ids = HashSet::new();
+    let mut ordered = Vec::with_capacity(rules.len());
+    for (index, rule) in rules.iter().enumerate() {
+        let id = rule.id.trim();
+        if id.is_empty()
+            || id.len() > CUSTOM_RULE_ID_MAX_CHARS
+            || !id
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        {
+            return Err(format!(
+                "custom_rules[{}].id must be 1-{} characters of [a-zA-Z0-9_-]",
+                index, CUSTOM_RULE_ID_MAX_CHARS
+            ));
+        }
+        if !seen_ids.insert(id) {
+            return Err(format!("custom_rules contains duplicate id '{}'", id));
+        }
+        let condition = compile_expression(rule.when.as_str())
+            .map_err(|err| format!("custom_rules[{}].when ({}): {}", index, id, err))?;
+        if rule.enabled {
+            ordered.push((
+                rule.priority,
+                index,
+                CompiledRule {
+                    id: id.to_string(),
+                    action: rule.action,
+                    execution_mode: rule.execution_mode,
+                    condition,
+                },
+            ));
+        }
+    }
+    // Higher priority first; equal priorities keep their list order.
+    ordered.sort_by(|left, right| right.0.cmp(&left.0).then(left.1.cmp(&right.1)));
+    Ok(ordered.into_iter().map(|(_, _, rule)| rule).collect())
+}
+
+/// Rejects rule sets that would not compile, so bad expressions never reach persisted config.
+pub(crate) fn validate_custom_rules(rules: &[CustomPolicyRule]) -> Result<(), String> {
+    compile_rules(rules).map(|_| ())
+}
+
+fn rules_cache_key(rules: &[CustomPolicyRule]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    if let Ok(bytes) = serde_json::to_vec(rules) {
+        bytes.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn compiled_rules_for(cfg: &Config) -> Arc<Vec<CompiledRule>> {
+    let key = rules_cache_key(&cfg.custom_rules);
+    {
+        let cache = COMPILED_RULES_CACHE.lock().unwrap();
+        if let Some(rules) = cache.get(&key) {
+            return rules.clone();
+        }
+    }
+    // Persisted config is validated on load, so a failure here means a hand-built config;
+    // it compiles to no rules rather than failing the request.
+    let rules = Arc::new(compile_rules(&cfg.custom_rules).unwrap_or_default());
+    cache_compiled_rules(&mut COMPILED_RULES_CACHE.lock().unwrap(), key, rules.clone());
+    rules
+}
+
+fn cache_compiled_rules(
+    cache: &mut IndexMap<u64, Arc<Vec<CompiledRule>>>,
+    key: u64,
+    rules: Arc<Vec<CompiledRule>>,
+) {
+    if !cache.contains_key(&key) && cache.len() >= CACHE_MAX_ENTRIES {
+        cache.shift_remove_index(0);
+    }
+    cache.insert(key, rules);
+}
+
+fn flag_value(field: FlagField, facts: &RequestFacts) -> bool {
+    match field {
+        FlagField::NotABotMarker => facts.not_a_bot_marker_valid,
+        FlagField::PrivacyPassToken => facts.privacy_pass_token_valid,
+        FlagField::VerifiedIdentity => facts.verified_identity.is_some(),
+        FlagField::BrowserNavigation => facts.browser_navigation_like,
+        FlagField::JsRequired => facts.needs_js,
+    }
+}
+
+fn header_value<'a>(facts: &'a RequestFacts, name: &str) -> Option<&'a str> {
+    facts
+        .request_headers
+        .iter()
+        .find(|(header, _)| header == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn text_value<'a>(field: &TextField, facts: &'a RequestFacts) -> Option<Cow<'a, str>> {
+    let identity = facts.verified_identity.as_ref();
+    match field {
+        TextField::Path => Some(Cow::Borrowed(facts.path.as_str())),
+        TextField::Method => Some(Cow::Owned(facts.method.to_string())),
+        TextField::Country => facts
+            .geo_country
+            .as_deref()
+            .map(|country| Cow::Owned(country.to_ascii_uppercase())),
+        TextField::UserAgent => Some(Cow::Borrowed(facts.user_agent.as_str())),
+        TextField::Header(name) => header_value(facts, name.as_str()).map(Cow::Borrowed),
+        TextField::IdentityOperator => {
+            identity.map(|identity| Cow::Borrowed(identity.operator.as_str()))
+        }
+        TextField::IdentityCategory => {
+            identity.map(|identity| Cow::Borrowed(identity.category.as_str()))
+        }
+        TextField::IdentityStableId => {
+            identity.map(|identity| Cow::Borrowed(identity.stable_identity.as_str()))
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, facts: &RequestFacts) -> bool {
+        match self {
+            Condition::Literal(value) => *value,
+            Condition::Any(left, right) => left.matches(facts) || right.matches(facts),
+            Condition::All(left, right) => left.matches(facts) && right.matches(facts),
+            Condition::Not(inner) => !inner.matches(facts),
+            Condition::Flag(field) => flag_value(*field, facts),
+            Condition::Signal(signal_id) => facts.botness_signal_ids.contains(signal_id),
+            Condition::HasHeader(name) => header_value(facts, name.as_str()).is_some(),
+            Condition::Botness(op, threshold) => {
+                let score = u32::from(facts.botness_score);
+                match op {
+                    NumberOp::Eq => score == *threshold,
+                    NumberOp::Ne => score != *threshold,
+                    NumberOp::Lt => score < *threshold,
+                    NumberOp::Le => score <= *threshold,
+                    NumberOp::Gt => score > *threshold,
+                    NumberOp::Ge => score >= *threshold,
+                }
+            }
+            // A missing value (no header, no country, no identity) only satisfies `!=`.
+            Condition::Text(field, op, literal) => match text_value(field, facts) {
+                Some(value) => match op {
+                    TextOp::Eq => value == literal.as_str(),
+                    TextOp::Ne => value != literal.as_str(),
+                    TextOp::StartsWith => value.starts_with(literal.as_str()),
+                    TextOp::EndsWith => value.ends_with(literal.as_str()),
+                    TextOp::Contains => value.contains(literal.as_str()),
+                },
+                None => *op == TextOp::Ne,
+            },
+            Condition::TextIn(field, items) => text_value(field, facts)
+                .map(|value| items.iter().any(|item| value == item.as_str()))
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Returns matching enabled rules in priority order, up to and including the first enforced
+/// one. Shadowed rules never stop evaluation, so a rule under trial cannot mask a live rule.
+pub(crate) fn matching_rules(facts: &RequestFacts, cfg: &Config) -> Vec<CustomRuleMatch> {
+    let mut matches = Vec::new();
+    if cfg.custom_rules.is_empty() {
+        return matches;
+    }
+    for rule in compiled_rules_for(cfg)
+        .iter()
+        .filter(|rule| rule.condition.matches(facts))
+    {
+        let execution_mode = cfg.policy_source_execution_mode(
+            format!("custom_rule:{}", rule.id).as_str(),
+            rule.execution_mode,
+        );
+        matches.push(CustomRuleMatch {
+            rule_id: rule.id.clone(),
+            action: rule.action,
+            execution_mode,
+        });
+        if execution_mode == PolicyExecutionMode::Enforced {
+            break;
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spin_sdk::http::{Method, Request};
+
+    fn facts(method: Method, path: &str, country: Option<&str>, botness: u8) -> RequestFacts {
+        let req = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("X-Client-Tier", "free")
+            .build();
+        crate::runtime::request_facts::build_request_facts(
+            &req,
+            crate::runtime::request_facts::RequestFactInputs {
+                site_id: "default".to_string(),
+                ip: "198.51.100.7".to_string(),
+                user_agent: "Mozilla/5.0".to_string(),
+                ip_range_evaluation: crate::signals::ip_range_policy::Evaluation::NoMatch,
+                ip_range_shadow_matches: vec![],
+                honeypot_hit: false,
+                rate_limit_exceeded: false,
+                existing_ban: false,
+                geo_route: crate::signals::geo::GeoPolicyRoute::None,
+                geo_country: country.map(str::to_string),
+                needs_js: false,
+                browser_navigation_like: false,
+                botness_score: botness,
+                botness_signal_ids: vec![SignalId::GeoRisk],
+                botness_summary: "none".to_string(),
+                botness_state_summary: "none".to_string(),
+                runtime_metadata_summary: "none".to_string(),
+                provider_summary: "none".to_string(),
+                verified_identity: None,
+                not_a_bot_marker_valid: false,
+                privacy_pass_token_valid: false,
+                crawl_licence: None,
+            },
+        )
+    }
+
+    fn first_match(facts: &RequestFacts, cfg: &Config) -> Option<CustomRuleMatch> {
+        matching_rules(facts, cfg)
+            .into_iter()
+            .find(|matched| matched.execution_mode == PolicyExecutionMode::Enforced)
+    }
+
+    fn rule(id: &str, priority: i32, when: &str, action: CustomRuleAction) -> CustomPolicyRule {
+        CustomPolicyRule {
+            id: id.to_string(),
+            enabled: true,
+            priority,
+            when: when.to_string(),
+            action,
+            execution_mode: PolicyExecutionMode::Enforced,
+        }
+    }
+
+    fn cfg_with(rules: Vec<CustomPolicyRule>) -> Config {
+        let mut cfg = crate::config::defaults().clone();
+        cfg.custom_rules = rules;
+        cfg
+    }
+
+    #[test]
+    fn checkout_rule_matches_only_the_described_traffic() {
+        let cfg = cfg_with(vec![rule(
+            "checkout-xx",
+            0,
+            r#"method == "post" && path starts_with "/checkout" && country in ["xx", "YY"]
+                && botness >= 40 && !not_a_bot_marker"#,
+            CustomRuleAction::Challenge,
+        )]);
+
+        let matched = first_match(&facts(Method::Post, "/checkout/pay", Some("XX"), 55), &cfg)
+            .expect("rule should match");
+        assert_eq!(matched.rule_id, "checkout-xx");
+        assert_eq!(matched.action, CustomRuleAction::Challenge);
+
+        assert!(first_match(&facts(Method::Get, "/checkout/pay", Some("XX"), 55), &cfg).is_none());
+        assert!(first_match(&facts(Method::Post, "/cart", Some("XX"), 55), &cfg).is_none());
+        assert!(first_match(&facts(Method::Post, "/checkout", Some("GB"), 55), &cfg).is_none());
+        assert!(first_match(&facts(Method::Post, "/checkout", Some("XX"), 39), &cfg).is_none());
+        assert!(first_match(&facts(Method::Post, "/checkout", None, 55), &cfg).is_none());
+    }
+
+    #[test]
+    fn headers_signals_and_missing_values_follow_documented_semantics() {
+        let request = facts(Method::Get, "/", None, 10);
+        let eval = |when: &str| {
+            compile_expression(when)
+                .unwrap_or_else(|err| panic!("{when}: {err}"))
+                .matches(&request)
+        };
+
+        assert!(eval(r#"header("x-client-tier") == "free""#));
+        assert!(eval(r#"has_header("X-Client-Tier")"#));
+        assert!(!eval(r#"has_header("x-missing")"#));
+        assert!(eval(r#"header("x-missing") != "anything""#));
+        assert!(!eval(r#"header("x-missing") contains "a""#));
+        assert!(!eval(r#"country == "US""#));
+        assert!(eval(
+            r#"signal("S_GEO_RISK") && !signal("S_RATE_USAGE_HIGH")"#
+        ));
+        assert!(eval("false || (true && !verified_identity)"));
+    }
+
+    #[test]
+    fn priority_orders_rules_and_disabled_rules_are_skipped() {
+        let mut disabled = rule("disabled", 100, "true", CustomRuleAction::Block);
+        disabled.enabled = false;
+        let cfg = cfg_with(vec![
+            rule("low", -5, "true", CustomRuleAction::Maze),
+            disabled,
+            rule("first-high", 10, "true", CustomRuleAction::Allow),
+            rule("second-high", 10, "true", CustomRuleAction::Block),
+        ]);
+
+        let matched = first_match(&facts(Method::Get, "/", None, 0), &cfg).unwrap();
+        assert_eq!(matched.rule_id, "first-high");
+        assert_eq!(matched.action, CustomRuleAction::Allow);
+    }
+
+    #[test]
+    fn shadowed_rules_are_reported_ahead_of_the_enforced_match() {
+        let mut trial = rule("trial", 50, "true", CustomRuleAction::Block);
+        trial.execution_mode = PolicyExecutionMode::Shadow;
+        let mut cfg = cfg_with(vec![
+            trial,
+            rule("live", 10, "true", CustomRuleAction::Challenge),
+            rule("never-reached", 0, "true", CustomRuleAction::Maze),
+        ]);
+        let request = facts(Method::Get, "/", None, 0);
+
+        let matched = matching_rules(&request, &cfg);
+        let summary = matched
+            .iter()
+            .map(|matched| (matched.rule_id.as_str(), matched.execution_mode))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("trial", PolicyExecutionMode::Shadow),
+                ("live", PolicyExecutionMode::Enforced),
+            ]
+        );
+
+        cfg.shadow_policy_sources = vec!["custom_rule".to_string()];
+        let matched = matching_rules(&request, &cfg);
+        assert_eq!(matched.len(), 3);
+        assert!(matched
+            .iter()
+            .all(|matched| matched.execution_mode == PolicyExecutionMode::Shadow));
+    }
+
+    #[test]
+    fn validation_rejects_unsafe_or_malformed_rules() {
+        let invalid = |rules: Vec<CustomPolicyRule>| validate_custom_rules(&rules).unwrap_err();
+
+        assert!(
+            invalid(vec![rule("a", 0, "path == ", CustomRuleAction::Block)])
+                .contains("custom_rules[0].when (a)")
+        );
+        assert!(invalid(vec![rule(
+            "a",
+            0,
+            "ip == \"1.2.3.4\"",
+            CustomRuleAction::Block
+        )])
+        .contains("unknown field 'ip'"));
+        assert!(invalid(vec![rule(
+            "a",
+            0,
+            "botness >= \"40\"",
+            CustomRuleAction::Block
+        )])
+        .contains("compared with a number"));
+        assert!(invalid(vec![rule(
+            "a",
+            0,
+            "signal(\"S_NOPE\")",
+            CustomRuleAction::Block
+        )])
+        .contains("not a botness signal id"));
+        assert!(
+            invalid(vec![rule("a", 0, "true true", CustomRuleAction::Block)])
+                .contains("after a complete condition")
+        );
+        assert!(invalid(vec![rule("a", 0, "", CustomRuleAction::Block)]).contains("empty"));
+        assert!(
+            invalid(vec![rule("bad id", 0, "true", CustomRuleAction::Block)])
+                .contains("custom_rules[0].id")
+        );
+        assert!(invalid(vec![
+            rule("dup", 0, "true", CustomRuleAction::Block),
+            rule("dup", 0, "false", CustomRuleAction::Block),
+        ])
+        .contains("duplicate id 'dup'"));
+
+        let deep = format!("{}true{}", "(".repeat(20), ")".repeat(20));
+        assert!(
+            invalid(vec![rule("a", 0, deep.as_str(), CustomRuleAction::Block)])
+                .contains("nests deeper")
+        );
+        assert!(validate_custom_rules(&[rule(
+            "ok",
+            0,
+            r#"identity.category in ["search"] || user_agent contains "curl""#,
+            CustomRuleAction::Block,
+        )])
+        .is_ok());
+    }
+
+    #[test]
+    fn compiled_rules_cache_evicts_the_oldest_rule_set() {
+        let mut cache = IndexMap::new();
+        for key in 0..CACHE_MAX_ENTRIES as u64 {
+            cache_compiled_rules(&mut cache, key, Arc::new(Vec::new()));
+        }
+        cache_compiled_rules(&mut cache, 0, Arc::new(Vec::new()));
+        assert_eq!(cache.len(), CACHE_MAX_ENTRIES);
+
+        cache_compiled_rules(&mut cache, 100, Arc::new(Vec::new()));
+        assert_eq!(cache.len(), CACHE_MAX_ENTRIES);
+        assert!(!cache.contains_key(&0));
+        assert!(cache.contains_key(&1));
+        assert_eq!(cache.keys().last(), Some(&100));
+    }
+}
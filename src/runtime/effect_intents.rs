@@ -5,10 +5,11 @@ mod response_renderer;
 
 pub(crate) use intent_executor::{
     execute_effect_intents, execute_metric_intents, execute_monitoring_store_intents,
-    execute_plan, execute_request_outcome_intents,
+    execute_plan, execute_policy_source_plan, execute_request_outcome_intents,
+    execute_shadowed_source_plan, shadow_action_for_response,
 };
 pub(crate) use intent_types::{
-    BanIntent, EffectExecutionContext, EffectIntent, ExecutionMode, ShadowAction,
+    BanIntent, EffectExecutionContext, EffectIntent, ExecutionMode, ResponseIntent, ShadowAction,
 };
 pub(crate) use plan_builder::plan_for_decision;
 pub(crate) use response_renderer::render_forward_allow_response;
@@ -11,12 +11,18 @@ use super::intent_types::{
 };
 use super::response_renderer::{execute_response_intent, render_shadow_allow_response};
 
-fn shadow_action_for_response(response: &super::intent_types::ResponseIntent) -> Option<ShadowAction> {
+pub(crate) fn shadow_action_for_response(
+    response: &super::intent_types::ResponseIntent,
+) -> Option<ShadowAction> {
     match response {
         super::intent_types::ResponseIntent::Continue
-        | super::intent_types::ResponseIntent::ForwardAllow { .. } => None,
+        | super::intent_types::ResponseIntent::ForwardAllow { .. }
+        | super::intent_types::ResponseIntent::ForwardServiceProfile { .. }
+        | super::intent_types::ResponseIntent::VerifiedIdentityBudget { .. }
+        | super::intent_types::ResponseIntent::VerifiedIdentityQuota { .. } => None,
         super::intent_types::ResponseIntent::BlockPage { .. }
-        | super::intent_types::ResponseIntent::PlainTextBlock { .. } => Some(ShadowAction::Block),
+        | super::intent_types::ResponseIntent::PlainTextBlock { .. }
+        | super::intent_types::ResponseIntent::LicenceRequired { .. } => Some(ShadowAction::Block),
         super::intent_types::ResponseIntent::DropConnection => Some(ShadowAction::DropConnection),
         super::intent_types::ResponseIntent::Redirect { .. } => Some(ShadowAction::Redirect),
         super::intent_types::ResponseIntent::Maze { .. } => Some(ShadowAction::Maze),
@@ -44,6 +50,7 @@ fn suppress_shadow_monitoring_intent(intent: &EffectIntent) -> bool {
             | EffectIntent::RecordHoneypotHit { .. }
             | EffectIntent::RecordNotABotServed
             | EffectIntent::RecordNotABotSubmit { .. }
+            | EffectIntent::RecordNotABotAccessibleAlternative { .. }
             | EffectIntent::RecordChallengeFailure { .. }
             | EffectIntent::RecordIpRangeChallengeSolved
             | EffectIntent::RecordLikelyHumanSample { .. }
@@ -164,12 +171,29 @@ fn apply_monitoring_intent_with_store<S: crate::challenge::KeyValueStore>(
             crate::observability::monitoring::record_not_a_bot_served(store);
             None
         }
-        EffectIntent::RecordNotABotSubmit { outcome, solve_ms } => {
+        EffectIntent::RecordNotABotSubmit {
+            outcome,
+            solve_ms,
+            accessibility_cohort,
+        } => {
             crate::observability::monitoring::record_not_a_bot_submit(
                 store,
                 outcome.as_str(),
                 solve_ms,
             );
+            if accessibility_cohort {
+                crate::observability::monitoring::record_not_a_bot_accessibility_cohort(
+                    store,
+                    outcome.as_str(),
+                );
+            }
+            None
+        }
+        EffectIntent::RecordNotABotAccessibleAlternative { outcome } => {
+            crate::observability::monitoring::record_not_a_bot_accessible_alternative(
+                store,
+                outcome.as_str(),
+            );
             None
         }
         EffectIntent::RecordChallengeFailure { outcome } => {
@@ -196,10 +220,65 @@ fn apply_monitoring_intent_with_store<S: crate::challenge::KeyValueStore>(
             crate::observability::monitoring::record_verified_identity_telemetry(store, &record);
             None
         }
+        EffectIntent::RecordVerifiedIdentityDirectoryKeyRotation { rotation } => {
+            crate::observability::monitoring::record_verified_identity_directory_key_rotation(
+                store,
+                rotation.pinned_keys_missing,
+            );
+            crate::admin::log_event(
+                store,
+                &crate::admin::EventLogEntry {
+                    ts: crate::admin::now_ts(),
+                    event: crate::admin::EventType::AdminAction,
+                    ip: None,
+                    reason: Some("verified_identity_directory_key_rotation".to_string()),
+                    outcome: Some(format!(
+                        "source_uri={} added={} removed={} pinned_keys_missing={}",
+                        rotation.source_uri,
+                        rotation.added_key_ids.join(","),
+                        rotation.removed_key_ids.join(","),
+                        rotation.pinned_keys_missing
+                    )),
+                    admin: None,
+                },
+            );
+            None
+        }
+        EffectIntent::RecordVerifiedIdentityUsage { site_id, record } => {
+            crate::bot_identity::usage::record_usage(
+                store,
+                site_id.as_str(),
+                &record,
+                crate::admin::now_ts(),
+            );
+            None
+        }
+        EffectIntent::RecordCrawlLicence {
+            site_id,
+            identity,
+            licence,
+        } => {
+            crate::bot_identity::licensing::record_accepted_licence(
+                store,
+                site_id.as_str(),
+                &identity,
+                &licence,
+                crate::admin::now_ts(),
+            );
+            None
+        }
         EffectIntent::RecordRequestOutcome { outcome } => {
             crate::observability::monitoring::record_request_outcome(store, &outcome);
             None
         }
+        EffectIntent::RecordRequestFactsJournal { site_id, row } => {
+            let _ = crate::observability::request_facts_journal::record_row(
+                store,
+                site_id.as_str(),
+                *row,
+            );
+            None
+        }
         EffectIntent::RecordShadowAction { action } => {
             crate::observability::monitoring::record_shadow_action(store, action);
             None
@@ -212,6 +291,10 @@ fn apply_monitoring_intent_with_store<S: crate::challenge::KeyValueStore>(
             crate::observability::monitoring::flush_pending_counters(store);
             None
         }
+        EffectIntent::FlushEventExportBatches => {
+            crate::observability::event_export::flush_due_batches(store, crate::admin::now_ts());
+            None
+        }
         other => Some(other),
     }
 }
@@ -247,6 +330,21 @@ pub(super) fn apply_event_log_intent(
     }
 }
 
+fn apply_privacy_pass_intent(
+    store: &Store,
+    ip: &str,
+    ua: &str,
+    intent: EffectIntent,
+) -> Option<EffectIntent> {
+    match intent {
+        EffectIntent::GrantPrivacyPassIssuance => {
+            crate::challenge::privacy_pass::grant_issuance(store, ip, ua, crate::admin::now_ts());
+            None
+        }
+        other => Some(other),
+    }
+}
+
 pub(super) fn apply_ban_intent(
     _capability: &BanWriteCapability,
     store: &Store,
@@ -285,6 +383,62 @@ pub(crate) fn execute_plan(
     execute_response_intent(plan.response, facts, context, capabilities)
 }
 
+fn policy_source_action_intent(
+    source_id: &str,
+    execution_mode: ExecutionMode,
+    response: &super::intent_types::ResponseIntent,
+) -> EffectIntent {
+    let mode = match execution_mode {
+        ExecutionMode::Enforced => "enforced",
+        ExecutionMode::Shadow => "shadow",
+    };
+    let action = shadow_action_for_response(response).map_or("allow", ShadowAction::as_str);
+    EffectIntent::IncrementMetric {
+        metric: crate::observability::metrics::MetricName::PolicySourceActions,
+        label: Some(format!("{}:{}:{}", source_id, mode, action)),
+    }
+}
+
+/// Execute a plan that came from a named policy source, counting its outcome under that source.
+pub(crate) fn execute_policy_source_plan(
+    mut plan: DecisionPlan,
+    source_id: &str,
+    facts: &crate::runtime::request_facts::RequestFacts,
+    context: &EffectExecutionContext<'_>,
+    capabilities: &PolicyExecutionCapabilities,
+) -> Option<RenderedResponseEvidence> {
+    plan.intents.push(policy_source_action_intent(
+        source_id,
+        context.execution_mode,
+        &plan.response,
+    ));
+    execute_plan(plan, facts, context, capabilities)
+}
+
+/// Record what a shadowed policy source would have done. Nothing is rendered, so the caller
+/// carries on evaluating the remaining sources. Allow-style plans have nothing to suppress and
+/// are only counted.
+pub(crate) fn execute_shadowed_source_plan(
+    plan: DecisionPlan,
+    source_id: &str,
+    context: &EffectExecutionContext<'_>,
+    capabilities: &PolicyExecutionCapabilities,
+) {
+    let shadow_context = EffectExecutionContext {
+        execution_mode: ExecutionMode::Shadow,
+        ..*context
+    };
+    let source_action =
+        policy_source_action_intent(source_id, ExecutionMode::Shadow, &plan.response);
+    let (mut prepared_intents, shadow_action) =
+        prepare_intents_for_execution(plan.intents, ExecutionMode::Shadow, &plan.response);
+    if shadow_action.is_none() {
+        prepared_intents.clear();
+    }
+    prepared_intents.push(source_action);
+    execute_effect_intents(prepared_intents, &shadow_context, capabilities, shadow_action);
+}
+
 pub(crate) fn execute_effect_intents(
     intents: Vec<EffectIntent>,
     context: &EffectExecutionContext<'_>,
@@ -314,6 +468,10 @@ pub(crate) fn execute_effect_intents(
         else {
             continue;
         };
+        let Some(intent) = apply_privacy_pass_intent(context.store, context.ip, context.ua, intent)
+        else {
+            continue;
+        };
         apply_ban_intent(
             capabilities.ban_write(),
             context.store,
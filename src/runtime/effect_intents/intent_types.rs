@@ -63,11 +63,16 @@ pub(crate) enum EffectIntent {
     RecordNotABotSubmit {
         outcome: String,
         solve_ms: Option<u64>,
+        accessibility_cohort: bool,
+    },
+    RecordNotABotAccessibleAlternative {
+        outcome: String,
     },
     RecordChallengeFailure {
         outcome: String,
     },
     RecordIpRangeChallengeSolved,
+    GrantPrivacyPassIssuance,
     RecordBotnessVisibility {
         assessment: crate::BotnessAssessment,
     },
@@ -78,14 +83,31 @@ pub(crate) enum EffectIntent {
     RecordVerifiedIdentityTelemetry {
         record: crate::bot_identity::telemetry::IdentityVerificationTelemetryRecord,
     },
+    RecordVerifiedIdentityDirectoryKeyRotation {
+        rotation: crate::bot_identity::verification::IdentityDirectoryKeyRotation,
+    },
+    RecordVerifiedIdentityUsage {
+        site_id: String,
+        record: crate::bot_identity::telemetry::IdentityUsageRecord,
+    },
+    RecordCrawlLicence {
+        site_id: String,
+        identity: crate::bot_identity::contracts::VerifiedIdentityEvidence,
+        licence: crate::bot_identity::licensing::VerifiedCrawlLicence,
+    },
     RecordRequestOutcome {
         outcome: crate::runtime::request_outcome::RenderedRequestOutcome,
     },
+    RecordRequestFactsJournal {
+        site_id: String,
+        row: Box<crate::observability::request_facts_journal::RequestFactsJournalRow>,
+    },
     RecordShadowAction {
         action: ShadowAction,
     },
     RecordShadowPassThrough,
     FlushPendingMonitoringCounters,
+    FlushEventExportBatches,
     LogEvent {
         event: crate::admin::EventType,
         reason: String,
@@ -99,6 +121,25 @@ pub(crate) enum ResponseIntent {
     ForwardAllow {
         reason: String,
     },
+    /// Forward, then reduce the upstream response to what the service profile may see.
+    ForwardServiceProfile {
+        reason: String,
+        profile: crate::bot_identity::policy::ServiceProfile,
+    },
+    /// Continue to later stages while the verified identity stays within its request budget.
+    VerifiedIdentityBudget {
+        budget_key: String,
+        requests_per_minute: u32,
+    },
+    /// Count the request against a usage quota and apply the overage action once it is spent.
+    VerifiedIdentityQuota {
+        quota: crate::bot_identity::policy::IdentityUsageQuota,
+        subject_key: String,
+    },
+    /// Answer `402 Payment Required`, quoting the licence offer.
+    LicenceRequired {
+        offer: crate::bot_identity::policy::IdentityLicenceOffer,
+    },
     BlockPage {
         status: u16,
         reason: crate::enforcement::block_page::BlockReason,
@@ -71,9 +71,123 @@ fn verified_identity_base_outcome(
     if let Some(service_profile_id) = resolution.service_profile_id.as_deref() {
         parts.push(format!("service_profile_id={service_profile_id}"));
     }
+    if let Some(licence_offer_id) = resolution.licence_offer_id.as_deref() {
+        parts.push(format!("licence_offer_id={licence_offer_id}"));
+        if let Some(licence) = facts.crawl_licence.as_ref() {
+            parts.push(format!("licence_id={}", licence.licence_id));
+        }
+    }
     parts.join(" ")
 }
 
+fn custom_rule_transition(
+    action: crate::config::CustomRuleAction,
+) -> crate::runtime::policy_taxonomy::PolicyTransition {
+    use crate::config::CustomRuleAction;
+    use crate::runtime::policy_taxonomy::PolicyTransition;
+
+    match action {
+        CustomRuleAction::Allow => PolicyTransition::CustomRuleAllow,
+        CustomRuleAction::Block => PolicyTransition::CustomRuleBlock,
+        CustomRuleAction::Challenge => PolicyTransition::CustomRuleChallenge,
+        CustomRuleAction::NotABot => PolicyTransition::CustomRuleNotABot,
+        CustomRuleAction::JsChallenge => PolicyTransition::CustomRuleJsChallenge,
+        CustomRuleAction::Maze => PolicyTransition::CustomRuleMaze,
+    }
+}
+
+fn custom_rule_plan(
+    rule_id: &str,
+    action: crate::config::CustomRuleAction,
+    facts: &crate::runtime::request_facts::RequestFacts,
+) -> DecisionPlan {
+    use crate::config::CustomRuleAction;
+    use crate::observability::metrics::MetricName;
+
+    let policy_match =
+        crate::runtime::policy_taxonomy::resolve_policy_match(custom_rule_transition(action));
+    let reason = format!("custom_rule_{}", action.as_str());
+    let outcome = policy_match.annotate_outcome(
+        format!("rule_id={} action={}", rule_id, action.as_str()).as_str(),
+    );
+    let mut intents = vec![
+        EffectIntent::RecordPolicyMatch(custom_rule_transition(action)),
+        EffectIntent::IncrementMetric {
+            metric: MetricName::CustomRuleHits,
+            label: Some(format!("{}:{}", rule_id, action.as_str())),
+        },
+    ];
+    let (event, response) = match action {
+        CustomRuleAction::Allow => (
+            crate::admin::EventType::AdminAction,
+            ResponseIntent::ForwardAllow {
+                reason: reason.clone(),
+            },
+        ),
+        CustomRuleAction::Block => {
+            intents.push(EffectIntent::IncrementMetric {
+                metric: MetricName::BlocksTotal,
+                label: None,
+            });
+            (
+                crate::admin::EventType::Block,
+                ResponseIntent::BlockPage {
+                    status: 403,
+                    reason: crate::enforcement::block_page::BlockReason::CustomRule,
+                },
+            )
+        }
+        CustomRuleAction::Challenge => {
+            intents.push(EffectIntent::IncrementMetric {
+                metric: MetricName::ChallengesTotal,
+                label: None,
+            });
+            intents.push(EffectIntent::IncrementMetric {
+                metric: MetricName::ChallengeServedTotal,
+                label: None,
+            });
+            (crate::admin::EventType::Challenge, ResponseIntent::Challenge)
+        }
+        CustomRuleAction::NotABot => {
+            intents.push(EffectIntent::IncrementMetric {
+                metric: MetricName::ChallengesTotal,
+                label: None,
+            });
+            intents.push(EffectIntent::IncrementMetric {
+                metric: MetricName::NotABotServedTotal,
+                label: None,
+            });
+            intents.push(EffectIntent::RecordNotABotServed);
+            (crate::admin::EventType::Challenge, ResponseIntent::NotABot)
+        }
+        CustomRuleAction::JsChallenge => {
+            intents.push(EffectIntent::IncrementMetric {
+                metric: MetricName::ChallengesTotal,
+                label: None,
+            });
+            (crate::admin::EventType::Challenge, ResponseIntent::JsChallenge)
+        }
+        // The maze response logs its own entry event.
+        CustomRuleAction::Maze => {
+            return DecisionPlan {
+                intents,
+                response: ResponseIntent::Maze {
+                    entry_path: crate::maze::entry_path("custom-rule"),
+                    event_reason: reason,
+                    event_outcome: outcome,
+                    botness_score: Some(facts.botness_score),
+                },
+            };
+        }
+    };
+    intents.push(EffectIntent::LogEvent {
+        event,
+        reason,
+        outcome,
+    });
+    DecisionPlan { intents, response }
+}
+
 pub(crate) fn plan_for_decision(
     decision: &crate::runtime::policy_graph::PolicyDecision,
     facts: &crate::runtime::request_facts::RequestFacts,
@@ -518,19 +632,44 @@ pub(crate) fn plan_for_decision(
             let policy_match =
                 resolve_policy_match(PolicyTransition::VerifiedIdentityPolicyAllow(signal_ids));
             let base_outcome = verified_identity_base_outcome(facts, resolution);
+            let mut intents = vec![
+                EffectIntent::RecordPolicyMatch(PolicyTransition::VerifiedIdentityPolicyAllow(
+                    verified_identity_signal_ids(resolution),
+                )),
+                EffectIntent::LogEvent {
+                    event: crate::admin::EventType::AdminAction,
+                    reason: "verified_identity_policy_allow".to_string(),
+                    outcome: policy_match.annotate_outcome(base_outcome.as_str()),
+                },
+            ];
+            if let (
+                crate::bot_identity::policy::IdentityPolicyOutcome::RequireLicence,
+                Some(identity),
+                Some(licence),
+            ) = (
+                resolution.outcome,
+                facts.verified_identity.as_ref(),
+                facts.crawl_licence.as_ref(),
+            ) {
+                intents.push(EffectIntent::RecordCrawlLicence {
+                    site_id: facts.site_id.clone(),
+                    identity: identity.clone(),
+                    licence: licence.clone(),
+                });
+            }
             DecisionPlan {
-                intents: vec![
-                    EffectIntent::RecordPolicyMatch(PolicyTransition::VerifiedIdentityPolicyAllow(
-                        verified_identity_signal_ids(resolution),
-                    )),
-                    EffectIntent::LogEvent {
-                        event: crate::admin::EventType::AdminAction,
+                intents,
+                response: match resolution.outcome {
+                    crate::bot_identity::policy::IdentityPolicyOutcome::UseServiceProfile(
+                        profile @ (crate::bot_identity::policy::ServiceProfile::MetadataOnly
+                        | crate::bot_identity::policy::ServiceProfile::StructuredAgent),
+                    ) => ResponseIntent::ForwardServiceProfile {
+                        reason: "verified_identity_policy_allow".to_string(),
+                        profile,
+                    },
+                    _ => ResponseIntent::ForwardAllow {
                         reason: "verified_identity_policy_allow".to_string(),
-                        outcome: policy_match.annotate_outcome(base_outcome.as_str()),
                     },
-                ],
-                response: ResponseIntent::ForwardAllow {
-                    reason: "verified_identity_policy_allow".to_string(),
                 },
             }
         }
@@ -560,6 +699,33 @@ pub(crate) fn plan_for_decision(
             let policy_match =
                 resolve_policy_match(PolicyTransition::VerifiedIdentityPolicyRestrict(signal_ids));
             let base_outcome = verified_identity_base_outcome(facts, resolution);
+            if crate::runtime::verified_identity_serving::restricted_path_denied(
+                &cfg.verified_identity.restrict_denied_path_prefixes,
+                facts.path.as_str(),
+            ) {
+                return DecisionPlan {
+                    intents: vec![
+                        EffectIntent::RecordPolicyMatch(
+                            PolicyTransition::VerifiedIdentityPolicyRestrict(
+                                verified_identity_signal_ids(resolution),
+                            ),
+                        ),
+                        EffectIntent::IncrementMetric {
+                            metric: crate::observability::metrics::MetricName::BlocksTotal,
+                            label: None,
+                        },
+                        EffectIntent::LogEvent {
+                            event: crate::admin::EventType::Block,
+                            reason: "verified_identity_policy_restrict_path".to_string(),
+                            outcome: policy_match.annotate_outcome(base_outcome.as_str()),
+                        },
+                    ],
+                    response: ResponseIntent::BlockPage {
+                        status: 403,
+                        reason: crate::enforcement::block_page::BlockReason::VerifiedIdentityPolicy,
+                    },
+                };
+            }
             DecisionPlan {
                 intents: vec![
                     EffectIntent::RecordPolicyMatch(
@@ -573,9 +739,55 @@ pub(crate) fn plan_for_decision(
                         outcome: policy_match.annotate_outcome(base_outcome.as_str()),
                     },
                 ],
-                response: ResponseIntent::Continue,
+                response: match facts.verified_identity.as_ref() {
+                    Some(identity) if cfg.verified_identity.restrict_requests_per_minute > 0 => {
+                        ResponseIntent::VerifiedIdentityBudget {
+                            budget_key:
+                                crate::runtime::verified_identity_serving::identity_budget_key(
+                                    identity,
+                                ),
+                            requests_per_minute: cfg.verified_identity.restrict_requests_per_minute,
+                        }
+                    }
+                    _ => ResponseIntent::Continue,
+                },
+            }
+        }
+        PolicyDecision::VerifiedIdentityLicenceRequired { resolution, offer } => {
+            let signal_ids = verified_identity_signal_ids(resolution);
+            let policy_match = resolve_policy_match(
+                PolicyTransition::VerifiedIdentityLicenceRequired(signal_ids),
+            );
+            let base_outcome = verified_identity_base_outcome(facts, resolution);
+            DecisionPlan {
+                intents: vec![
+                    EffectIntent::RecordPolicyMatch(
+                        PolicyTransition::VerifiedIdentityLicenceRequired(
+                            verified_identity_signal_ids(resolution),
+                        ),
+                    ),
+                    EffectIntent::LogEvent {
+                        event: crate::admin::EventType::Block,
+                        reason: "verified_identity_licence_required".to_string(),
+                        outcome: policy_match.annotate_outcome(base_outcome.as_str()),
+                    },
+                ],
+                response: ResponseIntent::LicenceRequired {
+                    offer: offer.as_ref().clone(),
+                },
             }
         }
+        PolicyDecision::VerifiedIdentityQuota { quota } => DecisionPlan {
+            // Usage is metered per request; overage is logged once per window by the renderer.
+            intents: Vec::new(),
+            response: match facts.verified_identity.as_ref() {
+                Some(identity) => ResponseIntent::VerifiedIdentityQuota {
+                    quota: quota.clone(),
+                    subject_key: crate::bot_identity::usage::quota_subject_key(quota, identity),
+                },
+                None => ResponseIntent::Continue,
+            },
+        },
         PolicyDecision::GeoBlock => {
             let country = facts.geo_country.clone();
             let country_summary = format!("country={}", country.as_deref().unwrap_or("unknown"));
@@ -901,6 +1113,27 @@ pub(crate) fn plan_for_decision(
                 response: ResponseIntent::JsChallenge,
             }
         }
+        PolicyDecision::PrivacyPassTokenRedeemed { score } => {
+            let policy_match = resolve_policy_match(PolicyTransition::PrivacyPassTokenRedeemed);
+            DecisionPlan {
+                intents: vec![
+                    EffectIntent::RecordPolicyMatch(PolicyTransition::PrivacyPassTokenRedeemed),
+                    EffectIntent::LogEvent {
+                        event: crate::admin::EventType::Challenge,
+                        reason: "privacy_pass_token_redeemed".to_string(),
+                        outcome: policy_match
+                            .annotate_outcome(compact_botness_outcome("bypassed", *score).as_str()),
+                    },
+                ],
+                response: ResponseIntent::ForwardAllow {
+                    reason: "privacy_pass_token_redeemed".to_string(),
+                },
+            }
+        }
+        PolicyDecision::CustomRule { rule_id, action } => {
+            custom_rule_plan(rule_id.as_str(), *action, facts)
+        }
+        PolicyDecision::Shadowed { decision, .. } => plan_for_decision(decision, facts, cfg),
     }
 }
 
@@ -919,6 +1152,7 @@ mod tests {
             end_user_controlled: true,
             directory_source: None,
             provenance: crate::bot_identity::contracts::IdentityProvenance::Provider,
+            delegation: None,
         }
     }
 
@@ -937,6 +1171,7 @@ mod tests {
                 ip: "203.0.113.9".to_string(),
                 user_agent: "ua".to_string(),
                 ip_range_evaluation: crate::signals::ip_range_policy::Evaluation::NoMatch,
+                ip_range_shadow_matches: vec![],
                 honeypot_hit: false,
                 rate_limit_exceeded: false,
                 existing_ban: false,
@@ -952,6 +1187,8 @@ mod tests {
                 provider_summary: "providers".to_string(),
                 verified_identity: None,
                 not_a_bot_marker_valid: false,
+                privacy_pass_token_valid: false,
+                crawl_licence: None,
             },
         )
     }
@@ -965,15 +1202,26 @@ mod tests {
             EffectIntent::RecordHoneypotHit { .. } => "record_honeypot_hit",
             EffectIntent::RecordNotABotServed => "record_not_a_bot_served",
             EffectIntent::RecordNotABotSubmit { .. } => "record_not_a_bot_submit",
+            EffectIntent::RecordNotABotAccessibleAlternative { .. } => {
+                "record_not_a_bot_accessible_alternative"
+            }
             EffectIntent::RecordChallengeFailure { .. } => "record_challenge_failure",
             EffectIntent::RecordIpRangeChallengeSolved => "record_ip_range_challenge_solved",
+            EffectIntent::GrantPrivacyPassIssuance => "grant_privacy_pass_issuance",
             EffectIntent::RecordBotnessVisibility { .. } => "record_botness_visibility",
             EffectIntent::RecordLikelyHumanSample { .. } => "record_likely_human_sample",
             EffectIntent::RecordVerifiedIdentityTelemetry { .. } => "record_verified_identity_telemetry",
+            EffectIntent::RecordVerifiedIdentityDirectoryKeyRotation { .. } => {
+                "record_verified_identity_directory_key_rotation"
+            }
+            EffectIntent::RecordVerifiedIdentityUsage { .. } => "record_verified_identity_usage",
+            EffectIntent::RecordCrawlLicence { .. } => "record_crawl_licence",
             EffectIntent::RecordRequestOutcome { .. } => "record_request_outcome",
+            EffectIntent::RecordRequestFactsJournal { .. } => "record_request_facts_journal",
             EffectIntent::RecordShadowAction { .. } => "record_shadow_action",
             EffectIntent::RecordShadowPassThrough => "record_shadow_pass_through",
             EffectIntent::FlushPendingMonitoringCounters => "flush_pending_monitoring_counters",
+            EffectIntent::FlushEventExportBatches => "flush_event_export_batches",
             EffectIntent::LogEvent { .. } => "log_event",
             EffectIntent::Ban(_) => "ban",
         }
@@ -983,6 +1231,10 @@ mod tests {
         match response {
             ResponseIntent::Continue => "continue",
             ResponseIntent::ForwardAllow { .. } => "forward_allow",
+            ResponseIntent::ForwardServiceProfile { .. } => "forward_service_profile",
+            ResponseIntent::VerifiedIdentityBudget { .. } => "verified_identity_budget",
+            ResponseIntent::VerifiedIdentityQuota { .. } => "verified_identity_quota",
+            ResponseIntent::LicenceRequired { .. } => "licence_required",
             ResponseIntent::BlockPage { .. } => "block_page",
             ResponseIntent::PlainTextBlock { .. } => "plain_text_block",
             ResponseIntent::DropConnection => "drop_connection",
@@ -1105,7 +1357,7 @@ mod tests {
     }
 
     #[test]
-    fn verified_identity_policy_allow_short_circuits_with_forward_allow() {
+    fn verified_identity_policy_allow_short_circuits_with_service_profile_forward() {
         let mut facts = facts();
         facts.verified_identity = Some(verified_identity());
 
@@ -1148,8 +1400,10 @@ mod tests {
 
         assert!(matches!(
             plan.response,
-            ResponseIntent::ForwardAllow { ref reason }
-                if reason == "verified_identity_policy_allow"
+            ResponseIntent::ForwardServiceProfile {
+                ref reason,
+                profile: crate::bot_identity::policy::ServiceProfile::StructuredAgent,
+            } if reason == "verified_identity_policy_allow"
         ));
         assert!(plan.intents.iter().any(|intent| matches!(
             intent,
@@ -1169,7 +1423,8 @@ mod tests {
     }
 
     #[test]
-    fn verified_identity_policy_observe_and_restrict_continue_with_distinct_taxonomy() {
+    fn verified_identity_policy_observe_continues_and_restrict_is_budgeted_with_distinct_taxonomy(
+    ) {
         let mut facts = facts();
         facts.verified_identity = Some(verified_identity());
         let objectives =
@@ -1228,7 +1483,13 @@ mod tests {
         );
 
         assert!(matches!(observe_plan.response, ResponseIntent::Continue));
-        assert!(matches!(restrict_plan.response, ResponseIntent::Continue));
+        assert!(matches!(
+            restrict_plan.response,
+            ResponseIntent::VerifiedIdentityBudget {
+                requests_per_minute,
+                ..
+            } if requests_per_minute == cfg().verified_identity.restrict_requests_per_minute
+        ));
 
         let observe_outcome = observe_plan
             .intents
@@ -1266,6 +1527,174 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verified_identity_restrict_blocks_denied_paths_and_skips_a_disabled_budget() {
+        let mut facts = facts();
+        facts.path = "/articles/full".to_string();
+        facts.verified_identity = Some(verified_identity());
+        let objectives =
+            crate::observability::operator_snapshot_objectives::humans_plus_verified_only_operator_objectives(1);
+        let context = crate::runtime::non_human_policy::verified_identity_policy_context(
+            &objectives,
+            facts.verified_identity.as_ref().expect("verified identity"),
+        );
+        let resolution = crate::bot_identity::policy::resolve_identity_policy(
+            &context,
+            &[crate::bot_identity::policy::IdentityPolicyEntry {
+                policy_id: "restrict-openai".to_string(),
+                description: None,
+                matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+                },
+                action: crate::bot_identity::policy::IdentityPolicyAction::Restrict,
+            }],
+            &[],
+            &cfg().verified_identity.service_profiles,
+            facts.verified_identity.as_ref().expect("verified identity"),
+            facts.path.as_str(),
+        );
+        let decision = crate::runtime::policy_graph::PolicyDecision::VerifiedIdentityPolicyRestrict {
+            resolution,
+        };
+
+        let mut cfg = cfg();
+        cfg.verified_identity.restrict_denied_path_prefixes = vec!["/articles/".to_string()];
+        let denied_plan = plan_for_decision(&decision, &facts, &cfg);
+        assert!(matches!(
+            denied_plan.response,
+            ResponseIntent::BlockPage {
+                status: 403,
+                reason: crate::enforcement::block_page::BlockReason::VerifiedIdentityPolicy,
+            }
+        ));
+        assert!(denied_plan.intents.iter().any(|intent| matches!(
+            intent,
+            EffectIntent::LogEvent { reason, .. } if reason == "verified_identity_policy_restrict_path"
+        )));
+
+        cfg.verified_identity.restrict_denied_path_prefixes = vec!["/private/".to_string()];
+        cfg.verified_identity.restrict_requests_per_minute = 0;
+        let unbudgeted_plan = plan_for_decision(&decision, &facts, &cfg);
+        assert!(matches!(unbudgeted_plan.response, ResponseIntent::Continue));
+    }
+
+    #[test]
+    fn verified_identity_licence_plans_quote_the_offer_and_record_accepted_licences() {
+        let mut facts = facts();
+        facts.verified_identity = Some(verified_identity());
+        let offer = crate::bot_identity::policy::IdentityLicenceOffer {
+            offer_id: "archive".to_string(),
+            description: None,
+            price: "0.002".to_string(),
+            currency: "USD".to_string(),
+            terms_url: "https://example.com/licensing".to_string(),
+        };
+        let resolution = crate::bot_identity::policy::resolve_identity_policy(
+            &crate::bot_identity::policy::IdentityPolicyContext {
+                profile_id: "humans_plus_verified_only".to_string(),
+                verified_identity_override_mode: "explicit_overrides_eligible".to_string(),
+                canonical_category_id: "agent_on_behalf_of_human".to_string(),
+                base_posture: "tolerated".to_string(),
+            },
+            &[crate::bot_identity::policy::IdentityPolicyEntry {
+                policy_id: "licence-openai".to_string(),
+                description: None,
+                matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+                },
+                action: crate::bot_identity::policy::IdentityPolicyAction::RequireLicence(
+                    "archive".to_string(),
+                ),
+            }],
+            &[],
+            &[],
+            facts.verified_identity.as_ref().expect("verified identity"),
+            "/articles/1",
+        );
+
+        let required = plan_for_decision(
+            &crate::runtime::policy_graph::PolicyDecision::VerifiedIdentityLicenceRequired {
+                resolution: resolution.clone(),
+                offer: Box::new(offer.clone()),
+            },
+            &facts,
+            &cfg(),
+        );
+        assert_eq!(
+            required.intents.iter().map(intent_label).collect::<Vec<_>>(),
+            vec!["record_policy_match", "log_event"]
+        );
+        assert!(matches!(
+            required.response,
+            ResponseIntent::LicenceRequired { offer: ref quoted } if quoted == &offer
+        ));
+        assert_eq!(
+            crate::runtime::effect_intents::shadow_action_for_response(&required.response),
+            Some(crate::runtime::effect_intents::ShadowAction::Block)
+        );
+
+        facts.crawl_licence = Some(crate::bot_identity::licensing::VerifiedCrawlLicence {
+            licence_id: "lic-1".to_string(),
+            offer_id: "archive".to_string(),
+            key_id: "billing".to_string(),
+            issued_at: 0,
+            expires_at: u64::MAX,
+        });
+        let accepted = plan_for_decision(
+            &crate::runtime::policy_graph::PolicyDecision::VerifiedIdentityPolicyAllow {
+                resolution,
+            },
+            &facts,
+            &cfg(),
+        );
+        assert_eq!(
+            accepted.intents.iter().map(intent_label).collect::<Vec<_>>(),
+            vec!["record_policy_match", "log_event", "record_crawl_licence"]
+        );
+        assert!(matches!(accepted.response, ResponseIntent::ForwardAllow { .. }));
+    }
+
+    #[test]
+    fn verified_identity_quota_plan_defers_metering_to_the_renderer() {
+        let mut facts = facts();
+        facts.verified_identity = Some(verified_identity());
+        let quota = crate::bot_identity::policy::IdentityUsageQuota {
+            quota_id: "openai-monthly".to_string(),
+            description: None,
+            matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                operator: Some("openai".to_string()),
+                ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+            },
+            scope: crate::bot_identity::policy::IdentityQuotaScope::StableIdentity,
+            window: crate::bot_identity::policy::IdentityQuotaWindow::Monthly,
+            max_requests: 10_000,
+            overage_action: crate::bot_identity::policy::IdentityQuotaOverageAction::Challenge,
+        };
+        let decision = crate::runtime::policy_graph::PolicyDecision::VerifiedIdentityQuota {
+            quota: quota.clone(),
+        };
+
+        let plan = plan_for_decision(&decision, &facts, &cfg());
+
+        assert!(plan.intents.is_empty());
+        assert!(matches!(
+            plan.response,
+            ResponseIntent::VerifiedIdentityQuota { quota: ref planned, ref subject_key }
+                if planned == &quota
+                    && subject_key
+                        == &crate::bot_identity::usage::quota_subject_key(
+                            &quota,
+                            facts.verified_identity.as_ref().expect("verified identity"),
+                        )
+        ));
+        assert_eq!(
+            crate::runtime::effect_intents::shadow_action_for_response(&plan.response),
+            None
+        );
+    }
+
     #[test]
     fn characterization_snapshot_captures_plan_parity_for_migrated_seams() {
         struct Case {
@@ -57,6 +57,135 @@ pub(super) fn execute_response_intent(
         ResponseIntent::ForwardAllow { reason } => {
             Some(render_forward_allow_response(context, reason.as_str()))
         }
+        ResponseIntent::ForwardServiceProfile { reason, profile } => {
+            let mut evidence = render_forward_allow_response(context, reason.as_str());
+            if evidence.forward_failure_class.is_none() {
+                evidence.response =
+                    crate::runtime::verified_identity_serving::shape_forwarded_response(
+                        profile,
+                        evidence.response,
+                    );
+            }
+            Some(evidence)
+        }
+        ResponseIntent::VerifiedIdentityBudget {
+            budget_key,
+            requests_per_minute,
+        } => {
+            // Shadow execution never consumes or enforces the budget.
+            if matches!(context.execution_mode, super::intent_types::ExecutionMode::Shadow)
+                || crate::runtime::verified_identity_serving::consume_identity_budget(
+                    context.store,
+                    context.site_id,
+                    budget_key.as_str(),
+                    requests_per_minute,
+                )
+            {
+                return None;
+            }
+            Some(RenderedResponseEvidence::local(
+                spin_sdk::http::Response::builder()
+                    .status(429)
+                    .header("Content-Type", "text/plain; charset=utf-8")
+                    .header("Cache-Control", "no-store")
+                    .header(
+                        "Retry-After",
+                        crate::runtime::verified_identity_serving::budget_retry_after_seconds()
+                            .to_string(),
+                    )
+                    .body("Verified identity request budget exceeded")
+                    .build(),
+                ResponseKind::PlainTextBlock,
+            ))
+        }
+        ResponseIntent::VerifiedIdentityQuota { quota, subject_key } => {
+            // Shadow execution never consumes or enforces quotas.
+            if matches!(context.execution_mode, super::intent_types::ExecutionMode::Shadow) {
+                return None;
+            }
+            let now = crate::admin::now_ts();
+            let used = crate::bot_identity::usage::consume_quota(
+                context.store,
+                context.site_id,
+                quota.quota_id.as_str(),
+                subject_key.as_str(),
+                quota.window,
+                now,
+            );
+            if used <= quota.max_requests {
+                return None;
+            }
+            if used == quota.max_requests + 1 {
+                crate::admin::log_event(
+                    context.store,
+                    &crate::admin::EventLogEntry {
+                        ts: now,
+                        event: match quota.overage_action {
+                            crate::bot_identity::policy::IdentityQuotaOverageAction::Block => {
+                                crate::admin::EventType::Block
+                            }
+                            crate::bot_identity::policy::IdentityQuotaOverageAction::Challenge => {
+                                crate::admin::EventType::Challenge
+                            }
+                            crate::bot_identity::policy::IdentityQuotaOverageAction::Observe
+                            | crate::bot_identity::policy::IdentityQuotaOverageAction::Throttle => {
+                                crate::admin::EventType::AdminAction
+                            }
+                        },
+                        ip: Some(context.ip.to_string()),
+                        reason: Some("verified_identity_quota_exceeded".to_string()),
+                        outcome: Some(format!(
+                            "quota={} scope={} window={} max_requests={} overage_action={}",
+                            quota.quota_id,
+                            quota.scope.as_str(),
+                            quota.window.as_str(),
+                            quota.max_requests,
+                            quota.overage_action.as_str()
+                        )),
+                        admin: None,
+                    },
+                );
+            }
+            match quota.overage_action {
+                crate::bot_identity::policy::IdentityQuotaOverageAction::Observe => None,
+                crate::bot_identity::policy::IdentityQuotaOverageAction::Throttle => {
+                    execute_response_intent(
+                        ResponseIntent::VerifiedIdentityBudget {
+                            budget_key: subject_key,
+                            requests_per_minute: context
+                                .cfg
+                                .verified_identity
+                                .restrict_requests_per_minute,
+                        },
+                        facts,
+                        context,
+                        capabilities,
+                    )
+                }
+                crate::bot_identity::policy::IdentityQuotaOverageAction::Block => {
+                    Some(RenderedResponseEvidence::local(
+                        spin_sdk::http::Response::builder()
+                            .status(429)
+                            .header("Content-Type", "text/plain; charset=utf-8")
+                            .header("Cache-Control", "no-store")
+                            .header(
+                                "Retry-After",
+                                crate::bot_identity::usage::quota_retry_after_seconds(
+                                    quota.window,
+                                    now,
+                                )
+                                .to_string(),
+                            )
+                            .body("Verified identity usage quota exceeded")
+                            .build(),
+                        ResponseKind::PlainTextBlock,
+                    ))
+                }
+                crate::bot_identity::policy::IdentityQuotaOverageAction::Challenge => {
+                    execute_response_intent(ResponseIntent::Challenge, facts, context, capabilities)
+                }
+            }
+        }
         ResponseIntent::BlockPage { status, reason } => Some(RenderedResponseEvidence::local(
             spin_sdk::http::Response::new(
                 status,
@@ -64,6 +193,22 @@ pub(super) fn execute_response_intent(
             ),
             ResponseKind::BlockPage,
         )),
+        ResponseIntent::LicenceRequired { offer } => {
+            let mut response = spin_sdk::http::Response::builder();
+            response
+                .status(402)
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "no-store");
+            for (name, value) in crate::bot_identity::licensing::offer_headers(&offer) {
+                response.header(name, value);
+            }
+            Some(RenderedResponseEvidence::local(
+                response
+                    .body(crate::bot_identity::licensing::offer_body(&offer))
+                    .build(),
+                ResponseKind::PlainTextBlock,
+            ))
+        }
         ResponseIntent::PlainTextBlock { body } => Some(RenderedResponseEvidence::local(
             spin_sdk::http::Response::builder()
                 .status(403)
@@ -112,14 +257,17 @@ pub(super) fn execute_response_intent(
             ResponseKind::Maze,
         )),
         ResponseIntent::Challenge => Some(RenderedResponseEvidence::local(
-            context
-                .provider_registry
-                .challenge_engine_provider()
-                .render_challenge(
-                    context.req,
-                    context.cfg.challenge_puzzle_transform_count as usize,
-                    context.cfg.challenge_puzzle_seed_ttl_seconds,
-                ),
+            crate::challenge::privacy_pass::with_token_challenge(
+                context.req,
+                context
+                    .provider_registry
+                    .challenge_engine_provider()
+                    .render_challenge(
+                        context.req,
+                        context.cfg.challenge_puzzle_transform_count as usize,
+                        context.cfg.challenge_puzzle_seed_ttl_seconds,
+                    ),
+            ),
             ResponseKind::Challenge,
         )),
         ResponseIntent::NotABot => {
@@ -128,13 +276,16 @@ pub(super) fn execute_response_intent(
                 .challenge_engine_provider()
                 .render_not_a_bot(context.req, context.cfg);
             Some(RenderedResponseEvidence::local(
-                crate::maze::covert_decoy::maybe_inject_non_maze_decoy(
+                crate::challenge::privacy_pass::with_token_challenge(
                     context.req,
-                    context.cfg,
-                    context.ip,
-                    context.ua,
-                    not_a_bot_response,
-                    facts.botness_score,
+                    crate::maze::covert_decoy::maybe_inject_non_maze_decoy(
+                        context.req,
+                        context.cfg,
+                        context.ip,
+                        context.ua,
+                        not_a_bot_response,
+                        facts.botness_score,
+                    ),
                 ),
                 ResponseKind::NotABot,
             ))
@@ -144,16 +295,27 @@ pub(super) fn execute_response_intent(
                 .provider_registry
                 .fingerprint_signal_provider()
                 .report_path();
-            Some(RenderedResponseEvidence::local(
-                crate::signals::js_verification::inject_js_challenge(
+            if context.cfg.cdp_detection_enabled {
+                crate::signals::cdp::record_report_expected(
+                    context.store,
                     context.ip,
                     context.ua,
-                    report_endpoint,
-                    context.cfg.pow_enabled,
-                    context.cfg.pow_difficulty,
-                    context.cfg.pow_ttl_seconds,
-                    context.cfg.cdp_probe_family,
-                    context.cfg.cdp_probe_rollout_percent,
+                    crate::admin::now_ts(),
+                );
+            }
+            Some(RenderedResponseEvidence::local(
+                crate::challenge::privacy_pass::with_token_challenge(
+                    context.req,
+                    crate::signals::js_verification::inject_js_challenge(
+                        context.ip,
+                        context.ua,
+                        report_endpoint,
+                        context.cfg.pow_enabled,
+                        context.cfg.pow_difficulty,
+                        context.cfg.pow_ttl_seconds,
+                        context.cfg.cdp_probe_family,
+                        context.cfg.cdp_probe_rollout_percent,
+                    ),
                 ),
                 ResponseKind::JsChallenge,
             ))
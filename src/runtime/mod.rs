@@ -1,10 +1,13 @@
 pub(crate) mod kv_gate;
+pub(crate) mod botness_calibration;
 pub(crate) mod capabilities;
+pub(crate) mod custom_rules;
 pub(crate) mod effect_intents;
 pub(crate) mod non_human_policy;
 pub(crate) mod non_human_taxonomy;
 pub(crate) mod policy_pipeline;
 pub(crate) mod policy_graph;
+pub(crate) mod policy_simulation;
 pub(crate) mod policy_taxonomy;
 pub(crate) mod traffic_classification;
 pub(crate) mod request_outcome;
@@ -16,6 +19,7 @@ pub(crate) mod sim_public;
 pub(crate) mod shadow_mode;
 pub(crate) mod upstream_canonicalization;
 pub(crate) mod upstream_proxy;
+pub(crate) mod verified_identity_serving;
 pub(crate) mod upstream_telemetry;
 
 #[cfg(test)]
@@ -47,6 +47,13 @@ pub(crate) enum PolicyDecision {
     VerifiedIdentityPolicyRestrict {
         resolution: crate::bot_identity::policy::IdentityPolicyResolution,
     },
+    VerifiedIdentityQuota {
+        quota: crate::bot_identity::policy::IdentityUsageQuota,
+    },
+    VerifiedIdentityLicenceRequired {
+        resolution: crate::bot_identity::policy::IdentityPolicyResolution,
+        offer: Box<crate::bot_identity::policy::IdentityLicenceOffer>,
+    },
     GeoBlock,
     GeoMaze,
     GeoMazeFallbackChallenge,
@@ -60,6 +67,17 @@ pub(crate) enum PolicyDecision {
     BotnessChallengeFallbackMaze { score: u8, signal_ids: Vec<SignalId> },
     BotnessChallengeFallbackBlock { score: u8, signal_ids: Vec<SignalId> },
     JsChallengeRequired,
+    PrivacyPassTokenRedeemed { score: u8 },
+    CustomRule {
+        rule_id: String,
+        action: crate::config::CustomRuleAction,
+    },
+    /// A decision from a policy source running in shadow. Its effects are recorded as what would
+    /// have happened and evaluation carries on to the next source.
+    Shadowed {
+        source_id: String,
+        decision: Box<PolicyDecision>,
+    },
 }
 
 impl PolicyDecision {
@@ -89,6 +107,10 @@ impl PolicyDecision {
             PolicyDecision::VerifiedIdentityPolicyRestrict { .. } => {
                 "verified_identity_policy_restrict"
             }
+            PolicyDecision::VerifiedIdentityQuota { .. } => "verified_identity_quota",
+            PolicyDecision::VerifiedIdentityLicenceRequired { .. } => {
+                "verified_identity_licence_required"
+            }
             PolicyDecision::GeoBlock => "geo_block",
             PolicyDecision::GeoMaze => "geo_maze",
             PolicyDecision::GeoMazeFallbackChallenge => "geo_maze_fallback_challenge",
@@ -106,6 +128,9 @@ impl PolicyDecision {
                 "botness_challenge_fallback_block"
             }
             PolicyDecision::JsChallengeRequired => "js_challenge_required",
+            PolicyDecision::PrivacyPassTokenRedeemed { .. } => "privacy_pass_token_redeemed",
+            PolicyDecision::CustomRule { .. } => "custom_rule",
+            PolicyDecision::Shadowed { .. } => "shadowed",
         }
     }
 
@@ -115,10 +140,80 @@ impl PolicyDecision {
             PolicyDecision::IpRangeAdvisory { .. }
                 | PolicyDecision::VerifiedIdentityPolicyObserve { .. }
                 | PolicyDecision::VerifiedIdentityPolicyRestrict { .. }
+                | PolicyDecision::VerifiedIdentityQuota { .. }
+                | PolicyDecision::Shadowed { .. }
         )
     }
 }
 
+/// Names the configurable policy source behind a decision, in the form accepted by
+/// `shadow_policy_sources`. Decisions with no per-source execution mode return `None`.
+pub(crate) fn policy_source_id(
+    decision: &PolicyDecision,
+    facts: &crate::runtime::request_facts::RequestFacts,
+) -> Option<String> {
+    match decision {
+        PolicyDecision::IpRangeForbidden { details }
+        | PolicyDecision::IpRangeCustomMessage { details, .. }
+        | PolicyDecision::IpRangeDropConnection { details }
+        | PolicyDecision::IpRangeRedirect { details, .. }
+        | PolicyDecision::IpRangeRateLimit { details }
+        | PolicyDecision::IpRangeHoneypot { details }
+        | PolicyDecision::IpRangeMaze { details }
+        | PolicyDecision::IpRangeTarpit { details } => {
+            Some(format!("ip_range:{}", details.source_id))
+        }
+        PolicyDecision::HoneypotHit => Some(format!("honeypot:{}", facts.path)),
+        PolicyDecision::GeoBlock => Some("geo_block".to_string()),
+        PolicyDecision::GeoMaze
+        | PolicyDecision::GeoMazeFallbackChallenge
+        | PolicyDecision::GeoFallbackBlockFromMaze => Some("geo_maze".to_string()),
+        PolicyDecision::GeoChallenge
+        | PolicyDecision::GeoChallengeFallbackMaze
+        | PolicyDecision::GeoFallbackBlockFromChallenge => Some("geo_challenge".to_string()),
+        PolicyDecision::BotnessMaze { .. } => Some("botness_maze".to_string()),
+        PolicyDecision::BotnessNotABot { .. } => Some("botness_not_a_bot".to_string()),
+        PolicyDecision::BotnessChallenge { .. }
+        | PolicyDecision::BotnessChallengeFallbackMaze { .. }
+        | PolicyDecision::BotnessChallengeFallbackBlock { .. } => {
+            Some("botness_challenge".to_string())
+        }
+        PolicyDecision::CustomRule { rule_id, .. } => Some(format!("custom_rule:{}", rule_id)),
+        PolicyDecision::Shadowed { source_id, .. } => Some(source_id.clone()),
+        PolicyDecision::IpRangeEmergencyAllowlisted { .. }
+        | PolicyDecision::IpRangeAdvisory { .. }
+        | PolicyDecision::RateLimitHit
+        | PolicyDecision::ExistingBan
+        | PolicyDecision::VerifiedIdentityPolicyDeny { .. }
+        | PolicyDecision::VerifiedIdentityPolicyAllow { .. }
+        | PolicyDecision::VerifiedIdentityPolicyObserve { .. }
+        | PolicyDecision::VerifiedIdentityPolicyRestrict { .. }
+        | PolicyDecision::VerifiedIdentityQuota { .. }
+        | PolicyDecision::VerifiedIdentityLicenceRequired { .. }
+        | PolicyDecision::JsChallengeRequired
+        | PolicyDecision::PrivacyPassTokenRedeemed { .. } => None,
+    }
+}
+
+/// Wraps a decision in `Shadowed` when `shadow_policy_sources` names its source.
+fn apply_source_execution_mode(
+    decision: PolicyDecision,
+    facts: &crate::runtime::request_facts::RequestFacts,
+    cfg: &crate::config::Config,
+    declared: crate::config::PolicyExecutionMode,
+) -> PolicyDecision {
+    let Some(source_id) = policy_source_id(&decision, facts) else {
+        return decision;
+    };
+    match cfg.policy_source_execution_mode(source_id.as_str(), declared) {
+        crate::config::PolicyExecutionMode::Enforced => decision,
+        crate::config::PolicyExecutionMode::Shadow => PolicyDecision::Shadowed {
+            source_id,
+            decision: Box::new(decision),
+        },
+    }
+}
+
 fn decide_ip_range(
     facts: &crate::runtime::request_facts::RequestFacts,
     cfg: &crate::config::Config,
@@ -131,49 +226,56 @@ fn decide_ip_range(
             })
         }
         crate::signals::ip_range_policy::Evaluation::Matched(details) => {
-            if cfg.ip_range_policy_mode == crate::config::IpRangePolicyMode::Advisory {
-                return Some(PolicyDecision::IpRangeAdvisory {
-                    details: details.clone(),
-                });
-            }
+            Some(decide_ip_range_match(details, cfg))
+        }
+    }
+}
 
-            Some(match details.action {
-                crate::config::IpRangePolicyAction::Forbidden403 => {
-                    PolicyDecision::IpRangeForbidden {
-                        details: details.clone(),
-                    }
-                }
-                crate::config::IpRangePolicyAction::CustomMessage => {
-                    PolicyDecision::IpRangeCustomMessage {
-                        details: details.clone(),
-                        message: details.custom_message.clone().unwrap_or_else(|| {
-                            "Access blocked by IP range policy.".to_string()
-                        }),
-                    }
-                }
-                crate::config::IpRangePolicyAction::DropConnection => {
-                    PolicyDecision::IpRangeDropConnection {
-                        details: details.clone(),
-                    }
-                }
-                crate::config::IpRangePolicyAction::Redirect308 => PolicyDecision::IpRangeRedirect {
-                    details: details.clone(),
-                    location: details.redirect_url.clone(),
-                },
-                crate::config::IpRangePolicyAction::RateLimit => PolicyDecision::IpRangeRateLimit {
-                    details: details.clone(),
-                },
-                crate::config::IpRangePolicyAction::Honeypot => PolicyDecision::IpRangeHoneypot {
-                    details: details.clone(),
-                },
-                crate::config::IpRangePolicyAction::Maze => PolicyDecision::IpRangeMaze {
-                    details: details.clone(),
-                },
-                crate::config::IpRangePolicyAction::Tarpit => PolicyDecision::IpRangeTarpit {
-                    details: details.clone(),
-                },
-            })
+fn decide_ip_range_match(
+    details: &crate::signals::ip_range_policy::MatchDetails,
+    cfg: &crate::config::Config,
+) -> PolicyDecision {
+    if cfg.ip_range_policy_mode == crate::config::IpRangePolicyMode::Advisory {
+        return PolicyDecision::IpRangeAdvisory {
+            details: details.clone(),
+        };
+    }
+
+    match details.action {
+        crate::config::IpRangePolicyAction::Forbidden403 => {
+            PolicyDecision::IpRangeForbidden {
+                details: details.clone(),
+            }
+        }
+        crate::config::IpRangePolicyAction::CustomMessage => {
+            PolicyDecision::IpRangeCustomMessage {
+                details: details.clone(),
+                message: details.custom_message.clone().unwrap_or_else(|| {
+                    "Access blocked by IP range policy.".to_string()
+                }),
+            }
+        }
+        crate::config::IpRangePolicyAction::DropConnection => {
+            PolicyDecision::IpRangeDropConnection {
+                details: details.clone(),
+            }
         }
+        crate::config::IpRangePolicyAction::Redirect308 => PolicyDecision::IpRangeRedirect {
+            details: details.clone(),
+            location: details.redirect_url.clone(),
+        },
+        crate::config::IpRangePolicyAction::RateLimit => PolicyDecision::IpRangeRateLimit {
+            details: details.clone(),
+        },
+        crate::config::IpRangePolicyAction::Honeypot => PolicyDecision::IpRangeHoneypot {
+            details: details.clone(),
+        },
+        crate::config::IpRangePolicyAction::Maze => PolicyDecision::IpRangeMaze {
+            details: details.clone(),
+        },
+        crate::config::IpRangePolicyAction::Tarpit => PolicyDecision::IpRangeTarpit {
+            details: details.clone(),
+        },
     }
 }
 
@@ -247,19 +349,46 @@ fn decide_verified_identity_policy(
         crate::bot_identity::policy::IdentityPolicyOutcome::Restrict => {
             PolicyDecision::VerifiedIdentityPolicyRestrict { resolution }
         }
+        crate::bot_identity::policy::IdentityPolicyOutcome::RequireLicence => {
+            // Config validation guarantees referenced offers exist. If the
+            // offer is somehow missing at runtime, fail closed as denied.
+            let Some(offer) = cfg.verified_identity.licence_offers.iter().find(|offer| {
+                resolution.licence_offer_id.as_deref() == Some(offer.offer_id.as_str())
+            }) else {
+                return Some(PolicyDecision::VerifiedIdentityPolicyDeny { resolution });
+            };
+            if facts
+                .crawl_licence
+                .as_ref()
+                .is_some_and(|licence| licence.offer_id == offer.offer_id)
+            {
+                PolicyDecision::VerifiedIdentityPolicyAllow { resolution }
+            } else {
+                PolicyDecision::VerifiedIdentityLicenceRequired {
+                    resolution,
+                    offer: Box::new(offer.clone()),
+                }
+            }
+        }
         crate::bot_identity::policy::IdentityPolicyOutcome::NoMatch => return None,
     })
 }
 
-fn decide_botness(
+/// Botness thresholds that fire for this score, strongest first. Only the first enforced one
+/// acts; shadowed thresholds ahead of it are recorded and skipped.
+fn botness_candidates(
     facts: &crate::runtime::request_facts::RequestFacts,
     cfg: &crate::config::Config,
-) -> Option<PolicyDecision> {
+) -> Vec<PolicyDecision> {
     let score = facts.botness_score;
     let signal_ids = facts.botness_signal_ids.clone();
+    let mut candidates = Vec::new();
 
     if cfg.maze_enabled && score >= cfg.botness_maze_threshold {
-        return Some(PolicyDecision::BotnessMaze { score, signal_ids });
+        candidates.push(PolicyDecision::BotnessMaze {
+            score,
+            signal_ids: signal_ids.clone(),
+        });
     }
 
     let not_a_bot_threshold = cfg.not_a_bot_risk_threshold;
@@ -270,20 +399,23 @@ fn decide_botness(
         && score < cfg.challenge_puzzle_risk_threshold
         && !facts.not_a_bot_marker_valid
     {
-        return Some(PolicyDecision::BotnessNotABot { score, signal_ids });
+        candidates.push(PolicyDecision::BotnessNotABot {
+            score,
+            signal_ids: signal_ids.clone(),
+        });
     }
 
     if score >= cfg.challenge_puzzle_risk_threshold {
-        if cfg.challenge_puzzle_enabled {
-            return Some(PolicyDecision::BotnessChallenge { score, signal_ids });
-        }
-        if cfg.maze_enabled {
-            return Some(PolicyDecision::BotnessChallengeFallbackMaze { score, signal_ids });
-        }
-        return Some(PolicyDecision::BotnessChallengeFallbackBlock { score, signal_ids });
+        candidates.push(if cfg.challenge_puzzle_enabled {
+            PolicyDecision::BotnessChallenge { score, signal_ids }
+        } else if cfg.maze_enabled {
+            PolicyDecision::BotnessChallengeFallbackMaze { score, signal_ids }
+        } else {
+            PolicyDecision::BotnessChallengeFallbackBlock { score, signal_ids }
+        });
     }
 
-    None
+    candidates
 }
 
 fn decide_js(
@@ -296,6 +428,96 @@ fn decide_js(
     None
 }
 
+/// Whether a redeemed Privacy Pass token could stand in for the friction this request faces:
+/// a not-a-bot or puzzle challenge, or JS verification when botness routes nowhere. Maze and
+/// block outcomes, including the puzzle's disabled fallbacks, are never replaced.
+fn privacy_pass_replaces_friction(
+    facts: &crate::runtime::request_facts::RequestFacts,
+    cfg: &crate::config::Config,
+    botness: Option<&PolicyDecision>,
+) -> bool {
+    match botness {
+        Some(PolicyDecision::BotnessNotABot { .. } | PolicyDecision::BotnessChallenge { .. }) => {
+            true
+        }
+        Some(_) => false,
+        None => decide_js(facts, cfg).is_some(),
+    }
+}
+
+/// A redeemed Privacy Pass token stands in for a challenge the client already passed.
+fn decide_privacy_pass(
+    facts: &crate::runtime::request_facts::RequestFacts,
+    cfg: &crate::config::Config,
+    botness: Option<&PolicyDecision>,
+) -> Option<PolicyDecision> {
+    (facts.privacy_pass_token_valid && privacy_pass_replaces_friction(facts, cfg, botness))
+        .then_some(PolicyDecision::PrivacyPassTokenRedeemed {
+            score: facts.botness_score,
+        })
+}
+
+/// Whether the second tranche would otherwise end in a challenge a Privacy Pass token can
+/// replace. Callers use this to spend a presented token only when it changes the outcome.
+pub(crate) fn second_tranche_would_accept_privacy_pass(
+    facts: &crate::runtime::request_facts::RequestFacts,
+    cfg: &crate::config::Config,
+) -> bool {
+    let mut with_token = facts.clone();
+    with_token.privacy_pass_token_valid = true;
+    evaluate_second_tranche(&with_token, cfg)
+        .iter()
+        .any(|decision| matches!(decision, PolicyDecision::PrivacyPassTokenRedeemed { .. }))
+}
+
+/// Actions that need a disabled defence fall back the way geo routing does: challenge and maze
+/// stand in for each other, and block is the last resort.
+fn effective_custom_rule_action(
+    action: crate::config::CustomRuleAction,
+    cfg: &crate::config::Config,
+) -> crate::config::CustomRuleAction {
+    use crate::config::CustomRuleAction;
+
+    match action {
+        CustomRuleAction::NotABot if !cfg.not_a_bot_enabled => {
+            effective_custom_rule_action(CustomRuleAction::Challenge, cfg)
+        }
+        CustomRuleAction::NotABot | CustomRuleAction::Challenge
+            if !cfg.challenge_puzzle_enabled =>
+        {
+            if cfg.maze_enabled {
+                CustomRuleAction::Maze
+            } else {
+                CustomRuleAction::Block
+            }
+        }
+        CustomRuleAction::Maze if !cfg.maze_enabled => {
+            if cfg.challenge_puzzle_enabled {
+                CustomRuleAction::Challenge
+            } else {
+                CustomRuleAction::Block
+            }
+        }
+        other => other,
+    }
+}
+
+fn decide_custom_rules(
+    facts: &crate::runtime::request_facts::RequestFacts,
+    cfg: &crate::config::Config,
+) -> Vec<PolicyDecision> {
+    crate::runtime::custom_rules::matching_rules(facts, cfg)
+        .into_iter()
+        .map(|matched| {
+            let decision = PolicyDecision::CustomRule {
+                rule_id: matched.rule_id,
+                action: effective_custom_rule_action(matched.action, cfg),
+            };
+            apply_source_execution_mode(decision, facts, cfg, matched.execution_mode)
+        })
+        .collect()
+}
+
 fn should_prefer_js_before_botness(
     facts: &crate::runtime::request_facts::RequestFacts,
     cfg: &crate::config::Config,
@@ -314,6 +536,13 @@ pub(crate) fn evaluate_first_tranche(
 ) -> Vec<PolicyDecision> {
     let mut decisions = Vec::new();
 
+    for details in &facts.ip_range_shadow_matches {
+        decisions.push(PolicyDecision::Shadowed {
+            source_id: format!("ip_range:{}", details.source_id),
+            decision: Box::new(decide_ip_range_match(details, cfg)),
+        });
+    }
+
     if let Some(ip_range) = decide_ip_range(facts, cfg) {
         let terminal = ip_range.is_terminal();
         decisions.push(ip_range);
@@ -323,8 +552,17 @@ pub(crate) fn evaluate_first_tranche(
     }
 
     if facts.honeypot_hit {
-        decisions.push(PolicyDecision::HoneypotHit);
-        return decisions;
+        let honeypot = apply_source_execution_mode(
+            PolicyDecision::HoneypotHit,
+            facts,
+            cfg,
+            crate::config::PolicyExecutionMode::Enforced,
+        );
+        let terminal = honeypot.is_terminal();
+        decisions.push(honeypot);
+        if terminal {
+            return decisions;
+        }
     }
 
     if facts.rate_limit_exceeded {
@@ -340,17 +578,43 @@ pub(crate) fn evaluate_first_tranche(
     decisions
 }
 
-/// Evaluate the second policy tranche.
+/// Evaluate the second policy tranche. Operator custom rules run first so they can override
+/// the built-in geo, botness and JS routing. Shadowed sources are recorded in order and never
+/// end the tranche.
 pub(crate) fn evaluate_second_tranche(
     facts: &crate::runtime::request_facts::RequestFacts,
     cfg: &crate::config::Config,
 ) -> Vec<PolicyDecision> {
+    let enforced = crate::config::PolicyExecutionMode::Enforced;
     let mut decisions = Vec::new();
+    for custom_rule in decide_custom_rules(facts, cfg) {
+        let terminal = custom_rule.is_terminal();
+        decisions.push(custom_rule);
+        if terminal {
+            return decisions;
+        }
+    }
     if let Some(geo) = decide_geo(facts, cfg) {
+        let geo = apply_source_execution_mode(geo, facts, cfg, enforced);
+        let terminal = geo.is_terminal();
         decisions.push(geo);
+        if terminal {
+            return decisions;
+        }
+    }
+    let mut botness = None;
+    for candidate in botness_candidates(facts, cfg) {
+        let candidate = apply_source_execution_mode(candidate, facts, cfg, enforced);
+        if candidate.is_terminal() {
+            botness = Some(candidate);
+            break;
+        }
+        decisions.push(candidate);
+    }
+    if let Some(privacy_pass) = decide_privacy_pass(facts, cfg, botness.as_ref()) {
+        decisions.push(privacy_pass);
         return decisions;
     }
-    let botness = decide_botness(facts, cfg);
     if should_prefer_js_before_botness(facts, cfg, botness.is_some()) {
         decisions.push(PolicyDecision::JsChallengeRequired);
         return decisions;
@@ -375,6 +639,24 @@ pub(crate) fn evaluate_verified_identity_tranche(
     let mut decisions = Vec::new();
     if let Some(verified_identity_policy) = decide_verified_identity_policy(facts, cfg, objectives)
     {
+        // Denied and unlicensed identities are refused outright and never draw down a quota.
+        if !matches!(
+            verified_identity_policy,
+            PolicyDecision::VerifiedIdentityPolicyDeny { .. }
+                | PolicyDecision::VerifiedIdentityLicenceRequired { .. }
+        ) {
+            if let Some(quota) = facts.verified_identity.as_ref().and_then(|identity| {
+                crate::bot_identity::policy::matching_usage_quota(
+                    &cfg.verified_identity.usage_quotas,
+                    identity,
+                    facts.path.as_str(),
+                )
+            }) {
+                decisions.push(PolicyDecision::VerifiedIdentityQuota {
+                    quota: quota.clone(),
+                });
+            }
+        }
         decisions.push(verified_identity_policy);
     }
     decisions
@@ -395,6 +677,7 @@ mod tests {
             end_user_controlled: true,
             directory_source: None,
             provenance: crate::bot_identity::contracts::IdentityProvenance::Provider,
+            delegation: None,
         }
     }
 
@@ -423,6 +706,7 @@ mod tests {
                 ip: "203.0.113.9".to_string(),
                 user_agent: "ua".to_string(),
                 ip_range_evaluation: crate::signals::ip_range_policy::Evaluation::NoMatch,
+                ip_range_shadow_matches: vec![],
                 honeypot_hit: false,
                 rate_limit_exceeded: false,
                 existing_ban: false,
@@ -438,6 +722,8 @@ mod tests {
                 provider_summary: "providers".to_string(),
                 verified_identity: None,
                 not_a_bot_marker_valid: false,
+                privacy_pass_token_valid: false,
+                crawl_licence: None,
             },
         )
     }
@@ -478,6 +764,44 @@ mod tests {
         assert_eq!(decisions[1].label(), "honeypot_hit");
     }
 
+    #[test]
+    fn pre_tranche_shadowed_sources_record_and_fall_through_to_enforced_stages() {
+        let mut request_facts = facts();
+        request_facts.honeypot_hit = true;
+        request_facts.rate_limit_exceeded = true;
+        request_facts.ip_range_shadow_matches = vec![crate::signals::ip_range_policy::MatchDetails {
+            source: crate::signals::ip_range_policy::MatchSource::CustomRule,
+            source_id: "trial".to_string(),
+            action: crate::config::IpRangePolicyAction::Forbidden403,
+            matched_cidr: "203.0.113.0/24".to_string(),
+            redirect_url: None,
+            custom_message: None,
+        }];
+
+        let mut cfg = cfg();
+        cfg.ip_range_policy_mode = crate::config::IpRangePolicyMode::Enforce;
+        cfg.shadow_policy_sources = vec![format!("honeypot:{}", request_facts.path)];
+
+        let decisions = evaluate_first_tranche(&request_facts, &cfg);
+        let labels = decisions
+            .iter()
+            .map(|decision| match decision {
+                PolicyDecision::Shadowed { source_id, decision } => {
+                    format!("shadow {} {}", source_id, decision.label())
+                }
+                other => other.label().to_string(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "shadow ip_range:trial ip_range_forbidden".to_string(),
+                format!("shadow honeypot:{} honeypot_hit", request_facts.path),
+                "rate_limit_hit".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn post_tranche_keeps_not_a_bot_before_js_for_browser_navigation_once_botness_applies() {
         let mut request_facts = facts();
@@ -498,6 +822,173 @@ mod tests {
         assert_eq!(decisions[0].label(), "botness_not_a_bot");
     }
 
+    #[test]
+    fn post_tranche_redeemed_privacy_pass_token_skips_challenges_but_not_maze() {
+        let mut request_facts = facts();
+        request_facts.botness_score = 4;
+        request_facts.needs_js = true;
+        request_facts.browser_navigation_like = true;
+        request_facts.privacy_pass_token_valid = true;
+
+        let mut cfg = cfg();
+        cfg.not_a_bot_enabled = true;
+        cfg.challenge_puzzle_enabled = true;
+        cfg.not_a_bot_risk_threshold = 3;
+        cfg.challenge_puzzle_risk_threshold = 7;
+        cfg.maze_enabled = true;
+        cfg.botness_maze_threshold = 9;
+        cfg.js_required_enforced = true;
+        cfg.defence_modes.js = crate::config::ComposabilityMode::Enforce;
+
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].label(), "privacy_pass_token_redeemed");
+
+        request_facts.botness_score = 1;
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions[0].label(), "privacy_pass_token_redeemed");
+
+        request_facts.botness_score = 9;
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions[0].label(), "botness_maze");
+
+        request_facts.botness_score = 0;
+        request_facts.needs_js = false;
+        assert!(evaluate_second_tranche(&request_facts, &cfg).is_empty());
+    }
+
+    #[test]
+    fn post_tranche_privacy_pass_token_never_replaces_block_fallbacks() {
+        let mut request_facts = facts();
+        request_facts.botness_score = 8;
+        request_facts.privacy_pass_token_valid = true;
+
+        let mut cfg = cfg();
+        cfg.challenge_puzzle_enabled = false;
+        cfg.challenge_puzzle_risk_threshold = 7;
+        cfg.maze_enabled = false;
+
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].label(), "botness_challenge_fallback_block");
+        assert!(!second_tranche_would_accept_privacy_pass(
+            &request_facts,
+            &cfg
+        ));
+
+        cfg.maze_enabled = true;
+        cfg.botness_maze_threshold = 10;
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions[0].label(), "botness_challenge_fallback_maze");
+    }
+
+    #[test]
+    fn post_tranche_privacy_pass_tokens_are_only_wanted_when_a_challenge_would_fire() {
+        let mut request_facts = facts();
+        request_facts.needs_js = false;
+        let mut cfg = cfg();
+        cfg.not_a_bot_enabled = false;
+        cfg.challenge_puzzle_enabled = true;
+        cfg.challenge_puzzle_risk_threshold = 7;
+        cfg.maze_enabled = false;
+
+        request_facts.botness_score = 2;
+        assert!(!second_tranche_would_accept_privacy_pass(
+            &request_facts,
+            &cfg
+        ));
+        request_facts.botness_score = 7;
+        assert!(second_tranche_would_accept_privacy_pass(
+            &request_facts,
+            &cfg
+        ));
+        // The probe does not mark the real facts as redeemed.
+        assert!(!request_facts.privacy_pass_token_valid);
+    }
+
+    #[test]
+    fn post_tranche_shadowed_geo_and_botness_thresholds_defer_to_the_next_enforced_source() {
+        let mut request_facts = facts();
+        request_facts.geo_route = crate::signals::geo::GeoPolicyRoute::Block;
+        request_facts.botness_score = 9;
+
+        let mut cfg = cfg();
+        cfg.defence_modes.geo = crate::config::ComposabilityMode::Enforce;
+        cfg.maze_enabled = true;
+        cfg.botness_maze_threshold = 8;
+        cfg.challenge_puzzle_enabled = true;
+        cfg.challenge_puzzle_risk_threshold = 5;
+        cfg.shadow_policy_sources = vec!["geo_block".to_string(), "botness_maze".to_string()];
+
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        let summary = decisions
+            .iter()
+            .map(|decision| {
+                (
+                    decision.label(),
+                    policy_source_id(decision, &request_facts).unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("shadowed", "geo_block".to_string()),
+                ("shadowed", "botness_maze".to_string()),
+                ("botness_challenge", "botness_challenge".to_string()),
+            ]
+        );
+
+        cfg.shadow_policy_sources.push("botness_challenge".to_string());
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions.len(), 3);
+        assert!(decisions.iter().all(|decision| !decision.is_terminal()));
+    }
+
+    #[test]
+    fn post_tranche_custom_rule_preempts_geo_and_falls_back_when_its_defence_is_off() {
+        let mut request_facts = facts();
+        request_facts.geo_route = crate::signals::geo::GeoPolicyRoute::Block;
+        request_facts.geo_country = Some("XX".to_string());
+
+        let mut cfg = cfg();
+        cfg.defence_modes.geo = crate::config::ComposabilityMode::Enforce;
+        cfg.custom_rules = vec![crate::config::CustomPolicyRule {
+            id: "xx-maze".to_string(),
+            enabled: true,
+            priority: 0,
+            when: r#"country == "XX""#.to_string(),
+            action: crate::config::CustomRuleAction::Maze,
+            execution_mode: crate::config::PolicyExecutionMode::Enforced,
+        }];
+        cfg.maze_enabled = true;
+
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(
+            decisions,
+            vec![PolicyDecision::CustomRule {
+                rule_id: "xx-maze".to_string(),
+                action: crate::config::CustomRuleAction::Maze,
+            }]
+        );
+
+        cfg.maze_enabled = false;
+        cfg.challenge_puzzle_enabled = false;
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(
+            decisions,
+            vec![PolicyDecision::CustomRule {
+                rule_id: "xx-maze".to_string(),
+                action: crate::config::CustomRuleAction::Block,
+            }]
+        );
+
+        request_facts.geo_country = Some("GB".to_string());
+        let decisions = evaluate_second_tranche(&request_facts, &cfg);
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].label(), "geo_block");
+    }
+
     #[test]
     fn post_tranche_prefers_js_before_botness_for_low_risk_browser_navigation() {
         let mut request_facts = facts();
@@ -658,6 +1149,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verified_identity_require_licence_quotes_the_offer_until_a_matching_licence_is_presented() {
+        let mut facts = facts();
+        facts.verified_identity = Some(verified_identity());
+        facts.path = "/articles/1".to_string();
+
+        let mut cfg = cfg();
+        cfg.verified_identity.enabled = true;
+        cfg.verified_identity.licence_offers = vec![crate::bot_identity::policy::IdentityLicenceOffer {
+            offer_id: "archive".to_string(),
+            description: None,
+            price: "0.002".to_string(),
+            currency: "USD".to_string(),
+            terms_url: "https://example.com/licensing".to_string(),
+        }];
+        cfg.verified_identity.named_policies = vec![crate::bot_identity::policy::IdentityPolicyEntry {
+            policy_id: "licence-openai".to_string(),
+            description: None,
+            matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                operator: Some("openai".to_string()),
+                ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+            },
+            action: crate::bot_identity::policy::IdentityPolicyAction::RequireLicence(
+                "archive".to_string(),
+            ),
+        }];
+        cfg.verified_identity.usage_quotas = vec![crate::bot_identity::policy::IdentityUsageQuota {
+            quota_id: "openai-daily".to_string(),
+            description: None,
+            matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                operator: Some("openai".to_string()),
+                ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+            },
+            scope: crate::bot_identity::policy::IdentityQuotaScope::Operator,
+            window: crate::bot_identity::policy::IdentityQuotaWindow::Daily,
+            max_requests: 10_000,
+            overage_action: crate::bot_identity::policy::IdentityQuotaOverageAction::Block,
+        }];
+        let objectives = relaxed_objectives();
+        let labels = |facts: &crate::runtime::request_facts::RequestFacts| {
+            evaluate_verified_identity_tranche(facts, &cfg, &objectives)
+                .into_iter()
+                .map(|decision| decision.label())
+                .collect::<Vec<_>>()
+        };
+        let licence = |offer_id: &str| crate::bot_identity::licensing::VerifiedCrawlLicence {
+            licence_id: "lic-1".to_string(),
+            offer_id: offer_id.to_string(),
+            key_id: "billing".to_string(),
+            issued_at: 0,
+            expires_at: u64::MAX,
+        };
+
+        assert_eq!(labels(&facts), vec!["verified_identity_licence_required"]);
+        facts.crawl_licence = Some(licence("other-offer"));
+        assert_eq!(labels(&facts), vec!["verified_identity_licence_required"]);
+        facts.crawl_licence = Some(licence("archive"));
+        assert_eq!(
+            labels(&facts),
+            vec!["verified_identity_quota", "verified_identity_policy_allow"]
+        );
+    }
+
+    #[test]
+    fn verified_identity_quota_precedes_every_outcome_except_deny() {
+        let mut observe_facts = facts();
+        observe_facts.verified_identity = Some(verified_identity());
+        observe_facts.path = "/docs/page".to_string();
+
+        let mut deny_facts = facts();
+        deny_facts.verified_identity = Some(verified_identity());
+        deny_facts.path = "/private/page".to_string();
+
+        let mut cfg = cfg();
+        cfg.verified_identity.enabled = true;
+        cfg.verified_identity.named_policies = vec![
+            crate::bot_identity::policy::IdentityPolicyEntry {
+                policy_id: "observe-openai".to_string(),
+                description: None,
+                matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    path_prefixes: vec!["/docs".to_string()],
+                    ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+                },
+                action: crate::bot_identity::policy::IdentityPolicyAction::Observe,
+            },
+            crate::bot_identity::policy::IdentityPolicyEntry {
+                policy_id: "deny-openai".to_string(),
+                description: None,
+                matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                    operator: Some("openai".to_string()),
+                    path_prefixes: vec!["/private".to_string()],
+                    ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+                },
+                action: crate::bot_identity::policy::IdentityPolicyAction::Deny,
+            },
+        ];
+        cfg.verified_identity.usage_quotas = vec![crate::bot_identity::policy::IdentityUsageQuota {
+            quota_id: "openai-daily".to_string(),
+            description: None,
+            matcher: crate::bot_identity::policy::IdentityPolicyMatcher {
+                operator: Some("openai".to_string()),
+                ..crate::bot_identity::policy::IdentityPolicyMatcher::default()
+            },
+            scope: crate::bot_identity::policy::IdentityQuotaScope::Operator,
+            window: crate::bot_identity::policy::IdentityQuotaWindow::Daily,
+            max_requests: 10_000,
+            overage_action: crate::bot_identity::policy::IdentityQuotaOverageAction::Block,
+        }];
+        let objectives = relaxed_objectives();
+        let labels = |facts: &crate::runtime::request_facts::RequestFacts,
+                      cfg: &crate::config::Config| {
+            evaluate_verified_identity_tranche(facts, cfg, &objectives)
+                .into_iter()
+                .map(|decision| decision.label())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            labels(&observe_facts, &cfg),
+            vec!["verified_identity_quota", "verified_identity_policy_observe"]
+        );
+        assert_eq!(
+            labels(&deny_facts, &cfg),
+            vec!["verified_identity_policy_deny"]
+        );
+        cfg.verified_identity.usage_quotas[0].matcher.operator = Some("example".to_string());
+        assert_eq!(
+            labels(&observe_facts, &cfg),
+            vec!["verified_identity_policy_observe"]
+        );
+    }
+
     #[test]
     fn characterization_matrix_captures_expected_policy_outcomes() {
         struct Case {
@@ -78,10 +78,33 @@ fn execute_decision_sequence(
     capabilities: &crate::runtime::capabilities::PolicyExecutionCapabilities,
 ) -> Option<HandledRequestResponse> {
     for decision in decisions {
-        let plan = crate::runtime::effect_intents::plan_for_decision(&decision, facts, context.cfg);
-        if let Some(rendered) =
-            crate::runtime::effect_intents::execute_plan(plan, facts, context, capabilities)
+        if let crate::runtime::policy_graph::PolicyDecision::Shadowed {
+            source_id,
+            decision: shadowed,
+        } = &decision
         {
+            let plan =
+                crate::runtime::effect_intents::plan_for_decision(shadowed, facts, context.cfg);
+            crate::runtime::effect_intents::execute_shadowed_source_plan(
+                plan,
+                source_id.as_str(),
+                context,
+                capabilities,
+            );
+            continue;
+        }
+        let plan = crate::runtime::effect_intents::plan_for_decision(&decision, facts, context.cfg);
+        let rendered = match crate::runtime::policy_graph::policy_source_id(&decision, facts) {
+            Some(source_id) => crate::runtime::effect_intents::execute_policy_source_plan(
+                plan,
+                source_id.as_str(),
+                facts,
+                context,
+                capabilities,
+            ),
+            None => crate::runtime::effect_intents::execute_plan(plan, facts, context, capabilities),
+        };
+        if let Some(rendered) = rendered {
             return Some(HandledRequestResponse {
                 branch: crate::runtime::traffic_classification::CurrentRuntimeBranch::PolicyDecision(
                     decision,
@@ -94,6 +117,43 @@ fn execute_decision_sequence(
     None
 }
 
+/// Sample the facts a tranche acted on into the request-facts journal used by what-if policy
+/// simulation and botness calibration. Adversary-sim traffic goes to its own namespace.
+fn maybe_journal_request_facts(
+    facts: &crate::runtime::request_facts::RequestFacts,
+    stage: crate::observability::request_facts_journal::RequestFactsJournalStage,
+    context: &crate::runtime::effect_intents::EffectExecutionContext<'_>,
+    capabilities: &crate::runtime::capabilities::PolicyExecutionCapabilities,
+) {
+    if !crate::observability::request_facts_journal::should_journal_request(
+        context.cfg.request_facts_journal_sample_percent,
+    ) {
+        return;
+    }
+    let site_id = if crate::runtime::sim_telemetry::current_metadata().is_some() {
+        crate::observability::request_facts_journal::adversary_sim_journal_site_id(&facts.site_id)
+    } else {
+        facts.site_id.clone()
+    };
+    crate::runtime::effect_intents::execute_effect_intents(
+        vec![
+            crate::runtime::effect_intents::EffectIntent::RecordRequestFactsJournal {
+                site_id,
+                row: Box::new(
+                    crate::observability::request_facts_journal::RequestFactsJournalRow::from_facts(
+                        facts,
+                        stage,
+                        crate::admin::now_ts(),
+                    ),
+                ),
+            },
+        ],
+        context,
+        capabilities,
+        None,
+    );
+}
+
 fn existing_ban_from_lookup_result(
     lookup_result: crate::providers::contracts::BanLookupResult,
     outage_mode: crate::config::BanStoreOutageMode,
@@ -162,6 +222,7 @@ pub(crate) fn maybe_handle_policy_graph_first_tranche(
             ip: ip.to_string(),
             user_agent: ua.to_string(),
             ip_range_evaluation: ip_range_evaluation.clone(),
+            ip_range_shadow_matches: crate::signals::ip_range_policy::shadow_matches(cfg, ip),
             honeypot_hit,
             rate_limit_exceeded,
             existing_ban,
@@ -177,11 +238,51 @@ pub(crate) fn maybe_handle_policy_graph_first_tranche(
             provider_summary: crate::provider_implementations_summary(provider_registry),
             verified_identity: verified_identity.cloned(),
             not_a_bot_marker_valid: false,
+            privacy_pass_token_valid: false,
+            crawl_licence: None,
         },
     );
 
     let decisions = crate::runtime::policy_graph::evaluate_first_tranche(&pre_facts, cfg);
-    execute_decision_sequence(decisions, &pre_facts, &context, &capabilities)
+    let handled = execute_decision_sequence(decisions, &pre_facts, &context, &capabilities);
+    if handled.is_some() {
+        maybe_journal_request_facts(
+            &pre_facts,
+            crate::observability::request_facts_journal::RequestFactsJournalStage::FirstTranche,
+            &context,
+            capabilities,
+        );
+    }
+    handled
+}
+
+/// Spend a Privacy Pass token presented inline. Only called once a challenge the token can
+/// replace would otherwise fire, so tokens are not burnt on requests that needed no friction.
+fn redeem_presented_privacy_pass_token(
+    req: &Request,
+    store: &Store,
+    context: &crate::runtime::effect_intents::EffectExecutionContext<'_>,
+    capabilities: &crate::runtime::capabilities::PolicyExecutionCapabilities,
+) -> bool {
+    let now = crate::admin::now_ts();
+    let outcome = crate::challenge::privacy_pass::redeem_request_token(store, req, now);
+    if matches!(
+        outcome,
+        crate::challenge::privacy_pass::RedemptionOutcome::Disabled
+            | crate::challenge::privacy_pass::RedemptionOutcome::Missing
+    ) {
+        return false;
+    }
+    crate::runtime::effect_intents::execute_effect_intents(
+        vec![crate::runtime::effect_intents::EffectIntent::IncrementMetric {
+            metric: crate::observability::metrics::MetricName::PrivacyPassRedemptionOutcomes,
+            label: Some(outcome.as_str().to_string()),
+        }],
+        context,
+        capabilities,
+        None,
+    );
+    outcome == crate::challenge::privacy_pass::RedemptionOutcome::Redeemed
 }
 
 pub(crate) fn maybe_handle_policy_graph_second_tranche(
@@ -227,6 +328,16 @@ pub(crate) fn maybe_handle_policy_graph_second_tranche(
         ip,
         geo_assessment.headers_trusted,
     );
+    let cdp_report_signals =
+        crate::signals::cdp::report_binding_bot_signals(store, cfg, ip, ua, crate::admin::now_ts());
+    let session_behavior_signals = crate::signals::session_behavior::collect_bot_signals(
+        store,
+        req,
+        cfg,
+        ip,
+        ua,
+        crate::signals::session_behavior::now_ms(),
+    );
     let botness = crate::compute_botness_assessment(
         crate::BotnessSignalContext {
             js_needed: needs_js,
@@ -237,6 +348,8 @@ pub(crate) fn maybe_handle_policy_graph_second_tranche(
             rate_limit: cfg.rate_limit,
             maze_behavior_score,
             fingerprint_signals,
+            cdp_report_signals,
+            session_behavior_signals,
         },
         cfg,
     );
@@ -249,13 +362,14 @@ pub(crate) fn maybe_handle_policy_graph_second_tranche(
         None,
     );
 
-    let facts = crate::runtime::request_facts::build_request_facts(
+    let mut facts = crate::runtime::request_facts::build_request_facts(
         req,
         crate::runtime::request_facts::RequestFactInputs {
             site_id: site_id.to_string(),
             ip: ip.to_string(),
             user_agent: ua.to_string(),
             ip_range_evaluation: ip_range_evaluation.clone(),
+            ip_range_shadow_matches: vec![], // first tranche only
             honeypot_hit: false, // first tranche only
             rate_limit_exceeded: false, // first tranche only
             existing_ban: false, // first tranche only
@@ -270,10 +384,35 @@ pub(crate) fn maybe_handle_policy_graph_second_tranche(
             runtime_metadata_summary: crate::defence_runtime_metadata_summary(cfg),
             provider_summary: crate::provider_implementations_summary(provider_registry),
             verified_identity: verified_identity.cloned(),
-            not_a_bot_marker_valid: crate::challenge::has_valid_not_a_bot_marker(req, ip, ua),
+            not_a_bot_marker_valid: crate::challenge::has_valid_not_a_bot_marker_for_score(
+                req,
+                ip,
+                ua,
+                botness.score,
+                cfg,
+            ),
+            privacy_pass_token_valid: crate::challenge::privacy_pass::has_valid_redemption_marker(
+                req,
+                ip,
+                ua,
+                crate::admin::now_ts(),
+            ),
+            crawl_licence: None,
         },
     );
+    if !facts.privacy_pass_token_valid
+        && crate::runtime::policy_graph::second_tranche_would_accept_privacy_pass(&facts, cfg)
+    {
+        facts.privacy_pass_token_valid =
+            redeem_presented_privacy_pass_token(req, store, &context, capabilities);
+    }
 
+    maybe_journal_request_facts(
+        &facts,
+        crate::observability::request_facts_journal::RequestFactsJournalStage::SecondTranche,
+        &context,
+        capabilities,
+    );
     let decisions = crate::runtime::policy_graph::evaluate_second_tranche(&facts, cfg);
     execute_decision_sequence(decisions, &facts, &context, &capabilities)
 }
@@ -317,6 +456,7 @@ pub(crate) fn maybe_handle_policy_graph_verified_identity_tranche(
             ip: ip.to_string(),
             user_agent: ua.to_string(),
             ip_range_evaluation: ip_range_evaluation.clone(),
+            ip_range_shadow_matches: vec![],
             honeypot_hit: false,
             rate_limit_exceeded: false,
             existing_ban: false,
@@ -332,6 +472,13 @@ pub(crate) fn maybe_handle_policy_graph_verified_identity_tranche(
             provider_summary: crate::provider_implementations_summary(provider_registry),
             verified_identity: Some(verified_identity.clone()),
             not_a_bot_marker_valid: false,
+            privacy_pass_token_valid: false,
+            crawl_licence: crate::bot_identity::licensing::presented_licence(
+                req,
+                &cfg.verified_identity,
+                verified_identity,
+                crate::admin::now_ts(),
+            ),
         },
     );
 
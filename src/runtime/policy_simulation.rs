@@ -0,0 +1,243 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::config::Config;
+use crate::observability::request_facts_journal::{
+    RequestFactsJournalRow, RequestFactsJournalStage,
+};
+use crate::runtime::effect_intents::{ResponseIntent, ShadowAction};
+use crate::runtime::policy_graph::PolicyDecision;
+use crate::runtime::request_facts::RequestFacts;
+use crate::runtime::traffic_classification::CurrentRuntimeBranch;
+
+type TrancheEvaluator = fn(&RequestFacts, &Config) -> Vec<PolicyDecision>;
+
+const REPLAYED_TRANCHES: [TrancheEvaluator; 2] = [
+    crate::runtime::policy_graph::evaluate_first_tranche,
+    crate::runtime::policy_graph::evaluate_second_tranche,
+];
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub(crate) struct OutcomeTransitionSegment {
+    pub replayed: u64,
+    pub changed: u64,
+    pub transitions: BTreeMap<String, u64>,
+}
+
+/// Outcome diff between the active config and a candidate over the same recorded requests.
+/// Transition keys read `from->to`, e.g. `allow->challenge`; unchanged requests are only counted.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub(crate) struct PolicySimulationReport {
+    pub replayed_requests: u64,
+    pub changed_requests: u64,
+    pub baseline_outcomes: BTreeMap<String, u64>,
+    pub candidate_outcomes: BTreeMap<String, u64>,
+    pub transitions: BTreeMap<String, u64>,
+    pub by_traffic_lane: BTreeMap<String, OutcomeTransitionSegment>,
+    pub by_human_likelihood: BTreeMap<String, OutcomeTransitionSegment>,
+}
+
+struct ReplayedOutcome {
+    decision: Option<PolicyDecision>,
+    action: &'static str,
+}
+
+/// Walk both tranches the way the request pipeline does: shadowed sources and decisions whose
+/// plan continues are passed over, and the first acting decision settles the request.
+fn replay_outcome(facts: &RequestFacts, cfg: &Config) -> ReplayedOutcome {
+    for evaluate in REPLAYED_TRANCHES {
+        for decision in evaluate(facts, cfg) {
+            if matches!(decision, PolicyDecision::Shadowed { .. }) {
+                continue;
+            }
+            let plan = crate::runtime::effect_intents::plan_for_decision(&decision, facts, cfg);
+            // Live budget and quota counters are not replayable, so both replay as continue.
+            if matches!(
+                plan.response,
+                ResponseIntent::Continue
+                    | ResponseIntent::VerifiedIdentityBudget { .. }
+                    | ResponseIntent::VerifiedIdentityQuota { .. }
+            ) {
+                continue;
+            }
+            let action = if crate::runtime::shadow_mode::shadow_mode_active(cfg) {
+                None
+            } else {
+                crate::runtime::effect_intents::shadow_action_for_response(&plan.response)
+            };
+            return ReplayedOutcome {
+                decision: Some(decision),
+                action: action.map_or("allow", ShadowAction::as_str),
+            };
+        }
+    }
+    ReplayedOutcome {
+        decision: None,
+        action: "allow",
+    }
+}
+
+fn traffic_lane_label(facts: &RequestFacts, baseline: &ReplayedOutcome) -> &'static str {
+    let branch = match &baseline.decision {
+        Some(decision) => CurrentRuntimeBranch::PolicyDecision(decision.clone()),
+        None => CurrentRuntimeBranch::CleanAllow {
+            not_a_bot_marker_valid: facts.not_a_bot_marker_valid,
+        },
+    };
+    let lane = crate::runtime::traffic_classification::classify_current_runtime_branch(&branch)
+        .traffic_lane
+        .or_else(|| {
+            facts
+                .verified_identity
+                .as_ref()
+                .map(crate::runtime::traffic_classification::verified_identity_lane_assignment)
+        });
+    lane.map_or("unclassified", |assignment| {
+        crate::runtime::request_outcome::normalize_traffic_lane(assignment.lane)
+    })
+}
+
+/// Coarse human-likelihood band from the recorded evidence. First-tranche rows never reached
+/// botness scoring and stay `unscored`.
+fn human_likelihood_label(row: &RequestFactsJournalRow) -> &'static str {
+    if row.stage == RequestFactsJournalStage::FirstTranche {
+        return "unscored";
+    }
+    if row.not_a_bot_marker_valid || row.privacy_pass_token_valid {
+        return "verified_human";
+    }
+    match row.botness_score {
+        0..=1 => "high",
+        2..=4 => "medium",
+        _ => "low",
+    }
+}
+
+fn record_segment(
+    segments: &mut BTreeMap<String, OutcomeTransitionSegment>,
+    label: &str,
+    transition: Option<&str>,
+) {
+    let segment = segments.entry(label.to_string()).or_default();
+    segment.replayed += 1;
+    if let Some(transition) = transition {
+        segment.changed += 1;
+        *segment
+            .transitions
+            .entry(transition.to_string())
+            .or_default() += 1;
+    }
+}
+
+/// Replay journaled requests under `baseline` and `candidate` and tally how outcomes move.
+pub(crate) fn simulate(
+    rows: &[RequestFactsJournalRow],
+    site_id: &str,
+    baseline: &Config,
+    candidate: &Config,
+) -> PolicySimulationReport {
+    let mut report = PolicySimulationReport::default();
+    for row in rows {
+        let baseline_facts = row.to_request_facts(site_id, baseline);
+        let baseline_outcome = replay_outcome(&baseline_facts, baseline);
+        let candidate_outcome =
+            replay_outcome(&row.to_request_facts(site_id, candidate), candidate);
+
+        report.replayed_requests += 1;
+        *report
+            .baseline_outcomes
+            .entry(baseline_outcome.action.to_string())
+            .or_default() += 1;
+        *report
+            .candidate_outcomes
+            .entry(candidate_outcome.action.to_string())
+            .or_default() += 1;
+        let transition = (baseline_outcome.action != candidate_outcome.action)
+            .then(|| format!("{}->{}", baseline_outcome.action, candidate_outcome.action));
+        if let Some(transition) = transition.as_deref() {
+            report.changed_requests += 1;
+            *report
+                .transitions
+                .entry(transition.to_string())
+                .or_default() += 1;
+        }
+        record_segment(
+            &mut report.by_traffic_lane,
+            traffic_lane_label(&baseline_facts, &baseline_outcome),
+            transition.as_deref(),
+        );
+        record_segment(
+            &mut report.by_human_likelihood,
+            human_likelihood_label(row),
+            transition.as_deref(),
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(
+        stage: RequestFactsJournalStage,
+        geo_country: &str,
+        existing_ban: bool,
+    ) -> RequestFactsJournalRow {
+        RequestFactsJournalRow {
+            recorded_at_ts: 1_700_000_000,
+            stage,
+            method: "GET".to_string(),
+            path: "/catalog".to_string(),
+            ip_bucket: "198.51.100.0".to_string(),
+            user_agent_family: "other".to_string(),
+            headers: vec![],
+            ip_range: None,
+            ip_range_shadow_matches: vec![],
+            honeypot_hit: false,
+            rate_limit_exceeded: false,
+            existing_ban,
+            geo_country: Some(geo_country.to_string()),
+            needs_js: false,
+            browser_navigation_like: true,
+            botness_score: 0,
+            botness_signal_ids: vec![],
+            verified_identity: None,
+            not_a_bot_marker_valid: false,
+            privacy_pass_token_valid: false,
+            crawl_licence: None,
+        }
+    }
+
+    #[test]
+    fn candidate_changes_are_tallied_by_transition_lane_and_human_likelihood() {
+        let rows = vec![
+            row(RequestFactsJournalStage::SecondTranche, "BR", false),
+            row(RequestFactsJournalStage::SecondTranche, "US", false),
+            row(RequestFactsJournalStage::FirstTranche, "US", true),
+        ];
+        let mut baseline = crate::config::defaults().clone();
+        baseline.shadow_mode = false;
+        let mut candidate = baseline.clone();
+        candidate.geo_block = vec!["BR".to_string()];
+
+        let report = simulate(&rows, "default", &baseline, &candidate);
+
+        assert_eq!(report.replayed_requests, 3);
+        assert_eq!(report.changed_requests, 1);
+        assert_eq!(report.transitions.get("allow->block"), Some(&1));
+        assert_eq!(report.baseline_outcomes.get("block"), Some(&1));
+        assert_eq!(report.candidate_outcomes.get("block"), Some(&2));
+        let interactive = &report.by_traffic_lane["unknown_interactive"];
+        assert_eq!((interactive.replayed, interactive.changed), (2, 1));
+        let suspicious = &report.by_traffic_lane["suspicious_automation"];
+        assert_eq!((suspicious.replayed, suspicious.changed), (1, 0));
+        assert_eq!(report.by_human_likelihood["high"].changed, 1);
+        assert_eq!(report.by_human_likelihood["unscored"].replayed, 1);
+
+        candidate.shadow_mode = true;
+        let shadowed = simulate(&rows, "default", &baseline, &candidate);
+        assert_eq!(shadowed.transitions.get("block->allow"), Some(&1));
+        assert_eq!(shadowed.candidate_outcomes.get("allow"), Some(&3));
+    }
+}
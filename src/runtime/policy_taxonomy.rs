@@ -360,19 +360,29 @@ pub enum SignalId {
     GeoRouteMaze,
     GeoRouteBlock,
     JsRequiredMissing,
+    PrivacyPassTokenValid,
     BrowserOutdated,
     CdpReportLow,
     CdpReportMedium,
     CdpReportStrong,
+    CdpReportBindingAnomaly,
+    CdpReportMissing,
     FingerprintUaHintMismatch,
     FingerprintUaTransportMismatch,
     FingerprintTemporalTransition,
     FingerprintFlowViolation,
     FingerprintPersistenceMissing,
     FingerprintUntrustedHeader,
+    FingerprintTransportProfileMismatch,
+    FingerprintHeaderShapeMismatch,
+    FingerprintHeaderOrderMismatch,
     EdgeFingerprintAdditive,
     EdgeFingerprintStrong,
     EdgeFingerprintAuthoritativeBan,
+    SessionAssetRatio,
+    SessionTraversal,
+    SessionTiming,
+    SessionReferer,
     MazeTraversal,
     MazeTokenInvalid,
     MazeTokenExpired,
@@ -386,6 +396,7 @@ pub enum SignalId {
     DecoyInteraction,
     TarpitPersistence,
     IpRangeCustom,
+    CustomRule,
     SimTagMissingSecret,
     SimTagMissingRequiredHeaders,
     SimTagInvalidHeaderValue,
@@ -428,19 +439,29 @@ impl SignalId {
             SignalId::GeoRouteMaze => "S_GEO_ROUTE_MAZE",
             SignalId::GeoRouteBlock => "S_GEO_ROUTE_BLOCK",
             SignalId::JsRequiredMissing => "S_JS_REQUIRED_MISSING",
+            SignalId::PrivacyPassTokenValid => "S_PRIVACY_PASS_TOKEN_VALID",
             SignalId::BrowserOutdated => "S_BROWSER_OUTDATED",
             SignalId::CdpReportLow => "S_CDP_REPORT_LOW",
             SignalId::CdpReportMedium => "S_CDP_REPORT_MEDIUM",
             SignalId::CdpReportStrong => "S_CDP_REPORT_STRONG",
+            SignalId::CdpReportBindingAnomaly => "S_CDP_REPORT_BINDING_ANOMALY",
+            SignalId::CdpReportMissing => "S_CDP_REPORT_MISSING",
             SignalId::FingerprintUaHintMismatch => "S_FP_UA_HINT_MISMATCH",
             SignalId::FingerprintUaTransportMismatch => "S_FP_UA_TRANSPORT_MISMATCH",
             SignalId::FingerprintTemporalTransition => "S_FP_TEMPORAL_TRANSITION",
             SignalId::FingerprintFlowViolation => "S_FP_FLOW_VIOLATION",
             SignalId::FingerprintPersistenceMissing => "S_FP_PERSISTENCE_MISSING",
             SignalId::FingerprintUntrustedHeader => "S_FP_UNTRUSTED_HEADER",
+            SignalId::FingerprintTransportProfileMismatch => "S_FP_TRANSPORT_PROFILE_MISMATCH",
+            SignalId::FingerprintHeaderShapeMismatch => "S_FP_HEADER_SHAPE_MISMATCH",
+            SignalId::FingerprintHeaderOrderMismatch => "S_FP_HEADER_ORDER_MISMATCH",
             SignalId::EdgeFingerprintAdditive => "S_FP_EDGE_ADDITIVE",
             SignalId::EdgeFingerprintStrong => "S_FP_EDGE_STRONG",
             SignalId::EdgeFingerprintAuthoritativeBan => "S_FP_EDGE_AUTHORITATIVE_BAN",
+            SignalId::SessionAssetRatio => "S_SESSION_ASSET_RATIO",
+            SignalId::SessionTraversal => "S_SESSION_TRAVERSAL",
+            SignalId::SessionTiming => "S_SESSION_TIMING",
+            SignalId::SessionReferer => "S_SESSION_REFERER",
             SignalId::MazeTraversal => "S_MAZE_TRAVERSAL",
             SignalId::MazeTokenInvalid => "S_MAZE_TOKEN_INVALID",
             SignalId::MazeTokenExpired => "S_MAZE_TOKEN_EXPIRED",
@@ -454,6 +475,7 @@ impl SignalId {
             SignalId::DecoyInteraction => "S_DECOY_INTERACTION",
             SignalId::TarpitPersistence => "S_TARPIT_PERSISTENCE",
             SignalId::IpRangeCustom => "S_IP_RANGE_CUSTOM",
+            SignalId::CustomRule => "S_CUSTOM_RULE",
             SignalId::SimTagMissingSecret => "S_SIM_TAG_MISSING_SECRET",
             SignalId::SimTagMissingRequiredHeaders => "S_SIM_TAG_MISSING_REQUIRED_HEADERS",
             SignalId::SimTagInvalidHeaderValue => "S_SIM_TAG_INVALID_HEADER_VALUE",
@@ -476,6 +498,7 @@ pub enum DetectionId {
     VerifiedIdentityPolicyObserve,
     VerifiedIdentityPolicyRestrict,
     VerifiedIdentityPolicyDeny,
+    VerifiedIdentityLicenceRequired,
     SeqOpMissing,
     SeqOpInvalid,
     SeqOpExpired,
@@ -496,6 +519,7 @@ pub enum DetectionId {
     BotnessGateChallenge,
     BotnessGateMaze,
     JsVerificationRequired,
+    PrivacyPassTokenRedeemed,
     CdpReportLow,
     CdpReportMedium,
     CdpReportStrong,
@@ -528,6 +552,12 @@ pub enum DetectionId {
     IpRangeHoneypot,
     IpRangeMaze,
     IpRangeTarpit,
+    CustomRuleAllow,
+    CustomRuleBlock,
+    CustomRuleChallenge,
+    CustomRuleNotABot,
+    CustomRuleJsChallenge,
+    CustomRuleMaze,
 }
 
 impl DetectionId {
@@ -543,6 +573,9 @@ impl DetectionId {
                 "D_VERIFIED_IDENTITY_POLICY_RESTRICT"
             }
             DetectionId::VerifiedIdentityPolicyDeny => "D_VERIFIED_IDENTITY_POLICY_DENY",
+            DetectionId::VerifiedIdentityLicenceRequired => {
+                "D_VERIFIED_IDENTITY_LICENCE_REQUIRED"
+            }
             DetectionId::SeqOpMissing => "D_SEQ_OP_MISSING",
             DetectionId::SeqOpInvalid => "D_SEQ_OP_INVALID",
             DetectionId::SeqOpExpired => "D_SEQ_OP_EXPIRED",
@@ -565,6 +598,7 @@ impl DetectionId {
             DetectionId::BotnessGateChallenge => "D_BOTNESS_GATE_CHALLENGE",
             DetectionId::BotnessGateMaze => "D_BOTNESS_GATE_MAZE",
             DetectionId::JsVerificationRequired => "D_JS_VERIFICATION_REQUIRED",
+            DetectionId::PrivacyPassTokenRedeemed => "D_PRIVACY_PASS_TOKEN_REDEEMED",
             DetectionId::CdpReportLow => "D_CDP_REPORT_LOW",
             DetectionId::CdpReportMedium => "D_CDP_REPORT_MEDIUM",
             DetectionId::CdpReportStrong => "D_CDP_REPORT_STRONG",
@@ -597,6 +631,12 @@ impl DetectionId {
             DetectionId::IpRangeHoneypot => "D_IP_RANGE_HONEYPOT",
             DetectionId::IpRangeMaze => "D_IP_RANGE_MAZE",
             DetectionId::IpRangeTarpit => "D_IP_RANGE_TARPIT",
+            DetectionId::CustomRuleAllow => "D_CUSTOM_RULE_ALLOW",
+            DetectionId::CustomRuleBlock => "D_CUSTOM_RULE_BLOCK",
+            DetectionId::CustomRuleChallenge => "D_CUSTOM_RULE_CHALLENGE",
+            DetectionId::CustomRuleNotABot => "D_CUSTOM_RULE_NOT_A_BOT",
+            DetectionId::CustomRuleJsChallenge => "D_CUSTOM_RULE_JS_CHALLENGE",
+            DetectionId::CustomRuleMaze => "D_CUSTOM_RULE_MAZE",
         }
     }
 }
@@ -658,6 +698,7 @@ pub enum PolicyTransition {
     VerifiedIdentityPolicyObserve(Vec<SignalId>),
     VerifiedIdentityPolicyRestrict(Vec<SignalId>),
     VerifiedIdentityPolicyDeny(Vec<SignalId>),
+    VerifiedIdentityLicenceRequired(Vec<SignalId>),
     SeqOpMissing,
     SeqOpInvalid,
     SeqOpExpired,
@@ -675,6 +716,7 @@ pub enum PolicyTransition {
     BotnessGateChallenge(Vec<SignalId>),
     BotnessGateMaze(Vec<SignalId>),
     JsVerificationRequired,
+    PrivacyPassTokenRedeemed,
     CdpReportLow,
     CdpReportMedium,
     CdpReportStrong,
@@ -704,6 +746,12 @@ pub enum PolicyTransition {
     IpRangeHoneypot(Vec<SignalId>),
     IpRangeMaze(Vec<SignalId>),
     IpRangeTarpit(Vec<SignalId>),
+    CustomRuleAllow,
+    CustomRuleBlock,
+    CustomRuleChallenge,
+    CustomRuleNotABot,
+    CustomRuleJsChallenge,
+    CustomRuleMaze,
 }
 
 pub fn resolve_policy_match(transition: PolicyTransition) -> PolicyMatch {
@@ -748,6 +796,11 @@ pub fn resolve_policy_match(transition: PolicyTransition) -> PolicyMatch {
             DetectionId::VerifiedIdentityPolicyDeny,
             signals,
         ),
+        PolicyTransition::VerifiedIdentityLicenceRequired(signals) => PolicyMatch::new(
+            EscalationLevelId::L3Shape,
+            DetectionId::VerifiedIdentityLicenceRequired,
+            signals,
+        ),
         PolicyTransition::SeqOpMissing => PolicyMatch::new(
             EscalationLevelId::L6ChallengeStrong,
             DetectionId::SeqOpMissing,
@@ -833,6 +886,11 @@ pub fn resolve_policy_match(transition: PolicyTransition) -> PolicyMatch {
             DetectionId::JsVerificationRequired,
             vec![SignalId::JsRequiredMissing],
         ),
+        PolicyTransition::PrivacyPassTokenRedeemed => PolicyMatch::new(
+            EscalationLevelId::L1AllowTagged,
+            DetectionId::PrivacyPassTokenRedeemed,
+            vec![SignalId::PrivacyPassTokenValid],
+        ),
         PolicyTransition::CdpReportLow => PolicyMatch::new(
             EscalationLevelId::L2Monitor,
             DetectionId::CdpReportLow,
@@ -978,6 +1036,36 @@ pub fn resolve_policy_match(transition: PolicyTransition) -> PolicyMatch {
             DetectionId::IpRangeTarpit,
             signals,
         ),
+        PolicyTransition::CustomRuleAllow => PolicyMatch::new(
+            EscalationLevelId::L1AllowTagged,
+            DetectionId::CustomRuleAllow,
+            vec![SignalId::CustomRule],
+        ),
+        PolicyTransition::CustomRuleBlock => PolicyMatch::new(
+            EscalationLevelId::L10DenyTemp,
+            DetectionId::CustomRuleBlock,
+            vec![SignalId::CustomRule],
+        ),
+        PolicyTransition::CustomRuleChallenge => PolicyMatch::new(
+            EscalationLevelId::L6ChallengeStrong,
+            DetectionId::CustomRuleChallenge,
+            vec![SignalId::CustomRule],
+        ),
+        PolicyTransition::CustomRuleNotABot => PolicyMatch::new(
+            EscalationLevelId::L5NotABot,
+            DetectionId::CustomRuleNotABot,
+            vec![SignalId::CustomRule],
+        ),
+        PolicyTransition::CustomRuleJsChallenge => PolicyMatch::new(
+            EscalationLevelId::L4VerifyJs,
+            DetectionId::CustomRuleJsChallenge,
+            vec![SignalId::CustomRule],
+        ),
+        PolicyTransition::CustomRuleMaze => PolicyMatch::new(
+            EscalationLevelId::L7DeceptionExplicit,
+            DetectionId::CustomRuleMaze,
+            vec![SignalId::CustomRule],
+        ),
     }
 }
 
@@ -990,6 +1078,39 @@ pub fn resolve_highest_level(candidates: &[EscalationLevelId]) -> EscalationLeve
         .unwrap_or(EscalationLevelId::L0AllowClean)
 }
 
+const BOTNESS_SIGNAL_KEYS: [&str; 22] = [
+    "js_verification_required",
+    "browser_outdated",
+    "geo_risk",
+    "rate_pressure_medium",
+    "rate_pressure_high",
+    "maze_behavior",
+    "fp_ua_ch_mismatch",
+    "fp_ua_transport_mismatch",
+    "fp_temporal_transition",
+    "fp_flow_violation",
+    "fp_persistence_marker_missing",
+    "fp_untrusted_transport_header",
+    "fp_transport_profile_mismatch",
+    "fp_header_shape_mismatch",
+    "fp_header_order_mismatch",
+    "fp_akamai_edge_additive",
+    "cdp_report_binding_anomaly",
+    "cdp_report_missing",
+    "session_asset_ratio",
+    "session_traversal",
+    "session_timing",
+    "session_referer",
+];
+
+/// Resolves a canonical `S_*` id back to a signal that botness scoring can raise.
+pub fn botness_signal_id_from_str(raw: &str) -> Option<SignalId> {
+    BOTNESS_SIGNAL_KEYS
+        .iter()
+        .filter_map(|key| signal_id_for_botness_key(key))
+        .find(|signal_id| signal_id.as_str() == raw)
+}
+
 pub fn signal_id_for_botness_key(key: &str) -> Option<SignalId> {
     match key {
         "js_verification_required" => Some(SignalId::JsRequiredMissing),
@@ -1004,7 +1125,16 @@ pub fn signal_id_for_botness_key(key: &str) -> Option<SignalId> {
         "fp_flow_violation" => Some(SignalId::FingerprintFlowViolation),
         "fp_persistence_marker_missing" => Some(SignalId::FingerprintPersistenceMissing),
         "fp_untrusted_transport_header" => Some(SignalId::FingerprintUntrustedHeader),
+        "fp_transport_profile_mismatch" => Some(SignalId::FingerprintTransportProfileMismatch),
+        "fp_header_shape_mismatch" => Some(SignalId::FingerprintHeaderShapeMismatch),
+        "fp_header_order_mismatch" => Some(SignalId::FingerprintHeaderOrderMismatch),
         "fp_akamai_edge_additive" => Some(SignalId::EdgeFingerprintAdditive),
+        "cdp_report_binding_anomaly" => Some(SignalId::CdpReportBindingAnomaly),
+        "cdp_report_missing" => Some(SignalId::CdpReportMissing),
+        "session_asset_ratio" => Some(SignalId::SessionAssetRatio),
+        "session_traversal" => Some(SignalId::SessionTraversal),
+        "session_timing" => Some(SignalId::SessionTiming),
+        "session_referer" => Some(SignalId::SessionReferer),
         _ => None,
     }
 }
@@ -1012,8 +1142,8 @@ pub fn signal_id_for_botness_key(key: &str) -> Option<SignalId> {
 #[cfg(test)]
 mod tests {
     use super::{
-        resolve_highest_level, resolve_policy_match, signal_id_for_botness_key, DetectionId,
-        EscalationLevelId, PolicyTransition, SignalId,
+        botness_signal_id_from_str, resolve_highest_level, resolve_policy_match,
+        signal_id_for_botness_key, DetectionId, EscalationLevelId, PolicyTransition, SignalId,
     };
 
     #[test]
@@ -1076,6 +1206,38 @@ mod tests {
                 .as_str(),
             "S_FP_EDGE_ADDITIVE"
         );
+        assert_eq!(
+            signal_id_for_botness_key("fp_transport_profile_mismatch")
+                .expect("known signal")
+                .as_str(),
+            "S_FP_TRANSPORT_PROFILE_MISMATCH"
+        );
+        assert_eq!(
+            botness_signal_id_from_str("S_FP_TRANSPORT_PROFILE_MISMATCH"),
+            Some(SignalId::FingerprintTransportProfileMismatch)
+        );
+        assert_eq!(
+            signal_id_for_botness_key("fp_header_shape_mismatch")
+                .expect("known signal")
+                .as_str(),
+            "S_FP_HEADER_SHAPE_MISMATCH"
+        );
+        assert_eq!(
+            botness_signal_id_from_str("S_FP_HEADER_ORDER_MISMATCH"),
+            Some(SignalId::FingerprintHeaderOrderMismatch)
+        );
+        assert_eq!(
+            signal_id_for_botness_key("cdp_report_binding_anomaly")
+                .expect("known signal")
+                .as_str(),
+            "S_CDP_REPORT_BINDING_ANOMALY"
+        );
+        assert_eq!(
+            signal_id_for_botness_key("session_timing")
+                .expect("known signal")
+                .as_str(),
+            "S_SESSION_TIMING"
+        );
         assert!(signal_id_for_botness_key("unknown").is_none());
     }
 
@@ -4,10 +4,13 @@ use spin_sdk::http::{Method, Request};
 pub(crate) struct RequestFacts {
     pub method: Method,
     pub path: String,
+    /// String-valued request headers, lowercased names, sorted for stable comparison.
+    pub request_headers: Vec<(String, String)>,
     pub site_id: String,
     pub ip: String,
     pub user_agent: String,
     pub ip_range_evaluation: crate::signals::ip_range_policy::Evaluation,
+    pub ip_range_shadow_matches: Vec<crate::signals::ip_range_policy::MatchDetails>,
     pub honeypot_hit: bool,
     pub rate_limit_exceeded: bool,
     pub existing_ban: bool,
@@ -23,6 +26,9 @@ pub(crate) struct RequestFacts {
     pub provider_summary: String,
     pub verified_identity: Option<crate::bot_identity::contracts::VerifiedIdentityEvidence>,
     pub not_a_bot_marker_valid: bool,
+    pub privacy_pass_token_valid: bool,
+    /// Licence token presented and verified for the verified identity, if any.
+    pub crawl_licence: Option<crate::bot_identity::licensing::VerifiedCrawlLicence>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,6 +37,7 @@ pub(crate) struct RequestFactInputs {
     pub ip: String,
     pub user_agent: String,
     pub ip_range_evaluation: crate::signals::ip_range_policy::Evaluation,
+    pub ip_range_shadow_matches: Vec<crate::signals::ip_range_policy::MatchDetails>,
     pub honeypot_hit: bool,
     pub rate_limit_exceeded: bool,
     pub existing_ban: bool,
@@ -46,16 +53,34 @@ pub(crate) struct RequestFactInputs {
     pub provider_summary: String,
     pub verified_identity: Option<crate::bot_identity::contracts::VerifiedIdentityEvidence>,
     pub not_a_bot_marker_valid: bool,
+    pub privacy_pass_token_valid: bool,
+    /// Licence token presented and verified for the verified identity, if any.
+    pub crawl_licence: Option<crate::bot_identity::licensing::VerifiedCrawlLicence>,
+}
+
+fn request_headers(req: &Request) -> Vec<(String, String)> {
+    let mut headers = req
+        .headers()
+        .filter_map(|(name, value)| {
+            value
+                .as_str()
+                .map(|value| (name.to_ascii_lowercase(), value.to_string()))
+        })
+        .collect::<Vec<_>>();
+    headers.sort();
+    headers
 }
 
 pub(crate) fn build_request_facts(req: &Request, inputs: RequestFactInputs) -> RequestFacts {
     RequestFacts {
         method: req.method().clone(),
         path: req.path().to_string(),
+        request_headers: request_headers(req),
         site_id: inputs.site_id,
         ip: inputs.ip,
         user_agent: inputs.user_agent,
         ip_range_evaluation: inputs.ip_range_evaluation,
+        ip_range_shadow_matches: inputs.ip_range_shadow_matches,
         honeypot_hit: inputs.honeypot_hit,
         rate_limit_exceeded: inputs.rate_limit_exceeded,
         existing_ban: inputs.existing_ban,
@@ -71,6 +96,8 @@ pub(crate) fn build_request_facts(req: &Request, inputs: RequestFactInputs) -> R
         provider_summary: inputs.provider_summary,
         verified_identity: inputs.verified_identity,
         not_a_bot_marker_valid: inputs.not_a_bot_marker_valid,
+        privacy_pass_token_valid: inputs.privacy_pass_token_valid,
+        crawl_licence: inputs.crawl_licence,
     }
 }
 
@@ -80,7 +107,11 @@ mod tests {
 
     #[test]
     fn builder_is_side_effect_free_projection_of_inputs() {
-        let req = Request::builder().method(Method::Post).uri("/x").build();
+        let req = Request::builder()
+            .method(Method::Post)
+            .uri("/x")
+            .header("X-Checkout-Step", "pay")
+            .build();
         let facts = build_request_facts(
             &req,
             RequestFactInputs {
@@ -88,6 +119,7 @@ mod tests {
                 ip: "203.0.113.10".to_string(),
                 user_agent: "ua".to_string(),
                 ip_range_evaluation: crate::signals::ip_range_policy::Evaluation::NoMatch,
+                ip_range_shadow_matches: vec![],
                 honeypot_hit: true,
                 rate_limit_exceeded: false,
                 existing_ban: false,
@@ -111,13 +143,20 @@ mod tests {
                     end_user_controlled: true,
                     directory_source: None,
                     provenance: crate::bot_identity::contracts::IdentityProvenance::Provider,
+                    delegation: None,
                 }),
                 not_a_bot_marker_valid: false,
+                privacy_pass_token_valid: false,
+                crawl_licence: None,
             },
         );
 
         assert_eq!(facts.method, Method::Post);
         assert_eq!(facts.path, "/x");
+        assert_eq!(
+            facts.request_headers,
+            vec![("x-checkout-step".to_string(), "pay".to_string())]
+        );
         assert!(facts.honeypot_hit);
         assert!(facts.needs_js);
         assert!(facts.browser_navigation_like);
@@ -48,9 +48,17 @@ fn observe_verified_identity_intents(
         return Vec::new();
     };
 
-    vec![
+    let mut intents = vec![
         crate::runtime::effect_intents::EffectIntent::RecordVerifiedIdentityTelemetry { record },
-    ]
+    ];
+    if let Some(rotation) = result.directory_key_rotation.as_deref() {
+        intents.push(
+            crate::runtime::effect_intents::EffectIntent::RecordVerifiedIdentityDirectoryKeyRotation {
+                rotation: rotation.clone(),
+            },
+        );
+    }
+    intents
 }
 
 fn observe_verified_identity_result(
@@ -60,9 +68,15 @@ fn observe_verified_identity_result(
     cfg: &crate::config::Config,
     provider_registry: &crate::providers::registry::ProviderRegistry,
 ) -> crate::bot_identity::verification::IdentityVerificationResult {
-    provider_registry
+    let result = provider_registry
         .verified_identity_provider()
-        .verify_identity(store, site_id, req, cfg)
+        .verify_identity(store, site_id, req, cfg);
+    if result.status
+        == crate::bot_identity::verification::IdentityVerificationResultStatus::NotAttempted
+    {
+        return crate::bot_identity::mtls::verify_request(req, cfg);
+    }
+    result
 }
 
 fn verified_identity_default_provenance(
@@ -153,6 +167,7 @@ pub(crate) fn handle_request(req: &Request) -> Response {
         if let Some(response) = crate::runtime::sim_public::maybe_handle_without_config(req, path) {
             return response;
         }
+        crate::signals::session_behavior::record_static_asset_bypass(req, &ip);
         return crate::runtime::upstream_proxy::forward_allow_request(
             crate::runtime::upstream_proxy::ForwardRequestContext { req, ip: &ip },
             "static_asset_bypass",
@@ -236,6 +251,19 @@ pub(crate) fn handle_request(req: &Request) -> Response {
     };
     let finalize_handled_response =
         |handled: crate::runtime::request_outcome::HandledRequestResponse| {
+            if let Some(identity) = verified_identity.as_ref() {
+                execute_request_intents(vec![
+                    crate::runtime::effect_intents::EffectIntent::RecordVerifiedIdentityUsage {
+                        site_id: site_id.to_string(),
+                        record: crate::bot_identity::telemetry::IdentityUsageRecord::from_response(
+                            identity,
+                            path,
+                            *handled.rendered.response.status(),
+                            handled.rendered.response.body().len() as u64,
+                        ),
+                    },
+                ]);
+            }
             finalize_request_outcome(
                 store,
                 &request_capabilities,
@@ -816,6 +844,37 @@ mod tests {
         assert_eq!(summary.verified_identity.failed, 0);
     }
 
+    #[test]
+    fn observe_verified_identity_intents_record_directory_key_rotations() {
+        let mut result = crate::bot_identity::verification::IdentityVerificationResult::failed(
+            crate::bot_identity::verification::IdentityVerificationFailure::SignatureInvalid,
+            crate::bot_identity::verification::IdentityVerificationFreshness::Fresh,
+        );
+        result.directory_key_rotation = Some(Box::new(
+            crate::bot_identity::verification::IdentityDirectoryKeyRotation {
+                source_uri: "https://agents.example/".to_string(),
+                added_key_ids: vec!["new".to_string()],
+                removed_key_ids: vec!["old".to_string()],
+                pinned_keys_missing: true,
+            },
+        ));
+
+        let intents = observe_verified_identity_intents(
+            crate::bot_identity::contracts::IdentityProvenance::Native,
+            &result,
+        );
+
+        assert_eq!(intents.len(), 2);
+        assert!(matches!(
+            intents.last(),
+            Some(
+                crate::runtime::effect_intents::EffectIntent::RecordVerifiedIdentityDirectoryKeyRotation {
+                    rotation
+                }
+            ) if rotation.pinned_keys_missing && rotation.removed_key_ids == vec!["old".to_string()]
+        ));
+    }
+
     #[test]
     fn observe_verified_identity_intents_preserve_native_provenance_for_failed_results() {
         let intents = observe_verified_identity_intents(
@@ -251,7 +251,7 @@ fn normalize_policy_source(source: PolicySource) -> &'static str {
     }
 }
 
-fn normalize_traffic_lane(lane: TrafficLane) -> &'static str {
+pub(crate) fn normalize_traffic_lane(lane: TrafficLane) -> &'static str {
     match lane {
         TrafficLane::LikelyHuman => "likely_human",
         TrafficLane::UnknownInteractive => "unknown_interactive",
@@ -59,7 +59,8 @@ fn classify_not_a_bot_failure_enforcement(
             ChallengeFailureEnforcement::TarpitOrShortBan
         }
         crate::challenge::NotABotSubmitOutcome::Pass
-        | crate::challenge::NotABotSubmitOutcome::EscalatePuzzle => {
+        | crate::challenge::NotABotSubmitOutcome::EscalatePuzzle
+        | crate::challenge::NotABotSubmitOutcome::AccessibleIncorrect => {
             ChallengeFailureEnforcement::MazeFallback
         }
     }
@@ -232,17 +233,120 @@ fn handle_not_a_bot_submit(
         vec![crate::runtime::effect_intents::EffectIntent::RecordNotABotSubmit {
             outcome: monitoring_outcome.to_string(),
             solve_ms: submit_result.solve_ms,
+            accessibility_cohort: submit_result.accessibility_cohort,
         }],
     );
 
+    respond_to_not_a_bot_result(
+        store,
+        req,
+        cfg,
+        capabilities,
+        &provider_registry,
+        ip.as_str(),
+        ua,
+        submit_result,
+        "not_a_bot",
+    )
+}
+
+fn handle_not_a_bot_accessible_submit(
+    store: &Store,
+    req: &Request,
+    cfg: &crate::config::Config,
+    capabilities: &crate::runtime::capabilities::PolicyExecutionCapabilities,
+) -> Response {
+    let submit_result =
+        crate::boundaries::handle_not_a_bot_accessible_submit_with_outcome(store, req, cfg);
+    let provider_registry = crate::providers::registry::ProviderRegistry::from_config(cfg);
+    let ip = crate::extract_client_ip(req);
+    let ua = request_user_agent(req);
+    let monitoring_outcome = match submit_result.outcome {
+        crate::challenge::NotABotSubmitOutcome::Pass => "pass",
+        crate::challenge::NotABotSubmitOutcome::AccessibleIncorrect => "retry",
+        crate::challenge::NotABotSubmitOutcome::Replay => "replay",
+        _ => "fail",
+    };
+    execute_capability_gated_intents(
+        req,
+        store,
+        cfg,
+        &provider_registry,
+        ip.as_str(),
+        ua,
+        capabilities,
+        vec![
+            crate::runtime::effect_intents::EffectIntent::RecordNotABotAccessibleAlternative {
+                outcome: monitoring_outcome.to_string(),
+            },
+        ],
+    );
+
+    respond_to_not_a_bot_result(
+        store,
+        req,
+        cfg,
+        capabilities,
+        &provider_registry,
+        ip.as_str(),
+        ua,
+        submit_result,
+        "not_a_bot_accessible",
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_not_a_bot_accessible_alternative(
+    store: &Store,
+    req: &Request,
+    cfg: &crate::config::Config,
+    capabilities: &crate::runtime::capabilities::PolicyExecutionCapabilities,
+    provider_registry: &crate::providers::registry::ProviderRegistry,
+    ip: &str,
+    ua: &str,
+    retry: bool,
+) -> Response {
+    let response = crate::boundaries::render_not_a_bot_accessible(store, req, cfg, retry);
+    // Sessions without an open offer get a refusal, which is not a served alternative.
+    if *response.status() == 200u16 {
+        execute_capability_gated_intents(
+            req,
+            store,
+            cfg,
+            provider_registry,
+            ip,
+            ua,
+            capabilities,
+            vec![
+                crate::runtime::effect_intents::EffectIntent::RecordNotABotAccessibleAlternative {
+                    outcome: "served".to_string(),
+                },
+            ],
+        );
+    }
+    response
+}
+
+#[allow(clippy::too_many_arguments)]
+fn respond_to_not_a_bot_result(
+    store: &Store,
+    req: &Request,
+    cfg: &crate::config::Config,
+    capabilities: &crate::runtime::capabilities::PolicyExecutionCapabilities,
+    provider_registry: &crate::providers::registry::ProviderRegistry,
+    ip: &str,
+    ua: &str,
+    submit_result: crate::challenge::NotABotSubmitResult,
+    reason_prefix: &str,
+) -> Response {
     match submit_result.decision {
         crate::challenge::NotABotDecision::Pass => {
             execute_capability_gated_intents(
                 req,
                 store,
                 cfg,
-                &provider_registry,
-                ip.as_str(),
+                provider_registry,
+                ip,
                 ua,
                 capabilities,
                 vec![
@@ -250,9 +354,10 @@ fn handle_not_a_bot_submit(
                         metric: crate::observability::metrics::MetricName::NotABotPassTotal,
                         label: None,
                     },
+                    crate::runtime::effect_intents::EffectIntent::GrantPrivacyPassIssuance,
                     crate::runtime::effect_intents::EffectIntent::LogEvent {
                         event: crate::admin::EventType::Challenge,
-                        reason: "not_a_bot_pass".to_string(),
+                        reason: format!("{}_pass", reason_prefix),
                         outcome: format!(
                             "return_to={} solve_ms={}",
                             submit_result.return_to,
@@ -270,13 +375,23 @@ fn handle_not_a_bot_submit(
             }
             builder.body(Vec::new()).build()
         }
+        crate::challenge::NotABotDecision::RetryAccessible => render_not_a_bot_accessible_alternative(
+            store,
+            req,
+            cfg,
+            capabilities,
+            provider_registry,
+            ip,
+            ua,
+            true,
+        ),
         crate::challenge::NotABotDecision::EscalatePuzzle => {
             execute_capability_gated_intents(
                 req,
                 store,
                 cfg,
-                &provider_registry,
-                ip.as_str(),
+                provider_registry,
+                ip,
                 ua,
                 capabilities,
                 vec![
@@ -296,8 +411,8 @@ fn handle_not_a_bot_submit(
                     req,
                     store,
                     cfg,
-                    &provider_registry,
-                    ip.as_str(),
+                    provider_registry,
+                    ip,
                     ua,
                     capabilities,
                     vec![
@@ -311,13 +426,17 @@ fn handle_not_a_bot_submit(
                         },
                     ],
                 );
-                return provider_registry
+                let puzzle = provider_registry
                     .challenge_engine_provider()
                     .render_challenge(
                         req,
                         cfg.challenge_puzzle_transform_count as usize,
                         cfg.challenge_puzzle_seed_ttl_seconds,
                     );
+                if submit_result.accessible_offer {
+                    return crate::boundaries::with_not_a_bot_accessible_offer(puzzle);
+                }
+                return puzzle;
             }
             if cfg.maze_enabled {
                 return provider_registry
@@ -326,7 +445,7 @@ fn handle_not_a_bot_submit(
                         req,
                         store,
                         cfg,
-                        ip.as_str(),
+                        ip,
                         ua,
                         crate::maze::entry_path("not-a-bot-escalate-fallback").as_str(),
                         "not_a_bot_escalate_puzzle_fallback_maze",
@@ -356,15 +475,15 @@ fn handle_not_a_bot_submit(
             }
             intents.push(crate::runtime::effect_intents::EffectIntent::LogEvent {
                 event: crate::admin::EventType::Challenge,
-                reason: "not_a_bot_fail".to_string(),
+                reason: format!("{}_fail", reason_prefix),
                 outcome: format!("{:?}", submit_result.outcome),
             });
             execute_capability_gated_intents(
                 req,
                 store,
                 cfg,
-                &provider_registry,
-                ip.as_str(),
+                provider_registry,
+                ip,
                 ua,
                 capabilities,
                 intents,
@@ -376,8 +495,8 @@ fn handle_not_a_bot_submit(
                         req,
                         store,
                         cfg,
-                        &provider_registry,
-                        ip.as_str(),
+                        provider_registry,
+                        ip,
                         ua,
                         "not-a-bot-fail",
                         "not_a_bot_submit_fail_maze",
@@ -391,8 +510,8 @@ fn handle_not_a_bot_submit(
                             req,
                             store,
                             cfg,
-                            &provider_registry,
-                            ip.as_str(),
+                            provider_registry,
+                            ip,
                             ua,
                             "not-a-bot-abuse-shadow-mode",
                             "not_a_bot_submit_abuse_shadow_mode_maze",
@@ -404,8 +523,8 @@ fn handle_not_a_bot_submit(
                         store,
                         req,
                         cfg,
-                        &provider_registry,
-                        ip.as_str(),
+                        provider_registry,
+                        ip,
                         capabilities,
                         "not_a_bot_abuse",
                         summary.as_str(),
@@ -498,6 +617,129 @@ pub(crate) fn maybe_handle_early_route(
         return Some(Response::new(500, "Key-value store error"));
     }
 
+    if path == crate::boundaries::challenge_not_a_bot_accessible_path()
+        && *req.method() == Method::Post
+    {
+        if let Ok(store) = Store::open_default() {
+            let cfg = match crate::load_runtime_config(&store, "default", path) {
+                Ok(cfg) => cfg,
+                Err(resp) => return Some(resp),
+            };
+            return Some(handle_not_a_bot_accessible_submit(&store, req, &cfg, capabilities));
+        }
+        return Some(Response::new(500, "Key-value store error"));
+    }
+
+    if path == crate::boundaries::challenge_not_a_bot_accessible_path()
+        && *req.method() == Method::Get
+    {
+        if let Ok(store) = Store::open_default() {
+            let cfg = match crate::load_runtime_config(&store, "default", path) {
+                Ok(cfg) => cfg,
+                Err(resp) => return Some(resp),
+            };
+            let provider_registry = crate::providers::registry::ProviderRegistry::from_config(&cfg);
+            let ip = crate::extract_client_ip(req);
+            return Some(render_not_a_bot_accessible_alternative(
+                &store,
+                req,
+                &cfg,
+                capabilities,
+                &provider_registry,
+                ip.as_str(),
+                request_user_agent(req),
+                false,
+            ));
+        }
+        return Some(Response::new(500, "Key-value store error"));
+    }
+
+    if path == crate::boundaries::privacy_pass_issuer_directory_path()
+        && *req.method() == Method::Get
+    {
+        return Some(crate::boundaries::serve_privacy_pass_issuer_directory(
+            crate::admin::now_ts(),
+        ));
+    }
+
+    if path == crate::boundaries::privacy_pass_token_request_path()
+        && *req.method() == Method::Post
+    {
+        if let Ok(store) = Store::open_default() {
+            let cfg = match crate::load_runtime_config(&store, "default", path) {
+                Ok(cfg) => cfg,
+                Err(resp) => return Some(resp),
+            };
+            let provider_registry = crate::providers::registry::ProviderRegistry::from_config(&cfg);
+            let ip = crate::extract_client_ip(req);
+            let ua = request_user_agent(req);
+            let (response, outcome) = crate::boundaries::handle_privacy_pass_token_request(
+                &store,
+                req,
+                ip.as_str(),
+                ua,
+                crate::admin::now_ts(),
+            );
+            execute_capability_gated_intents(
+                req,
+                &store,
+                &cfg,
+                &provider_registry,
+                ip.as_str(),
+                ua,
+                capabilities,
+                vec![crate::runtime::effect_intents::EffectIntent::IncrementMetric {
+                    metric: crate::observability::metrics::MetricName::PrivacyPassIssuanceOutcomes,
+                    label: Some(outcome.as_str().to_string()),
+                }],
+            );
+            return Some(response);
+        }
+        return Some(Response::new(500, "Key-value store error"));
+    }
+
+    if path == crate::boundaries::privacy_pass_redeem_path() && *req.method() == Method::Post {
+        if let Ok(store) = Store::open_default() {
+            let cfg = match crate::load_runtime_config(&store, "default", path) {
+                Ok(cfg) => cfg,
+                Err(resp) => return Some(resp),
+            };
+            let provider_registry = crate::providers::registry::ProviderRegistry::from_config(&cfg);
+            let ip = crate::extract_client_ip(req);
+            let ua = request_user_agent(req);
+            let (response, outcome) = crate::boundaries::handle_privacy_pass_redeem(
+                &store,
+                req,
+                ip.as_str(),
+                ua,
+                crate::admin::now_ts(),
+            );
+            execute_capability_gated_intents(
+                req,
+                &store,
+                &cfg,
+                &provider_registry,
+                ip.as_str(),
+                ua,
+                capabilities,
+                vec![
+                    crate::runtime::effect_intents::EffectIntent::IncrementMetric {
+                        metric:
+                            crate::observability::metrics::MetricName::PrivacyPassRedemptionOutcomes,
+                        label: Some(outcome.as_str().to_string()),
+                    },
+                    crate::runtime::effect_intents::EffectIntent::LogEvent {
+                        event: crate::admin::EventType::Challenge,
+                        reason: "privacy_pass_redeem".to_string(),
+                        outcome: outcome.as_str().to_string(),
+                    },
+                ],
+            );
+            return Some(response);
+        }
+        return Some(Response::new(500, "Key-value store error"));
+    }
+
     if path == crate::boundaries::challenge_not_a_bot_path() && *req.method() == Method::Get {
         if let Ok(store) = Store::open_default() {
             let cfg = match crate::load_runtime_config(&store, "default", path) {
@@ -555,6 +797,7 @@ pub(crate) fn maybe_handle_early_route(
                         label: None,
                     },
                     crate::runtime::effect_intents::EffectIntent::RecordIpRangeChallengeSolved,
+                    crate::runtime::effect_intents::EffectIntent::GrantPrivacyPassIssuance,
                     crate::runtime::effect_intents::EffectIntent::LogEvent {
                         event: crate::admin::EventType::Challenge,
                         reason: "challenge_puzzle_pass".to_string(),
@@ -281,7 +281,9 @@ fn classify_policy_decision(decision: &PolicyDecision) -> MonitoringTrafficClass
         PolicyDecision::VerifiedIdentityPolicyDeny { .. }
         | PolicyDecision::VerifiedIdentityPolicyAllow { .. }
         | PolicyDecision::VerifiedIdentityPolicyObserve { .. }
-        | PolicyDecision::VerifiedIdentityPolicyRestrict { .. } => MonitoringTrafficClassification {
+        | PolicyDecision::VerifiedIdentityPolicyRestrict { .. }
+        | PolicyDecision::VerifiedIdentityQuota { .. }
+        | PolicyDecision::VerifiedIdentityLicenceRequired { .. } => MonitoringTrafficClassification {
             measurement_scope: MeasurementScope::IngressPrimary,
             route_action_family: RouteActionFamily::PublicContent,
             traffic_lane: None,
@@ -300,6 +302,20 @@ fn classify_policy_decision(decision: &PolicyDecision) -> MonitoringTrafficClass
             traffic_lane: Some(UNKNOWN_INTERACTIVE_RESIDUAL),
             policy_source: PolicySource::PolicyGraphSecondTranche,
         },
+        PolicyDecision::CustomRule { action, .. } => MonitoringTrafficClassification {
+            measurement_scope: MeasurementScope::IngressPrimary,
+            route_action_family: RouteActionFamily::PublicContent,
+            traffic_lane: (*action != crate::config::CustomRuleAction::Allow)
+                .then_some(SUSPICIOUS_POLICY),
+            policy_source: PolicySource::PolicyGraphSecondTranche,
+        },
+        PolicyDecision::Shadowed { decision, .. } => classify_policy_decision(decision),
+        PolicyDecision::PrivacyPassTokenRedeemed { .. } => MonitoringTrafficClassification {
+            measurement_scope: MeasurementScope::IngressPrimary,
+            route_action_family: RouteActionFamily::PublicContent,
+            traffic_lane: Some(LIKELY_HUMAN_OBSERVED),
+            policy_source: PolicySource::PolicyGraphSecondTranche,
+        },
         PolicyDecision::BotnessMaze { .. }
         | PolicyDecision::BotnessNotABot { .. }
         | PolicyDecision::BotnessChallenge { .. }
@@ -394,6 +410,7 @@ mod tests {
                                 directory_source: None,
                                 provenance:
                                     crate::bot_identity::contracts::IdentityProvenance::Provider,
+                                delegation: None,
                             },
                         ),
                         &[crate::bot_identity::policy::IdentityPolicyEntry {
@@ -418,6 +435,7 @@ mod tests {
                             directory_source: None,
                             provenance:
                                 crate::bot_identity::contracts::IdentityProvenance::Provider,
+                            delegation: None,
                         },
                         "/pricing",
                     ),
@@ -474,6 +492,7 @@ mod tests {
             end_user_controlled: true,
             directory_source: None,
             provenance: crate::bot_identity::contracts::IdentityProvenance::Provider,
+            delegation: None,
         });
 
         assert_eq!(lane, SIGNED_AGENT_OBSERVED);
@@ -490,6 +509,7 @@ mod tests {
             end_user_controlled: false,
             directory_source: None,
             provenance: crate::bot_identity::contracts::IdentityProvenance::Provider,
+            delegation: None,
         });
 
         assert_eq!(lane, VERIFIED_BOT_OBSERVED);
@@ -507,6 +527,7 @@ mod tests {
             end_user_controlled: false,
             directory_source: None,
             provenance: crate::bot_identity::contracts::IdentityProvenance::Native,
+            delegation: None,
         };
 
         assert_eq!(
@@ -0,0 +1,622 @@
+//! Runtime effects for verified identities that are neither fully allowed nor denied: the
+//! per-identity budget and path scope behind `restrict`, and the reduced representations served
+//! to `metadata_only` and `structured_agent` service profiles.
+
+use std::io::Read;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use spin_sdk::http::Response;
+
+use crate::bot_identity::contracts::VerifiedIdentityEvidence;
+use crate::bot_identity::policy::ServiceProfile;
+use crate::challenge::KeyValueStore;
+
+pub(crate) const SERVICE_PROFILE_HEADER: &str = "x-shuma-service-profile";
+const BUDGET_WINDOW_SECONDS: u64 = 60;
+
+/// Elements whose content never reaches a structured-agent representation.
+const STRUCTURED_DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "iframe", "object", "canvas", "form", "nav",
+    "footer", "aside", "button", "select", "dialog",
+];
+
+/// Elements kept (attribute-free, apart from `href` on links) in a structured-agent body.
+const STRUCTURED_KEPT_ELEMENTS: &[&str] = &[
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "p",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "a",
+    "blockquote",
+    "pre",
+    "code",
+    "table",
+    "thead",
+    "tbody",
+    "tr",
+    "th",
+    "td",
+    "main",
+    "article",
+    "section",
+    "br",
+];
+
+/// Budget bucket for one verified identity. The stable identity is hashed so arbitrary
+/// operator-supplied identifiers cannot shape KV keys.
+pub(crate) fn identity_budget_key(identity: &VerifiedIdentityEvidence) -> String {
+    let digest = Sha256::digest(
+        format!("{}:{}", identity.scheme.as_str(), identity.stable_identity).as_bytes(),
+    );
+    format!("{:x}", digest)[..24].to_string()
+}
+
+/// Counts one request against the identity's fixed one-minute window. A zero limit disables the
+/// budget.
+pub(crate) fn consume_identity_budget<S: KeyValueStore>(
+    store: &S,
+    site_id: &str,
+    budget_key: &str,
+    requests_per_minute: u32,
+) -> bool {
+    if requests_per_minute == 0 {
+        return true;
+    }
+    let window = now_ts() / BUDGET_WINDOW_SECONDS;
+    let key = format!(
+        "verified_identity_budget:{}:{}:{}",
+        site_id, budget_key, window
+    );
+    let count = store
+        .get(&key)
+        .ok()
+        .flatten()
+        .and_then(|value| String::from_utf8(value).ok())
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(0);
+    if count >= requests_per_minute {
+        return false;
+    }
+    if let Err(err) = store.set(&key, (count + 1).to_string().as_bytes()) {
+        eprintln!(
+            "[verified_identity] failed to persist budget counter for key {}: {:?}",
+            key, err
+        );
+    }
+    true
+}
+
+pub(crate) fn budget_retry_after_seconds() -> u64 {
+    BUDGET_WINDOW_SECONDS - now_ts() % BUDGET_WINDOW_SECONDS
+}
+
+pub(crate) fn restricted_path_denied(denied_prefixes: &[String], path: &str) -> bool {
+    denied_prefixes
+        .iter()
+        .any(|prefix| !prefix.is_empty() && path.starts_with(prefix.as_str()))
+}
+
+/// Reduce a forwarded upstream response to what the service profile is entitled to see.
+/// Non-success and bodiless responses pass through untouched; anything that cannot be decoded
+/// fails closed rather than leaking the full representation.
+pub(crate) fn shape_forwarded_response(profile: ServiceProfile, response: Response) -> Response {
+    if !matches!(
+        profile,
+        ServiceProfile::MetadataOnly | ServiceProfile::StructuredAgent
+    ) {
+        return response;
+    }
+    let status = *response.status();
+    if !(200..300).contains(&status) || response.body().is_empty() {
+        return response;
+    }
+    let content_type = header_value(&response, "content-type").unwrap_or_default();
+    if !content_type.to_ascii_lowercase().contains("html") {
+        return match profile {
+            ServiceProfile::MetadataOnly => profile_refusal(
+                403,
+                "Metadata-only access does not include this resource",
+                profile,
+            ),
+            _ => response,
+        };
+    }
+    let encoding = header_value(&response, "content-encoding").unwrap_or_default();
+    let Some(body) = decode_body(encoding.as_str(), response.body()) else {
+        return profile_refusal(
+            502,
+            "Upstream response encoding cannot be shaped for this service profile",
+            profile,
+        );
+    };
+    let html = String::from_utf8_lossy(body.as_slice());
+    let shaped = match profile {
+        ServiceProfile::MetadataOnly => metadata_only_document(&html),
+        _ => structured_agent_document(&html),
+    };
+
+    let mut response_builder = Response::builder();
+    let mut builder = response_builder.status(status);
+    for (name, value) in response.headers() {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        let lower = name.to_ascii_lowercase();
+        if matches!(
+            lower.as_str(),
+            "content-type" | "content-length" | "content-encoding" | "etag" | "content-md5"
+        ) {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+    builder
+        .header("Content-Type", "text/html; charset=utf-8")
+        .header(SERVICE_PROFILE_HEADER, profile.as_str())
+        .body(shaped)
+        .build()
+}
+
+/// `<head>` metadata only: title, named/property meta tags, and canonical/alternate links.
+pub(crate) fn metadata_only_document(html: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head>\n{}</head><body></body></html>\n",
+        head_metadata(html)
+    )
+}
+
+/// Head metadata plus the document's text structure, with scripts, chrome, and styling removed.
+pub(crate) fn structured_agent_document(html: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head>\n{}</head><body>\n{}\n</body></html>\n",
+        head_metadata(html),
+        structured_body(html)
+    )
+}
+
+fn profile_refusal(status: u16, message: &str, profile: ServiceProfile) -> Response {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .header("Cache-Control", "no-store")
+        .header(SERVICE_PROFILE_HEADER, profile.as_str())
+        .body(message.to_string())
+        .build()
+}
+
+fn header_value(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+        .map(|value| value.trim().to_string())
+}
+
+fn decode_body(encoding: &str, body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    match encoding.to_ascii_lowercase().as_str() {
+        "" | "identity" => return Some(body.to_vec()),
+        "gzip" | "x-gzip" => flate2::read::GzDecoder::new(body)
+            .read_to_end(&mut decoded)
+            .ok()?,
+        "deflate" => flate2::read::ZlibDecoder::new(body)
+            .read_to_end(&mut decoded)
+            .ok()?,
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    Open { name: String, attrs: &'a str },
+    Close { name: String },
+}
+
+/// A deliberately small tokenizer: enough structure to pick out metadata and text blocks, with
+/// raw-text elements skipped whole so their contents are never mistaken for markup.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let lower = html.to_ascii_lowercase();
+    let bytes = html.as_bytes();
+    let mut cursor = 0;
+    while cursor < html.len() {
+        let Some(offset) = html[cursor..].find('<') else {
+            tokens.push(Token::Text(&html[cursor..]));
+            break;
+        };
+        let start = cursor + offset;
+        if start > cursor {
+            tokens.push(Token::Text(&html[cursor..start]));
+        }
+        if lower[start..].starts_with("<!--") {
+            cursor = lower[start..]
+                .find("-->")
+                .map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+        let Some(end) = tag_end(bytes, start) else {
+            break;
+        };
+        let inner = &html[start + 1..end];
+        cursor = end + 1;
+        if inner.starts_with('!') || inner.starts_with('?') {
+            continue;
+        }
+        let closing = inner.starts_with('/');
+        let body = inner.trim_start_matches('/');
+        let name_len = body
+            .find(|c: char| c.is_ascii_whitespace() || c == '/')
+            .unwrap_or(body.len());
+        let name = body[..name_len].to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        if closing {
+            tokens.push(Token::Close { name });
+            continue;
+        }
+        let attrs = body[name_len..].trim_end_matches('/');
+        let raw_text = matches!(name.as_str(), "script" | "style" | "title" | "textarea");
+        tokens.push(Token::Open {
+            name: name.clone(),
+            attrs,
+        });
+        if raw_text {
+            let closing_tag = format!("</{}", name);
+            let text_end = lower[cursor..]
+                .find(closing_tag.as_str())
+                .map_or(html.len(), |offset| cursor + offset);
+            if text_end > cursor {
+                tokens.push(Token::Text(&html[cursor..text_end]));
+            }
+            tokens.push(Token::Close { name });
+            cursor = lower[text_end..]
+                .find('>')
+                .map_or(html.len(), |offset| text_end + offset + 1);
+        }
+    }
+    tokens
+}
+
+fn tag_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (index, byte) in bytes.iter().enumerate().skip(start + 1) {
+        match (quote, *byte) {
+            (Some(open), current) if current == open => quote = None,
+            (Some(_), _) => {}
+            (None, b'"') | (None, b'\'') => quote = Some(*byte),
+            (None, b'>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn attribute<'a>(attrs: &'a str, wanted: &str) -> Option<&'a str> {
+    let mut rest = attrs.trim_start();
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        rest = rest[name_len..].trim_start();
+        let mut value = "";
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (parsed, remaining) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let close = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..close], inner.get(close + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let close = after_eq
+                        .find(|c: char| c.is_ascii_whitespace())
+                        .unwrap_or(after_eq.len());
+                    (&after_eq[..close], &after_eq[close..])
+                }
+            };
+            value = parsed;
+            rest = remaining.trim_start();
+        }
+        if name.eq_ignore_ascii_case(wanted) {
+            return Some(value);
+        }
+        if name_len == 0 {
+            break;
+        }
+    }
+    None
+}
+
+fn quoted(value: &str) -> String {
+    value.replace('"', "&quot;").replace('<', "&lt;")
+}
+
+fn head_metadata(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_title = false;
+    for token in tokenize(html) {
+        match token {
+            Token::Open { name, .. } if name == "body" => break,
+            Token::Open { name, .. } if name == "title" => {
+                in_title = true;
+                out.push_str("<title>");
+            }
+            Token::Close { name } if name == "title" && in_title => {
+                in_title = false;
+                out.push_str("</title>\n");
+            }
+            Token::Text(text) if in_title => out.push_str(text.trim()),
+            Token::Open { name, attrs } if name == "meta" => {
+                if attribute(attrs, "http-equiv").is_some() {
+                    continue;
+                }
+                if let Some(charset) = attribute(attrs, "charset") {
+                    out.push_str(format!("<meta charset=\"{}\">\n", quoted(charset)).as_str());
+                    continue;
+                }
+                let key = attribute(attrs, "name")
+                    .map(|value| ("name", value))
+                    .or_else(|| attribute(attrs, "property").map(|value| ("property", value)));
+                if let (Some((key, key_value)), Some(content)) = (key, attribute(attrs, "content"))
+                {
+                    out.push_str(
+                        format!(
+                            "<meta {}=\"{}\" content=\"{}\">\n",
+                            key,
+                            quoted(key_value),
+                            quoted(content)
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+            Token::Open { name, attrs } if name == "link" => {
+                let (Some(rel), Some(href)) = (attribute(attrs, "rel"), attribute(attrs, "href"))
+                else {
+                    continue;
+                };
+                let rel_lower = rel.to_ascii_lowercase();
+                if rel_lower.contains("canonical") || rel_lower.contains("alternate") {
+                    out.push_str(
+                        format!("<link rel=\"{}\" href=\"{}\">\n", quoted(rel), quoted(href))
+                            .as_str(),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn structured_body(html: &str) -> String {
+    let tokens = tokenize(html);
+    let has_body = tokens
+        .iter()
+        .any(|token| matches!(token, Token::Open { name, .. } if name == "body"));
+    let mut in_body = !has_body;
+    let mut in_head = false;
+    let mut dropped: Option<(String, usize)> = None;
+    let mut out = String::new();
+    for token in tokens {
+        if let Some((dropped_name, depth)) = dropped.as_mut() {
+            match &token {
+                Token::Open { name, .. } if name == dropped_name => *depth += 1,
+                Token::Close { name } if name == dropped_name => {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        dropped = None;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match token {
+            Token::Open { name, .. } if name == "body" => in_body = true,
+            Token::Open { name, .. } if name == "head" => in_head = true,
+            Token::Close { name } if name == "head" => in_head = false,
+            _ if !in_body || in_head => {}
+            Token::Open { name, .. } if name == "title" => dropped = Some((name, 1)),
+            Token::Open { name, .. } if STRUCTURED_DROPPED_ELEMENTS.contains(&name.as_str()) => {
+                dropped = Some((name, 1));
+            }
+            Token::Open { name, attrs } if STRUCTURED_KEPT_ELEMENTS.contains(&name.as_str()) => {
+                match (name.as_str(), attribute(attrs, "href")) {
+                    ("a", Some(href)) => {
+                        out.push_str(format!("<a href=\"{}\">", quoted(href)).as_str())
+                    }
+                    _ => out.push_str(format!("<{}>", name).as_str()),
+                }
+            }
+            Token::Close { name }
+                if name != "br" && STRUCTURED_KEPT_ELEMENTS.contains(&name.as_str()) =>
+            {
+                out.push_str(format!("</{}>", name).as_str());
+            }
+            Token::Text(text) => {
+                if text.trim().is_empty() {
+                    if !out.ends_with('\n') && !out.is_empty() {
+                        out.push('\n');
+                    }
+                } else {
+                    out.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <meta http-equiv="refresh" content="5">
+  <title>Quarterly Results</title>
+  <meta name="description" content="Revenue grew &amp; margins held.">
+  <meta property="og:title" content='Quarterly "Results"'>
+  <link rel="canonical" href="https://example.com/results">
+  <link rel="stylesheet" href="/site.css">
+  <script>var tracking = "<p>not content</p>";</script>
+</head>
+<body class="article">
+  <nav><a href="/">Home</a></nav>
+  <!-- <p>commented out</p> -->
+  <main>
+    <h1 id="headline">Quarterly Results</h1>
+    <p style="color:red">Full article body with <a href="/detail" onclick="x()">a link</a>.</p>
+    <div><span>Nested text</span></div>
+    <form action="/subscribe"><input name="email"></form>
+  </main>
+  <footer>Copyright</footer>
+</body>
+</html>"#;
+
+    #[test]
+    fn metadata_only_keeps_head_metadata_and_drops_the_body() {
+        let document = metadata_only_document(ARTICLE);
+
+        assert!(document.contains("<title>Quarterly Results</title>"));
+        assert!(document.contains("<meta charset=\"utf-8\">"));
+        assert!(document
+            .contains("<meta name=\"description\" content=\"Revenue grew &amp; margins held.\">"));
+        assert!(document
+            .contains("<meta property=\"og:title\" content=\"Quarterly &quot;Results&quot;\">"));
+        assert!(document.contains("<link rel=\"canonical\" href=\"https://example.com/results\">"));
+        assert!(!document.contains("refresh"));
+        assert!(!document.contains("site.css"));
+        assert!(!document.contains("tracking"));
+        assert!(!document.contains("Full article body"));
+        assert!(document.contains("<body></body>"));
+    }
+
+    #[test]
+    fn structured_agent_keeps_text_structure_without_scripts_or_chrome() {
+        let document = structured_agent_document(ARTICLE);
+
+        assert!(document.contains("<title>Quarterly Results</title>"));
+        assert!(document.contains("<h1>Quarterly Results</h1>"));
+        assert!(document.contains("<p>Full article body with <a href=\"/detail\">a link</a>.</p>"));
+        assert!(document.contains("Nested text"));
+        assert!(!document.contains("<span>"));
+        assert!(!document.contains("Home"));
+        assert!(!document.contains("Copyright"));
+        assert!(!document.contains("commented out"));
+        assert!(!document.contains("tracking"));
+        assert!(!document.contains("onclick"));
+        assert!(!document.contains("email"));
+    }
+
+    #[test]
+    fn shaping_decodes_gzip_and_fails_closed_on_unknown_encodings_and_non_html() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(ARTICLE.as_bytes()).expect("gzip write");
+        let gzipped = encoder.finish().expect("gzip finish");
+        let upstream = Response::builder()
+            .status(200)
+            .header("Content-Type", "text/html")
+            .header("Content-Encoding", "gzip")
+            .header("ETag", "\"abc\"")
+            .header("Cache-Control", "max-age=60")
+            .body(gzipped)
+            .build();
+        let shaped = shape_forwarded_response(ServiceProfile::MetadataOnly, upstream);
+        let body = String::from_utf8(shaped.body().to_vec()).expect("utf8 body");
+        assert_eq!(*shaped.status(), 200);
+        assert!(body.contains("<title>Quarterly Results</title>"));
+        assert!(!body.contains("Full article body"));
+        assert!(header_value(&shaped, "content-encoding").is_none());
+        assert!(header_value(&shaped, "etag").is_none());
+        assert_eq!(
+            header_value(&shaped, "cache-control").as_deref(),
+            Some("max-age=60")
+        );
+        assert_eq!(
+            header_value(&shaped, SERVICE_PROFILE_HEADER).as_deref(),
+            Some("metadata_only")
+        );
+
+        let brotli = Response::builder()
+            .status(200)
+            .header("Content-Type", "text/html")
+            .header("Content-Encoding", "br")
+            .body(vec![1, 2, 3])
+            .build();
+        let shaped = shape_forwarded_response(ServiceProfile::StructuredAgent, brotli);
+        assert_eq!(*shaped.status(), 502);
+
+        let image = || {
+            Response::builder()
+                .status(200)
+                .header("Content-Type", "image/png")
+                .body(vec![137, 80, 78, 71])
+                .build()
+        };
+        assert_eq!(
+            *shape_forwarded_response(ServiceProfile::MetadataOnly, image()).status(),
+            403
+        );
+        let passed = shape_forwarded_response(ServiceProfile::StructuredAgent, image());
+        assert_eq!(passed.body(), &[137, 80, 78, 71]);
+
+        let not_found = Response::builder()
+            .status(404)
+            .header("Content-Type", "text/html")
+            .body("<p>missing</p>")
+            .build();
+        let passed = shape_forwarded_response(ServiceProfile::MetadataOnly, not_found);
+        assert_eq!(passed.body(), b"<p>missing</p>");
+    }
+
+    #[test]
+    fn identity_budget_counts_per_identity_and_zero_disables_it() {
+        let store = crate::test_support::InMemoryStore::default();
+
+        assert!(consume_identity_budget(&store, "default", "crawler-a", 2));
+        assert!(consume_identity_budget(&store, "default", "crawler-a", 2));
+        assert!(!consume_identity_budget(&store, "default", "crawler-a", 2));
+        assert!(consume_identity_budget(&store, "default", "crawler-b", 2));
+        for _ in 0..5 {
+            assert!(consume_identity_budget(&store, "default", "crawler-a", 0));
+        }
+    }
+
+    #[test]
+    fn restricted_paths_match_by_prefix() {
+        let prefixes = vec!["/articles/".to_string(), String::new()];
+
+        assert!(restricted_path_denied(&prefixes, "/articles/2026/results"));
+        assert!(!restricted_path_denied(&prefixes, "/about"));
+        assert!(!restricted_path_denied(&[], "/articles/x"));
+    }
+}
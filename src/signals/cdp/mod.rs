@@ -17,6 +17,12 @@ use sha2::{Digest, Sha256};
 use spin_sdk::http::{Request, Response};
 use spin_sdk::key_value::Store;
 
+mod report_binding;
+
+pub(crate) use report_binding::{
+    bot_signals as report_binding_bot_signals, record_report_expected, CdpReportBindingOutcome,
+};
+
 const MAX_CDP_CHECKS: usize = 32;
 
 /// CDP detection report from client-side JavaScript
@@ -27,6 +33,19 @@ pub struct CdpReport {
     pub checks: Vec<String>,
 }
 
+/// Report body as posted by the probe page: the report plus its request binding.
+#[derive(Debug, Deserialize)]
+struct CdpReportSubmission {
+    #[serde(flatten)]
+    report: CdpReport,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    proof: Option<String>,
+    #[serde(default)]
+    unavailable: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CdpTier {
     Low,
@@ -88,7 +107,7 @@ fn cdp_tier_label(tier: CdpTier) -> &'static str {
     }
 }
 
-fn increment_kv_counter(store: &Store, key: &str) {
+fn increment_kv_counter<S: crate::challenge::KeyValueStore>(store: &S, key: &str) {
     let current: u64 = store
         .get(key)
         .ok()
@@ -101,7 +120,43 @@ fn increment_kv_counter(store: &Store, key: &str) {
     }
 }
 
+fn record_report_binding_outcome<S: crate::challenge::KeyValueStore>(
+    store: &S,
+    ip: &str,
+    user_agent: &str,
+    binding: CdpReportBindingOutcome,
+    unavailable: bool,
+) {
+    increment_kv_counter(
+        store,
+        format!("cdp:report_binding:{}", binding.as_str()).as_str(),
+    );
+    if !binding.is_anomalous() {
+        // An `unavailable` report is bound but carries no probe result, so the
+        // expectation stays open and can still surface as a missing report.
+        if !unavailable {
+            report_binding::record_report_received(store, ip, user_agent);
+        }
+        return;
+    }
+
+    report_binding::record_report_anomaly(store, ip, user_agent, binding, crate::admin::now_ts());
+    crate::admin::log_event(
+        store,
+        &crate::admin::EventLogEntry {
+            ts: crate::admin::now_ts(),
+            event: crate::admin::EventType::Challenge,
+            ip: Some(ip.to_string()),
+            reason: Some(format!("cdp_report_binding:{}", binding.as_str())),
+            outcome: Some("report_binding_anomaly".to_string()),
+            admin: None,
+        },
+    );
+}
+
 /// Handles incoming CDP detection reports from client-side JavaScript.
+/// Every report is checked against its request-bound nonce first; binding anomalies are
+/// recorded as botness evidence independent of what the report itself claims.
 /// Auto-bans are only applied for strong-tier automation detections.
 pub fn handle_cdp_report(store: &Store, req: &Request) -> Response {
     let ip = crate::extract_client_ip(req);
@@ -123,10 +178,17 @@ pub fn handle_cdp_report(store: &Store, req: &Request) -> Response {
     ) {
         return Response::new(400, e);
     }
-    let mut report: CdpReport = match serde_json::from_slice(body) {
+    let submission: CdpReportSubmission = match serde_json::from_slice(body) {
         Ok(r) => r,
         Err(_) => return Response::new(400, "Invalid CDP report format"),
     };
+    let mut report = submission.report;
+    let verdict = report_binding::report_verdict(
+        report.cdp_detected,
+        report.score,
+        report.checks.as_slice(),
+        submission.unavailable,
+    );
     if !report.score.is_finite() || report.score < 0.0 || report.score > 5.0 {
         return Response::new(400, "Invalid CDP score");
     }
@@ -144,8 +206,37 @@ pub fn handle_cdp_report(store: &Store, req: &Request) -> Response {
     }
     report.checks = sanitized_checks;
 
+    let user_agent = req
+        .header("user-agent")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let binding = report_binding::validate_report_binding(
+        store,
+        submission.nonce.as_deref(),
+        submission.proof.as_deref(),
+        verdict.as_str(),
+        ip.as_str(),
+        user_agent,
+        report_binding::now_ms(),
+    );
+    record_report_binding_outcome(
+        store,
+        ip.as_str(),
+        user_agent,
+        binding,
+        submission.unavailable,
+    );
+    if submission.unavailable {
+        increment_kv_counter(store, "cdp:unavailable_reports");
+        return Response::new(200, "Report received");
+    }
+
     let cdp_tier = classify_cdp_tier(&report, cfg.cdp_detection_threshold);
     let tier_label = cdp_tier_label(cdp_tier);
+    if !report.cdp_detected && cdp_tier == CdpTier::Low {
+        increment_kv_counter(store, "cdp:clean_reports");
+        return Response::new(200, "Report received");
+    }
     let detection_policy_match = match cdp_tier {
         CdpTier::Low => crate::runtime::policy_taxonomy::resolve_policy_match(
             crate::runtime::policy_taxonomy::PolicyTransition::CdpReportLow,
@@ -573,6 +664,17 @@ pub fn get_cdp_detection_script_for_request(
     }
 }
 
+/// Returns the nonce-bound report script, and the `<html>` attribute holding the rest of its
+/// key, for one probe page served to `ip`/`user_agent`.
+pub(crate) fn get_bound_cdp_report_script(
+    report_endpoint: &str,
+    ip: &str,
+    user_agent: &str,
+) -> report_binding::BoundReportScript {
+    let nonce = report_binding::issue_report_nonce(ip, user_agent, report_binding::now_ms());
+    report_binding::bound_report_script(report_endpoint, nonce.as_str())
+}
+
 /// Returns the CDP detection JavaScript as a string
 pub fn get_cdp_detection_script() -> &'static str {
     CDP_DETECTION_JS
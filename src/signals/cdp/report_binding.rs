@@ -0,0 +1,549 @@
+// src/signals/cdp/report_binding.rs
+// Request-bound nonces for CDP probe reports.
+//
+// The CDP probe page is issued with a signed nonce bound to the IP bucket, UA bucket
+// and issue time, plus a per-nonce key split between the script and the page's `<html>`
+// element. The page never carries a finished proof: it has to hash the reassembled key with
+// the nonce, its own `navigator.userAgent` and the verdict it reports, so a proof lifted for
+// one verdict cannot vouch for another. Reports that lack a valid binding, replay a nonce or
+// arrive implausibly fast are tracked as their own botness evidence, and so are reports that
+// were expected but never arrived.
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::challenge::operation_envelope;
+use crate::signals::botness::{BotSignal, SignalFamily, SignalProvenance};
+
+pub(crate) const CDP_REPORT_BINDING_ANOMALY_KEY: &str = "cdp_report_binding_anomaly";
+pub(crate) const CDP_REPORT_MISSING_KEY: &str = "cdp_report_missing";
+const CDP_REPORT_BINDING_ANOMALY_LABEL: &str = "CDP report binding anomaly";
+const CDP_REPORT_MISSING_LABEL: &str = "Expected CDP report missing";
+
+pub(crate) const WEIGHT_CDP_REPORT_BINDING_ANOMALY: u8 = 3;
+pub(crate) const WEIGHT_CDP_REPORT_MISSING: u8 = 2;
+
+const CDP_REPORT_EXPECTED_KEY_PREFIX: &str = "cdp:report_expected:";
+const CDP_REPORT_ANOMALY_KEY_PREFIX: &str = "cdp:report_anomaly:";
+const CDP_REPORT_EXPECTATION_GRACE_SECONDS: u64 = 20;
+const CDP_REPORT_EXPECTATION_TTL_SECONDS: u64 = 1800;
+const CDP_REPORT_ANOMALY_TTL_SECONDS: u64 = 1800;
+
+const CDP_REPORT_KEY_DOMAIN: &str = "shuma-cdp-report-key";
+/// Hex characters of the per-nonce key served inside the script; the rest sits on `<html>`.
+const CDP_REPORT_KEY_SCRIPT_HEX_LEN: usize = 32;
+pub(crate) const CDP_REPORT_KEY_ATTRIBUTE: &str = "data-shuma-cdp";
+const MAX_CDP_REPORT_NONCE_LEN: usize = 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CdpReportNoncePayload {
+    operation_id: String,
+    flow_id: String,
+    step_id: String,
+    ip_bucket: String,
+    ua_bucket: String,
+    path_class: String,
+    issued_at_ms: u64,
+    issued_at: u64,
+    expires_at: u64,
+    token_version: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CdpReportBindingOutcome {
+    Valid,
+    Missing,
+    Invalid,
+    Expired,
+    BindingMismatch,
+    ProofMismatch,
+    Replayed,
+    TooFast,
+}
+
+impl CdpReportBindingOutcome {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            CdpReportBindingOutcome::Valid => "valid",
+            CdpReportBindingOutcome::Missing => "missing",
+            CdpReportBindingOutcome::Invalid => "invalid",
+            CdpReportBindingOutcome::Expired => "expired",
+            CdpReportBindingOutcome::BindingMismatch => "binding_mismatch",
+            CdpReportBindingOutcome::ProofMismatch => "proof_mismatch",
+            CdpReportBindingOutcome::Replayed => "replayed",
+            CdpReportBindingOutcome::TooFast => "too_fast",
+        }
+    }
+
+    pub(crate) fn is_anomalous(self) -> bool {
+        self != CdpReportBindingOutcome::Valid
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CdpReportExpectation {
+    issued_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CdpReportAnomalyState {
+    ts: u64,
+    outcome: String,
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn report_binding_secret() -> String {
+    crate::config::env_string_required("SHUMA_JS_SECRET")
+}
+
+fn sign_payload(payload: &str) -> Vec<u8> {
+    let secret = report_binding_secret();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn verify_signature(payload: &str, sig: &[u8]) -> bool {
+    let secret = report_binding_secret();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(payload.as_bytes());
+    mac.verify_slice(sig).is_ok()
+}
+
+fn client_bucket(ip: &str, user_agent: &str) -> String {
+    format!(
+        "{}:{}",
+        crate::signals::ip_identity::bucket_ip(ip),
+        operation_envelope::user_agent_bucket(user_agent)
+    )
+}
+
+/// Issues a signed CDP report nonce bound to the client IP bucket, UA bucket and issue time.
+pub(crate) fn issue_report_nonce(ip: &str, user_agent: &str, issued_at_ms: u64) -> String {
+    let mut rng = rand::rng();
+    let issued_at = issued_at_ms / 1000;
+    let payload = CdpReportNoncePayload {
+        operation_id: format!("{:016x}{:016x}", rng.random::<u64>(), rng.random::<u64>()),
+        flow_id: operation_envelope::FLOW_CDP_REPORT.to_string(),
+        step_id: operation_envelope::STEP_CDP_REPORT_SUBMIT.to_string(),
+        ip_bucket: crate::signals::ip_identity::bucket_ip(ip),
+        ua_bucket: operation_envelope::user_agent_bucket(user_agent),
+        path_class: operation_envelope::PATH_CLASS_CDP_REPORT_SUBMIT.to_string(),
+        issued_at_ms,
+        issued_at,
+        expires_at: issued_at + operation_envelope::MAX_STEP_WINDOW_SECONDS_CDP_REPORT,
+        token_version: operation_envelope::TOKEN_VERSION_V1,
+    };
+    let payload_json = serde_json::to_string(&payload).unwrap();
+    let sig = sign_payload(&payload_json);
+    format!(
+        "{}.{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(payload_json.as_bytes()),
+        general_purpose::URL_SAFE_NO_PAD.encode(sig)
+    )
+}
+
+fn parse_report_nonce(nonce: &str) -> Option<CdpReportNoncePayload> {
+    if nonce.len() > MAX_CDP_REPORT_NONCE_LEN {
+        return None;
+    }
+    let (payload_b64, sig_b64) = nonce.split_once('.')?;
+    let payload_bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload_b64.as_bytes())
+        .ok()?;
+    let sig = general_purpose::URL_SAFE_NO_PAD.decode(sig_b64.as_bytes()).ok()?;
+    let payload_json = String::from_utf8(payload_bytes).ok()?;
+    if !verify_signature(&payload_json, &sig) {
+        return None;
+    }
+    let payload = serde_json::from_str::<CdpReportNoncePayload>(&payload_json).ok()?;
+    operation_envelope::validate_signed_operation_envelope(
+        payload.operation_id.as_str(),
+        payload.flow_id.as_str(),
+        payload.step_id.as_str(),
+        payload.issued_at,
+        payload.expires_at,
+        payload.token_version,
+        operation_envelope::FLOW_CDP_REPORT,
+        operation_envelope::STEP_CDP_REPORT_SUBMIT,
+    )
+    .ok()?;
+    Some(payload)
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push_str(format!("{:02x}", byte).as_str());
+    }
+    hex
+}
+
+/// Per-nonce key the probe page hashes its report with. Never served in one piece.
+fn report_key(nonce: &str) -> String {
+    let secret = report_binding_secret();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(CDP_REPORT_KEY_DOMAIN.as_bytes());
+    mac.update(b":");
+    mac.update(nonce.as_bytes());
+    hex_encode(mac.finalize().into_bytes().as_slice())
+}
+
+/// Canonical form of the claims a report makes, as the probe page builds it before hashing.
+///
+/// Scores are compared in hundredths so float formatting differences between the page and
+/// the server cannot break the proof.
+pub(crate) fn report_verdict(
+    cdp_detected: bool,
+    score: f32,
+    checks: &[String],
+    unavailable: bool,
+) -> String {
+    let detected = if unavailable {
+        "unavailable"
+    } else if cdp_detected {
+        "1"
+    } else {
+        "0"
+    };
+    format!(
+        "{}|{}|{}",
+        detected,
+        (score * 100.0).round() as i64,
+        checks.join(",")
+    )
+}
+
+/// The proof a genuine probe page computes in-page for `verdict`.
+pub(crate) fn expected_report_proof(nonce: &str, user_agent: &str, verdict: &str) -> String {
+    let input = format!("{}:{}:{}:{}", report_key(nonce), nonce, user_agent, verdict);
+    hex_encode(Sha256::digest(input.as_bytes()).as_slice())
+}
+
+/// Validates the nonce/proof binding of one CDP report.
+///
+/// Replay tracking is consumed before the timing check so an implausibly fast report
+/// cannot be retried with the same nonce.
+pub(crate) fn validate_report_binding<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    nonce: Option<&str>,
+    proof: Option<&str>,
+    verdict: &str,
+    ip: &str,
+    user_agent: &str,
+    now_ms: u64,
+) -> CdpReportBindingOutcome {
+    let Some(nonce) = nonce.map(str::trim).filter(|value| !value.is_empty()) else {
+        return CdpReportBindingOutcome::Missing;
+    };
+    let Some(payload) = parse_report_nonce(nonce) else {
+        return CdpReportBindingOutcome::Invalid;
+    };
+
+    let now = now_ms / 1000;
+    if operation_envelope::validate_ordering_window(
+        payload.flow_id.as_str(),
+        payload.step_id.as_str(),
+        0,
+        payload.issued_at,
+        payload.expires_at,
+        now,
+        operation_envelope::FLOW_CDP_REPORT,
+        operation_envelope::STEP_CDP_REPORT_SUBMIT,
+        0,
+        operation_envelope::MAX_STEP_WINDOW_SECONDS_CDP_REPORT,
+    )
+    .is_err()
+    {
+        return CdpReportBindingOutcome::Expired;
+    }
+
+    if operation_envelope::validate_request_binding(
+        payload.ip_bucket.as_str(),
+        payload.ua_bucket.as_str(),
+        payload.path_class.as_str(),
+        ip,
+        user_agent,
+        operation_envelope::PATH_CLASS_CDP_REPORT_SUBMIT,
+    )
+    .is_err()
+    {
+        return CdpReportBindingOutcome::BindingMismatch;
+    }
+
+    let proof_matches = proof
+        .map(str::trim)
+        .map(|value| {
+            value.eq_ignore_ascii_case(expected_report_proof(nonce, user_agent, verdict).as_str())
+        })
+        .unwrap_or(false);
+    if !proof_matches {
+        return CdpReportBindingOutcome::ProofMismatch;
+    }
+
+    match operation_envelope::validate_operation_replay(
+        store,
+        payload.flow_id.as_str(),
+        payload.operation_id.as_str(),
+        now,
+        payload.expires_at,
+        operation_envelope::MAX_OPERATION_REPLAY_TTL_SECONDS_CDP_REPORT,
+    ) {
+        Ok(()) => {}
+        Err(operation_envelope::ReplayValidationError::ReplayDetected) => {
+            return CdpReportBindingOutcome::Replayed;
+        }
+        Err(operation_envelope::ReplayValidationError::ExpiredOperation) => {
+            return CdpReportBindingOutcome::Expired;
+        }
+    }
+
+    if now_ms < payload.issued_at_ms
+        || now_ms - payload.issued_at_ms < operation_envelope::MIN_STEP_LATENCY_MS_CDP_REPORT
+    {
+        return CdpReportBindingOutcome::TooFast;
+    }
+
+    CdpReportBindingOutcome::Valid
+}
+
+/// Records that a CDP probe was served to this client and a report should follow.
+///
+/// The earliest outstanding expectation is kept so repeated probe pages cannot keep
+/// pushing the grace window forward.
+pub(crate) fn record_report_expected<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    ip: &str,
+    user_agent: &str,
+    now: u64,
+) {
+    let key = format!("{}{}", CDP_REPORT_EXPECTED_KEY_PREFIX, client_bucket(ip, user_agent));
+    if load_expectation(store, key.as_str(), now).is_some() {
+        return;
+    }
+    let Ok(raw) = serde_json::to_vec(&CdpReportExpectation { issued_at: now }) else {
+        return;
+    };
+    if store.set(key.as_str(), raw.as_slice()).is_err() {
+        eprintln!("[cdp] failed to persist report expectation {}", key);
+    }
+}
+
+/// Clears the outstanding report expectation once a validly bound probe result arrives.
+///
+/// `unavailable` reports never reach this: a page that claims it could not run the probe has
+/// not delivered what the expectation was waiting for.
+pub(crate) fn record_report_received<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    ip: &str,
+    user_agent: &str,
+) {
+    let key = format!("{}{}", CDP_REPORT_EXPECTED_KEY_PREFIX, client_bucket(ip, user_agent));
+    let _ = store.delete(key.as_str());
+}
+
+pub(crate) fn record_report_anomaly<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    ip: &str,
+    user_agent: &str,
+    outcome: CdpReportBindingOutcome,
+    now: u64,
+) {
+    let key = format!("{}{}", CDP_REPORT_ANOMALY_KEY_PREFIX, client_bucket(ip, user_agent));
+    let state = CdpReportAnomalyState {
+        ts: now,
+        outcome: outcome.as_str().to_string(),
+    };
+    let Ok(raw) = serde_json::to_vec(&state) else {
+        return;
+    };
+    if store.set(key.as_str(), raw.as_slice()).is_err() {
+        eprintln!("[cdp] failed to persist report anomaly {}", key);
+    }
+}
+
+fn load_expectation<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    key: &str,
+    now: u64,
+) -> Option<CdpReportExpectation> {
+    let raw = store.get(key).ok().flatten()?;
+    let expectation = serde_json::from_slice::<CdpReportExpectation>(raw.as_slice()).ok()?;
+    if now.saturating_sub(expectation.issued_at) > CDP_REPORT_EXPECTATION_TTL_SECONDS {
+        let _ = store.delete(key);
+        return None;
+    }
+    Some(expectation)
+}
+
+fn report_missing<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    bucket: &str,
+    now: u64,
+) -> bool {
+    let key = format!("{}{}", CDP_REPORT_EXPECTED_KEY_PREFIX, bucket);
+    load_expectation(store, key.as_str(), now)
+        .map(|expectation| {
+            now.saturating_sub(expectation.issued_at) > CDP_REPORT_EXPECTATION_GRACE_SECONDS
+        })
+        .unwrap_or(false)
+}
+
+fn report_anomaly_active<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    bucket: &str,
+    now: u64,
+) -> bool {
+    let key = format!("{}{}", CDP_REPORT_ANOMALY_KEY_PREFIX, bucket);
+    let Some(state) = store
+        .get(key.as_str())
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_slice::<CdpReportAnomalyState>(raw.as_slice()).ok())
+    else {
+        return false;
+    };
+    if now.saturating_sub(state.ts) > CDP_REPORT_ANOMALY_TTL_SECONDS {
+        let _ = store.delete(key.as_str());
+        return false;
+    }
+    true
+}
+
+/// Botness signals derived from CDP report binding anomalies and missing reports.
+pub(crate) fn bot_signals<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    cfg: &crate::config::Config,
+    ip: &str,
+    user_agent: &str,
+    now: u64,
+) -> Vec<BotSignal> {
+    if !cfg.cdp_detection_enabled {
+        return vec![
+            BotSignal::disabled_with_metadata(
+                CDP_REPORT_BINDING_ANOMALY_KEY,
+                CDP_REPORT_BINDING_ANOMALY_LABEL,
+                SignalProvenance::Derived,
+                8,
+                SignalFamily::RequestIntegrity,
+            ),
+            BotSignal::disabled_with_metadata(
+                CDP_REPORT_MISSING_KEY,
+                CDP_REPORT_MISSING_LABEL,
+                SignalProvenance::Derived,
+                6,
+                SignalFamily::RequestIntegrity,
+            ),
+        ];
+    }
+
+    let bucket = client_bucket(ip, user_agent);
+    vec![
+        BotSignal::scored_with_metadata(
+            CDP_REPORT_BINDING_ANOMALY_KEY,
+            CDP_REPORT_BINDING_ANOMALY_LABEL,
+            report_anomaly_active(store, bucket.as_str(), now),
+            WEIGHT_CDP_REPORT_BINDING_ANOMALY,
+            SignalProvenance::Derived,
+            8,
+            SignalFamily::RequestIntegrity,
+        ),
+        BotSignal::scored_with_metadata(
+            CDP_REPORT_MISSING_KEY,
+            CDP_REPORT_MISSING_LABEL,
+            report_missing(store, bucket.as_str(), now),
+            WEIGHT_CDP_REPORT_MISSING,
+            SignalProvenance::Derived,
+            6,
+            SignalFamily::RequestIntegrity,
+        ),
+    ]
+}
+
+/// Probe-report script plus the `<html>` attribute carrying the rest of its key.
+pub(crate) struct BoundReportScript {
+    pub(crate) script: String,
+    pub(crate) html_attribute: String,
+}
+
+/// JavaScript that runs the CDP probe and always posts a nonce-bound report.
+///
+/// Exposes `window._shumaReportCdp()`, which resolves once the report has been sent. The proof
+/// is computed in-page with WebCrypto from the reassembled key, so pages without
+/// `crypto.subtle` post nothing and surface as a missing report. When the probe itself is
+/// unavailable the page still posts an explicit, proven `unavailable` report.
+pub(crate) fn bound_report_script(report_endpoint: &str, nonce: &str) -> BoundReportScript {
+    let key = report_key(nonce);
+    let (script_key, page_key) = key.split_at(CDP_REPORT_KEY_SCRIPT_HEX_LEN);
+    let script = format!(
+        r#"
+(function() {{
+    var CDP_REPORT_NONCE = '{nonce}';
+    var CDP_REPORT_KEY = '{script_key}';
+    function toHex(buffer) {{
+        return Array.prototype.map.call(new Uint8Array(buffer), function(b) {{
+            return ('0' + b.toString(16)).slice(-2);
+        }}).join('');
+    }}
+    function proveReport(body) {{
+        var pageKey = document.documentElement.getAttribute('{key_attribute}') || '';
+        var verdict = (body.unavailable ? 'unavailable' : (body.cdp_detected ? '1' : '0')) +
+            '|' + Math.round((body.score || 0) * 100) + '|' + body.checks.join(',');
+        var input = CDP_REPORT_KEY + pageKey + ':' + CDP_REPORT_NONCE + ':' +
+            navigator.userAgent + ':' + verdict;
+        return crypto.subtle.digest('SHA-256', new TextEncoder().encode(input)).then(toHex);
+    }}
+    function postReport(body) {{
+        if (!window.crypto || !crypto.subtle) {{
+            return Promise.resolve();
+        }}
+        return proveReport(body).then(function(proof) {{
+            body.nonce = CDP_REPORT_NONCE;
+            body.proof = proof;
+            return fetch('{report_endpoint}', {{
+                method: 'POST',
+                keepalive: true,
+                headers: {{ 'Content-Type': 'application/json' }},
+                body: JSON.stringify(body)
+            }});
+        }});
+    }}
+    window._shumaReportCdp = function() {{
+        if (!window._checkCDPAutomation) {{
+            return postReport({{
+                cdp_detected: false,
+                score: 0,
+                checks: [],
+                unavailable: true
+            }}).catch(function() {{}});
+        }}
+        return window._checkCDPAutomation().then(function(result) {{
+            return postReport({{
+                cdp_detected: !!result.detected,
+                score: result.score || 0,
+                checks: result.checks || []
+            }});
+        }}).catch(function() {{}});
+    }};
+}})();
+"#,
+        nonce = nonce,
+        script_key = script_key,
+        key_attribute = CDP_REPORT_KEY_ATTRIBUTE,
+        report_endpoint = report_endpoint,
+    );
+    BoundReportScript {
+        script,
+        html_attribute: format!("{}=\"{}\"", CDP_REPORT_KEY_ATTRIBUTE, page_key),
+    }
+}
@@ -204,3 +204,302 @@ fn test_cdp_tier_low_when_only_weak_signal_present() {
     };
     assert_eq!(classify_cdp_tier(&report, 0.8), CdpTier::Low);
 }
+
+const BOUND_IP: &str = "203.0.113.40";
+const BOUND_UA: &str = "Mozilla/5.0 Chrome/126.0";
+const ISSUED_AT_MS: u64 = 1_700_000_000_000;
+const CLEAN_VERDICT: &str = "0|0|";
+
+fn issue_bound_nonce() -> (String, String) {
+    let nonce = report_binding::issue_report_nonce(BOUND_IP, BOUND_UA, ISSUED_AT_MS);
+    let proof = report_binding::expected_report_proof(nonce.as_str(), BOUND_UA, CLEAN_VERDICT);
+    (nonce, proof)
+}
+
+#[test]
+fn report_binding_accepts_valid_nonce_and_rejects_replay() {
+    let store = crate::test_support::InMemoryStore::default();
+    let (nonce, proof) = issue_bound_nonce();
+
+    let first = report_binding::validate_report_binding(
+        &store,
+        Some(nonce.as_str()),
+        Some(proof.as_str()),
+        CLEAN_VERDICT,
+        BOUND_IP,
+        BOUND_UA,
+        ISSUED_AT_MS + 2_000,
+    );
+    assert_eq!(first, CdpReportBindingOutcome::Valid);
+
+    let replay = report_binding::validate_report_binding(
+        &store,
+        Some(nonce.as_str()),
+        Some(proof.as_str()),
+        CLEAN_VERDICT,
+        BOUND_IP,
+        BOUND_UA,
+        ISSUED_AT_MS + 3_000,
+    );
+    assert_eq!(replay, CdpReportBindingOutcome::Replayed);
+}
+
+#[test]
+fn report_binding_flags_missing_tampered_and_unbound_reports() {
+    let store = crate::test_support::InMemoryStore::default();
+    let (nonce, proof) = issue_bound_nonce();
+    let now_ms = ISSUED_AT_MS + 2_000;
+
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            None,
+            None,
+            CLEAN_VERDICT,
+            BOUND_IP,
+            BOUND_UA,
+            now_ms
+        ),
+        CdpReportBindingOutcome::Missing
+    );
+
+    let tampered = format!("{}x", nonce);
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(tampered.as_str()),
+            Some(proof.as_str()),
+            CLEAN_VERDICT,
+            BOUND_IP,
+            BOUND_UA,
+            now_ms,
+        ),
+        CdpReportBindingOutcome::Invalid
+    );
+
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(nonce.as_str()),
+            Some(proof.as_str()),
+            CLEAN_VERDICT,
+            "198.51.100.7",
+            BOUND_UA,
+            now_ms,
+        ),
+        CdpReportBindingOutcome::BindingMismatch
+    );
+
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(nonce.as_str()),
+            Some("00000000000000000000000000000000"),
+            CLEAN_VERDICT,
+            BOUND_IP,
+            BOUND_UA,
+            now_ms,
+        ),
+        CdpReportBindingOutcome::ProofMismatch
+    );
+}
+
+#[test]
+fn report_binding_flags_implausibly_fast_and_expired_reports() {
+    let store = crate::test_support::InMemoryStore::default();
+    let (nonce, proof) = issue_bound_nonce();
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(nonce.as_str()),
+            Some(proof.as_str()),
+            CLEAN_VERDICT,
+            BOUND_IP,
+            BOUND_UA,
+            ISSUED_AT_MS + 20,
+        ),
+        CdpReportBindingOutcome::TooFast
+    );
+
+    let (late_nonce, late_proof) = issue_bound_nonce();
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(late_nonce.as_str()),
+            Some(late_proof.as_str()),
+            CLEAN_VERDICT,
+            BOUND_IP,
+            BOUND_UA,
+            ISSUED_AT_MS + 3_600_000,
+        ),
+        CdpReportBindingOutcome::Expired
+    );
+}
+
+#[test]
+fn report_binding_signals_track_anomalies_and_missing_reports() {
+    let store = crate::test_support::InMemoryStore::default();
+    let mut cfg = crate::config::defaults().clone();
+    cfg.cdp_detection_enabled = true;
+    let now = ISSUED_AT_MS / 1000;
+    let signal_active = |signals: &[crate::signals::botness::BotSignal], key: &str| {
+        signals
+            .iter()
+            .find(|signal| signal.key == key)
+            .map(|signal| signal.active)
+            .unwrap_or(false)
+    };
+
+    record_report_expected(&store, BOUND_IP, BOUND_UA, now);
+    let within_grace = report_binding_bot_signals(&store, &cfg, BOUND_IP, BOUND_UA, now + 5);
+    assert!(!signal_active(&within_grace, "cdp_report_missing"));
+
+    let after_grace = report_binding_bot_signals(&store, &cfg, BOUND_IP, BOUND_UA, now + 60);
+    assert!(signal_active(&after_grace, "cdp_report_missing"));
+    assert!(!signal_active(&after_grace, "cdp_report_binding_anomaly"));
+
+    report_binding::record_report_received(&store, BOUND_IP, BOUND_UA);
+    report_binding::record_report_anomaly(
+        &store,
+        BOUND_IP,
+        BOUND_UA,
+        CdpReportBindingOutcome::Replayed,
+        now + 61,
+    );
+    let after_report = report_binding_bot_signals(&store, &cfg, BOUND_IP, BOUND_UA, now + 62);
+    assert!(!signal_active(&after_report, "cdp_report_missing"));
+    assert!(signal_active(&after_report, "cdp_report_binding_anomaly"));
+
+    cfg.cdp_detection_enabled = false;
+    let disabled = report_binding_bot_signals(&store, &cfg, BOUND_IP, BOUND_UA, now + 62);
+    assert!(disabled.iter().all(|signal| !signal.active));
+}
+
+#[test]
+fn report_proof_covers_the_reported_verdict() {
+    let store = crate::test_support::InMemoryStore::default();
+    let (nonce, clean_proof) = issue_bound_nonce();
+    let detected_verdict = report_binding::report_verdict(
+        true,
+        1.9,
+        &["webdriver".to_string(), "cdp_timing".to_string()],
+        false,
+    );
+    assert_eq!(detected_verdict, "1|190|webdriver,cdp_timing");
+    assert_eq!(
+        report_binding::report_verdict(true, 0.0, &[], true),
+        "unavailable|0|"
+    );
+
+    // A proof computed for a clean verdict cannot vouch for any other claim.
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(nonce.as_str()),
+            Some(clean_proof.as_str()),
+            "unavailable|0|",
+            BOUND_IP,
+            BOUND_UA,
+            ISSUED_AT_MS + 2_000,
+        ),
+        CdpReportBindingOutcome::ProofMismatch
+    );
+    let detected_proof =
+        report_binding::expected_report_proof(nonce.as_str(), BOUND_UA, detected_verdict.as_str());
+    assert_eq!(
+        report_binding::validate_report_binding(
+            &store,
+            Some(nonce.as_str()),
+            Some(detected_proof.as_str()),
+            detected_verdict.as_str(),
+            BOUND_IP,
+            BOUND_UA,
+            ISSUED_AT_MS + 2_000,
+        ),
+        CdpReportBindingOutcome::Valid
+    );
+}
+
+#[test]
+fn unavailable_reports_leave_the_expectation_open() {
+    let store = crate::test_support::InMemoryStore::default();
+    let mut cfg = crate::config::defaults().clone();
+    cfg.cdp_detection_enabled = true;
+    let now = crate::admin::now_ts();
+    let missing_active = |at: u64| {
+        report_binding_bot_signals(&store, &cfg, BOUND_IP, BOUND_UA, at)
+            .iter()
+            .any(|signal| signal.key == "cdp_report_missing" && signal.active)
+    };
+
+    record_report_expected(&store, BOUND_IP, BOUND_UA, now);
+    record_report_binding_outcome(
+        &store,
+        BOUND_IP,
+        BOUND_UA,
+        CdpReportBindingOutcome::Valid,
+        true,
+    );
+    assert!(missing_active(now + 60));
+
+    record_report_binding_outcome(
+        &store,
+        BOUND_IP,
+        BOUND_UA,
+        CdpReportBindingOutcome::Valid,
+        false,
+    );
+    assert!(!missing_active(now + 60));
+}
+
+#[test]
+fn bound_report_script_computes_its_proof_in_page() {
+    let bound = get_bound_cdp_report_script("/cdp-report", BOUND_IP, BOUND_UA);
+    let script = bound.script.as_str();
+    assert!(script.contains("window._shumaReportCdp"));
+    assert!(script.contains("fetch('/cdp-report'"));
+    assert!(script.contains("body.nonce = CDP_REPORT_NONCE"));
+    assert!(script.contains("body.proof = proof"));
+    assert!(script.contains("crypto.subtle.digest('SHA-256'"));
+    assert!(script.contains("navigator.userAgent"));
+    assert!(script.contains("getAttribute('data-shuma-cdp')"));
+    assert!(script.contains("unavailable: true"));
+    assert!(!script.contains("if (result.detected)"));
+    assert!(bound.html_attribute.starts_with("data-shuma-cdp=\""));
+}
+
+#[test]
+fn report_key_is_split_between_script_and_page() {
+    let (nonce, proof) = issue_bound_nonce();
+    let bound = report_binding::bound_report_script("/cdp-report", nonce.as_str());
+    assert_eq!(proof.len(), 64);
+    assert!(!bound.script.contains(proof.as_str()));
+
+    let script_key = bound
+        .script
+        .split("var CDP_REPORT_KEY = '")
+        .nth(1)
+        .and_then(|rest| rest.split('\'').next())
+        .expect("script key");
+    let page_key = bound
+        .html_attribute
+        .trim_start_matches("data-shuma-cdp=\"")
+        .trim_end_matches('"');
+    assert_eq!(script_key.len(), 32);
+    assert_eq!(page_key.len(), 32);
+    assert!(!bound.script.contains(page_key));
+
+    // Reassembling both halves the way the page does reproduces the expected proof.
+    let input = format!(
+        "{}{}:{}:{}:{}",
+        script_key, page_key, nonce, BOUND_UA, CLEAN_VERDICT
+    );
+    let in_page = {
+        use sha2::Digest;
+        sha2::Sha256::digest(input.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    };
+    assert_eq!(in_page, proof);
+}
@@ -1,3 +1,4 @@
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use spin_sdk::http::Request;
@@ -13,6 +14,21 @@ const FP_FLOW_VIOLATION_KEY: &str = "fp_flow_violation";
 const FP_PERSISTENCE_MARKER_KEY: &str = "fp_persistence_marker_missing";
 const FP_UNTRUSTED_TRANSPORT_HEADER_KEY: &str = "fp_untrusted_transport_header";
 const FP_AKAMAI_EDGE_ADDITIVE_KEY: &str = "fp_akamai_edge_additive";
+const FP_TRANSPORT_PROFILE_MISMATCH_KEY: &str = "fp_transport_profile_mismatch";
+const FP_HEADER_SHAPE_MISMATCH_KEY: &str = "fp_header_shape_mismatch";
+const FP_HEADER_ORDER_MISMATCH_KEY: &str = "fp_header_order_mismatch";
+
+const PROXY_JA4_HEADER: &str = "x-shuma-proxy-ja4";
+const PROXY_JA4H_HEADER: &str = "x-shuma-proxy-ja4h";
+const PROXY_H2_HEADER: &str = "x-shuma-proxy-h2";
+const PROXY_HEADER_ORDER_HEADER: &str = "x-shuma-proxy-header-order";
+const PROXY_TIMESTAMP_HEADER: &str = "x-shuma-proxy-fp-timestamp";
+const PROXY_SIGNATURE_HEADER: &str = "x-shuma-proxy-fp-signature";
+const PROXY_SIGNATURE_MAX_SKEW_SECONDS: u64 = 60;
+const TRANSPORT_PROFILE_BROWSER_FAMILIES: [&str; 4] = ["chrome", "edge", "firefox", "safari"];
+const TRANSPORT_PROFILES_MAX: usize = 16;
+const TRANSPORT_PROFILE_PATTERNS_MAX: usize = 32;
+const PROXY_HEADER_ORDER_NAMES_MAX: usize = 64;
 
 const FP_KEY_PREFIX_STATE: &str = "fp:state:";
 const FP_KEY_PREFIX_FLOW: &str = "fp:flow:";
@@ -26,6 +42,9 @@ const WEIGHT_FLOW_VIOLATION: u8 = 2;
 const WEIGHT_PERSISTENCE_MARKER_MISSING: u8 = 1;
 const WEIGHT_UNTRUSTED_TRANSPORT_HEADER: u8 = 3;
 const WEIGHT_AKAMAI_EDGE_ADDITIVE: u8 = 2;
+const WEIGHT_TRANSPORT_PROFILE_MISMATCH: u8 = 3;
+const WEIGHT_HEADER_SHAPE_MISMATCH: u8 = 2;
+const WEIGHT_HEADER_ORDER_MISMATCH: u8 = 2;
 const AKAMAI_EDGE_ADDITIVE_CONFIDENCE_MIN: u8 = 7;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,11 +65,41 @@ struct ExternalEdgeSignalState {
 struct TransportEvidence {
     ja3: Option<String>,
     ja4: Option<String>,
+    ja4h: Option<String>,
+    h2: Option<String>,
+    header_order: Option<Vec<String>>,
     edge_browser_family: Option<String>,
     edge_score: Option<f32>,
     untrusted_headers_present: bool,
 }
 
+/// Transport fingerprints forwarded by a local reverse proxy, accepted only when signed.
+#[derive(Debug, Default)]
+struct ProxyFingerprints {
+    ja4: Option<String>,
+    ja4h: Option<String>,
+    h2: Option<String>,
+    header_order: Option<Vec<String>>,
+    rejected: bool,
+}
+
+/// Expected transport fingerprints for one browser family, keyed by the family the user agent
+/// claims. Patterns match exactly, as a prefix when they end in `*`, or as a suffix when they
+/// start with `*`. JA4 patterns ignore the leading transport letter (`t` TCP, `q` QUIC, `d` DTLS)
+/// on both sides, so one pattern covers a browser over HTTP/2 and HTTP/3. An empty pattern list
+/// leaves that fingerprint unchecked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TransportFingerprintProfile {
+    pub browser_family: String,
+    #[serde(default)]
+    pub ja4: Vec<String>,
+    #[serde(default)]
+    pub ja4h: Vec<String>,
+    #[serde(default)]
+    pub h2: Vec<String>,
+}
+
 #[cfg(not(test))]
 fn now_ts() -> u64 {
     SystemTime::now()
@@ -72,7 +121,7 @@ fn header_value(req: &Request, name: &str) -> Option<String> {
         .map(ToOwned::to_owned)
 }
 
-fn normalize_browser_family(raw: &str) -> &'static str {
+pub(crate) fn normalize_browser_family(raw: &str) -> &'static str {
     let lower = raw.to_ascii_lowercase();
     if lower.contains("edg/") || lower.contains("edge") || lower == "edge" {
         "edge"
@@ -157,6 +206,22 @@ fn sanitize_transport_token(value: Option<String>) -> Option<String> {
     Some(trimmed.to_ascii_lowercase())
 }
 
+/// HTTP/2 fingerprints use the Akamai `SETTINGS|WINDOW_UPDATE|PRIORITY|pseudo-headers` form.
+fn sanitize_h2_fingerprint(value: Option<String>) -> Option<String> {
+    let value = value?;
+    let trimmed = value.trim();
+    if trimmed.is_empty() || trimmed.len() > 512 {
+        return None;
+    }
+    if !trimmed
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | ';' | '|' | ',' | '.' | '-' | '_'))
+    {
+        return None;
+    }
+    Some(trimmed.to_ascii_lowercase())
+}
+
 fn parse_score(value: Option<String>) -> Option<f32> {
     let raw = value?;
     let score = raw.trim().parse::<f32>().ok()?;
@@ -167,7 +232,114 @@ fn parse_score(value: Option<String>) -> Option<f32> {
     }
 }
 
+/// Header names as the client sent them, in wire order. HTTP/2 requests list their pseudo-headers
+/// too, which is how HTTP/1.1 casing checks tell the two apart.
+fn sanitize_header_order(value: Option<String>) -> Option<Vec<String>> {
+    let value = value?;
+    let names: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .map(ToOwned::to_owned)
+        .collect();
+    let valid = names.len() <= PROXY_HEADER_ORDER_NAMES_MAX
+        && names.iter().all(|name| {
+            let body = name.strip_prefix(':').unwrap_or(name);
+            !body.is_empty()
+                && body.len() <= 64
+                && body.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    valid.then_some(names)
+}
+
+/// The signed string is `v1`, the timestamp, method, path with query, JA4, JA4H, HTTP/2
+/// fingerprint, and header order, joined by newlines with absent values left empty.
+fn proxy_fingerprint_signing_input(
+    timestamp: &str,
+    method: &str,
+    path_with_query: &str,
+    ja4: &str,
+    ja4h: &str,
+    h2: &str,
+    header_order: &str,
+) -> String {
+    format!(
+        "v1\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        timestamp, method, path_with_query, ja4, ja4h, h2, header_order
+    )
+}
+
+fn extract_proxy_fingerprints(req: &Request, now: u64) -> ProxyFingerprints {
+    let raw_ja4 = header_value(req, PROXY_JA4_HEADER);
+    let raw_ja4h = header_value(req, PROXY_JA4H_HEADER);
+    let raw_h2 = header_value(req, PROXY_H2_HEADER);
+    let raw_header_order = header_value(req, PROXY_HEADER_ORDER_HEADER);
+    if raw_ja4.is_none() && raw_ja4h.is_none() && raw_h2.is_none() && raw_header_order.is_none()
+    {
+        return ProxyFingerprints::default();
+    }
+    let rejected = ProxyFingerprints {
+        rejected: true,
+        ..ProxyFingerprints::default()
+    };
+    let Some(secret) = crate::config::runtime_var_trimmed_optional("SHUMA_FORWARDED_IP_SECRET")
+    else {
+        return rejected;
+    };
+    let (Some(timestamp), Some(signature)) = (
+        header_value(req, PROXY_TIMESTAMP_HEADER),
+        header_value(req, PROXY_SIGNATURE_HEADER),
+    ) else {
+        return rejected;
+    };
+    let fresh = timestamp
+        .parse::<u64>()
+        .is_ok_and(|ts| ts.abs_diff(now) <= PROXY_SIGNATURE_MAX_SKEW_SECONDS);
+    let Some(provided) = signature.strip_prefix("v1=") else {
+        return rejected;
+    };
+    let path_with_query = if req.query().is_empty() {
+        req.path().to_string()
+    } else {
+        format!("{}?{}", req.path(), req.query())
+    };
+    let signing_input = proxy_fingerprint_signing_input(
+        timestamp.as_str(),
+        req.method().to_string().as_str(),
+        path_with_query.as_str(),
+        raw_ja4.as_deref().unwrap_or(""),
+        raw_ja4h.as_deref().unwrap_or(""),
+        raw_h2.as_deref().unwrap_or(""),
+        raw_header_order.as_deref().unwrap_or(""),
+    );
+    let Ok(mut mac) = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()) else {
+        return rejected;
+    };
+    mac.update(signing_input.as_bytes());
+    let expected: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    if !fresh
+        || !crate::admin::auth::constant_time_eq(
+            expected.as_str(),
+            provided.to_ascii_lowercase().as_str(),
+        )
+    {
+        return rejected;
+    }
+    ProxyFingerprints {
+        ja4: sanitize_transport_token(raw_ja4),
+        ja4h: sanitize_transport_token(raw_ja4h),
+        h2: sanitize_h2_fingerprint(raw_h2),
+        header_order: sanitize_header_order(raw_header_order),
+        rejected: false,
+    }
+}
+
 fn extract_transport_evidence(req: &Request, headers_trusted: bool) -> TransportEvidence {
+    let proxy = extract_proxy_fingerprints(req, now_ts());
     let ja3 = sanitize_transport_token(header_value(req, "x-shuma-edge-ja3"));
     let ja4 = sanitize_transport_token(header_value(req, "x-shuma-edge-ja4"));
     let edge_browser_family = sanitize_transport_token(header_value(
@@ -179,24 +351,352 @@ fn extract_transport_evidence(req: &Request, headers_trusted: bool) -> Transport
     if headers_trusted {
         return TransportEvidence {
             ja3,
-            ja4,
+            ja4: ja4.or(proxy.ja4),
+            ja4h: proxy.ja4h,
+            h2: proxy.h2,
+            header_order: proxy.header_order,
             edge_browser_family,
             edge_score,
-            untrusted_headers_present: false,
+            untrusted_headers_present: proxy.rejected,
         };
     }
 
-    let untrusted_headers_present =
-        ja3.is_some() || ja4.is_some() || edge_browser_family.is_some() || edge_score.is_some();
+    let untrusted_headers_present = ja3.is_some()
+        || ja4.is_some()
+        || edge_browser_family.is_some()
+        || edge_score.is_some()
+        || proxy.rejected;
     TransportEvidence {
         ja3: None,
-        ja4: None,
+        ja4: proxy.ja4,
+        ja4h: proxy.ja4h,
+        h2: proxy.h2,
+        header_order: proxy.header_order,
         edge_browser_family: None,
         edge_score: None,
         untrusted_headers_present,
     }
 }
 
+fn fingerprint_pattern_matches(pattern: &str, observed: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        observed.starts_with(prefix)
+    } else if let Some(suffix) = pattern.strip_prefix('*') {
+        observed.ends_with(suffix)
+    } else {
+        observed == pattern
+    }
+}
+
+/// JA4 with its leading transport letter removed. The rest (TLS version, SNI, cipher and extension
+/// counts, ALPN, hashes) is the same whether the handshake ran over TCP or QUIC.
+fn ja4_without_transport(value: &str) -> &str {
+    match value.as_bytes().first() {
+        Some(b't' | b'q' | b'd' | b'T' | b'Q' | b'D') => &value[1..],
+        _ => value,
+    }
+}
+
+fn ja4_pattern_matches(pattern: &str, observed: &str) -> bool {
+    if pattern.starts_with('*') {
+        return fingerprint_pattern_matches(pattern, observed);
+    }
+    fingerprint_pattern_matches(
+        ja4_without_transport(pattern),
+        ja4_without_transport(observed),
+    )
+}
+
+/// True when the claimed browser family has a profile and an observed fingerprint matches none of
+/// that profile's patterns for the same fingerprint kind.
+fn transport_profile_mismatch(
+    ua_family: &str,
+    transport: &TransportEvidence,
+    profiles: &[TransportFingerprintProfile],
+) -> bool {
+    let Some(profile) = profiles
+        .iter()
+        .find(|profile| profile.browser_family == ua_family)
+    else {
+        return false;
+    };
+    let ja4_matches: fn(&str, &str) -> bool = ja4_pattern_matches;
+    let exact_matches: fn(&str, &str) -> bool = fingerprint_pattern_matches;
+    [
+        (transport.ja4.as_deref(), &profile.ja4, ja4_matches),
+        (transport.ja4h.as_deref(), &profile.ja4h, exact_matches),
+        (transport.h2.as_deref(), &profile.h2, exact_matches),
+    ]
+    .into_iter()
+    .any(|(observed, patterns, matches)| match observed {
+        Some(observed) if !patterns.is_empty() => {
+            !patterns.iter().any(|pattern| matches(pattern, observed))
+        }
+        _ => false,
+    })
+}
+
+pub(crate) fn validate_transport_profiles(
+    profiles: &[TransportFingerprintProfile],
+) -> Result<(), String> {
+    if profiles.len() > TRANSPORT_PROFILES_MAX {
+        return Err(format!(
+            "fingerprint_transport_profiles must not contain more than {} entries",
+            TRANSPORT_PROFILES_MAX
+        ));
+    }
+    for (index, profile) in profiles.iter().enumerate() {
+        if !TRANSPORT_PROFILE_BROWSER_FAMILIES.contains(&profile.browser_family.as_str()) {
+            return Err(format!(
+                "fingerprint_transport_profiles[{}].browser_family must be one of {}",
+                index,
+                TRANSPORT_PROFILE_BROWSER_FAMILIES.join(", ")
+            ));
+        }
+        if profiles[..index]
+            .iter()
+            .any(|earlier| earlier.browser_family == profile.browser_family)
+        {
+            return Err(format!(
+                "fingerprint_transport_profiles[{}].browser_family duplicates {}",
+                index, profile.browser_family
+            ));
+        }
+        for (field, patterns) in [
+            ("ja4", &profile.ja4),
+            ("ja4h", &profile.ja4h),
+            ("h2", &profile.h2),
+        ] {
+            if patterns.len() > TRANSPORT_PROFILE_PATTERNS_MAX {
+                return Err(format!(
+                    "fingerprint_transport_profiles[{}].{} must not contain more than {} patterns",
+                    index, field, TRANSPORT_PROFILE_PATTERNS_MAX
+                ));
+            }
+            for pattern in patterns {
+                let body = pattern.trim_start_matches('*').trim_end_matches('*');
+                let sanitized = if field == "h2" {
+                    sanitize_h2_fingerprint(Some(body.to_string()))
+                } else {
+                    sanitize_transport_token(Some(body.to_string()))
+                };
+                if sanitized.is_none() || pattern.starts_with('*') && pattern.ends_with('*') {
+                    return Err(format!(
+                        "fingerprint_transport_profiles[{}].{} pattern '{}' is invalid",
+                        index, field, pattern
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Built-in request shape for one browser family. `secure_required` headers are only sent to
+/// HTTPS origins, and `ordered` lists navigation headers in the relative order that family emits
+/// them; an empty order leaves ordering unchecked.
+struct HeaderShapeProfile {
+    browser_family: &'static str,
+    secure_required: &'static [&'static str],
+    forbidden: &'static [&'static str],
+    ordered: &'static [&'static str],
+}
+
+const HEADER_SHAPE_ALWAYS_REQUIRED: [&str; 2] = ["accept-language", "accept-encoding"];
+const CHROMIUM_SECURE_REQUIRED: [&str; 6] = [
+    "sec-ch-ua",
+    "sec-ch-ua-mobile",
+    "sec-ch-ua-platform",
+    "sec-fetch-site",
+    "sec-fetch-mode",
+    "sec-fetch-dest",
+];
+const CHROMIUM_HEADER_ORDER: [&str; 12] = [
+    "sec-ch-ua",
+    "sec-ch-ua-mobile",
+    "sec-ch-ua-platform",
+    "upgrade-insecure-requests",
+    "user-agent",
+    "accept",
+    "sec-fetch-site",
+    "sec-fetch-mode",
+    "sec-fetch-user",
+    "sec-fetch-dest",
+    "accept-encoding",
+    "accept-language",
+];
+const FIREFOX_HEADER_ORDER: [&str; 9] = [
+    "user-agent",
+    "accept",
+    "accept-language",
+    "accept-encoding",
+    "upgrade-insecure-requests",
+    "sec-fetch-dest",
+    "sec-fetch-mode",
+    "sec-fetch-site",
+    "sec-fetch-user",
+];
+const HEADER_SHAPE_PROFILES: [HeaderShapeProfile; 4] = [
+    HeaderShapeProfile {
+        browser_family: "chrome",
+        secure_required: &CHROMIUM_SECURE_REQUIRED,
+        forbidden: &[],
+        ordered: &CHROMIUM_HEADER_ORDER,
+    },
+    HeaderShapeProfile {
+        browser_family: "edge",
+        secure_required: &CHROMIUM_SECURE_REQUIRED,
+        forbidden: &[],
+        ordered: &CHROMIUM_HEADER_ORDER,
+    },
+    HeaderShapeProfile {
+        browser_family: "firefox",
+        secure_required: &["sec-fetch-site", "sec-fetch-mode", "sec-fetch-dest"],
+        forbidden: &["sec-ch-ua"],
+        ordered: &FIREFOX_HEADER_ORDER,
+    },
+    HeaderShapeProfile {
+        browser_family: "safari",
+        secure_required: &[],
+        forbidden: &["sec-ch-ua"],
+        ordered: &[],
+    },
+];
+const SEC_FETCH_SITE_VALUES: [&str; 4] = ["cross-site", "same-origin", "same-site", "none"];
+const SEC_FETCH_MODE_VALUES: [&str; 5] = ["cors", "navigate", "no-cors", "same-origin", "websocket"];
+const SEC_FETCH_DEST_VALUES: [&str; 23] = [
+    "audio",
+    "audioworklet",
+    "document",
+    "embed",
+    "empty",
+    "font",
+    "frame",
+    "iframe",
+    "image",
+    "json",
+    "manifest",
+    "object",
+    "paintworklet",
+    "report",
+    "script",
+    "serviceworker",
+    "sharedworker",
+    "style",
+    "track",
+    "video",
+    "webidentity",
+    "worker",
+    "xslt",
+];
+
+fn header_shape_profile(ua_family: &str) -> Option<&'static HeaderShapeProfile> {
+    HEADER_SHAPE_PROFILES
+        .iter()
+        .find(|profile| profile.browser_family == ua_family)
+}
+
+fn quoted_client_hint_item(raw: &str) -> Option<&str> {
+    raw.strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .filter(|inner| !inner.is_empty() && !inner.contains('"'))
+}
+
+/// `Sec-CH-UA` is a structured-header list of `"brand";v="version"` items.
+fn client_hint_brand_list_valid(raw: &str) -> bool {
+    raw.split(',').all(|item| {
+        let Some((brand, version)) = item.trim().split_once(";v=") else {
+            return false;
+        };
+        quoted_client_hint_item(brand).is_some()
+            && quoted_client_hint_item(version)
+                .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    })
+}
+
+fn header_values_malformed(req: &Request) -> bool {
+    let outside = |name: &str, allowed: &[&str]| {
+        header_value(req, name).is_some_and(|value| !allowed.contains(&value.as_str()))
+    };
+    if outside("sec-fetch-site", &SEC_FETCH_SITE_VALUES)
+        || outside("sec-fetch-mode", &SEC_FETCH_MODE_VALUES)
+        || outside("sec-fetch-dest", &SEC_FETCH_DEST_VALUES)
+        || outside("sec-fetch-user", &["?1"])
+        || outside("sec-ch-ua-mobile", &["?0", "?1"])
+    {
+        return true;
+    }
+    if header_value(req, "sec-ch-ua").is_some_and(|value| !client_hint_brand_list_valid(&value)) {
+        return true;
+    }
+    let document_navigation = header_value(req, "sec-fetch-mode").as_deref() == Some("navigate")
+        && header_value(req, "sec-fetch-dest").as_deref() == Some("document");
+    document_navigation
+        && !header_value(req, "accept").is_some_and(|accept| accept.contains("text/html"))
+}
+
+/// True when a request claiming a known browser family is missing headers that browser always
+/// sends, carries headers it never sends, or formats `Sec-Fetch-*`/`Sec-CH-UA`/`Accept` values in
+/// a way no browser does.
+fn header_shape_mismatch(req: &Request, ua_family: &str) -> bool {
+    let Some(profile) = header_shape_profile(ua_family) else {
+        return false;
+    };
+    let missing = |names: &[&str]| names.iter().any(|name| header_value(req, name).is_none());
+    missing(&HEADER_SHAPE_ALWAYS_REQUIRED)
+        || (crate::request_is_https(req) && missing(profile.secure_required))
+        || profile
+            .forbidden
+            .iter()
+            .any(|name| header_value(req, name).is_some())
+        || header_values_malformed(req)
+}
+
+fn request_is_navigation(req: &Request) -> bool {
+    match header_value(req, "sec-fetch-mode") {
+        Some(mode) => mode == "navigate",
+        None => header_value(req, "accept").is_some_and(|accept| accept.contains("text/html")),
+    }
+}
+
+/// Ordering and casing need the wire header list, which only a signing proxy can supply: Spin
+/// hands the component a lowercased header map, so without it the check is unavailable (`None`).
+/// HTTP/1.1 browsers send `Host` and `User-Agent` title-cased; HTTP/2 lists are lowercase by
+/// protocol, so only the relative order of navigation headers is compared there.
+fn header_order_mismatch(
+    req: &Request,
+    ua_family: &str,
+    header_order: Option<&[String]>,
+) -> Option<bool> {
+    let names = header_order?;
+    let Some(profile) = header_shape_profile(ua_family) else {
+        return Some(false);
+    };
+    let http2 = names.iter().any(|name| name.starts_with(':'));
+    let miscased = |canonical: &str| {
+        names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(canonical) && name != canonical)
+    };
+    if !http2 && (miscased("Host") || miscased("User-Agent")) {
+        return Some(true);
+    }
+    if !request_is_navigation(req) {
+        return Some(false);
+    }
+    let positions: Vec<usize> = names
+        .iter()
+        .filter_map(|name| {
+            profile
+                .ordered
+                .iter()
+                .position(|expected| name.eq_ignore_ascii_case(expected))
+        })
+        .collect();
+    Some(positions.windows(2).any(|pair| pair[0] > pair[1]))
+}
+
 fn fingerprint_secret() -> String {
     crate::config::runtime_var_trimmed_optional("SHUMA_JS_SECRET")
         .unwrap_or_else(|| "shuma-fingerprint-default-secret".to_string())
@@ -435,13 +935,23 @@ fn ua_transport_family_mismatch(ua_family: &str, transport: &TransportEvidence)
     edge_family != "other" && ua_family != "other" && edge_family != ua_family
 }
 
-fn fingerprint_signal_catalog() -> [(&'static str, &'static str, SignalFamily); 7] {
+fn fingerprint_signal_catalog() -> [(&'static str, &'static str, SignalFamily); 10] {
     [
         (
             FP_UA_CH_MISMATCH_KEY,
             "UA and client-hint mismatch",
             SignalFamily::FingerprintHeaderRuntime,
         ),
+        (
+            FP_HEADER_SHAPE_MISMATCH_KEY,
+            "Header presence/format mismatch for claimed browser",
+            SignalFamily::FingerprintHeaderRuntime,
+        ),
+        (
+            FP_HEADER_ORDER_MISMATCH_KEY,
+            "Header order/casing mismatch for claimed browser",
+            SignalFamily::FingerprintHeaderRuntime,
+        ),
         (
             FP_UA_TRANSPORT_MISMATCH_KEY,
             "UA and transport/browser mismatch",
@@ -472,6 +982,11 @@ fn fingerprint_signal_catalog() -> [(&'static str, &'static str, SignalFamily);
             "Akamai edge bot signal (additive)",
             SignalFamily::FingerprintTransport,
         ),
+        (
+            FP_TRANSPORT_PROFILE_MISMATCH_KEY,
+            "UA and transport fingerprint profile mismatch",
+            SignalFamily::FingerprintTransport,
+        ),
     ]
 }
 
@@ -505,7 +1020,12 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
     let ua_family = extract_ua_family(req);
     let transport = extract_transport_evidence(req, headers_trusted);
     let ua_ch_mismatch = detect_ua_client_hint_mismatch(req);
+    let header_shape_mismatch = header_shape_mismatch(req, ua_family);
+    let header_order_mismatch =
+        header_order_mismatch(req, ua_family, transport.header_order.as_deref());
     let ua_transport_mismatch = ua_transport_family_mismatch(ua_family, &transport);
+    let transport_profile_mismatch =
+        transport_profile_mismatch(ua_family, &transport, &cfg.fingerprint_transport_profiles);
     let now = now_ts();
     let edge_additive_state = if cfg.provider_backends.fingerprint_signal
         == crate::config::ProviderBackend::External
@@ -540,8 +1060,13 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
         ja4_hash.as_deref(),
     );
 
-    let mismatch_observed =
-        ua_ch_mismatch || ua_transport_mismatch || temporal_transition || transport.untrusted_headers_present;
+    let mismatch_observed = ua_ch_mismatch
+        || header_shape_mismatch
+        || header_order_mismatch == Some(true)
+        || ua_transport_mismatch
+        || transport_profile_mismatch
+        || temporal_transition
+        || transport.untrusted_headers_present;
     let mismatch_count = update_flow_mismatch_count(
         store,
         identity.as_str(),
@@ -568,9 +1093,18 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
     if ua_ch_mismatch {
         increment_counter(store, "fingerprint:ua_ch_mismatch");
     }
+    if header_shape_mismatch {
+        increment_counter(store, "fingerprint:header_shape_mismatch");
+    }
+    if header_order_mismatch == Some(true) {
+        increment_counter(store, "fingerprint:header_order_mismatch");
+    }
     if ua_transport_mismatch {
         increment_counter(store, "fingerprint:ua_transport_mismatch");
     }
+    if transport_profile_mismatch {
+        increment_counter(store, "fingerprint:transport_profile_mismatch");
+    }
     if temporal_transition {
         increment_counter(store, "fingerprint:temporal_transition");
     }
@@ -594,7 +1128,7 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
     } else {
         7
     };
-    let mut signals = Vec::with_capacity(7);
+    let mut signals = Vec::with_capacity(10);
     signals.push(BotSignal::scored_with_metadata(
         FP_UA_CH_MISMATCH_KEY,
         "UA and client-hint mismatch",
@@ -604,6 +1138,33 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
         8,
         SignalFamily::FingerprintHeaderRuntime,
     ));
+    signals.push(BotSignal::scored_with_metadata(
+        FP_HEADER_SHAPE_MISMATCH_KEY,
+        "Header presence/format mismatch for claimed browser",
+        header_shape_mismatch,
+        WEIGHT_HEADER_SHAPE_MISMATCH,
+        SignalProvenance::Internal,
+        7,
+        SignalFamily::FingerprintHeaderRuntime,
+    ));
+    signals.push(match header_order_mismatch {
+        Some(active) => BotSignal::scored_with_metadata(
+            FP_HEADER_ORDER_MISMATCH_KEY,
+            "Header order/casing mismatch for claimed browser",
+            active,
+            WEIGHT_HEADER_ORDER_MISMATCH,
+            SignalProvenance::Derived,
+            7,
+            SignalFamily::FingerprintHeaderRuntime,
+        ),
+        None => BotSignal::unavailable_with_metadata(
+            FP_HEADER_ORDER_MISMATCH_KEY,
+            "Header order/casing mismatch for claimed browser",
+            SignalProvenance::Derived,
+            10,
+            SignalFamily::FingerprintHeaderRuntime,
+        ),
+    });
     signals.push(BotSignal::scored_with_metadata(
         FP_UA_TRANSPORT_MISMATCH_KEY,
         "UA and transport/browser mismatch",
@@ -613,6 +1174,15 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
         edge_confidence,
         SignalFamily::FingerprintTransport,
     ));
+    signals.push(BotSignal::scored_with_metadata(
+        FP_TRANSPORT_PROFILE_MISMATCH_KEY,
+        "UA and transport fingerprint profile mismatch",
+        transport_profile_mismatch,
+        WEIGHT_TRANSPORT_PROFILE_MISMATCH,
+        SignalProvenance::Derived,
+        8,
+        SignalFamily::FingerprintTransport,
+    ));
     signals.push(BotSignal::scored_with_metadata(
         FP_TEMPORAL_TRANSITION_KEY,
         "Impossible short-window fingerprint transition",
@@ -676,7 +1246,9 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
     }
 
     // Keep transport signal availability explicit when trusted transport ingestion is unavailable.
-    if !headers_trusted && !transport.untrusted_headers_present {
+    let proxy_transport_present =
+        transport.ja4.is_some() || transport.ja4h.is_some() || transport.h2.is_some();
+    if !headers_trusted && !transport.untrusted_headers_present && !proxy_transport_present {
         signals.push(BotSignal::unavailable_with_metadata(
             "fp_transport_signal_unavailable",
             "Transport fingerprint headers unavailable",
@@ -693,12 +1265,16 @@ pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore>(
 mod tests {
     use crate::challenge::KeyValueStore;
     use super::{
-        collect_bot_signals, flow_identity, now_ts, record_akamai_edge_signal,
-        record_external_payload_rejection, FingerprintState, FP_AKAMAI_EDGE_ADDITIVE_KEY,
-        FP_FLOW_VIOLATION_KEY, FP_KEY_PREFIX_STATE, FP_TEMPORAL_TRANSITION_KEY,
-        FP_UA_CH_MISMATCH_KEY, FP_UA_TRANSPORT_MISMATCH_KEY,
-        FP_UNTRUSTED_TRANSPORT_HEADER_KEY,
+        collect_bot_signals, flow_identity, now_ts, proxy_fingerprint_signing_input,
+        record_akamai_edge_signal, record_external_payload_rejection,
+        transport_profile_mismatch, validate_transport_profiles, FingerprintState,
+        TransportEvidence, TransportFingerprintProfile, FP_AKAMAI_EDGE_ADDITIVE_KEY, FP_FLOW_VIOLATION_KEY, FP_HEADER_ORDER_MISMATCH_KEY,
+        FP_HEADER_SHAPE_MISMATCH_KEY, FP_KEY_PREFIX_STATE,
+        FP_TEMPORAL_TRANSITION_KEY, FP_TRANSPORT_PROFILE_MISMATCH_KEY, FP_UA_CH_MISMATCH_KEY,
+        FP_UA_TRANSPORT_MISMATCH_KEY, FP_UNTRUSTED_TRANSPORT_HEADER_KEY,
     };
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
     use spin_sdk::http::Request;
     use std::collections::HashMap;
     use std::sync::Mutex;
@@ -744,7 +1320,9 @@ mod tests {
             .unwrap_or(false)
     }
 
-    use crate::signals::botness::BotSignal;
+    use crate::signals::botness::{
+        BotSignal, SignalAccumulator, SignalAvailability, SignalBudgetPolicy, SignalFamily,
+    };
 
     #[test]
     fn detects_ua_client_hint_mismatch() {
@@ -797,6 +1375,282 @@ mod tests {
         assert!(signal_active(&signals, FP_UNTRUSTED_TRANSPORT_HEADER_KEY));
     }
 
+    fn proxy_signature(
+        timestamp: &str,
+        ja4: &str,
+        h2: &str,
+        header_order: &str,
+        secret: &str,
+    ) -> String {
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()).expect("hmac key");
+        mac.update(
+            proxy_fingerprint_signing_input(
+                timestamp,
+                "GET",
+                "/catalog?page=2",
+                ja4,
+                "",
+                h2,
+                header_order,
+            )
+            .as_bytes(),
+        );
+        let signature: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("v1={}", signature)
+    }
+
+    fn signed_proxy_request(user_agent: &str, h2: &str, timestamp: u64, secret: &str) -> Request {
+        let ja4 = "t13d1516h2_8daaf6152771_02713d6af862";
+        let timestamp = timestamp.to_string();
+        let signature = proxy_signature(timestamp.as_str(), ja4, h2, "", secret);
+        request(
+            "/catalog?page=2",
+            &[
+                ("user-agent", user_agent),
+                ("x-shuma-proxy-ja4", ja4),
+                ("x-shuma-proxy-h2", h2),
+                ("x-shuma-proxy-fp-timestamp", timestamp.as_str()),
+                ("x-shuma-proxy-fp-signature", signature.as_str()),
+            ],
+        )
+    }
+
+    #[test]
+    fn signed_proxy_fingerprints_are_checked_against_browser_profiles() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "proxy-secret");
+        let store = MockStore::default();
+        let cfg = crate::config::defaults().clone();
+        let chrome = "Mozilla/5.0 (X11; Linux x86_64) Chrome/126.0 Safari/537.36";
+        let chrome_h2 = "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p";
+        let firefox_h2 = "1:65536;2:0;4:131072;5:16384|12517377|0|m,p,a,s";
+
+        let req = signed_proxy_request(chrome, chrome_h2, now_ts(), "proxy-secret");
+        let signals = collect_bot_signals(&store, &req, &cfg, "203.0.113.21", false);
+        assert!(!signal_active(&signals, FP_TRANSPORT_PROFILE_MISMATCH_KEY));
+        assert!(!signal_active(&signals, FP_UNTRUSTED_TRANSPORT_HEADER_KEY));
+        assert!(!signals
+            .iter()
+            .any(|signal| signal.key == "fp_transport_signal_unavailable"));
+
+        let req = signed_proxy_request(chrome, firefox_h2, now_ts(), "proxy-secret");
+        let signals = collect_bot_signals(&store, &req, &cfg, "203.0.113.22", false);
+        assert!(signal_active(&signals, FP_TRANSPORT_PROFILE_MISMATCH_KEY));
+
+        let req = signed_proxy_request(chrome, firefox_h2, now_ts() - 600, "proxy-secret");
+        let signals = collect_bot_signals(&store, &req, &cfg, "203.0.113.23", false);
+        assert!(!signal_active(&signals, FP_TRANSPORT_PROFILE_MISMATCH_KEY));
+        assert!(signal_active(&signals, FP_UNTRUSTED_TRANSPORT_HEADER_KEY));
+
+        let req = signed_proxy_request(chrome, firefox_h2, now_ts(), "wrong-secret");
+        let signals = collect_bot_signals(&store, &req, &cfg, "203.0.113.24", false);
+        assert!(!signal_active(&signals, FP_TRANSPORT_PROFILE_MISMATCH_KEY));
+        assert!(signal_active(&signals, FP_UNTRUSTED_TRANSPORT_HEADER_KEY));
+        std::env::remove_var("SHUMA_FORWARDED_IP_SECRET");
+    }
+
+    const CHROME_UA: &str = "Mozilla/5.0 (X11; Linux x86_64) Chrome/126.0 Safari/537.36";
+    const CHROME_NAVIGATION_HEADERS: [(&str, &str); 10] = [
+        ("sec-ch-ua", "\"Chromium\";v=\"126\", \"Not)A;Brand\";v=\"8\""),
+        ("sec-ch-ua-mobile", "?0"),
+        ("sec-ch-ua-platform", "\"Linux\""),
+        ("user-agent", CHROME_UA),
+        ("accept", "text/html,application/xhtml+xml,*/*;q=0.8"),
+        ("sec-fetch-site", "none"),
+        ("sec-fetch-mode", "navigate"),
+        ("sec-fetch-dest", "document"),
+        ("accept-encoding", "gzip, deflate, br, zstd"),
+        ("accept-language", "en-GB,en;q=0.9"),
+    ];
+
+    fn chrome_navigation(uri: &str, overrides: &[(&str, &str)], omit: &str) -> Request {
+        let mut headers: Vec<(&str, &str)> = CHROME_NAVIGATION_HEADERS
+            .iter()
+            .copied()
+            .filter(|(name, _)| *name != omit)
+            .filter(|(name, _)| !overrides.iter().any(|(other, _)| other == name))
+            .collect();
+        headers.extend_from_slice(overrides);
+        request(uri, &headers)
+    }
+
+    #[test]
+    fn header_shape_checks_presence_sets_and_value_formats() {
+        let store = MockStore::default();
+        let cfg = crate::config::defaults().clone();
+        let shape_active = |req: &Request| {
+            let signals = collect_bot_signals(&store, req, &cfg, "203.0.113.31", false);
+            signal_active(&signals, FP_HEADER_SHAPE_MISMATCH_KEY)
+        };
+
+        assert!(!shape_active(&chrome_navigation("https://example.com/", &[], "")));
+        assert!(!shape_active(&chrome_navigation("/", &[], "sec-ch-ua")));
+        assert!(shape_active(&chrome_navigation("https://example.com/", &[], "sec-ch-ua")));
+        assert!(shape_active(&chrome_navigation("/", &[], "accept-language")));
+        assert!(shape_active(&chrome_navigation("/", &[("sec-fetch-mode", "browse")], "")));
+        assert!(shape_active(&chrome_navigation("/", &[("accept", "*/*")], "")));
+        assert!(shape_active(&chrome_navigation(
+            "/",
+            &[("sec-ch-ua", "Chromium;v=126")],
+            ""
+        )));
+        assert!(shape_active(&chrome_navigation(
+            "/",
+            &[("user-agent", "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Firefox/128.0")],
+            ""
+        )));
+
+        let signals = collect_bot_signals(
+            &store,
+            &chrome_navigation("/", &[], ""),
+            &cfg,
+            "203.0.113.32",
+            false,
+        );
+        let order = signals
+            .iter()
+            .find(|signal| signal.key == FP_HEADER_ORDER_MISMATCH_KEY)
+            .expect("order signal listed");
+        assert_eq!(order.availability, SignalAvailability::Unavailable);
+    }
+
+    #[test]
+    fn signed_header_order_is_checked_and_shares_the_header_runtime_cap() {
+        let _lock = crate::test_support::lock_env();
+        std::env::set_var("SHUMA_FORWARDED_IP_SECRET", "proxy-secret");
+        let store = MockStore::default();
+        let cfg = crate::config::defaults().clone();
+        let timestamp = now_ts().to_string();
+        let signed_order = |header_order: &str, overrides: &[(&str, &str)]| {
+            let signature =
+                proxy_signature(timestamp.as_str(), "", "", header_order, "proxy-secret");
+            let mut headers = vec![
+                ("x-shuma-proxy-header-order", header_order),
+                ("x-shuma-proxy-fp-timestamp", timestamp.as_str()),
+                ("x-shuma-proxy-fp-signature", signature.as_str()),
+            ];
+            headers.extend_from_slice(overrides);
+            let req = chrome_navigation("/catalog?page=2", &headers, "");
+            collect_bot_signals(&store, &req, &cfg, "203.0.113.33", false)
+        };
+
+        let browser_order = ":method,:authority,:scheme,:path,sec-ch-ua,sec-ch-ua-mobile,\
+            sec-ch-ua-platform,upgrade-insecure-requests,user-agent,accept,sec-fetch-site,\
+            sec-fetch-mode,sec-fetch-user,sec-fetch-dest,accept-encoding,accept-language";
+        let signals = signed_order(browser_order, &[]);
+        let order = signals
+            .iter()
+            .find(|signal| signal.key == FP_HEADER_ORDER_MISMATCH_KEY)
+            .expect("order signal listed");
+        assert_eq!(order.availability, SignalAvailability::Active);
+        assert!(!order.active);
+
+        let library_order = "Host,user-agent,Accept-Encoding,Accept,Connection,sec-ch-ua";
+        assert!(signal_active(
+            &signed_order(library_order, &[]),
+            FP_HEADER_ORDER_MISMATCH_KEY
+        ));
+        let reordered = ":method,:authority,:scheme,:path,user-agent,accept,accept-language,\
+            accept-encoding,sec-ch-ua,sec-fetch-site,sec-fetch-mode,sec-fetch-dest";
+        let signals = signed_order(reordered, &[("accept", "*/*")]);
+        assert!(signal_active(&signals, FP_HEADER_ORDER_MISMATCH_KEY));
+        assert!(signal_active(&signals, FP_HEADER_SHAPE_MISMATCH_KEY));
+
+        let mut accumulator = SignalAccumulator::with_capacity_and_policy(
+            signals.len(),
+            SignalBudgetPolicy {
+                fingerprint_total_cap: cfg.fingerprint_entropy_budget,
+                fingerprint_header_runtime_cap: cfg.fingerprint_family_cap_header_runtime,
+                fingerprint_transport_cap: cfg.fingerprint_family_cap_transport,
+                fingerprint_temporal_cap: cfg.fingerprint_family_cap_temporal,
+                fingerprint_persistence_cap: cfg.fingerprint_family_cap_persistence,
+                fingerprint_behavior_cap: cfg.fingerprint_family_cap_behavior,
+            },
+        );
+        for signal in signals {
+            accumulator.push(signal);
+        }
+        let (_, scored) = accumulator.finish();
+        let header_runtime_total: u8 = scored
+            .iter()
+            .filter(|signal| signal.family == SignalFamily::FingerprintHeaderRuntime)
+            .map(|signal| signal.contribution)
+            .sum();
+        assert_eq!(header_runtime_total, cfg.fingerprint_family_cap_header_runtime);
+        std::env::remove_var("SHUMA_FORWARDED_IP_SECRET");
+    }
+
+    #[test]
+    fn default_transport_profiles_accept_http3_and_cipher_count_drift() {
+        let profiles = &crate::config::defaults().fingerprint_transport_profiles;
+        let ja4 = |value: &str| TransportEvidence {
+            ja4: Some(value.to_string()),
+            ..TransportEvidence::default()
+        };
+
+        // Chrome over HTTP/3 offers only the three TLS 1.3 suites and carries no h2 fingerprint.
+        let chrome_http3 = ja4("q13d0312h3_55b375c5d22e_06cda9e17597");
+        assert!(!transport_profile_mismatch("chrome", &chrome_http3, profiles));
+        assert!(!transport_profile_mismatch("edge", &chrome_http3, profiles));
+        assert!(!transport_profile_mismatch(
+            "chrome",
+            &ja4("t13d1617h2_8daaf6152771_02713d6af862"),
+            profiles
+        ));
+        assert!(!transport_profile_mismatch(
+            "firefox",
+            &ja4("t13d1815h2_5b57614c22b0_3cbfd9057e0d"),
+            profiles
+        ));
+        assert!(!transport_profile_mismatch(
+            "safari",
+            &ja4("q13d0310h3_55b375c5d22e_c7c3f28fcd2b"),
+            profiles
+        ));
+
+        // A TLS 1.2 client, or one without SNI, still does not pass for a modern browser.
+        assert!(transport_profile_mismatch(
+            "chrome",
+            &ja4("t12d1209h2_d34a8e72043a_b39be8c56a14"),
+            profiles
+        ));
+        assert!(transport_profile_mismatch(
+            "chrome",
+            &ja4("q13i0310h3_55b375c5d22e_06cda9e17597"),
+            profiles
+        ));
+    }
+
+    #[test]
+    fn transport_profile_validation_rejects_unknown_families_and_bad_patterns() {
+        let defaults = &crate::config::defaults().fingerprint_transport_profiles;
+        assert!(validate_transport_profiles(defaults).is_ok());
+        let profile = |family: &str, h2: &str| TransportFingerprintProfile {
+            browser_family: family.to_string(),
+            ja4: Vec::new(),
+            ja4h: Vec::new(),
+            h2: vec![h2.to_string()],
+        };
+        assert!(validate_transport_profiles(&[profile("opera", "*|m,a,s,p")])
+            .expect_err("unknown family")
+            .contains("browser_family"));
+        assert!(validate_transport_profiles(&[profile("chrome", "*m a s p*")])
+            .expect_err("bad pattern")
+            .contains("h2 pattern"));
+        assert!(validate_transport_profiles(&[
+            profile("chrome", "*|m,a,s,p"),
+            profile("chrome", "*|m,a,s,p"),
+        ])
+        .expect_err("duplicate family")
+        .contains("duplicates chrome"));
+    }
+
     #[test]
     fn detects_temporal_impossible_transition_in_same_window() {
         let store = MockStore::default();
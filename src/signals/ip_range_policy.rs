@@ -1,4 +1,6 @@
-use crate::config::{Config, IpRangePolicyAction, IpRangePolicyMode, IpRangePolicyRule};
+use crate::config::{
+    Config, IpRangePolicyAction, IpRangePolicyMode, IpRangePolicyRule, PolicyExecutionMode,
+};
 use ipnet::IpNet;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
@@ -39,6 +41,7 @@ struct CompiledRule {
     action: IpRangePolicyAction,
     redirect_url: Option<String>,
     custom_message: Option<String>,
+    execution_mode: PolicyExecutionMode,
     nets: Vec<IpNet>,
 }
 
@@ -60,6 +63,7 @@ fn policy_cache_key(cfg: &Config) -> u64 {
     if let Ok(bytes) = serde_json::to_vec(&cfg.ip_range_custom_rules) {
         bytes.hash(&mut hasher);
     }
+    cfg.shadow_policy_sources.hash(&mut hasher);
     hasher.finish()
 }
 
@@ -151,7 +155,7 @@ fn normalize_rule_id(raw: &str, fallback_index: usize) -> String {
     }
 }
 
-fn compile_custom_rule(rule: &IpRangePolicyRule, index: usize) -> Option<CompiledRule> {
+fn compile_custom_rule(cfg: &Config, rule: &IpRangePolicyRule, index: usize) -> Option<CompiledRule> {
     if !rule.enabled {
         return None;
     }
@@ -159,12 +163,18 @@ fn compile_custom_rule(rule: &IpRangePolicyRule, index: usize) -> Option<Compile
     if nets.is_empty() {
         return None;
     }
+    let source_id = normalize_rule_id(rule.id.as_str(), index + 1);
+    let execution_mode = cfg.policy_source_execution_mode(
+        format!("ip_range:{}", source_id).as_str(),
+        rule.execution_mode,
+    );
     Some(CompiledRule {
         source: MatchSource::CustomRule,
-        source_id: normalize_rule_id(rule.id.as_str(), index + 1),
+        source_id,
         action: rule.action,
         redirect_url: sanitize_redirect_url(rule.redirect_url.as_deref()),
         custom_message: sanitize_custom_message(rule.custom_message.as_deref()),
+        execution_mode,
         nets,
     })
 }
@@ -175,7 +185,7 @@ fn compile_policy(cfg: &Config) -> CompiledPolicy {
         .ip_range_custom_rules
         .iter()
         .enumerate()
-        .filter_map(|(index, rule)| compile_custom_rule(rule, index))
+        .filter_map(|(index, rule)| compile_custom_rule(cfg, rule, index))
         .collect::<Vec<_>>();
     CompiledPolicy {
         emergency_allowlist,
@@ -219,39 +229,61 @@ fn match_rule(rule: &CompiledRule, ip: IpAddr) -> Option<MatchDetails> {
 }
 
 fn evaluate_with_now(cfg: &Config, ip: &str, _now_unix: u64) -> Evaluation {
+    evaluate_with_shadow_matches(cfg, ip).0
+}
+
+/// Shadowed rules never produce the returned `Evaluation`; instead every shadowed rule that
+/// matches ahead of the first enforced match is reported alongside it.
+fn evaluate_with_shadow_matches(cfg: &Config, ip: &str) -> (Evaluation, Vec<MatchDetails>) {
     if cfg.ip_range_policy_mode == IpRangePolicyMode::Off {
-        return Evaluation::NoMatch;
+        return (Evaluation::NoMatch, Vec::new());
     }
     let Ok(ip_addr) = ip.parse::<IpAddr>() else {
-        return Evaluation::NoMatch;
+        return (Evaluation::NoMatch, Vec::new());
     };
     let compiled = compiled_policy_for(cfg);
 
     for net in &compiled.emergency_allowlist {
         if net.contains(&ip_addr) {
-            return Evaluation::EmergencyAllowlisted {
-                matched_cidr: net.to_string(),
-            };
+            return (
+                Evaluation::EmergencyAllowlisted {
+                    matched_cidr: net.to_string(),
+                },
+                Vec::new(),
+            );
         }
     }
 
+    let mut shadow_matches = Vec::new();
     for rule in &compiled.custom_rules {
-        if let Some(matched) = match_rule(rule, ip_addr) {
-            return Evaluation::Matched(matched);
+        let Some(matched) = match_rule(rule, ip_addr) else {
+            continue;
+        };
+        if rule.execution_mode == PolicyExecutionMode::Shadow {
+            shadow_matches.push(matched);
+            continue;
         }
+        return (Evaluation::Matched(matched), shadow_matches);
     }
 
-    Evaluation::NoMatch
+    (Evaluation::NoMatch, shadow_matches)
 }
 
 pub(crate) fn evaluate(cfg: &Config, ip: &str) -> Evaluation {
     evaluate_with_now(cfg, ip, 0)
 }
 
+/// Shadow-mode rules that would have matched this IP ahead of any enforced rule.
+pub(crate) fn shadow_matches(cfg: &Config, ip: &str) -> Vec<MatchDetails> {
+    evaluate_with_shadow_matches(cfg, ip).1
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{evaluate, evaluate_with_now, Evaluation, MatchSource};
-    use crate::config::{defaults, IpRangePolicyAction, IpRangePolicyMode, IpRangePolicyRule};
+    use super::{evaluate, evaluate_with_now, shadow_matches, Evaluation, MatchSource};
+    use crate::config::{
+        defaults, IpRangePolicyAction, IpRangePolicyMode, IpRangePolicyRule, PolicyExecutionMode,
+    };
 
     #[test]
     fn emergency_allowlist_short_circuits_matches() {
@@ -265,6 +297,7 @@ mod tests {
             action: IpRangePolicyAction::Forbidden403,
             redirect_url: None,
             custom_message: None,
+            execution_mode: PolicyExecutionMode::Enforced,
         }];
 
         let result = evaluate(&cfg, "203.0.113.9");
@@ -287,6 +320,7 @@ mod tests {
             action: IpRangePolicyAction::Maze,
             redirect_url: None,
             custom_message: None,
+            execution_mode: PolicyExecutionMode::Enforced,
         }];
 
         let result = evaluate(&cfg, "198.51.100.10");
@@ -310,11 +344,46 @@ mod tests {
             action: IpRangePolicyAction::Forbidden403,
             redirect_url: None,
             custom_message: None,
+            execution_mode: PolicyExecutionMode::Enforced,
         }];
 
         assert_eq!(evaluate_with_now(&cfg, "198.51.100.10", 123), Evaluation::NoMatch);
     }
 
+    #[test]
+    fn shadow_rules_are_reported_without_hiding_later_enforced_rules() {
+        let rule = |id: &str, cidr: &str, execution_mode| IpRangePolicyRule {
+            id: id.to_string(),
+            enabled: true,
+            cidrs: vec![cidr.to_string()],
+            action: IpRangePolicyAction::Forbidden403,
+            redirect_url: None,
+            custom_message: None,
+            execution_mode,
+        };
+        let mut cfg = defaults().clone();
+        cfg.ip_range_policy_mode = IpRangePolicyMode::Enforce;
+        cfg.ip_range_custom_rules = vec![
+            rule("trial_wide", "198.51.0.0/16", PolicyExecutionMode::Shadow),
+            rule("known_bad", "198.51.100.0/24", PolicyExecutionMode::Enforced),
+        ];
+
+        let Evaluation::Matched(details) = evaluate(&cfg, "198.51.100.10") else {
+            panic!("expected enforced match");
+        };
+        assert_eq!(details.source_id, "known_bad");
+        let shadowed = shadow_matches(&cfg, "198.51.100.10");
+        assert_eq!(shadowed.len(), 1);
+        assert_eq!(shadowed[0].source_id, "trial_wide");
+
+        assert_eq!(evaluate(&cfg, "198.51.7.1"), Evaluation::NoMatch);
+        assert_eq!(shadow_matches(&cfg, "198.51.7.1")[0].source_id, "trial_wide");
+
+        cfg.shadow_policy_sources = vec!["ip_range:known_bad".to_string()];
+        assert_eq!(evaluate(&cfg, "198.51.100.10"), Evaluation::NoMatch);
+        assert_eq!(shadow_matches(&cfg, "198.51.100.10").len(), 2);
+    }
+
     #[test]
     fn invalid_ip_or_mode_off_returns_no_match() {
         let mut cfg = defaults().clone();
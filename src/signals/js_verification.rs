@@ -33,6 +33,8 @@ use spin_sdk::http::{Request, Response};
 use spin_sdk::key_value::Store;
 
 const VERIFYING_VISIBILITY_DELAY_MS: u64 = 200;
+/// Upper bound on how long the non-PoW interstitial waits for the CDP report before reloading.
+const CDP_REPORT_MAX_WAIT_MS: u64 = 1500;
 
 /// Secret used for HMAC token generation for JS verification cookies.
 /// Pull from env to avoid a repo-known static secret in production.
@@ -116,11 +118,18 @@ pub fn inject_js_challenge(
     cdp_probe_family: crate::config::CdpProbeFamily,
     cdp_probe_rollout_percent: u8,
 ) -> Response {
-    let cdp_script = crate::signals::cdp::get_cdp_detection_script_for_request(
-        cdp_probe_family,
-        cdp_probe_rollout_percent,
-        ip,
+    let cdp_report =
+        crate::signals::cdp::get_bound_cdp_report_script(report_endpoint, ip, user_agent);
+    let cdp_script = format!(
+        "{}\n{}",
+        crate::signals::cdp::get_cdp_detection_script_for_request(
+            cdp_probe_family,
+            cdp_probe_rollout_percent,
+            ip,
+        ),
+        cdp_report.script
     );
+    let cdp_html_attribute = cdp_report.html_attribute;
 
     if pow_enabled {
         let challenge = crate::challenge::pow::issue_pow_challenge(
@@ -131,24 +140,10 @@ pub fn inject_js_challenge(
         );
         let html = format!(
             r#"
-        <html><head><script>{cdp_script}</script></head><body>
+        <html {cdp_html_attribute}><head><script>{cdp_script}</script></head><body>
         <script>
-            // Run CDP detection before allowing access
-            if (window._checkCDPAutomation) {{
-                window._checkCDPAutomation().then(function(result) {{
-                    if (result.detected) {{
-                        fetch('{report_endpoint}', {{
-                            method: 'POST',
-                            headers: {{ 'Content-Type': 'application/json' }},
-                            body: JSON.stringify({{
-                                cdp_detected: true,
-                                score: result.score,
-                                checks: result.checks
-                            }})
-                        }});
-                    }}
-                }});
-            }}
+            // Run CDP detection and post the nonce-bound report before allowing access
+            const cdpReported = window._shumaReportCdp ? window._shumaReportCdp() : Promise.resolve();
 
             const POW_SEED = "{seed}";
             const SHUMA_POW_DIFFICULTY = {difficulty};
@@ -246,32 +241,22 @@ pub fn inject_js_challenge(
     let token = make_token(ip);
     let html = format!(
         r#"
-        <html><head><script>{cdp_script}</script></head><body>
+        <html {cdp_html_attribute}><head><script>{cdp_script}</script></head><body>
         <script>
-            // Run CDP detection before allowing access
-            if (window._checkCDPAutomation) {{
-                window._checkCDPAutomation().then(function(result) {{
-                    if (result.detected) {{
-                        fetch('{report_endpoint}', {{
-                            method: 'POST',
-                            headers: {{ 'Content-Type': 'application/json' }},
-                            body: JSON.stringify({{
-                                cdp_detected: true,
-                                score: result.score,
-                                checks: result.checks
-                            }})
-                        }});
-                    }}
-                }});
-            }}
+            // Run CDP detection and post the nonce-bound report before allowing access
+            const cdpReported = window._shumaReportCdp ? window._shumaReportCdp() : Promise.resolve();
             document.cookie = 'js_verified={token}; path=/; SameSite=Strict; Max-Age=86400';
             document.cookie = '{fp_marker_cookie}';
-            window.location.reload();
+            Promise.race([
+                cdpReported,
+                new Promise(r => setTimeout(r, {cdp_report_wait_ms}))
+            ]).then(function() {{ window.location.reload(); }});
     </script>
     <noscript>Please enable JS to continue.</noscript>
     </body></html>
     "#,
-        fp_marker_cookie = fingerprint_marker_cookie()
+        fp_marker_cookie = fingerprint_marker_cookie(),
+        cdp_report_wait_ms = CDP_REPORT_MAX_WAIT_MS,
     );
     Response::new(200, html)
 }
@@ -294,6 +279,7 @@ mod tests {
         );
         let body = String::from_utf8_lossy(resp.body());
         assert!(body.contains("fetch('/fingerprint-report'"));
+        assert!(body.contains("<html data-shuma-cdp=\""));
     }
 
     #[test]
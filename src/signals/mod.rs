@@ -8,4 +8,5 @@ pub(crate) mod ip_range_policy;
 pub(crate) mod ip_range_suggestions;
 pub(crate) mod js_verification;
 pub(crate) mod rate_pressure;
+pub(crate) mod session_behavior;
 pub(crate) mod allowlist;
@@ -0,0 +1,717 @@
+//! Per-session navigation behaviour.
+//!
+//! A session is a client's IP bucket, UA bucket, and JS verification marker. Each session keeps a
+//! bounded sliding summary in KV of its recent document fetches (hashed path, depth, referer) and
+//! the times of its asset fetches. Scrapers that skip assets, walk a site breadth-first, fetch on
+//! a fixed clock, or forge referers stand out in that summary even when every request looks clean
+//! on its own.
+//!
+//! Clients that never run the CDP probe script they were served are not tracked here: the report
+//! expectation behind `cdp_report_missing` already covers them for the same IP and UA bucket.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use spin_sdk::http::{Method, Request};
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::signals::botness::{BotSignal, SignalFamily, SignalProvenance};
+
+pub(crate) const SESSION_ASSET_RATIO_KEY: &str = "session_asset_ratio";
+pub(crate) const SESSION_TRAVERSAL_KEY: &str = "session_traversal";
+pub(crate) const SESSION_TIMING_KEY: &str = "session_timing";
+pub(crate) const SESSION_REFERER_KEY: &str = "session_referer";
+const SESSION_ASSET_RATIO_LABEL: &str = "Session fetches documents without assets";
+const SESSION_TRAVERSAL_LABEL: &str = "Session walks the site breadth-first";
+const SESSION_TIMING_LABEL: &str = "Session requests arrive on a fixed clock";
+const SESSION_REFERER_LABEL: &str = "Session referers are inconsistent with its history";
+
+const SESSION_KEY_PREFIX: &str = "session:behavior:";
+const SESSION_MARKER_COOKIE: &str = "js_verified";
+const SESSION_WINDOW_MS: u64 = 10 * 60 * 1000;
+const SESSION_DOCUMENTS_MAX: usize = 16;
+const SESSION_ASSETS_MAX: usize = 64;
+/// One page load pulls in many assets at once; a single sample per burst is enough for the ratio.
+const SESSION_ASSET_SAMPLE_INTERVAL_MS: u64 = 5_000;
+const DIGEST_HEX_LEN: usize = 12;
+
+const ASSET_RATIO_MIN_DOCUMENTS: usize = 6;
+const TRAVERSAL_MIN_DOCUMENTS: usize = 8;
+const TIMING_MIN_INTERVALS: usize = 6;
+/// Bursts faster than this are rate pressure, not clockwork.
+const TIMING_MIN_MEAN_INTERVAL_MS: f64 = 500.0;
+const TIMING_MAX_VARIATION: f64 = 0.1;
+const REFERER_MIN_DOCUMENTS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RefererKind {
+    Absent,
+    Visited,
+    Unvisited,
+    External,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DocumentHit {
+    ts_ms: u64,
+    path: String,
+    depth: u8,
+    referer: RefererKind,
+    referer_path: Option<String>,
+    referer_digest: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionBehaviorSummary {
+    documents: VecDeque<DocumentHit>,
+    assets: VecDeque<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionFetch {
+    Document,
+    Asset,
+    Other,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SessionBehaviorFindings {
+    asset_ratio: bool,
+    traversal: bool,
+    timing: bool,
+    referer: bool,
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn digest(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>()[..DIGEST_HEX_LEN]
+        .to_string()
+}
+
+fn header_value(req: &Request, name: &str) -> Option<String> {
+    req.header(name)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+fn session_key(req: &Request, ip: &str, user_agent: &str) -> String {
+    let marker = crate::admin::auth::parse_cookie(req, SESSION_MARKER_COOKIE)
+        .map(|value| digest(value.as_str()))
+        .unwrap_or_else(|| "none".to_string());
+    format!(
+        "{}{}:{}:{}",
+        SESSION_KEY_PREFIX,
+        crate::signals::ip_identity::bucket_ip(ip),
+        crate::challenge::operation_envelope::user_agent_bucket(user_agent),
+        marker
+    )
+}
+
+fn classify_fetch(req: &Request) -> SessionFetch {
+    if !matches!(req.method(), Method::Get | Method::Head) {
+        return SessionFetch::Other;
+    }
+    match header_value(req, "sec-fetch-dest").as_deref() {
+        Some("document" | "iframe" | "frame") => SessionFetch::Document,
+        Some("script" | "style" | "image" | "font" | "audio" | "video" | "track" | "manifest") => {
+            SessionFetch::Asset
+        }
+        Some(_) => SessionFetch::Other,
+        None if header_value(req, "accept")
+            .is_some_and(|accept| accept.starts_with("application/json")) =>
+        {
+            SessionFetch::Other
+        }
+        None => SessionFetch::Document,
+    }
+}
+
+fn path_depth(path: &str) -> u8 {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .count()
+        .min(u8::MAX as usize) as u8
+}
+
+/// Splits a referer into its host and path, dropping scheme, query, and fragment.
+fn referer_host_and_path(referer: &str) -> Option<(String, String)> {
+    let rest = referer
+        .strip_prefix("https://")
+        .or_else(|| referer.strip_prefix("http://"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or("");
+    let (host, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    Some((host.to_ascii_lowercase(), path.to_string()))
+}
+
+fn load_summary<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    key: &str,
+) -> Option<SessionBehaviorSummary> {
+    let raw = store.get(key).ok().flatten()?;
+    serde_json::from_slice(raw.as_slice()).ok()
+}
+
+fn store_summary<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    key: &str,
+    summary: &SessionBehaviorSummary,
+) {
+    let Ok(raw) = serde_json::to_vec(summary) else {
+        return;
+    };
+    if store.set(key, raw.as_slice()).is_err() {
+        eprintln!("[session] failed to persist behaviour summary {}", key);
+    }
+}
+
+/// Drops hits older than the session window and reports whether anything was dropped.
+fn prune(summary: &mut SessionBehaviorSummary, now_ms: u64) -> bool {
+    let cutoff = now_ms.saturating_sub(SESSION_WINDOW_MS);
+    let before = summary.documents.len() + summary.assets.len();
+    summary.documents.retain(|hit| hit.ts_ms >= cutoff);
+    summary.assets.retain(|ts| *ts >= cutoff);
+    summary.documents.len() + summary.assets.len() != before
+}
+
+fn record_document(summary: &mut SessionBehaviorSummary, req: &Request, now_ms: u64) {
+    let path = digest(req.path());
+    let request_host = header_value(req, "host").map(|host| host.to_ascii_lowercase());
+    let raw_referer = header_value(req, "referer");
+    let (referer, referer_path) = match raw_referer.as_deref().map(referer_host_and_path) {
+        None => (RefererKind::Absent, None),
+        Some(Some((host, referer_path))) if Some(&host) == request_host.as_ref() => {
+            let referer_path = digest(referer_path.as_str());
+            let visited = summary.documents.iter().any(|hit| hit.path == referer_path);
+            let kind = if visited {
+                RefererKind::Visited
+            } else {
+                RefererKind::Unvisited
+            };
+            (kind, Some(referer_path))
+        }
+        Some(_) => (RefererKind::External, None),
+    };
+    summary.documents.push_back(DocumentHit {
+        ts_ms: now_ms,
+        path,
+        depth: path_depth(req.path()),
+        referer,
+        referer_path,
+        referer_digest: raw_referer.as_deref().map(digest),
+    });
+    while summary.documents.len() > SESSION_DOCUMENTS_MAX {
+        summary.documents.pop_front();
+    }
+}
+
+/// Samples an asset fetch, skipping those within the sample interval of the last one kept.
+/// Returns whether the summary changed.
+fn record_asset(summary: &mut SessionBehaviorSummary, now_ms: u64) -> bool {
+    if summary
+        .assets
+        .back()
+        .is_some_and(|last| now_ms.saturating_sub(*last) < SESSION_ASSET_SAMPLE_INTERVAL_MS)
+    {
+        return false;
+    }
+    summary.assets.push_back(now_ms);
+    while summary.assets.len() > SESSION_ASSETS_MAX {
+        summary.assets.pop_front();
+    }
+    true
+}
+
+/// Asset fetches only feed `session_asset_ratio`. Sites whose assets are served by a CDN never
+/// show them to Shuma, so the ratio is opt-in through its weight and assets are not tracked while
+/// the weight is 0.
+fn asset_tracking_enabled(cfg: &crate::config::Config) -> bool {
+    cfg.fingerprint_signal_enabled && cfg.botness_weights.session_asset_ratio > 0
+}
+
+/// Fewer than one asset per ten documents: browsers load at least some scripts, styles, or images
+/// for most pages, even with a warm cache.
+fn asset_ratio_anomalous(summary: &SessionBehaviorSummary) -> bool {
+    let documents = summary.documents.len();
+    documents >= ASSET_RATIO_MIN_DOCUMENTS && summary.assets.len() * 10 < documents
+}
+
+/// Breadth-first crawlers never revisit a page, never climb back up the tree, and rarely follow
+/// a link from the page they just fetched; people do all three.
+fn traversal_anomalous(summary: &SessionBehaviorSummary) -> bool {
+    let documents = &summary.documents;
+    if documents.len() < TRAVERSAL_MIN_DOCUMENTS {
+        return false;
+    }
+    let all_distinct = documents.iter().enumerate().all(|(index, hit)| {
+        documents
+            .iter()
+            .skip(index + 1)
+            .all(|later| later.path != hit.path)
+    });
+    let never_climbs = documents
+        .iter()
+        .zip(documents.iter().skip(1))
+        .all(|(earlier, later)| later.depth >= earlier.depth);
+    let followed_previous = documents
+        .iter()
+        .zip(documents.iter().skip(1))
+        .filter(|(earlier, later)| later.referer_path.as_deref() == Some(earlier.path.as_str()))
+        .count();
+    all_distinct && never_climbs && followed_previous * 2 < documents.len() - 1
+}
+
+/// Inter-document intervals whose spread is under a tenth of their mean.
+fn timing_anomalous(summary: &SessionBehaviorSummary) -> bool {
+    let intervals: Vec<f64> = summary
+        .documents
+        .iter()
+        .zip(summary.documents.iter().skip(1))
+        .map(|(earlier, later)| later.ts_ms.saturating_sub(earlier.ts_ms) as f64)
+        .collect();
+    if intervals.len() < TIMING_MIN_INTERVALS {
+        return false;
+    }
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    if mean < TIMING_MIN_MEAN_INTERVAL_MS {
+        return false;
+    }
+    let variance = intervals
+        .iter()
+        .map(|interval| (interval - mean).powi(2))
+        .sum::<f64>()
+        / intervals.len() as f64;
+    variance.sqrt() / mean < TIMING_MAX_VARIATION
+}
+
+/// Most follow-up documents claim to come from same-site pages this session never fetched, or
+/// every document carries the same referer.
+fn referer_anomalous(summary: &SessionBehaviorSummary) -> bool {
+    let documents = &summary.documents;
+    if documents.len() < REFERER_MIN_DOCUMENTS {
+        return false;
+    }
+    let follow_ups = documents.len() - 1;
+    let unvisited = documents
+        .iter()
+        .skip(1)
+        .filter(|hit| hit.referer == RefererKind::Unvisited)
+        .count();
+    let constant_referer = documents[0].referer_digest.is_some()
+        && documents
+            .iter()
+            .all(|hit| hit.referer_digest == documents[0].referer_digest);
+    unvisited * 2 >= follow_ups || constant_referer
+}
+
+fn evaluate(summary: &SessionBehaviorSummary) -> SessionBehaviorFindings {
+    SessionBehaviorFindings {
+        asset_ratio: asset_ratio_anomalous(summary),
+        traversal: traversal_anomalous(summary),
+        timing: timing_anomalous(summary),
+        referer: referer_anomalous(summary),
+    }
+}
+
+fn disabled_bot_signals() -> Vec<BotSignal> {
+    [
+        (SESSION_ASSET_RATIO_KEY, SESSION_ASSET_RATIO_LABEL, 6),
+        (SESSION_TRAVERSAL_KEY, SESSION_TRAVERSAL_LABEL, 7),
+        (SESSION_TIMING_KEY, SESSION_TIMING_LABEL, 7),
+        (SESSION_REFERER_KEY, SESSION_REFERER_LABEL, 6),
+    ]
+    .into_iter()
+    .map(|(key, label, confidence)| {
+        BotSignal::disabled_with_metadata(
+            key,
+            label,
+            SignalProvenance::Derived,
+            confidence,
+            SignalFamily::FingerprintBehavior,
+        )
+    })
+    .collect()
+}
+
+/// Records this request in its session summary and scores the session's behaviour so far.
+pub(crate) fn collect_bot_signals<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    req: &Request,
+    cfg: &crate::config::Config,
+    ip: &str,
+    user_agent: &str,
+    now_ms: u64,
+) -> Vec<BotSignal> {
+    if !cfg.fingerprint_signal_enabled {
+        return disabled_bot_signals();
+    }
+
+    let key = session_key(req, ip, user_agent);
+    let track_assets = asset_tracking_enabled(cfg);
+    let mut summary = load_summary(store, key.as_str()).unwrap_or_default();
+    let mut changed = prune(&mut summary, now_ms);
+    match classify_fetch(req) {
+        SessionFetch::Document => {
+            record_document(&mut summary, req, now_ms);
+            changed = true;
+        }
+        SessionFetch::Asset if track_assets => changed |= record_asset(&mut summary, now_ms),
+        SessionFetch::Asset | SessionFetch::Other => {}
+    }
+    if changed {
+        store_summary(store, key.as_str(), &summary);
+    }
+
+    let findings = evaluate(&summary);
+    let weights = &cfg.botness_weights;
+    let asset_ratio = if track_assets {
+        BotSignal::scored_with_metadata(
+            SESSION_ASSET_RATIO_KEY,
+            SESSION_ASSET_RATIO_LABEL,
+            findings.asset_ratio,
+            weights.session_asset_ratio,
+            SignalProvenance::Derived,
+            6,
+            SignalFamily::FingerprintBehavior,
+        )
+    } else {
+        BotSignal::disabled_with_metadata(
+            SESSION_ASSET_RATIO_KEY,
+            SESSION_ASSET_RATIO_LABEL,
+            SignalProvenance::Derived,
+            6,
+            SignalFamily::FingerprintBehavior,
+        )
+    };
+    vec![
+        asset_ratio,
+        BotSignal::scored_with_metadata(
+            SESSION_TRAVERSAL_KEY,
+            SESSION_TRAVERSAL_LABEL,
+            findings.traversal,
+            weights.session_traversal,
+            SignalProvenance::Derived,
+            7,
+            SignalFamily::FingerprintBehavior,
+        ),
+        BotSignal::scored_with_metadata(
+            SESSION_TIMING_KEY,
+            SESSION_TIMING_LABEL,
+            findings.timing,
+            weights.session_timing,
+            SignalProvenance::Derived,
+            7,
+            SignalFamily::FingerprintBehavior,
+        ),
+        BotSignal::scored_with_metadata(
+            SESSION_REFERER_KEY,
+            SESSION_REFERER_LABEL,
+            findings.referer,
+            weights.session_referer,
+            SignalProvenance::Derived,
+            6,
+            SignalFamily::FingerprintBehavior,
+        ),
+    ]
+}
+
+/// Counts a static asset served on the bypass path. Only sessions that already fetched a document
+/// are updated, so asset-only traffic never creates summaries, and assets inside the sample
+/// interval leave the stored summary untouched.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn record_static_asset_fetch<S: crate::challenge::KeyValueStore + ?Sized>(
+    store: &S,
+    req: &Request,
+    cfg: &crate::config::Config,
+    ip: &str,
+    user_agent: &str,
+    now_ms: u64,
+) {
+    if !asset_tracking_enabled(cfg) {
+        return;
+    }
+    let key = session_key(req, ip, user_agent);
+    let Some(mut summary) = load_summary(store, key.as_str()) else {
+        return;
+    };
+    let pruned = prune(&mut summary, now_ms);
+    if summary.documents.is_empty() {
+        let _ = store.delete(key.as_str());
+        return;
+    }
+    if record_asset(&mut summary, now_ms) || pruned {
+        store_summary(store, key.as_str(), &summary);
+    }
+}
+
+/// The static bypass runs before the request store is opened, so this opens the default store
+/// just long enough to count the asset. Runtime config is served from the in-process cache, and
+/// nothing else is read unless asset tracking is on. Native builds have no KV host and skip it.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn record_static_asset_bypass(req: &Request, ip: &str) {
+    let Ok(store) = spin_sdk::key_value::Store::open_default() else {
+        return;
+    };
+    let Ok(cfg) = crate::config::load_runtime_cached(&store, "default") else {
+        return;
+    };
+    let user_agent = header_value(req, "user-agent").unwrap_or_default();
+    record_static_asset_fetch(&store, req, &cfg, ip, user_agent.as_str(), now_ms());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn record_static_asset_bypass(_req: &Request, _ip: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::botness::SignalAvailability;
+    use crate::test_support::InMemoryStore;
+
+    const IP: &str = "203.0.113.40";
+    const UA: &str = "Mozilla/5.0 (X11; Linux x86_64) Chrome/126.0 Safari/537.36";
+    const START_MS: u64 = 1_792_411_200_000;
+
+    fn request(path: &str, dest: &str, referer: Option<&str>) -> Request {
+        let mut builder = Request::builder();
+        builder
+            .method(Method::Get)
+            .uri(path)
+            .header("host", "shop.example")
+            .header("user-agent", UA)
+            .header("sec-fetch-dest", dest);
+        if let Some(referer) = referer {
+            builder.header("referer", referer);
+        }
+        builder.build()
+    }
+
+    fn asset_ratio_config() -> crate::config::Config {
+        let mut cfg = crate::config::defaults().clone();
+        cfg.botness_weights.session_asset_ratio = 2;
+        cfg
+    }
+
+    fn active(signals: &[BotSignal], key: &str) -> bool {
+        signals
+            .iter()
+            .any(|signal| signal.key == key && signal.active)
+    }
+
+    #[test]
+    fn breadth_first_clockwork_crawl_trips_every_session_signal() {
+        let store = InMemoryStore::default();
+        let cfg = asset_ratio_config();
+        let mut signals = Vec::new();
+        let pages = [
+            "/",
+            "/shoes",
+            "/hats",
+            "/bags",
+            "/shoes/red",
+            "/shoes/blue",
+            "/hats/wool",
+            "/bags/tote",
+        ];
+        for (index, page) in pages.iter().enumerate() {
+            let referer = (index > 0).then_some("https://shop.example/sitemap");
+            signals = collect_bot_signals(
+                &store,
+                &request(page, "document", referer),
+                &cfg,
+                IP,
+                UA,
+                START_MS + index as u64 * 2_000,
+            );
+        }
+        assert!(active(&signals, SESSION_ASSET_RATIO_KEY));
+        assert!(active(&signals, SESSION_TRAVERSAL_KEY));
+        assert!(active(&signals, SESSION_TIMING_KEY));
+        assert!(active(&signals, SESSION_REFERER_KEY));
+        assert!(signals
+            .iter()
+            .all(|signal| signal.family == SignalFamily::FingerprintBehavior));
+    }
+
+    #[test]
+    fn browsing_with_assets_and_followed_links_stays_clean() {
+        let store = InMemoryStore::default();
+        let cfg = asset_ratio_config();
+        let visits = [
+            ("/", None),
+            ("/shoes", Some("https://shop.example/")),
+            ("/shoes/red", Some("https://shop.example/shoes")),
+            ("/shoes", Some("https://shop.example/shoes/red")),
+            ("/shoes/blue", Some("https://shop.example/shoes")),
+            ("/", Some("https://shop.example/shoes/blue")),
+            ("/hats", Some("https://shop.example/")),
+            ("/hats/wool", Some("https://shop.example/hats")),
+        ];
+        let mut now = START_MS;
+        let mut signals = Vec::new();
+        for (index, (page, referer)) in visits.iter().enumerate() {
+            now += 1_500 + (index as u64 * 3_700) % 9_000;
+            signals = collect_bot_signals(
+                &store,
+                &request(page, "document", *referer),
+                &cfg,
+                IP,
+                UA,
+                now,
+            );
+            record_static_asset_fetch(
+                &store,
+                &request("/assets/site.css", "style", None),
+                &cfg,
+                IP,
+                UA,
+                now,
+            );
+        }
+        for key in [
+            SESSION_ASSET_RATIO_KEY,
+            SESSION_TRAVERSAL_KEY,
+            SESSION_TIMING_KEY,
+            SESSION_REFERER_KEY,
+        ] {
+            assert!(!active(&signals, key), "{} should stay inactive", key);
+        }
+
+        let store = InMemoryStore::default();
+        record_static_asset_fetch(
+            &store,
+            &request("/assets/site.css", "style", None),
+            &cfg,
+            IP,
+            UA,
+            now,
+        );
+        assert!(load_summary(
+            &store,
+            session_key(&request("/", "document", None), IP, UA).as_str()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn summaries_stay_bounded_and_slide_out_old_documents() {
+        let store = InMemoryStore::default();
+        let cfg = crate::config::defaults().clone();
+        let req = request("/", "document", None);
+        let key = session_key(&req, IP, UA);
+        for index in 0..40u64 {
+            let page = format!("/p/{}", index);
+            collect_bot_signals(
+                &store,
+                &request(page.as_str(), "document", None),
+                &cfg,
+                IP,
+                UA,
+                START_MS + index * 1_000,
+            );
+        }
+        let summary = load_summary(&store, key.as_str()).expect("summary stored");
+        assert_eq!(summary.documents.len(), SESSION_DOCUMENTS_MAX);
+
+        collect_bot_signals(
+            &store,
+            &req,
+            &cfg,
+            IP,
+            UA,
+            START_MS + SESSION_WINDOW_MS + 60_000,
+        );
+        let summary = load_summary(&store, key.as_str()).expect("summary stored");
+        assert_eq!(summary.documents.len(), 1);
+
+        let mut disabled = cfg.clone();
+        disabled.fingerprint_signal_enabled = false;
+        let signals = collect_bot_signals(&store, &req, &disabled, IP, UA, START_MS);
+        assert_eq!(signals.len(), 4);
+        assert!(signals.iter().all(|signal| !signal.active));
+    }
+
+    struct CountingStore {
+        inner: InMemoryStore,
+        sets: std::sync::atomic::AtomicUsize,
+    }
+
+    impl crate::challenge::KeyValueStore for CountingStore {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ()> {
+            self.inner.get(key)
+        }
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), ()> {
+            self.sets.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.set(key, value)
+        }
+        fn delete(&self, key: &str) -> Result<(), ()> {
+            self.inner.delete(key)
+        }
+        fn get_keys(&self) -> Result<Vec<String>, ()> {
+            self.inner.get_keys()
+        }
+    }
+
+    #[test]
+    fn asset_tracking_is_opt_in_sampled_and_skips_unchanged_writes() {
+        let store = CountingStore {
+            inner: InMemoryStore::default(),
+            sets: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let sets = || store.sets.load(std::sync::atomic::Ordering::SeqCst);
+        let default_cfg = crate::config::defaults().clone();
+        assert_eq!(default_cfg.botness_weights.session_asset_ratio, 0);
+        let asset = request("/assets/site.css", "style", None);
+        let page = request("/", "document", None);
+
+        let signals = collect_bot_signals(&store, &page, &default_cfg, IP, UA, START_MS);
+        assert_eq!(sets(), 1);
+        assert!(signals
+            .iter()
+            .any(|signal| signal.key == SESSION_ASSET_RATIO_KEY
+                && signal.availability == SignalAvailability::Disabled));
+        record_static_asset_fetch(&store, &asset, &default_cfg, IP, UA, START_MS + 100);
+        collect_bot_signals(&store, &asset, &default_cfg, IP, UA, START_MS + 200);
+        assert_eq!(sets(), 1);
+
+        let cfg = asset_ratio_config();
+        for offset in [300, 400, 500] {
+            record_static_asset_fetch(&store, &asset, &cfg, IP, UA, START_MS + offset);
+        }
+        collect_bot_signals(&store, &asset, &cfg, IP, UA, START_MS + 600);
+        assert_eq!(sets(), 2);
+        record_static_asset_fetch(
+            &store,
+            &asset,
+            &cfg,
+            IP,
+            UA,
+            START_MS + 300 + SESSION_ASSET_SAMPLE_INTERVAL_MS,
+        );
+        assert_eq!(sets(), 3);
+        let summary = load_summary(&store, session_key(&page, IP, UA).as_str()).expect("summary");
+        assert_eq!(summary.assets.len(), 2);
+
+        let other = Request::builder()
+            .method(Method::Post)
+            .uri("/cart")
+            .header("user-agent", UA)
+            .build();
+        collect_bot_signals(&store, &other, &cfg, IP, UA, START_MS + 9_000);
+        assert_eq!(sets(), 3);
+    }
+}